@@ -16,6 +16,8 @@ pub use self::{fee_handling::FeeHandling, refund_handling::RefundHandling};
 
 /// Default value for a maximum query depth configuration option.
 pub const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+/// Default value for a maximum keys page size configuration option.
+pub const DEFAULT_MAX_KEYS_PAGE_SIZE: u32 = 1_000;
 /// Default value for maximum associated keys configuration option.
 pub const DEFAULT_MAX_ASSOCIATED_KEYS: u32 = 100;
 /// Default value for maximum runtime call stack height configuration option.
@@ -53,6 +55,8 @@ pub const DEFAULT_FEE_HANDLING: FeeHandling = FeeHandling::PayToProposer;
 pub struct EngineConfig {
     /// Max query depth of the engine.
     pub(crate) max_query_depth: u64,
+    /// Max number of keys examined for a single page when listing keys by prefix.
+    pub(crate) max_keys_page_size: u32,
     /// Maximum number of associated keys (i.e. map of
     /// [`AccountHash`](casper_types::account::AccountHash)s to
     /// [`Weight`](casper_types::account::Weight)s) for a single account.
@@ -87,6 +91,7 @@ impl Default for EngineConfig {
     fn default() -> Self {
         EngineConfig {
             max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_keys_page_size: DEFAULT_MAX_KEYS_PAGE_SIZE,
             max_associated_keys: DEFAULT_MAX_ASSOCIATED_KEYS,
             max_runtime_call_stack_height: DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
             minimum_delegation_amount: DEFAULT_MINIMUM_DELEGATION_AMOUNT,
@@ -127,6 +132,7 @@ impl EngineConfig {
     ) -> EngineConfig {
         Self {
             max_query_depth,
+            max_keys_page_size: DEFAULT_MAX_KEYS_PAGE_SIZE,
             max_associated_keys,
             max_runtime_call_stack_height,
             minimum_delegation_amount,
@@ -143,6 +149,11 @@ impl EngineConfig {
         }
     }
 
+    /// Returns the current max keys page size config.
+    pub fn max_keys_page_size(&self) -> u32 {
+        self.max_keys_page_size
+    }
+
     /// Returns the current max associated keys config.
     pub fn max_associated_keys(&self) -> u32 {
         self.max_associated_keys
@@ -227,6 +238,7 @@ impl EngineConfig {
 #[derive(Default, Debug)]
 pub struct EngineConfigBuilder {
     max_query_depth: Option<u64>,
+    max_keys_page_size: Option<u32>,
     max_associated_keys: Option<u32>,
     max_runtime_call_stack_height: Option<u32>,
     minimum_delegation_amount: Option<u64>,
@@ -254,6 +266,12 @@ impl EngineConfigBuilder {
         self
     }
 
+    /// Sets the max keys page size config option.
+    pub fn with_max_keys_page_size(mut self, max_keys_page_size: u32) -> Self {
+        self.max_keys_page_size = Some(max_keys_page_size);
+        self
+    }
+
     /// Sets the max associated keys config option.
     pub fn with_max_associated_keys(mut self, max_associated_keys: u32) -> Self {
         self.max_associated_keys = Some(max_associated_keys);
@@ -357,6 +375,9 @@ impl EngineConfigBuilder {
     /// Builds a new [`EngineConfig`] object.
     pub fn build(self) -> EngineConfig {
         let max_query_depth = self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH);
+        let max_keys_page_size = self
+            .max_keys_page_size
+            .unwrap_or(DEFAULT_MAX_KEYS_PAGE_SIZE);
         let max_associated_keys = self
             .max_associated_keys
             .unwrap_or(DEFAULT_MAX_ASSOCIATED_KEYS);
@@ -394,6 +415,7 @@ impl EngineConfigBuilder {
 
         EngineConfig {
             max_query_depth,
+            max_keys_page_size,
             max_associated_keys,
             max_runtime_call_stack_height,
             minimum_delegation_amount,
@@ -24,7 +24,7 @@ impl From<ExecutionJournal> for ExecutionEffect {
             match transform {
                 Transform::Failure(_) => (),
                 Transform::Identity => ops.insert_add(key, Op::Read),
-                Transform::Write(_) => ops.insert_add(key, Op::Write),
+                Transform::Write(_) | Transform::Prune(_) => ops.insert_add(key, Op::Write),
                 Transform::AddInt32(_)
                 | Transform::AddUInt64(_)
                 | Transform::AddUInt128(_)
@@ -3,7 +3,8 @@
 use std::collections::VecDeque;
 
 use casper_types::{
-    bytesrepr::FromBytes, CLTyped, CLValue, Gas, Key, Motes, StoredValue, TransferAddr,
+    bytesrepr::FromBytes, CLTyped, CLValue, ContractEvent, Gas, Key, Motes, StoredValue,
+    TransferAddr,
 };
 
 use super::error;
@@ -49,6 +50,8 @@ pub enum ExecutionResult {
         cost: Gas,
         /// Journal of execution.
         execution_journal: ExecutionJournal,
+        /// Events emitted during execution which were not reverted by the failure.
+        events: Vec<ContractEvent>,
     },
     /// Execution was finished successfully
     Success {
@@ -58,6 +61,8 @@ pub enum ExecutionResult {
         cost: Gas,
         /// Journal of execution.
         execution_journal: ExecutionJournal,
+        /// Events emitted during execution.
+        events: Vec<ContractEvent>,
     },
 }
 
@@ -67,6 +72,34 @@ impl Default for ExecutionResult {
             execution_journal: Default::default(),
             transfers: Default::default(),
             cost: Default::default(),
+            events: Default::default(),
+        }
+    }
+}
+
+/// The gas consumed by the payment and session phases of a deploy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionPhaseCosts {
+    /// Gas consumed by the payment code, or charged for a failure prior to running session code.
+    pub payment: Gas,
+    /// Gas consumed by the session code.
+    pub session: Gas,
+}
+
+impl ExecutionPhaseCosts {
+    /// Splits the total cost of a deploy given the cost of its payment code.
+    ///
+    /// If the payment cost is unknown, i.e. the session code never ran, the whole cost is
+    /// attributed to the payment phase.
+    pub(crate) fn new(total_cost: Gas, maybe_payment_cost: Option<Gas>) -> Self {
+        match maybe_payment_cost
+            .and_then(|payment| Some((payment, total_cost.checked_sub(payment)?)))
+        {
+            Some((payment, session)) => ExecutionPhaseCosts { payment, session },
+            None => ExecutionPhaseCosts {
+                payment: total_cost,
+                session: Gas::default(),
+            },
         }
     }
 }
@@ -94,6 +127,7 @@ impl ExecutionResult {
             transfers: Vec::default(),
             cost: Gas::default(),
             execution_journal: Default::default(),
+            events: Vec::default(),
         }
     }
 
@@ -156,6 +190,14 @@ impl ExecutionResult {
         }
     }
 
+    /// Returns the events emitted during execution regardless of variant.
+    pub fn events(&self) -> &[ContractEvent] {
+        match self {
+            ExecutionResult::Failure { events, .. } => events,
+            ExecutionResult::Success { events, .. } => events,
+        }
+    }
+
     /// Returns a new execution result with updated gas cost.
     ///
     /// This method preserves the [`ExecutionResult`] variant and updates the cost field
@@ -166,21 +208,25 @@ impl ExecutionResult {
                 error,
                 transfers,
                 execution_journal,
+                events,
                 ..
             } => ExecutionResult::Failure {
                 error,
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
             ExecutionResult::Success {
                 transfers,
                 execution_journal,
+                events,
                 ..
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
         }
     }
@@ -195,21 +241,25 @@ impl ExecutionResult {
                 error,
                 cost,
                 execution_journal,
+                events,
                 ..
             } => ExecutionResult::Failure {
                 error,
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
             ExecutionResult::Success {
                 cost,
                 execution_journal,
+                events,
                 ..
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
         }
     }
@@ -225,20 +275,24 @@ impl ExecutionResult {
                 transfers,
                 cost,
                 execution_journal: _,
+                events,
             } => ExecutionResult::Failure {
                 error,
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
             ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal: _,
+                events,
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
         }
     }
@@ -328,6 +382,7 @@ impl ExecutionResult {
             execution_journal,
             transfers,
             cost: gas_cost,
+            events: Vec::default(),
         })
     }
 
@@ -349,21 +404,25 @@ impl From<&ExecutionResult> for casper_types::ExecutionResult {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             } => casper_types::ExecutionResult::Success {
                 effect: execution_journal.into(),
                 transfers: transfers.clone(),
                 cost: cost.value(),
+                events: events.clone(),
             },
             ExecutionResult::Failure {
                 error,
                 transfers,
                 cost,
                 execution_journal,
+                events,
             } => casper_types::ExecutionResult::Failure {
                 effect: execution_journal.into(),
                 transfers: transfers.clone(),
                 cost: cost.value(),
                 error_message: error.to_string(),
+                events: events.clone(),
             },
         }
     }
@@ -376,21 +435,25 @@ impl From<ExecutionResult> for casper_types::ExecutionResult {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             } => casper_types::ExecutionResult::Success {
                 effect: execution_journal.into(),
                 transfers,
                 cost: cost.value(),
+                events,
             },
             ExecutionResult::Failure {
                 error,
                 transfers,
                 cost,
                 execution_journal,
+                events,
             } => casper_types::ExecutionResult::Failure {
                 effect: execution_journal.into(),
                 transfers,
                 cost: cost.value(),
                 error_message: error.to_string(),
+                events,
             },
         }
     }
@@ -486,11 +549,13 @@ impl ExecutionResultBuilder {
         let mut transfers = self.transfers();
         let cost = self.total_cost();
 
-        let mut journal = match self.payment_execution_result {
+        let (mut journal, mut events) = match self.payment_execution_result {
             Some(result @ ExecutionResult::Failure { .. }) => return Ok(result),
             Some(ExecutionResult::Success {
-                execution_journal, ..
-            }) => execution_journal,
+                execution_journal,
+                events,
+                ..
+            }) => (execution_journal, events),
             None => return Err(ExecutionResultBuilderError::MissingPaymentExecutionResult),
         };
 
@@ -502,13 +567,19 @@ impl ExecutionResultBuilder {
                 transfers: session_transfers,
                 execution_journal: _,
                 cost: _,
+                events: _,
             }) => {
                 error = Some(session_error);
                 transfers = session_transfers;
             }
             Some(ExecutionResult::Success {
-                execution_journal, ..
-            }) => journal.extend(execution_journal),
+                execution_journal,
+                events: session_events,
+                ..
+            }) => {
+                journal.extend(execution_journal);
+                events.extend(session_events);
+            }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
         };
 
@@ -520,8 +591,13 @@ impl ExecutionResultBuilder {
                 ));
             }
             Some(ExecutionResult::Success {
-                execution_journal, ..
-            }) => journal.extend(execution_journal),
+                execution_journal,
+                events: finalize_events,
+                ..
+            }) => {
+                journal.extend(execution_journal);
+                events.extend(finalize_events);
+            }
             None => return Err(ExecutionResultBuilderError::MissingFinalizeExecutionResult),
         }
 
@@ -530,13 +606,39 @@ impl ExecutionResultBuilder {
                 transfers,
                 cost,
                 execution_journal: journal,
+                events,
             }),
             Some(error) => Ok(ExecutionResult::Failure {
                 error,
                 transfers,
                 cost,
                 execution_journal: journal,
+                events,
             }),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_phase_costs() {
+        let phase_costs = ExecutionPhaseCosts::new(Gas::new(100.into()), Some(Gas::new(30.into())));
+        assert_eq!(phase_costs.payment, Gas::new(30.into()));
+        assert_eq!(phase_costs.session, Gas::new(70.into()));
+    }
+
+    #[test]
+    fn should_attribute_whole_cost_to_payment_if_session_not_run() {
+        let phase_costs = ExecutionPhaseCosts::new(Gas::new(100.into()), None);
+        assert_eq!(phase_costs.payment, Gas::new(100.into()));
+        assert_eq!(phase_costs.session, Gas::default());
+
+        // A failure after the session phase, such as in finalization, may report a lower total.
+        let phase_costs = ExecutionPhaseCosts::new(Gas::default(), Some(Gas::new(30.into())));
+        assert_eq!(phase_costs.payment, Gas::default());
+        assert_eq!(phase_costs.session, Gas::default());
+    }
+}
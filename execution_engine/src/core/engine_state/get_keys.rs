@@ -0,0 +1,96 @@
+//! Support for listing the keys in global state by prefix.
+use casper_hashing::Digest;
+use casper_types::{Key, KeyTag, URefAddr};
+
+/// Identifies the keys to be listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyPrefix {
+    /// Keys whose serialized bytes start with the given bytes.
+    Bytes(Vec<u8>),
+    /// The dictionary items of the dictionary with the given seed `URef` address.
+    ///
+    /// As a dictionary item's key is a hash, this requires reading every dictionary item.  The
+    /// index entries through which contracts list the items of a dictionary are left out.
+    DictionaryItems(URefAddr),
+}
+
+impl KeyPrefix {
+    /// Returns the prefix identifying all keys with the given tag.
+    pub fn key_tag(key_tag: KeyTag) -> Self {
+        KeyPrefix::Bytes(vec![key_tag as u8])
+    }
+
+    /// Returns the prefix of the serialized bytes of the keys to be examined.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            KeyPrefix::Bytes(bytes) => bytes.clone(),
+            KeyPrefix::DictionaryItems(_) => vec![KeyTag::Dictionary as u8],
+        }
+    }
+}
+
+/// Represents a request to obtain a page of the keys in global state with a given prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetKeysRequest {
+    state_hash: Digest,
+    prefix: KeyPrefix,
+    page_start: Option<Key>,
+    page_size: Option<u32>,
+}
+
+impl GetKeysRequest {
+    /// Creates new request for a page starting from `page_start` if given.
+    ///
+    /// At most `page_size` keys are examined, capped at the engine's max keys page size, which is
+    /// also used if `page_size` is `None`.
+    pub fn new(
+        state_hash: Digest,
+        prefix: KeyPrefix,
+        page_start: Option<Key>,
+        page_size: Option<u32>,
+    ) -> Self {
+        GetKeysRequest {
+            state_hash,
+            prefix,
+            page_start,
+            page_size,
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns the prefix identifying the keys to be listed.
+    pub fn prefix(&self) -> &KeyPrefix {
+        &self.prefix
+    }
+
+    /// Returns the first key of the requested page, if any.
+    pub fn page_start(&self) -> Option<&Key> {
+        self.page_start.as_ref()
+    }
+
+    /// Returns the requested maximum number of keys to examine, if any.
+    pub fn page_size(&self) -> Option<u32> {
+        self.page_size
+    }
+}
+
+/// Represents a result of a `get_keys` request.
+#[derive(Debug)]
+pub enum GetKeysResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// The global state under the state root hash is incomplete; a trie with the given hash is
+    /// missing.
+    MissingTrie(Digest),
+    /// Contains a page of the keys.
+    Success {
+        /// The keys, ordered by their serialized bytes.
+        keys: Vec<Key>,
+        /// The key from which to continue listing, if there are further keys to examine.
+        next_page_start: Option<Key>,
+    },
+}
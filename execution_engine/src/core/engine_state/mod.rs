@@ -12,10 +12,12 @@ pub mod execution_effect;
 pub mod execution_result;
 pub mod genesis;
 pub mod get_bids;
+pub mod get_keys;
 pub mod op;
 mod prune;
 pub mod query;
 pub mod run_genesis_request;
+pub mod state_diff;
 pub mod step;
 pub mod system_contract_registry;
 mod transfer;
@@ -23,7 +25,7 @@ pub mod upgrade;
 
 use std::{
     cell::RefCell,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     convert::TryFrom,
     rc::Rc,
 };
@@ -59,7 +61,7 @@ pub use self::{
     checksum_registry::ChecksumRegistry,
     deploy_item::DeployItem,
     engine_config::{
-        EngineConfig, EngineConfigBuilder, DEFAULT_MAX_QUERY_DEPTH,
+        EngineConfig, EngineConfigBuilder, DEFAULT_MAX_KEYS_PAGE_SIZE, DEFAULT_MAX_QUERY_DEPTH,
         DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT,
     },
     era_validators::{GetEraValidatorsError, GetEraValidatorsRequest},
@@ -67,12 +69,14 @@ pub use self::{
     executable_deploy_item::{ExecutableDeployItem, ExecutableDeployItemIdentifier},
     execute_request::ExecuteRequest,
     execution::Error as ExecError,
-    execution_result::{ExecutionResult, ForcedTransferResult},
+    execution_result::{ExecutionPhaseCosts, ExecutionResult, ForcedTransferResult},
     genesis::{ExecConfig, GenesisAccount, GenesisConfig, GenesisSuccess},
     get_bids::{GetBidsRequest, GetBidsResult},
+    get_keys::{GetKeysRequest, GetKeysResult, KeyPrefix},
     prune::{PruneConfig, PruneResult},
     query::{QueryRequest, QueryResult},
     run_genesis_request::RunGenesisRequest,
+    state_diff::{StateDiffEntry, StateDiffRequest, StateDiffResult},
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     system_contract_registry::SystemContractRegistry,
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
@@ -87,18 +91,20 @@ use crate::{
             genesis::GenesisInstaller,
             upgrade::{ProtocolUpgradeError, SystemUpgrader},
         },
-        execution::{self, DirectSystemContractCall, Executor},
-        runtime::RuntimeStack,
+        execution::{self, AddressGenerator, DirectSystemContractCall, Executor},
+        runtime::{ExecutionTrace, RuntimeStack},
+        runtime_context::dictionary::{self, DictionaryValue},
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::{
+        error as storage_error,
         global_state::{
-            lmdb::LmdbGlobalState, scratch::ScratchGlobalState, CommitProvider, StateProvider,
-            StateReader,
+            lmdb::LmdbGlobalState, persistent::PersistentGlobalState, scratch::ScratchGlobalState,
+            CommitError, CommitProvider, StateProvider, StateReader,
         },
         trie::{merkle_proof::TrieMerkleProof, TrieRaw},
-        trie_store::operations::DeleteResult,
+        trie_store::operations::{DeleteResult, DiffResult, LeavesResult},
     },
     system::auction,
 };
@@ -127,9 +133,9 @@ pub struct EngineState<S> {
     state: S,
 }
 
-impl EngineState<ScratchGlobalState> {
+impl<S> EngineState<ScratchGlobalState<S>> {
     /// Returns the inner state
-    pub fn into_inner(self) -> ScratchGlobalState {
+    pub fn into_inner(self) -> ScratchGlobalState<S> {
         self.state
     }
 }
@@ -162,10 +168,49 @@ impl EngineState<LmdbGlobalState> {
         state_root_hash: Digest,
         scratch_global_state: ScratchGlobalState,
     ) -> Result<Digest, Error> {
-        let stored_values = scratch_global_state.into_inner();
-        self.state
-            .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)
-            .map_err(Into::into)
+        let (stored_values, pruned_keys) = scratch_global_state.into_inner();
+        let post_state_hash =
+            self.state
+                .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)?;
+        delete_pruned_keys(&self.state, post_state_hash, pruned_keys)
+    }
+}
+
+impl EngineState<PersistentGlobalState> {
+    /// Gets underlying PersistentGlobalState
+    pub fn get_state(&self) -> &PersistentGlobalState {
+        &self.state
+    }
+
+    /// Flushes the backing store to disk when manual sync is enabled in the config.toml.
+    pub fn flush_environment(&self) -> Result<(), storage_error::Error> {
+        if self.state.is_manual_sync_enabled() {
+            self.state.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Provide a local cached-only version of engine-state.
+    pub fn get_scratch_engine_state(
+        &self,
+    ) -> EngineState<ScratchGlobalState<PersistentGlobalState>> {
+        EngineState {
+            config: self.config.clone(),
+            state: self.state.create_scratch(),
+        }
+    }
+
+    /// Writes state cached in an `EngineState<ScratchEngineState>` to the backing store.
+    pub fn write_scratch_to_db(
+        &self,
+        state_root_hash: Digest,
+        scratch_global_state: ScratchGlobalState<PersistentGlobalState>,
+    ) -> Result<Digest, Error> {
+        let (stored_values, pruned_keys) = scratch_global_state.into_inner();
+        let post_state_hash =
+            self.state
+                .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)?;
+        delete_pruned_keys(&self.state, post_state_hash, pruned_keys)
     }
 }
 
@@ -550,27 +595,85 @@ where
     pub fn run_execute(
         &self,
         correlation_id: CorrelationId,
-        mut exec_request: ExecuteRequest,
+        exec_request: ExecuteRequest,
     ) -> Result<ExecutionResults, Error> {
+        let results = self
+            .run_execute_with_phase_costs(correlation_id, exec_request)?
+            .into_iter()
+            .map(|(result, _phase_costs)| result)
+            .collect();
+        Ok(results)
+    }
+
+    /// Runs a deploy execution request in the same way as [`EngineState::run_execute`].
+    ///
+    /// Along with the result of each deploy, returns the gas consumed by its payment and session
+    /// phases.
+    pub fn run_execute_with_phase_costs(
+        &self,
+        correlation_id: CorrelationId,
+        exec_request: ExecuteRequest,
+    ) -> Result<Vec<(ExecutionResult, ExecutionPhaseCosts)>, Error> {
         let executor = Executor::new(self.config().clone());
+        self.execute_deploy_items(
+            correlation_id,
+            exec_request,
+            &executor,
+            |result, phase_costs| (result, phase_costs),
+        )
+    }
 
+    /// Runs a deploy execution request in the same way as
+    /// [`EngineState::run_execute_with_phase_costs`].
+    ///
+    /// Additionally returns a trace of the host functions invoked by each deploy.
+    pub fn run_execute_with_trace(
+        &self,
+        correlation_id: CorrelationId,
+        exec_request: ExecuteRequest,
+    ) -> Result<Vec<(ExecutionResult, ExecutionPhaseCosts, ExecutionTrace)>, Error> {
+        let executor = Executor::new_with_tracing(self.config().clone());
+        self.execute_deploy_items(
+            correlation_id,
+            exec_request,
+            &executor,
+            |result, phase_costs| (result, phase_costs, executor.take_trace()),
+        )
+    }
+
+    fn execute_deploy_items<T>(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+        executor: &Executor,
+        mut on_result: impl FnMut(ExecutionResult, ExecutionPhaseCosts) -> T,
+    ) -> Result<Vec<T>, Error> {
         let deploys = exec_request.take_deploys();
-        let mut results = ExecutionResults::with_capacity(deploys.len());
+        let mut results = Vec::with_capacity(deploys.len());
 
         for deploy_item in deploys {
             let result = match deploy_item.session {
-                ExecutableDeployItem::Transfer { .. } => self.transfer(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-                _ => self.deploy(
+                ExecutableDeployItem::Transfer { .. } => self
+                    .transfer(
+                        correlation_id,
+                        executor,
+                        exec_request.protocol_version,
+                        exec_request.parent_state_hash,
+                        BlockTime::new(exec_request.block_time),
+                        deploy_item,
+                        exec_request.proposer.clone(),
+                    )
+                    .map(|result| {
+                        // Native transfers skip the payment phase entirely.
+                        let phase_costs = ExecutionPhaseCosts {
+                            payment: Gas::default(),
+                            session: result.cost(),
+                        };
+                        (result, phase_costs)
+                    }),
+                _ => self.deploy_with_phase_costs(
                     correlation_id,
-                    &executor,
+                    executor,
                     exec_request.protocol_version,
                     exec_request.parent_state_hash,
                     BlockTime::new(exec_request.block_time),
@@ -579,7 +682,7 @@ where
                 ),
             };
             match result {
-                Ok(result) => results.push_back(result),
+                Ok((result, phase_costs)) => results.push(on_result(result, phase_costs)),
                 Err(error) => {
                     return Err(error);
                 }
@@ -717,9 +820,15 @@ where
 
         let gas_limit = Gas::new(U512::from(std::u64::MAX));
 
-        let wasmless_transfer_gas_cost = Gas::new(U512::from(
-            self.config().system_config().wasmless_transfer_cost(),
-        ));
+        let mut runtime_args_builder =
+            TransferRuntimeArgsBuilder::new(deploy_item.session.args().clone());
+        let has_multiple_targets = runtime_args_builder.has_multiple_targets();
+
+        let wasmless_transfer_gas_cost = Gas::new(
+            self.config()
+                .system_config()
+                .wasmless_transfer_cost_for_targets(runtime_args_builder.target_count()),
+        );
 
         let wasmless_transfer_motes = match Motes::from_gas(
             wasmless_transfer_gas_cost,
@@ -791,104 +900,141 @@ where
         // All wasmless transfer preconditions are met.
         // Any error that occurs in logic below this point would result in a charge for user error.
 
-        let mut runtime_args_builder =
-            TransferRuntimeArgsBuilder::new(deploy_item.session.args().clone());
-
-        let transfer_target_mode = match runtime_args_builder
-            .resolve_transfer_target_mode(correlation_id, Rc::clone(&tracking_copy))
+        let transfer_target_modes = match runtime_args_builder
+            .resolve_transfer_target_modes(correlation_id, Rc::clone(&tracking_copy))
         {
-            Ok(transfer_target_mode) => transfer_target_mode,
+            Ok(transfer_target_modes) => transfer_target_modes,
             Err(error) => return Ok(make_charged_execution_failure(error)),
         };
 
-        // At this point we know target refers to either a purse on an existing account or an
+        // At this point we know each target refers to either a purse on an existing account or an
         // account which has to be created.
 
         if !self.config.allow_unrestricted_transfers()
             && !self.config.is_administrator(&account_hash)
         {
             // We need to make sure that source or target has to be admin.
-            match transfer_target_mode {
-                NewTransferTargetMode::ExistingAccount {
-                    target_account_hash,
-                    ..
-                }
-                | NewTransferTargetMode::CreateAccount(target_account_hash) => {
-                    let is_target_system_account =
-                        target_account_hash == PublicKey::System.to_account_hash();
-                    let is_target_administrator =
-                        self.config.is_administrator(&target_account_hash);
-                    if !(is_target_system_account || is_target_administrator) {
-                        // Transferring from normal account to a purse doesn't work.
+            for transfer_target_mode in &transfer_target_modes {
+                match *transfer_target_mode {
+                    NewTransferTargetMode::ExistingAccount {
+                        target_account_hash,
+                        ..
+                    }
+                    | NewTransferTargetMode::CreateAccount(target_account_hash) => {
+                        let is_target_system_account =
+                            target_account_hash == PublicKey::System.to_account_hash();
+                        let is_target_administrator =
+                            self.config.is_administrator(&target_account_hash);
+                        if !(is_target_system_account || is_target_administrator) {
+                            // Transferring from normal account to a purse doesn't work.
+                            return Ok(make_charged_execution_failure(
+                                execution::Error::DisabledUnrestrictedTransfers.into(),
+                            ));
+                        }
+                    }
+                    NewTransferTargetMode::PurseExists(_) => {
+                        // We don't know who is the target and we can't simply reverse search
+                        // account/contract that owns it. We also can't know if purse is owned
+                        // exactly by one entity in the system.
                         return Ok(make_charged_execution_failure(
                             execution::Error::DisabledUnrestrictedTransfers.into(),
                         ));
                     }
                 }
-                NewTransferTargetMode::PurseExists(_) => {
-                    // We don't know who is the target and we can't simply reverse search
-                    // account/contract that owns it. We also can't know if purse is owned exactly
-                    // by one entity in the system.
-                    return Ok(make_charged_execution_failure(
-                        execution::Error::DisabledUnrestrictedTransfers.into(),
-                    ));
-                }
             }
         }
 
-        match transfer_target_mode {
-            NewTransferTargetMode::ExistingAccount { .. }
-            | NewTransferTargetMode::PurseExists(_) => {
-                // Noop
+        // The calls made for a transfer to multiple targets share an address generator, so that
+        // the purses of new accounts and the transfer records all get unique addresses.
+        let shared_address_generator = Rc::new(RefCell::new(AddressGenerator::new(
+            deploy_item.deploy_hash.as_bytes(),
+            Phase::Session,
+        )));
+        let session_address_generator = || {
+            if has_multiple_targets {
+                Rc::clone(&shared_address_generator)
+            } else {
+                Rc::new(RefCell::new(AddressGenerator::new(
+                    deploy_item.deploy_hash.as_bytes(),
+                    Phase::Session,
+                )))
             }
-            NewTransferTargetMode::CreateAccount(account_hash) => {
-                let create_purse_stack = self.get_new_system_call_stack();
-                let (maybe_uref, execution_result): (Option<URef>, ExecutionResult) = executor
-                    .call_system_contract(
-                        DirectSystemContractCall::CreatePurse,
-                        RuntimeArgs::new(), // mint create takes no arguments
-                        &account,
-                        authorization_keys.clone(),
-                        blocktime,
-                        deploy_item.deploy_hash,
-                        gas_limit,
-                        protocol_version,
-                        correlation_id,
-                        Rc::clone(&tracking_copy),
-                        Phase::Session,
-                        create_purse_stack,
-                        // We're just creating a purse.
-                        U512::zero(),
-                    );
-                match maybe_uref {
-                    Some(main_purse) => {
-                        let new_account = {
-                            let named_keys = NamedKeys::default();
-                            Account::create(account_hash, named_keys, main_purse)
-                        };
-                        // write new account
-                        tracking_copy.borrow_mut().write(
-                            Key::Account(account_hash),
-                            StoredValue::Account(new_account),
-                        );
+        };
+
+        let mut new_account_hashes = BTreeSet::new();
+        for transfer_target_mode in transfer_target_modes {
+            match transfer_target_mode {
+                NewTransferTargetMode::ExistingAccount { .. }
+                | NewTransferTargetMode::PurseExists(_) => {
+                    // Noop
+                }
+                NewTransferTargetMode::CreateAccount(account_hash) => {
+                    if !new_account_hashes.insert(account_hash) {
+                        // The account was created for an earlier target of this transfer.
+                        continue;
                     }
-                    None => {
-                        // This case implies that the execution_result is a failure variant as
-                        // implemented inside host_exec().
-                        let error = execution_result
-                            .take_error()
-                            .unwrap_or(Error::InsufficientPayment);
-                        return Ok(make_charged_execution_failure(error));
+                    let create_purse_stack = self.get_new_system_call_stack();
+                    let (maybe_uref, execution_result): (Option<URef>, ExecutionResult) = executor
+                        .call_system_contract_with_address_generator(
+                            DirectSystemContractCall::CreatePurse,
+                            RuntimeArgs::new(), // mint create takes no arguments
+                            &account,
+                            authorization_keys.clone(),
+                            blocktime,
+                            deploy_item.deploy_hash,
+                            gas_limit,
+                            protocol_version,
+                            correlation_id,
+                            Rc::clone(&tracking_copy),
+                            Phase::Session,
+                            create_purse_stack,
+                            // We're just creating a purse.
+                            U512::zero(),
+                            session_address_generator(),
+                        );
+                    match maybe_uref {
+                        Some(main_purse) => {
+                            let new_account = {
+                                let named_keys = NamedKeys::default();
+                                Account::create(account_hash, named_keys, main_purse)
+                            };
+                            // write new account
+                            tracking_copy.borrow_mut().write(
+                                Key::Account(account_hash),
+                                StoredValue::Account(new_account),
+                            );
+                        }
+                        None => {
+                            // This case implies that the execution_result is a failure variant as
+                            // implemented inside host_exec().
+                            let error = execution_result
+                                .take_error()
+                                .unwrap_or(Error::InsufficientPayment);
+                            return Ok(make_charged_execution_failure(error));
+                        }
                     }
                 }
             }
         }
 
-        let transfer_args =
-            match runtime_args_builder.build(&account, correlation_id, Rc::clone(&tracking_copy)) {
-                Ok(transfer_args) => transfer_args,
-                Err(error) => return Ok(make_charged_execution_failure(error)),
-            };
+        let all_transfer_args = match runtime_args_builder.build_all(
+            &account,
+            correlation_id,
+            Rc::clone(&tracking_copy),
+        ) {
+            Ok(all_transfer_args) => all_transfer_args,
+            Err(error) => return Ok(make_charged_execution_failure(error)),
+        };
+
+        // All the transfers share the source purse and id of the first one.
+        let transfer_args = match all_transfer_args.first() {
+            Some(transfer_args) => *transfer_args,
+            None => {
+                return Ok(make_charged_execution_failure(Error::reverter(
+                    ApiError::MissingArgument,
+                )))
+            }
+        };
 
         let payment_uref;
 
@@ -917,9 +1063,16 @@ where
                 account_main_purse_balance
             };
 
-            let transfer_amount_motes = Motes::new(transfer_args.amount());
+            let transfer_amount_motes =
+                all_transfer_args
+                    .iter()
+                    .try_fold(Motes::zero(), |total_amount, transfer_args| {
+                        total_amount.checked_add(Motes::new(transfer_args.amount()))
+                    });
 
-            match wasmless_transfer_motes.checked_add(transfer_amount_motes) {
+            match transfer_amount_motes
+                .and_then(|amount| wasmless_transfer_motes.checked_add(amount))
+            {
                 Some(total_amount) if source_purse_balance < total_amount => {
                     // We can't continue if the minimum funds in source purse are lower than the
                     // required cost.
@@ -999,16 +1152,7 @@ where
                 return Ok(make_charged_execution_failure(error));
             }
 
-            let transfer_result = match actual_result {
-                Some(Ok(())) => Ok(()),
-                Some(Err(mint_error)) => match mint::Error::try_from(mint_error) {
-                    Ok(mint_error) => Err(ApiError::from(mint_error)),
-                    Err(_) => Err(ApiError::Transfer),
-                },
-                None => Err(ApiError::Transfer),
-            };
-
-            if let Err(error) = transfer_result {
+            if let Err(error) = mint_transfer_result(actual_result) {
                 return Ok(make_charged_execution_failure(Error::Exec(
                     ExecError::Revert(error),
                 )));
@@ -1052,34 +1196,57 @@ where
             payment_result.with_cost(payment_gas)
         };
 
-        let runtime_args = match RuntimeArgs::try_from(transfer_args) {
-            Ok(runtime_args) => runtime_args,
-            Err(error) => {
-                return Ok(make_charged_execution_failure(
-                    ExecError::from(error).into(),
-                ))
+        let mut session_result = ExecutionResult::default();
+        let mut transfers = vec![];
+        for transfer_args in all_transfer_args {
+            let runtime_args = match RuntimeArgs::try_from(transfer_args) {
+                Ok(runtime_args) => runtime_args,
+                Err(error) => {
+                    return Ok(make_charged_execution_failure(
+                        ExecError::from(error).into(),
+                    ))
+                }
+            };
+
+            let transfer_stack = self.get_new_system_call_stack();
+            let (transfer_result, result): (Option<Result<(), u8>>, ExecutionResult) = executor
+                .call_system_contract_with_address_generator(
+                    DirectSystemContractCall::Transfer,
+                    runtime_args,
+                    &account,
+                    authorization_keys.clone(),
+                    blocktime,
+                    deploy_item.deploy_hash,
+                    gas_limit,
+                    protocol_version,
+                    correlation_id,
+                    Rc::clone(&tracking_copy),
+                    Phase::Session,
+                    transfer_stack,
+                    // We limit native transfer to the amount that user signed over as `amount`
+                    // argument.
+                    transfer_args.amount(),
+                    session_address_generator(),
+                );
+            session_result = result;
+
+            // A transfer to multiple targets only succeeds if each of its transfers does.
+            if has_multiple_targets {
+                if session_result.is_failure() {
+                    break;
+                }
+                if let Err(error) = mint_transfer_result(transfer_result) {
+                    session_result =
+                        ExecutionResult::precondition_failure(ExecError::Revert(error).into());
+                    break;
+                }
+                transfers.extend_from_slice(session_result.transfers());
             }
-        };
+        }
 
-        let transfer_stack = self.get_new_system_call_stack();
-        let (_, mut session_result): (Option<Result<(), u8>>, ExecutionResult) = executor
-            .call_system_contract(
-                DirectSystemContractCall::Transfer,
-                runtime_args,
-                &account,
-                authorization_keys.clone(),
-                blocktime,
-                deploy_item.deploy_hash,
-                gas_limit,
-                protocol_version,
-                correlation_id,
-                Rc::clone(&tracking_copy),
-                Phase::Session,
-                transfer_stack,
-                // We limit native transfer to the amount that user signed over as `amount`
-                // argument.
-                transfer_args.amount(),
-            );
+        if has_multiple_targets && session_result.is_success() {
+            session_result = session_result.with_transfers(transfers);
+        }
 
         // User is already charged fee for wasmless contract, and we need to make sure we will not
         // charge for anything that happens while calling transfer entrypoint.
@@ -1134,7 +1301,7 @@ where
                     Phase::FinalizePayment,
                     finalize_payment_stack,
                     // Spending limit is cost of wasmless execution.
-                    U512::from(self.config().system_config().wasmless_transfer_cost()),
+                    wasmless_transfer_gas_cost.value(),
                 );
 
             finalize_result
@@ -1193,6 +1360,62 @@ where
         blocktime: BlockTime,
         deploy_item: DeployItem,
         proposer: PublicKey,
+    ) -> Result<ExecutionResult, Error> {
+        self.deploy_with_phase_costs(
+            correlation_id,
+            executor,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            deploy_item,
+            proposer,
+        )
+        .map(|(result, _phase_costs)| result)
+    }
+
+    /// Executes a deploy in the same way as [`EngineState::deploy`].
+    ///
+    /// Along with the [`ExecutionResult`], returns the gas consumed by the payment and session
+    /// phases of the deploy.
+    #[allow(clippy::too_many_arguments)]
+    pub fn deploy_with_phase_costs(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Digest,
+        blocktime: BlockTime,
+        deploy_item: DeployItem,
+        proposer: PublicKey,
+    ) -> Result<(ExecutionResult, ExecutionPhaseCosts), Error> {
+        let mut maybe_payment_cost = None;
+        let result = self.execute_deploy(
+            correlation_id,
+            executor,
+            protocol_version,
+            prestate_hash,
+            blocktime,
+            deploy_item,
+            proposer,
+            &mut maybe_payment_cost,
+        )?;
+        let phase_costs = ExecutionPhaseCosts::new(result.cost(), maybe_payment_cost);
+        Ok((result, phase_costs))
+    }
+
+    /// Executes a deploy, setting `maybe_payment_cost` to the gas consumed by the payment code if
+    /// execution reaches the session phase.
+    #[allow(clippy::too_many_arguments)]
+    fn execute_deploy(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Digest,
+        blocktime: BlockTime,
+        deploy_item: DeployItem,
+        proposer: PublicKey,
+        maybe_payment_cost: &mut Option<Gas>,
     ) -> Result<ExecutionResult, Error> {
         // spec: https://casperlabs.atlassian.net/wiki/spaces/EN/pages/123404576/Payment+code+execution+specification
 
@@ -1623,6 +1846,7 @@ where
         // NOTE: session_code_spec_3: (do not include session execution effects in
         // results) is enforced in execution_result_builder.build()
         execution_result_builder.set_session_execution_result(session_result);
+        *maybe_payment_cost = Some(payment_result_cost);
 
         // payment_code_spec_5: run finalize process
         let finalize_result: ExecutionResult = {
@@ -1919,6 +2143,103 @@ where
         Ok(GetBidsResult::Success { bids })
     }
 
+    /// Obtains a page of the differences between the global state under two state root hashes.
+    pub fn get_state_diff(
+        &self,
+        correlation_id: CorrelationId,
+        state_diff_request: StateDiffRequest,
+    ) -> Result<StateDiffResult, Error> {
+        let result = self
+            .state
+            .diff(
+                correlation_id,
+                state_diff_request.before(),
+                state_diff_request.after(),
+                state_diff_request.key_prefix(),
+                state_diff_request.page_start(),
+                state_diff_request.page_size(),
+            )
+            .map_err(|error| Error::Exec(error.into()))?;
+        Ok(match result {
+            DiffResult::RootNotFound(root) => StateDiffResult::RootNotFound(root),
+            DiffResult::MissingTrie(hash) => StateDiffResult::MissingTrie(hash),
+            DiffResult::Success {
+                entries,
+                next_page_start,
+            } => StateDiffResult::Success {
+                entries: entries
+                    .into_iter()
+                    .map(|entry| StateDiffEntry {
+                        key: entry.key,
+                        before: entry.before,
+                        after: entry.after,
+                    })
+                    .collect(),
+                next_page_start,
+            },
+        })
+    }
+
+    /// Obtains a page of the keys in global state with a given prefix.
+    ///
+    /// At most the engine's max keys page size keys are examined for a single page, so a page may
+    /// hold fewer keys than requested, or none, while further keys remain to be examined.
+    pub fn get_keys(
+        &self,
+        correlation_id: CorrelationId,
+        get_keys_request: GetKeysRequest,
+    ) -> Result<GetKeysResult, Error> {
+        let max_page_size = self.config.max_keys_page_size();
+        let page_size = get_keys_request
+            .page_size()
+            .map_or(max_page_size, |page_size| page_size.min(max_page_size))
+            .max(1);
+        let result = self
+            .state
+            .leaves_with_prefix(
+                correlation_id,
+                get_keys_request.state_hash(),
+                &get_keys_request.prefix().to_bytes(),
+                get_keys_request.page_start(),
+                page_size as usize,
+            )
+            .map_err(|error| Error::Exec(error.into()))?;
+        let (leaves, next_page_start) = match result {
+            LeavesResult::RootNotFound => return Ok(GetKeysResult::RootNotFound),
+            LeavesResult::MissingTrie(hash) => return Ok(GetKeysResult::MissingTrie(hash)),
+            LeavesResult::Success {
+                leaves,
+                next_page_start,
+            } => (leaves, next_page_start),
+        };
+
+        let keys = match get_keys_request.prefix() {
+            KeyPrefix::Bytes(_) => leaves.into_iter().map(|(key, _)| key).collect(),
+            KeyPrefix::DictionaryItems(seed_uref_addr) => leaves
+                .into_iter()
+                .filter_map(|(key, value)| match value {
+                    StoredValue::CLValue(cl_value) => cl_value
+                        .into_t::<DictionaryValue>()
+                        .ok()
+                        .filter(|dictionary_value| {
+                            dictionary_value.seed_uref_addr() == seed_uref_addr.as_slice()
+                                && key
+                                    != dictionary::dictionary_index_key(
+                                        *seed_uref_addr,
+                                        dictionary_value.dictionary_item_key_bytes(),
+                                    )
+                        })
+                        .map(|_| key),
+                    _ => None,
+                })
+                .collect(),
+        };
+        Ok(GetKeysResult::Success {
+            keys,
+            next_page_start,
+        })
+    }
+
     /// Executes a step request.
     pub fn commit_step(
         &self,
@@ -2275,6 +2596,42 @@ where
     }
 }
 
+/// Deletes the keys pruned in a scratch global state from the global state under `state_root_hash`,
+/// returning the resulting state root hash.
+fn delete_pruned_keys<S>(
+    state: &S,
+    mut state_root_hash: Digest,
+    pruned_keys: HashSet<Key>,
+) -> Result<Digest, Error>
+where
+    S: StateProvider,
+    S::Error: Into<Error>,
+{
+    for key in pruned_keys {
+        state_root_hash = match state
+            .delete_keys(CorrelationId::new(), state_root_hash, &[key])
+            .map_err(Into::into)?
+        {
+            DeleteResult::Deleted(post_state_hash) => post_state_hash,
+            DeleteResult::DoesNotExist => return Err(CommitError::KeyNotFound(key).into()),
+            DeleteResult::RootNotFound => return Err(Error::RootNotFound(state_root_hash)),
+        };
+    }
+    Ok(state_root_hash)
+}
+
+/// Converts the value returned by the mint's transfer entry point into a result.
+fn mint_transfer_result(actual_result: Option<Result<(), u8>>) -> Result<(), ApiError> {
+    match actual_result {
+        Some(Ok(())) => Ok(()),
+        Some(Err(mint_error)) => match mint::Error::try_from(mint_error) {
+            Ok(mint_error) => Err(ApiError::from(mint_error)),
+            Err(_) => Err(ApiError::Transfer),
+        },
+        None => Err(ApiError::Transfer),
+    }
+}
+
 fn log_execution_result(preamble: &'static str, result: &ExecutionResult) {
     trace!("{}: {:?}", preamble, result);
     match result {
@@ -2282,11 +2639,13 @@ fn log_execution_result(preamble: &'static str, result: &ExecutionResult) {
             transfers,
             cost,
             execution_journal,
+            events,
         } => {
             debug!(
                 %cost,
                 transfer_count=%transfers.len(),
                 journal_entries=%execution_journal.len(),
+                event_count=%events.len(),
                 "{}: execution success",
                 preamble
             );
@@ -2296,12 +2655,14 @@ fn log_execution_result(preamble: &'static str, result: &ExecutionResult) {
             transfers,
             cost,
             execution_journal,
+            events,
         } => {
             debug!(
                 %error,
                 %cost,
                 transfer_count=%transfers.len(),
                 journal_entries=%execution_journal.len(),
+                event_count=%events.len(),
                 "{}: execution failure",
                 preamble
             );
@@ -2316,6 +2677,7 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
             transfers: _,
             cost: _,
             execution_journal: _,
+            events: _,
         } => match error {
             Error::Exec(err) => match err {
                 ExecError::WasmPreprocessing(_) | ExecError::UnsupportedWasmStart => true,
@@ -2365,7 +2727,8 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
                 | ExecError::ValueTooLarge
                 | ExecError::MissingRuntimeStack
                 | ExecError::DisabledContract(_)
-                | ExecError::DisabledUnrestrictedTransfers => false,
+                | ExecError::DisabledUnrestrictedTransfers
+                | ExecError::IncompleteGlobalState => false,
             },
             Error::WasmPreprocessing(_) => true,
             Error::WasmSerialization(_) => true,
@@ -0,0 +1,100 @@
+//! Support for comparing the global state under two state root hashes.
+use casper_hashing::Digest;
+use casper_types::{Key, KeyTag, StoredValue};
+
+/// Represents a request to obtain the differences between the global state under two state root
+/// hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiffRequest {
+    before: Digest,
+    after: Digest,
+    key_prefix: Vec<u8>,
+    page_start: Option<Key>,
+    page_size: usize,
+}
+
+impl StateDiffRequest {
+    /// Creates new request for at most `page_size` differences, starting from `page_start` if
+    /// given.
+    ///
+    /// Only keys whose serialized bytes start with `key_prefix` are compared.
+    pub fn new(
+        before: Digest,
+        after: Digest,
+        key_prefix: Vec<u8>,
+        page_start: Option<Key>,
+        page_size: usize,
+    ) -> Self {
+        StateDiffRequest {
+            before,
+            after,
+            key_prefix,
+            page_start,
+            page_size,
+        }
+    }
+
+    /// Creates new request comparing only keys with the given tag.
+    pub fn with_key_tag(
+        before: Digest,
+        after: Digest,
+        key_tag: KeyTag,
+        page_start: Option<Key>,
+        page_size: usize,
+    ) -> Self {
+        StateDiffRequest::new(before, after, vec![key_tag as u8], page_start, page_size)
+    }
+
+    /// Returns the state root hash of the earlier global state.
+    pub fn before(&self) -> Digest {
+        self.before
+    }
+
+    /// Returns the state root hash of the later global state.
+    pub fn after(&self) -> Digest {
+        self.after
+    }
+
+    /// Returns the prefix of the serialized keys which are compared.
+    pub fn key_prefix(&self) -> &[u8] {
+        &self.key_prefix
+    }
+
+    /// Returns the first key of the requested page, if any.
+    pub fn page_start(&self) -> Option<&Key> {
+        self.page_start.as_ref()
+    }
+
+    /// Returns the maximum number of differences to return.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+}
+
+/// A key whose value differs between two global states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiffEntry {
+    /// The key.
+    pub key: Key,
+    /// The value under the key in the earlier global state, or `None` if the key was added.
+    pub before: Option<StoredValue>,
+    /// The value under the key in the later global state, or `None` if the key was removed.
+    pub after: Option<StoredValue>,
+}
+
+/// Represents a result of a `get_state_diff` request.
+#[derive(Debug)]
+pub enum StateDiffResult {
+    /// The state root hash was not found.
+    RootNotFound(Digest),
+    /// The global state under one of the state root hashes is incomplete; a trie with the given
+    /// hash is missing.
+    MissingTrie(Digest),
+    /// Contains a page of the differences between the two global states.
+    Success {
+        /// The keys whose values differ, ordered by their serialized bytes.
+        entries: Vec<StateDiffEntry>,
+        /// The first key of the next page, if there are further differences.
+        next_page_start: Option<Key>,
+    },
+}
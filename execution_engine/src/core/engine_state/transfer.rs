@@ -3,8 +3,8 @@ use std::{cell::RefCell, convert::TryFrom, rc::Rc};
 use casper_types::{
     account::{Account, AccountHash},
     system::mint,
-    AccessRights, ApiError, CLType, CLValueError, Key, PublicKey, RuntimeArgs, StoredValue, URef,
-    U512,
+    AccessRights, ApiError, CLType, CLValue, CLValueError, Key, PublicKey, RuntimeArgs,
+    StoredValue, URef, U512,
 };
 
 use crate::{
@@ -17,6 +17,12 @@ use crate::{
     storage::global_state::StateReader,
 };
 
+/// The name of the argument of a native transfer to multiple targets which lists the targets.
+const ARG_TARGETS: &str = "targets";
+/// The name of the argument of a native transfer to multiple targets which lists the amount to be
+/// transferred to each of the targets.
+const ARG_AMOUNTS: &str = "amounts";
+
 /// A target mode indicates if a native transfer's arguments will resolve to an existing purse, or
 /// will have to create a new account first.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -46,6 +52,15 @@ pub(crate) enum NewTransferTargetMode {
     CreateAccount(AccountHash),
 }
 
+/// The target of a native transfer, as given in its arguments.
+#[derive(Copy, Clone, Debug)]
+enum TransferTarget {
+    /// A purse.
+    Purse(URef),
+    /// The main purse of an account, which may not exist yet.
+    Account(AccountHash),
+}
+
 /// Mint's transfer arguments.
 ///
 /// A struct has a benefit of static typing, which is helpful while resolving the arguments.
@@ -221,20 +236,83 @@ impl TransferRuntimeArgsBuilder {
         }
     }
 
-    /// Resolves a transfer target mode.
-    ///
-    /// User has to specify a "target" argument which must be one of the following types:
-    ///   * an existing purse [`URef`]
-    ///   * a 32-byte array, interpreted as an account hash
-    ///   * a [`Key::Account`], from which the account hash is extracted
-    ///   * a [`PublicKey`], which is converted to an account hash
-    ///
-    /// If the "target" account hash is not existing, then a special variant is returned that
-    /// indicates that the system has to create new account first.
+    /// Parses a transfer target from the value of a "target" argument.
+    fn parse_target(cl_value: &CLValue) -> Result<TransferTarget, Error> {
+        match cl_value.cl_type() {
+            CLType::URef => {
+                let uref: URef = cl_value.clone().into_t().map_err(Error::reverter)?;
+                Ok(TransferTarget::Purse(uref))
+            }
+            CLType::ByteArray(32) => {
+                let account_hash: AccountHash =
+                    cl_value.clone().into_t().map_err(Error::reverter)?;
+                Ok(TransferTarget::Account(account_hash))
+            }
+            CLType::Key => {
+                let account_key: Key = cl_value.clone().into_t().map_err(Error::reverter)?;
+                Self::parse_account_key(account_key)
+            }
+            CLType::PublicKey => {
+                let public_key: PublicKey = cl_value.clone().into_t().map_err(Error::reverter)?;
+                Ok(TransferTarget::Account(AccountHash::from(&public_key)))
+            }
+            _ => Err(Error::reverter(ApiError::InvalidArgument)),
+        }
+    }
+
+    /// Parses transfer targets from the value of a "targets" argument, which must be a list of
+    /// any one of the types accepted as a "target" argument.
+    fn parse_targets(cl_value: &CLValue) -> Result<Vec<TransferTarget>, Error> {
+        let element_type = match cl_value.cl_type() {
+            CLType::List(element_type) => element_type.as_ref(),
+            _ => return Err(Error::reverter(ApiError::InvalidArgument)),
+        };
+        let cl_value = cl_value.clone();
+        match element_type {
+            CLType::URef => {
+                let urefs: Vec<URef> = cl_value.into_t().map_err(Error::reverter)?;
+                Ok(urefs.into_iter().map(TransferTarget::Purse).collect())
+            }
+            CLType::ByteArray(32) => {
+                let account_hashes: Vec<AccountHash> =
+                    cl_value.into_t().map_err(Error::reverter)?;
+                Ok(account_hashes
+                    .into_iter()
+                    .map(TransferTarget::Account)
+                    .collect())
+            }
+            CLType::Key => {
+                let account_keys: Vec<Key> = cl_value.into_t().map_err(Error::reverter)?;
+                account_keys
+                    .into_iter()
+                    .map(Self::parse_account_key)
+                    .collect()
+            }
+            CLType::PublicKey => {
+                let public_keys: Vec<PublicKey> = cl_value.into_t().map_err(Error::reverter)?;
+                Ok(public_keys
+                    .iter()
+                    .map(|public_key| TransferTarget::Account(AccountHash::from(public_key)))
+                    .collect())
+            }
+            _ => Err(Error::reverter(ApiError::InvalidArgument)),
+        }
+    }
+
+    fn parse_account_key(account_key: Key) -> Result<TransferTarget, Error> {
+        account_key
+            .into_account()
+            .map(TransferTarget::Account)
+            .ok_or_else(|| Error::reverter(ApiError::Transfer))
+    }
+
+    /// Resolves the target mode of a transfer target.
     ///
-    /// Returns [`NewTransferTargetMode`] with a resolved variant.
-    pub(super) fn resolve_transfer_target_mode<R>(
-        &mut self,
+    /// If the target account does not exist, then a special variant is returned that indicates
+    /// that the system has to create new account first.
+    fn resolve_target<R>(
+        &self,
+        target: TransferTarget,
         correlation_id: CorrelationId,
         tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
     ) -> Result<NewTransferTargetMode, Error>
@@ -242,39 +320,15 @@ impl TransferRuntimeArgsBuilder {
         R: StateReader<Key, StoredValue>,
         R::Error: Into<ExecError>,
     {
-        let imputed_runtime_args = &self.inner;
-        let arg_name = mint::ARG_TARGET;
-
-        let account_hash = match imputed_runtime_args.get(arg_name) {
-            Some(cl_value) if *cl_value.cl_type() == CLType::URef => {
-                let uref: URef = cl_value.clone().into_t().map_err(Error::reverter)?;
-
+        let account_hash = match target {
+            TransferTarget::Purse(uref) => {
                 if !self.purse_exists(uref, correlation_id, tracking_copy) {
                     return Err(Error::reverter(ApiError::InvalidPurse));
                 }
 
                 return Ok(NewTransferTargetMode::PurseExists(uref));
             }
-            Some(cl_value) if *cl_value.cl_type() == CLType::ByteArray(32) => {
-                let account_hash: AccountHash =
-                    cl_value.clone().into_t().map_err(Error::reverter)?;
-                account_hash
-            }
-            Some(cl_value) if *cl_value.cl_type() == CLType::Key => {
-                let account_key: Key = cl_value.clone().into_t().map_err(Error::reverter)?;
-
-                let account_hash: AccountHash = account_key
-                    .into_account()
-                    .ok_or_else(|| Error::reverter(ApiError::Transfer))?;
-                account_hash
-            }
-            Some(cl_value) if *cl_value.cl_type() == CLType::PublicKey => {
-                let public_key: PublicKey = cl_value.clone().into_t().map_err(Error::reverter)?;
-
-                AccountHash::from(&public_key)
-            }
-            Some(_) => return Err(Error::reverter(ApiError::InvalidArgument)),
-            None => return Err(Error::reverter(ApiError::MissingArgument)),
+            TransferTarget::Account(account_hash) => account_hash,
         };
 
         match tracking_copy
@@ -292,6 +346,86 @@ impl TransferRuntimeArgsBuilder {
         }
     }
 
+    /// Resolves a transfer target mode.
+    ///
+    /// User has to specify a "target" argument which must be one of the following types:
+    ///   * an existing purse [`URef`]
+    ///   * a 32-byte array, interpreted as an account hash
+    ///   * a [`Key::Account`], from which the account hash is extracted
+    ///   * a [`PublicKey`], which is converted to an account hash
+    ///
+    /// If the "target" account hash is not existing, then a special variant is returned that
+    /// indicates that the system has to create new account first.
+    ///
+    /// Returns [`NewTransferTargetMode`] with a resolved variant.
+    pub(super) fn resolve_transfer_target_mode<R>(
+        &mut self,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+    ) -> Result<NewTransferTargetMode, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<ExecError>,
+    {
+        let target = match self.inner.get(mint::ARG_TARGET) {
+            Some(cl_value) => Self::parse_target(cl_value)?,
+            None => return Err(Error::reverter(ApiError::MissingArgument)),
+        };
+        self.resolve_target(target, correlation_id, tracking_copy)
+    }
+
+    /// Returns `true` if the transfer has a "targets" argument, making it a transfer to multiple
+    /// targets.
+    pub(super) fn has_multiple_targets(&self) -> bool {
+        self.inner.get(ARG_TARGETS).is_some()
+    }
+
+    /// Returns the number of targets of the transfer, which determines its cost.
+    ///
+    /// If the "targets" argument is invalid, the transfer fails and it is charged as a transfer
+    /// to a single target.
+    pub(super) fn target_count(&self) -> usize {
+        match self.inner.get(ARG_TARGETS) {
+            Some(cl_value) => Self::parse_targets(cl_value)
+                .map(|targets| targets.len().max(1))
+                .unwrap_or(1),
+            None => 1,
+        }
+    }
+
+    /// Resolves the target modes of all the targets of the transfer.
+    ///
+    /// If the user specifies a "targets" argument, each of the listed targets is resolved in the
+    /// same way as a "target" argument.  The list must not be empty.  Otherwise, the "target"
+    /// argument is resolved.
+    pub(super) fn resolve_transfer_target_modes<R>(
+        &mut self,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+    ) -> Result<Vec<NewTransferTargetMode>, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<ExecError>,
+    {
+        let targets = match self.inner.get(ARG_TARGETS) {
+            Some(cl_value) => Self::parse_targets(cl_value)?,
+            None => {
+                let target_mode =
+                    self.resolve_transfer_target_mode(correlation_id, tracking_copy)?;
+                return Ok(vec![target_mode]);
+            }
+        };
+
+        if targets.is_empty() {
+            return Err(Error::reverter(ApiError::InvalidArgument));
+        }
+
+        targets
+            .into_iter()
+            .map(|target| self.resolve_target(target, correlation_id, Rc::clone(&tracking_copy)))
+            .collect()
+    }
+
     /// Resolves amount.
     ///
     /// User has to specify "amount" argument that could be either a [`U512`] or a u64.
@@ -321,6 +455,35 @@ impl TransferRuntimeArgsBuilder {
         Ok(amount)
     }
 
+    /// Resolves the amounts of a transfer to multiple targets.
+    ///
+    /// User has to specify an "amounts" argument that is a list of [`U512`] with one non-zero
+    /// amount for each of the `target_count` targets.
+    fn resolve_amounts(&self, target_count: usize) -> Result<Vec<U512>, Error> {
+        let amounts = match self.inner.get(ARG_AMOUNTS) {
+            Some(amounts_value)
+                if *amounts_value.cl_type() == CLType::List(Box::new(CLType::U512)) =>
+            {
+                amounts_value
+                    .clone()
+                    .into_t::<Vec<U512>>()
+                    .map_err(Error::reverter)?
+            }
+            Some(_) => return Err(Error::reverter(ApiError::InvalidArgument)),
+            None => return Err(Error::reverter(ApiError::MissingArgument)),
+        };
+
+        if amounts.len() != target_count {
+            return Err(Error::reverter(ApiError::InvalidArgument));
+        }
+
+        if amounts.iter().any(U512::is_zero) {
+            return Err(Error::reverter(ApiError::Transfer));
+        }
+
+        Ok(amounts)
+    }
+
     fn resolve_id(&self) -> Result<Option<u64>, Error> {
         let id_value = self
             .inner
@@ -341,9 +504,9 @@ impl TransferRuntimeArgsBuilder {
         R: StateReader<Key, StoredValue>,
         R::Error: Into<ExecError>,
     {
-        let (to, target_uref) = match self
-            .resolve_transfer_target_mode(correlation_id, Rc::clone(&tracking_copy))?
-        {
+        let target_mode =
+            self.resolve_transfer_target_mode(correlation_id, Rc::clone(&tracking_copy))?;
+        let (to, target_uref) = match target_mode {
             NewTransferTargetMode::ExistingAccount {
                 main_purse: purse_uref,
                 target_account_hash: target_account,
@@ -376,4 +539,64 @@ impl TransferRuntimeArgsBuilder {
             arg_id: id,
         })
     }
+
+    /// Creates new [`TransferArgs`] instances, one for each of the targets of the transfer.
+    ///
+    /// All the transfers are made from the same source purse and share the same "id" argument.
+    pub fn build_all<R>(
+        mut self,
+        from: &Account,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+    ) -> Result<Vec<TransferArgs>, Error>
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<ExecError>,
+    {
+        if !self.has_multiple_targets() {
+            return self
+                .build(from, correlation_id, tracking_copy)
+                .map(|transfer_args| vec![transfer_args]);
+        }
+
+        let target_modes =
+            self.resolve_transfer_target_modes(correlation_id, Rc::clone(&tracking_copy))?;
+
+        let source_uref =
+            self.resolve_source_uref(from, correlation_id, Rc::clone(&tracking_copy))?;
+
+        let amounts = self.resolve_amounts(target_modes.len())?;
+
+        let id = self.resolve_id()?;
+
+        target_modes
+            .into_iter()
+            .zip(amounts)
+            .map(|(target_mode, amount)| {
+                let (to, target_uref) = match target_mode {
+                    NewTransferTargetMode::ExistingAccount {
+                        main_purse: purse_uref,
+                        target_account_hash: target_account,
+                    } => (Some(target_account), purse_uref),
+                    NewTransferTargetMode::PurseExists(purse_uref) => (None, purse_uref),
+                    NewTransferTargetMode::CreateAccount(_) => {
+                        // As in `build()`, any new accounts have already been created.
+                        return Err(Error::reverter(ApiError::Transfer));
+                    }
+                };
+
+                if source_uref.addr() == target_uref.addr() {
+                    return Err(Error::reverter(ApiError::InvalidPurse));
+                }
+
+                Ok(TransferArgs {
+                    to,
+                    source: source_uref,
+                    target: target_uref,
+                    amount,
+                    arg_id: id,
+                })
+            })
+            .collect()
+    }
 }
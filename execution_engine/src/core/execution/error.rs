@@ -176,6 +176,9 @@ pub enum Error {
     /// Failed to transfer tokens on a private chain.
     #[error("Failed to transfer with unrestricted transfers disabled")]
     DisabledUnrestrictedTransfers,
+    /// The global state being read is incomplete.
+    #[error("Global state is incomplete")]
+    IncompleteGlobalState,
 }
 
 impl From<wasm_prep::PreprocessingError> for Error {
@@ -16,7 +16,7 @@ use crate::{
             ExecError,
         },
         execution::{address_generator::AddressGenerator, Error},
-        runtime::{Runtime, RuntimeStack},
+        runtime::{ExecutionTrace, ExecutionTracer, Runtime, RuntimeStack},
         runtime_context::RuntimeContext,
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
@@ -35,12 +35,33 @@ fn try_get_amount(runtime_args: &RuntimeArgs) -> Result<U512, ExecError> {
 /// Executor object deals with execution of WASM modules.
 pub struct Executor {
     config: EngineConfig,
+    tracer: Option<ExecutionTracer>,
 }
 
 impl Executor {
     /// Creates new executor object.
     pub fn new(config: EngineConfig) -> Self {
-        Executor { config }
+        Executor {
+            config,
+            tracer: None,
+        }
+    }
+
+    /// Creates new executor object which records a trace of the host functions invoked by the
+    /// code it executes.
+    pub fn new_with_tracing(config: EngineConfig) -> Self {
+        Executor {
+            config,
+            tracer: Some(ExecutionTracer::default()),
+        }
+    }
+
+    /// Takes the trace recorded since the last call, or an empty trace if tracing is not enabled.
+    pub fn take_trace(&self) -> ExecutionTrace {
+        self.tracer
+            .as_ref()
+            .map(ExecutionTracer::take)
+            .unwrap_or_default()
     }
 
     /// Executes a WASM module.
@@ -100,7 +121,7 @@ impl Executor {
             spending_limit,
         );
 
-        let mut runtime = Runtime::new(self.config.clone(), context);
+        let mut runtime = Runtime::new(self.config.clone(), context, self.tracer.clone());
 
         let result = match execution_kind {
             ExecutionKind::Module(module_bytes) => {
@@ -121,13 +142,17 @@ impl Executor {
             Ok(_) => ExecutionResult::Success {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
-                cost: runtime.context().gas_counter(),
+                // The data removed by a successful execution stays removed, so its storage refund
+                // is deducted from the cost.
+                cost: runtime.context().gas_cost(),
+                events: runtime.context().events(),
             },
             Err(error) => ExecutionResult::Failure {
                 error: error.into(),
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events: runtime.context().events(),
             },
         }
     }
@@ -187,22 +212,25 @@ impl Executor {
         );
 
         let execution_journal = tracking_copy.borrow().execution_journal();
+        let events = tracking_copy.borrow().events();
 
         // Standard payment is executed in the calling account's context; the stack already
         // captures that.
-        let mut runtime = Runtime::new(self.config.clone(), runtime_context);
+        let mut runtime = Runtime::new(self.config.clone(), runtime_context, self.tracer.clone());
 
         match runtime.call_host_standard_payment(stack) {
             Ok(()) => ExecutionResult::Success {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events: runtime.context().events(),
             },
             Err(error) => ExecutionResult::Failure {
                 execution_journal,
                 error: error.into(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events,
             },
         }
     }
@@ -236,6 +264,51 @@ impl Executor {
             Rc::new(RefCell::new(generator))
         };
 
+        self.call_system_contract_with_address_generator(
+            direct_system_contract_call,
+            runtime_args,
+            account,
+            authorization_keys,
+            blocktime,
+            deploy_hash,
+            gas_limit,
+            protocol_version,
+            correlation_id,
+            tracking_copy,
+            phase,
+            stack,
+            remaining_spending_limit,
+            address_generator,
+        )
+    }
+
+    /// Calls a system contract in the same way as [`Executor::call_system_contract`], generating
+    /// any new addresses with the given `address_generator`.
+    ///
+    /// Sharing an address generator between calls ensures they do not generate the same addresses.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn call_system_contract_with_address_generator<R, T>(
+        &self,
+        direct_system_contract_call: DirectSystemContractCall,
+        runtime_args: RuntimeArgs,
+        account: &Account,
+        authorization_keys: BTreeSet<AccountHash>,
+        blocktime: BlockTime,
+        deploy_hash: DeployHash,
+        gas_limit: Gas,
+        protocol_version: ProtocolVersion,
+        correlation_id: CorrelationId,
+        tracking_copy: Rc<RefCell<TrackingCopy<R>>>,
+        phase: Phase,
+        stack: RuntimeStack,
+        remaining_spending_limit: U512,
+        address_generator: Rc<RefCell<AddressGenerator>>,
+    ) -> (Option<T>, ExecutionResult)
+    where
+        R: StateReader<Key, StoredValue>,
+        R::Error: Into<Error>,
+        T: FromBytes + CLTyped,
+    {
         // Today lack of existence of the system contract registry and lack of entry
         // for the minimum defined system contracts (mint, auction, handle_payment)
         // should cause the EE to panic. Do not remove the panics.
@@ -247,6 +320,7 @@ impl Executor {
         // Snapshot of effects before execution, so in case of error only nonce update
         // can be returned.
         let execution_journal = tracking_copy.borrow().execution_journal();
+        let events = tracking_copy.borrow().events();
 
         let entry_point_name = direct_system_contract_call.entry_point_name();
 
@@ -306,7 +380,7 @@ impl Executor {
             remaining_spending_limit,
         );
 
-        let mut runtime = Runtime::new(self.config.clone(), runtime_context);
+        let mut runtime = Runtime::new(self.config.clone(), runtime_context, self.tracer.clone());
 
         // DO NOT alter this logic to call a system contract directly (such as via mint_internal,
         // etc). Doing so would bypass necessary context based security checks in some use cases. It
@@ -322,6 +396,7 @@ impl Executor {
                     execution_journal: runtime.context().execution_journal(),
                     transfers: runtime.context().transfers().to_owned(),
                     cost: runtime.context().gas_counter(),
+                    events: runtime.context().events(),
                 }
                 .take_with_ret(ret),
                 Err(error) => ExecutionResult::Failure {
@@ -329,6 +404,7 @@ impl Executor {
                     error: Error::CLValue(error).into(),
                     transfers: runtime.context().transfers().to_owned(),
                     cost: runtime.context().gas_counter(),
+                    events,
                 }
                 .take_without_ret(),
             },
@@ -337,6 +413,7 @@ impl Executor {
                 error: error.into(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events,
             }
             .take_without_ret(),
         }
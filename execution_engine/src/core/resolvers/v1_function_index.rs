@@ -58,6 +58,10 @@ pub(crate) enum FunctionIndex {
     RandomBytes,
     DictionaryReadFuncIndex,
     EnableContractVersion,
+    DictionaryKeysFuncIndex,
+    DictionaryRemoveFuncIndex,
+    PruneFuncIndex,
+    EmitEventFuncIndex,
 }
 
 impl From<FunctionIndex> for usize {
@@ -31,6 +31,7 @@ where
         args: RuntimeArgs,
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
+        self.trace_host_function_call(func);
 
         let host_function_costs = self.config.wasm_config().take_host_function_costs();
 
@@ -1100,6 +1101,81 @@ where
 
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
             }
+
+            FunctionIndex::DictionaryKeysFuncIndex => {
+                // args(0) = pointer to uref in Wasm memory
+                // args(1) = size of uref in Wasm memory
+                // args(2) = pointer to optional page start key in Wasm memory
+                // args(3) = size of optional page start key in Wasm memory
+                // args(4) = maximum number of dictionary items to examine
+                // args(5) = pointer to output size (output param)
+                let (
+                    uref_ptr,
+                    uref_size,
+                    page_start_ptr,
+                    page_start_size,
+                    page_size,
+                    output_size_ptr,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.dictionary_keys,
+                    [
+                        uref_ptr,
+                        uref_size,
+                        page_start_ptr,
+                        page_start_size,
+                        page_size,
+                        output_size_ptr,
+                    ],
+                )?;
+                let ret = self.dictionary_keys(
+                    uref_ptr,
+                    uref_size,
+                    page_start_ptr,
+                    page_start_size,
+                    page_size,
+                    output_size_ptr,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::DictionaryRemoveFuncIndex => {
+                // args(0) = pointer to uref in Wasm memory
+                // args(1) = size of uref in Wasm memory
+                // args(2) = pointer to key bytes pointer in Wasm memory
+                // args(3) = pointer to key bytes size in Wasm memory
+                let (uref_ptr, uref_size, key_bytes_ptr, key_bytes_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.dictionary_remove,
+                    [uref_ptr, uref_size, key_bytes_ptr, key_bytes_size],
+                )?;
+                let ret =
+                    self.dictionary_remove(uref_ptr, uref_size, key_bytes_ptr, key_bytes_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::PruneFuncIndex => {
+                // args(0) = pointer to key in Wasm memory
+                // args(1) = size of key in Wasm memory
+                let (key_ptr, key_size) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.prune, [key_ptr, key_size])?;
+                let ret = self.prune(key_ptr, key_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::EmitEventFuncIndex => {
+                // args(0) = pointer to topic in Wasm memory
+                // args(1) = size of topic in Wasm memory
+                // args(2) = pointer to payload in Wasm memory
+                // args(3) = size of payload in Wasm memory
+                let (topic_ptr, topic_size, payload_ptr, payload_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_event,
+                    [topic_ptr, topic_size, payload_ptr, payload_size],
+                )?;
+                self.emit_event(topic_ptr, topic_size, payload_ptr, payload_size)?;
+                Ok(None)
+            }
         }
     }
 }
@@ -7,6 +7,7 @@ mod host_function_flag;
 mod mint_internal;
 pub mod stack;
 mod standard_payment_internal;
+mod trace;
 mod utils;
 
 use std::{
@@ -50,6 +51,7 @@ use crate::{
     core::{
         engine_state::EngineConfig,
         execution::{self, Error},
+        resolvers::v1_function_index::FunctionIndex,
         runtime::host_function_flag::HostFunctionFlag,
         runtime_context::{self, RuntimeContext},
         tracking_copy::TrackingCopyExt,
@@ -65,6 +67,10 @@ use crate::{
     },
 };
 pub use stack::{RuntimeStack, RuntimeStackFrame, RuntimeStackOverflow};
+pub(crate) use trace::ExecutionTracer;
+pub use trace::{
+    ExecutionTrace, HostFunctionCallTrace, RevertTrace, MAX_TRACED_HOST_FUNCTION_CALLS,
+};
 
 enum CallContractIdentifier {
     Contract {
@@ -85,6 +91,7 @@ pub struct Runtime<'a, R> {
     context: RuntimeContext<'a, R>,
     stack: Option<RuntimeStack>,
     host_function_flag: HostFunctionFlag,
+    tracer: Option<ExecutionTracer>,
 }
 
 impl<'a, R> Runtime<'a, R>
@@ -92,8 +99,12 @@ where
     R: StateReader<Key, StoredValue>,
     R::Error: Into<Error>,
 {
-    /// Creates a new runtime instance.
-    pub(crate) fn new(config: EngineConfig, context: RuntimeContext<'a, R>) -> Self {
+    /// Creates a new runtime instance, recording an execution trace via `tracer` if provided.
+    pub(crate) fn new(
+        config: EngineConfig,
+        context: RuntimeContext<'a, R>,
+        tracer: Option<ExecutionTracer>,
+    ) -> Self {
         Runtime {
             config,
             memory: None,
@@ -102,10 +113,12 @@ where
             context,
             stack: None,
             host_function_flag: HostFunctionFlag::default(),
+            tracer,
         }
     }
 
-    /// Creates a new runtime instance by cloning the config, and host function flag from `self`.
+    /// Creates a new runtime instance by cloning the config, host function flag and tracer from
+    /// `self`.
     fn new_invocation_runtime(
         &self,
         context: RuntimeContext<'a, R>,
@@ -122,10 +135,11 @@ where
             context,
             stack: Some(stack),
             host_function_flag: self.host_function_flag.clone(),
+            tracer: self.tracer.clone(),
         }
     }
 
-    /// Creates a new runtime instance with a stack from `self`.
+    /// Creates a new runtime instance with a stack, and with the tracer from `self`.
     pub(crate) fn new_with_stack(
         &self,
         context: RuntimeContext<'a, R>,
@@ -140,6 +154,7 @@ where
             context,
             stack: Some(stack),
             host_function_flag: self.host_function_flag.clone(),
+            tracer: self.tracer.clone(),
         }
     }
 
@@ -1409,10 +1424,12 @@ where
 
         let result = instance.invoke_export(entry_point.name(), &[], runtime);
 
-        // The `runtime`'s context was initialized with our counter from before the call and any gas
-        // charged by the sub-call was added to its counter - so let's copy the correct value of the
-        // counter from there to our counter.
+        // The `runtime`'s context was initialized with our counter and storage refund from before
+        // the call and any gas charged or refunded by the sub-call was added to them - so let's
+        // copy the correct values from there to ours.
         self.context.set_gas_counter(runtime.context.gas_counter());
+        self.context
+            .set_storage_refund(runtime.context.storage_refund());
 
         {
             let transfers = self.context.transfers_mut();
@@ -1879,6 +1896,29 @@ where
             .map_err(Into::into)
     }
 
+    /// Removes the value under `key` from GlobalState.
+    fn prune(&mut self, key_ptr: u32, key_size: u32) -> Result<Result<(), ApiError>, Trap> {
+        let key = self.key_from_mem(key_ptr, key_size)?;
+        if !self.context.metered_prune_gs(key)? {
+            return Ok(Err(ApiError::ValueNotFound));
+        }
+        Ok(Ok(()))
+    }
+
+    /// Records an event with the topic and payload read from Wasm memory.
+    fn emit_event(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        payload_ptr: u32,
+        payload_size: u32,
+    ) -> Result<(), Trap> {
+        let topic = self.string_from_mem(topic_ptr, topic_size)?;
+        let payload = self.cl_value_from_mem(payload_ptr, payload_size)?;
+        self.context.emit_event(topic, payload);
+        Ok(())
+    }
+
     /// Records a transfer.
     fn record_transfer(
         &mut self,
@@ -1983,7 +2023,30 @@ where
 
     /// Reverts contract execution with a status specified.
     fn revert(&mut self, status: u32) -> Trap {
-        Error::Revert(status.into()).into()
+        let error = ApiError::from(status);
+        if let Some(tracer) = &self.tracer {
+            tracer.record_revert(error, self.context.phase(), self.traced_call_stack());
+        }
+        Error::Revert(error).into()
+    }
+
+    /// Returns the current call stack for inclusion in an execution trace.
+    fn traced_call_stack(&self) -> Vec<CallStackElement> {
+        self.stack
+            .as_ref()
+            .map(|stack| stack.call_stack_elements().clone())
+            .unwrap_or_default()
+    }
+
+    /// Records the invocation of a host function in the execution trace, if tracing is enabled.
+    fn trace_host_function_call(&self, host_function: FunctionIndex) {
+        if let Some(tracer) = &self.tracer {
+            tracer.record_host_function_call(
+                format!("{:?}", host_function),
+                self.context.phase(),
+                self.traced_call_stack(),
+            );
+        }
     }
 
     /// Checks if a caller can manage its own associated keys and thresholds.
@@ -2819,6 +2882,9 @@ where
         T: AsRef<[Cost]> + Copy,
     {
         let cost = host_function.calculate_gas_cost(weights);
+        if let Some(tracer) = &self.tracer {
+            tracer.record_host_function_cost(weights.as_ref(), cost);
+        }
         self.gas(cost)?;
         Ok(())
     }
@@ -2831,7 +2897,7 @@ where
         }
 
         // Create new URef
-        let new_uref = self.context.new_unit_uref()?;
+        let new_uref = self.context.new_dictionary_seed_uref()?;
 
         // create CLValue for return value
         let new_uref_value = CLValue::from_t(new_uref)?;
@@ -2971,6 +3037,79 @@ where
         Ok(Ok(()))
     }
 
+    /// Reads a page of the item keys of a dictionary into the host buffer.
+    fn dictionary_keys(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        page_start_ptr: u32,
+        page_start_size: u32,
+        page_size: u32,
+        output_size_ptr: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        // check we can write to the host buffer
+        if let Err(err) = self.check_host_buffer() {
+            return Ok(Err(err));
+        }
+
+        let uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        let page_start: Option<Key> = self.t_from_mem(page_start_ptr, page_start_size)?;
+        if page_size == 0 || !matches!(page_start, None | Some(Key::Dictionary(_))) {
+            return Ok(Err(ApiError::InvalidArgument));
+        }
+
+        let page = match self.context.dictionary_keys(uref, page_start, page_size)? {
+            Some(page) => page,
+            None => return Ok(Err(ApiError::DictionaryNotIndexed)),
+        };
+        let cl_value = CLValue::from_t(page).map_err(Error::CLValue)?;
+
+        let value_size: u32 = match cl_value.inner_bytes().len().try_into() {
+            Ok(value) => value,
+            Err(_) => return Ok(Err(ApiError::BufferTooSmall)),
+        };
+
+        if let Err(error) = self.write_host_buffer(cl_value) {
+            return Ok(Err(error));
+        }
+
+        let value_bytes = value_size.to_le_bytes(); // Wasm is little-endian
+        if let Err(error) = self.try_get_memory()?.set(output_size_ptr, &value_bytes) {
+            return Err(Error::Interpreter(error.into()).into());
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Removes an item from a dictionary.
+    fn dictionary_remove(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        key_ptr: u32,
+        key_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        let dictionary_item_key_bytes = {
+            if (key_size as usize) > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+                return Ok(Err(ApiError::DictionaryItemKeyExceedsLength));
+            }
+            self.checked_memory_slice(key_ptr as usize, key_size as usize, |data| {
+                std::str::from_utf8(data).map(ToOwned::to_owned)
+            })?
+        };
+
+        let dictionary_item_key = if let Ok(item_key) = dictionary_item_key_bytes {
+            item_key
+        } else {
+            return Ok(Err(ApiError::InvalidDictionaryItemKey));
+        };
+        if !self.context.dictionary_remove(uref, &dictionary_item_key)? {
+            return Ok(Err(ApiError::ValueNotFound));
+        }
+        Ok(Ok(()))
+    }
+
     /// Checks if immediate caller is a system contract or account.
     ///
     /// For cases where call stack is only the session code, then this method returns `true` if the
@@ -0,0 +1,151 @@
+//! Opt-in tracing of the host functions invoked during wasm execution.
+
+use std::{cell::RefCell, mem, rc::Rc};
+
+use casper_types::{system::CallStackElement, ApiError, Gas, Phase};
+
+/// The maximum number of host function calls recorded in a single trace.  Further calls are only
+/// counted.
+pub const MAX_TRACED_HOST_FUNCTION_CALLS: usize = 10_000;
+
+/// A host function invoked by wasm code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostFunctionCallTrace {
+    /// The name of the host function's index.
+    pub host_function: String,
+    /// The arguments used to calculate the cost of the call, typically pointers into and sizes of
+    /// wasm memory.
+    pub args: Vec<u32>,
+    /// The gas charged for the call according to the host function costs.
+    pub gas_charged: Gas,
+    /// The phase during which the call was made.
+    pub phase: Phase,
+    /// The call stack at the time of the call, outermost frame first.
+    pub call_stack: Vec<CallStackElement>,
+}
+
+/// The point at which wasm code reverted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevertTrace {
+    /// The error with which the code reverted.
+    pub error: ApiError,
+    /// The phase during which the code reverted.
+    pub phase: Phase,
+    /// The call stack at the time of the revert, outermost frame first.
+    pub call_stack: Vec<CallStackElement>,
+}
+
+/// A trace of the host functions invoked during the execution of a deploy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    /// The host functions invoked, in order, up to [`MAX_TRACED_HOST_FUNCTION_CALLS`] of them.
+    pub host_function_calls: Vec<HostFunctionCallTrace>,
+    /// The number of host function calls not recorded due to the limit.
+    pub omitted_host_function_calls: u64,
+    /// The point at which the code reverted, if it did.
+    pub revert: Option<RevertTrace>,
+}
+
+/// Collects an [`ExecutionTrace`], shared by all the runtimes involved in the execution of a
+/// deploy.
+#[derive(Clone, Default)]
+pub(crate) struct ExecutionTracer(Rc<RefCell<ExecutionTrace>>);
+
+impl ExecutionTracer {
+    /// Records the invocation of a host function.
+    pub(crate) fn record_host_function_call(
+        &self,
+        host_function: String,
+        phase: Phase,
+        call_stack: Vec<CallStackElement>,
+    ) {
+        let mut trace = self.0.borrow_mut();
+        if trace.host_function_calls.len() < MAX_TRACED_HOST_FUNCTION_CALLS {
+            trace.host_function_calls.push(HostFunctionCallTrace {
+                host_function,
+                args: Vec::new(),
+                gas_charged: Gas::default(),
+                phase,
+                call_stack,
+            });
+        } else {
+            trace.omitted_host_function_calls += 1;
+        }
+    }
+
+    /// Records the cost of the most recently invoked host function.
+    pub(crate) fn record_host_function_cost(&self, args: &[u32], gas_charged: Gas) {
+        let mut trace = self.0.borrow_mut();
+        if trace.omitted_host_function_calls > 0 {
+            return;
+        }
+        if let Some(call) = trace.host_function_calls.last_mut() {
+            call.args = args.to_vec();
+            call.gas_charged = gas_charged;
+        }
+    }
+
+    /// Records the point at which wasm code reverted.
+    pub(crate) fn record_revert(
+        &self,
+        error: ApiError,
+        phase: Phase,
+        call_stack: Vec<CallStackElement>,
+    ) {
+        self.0.borrow_mut().revert = Some(RevertTrace {
+            error,
+            phase,
+            call_stack,
+        });
+    }
+
+    /// Takes the trace collected so far, leaving an empty one in its place.
+    pub(crate) fn take(&self) -> ExecutionTrace {
+        mem::take(&mut *self.0.borrow_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::U512;
+
+    use super::*;
+
+    #[test]
+    fn should_record_cost_of_last_call() {
+        let tracer = ExecutionTracer::default();
+        tracer.record_host_function_call("Write".to_string(), Phase::Session, vec![]);
+        tracer.record_host_function_cost(&[1, 2], Gas::new(U512::from(10)));
+        tracer.record_host_function_call("Read".to_string(), Phase::Session, vec![]);
+        tracer.record_host_function_cost(&[3], Gas::new(U512::from(20)));
+
+        let trace = tracer.take();
+        assert_eq!(trace.host_function_calls.len(), 2);
+        assert_eq!(trace.host_function_calls[0].args, vec![1, 2]);
+        assert_eq!(
+            trace.host_function_calls[1].gas_charged,
+            Gas::new(U512::from(20))
+        );
+        assert_eq!(tracer.take(), ExecutionTrace::default());
+    }
+
+    #[test]
+    fn should_count_calls_beyond_limit() {
+        let tracer = ExecutionTracer::default();
+        for _ in 0..MAX_TRACED_HOST_FUNCTION_CALLS + 2 {
+            tracer.record_host_function_call("Read".to_string(), Phase::Session, vec![]);
+            tracer.record_host_function_cost(&[1], Gas::new(U512::from(1)));
+        }
+
+        let trace = tracer.take();
+        assert_eq!(
+            trace.host_function_calls.len(),
+            MAX_TRACED_HOST_FUNCTION_CALLS
+        );
+        assert_eq!(trace.omitted_host_function_calls, 2);
+        assert!(trace
+            .host_function_calls
+            .iter()
+            .all(|call| call.gas_charged == Gas::new(U512::from(1))));
+    }
+}
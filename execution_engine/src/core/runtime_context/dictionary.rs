@@ -1,8 +1,18 @@
 use casper_types::{
     bytesrepr::{self, Bytes, FromBytes, ToBytes},
-    CLType, CLTyped, CLValue, CLValueError, Key, StoredValue,
+    crypto, CLType, CLTyped, CLValue, CLValueError, DictionaryAddr, Key, KeyTag, StoredValue,
+    URefAddr, KEY_DICTIONARY_LENGTH,
 };
 
+/// Domain separator mixed into the seed of a dictionary to derive the prefix of its index entries.
+const DICTIONARY_INDEX_DOMAIN: &[u8] = b"dictionary-index";
+
+/// Length of the seed-derived prefix shared by the addresses of all index entries of a dictionary.
+const DICTIONARY_INDEX_PREFIX_LENGTH: usize = 16;
+
+/// Domain separator mixed into the seed of a dictionary to derive the key of its index marker.
+const DICTIONARY_INDEX_MARKER_DOMAIN: &[u8] = b"dictionary-index-marker";
+
 /// Wraps a [`CLValue`] for storage in a dictionary.
 ///
 /// Note that we include the dictionary [`casper_types::URef`] and key used to create the
@@ -31,6 +41,17 @@ impl DictionaryValue {
         }
     }
 
+    /// Returns the address of the seed [`URef`](casper_types::URef) of the dictionary holding
+    /// this value.
+    pub fn seed_uref_addr(&self) -> &[u8] {
+        self.seed_uref_addr.as_slice()
+    }
+
+    /// Returns the bytes of the dictionary item key under which this value is stored.
+    pub fn dictionary_item_key_bytes(&self) -> &[u8] {
+        self.dictionary_item_key_bytes.as_slice()
+    }
+
     /// Get a reference to the [`DictionaryValue`]'s wrapper's cl value.
     pub fn into_cl_value(self) -> CLValue {
         self.cl_value
@@ -80,6 +101,50 @@ impl ToBytes for DictionaryValue {
     }
 }
 
+/// Returns the prefix shared by the serialized keys of all index entries of the dictionary
+/// with the seed `URef` address `seed_uref_addr`.
+///
+/// Each item of a dictionary has an index entry, holding the item key, under a `Key::Dictionary`
+/// whose address starts with bytes derived from the seed alone.  Listing the items of a dictionary
+/// therefore only visits the leaves of that dictionary.
+pub fn dictionary_index_prefix(seed_uref_addr: URefAddr) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(1 + DICTIONARY_INDEX_PREFIX_LENGTH);
+    prefix.push(KeyTag::Dictionary as u8);
+    prefix.extend_from_slice(&index_addr_prefix(seed_uref_addr));
+    prefix
+}
+
+/// Returns the key of the index entry for the item under `dictionary_item_key_bytes` of the
+/// dictionary with the seed `URef` address `seed_uref_addr`.
+pub fn dictionary_index_key(seed_uref_addr: URefAddr, dictionary_item_key_bytes: &[u8]) -> Key {
+    let mut addr = DictionaryAddr::default();
+    addr[..DICTIONARY_INDEX_PREFIX_LENGTH].copy_from_slice(&index_addr_prefix(seed_uref_addr));
+    addr[DICTIONARY_INDEX_PREFIX_LENGTH..].copy_from_slice(
+        &crypto::blake2b(dictionary_item_key_bytes)
+            [..KEY_DICTIONARY_LENGTH - DICTIONARY_INDEX_PREFIX_LENGTH],
+    );
+    Key::Dictionary(addr)
+}
+
+/// Returns the key of the marker recording that every item of the dictionary with the seed `URef`
+/// address `seed_uref_addr` has an index entry.
+///
+/// The marker is written when a dictionary is created with `new_dictionary`.  Dictionaries created
+/// before items were indexed have no marker, since items written back then have no index entries.
+pub fn dictionary_index_marker_key(seed_uref_addr: URefAddr) -> Key {
+    let mut data = DICTIONARY_INDEX_MARKER_DOMAIN.to_vec();
+    data.extend_from_slice(&seed_uref_addr);
+    Key::Dictionary(crypto::blake2b(data))
+}
+
+fn index_addr_prefix(seed_uref_addr: URefAddr) -> [u8; DICTIONARY_INDEX_PREFIX_LENGTH] {
+    let mut data = DICTIONARY_INDEX_DOMAIN.to_vec();
+    data.extend_from_slice(&seed_uref_addr);
+    let mut prefix = [0; DICTIONARY_INDEX_PREFIX_LENGTH];
+    prefix.copy_from_slice(&crypto::blake2b(data)[..DICTIONARY_INDEX_PREFIX_LENGTH]);
+    prefix
+}
+
 /// Inspects `key` argument whether it contains a dictionary variant, and checks if `stored_value`
 /// contains a [`CLValue`], then it will attempt a conversion from the held clvalue into
 /// [`DictionaryValue`] and returns the real [`CLValue`] held by it.
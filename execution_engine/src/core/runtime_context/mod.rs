@@ -17,10 +17,11 @@ use casper_types::{
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::auction::EraInfo,
-    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractHash,
-    ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess, EntryPointType,
-    Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue,
-    Transfer, TransferAddr, URef, URefAddr, DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
+    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractEvent,
+    ContractHash, ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess,
+    EntryPointType, Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey,
+    RuntimeArgs, StoredValue, Transfer, TransferAddr, URef, URefAddr,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
 };
 
 use crate::{
@@ -31,7 +32,7 @@ use crate::{
         tracking_copy::{AddResult, TrackingCopy, TrackingCopyExt},
     },
     shared::{execution_journal::ExecutionJournal, newtypes::CorrelationId},
-    storage::global_state::StateReader,
+    storage::{global_state::StateReader, trie_store::operations::LeavesResult},
 };
 
 pub(crate) mod dictionary;
@@ -41,6 +42,13 @@ mod tests;
 /// Number of bytes returned from the `random_bytes` function.
 pub const RANDOM_BYTES_COUNT: usize = 32;
 
+/// The storage refund of an execution is capped at its gas counter divided by this value.
+const MAX_STORAGE_REFUND_DIVISOR: u32 = 2;
+
+/// A page of dictionary item keys, along with the key from which to continue listing if there are
+/// further dictionary items.
+pub(crate) type DictionaryKeysPage = (Vec<String>, Option<Key>);
+
 /// Validates an entry point access with a special validator callback.
 ///
 /// If the passed `access` object is a `Groups` variant, then this function will return a
@@ -96,6 +104,9 @@ pub struct RuntimeContext<'a, R> {
     deploy_hash: DeployHash,
     gas_limit: Gas,
     gas_counter: Gas,
+    // Gas refunded for removing data from global state, deducted from the cost once execution has
+    // finished
+    storage_refund: Gas,
     address_generator: Rc<RefCell<AddressGenerator>>,
     protocol_version: ProtocolVersion,
     correlation_id: CorrelationId,
@@ -149,6 +160,7 @@ where
             base_key,
             gas_limit,
             gas_counter,
+            storage_refund: Gas::default(),
             address_generator,
             protocol_version,
             correlation_id,
@@ -177,6 +189,7 @@ where
         let deploy_hash = self.deploy_hash;
         let gas_limit = self.gas_limit;
         let gas_counter = self.gas_counter;
+        let storage_refund = self.storage_refund;
         let address_generator = self.address_generator.clone();
         let protocol_version = self.protocol_version;
         let correlation_id = self.correlation_id;
@@ -198,6 +211,7 @@ where
             base_key,
             gas_limit,
             gas_counter,
+            storage_refund,
             address_generator,
             protocol_version,
             correlation_id,
@@ -405,6 +419,26 @@ where
         self.gas_counter = new_gas_counter;
     }
 
+    /// Returns the gas refunded so far for removing data from global state.
+    pub fn storage_refund(&self) -> Gas {
+        self.storage_refund
+    }
+
+    /// Sets the gas refunded for removing data from global state to a new value.
+    pub fn set_storage_refund(&mut self, new_storage_refund: Gas) {
+        self.storage_refund = new_storage_refund;
+    }
+
+    /// Returns the cost of the execution so far, that is the gas counter less the storage refund.
+    ///
+    /// The storage refund never offsets more than half of the gas counter, so removing data written
+    /// by earlier deploys cannot make execution free.
+    pub fn gas_cost(&self) -> Gas {
+        let max_refund = Gas::new(self.gas_counter.value() / MAX_STORAGE_REFUND_DIVISOR);
+        let refund = self.storage_refund.min(max_refund);
+        self.gas_counter - refund
+    }
+
     /// Returns the base key.
     ///
     /// This could be either a [`Key::Account`] or a [`Key::Hash`] depending on the entry point
@@ -454,6 +488,15 @@ where
         self.new_uref(StoredValue::CLValue(CLValue::unit()))
     }
 
+    /// Creates the seed [`URef`] of a new dictionary, and the marker recording that all of its
+    /// items have index entries, so that they can be listed.
+    pub(crate) fn new_dictionary_seed_uref(&mut self) -> Result<URef, Error> {
+        let seed_uref = self.new_unit_uref()?;
+        let marker_key = dictionary::dictionary_index_marker_key(seed_uref.addr());
+        self.metered_write_gs_unsafe(marker_key, StoredValue::CLValue(CLValue::unit()))?;
+        Ok(seed_uref)
+    }
+
     /// Creates a new transfer address using a transfer address generator.
     pub fn new_transfer_addr(&mut self) -> Result<TransferAddr, Error> {
         let transfer_addr = self.address_generator.borrow_mut().create_address();
@@ -645,6 +688,22 @@ where
         self.tracking_copy.borrow().execution_journal()
     }
 
+    /// Returns the events emitted so far.
+    pub fn events(&self) -> Vec<ContractEvent> {
+        self.tracking_copy.borrow().events()
+    }
+
+    /// Records an event with the given topic and payload, emitted by the current context's base
+    /// key.
+    pub(crate) fn emit_event(&mut self, topic: String, payload: CLValue) {
+        let event = ContractEvent {
+            emitter: self.base_key(),
+            topic,
+            payload,
+        };
+        self.tracking_copy.borrow_mut().emit_event(event);
+    }
+
     /// Returns list of transfers.
     pub fn transfers(&self) -> &Vec<TransferAddr> {
         &self.transfers
@@ -888,6 +947,31 @@ where
         self.charge_gas(gas_cost)
     }
 
+    /// Records a gas refund for specified amount of bytes removed.
+    ///
+    /// The gas counter is left untouched so that the refund cannot pay for further execution;
+    /// instead the refund is deducted from the cost once execution has finished (see
+    /// [`RuntimeContext::gas_cost`]).
+    fn refund_gas_storage(&mut self, bytes_count: usize) -> Result<(), Error> {
+        if let Some(base_key) = self.base_key().into_hash() {
+            let contract_hash = ContractHash::new(base_key);
+            if self.is_system_contract(&contract_hash)? {
+                // Storage used while executing a system contract is not charged for.
+                return Ok(());
+            }
+        }
+
+        let storage_costs = self.engine_config.wasm_config().storage_costs();
+
+        let gas_refund = storage_costs.calculate_gas_refund(bytes_count);
+
+        self.storage_refund = self
+            .storage_refund
+            .checked_add(gas_refund)
+            .unwrap_or(self.storage_refund);
+        Ok(())
+    }
+
     /// Charges gas for using a host system contract's entrypoint.
     pub(crate) fn charge_system_contract_call<T>(&mut self, call_cost: T) -> Result<(), Error>
     where
@@ -938,6 +1022,37 @@ where
         self.metered_write_gs_unsafe(key, stored_value)
     }
 
+    /// Removes data from global state, recording a storage refund for the bytes no longer stored.
+    ///
+    /// Returns `false` if there is no value under the key.
+    ///
+    /// Use with caution - there is no validation done as the key is assumed to be validated
+    /// already.
+    fn metered_prune_gs_unsafe(&mut self, key: Key) -> Result<bool, Error> {
+        let maybe_stored_value = self
+            .tracking_copy
+            .borrow_mut()
+            .read(self.correlation_id, &key)
+            .map_err(Into::into)?;
+        let stored_value = match maybe_stored_value {
+            Some(stored_value) => stored_value,
+            None => return Ok(false),
+        };
+
+        self.tracking_copy.borrow_mut().prune(key);
+        self.refund_gas_storage(stored_value.serialized_length())?;
+        Ok(true)
+    }
+
+    /// Removes data from global state and records a storage refund for the bytes no longer stored.
+    ///
+    /// This method performs full validation of the key to be removed.
+    pub(crate) fn metered_prune_gs(&mut self, key: Key) -> Result<bool, Error> {
+        self.validate_writeable(&key)?;
+        self.validate_key(&key)?;
+        self.metered_prune_gs_unsafe(key)
+    }
+
     /// Adds data to a global state key and charges for bytes stored.
     ///
     /// This method performs full validation of the key to be written.
@@ -1244,9 +1359,129 @@ where
 
         let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key_bytes);
         self.metered_write_gs_unsafe(dictionary_key, wrapped_cl_value)?;
+        if self.is_dictionary_indexed(seed_uref)? {
+            self.write_dictionary_index_entry(seed_uref, dictionary_item_key_bytes)?;
+        }
         Ok(())
     }
 
+    /// Checks if the dictionary referenced by a `seed_uref` has an index marker, i.e. it was
+    /// created by `new_dictionary_seed_uref` and its items have index entries.
+    fn is_dictionary_indexed(&mut self, seed_uref: URef) -> Result<bool, Error> {
+        let marker_key = dictionary::dictionary_index_marker_key(seed_uref.addr());
+        let maybe_marker = self
+            .tracking_copy
+            .borrow_mut()
+            .read(self.correlation_id, &marker_key)
+            .map_err(Into::into)?;
+        Ok(maybe_marker.is_some())
+    }
+
+    /// Writes the index entry through which `dictionary_keys` lists the item under
+    /// `dictionary_item_key_bytes`, charging for the bytes stored like for the item itself.
+    fn write_dictionary_index_entry(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key_bytes: &[u8],
+    ) -> Result<(), Error> {
+        let index_value = DictionaryValue::new(
+            CLValue::unit(),
+            seed_uref.addr().to_vec(),
+            dictionary_item_key_bytes.to_vec(),
+        );
+        let index_cl_value = CLValue::from_t(index_value).map_err(Error::from)?;
+        let index_key =
+            dictionary::dictionary_index_key(seed_uref.addr(), dictionary_item_key_bytes);
+        self.metered_write_gs_unsafe(index_key, StoredValue::CLValue(index_cl_value))
+    }
+
+    /// Gets a page of the dictionary item keys of a dictionary referenced by a `seed_uref`, along
+    /// with the key from which to continue listing if there are further dictionary items.
+    ///
+    /// Only the index entries of the given dictionary are visited, so a page holds `page_size` item
+    /// keys unless it is the last one.
+    ///
+    /// Returns `None` if the dictionary has no index marker, i.e. it wasn't created by
+    /// `new_dictionary_seed_uref` and some of its items may not be indexed.
+    pub(crate) fn dictionary_keys(
+        &mut self,
+        seed_uref: URef,
+        page_start: Option<Key>,
+        page_size: u32,
+    ) -> Result<Option<DictionaryKeysPage>, Error> {
+        self.validate_readable(&seed_uref.into())?;
+        self.validate_key(&seed_uref.into())?;
+
+        if !self.is_dictionary_indexed(seed_uref)? {
+            return Ok(None);
+        }
+
+        let result = self
+            .tracking_copy
+            .borrow()
+            .leaves_with_prefix(
+                self.correlation_id,
+                &dictionary::dictionary_index_prefix(seed_uref.addr()),
+                page_start.as_ref(),
+                page_size as usize,
+            )
+            .map_err(Into::into)?;
+        let (leaves, next_page_start) = match result {
+            LeavesResult::Success {
+                leaves,
+                next_page_start,
+            } => (leaves, next_page_start),
+            LeavesResult::RootNotFound | LeavesResult::MissingTrie(_) => {
+                return Err(Error::IncompleteGlobalState)
+            }
+        };
+
+        let dictionary_item_keys = leaves
+            .into_iter()
+            .filter_map(|(_key, stored_value)| match stored_value {
+                StoredValue::CLValue(cl_value) => cl_value.into_t::<DictionaryValue>().ok(),
+                _ => None,
+            })
+            .filter(|dictionary_value| {
+                dictionary_value.seed_uref_addr() == seed_uref.addr().as_slice()
+            })
+            .map(|dictionary_value| {
+                String::from_utf8_lossy(dictionary_value.dictionary_item_key_bytes()).into_owned()
+            })
+            .collect();
+        Ok(Some((dictionary_item_keys, next_page_start)))
+    }
+
+    /// Removes a dictionary item and its index entry from a dictionary referenced by a
+    /// `seed_uref`, refunding gas for the bytes no longer stored.
+    ///
+    /// Returns `false` if there is no such dictionary item.
+    pub(crate) fn dictionary_remove(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<bool, Error> {
+        let dictionary_item_key_bytes = dictionary_item_key.as_bytes();
+
+        if dictionary_item_key_bytes.len() > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            return Err(Error::DictionaryItemKeyExceedsLength);
+        }
+
+        self.validate_writeable(&seed_uref.into())?;
+        self.validate_uref(&seed_uref)?;
+
+        let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key_bytes);
+        if !self.metered_prune_gs_unsafe(dictionary_key)? {
+            return Ok(false);
+        }
+        if self.is_dictionary_indexed(seed_uref)? {
+            let index_key =
+                dictionary::dictionary_index_key(seed_uref.addr(), dictionary_item_key_bytes);
+            self.metered_prune_gs_unsafe(index_key)?;
+        }
+        Ok(true)
+    }
+
     /// Gets system contract by name.
     pub(crate) fn get_system_contract(&self, name: &str) -> Result<ContractHash, Error> {
         let registry = self.system_contract_registry()?;
@@ -12,7 +12,7 @@ use casper_types::{
         Account, AccountHash, ActionType, AddKeyFailure, AssociatedKeys, RemoveKeyFailure,
         SetThresholdFailure, Weight, ACCOUNT_HASH_LENGTH,
     },
-    bytesrepr::ToBytes,
+    bytesrepr::{Bytes, ToBytes},
     contracts::NamedKeys,
     system::{AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT},
     AccessRights, BlockTime, CLValue, ContextAccessRights, Contract, ContractHash, DeployHash,
@@ -20,7 +20,10 @@ use casper_types::{
     SecretKey, StoredValue, URef, KEY_HASH_LENGTH, U256, U512,
 };
 
-use super::{Error, RuntimeContext};
+use super::{
+    dictionary::{self, DictionaryValue},
+    Error, RuntimeContext,
+};
 use crate::{
     core::{
         engine_state::{EngineConfig, SystemContractRegistry},
@@ -1034,3 +1037,142 @@ fn associated_keys_add_full() {
         Error::AddKeyFailure(AddKeyFailure::MaxKeysLimit)
     ));
 }
+
+#[test]
+fn should_list_dictionary_keys_in_full_pages() {
+    let item_keys = ["item-key-a", "item-key-b", "item-key-c", "item-key-d"];
+
+    let listed_pages = build_runtime_context_and_execute(NamedKeys::new(), |mut rc| {
+        let seed_uref = rc.new_dictionary_seed_uref()?;
+        let other_seed_uref = rc.new_dictionary_seed_uref()?;
+        for item_key in item_keys {
+            let value = CLValue::from_t(item_key.to_string()).unwrap();
+            rc.dictionary_put(seed_uref, item_key, value.clone())?;
+            rc.dictionary_put(other_seed_uref, item_key, value)?;
+        }
+        assert!(rc.dictionary_remove(seed_uref, item_keys[1])?);
+        assert!(!rc.dictionary_remove(seed_uref, item_keys[1])?);
+
+        let mut pages = vec![];
+        let mut page_start = None;
+        loop {
+            let (page, next_page_start) = rc
+                .dictionary_keys(seed_uref, page_start, 2)?
+                .expect("should be indexed");
+            pages.push(page);
+            match next_page_start {
+                Some(next_page_start) => page_start = Some(next_page_start),
+                None => break,
+            }
+        }
+        Ok(pages)
+    })
+    .expect("should list dictionary keys");
+
+    assert_eq!(listed_pages.len(), 2);
+    assert_eq!(listed_pages[0].len(), 2);
+    assert_eq!(listed_pages[1].len(), 1);
+    let mut listed_item_keys: Vec<String> = listed_pages.into_iter().flatten().collect();
+    listed_item_keys.sort();
+    assert_eq!(listed_item_keys, [item_keys[0], item_keys[2], item_keys[3]]);
+}
+
+#[test]
+fn should_not_list_keys_of_unindexed_dictionary() {
+    let maybe_page = build_runtime_context_and_execute(NamedKeys::new(), |mut rc| {
+        // A seed created without an index marker, like the seeds of dictionaries created before
+        // dictionary items were indexed.
+        let seed_uref = rc.new_unit_uref()?;
+        rc.dictionary_put(seed_uref, "item-key", CLValue::unit())?;
+        rc.dictionary_keys(seed_uref, None, 10)
+    })
+    .expect("should run test");
+
+    assert!(maybe_page.is_none());
+}
+
+#[test]
+fn should_charge_for_dictionary_index_entries() {
+    let item_key = "item-key";
+    let value = CLValue::from_t(Bytes::from(vec![0u8; 1000])).unwrap();
+
+    let (seed_uref, gas_for_put) = build_runtime_context_and_execute(NamedKeys::new(), |mut rc| {
+        let seed_uref = rc.new_dictionary_seed_uref()?;
+        let gas_before_put = rc.gas_counter();
+        rc.dictionary_put(seed_uref, item_key, value.clone())?;
+        Ok((seed_uref, rc.gas_counter() - gas_before_put))
+    })
+    .expect("should run test");
+
+    let stored_length = |cl_value: CLValue| {
+        let dictionary_value = DictionaryValue::new(
+            cl_value,
+            seed_uref.addr().to_vec(),
+            item_key.as_bytes().to_vec(),
+        );
+        StoredValue::CLValue(CLValue::from_t(dictionary_value).unwrap()).serialized_length()
+    };
+    let storage_costs = test_engine_config().wasm_config().storage_costs();
+    let expected_gas = storage_costs.calculate_gas_cost(stored_length(value))
+        + storage_costs.calculate_gas_cost(stored_length(CLValue::unit()));
+    assert_eq!(gas_for_put, expected_gas);
+}
+
+#[test]
+fn should_not_index_items_of_unindexed_dictionary() {
+    let item_key = "item-key";
+    let value = CLValue::from_t(Bytes::from(vec![0u8; 1000])).unwrap();
+
+    let (seed_uref, gas_for_put, maybe_index_entry) =
+        build_runtime_context_and_execute(NamedKeys::new(), |mut rc| {
+            // A seed created without an index marker, like the seeds of dictionaries created
+            // before dictionary items were indexed.
+            let seed_uref = rc.new_unit_uref()?;
+            let gas_before_put = rc.gas_counter();
+            rc.dictionary_put(seed_uref, item_key, value.clone())?;
+            let gas_for_put = rc.gas_counter() - gas_before_put;
+            let index_key = dictionary::dictionary_index_key(seed_uref.addr(), item_key.as_bytes());
+            let maybe_index_entry = rc.read_gs(&index_key)?;
+            Ok((seed_uref, gas_for_put, maybe_index_entry))
+        })
+        .expect("should run test");
+
+    let dictionary_value = DictionaryValue::new(
+        value,
+        seed_uref.addr().to_vec(),
+        item_key.as_bytes().to_vec(),
+    );
+    let stored_length =
+        StoredValue::CLValue(CLValue::from_t(dictionary_value).unwrap()).serialized_length();
+    let storage_costs = test_engine_config().wasm_config().storage_costs();
+    assert_eq!(gas_for_put, storage_costs.calculate_gas_cost(stored_length));
+    assert!(maybe_index_entry.is_none());
+}
+
+#[test]
+fn should_defer_and_cap_storage_refund() {
+    let item_key = "item-key";
+    let value = CLValue::from_t(Bytes::from(vec![0u8; 1000])).unwrap();
+
+    let (gas_counter, storage_refund, cost_without_gas, cost_with_gas) =
+        build_runtime_context_and_execute(NamedKeys::new(), |mut rc| {
+            let seed_uref = rc.new_unit_uref()?;
+            rc.dictionary_put(seed_uref, item_key, value)?;
+            // Treat the item as written by an earlier deploy.
+            rc.set_gas_counter(Gas::default());
+
+            assert!(rc.dictionary_remove(seed_uref, item_key)?);
+            let gas_counter = rc.gas_counter();
+            let storage_refund = rc.storage_refund();
+            let cost_without_gas = rc.gas_cost();
+
+            rc.charge_gas(Gas::new(U512::from(1_000)))?;
+            Ok((gas_counter, storage_refund, cost_without_gas, rc.gas_cost()))
+        })
+        .expect("should run test");
+
+    assert!(storage_refund > Gas::default());
+    assert_eq!(gas_counter, Gas::default());
+    assert_eq!(cost_without_gas, Gas::default());
+    assert_eq!(cost_with_gas, Gas::new(U512::from(500)));
+}
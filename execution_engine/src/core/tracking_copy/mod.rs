@@ -19,7 +19,8 @@ use thiserror::Error;
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::{self},
-    CLType, CLValue, CLValueError, Key, KeyTag, StoredValue, StoredValueTypeMismatch, Tagged, U512,
+    CLType, CLValue, CLValueError, ContractEvent, Key, KeyTag, StoredValue,
+    StoredValueTypeMismatch, Tagged, U512,
 };
 
 pub use self::ext::TrackingCopyExt;
@@ -32,7 +33,11 @@ use crate::{
         newtypes::CorrelationId,
         transform::{self, Transform},
     },
-    storage::{global_state::StateReader, trie::merkle_proof::TrieMerkleProof},
+    storage::{
+        global_state::{overlay_leaves, StateReader},
+        trie::merkle_proof::TrieMerkleProof,
+        trie_store::operations::LeavesResult,
+    },
 };
 
 /// Result of a query on a `TrackingCopy`.
@@ -137,6 +142,7 @@ pub struct TrackingCopyCache<M> {
     muts_cached: HashMap<Key, StoredValue>,
     key_tag_reads_cached: LinkedHashMap<KeyTag, BTreeSet<Key>>,
     key_tag_muts_cached: HashMap<KeyTag, BTreeSet<Key>>,
+    prunes_cached: HashSet<Key>,
     meter: M,
 }
 
@@ -153,6 +159,7 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
             muts_cached: HashMap::new(),
             key_tag_reads_cached: LinkedHashMap::new(),
             key_tag_muts_cached: HashMap::new(),
+            prunes_cached: HashSet::new(),
             meter,
         }
     }
@@ -191,6 +198,7 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
 
     /// Inserts `key` and `value` pair to Write/Add cache.
     pub fn insert_write(&mut self, key: Key, value: StoredValue) {
+        self.prunes_cached.remove(&key);
         self.muts_cached.insert(key, value);
 
         let key_set = self.key_tag_muts_cached.entry(key.tag()).or_default();
@@ -198,6 +206,27 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
         key_set.insert(key);
     }
 
+    /// Marks `key` as pruned, evicting it from the Read and Write/Add caches.
+    pub fn insert_prune(&mut self, key: Key) {
+        self.muts_cached.remove(&key);
+        if let Some(value) = self.reads_cached.remove(&key) {
+            let element_size = Meter::measure(&self.meter, &key, &value);
+            self.current_cache_size -= element_size;
+        }
+        if let Some(keys) = self.key_tag_muts_cached.get_mut(&key.tag()) {
+            keys.remove(&key);
+        }
+        if let Some(keys) = self.key_tag_reads_cached.get_mut(&key.tag()) {
+            keys.remove(&key);
+        }
+        self.prunes_cached.insert(key);
+    }
+
+    /// Returns `true` if `key` has been pruned.
+    pub fn is_pruned(&self, key: &Key) -> bool {
+        self.prunes_cached.contains(key)
+    }
+
     /// Gets value from `key` in the cache.
     pub fn get(&mut self, key: &Key) -> Option<&StoredValue> {
         if let Some(value) = self.muts_cached.get(key) {
@@ -225,6 +254,7 @@ pub struct TrackingCopy<R> {
     reader: R,
     cache: TrackingCopyCache<HeapSize>,
     journal: ExecutionJournal,
+    events: Vec<ContractEvent>,
 }
 
 /// Result of executing an "add" operation on a value in the state.
@@ -263,6 +293,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
              * be fraction of wasm memory
              * limit? */
             journal: Default::default(),
+            events: Vec::new(),
         }
     }
 
@@ -292,6 +323,9 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, R::Error> {
+        if self.cache.is_pruned(key) {
+            return Ok(None);
+        }
         if let Some(value) = self.cache.get(key) {
             return Ok(Some(value.to_owned()));
         }
@@ -324,9 +358,32 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         if let Some(keys) = self.cache.get_key_tag_muts_cached(key_tag) {
             ret.extend(keys)
         }
+        ret.retain(|key| !self.cache.is_pruned(key));
         Ok(ret)
     }
 
+    /// Gets a page of at most `page_size` keys in the state whose serialized bytes start with
+    /// `prefix`, along with their values, starting from `page_start` if given.
+    pub fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, R::Error> {
+        let result =
+            self.reader
+                .leaves_with_prefix(correlation_id, prefix, page_start, page_size)?;
+        Ok(overlay_leaves(
+            result,
+            prefix,
+            page_start,
+            page_size,
+            self.cache.muts_cached.iter(),
+            &self.cache.prunes_cached,
+        ))
+    }
+
     /// Reads the value stored under `key`.
     pub fn read(
         &mut self,
@@ -350,6 +407,15 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         self.journal.push((normalized_key, Transform::Write(value)));
     }
 
+    /// Removes `key` from the state. Note that the removal is only cached, and the global state
+    /// itself remains unmodified.
+    pub fn prune(&mut self, key: Key) {
+        let normalized_key = key.normalize();
+        self.cache.insert_prune(normalized_key);
+        self.journal
+            .push((normalized_key, Transform::Prune(normalized_key)));
+    }
+
     /// Ok(None) represents missing key to which we want to "add" some value.
     /// Ok(Some(unit)) represents successful operation.
     /// Err(error) is reserved for unexpected errors when accessing global
@@ -436,6 +502,17 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         self.journal.clone()
     }
 
+    /// Records an event emitted by a contract.  Like the journal, the events are discarded along
+    /// with this instance if execution fails.
+    pub fn emit_event(&mut self, event: ContractEvent) {
+        self.events.push(event);
+    }
+
+    /// Returns the events emitted by contracts executed on this instance.
+    pub fn events(&self) -> Vec<ContractEvent> {
+        self.events.clone()
+    }
+
     /// Calling `query()` avoids calling into `self.cache`, so this will not return any values
     /// written or mutated in this `TrackingCopy` via previous calls to `write()` or `add()`, since
     /// these updates are only held in `self.cache`.
@@ -574,6 +651,9 @@ impl<R: StateReader<Key, StoredValue>> StateReader<Key, StoredValue> for &Tracki
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
+        if self.cache.is_pruned(key) {
+            return Ok(None);
+        }
         if let Some(value) = self.cache.muts_cached.get(key) {
             return Ok(Some(value.to_owned()));
         }
@@ -599,6 +679,26 @@ impl<R: StateReader<Key, StoredValue>> StateReader<Key, StoredValue> for &Tracki
     ) -> Result<Vec<Key>, Self::Error> {
         self.reader.keys_with_prefix(correlation_id, prefix)
     }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let result =
+            self.reader
+                .leaves_with_prefix(correlation_id, prefix, page_start, page_size)?;
+        Ok(overlay_leaves(
+            result,
+            prefix,
+            page_start,
+            page_size,
+            self.cache.muts_cached.iter(),
+            &self.cache.prunes_cached,
+        ))
+    }
 }
 
 /// Error conditions of a proof validation.
@@ -23,6 +23,7 @@ use crate::{
     storage::{
         global_state::{in_memory::InMemoryGlobalState, StateProvider, StateReader},
         trie::merkle_proof::TrieMerkleProof,
+        trie_store::operations::LeavesResult,
     },
 };
 
@@ -78,6 +79,19 @@ impl StateReader<Key, StoredValue> for CountingDb {
     ) -> Result<Vec<Key>, Self::Error> {
         Ok(Vec::new())
     }
+
+    fn leaves_with_prefix(
+        &self,
+        _correlation_id: CorrelationId,
+        _prefix: &[u8],
+        _page_start: Option<&Key>,
+        _page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        Ok(LeavesResult::Success {
+            leaves: Vec::new(),
+            next_page_start: None,
+        })
+    }
 }
 
 #[test]
@@ -952,6 +966,93 @@ fn get_keys_should_return_keys_in_the_uref_keyspace() {
     assert!(!key_set.contains(&account_key));
 }
 
+#[test]
+fn tracking_copy_prune() {
+    let correlation_id = CorrelationId::new();
+    let k = Key::Hash([0u8; 32]);
+    let one = StoredValue::CLValue(CLValue::from_t(1_i32).unwrap());
+    let (global_state, root_hash) =
+        InMemoryGlobalState::from_pairs(correlation_id, &[(k, one.clone())]).unwrap();
+    let view = global_state.checkout(root_hash).unwrap().unwrap();
+    let mut tc = TrackingCopy::new(view);
+
+    tc.prune(k);
+    // Pruning creates a prune transform.
+    assert_eq!(
+        tc.journal,
+        ExecutionJournal::new(vec![(k, Transform::Prune(k))])
+    );
+    // the pruned key can no longer be read, nor is it listed
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), None);
+    assert_eq!((&tc).read(correlation_id, &k).unwrap(), None);
+    let key_set = tc.get_keys(correlation_id, &KeyTag::Hash).unwrap();
+    assert!(key_set.is_empty());
+
+    // writing the key again restores it
+    tc.write(k, one.clone());
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), Some(one));
+}
+
+#[test]
+fn leaves_with_prefix_should_include_cached_changes() {
+    let correlation_id = CorrelationId::new();
+    let uref_keys: Vec<Key> = (1..=4)
+        .map(|i| Key::URef(URef::new([i * 2; 32], AccessRights::NONE)))
+        .collect();
+    let value = |i: u64| StoredValue::CLValue(CLValue::from_t(i).unwrap());
+    let account_key = Key::Account(AccountHash::new([1; 32]));
+    let (global_state, root_hash) = InMemoryGlobalState::from_pairs(
+        correlation_id,
+        &[
+            (uref_keys[0], value(0)),
+            (uref_keys[1], value(1)),
+            (uref_keys[2], value(2)),
+            (uref_keys[3], value(3)),
+            (account_key, value(4)),
+        ],
+    )
+    .unwrap();
+    let view = global_state.checkout(root_hash).unwrap().unwrap();
+    let mut tracking_copy = TrackingCopy::new(view);
+
+    let new_key = Key::URef(URef::new([3; 32], AccessRights::NONE));
+    tracking_copy.prune(uref_keys[0]);
+    tracking_copy.write(uref_keys[1], value(5));
+    tracking_copy.write(new_key, value(6));
+
+    let prefix = [KeyTag::URef as u8];
+    let mut leaves = vec![];
+    let mut page_start = None;
+    loop {
+        let result = tracking_copy
+            .leaves_with_prefix(correlation_id, &prefix, page_start.as_ref(), 2)
+            .unwrap();
+        let (page, next_page_start) = match result {
+            LeavesResult::Success {
+                leaves,
+                next_page_start,
+            } => (leaves, next_page_start),
+            other => panic!("unexpected leaves result {:?}", other),
+        };
+        assert!(page.len() <= 2);
+        leaves.extend(page);
+        page_start = match next_page_start {
+            Some(next_page_start) => Some(next_page_start),
+            None => break,
+        };
+    }
+
+    assert_eq!(
+        leaves,
+        vec![
+            (new_key, value(6)),
+            (uref_keys[1], value(5)),
+            (uref_keys[2], value(2)),
+            (uref_keys[3], value(3)),
+        ]
+    );
+}
+
 #[test]
 fn get_keys_should_handle_reads_from_empty_trie() {
     let correlation_id = CorrelationId::new();
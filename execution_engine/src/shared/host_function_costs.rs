@@ -8,6 +8,8 @@ use casper_types::{
     Gas,
 };
 
+use crate::shared::storage_costs::DEFAULT_GAS_PER_BYTE_COST;
+
 /// Representation of argument's cost.
 pub type Cost = u32;
 
@@ -78,6 +80,17 @@ const DEFAULT_DICTIONARY_PUT_KEY_BYTES_SIZE_WEIGHT: u32 = 1_800;
 const DEFAULT_DICTIONARY_PUT_VALUE_SIZE_WEIGHT: u32 = 520;
 const DEFAULT_BLAKE2B_COST: u32 = 1_200_000;
 
+const DEFAULT_DICTIONARY_KEYS_COST: u32 = 60_000;
+const DEFAULT_DICTIONARY_KEYS_PAGE_SIZE_WEIGHT: u32 = 60_000;
+const DEFAULT_DICTIONARY_REMOVE_COST: u32 = 9_500;
+const DEFAULT_DICTIONARY_REMOVE_KEY_BYTES_SIZE_WEIGHT: u32 = 1_800;
+const DEFAULT_PRUNE_COST: u32 = 14_000;
+const DEFAULT_EMIT_EVENT_COST: u32 = 9_500;
+// Events are kept in the execution results for good, so their bytes cost as much as bytes written
+// to the global state.
+const DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT: u32 = DEFAULT_GAS_PER_BYTE_COST;
+const DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT: u32 = DEFAULT_GAS_PER_BYTE_COST;
+
 /// Representation of a host function cost.
 ///
 /// The total gas cost is equal to `cost` + sum of each argument weight multiplied by the byte size
@@ -287,6 +300,14 @@ pub struct HostFunctionCosts {
     pub random_bytes: HostFunction<[Cost; 2]>,
     /// Cost of calling the `enable_contract_version` host function.
     pub enable_contract_version: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `dictionary_keys` host function.
+    pub dictionary_keys: HostFunction<[Cost; 6]>,
+    /// Cost of calling the `dictionary_remove` host function.
+    pub dictionary_remove: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `prune` host function.
+    pub prune: HostFunction<[Cost; 2]>,
+    /// Cost of calling the `emit_event` host function.
+    pub emit_event: HostFunction<[Cost; 4]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -452,6 +473,36 @@ impl Default for HostFunctionCosts {
             ),
             random_bytes: HostFunction::default(),
             enable_contract_version: HostFunction::default(),
+            dictionary_keys: HostFunction::new(
+                DEFAULT_DICTIONARY_KEYS_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_DICTIONARY_KEYS_PAGE_SIZE_WEIGHT,
+                    NOT_USED,
+                ],
+            ),
+            dictionary_remove: HostFunction::new(
+                DEFAULT_DICTIONARY_REMOVE_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_DICTIONARY_REMOVE_KEY_BYTES_SIZE_WEIGHT,
+                ],
+            ),
+            prune: HostFunction::fixed(DEFAULT_PRUNE_COST),
+            emit_event: HostFunction::new(
+                DEFAULT_EMIT_EVENT_COST,
+                [
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT,
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT,
+                ],
+            ),
         }
     }
 }
@@ -503,6 +554,10 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.blake2b.to_bytes()?);
         ret.append(&mut self.random_bytes.to_bytes()?);
         ret.append(&mut self.enable_contract_version.to_bytes()?);
+        ret.append(&mut self.dictionary_keys.to_bytes()?);
+        ret.append(&mut self.dictionary_remove.to_bytes()?);
+        ret.append(&mut self.prune.to_bytes()?);
+        ret.append(&mut self.emit_event.to_bytes()?);
         Ok(ret)
     }
 
@@ -551,6 +606,10 @@ impl ToBytes for HostFunctionCosts {
             + self.blake2b.serialized_length()
             + self.random_bytes.serialized_length()
             + self.enable_contract_version.serialized_length()
+            + self.dictionary_keys.serialized_length()
+            + self.dictionary_remove.serialized_length()
+            + self.prune.serialized_length()
+            + self.emit_event.serialized_length()
     }
 }
 
@@ -600,6 +659,10 @@ impl FromBytes for HostFunctionCosts {
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
         let (random_bytes, rem) = FromBytes::from_bytes(rem)?;
         let (enable_contract_version, rem) = FromBytes::from_bytes(rem)?;
+        let (dictionary_keys, rem) = FromBytes::from_bytes(rem)?;
+        let (dictionary_remove, rem) = FromBytes::from_bytes(rem)?;
+        let (prune, rem) = FromBytes::from_bytes(rem)?;
+        let (emit_event, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -646,6 +709,10 @@ impl FromBytes for HostFunctionCosts {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                dictionary_keys,
+                dictionary_remove,
+                prune,
+                emit_event,
             },
             rem,
         ))
@@ -699,6 +766,10 @@ impl Distribution<HostFunctionCosts> for Standard {
             blake2b: rng.gen(),
             random_bytes: rng.gen(),
             enable_contract_version: rng.gen(),
+            dictionary_keys: rng.gen(),
+            dictionary_remove: rng.gen(),
+            prune: rng.gen(),
+            emit_event: rng.gen(),
         }
     }
 }
@@ -760,6 +831,10 @@ pub mod gens {
             blake2b in host_function_cost_arb(),
             random_bytes in host_function_cost_arb(),
             enable_contract_version in host_function_cost_arb(),
+            dictionary_keys in host_function_cost_arb(),
+            dictionary_remove in host_function_cost_arb(),
+            prune in host_function_cost_arb(),
+            emit_event in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -806,6 +881,10 @@ pub mod gens {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                dictionary_keys,
+                dictionary_remove,
+                prune,
+                emit_event,
             }
         }
     }
@@ -11,18 +11,26 @@ use casper_types::{
 /// Default gas cost per byte stored.
 pub const DEFAULT_GAS_PER_BYTE_COST: u32 = 1_117_587;
 
+/// Default gas refunded per byte removed.
+pub const DEFAULT_GAS_REFUND_PER_BYTE: u32 = 558_793;
+
 /// Represents a cost table for storage costs.
 #[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
 #[serde(deny_unknown_fields)]
 pub struct StorageCosts {
     /// Gas charged per byte stored in the global state.
     gas_per_byte: u32,
+    /// Gas refunded per byte removed from the global state.
+    gas_refund_per_byte: u32,
 }
 
 impl StorageCosts {
     /// Creates new `StorageCosts`.
-    pub const fn new(gas_per_byte: u32) -> Self {
-        Self { gas_per_byte }
+    pub const fn new(gas_per_byte: u32, gas_refund_per_byte: u32) -> Self {
+        Self {
+            gas_per_byte,
+            gas_refund_per_byte,
+        }
     }
 
     /// Returns amount of gas per byte stored.
@@ -30,17 +38,29 @@ impl StorageCosts {
         self.gas_per_byte
     }
 
+    /// Returns amount of gas refunded per byte removed.
+    pub fn gas_refund_per_byte(&self) -> u32 {
+        self.gas_refund_per_byte
+    }
+
     /// Calculates gas cost for storing `bytes`.
     pub fn calculate_gas_cost(&self, bytes: usize) -> Gas {
         let value = U512::from(self.gas_per_byte) * U512::from(bytes);
         Gas::new(value)
     }
+
+    /// Calculates gas refunded for removing `bytes`.
+    pub fn calculate_gas_refund(&self, bytes: usize) -> Gas {
+        let value = U512::from(self.gas_refund_per_byte) * U512::from(bytes);
+        Gas::new(value)
+    }
 }
 
 impl Default for StorageCosts {
     fn default() -> Self {
         Self {
             gas_per_byte: DEFAULT_GAS_PER_BYTE_COST,
+            gas_refund_per_byte: DEFAULT_GAS_REFUND_PER_BYTE,
         }
     }
 }
@@ -49,6 +69,7 @@ impl Distribution<StorageCosts> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> StorageCosts {
         StorageCosts {
             gas_per_byte: rng.gen(),
+            gas_refund_per_byte: rng.gen(),
         }
     }
 }
@@ -58,20 +79,28 @@ impl ToBytes for StorageCosts {
         let mut ret = bytesrepr::unchecked_allocate_buffer(self);
 
         ret.append(&mut self.gas_per_byte.to_bytes()?);
+        ret.append(&mut self.gas_refund_per_byte.to_bytes()?);
 
         Ok(ret)
     }
 
     fn serialized_length(&self) -> usize {
-        self.gas_per_byte.serialized_length()
+        self.gas_per_byte.serialized_length() + self.gas_refund_per_byte.serialized_length()
     }
 }
 
 impl FromBytes for StorageCosts {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
         let (gas_per_byte, rem) = FromBytes::from_bytes(bytes)?;
+        let (gas_refund_per_byte, rem) = FromBytes::from_bytes(rem)?;
 
-        Ok((StorageCosts { gas_per_byte }, rem))
+        Ok((
+            StorageCosts {
+                gas_per_byte,
+                gas_refund_per_byte,
+            },
+            rem,
+        ))
     }
 }
 
@@ -103,6 +132,16 @@ pub mod tests {
         let expected_cost = U512::from(DEFAULT_GAS_PER_BYTE_COST) * U512::from(LARGE_WEIGHT);
         assert_eq!(cost, Gas::new(expected_cost));
     }
+
+    #[test]
+    fn should_calculate_gas_refund() {
+        let storage_costs = StorageCosts::default();
+
+        let refund = storage_costs.calculate_gas_refund(SMALL_WEIGHT);
+
+        let expected_refund = U512::from(DEFAULT_GAS_REFUND_PER_BYTE) * U512::from(SMALL_WEIGHT);
+        assert_eq!(refund, Gas::new(expected_refund));
+    }
 }
 
 #[doc(hidden)]
@@ -115,9 +154,11 @@ pub mod gens {
     prop_compose! {
         pub fn storage_costs_arb()(
             gas_per_byte in num::u32::ANY,
+            gas_refund_per_byte in num::u32::ANY,
         ) -> StorageCosts {
             StorageCosts {
                 gas_per_byte,
+                gas_refund_per_byte,
             }
         }
     }
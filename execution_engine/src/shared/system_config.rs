@@ -8,7 +8,10 @@ use datasize::DataSize;
 use rand::{distributions::Standard, prelude::*, Rng};
 use serde::{Deserialize, Serialize};
 
-use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    U512,
+};
 
 use self::{
     auction_costs::AuctionCosts, handle_payment_costs::HandlePaymentCosts, mint_costs::MintCosts,
@@ -18,16 +21,23 @@ use self::{
 /// Default gas cost for a wasmless transfer.
 pub const DEFAULT_WASMLESS_TRANSFER_COST: u32 = 100_000_000;
 
+/// Default gas cost of each target after the first of a wasmless transfer to multiple targets.
+pub const DEFAULT_WASMLESS_TRANSFER_TARGET_COST: u32 = 100_000_000;
+
 /// Definition of costs in the system.
 ///
 /// This structure contains the costs of all the system contract's entry points and, additionally,
-/// it defines a wasmless transfer cost.
+/// it defines the cost of a wasmless transfer.
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
 #[serde(deny_unknown_fields)]
 pub struct SystemConfig {
     /// Wasmless transfer cost expressed in gas.
     wasmless_transfer_cost: u32,
 
+    /// Cost expressed in gas of each target after the first of a wasmless transfer to multiple
+    /// targets.
+    wasmless_transfer_target_cost: u32,
+
     /// Configuration of auction entrypoint costs.
     auction_costs: AuctionCosts,
 
@@ -45,6 +55,7 @@ impl SystemConfig {
     /// Creates new system config instance.
     pub fn new(
         wasmless_transfer_cost: u32,
+        wasmless_transfer_target_cost: u32,
         auction_costs: AuctionCosts,
         mint_costs: MintCosts,
         handle_payment_costs: HandlePaymentCosts,
@@ -52,6 +63,7 @@ impl SystemConfig {
     ) -> Self {
         Self {
             wasmless_transfer_cost,
+            wasmless_transfer_target_cost,
             auction_costs,
             mint_costs,
             handle_payment_costs,
@@ -64,6 +76,18 @@ impl SystemConfig {
         self.wasmless_transfer_cost
     }
 
+    /// Returns the cost of each target after the first of a wasmless transfer to multiple targets.
+    pub fn wasmless_transfer_target_cost(&self) -> u32 {
+        self.wasmless_transfer_target_cost
+    }
+
+    /// Returns the total cost of a wasmless transfer to `target_count` targets.
+    pub fn wasmless_transfer_cost_for_targets(&self, target_count: usize) -> U512 {
+        let additional_targets = U512::from(target_count.saturating_sub(1));
+        U512::from(self.wasmless_transfer_cost)
+            + U512::from(self.wasmless_transfer_target_cost) * additional_targets
+    }
+
     /// Returns the costs of executing auction entry points.
     pub fn auction_costs(&self) -> &AuctionCosts {
         &self.auction_costs
@@ -89,6 +113,7 @@ impl Default for SystemConfig {
     fn default() -> Self {
         Self {
             wasmless_transfer_cost: DEFAULT_WASMLESS_TRANSFER_COST,
+            wasmless_transfer_target_cost: DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
             auction_costs: AuctionCosts::default(),
             mint_costs: MintCosts::default(),
             handle_payment_costs: HandlePaymentCosts::default(),
@@ -101,6 +126,7 @@ impl Distribution<SystemConfig> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SystemConfig {
         SystemConfig {
             wasmless_transfer_cost: rng.gen(),
+            wasmless_transfer_target_cost: rng.gen(),
             auction_costs: rng.gen(),
             mint_costs: rng.gen(),
             handle_payment_costs: rng.gen(),
@@ -114,6 +140,7 @@ impl ToBytes for SystemConfig {
         let mut ret = bytesrepr::unchecked_allocate_buffer(self);
 
         ret.append(&mut self.wasmless_transfer_cost.to_bytes()?);
+        ret.append(&mut self.wasmless_transfer_target_cost.to_bytes()?);
         ret.append(&mut self.auction_costs.to_bytes()?);
         ret.append(&mut self.mint_costs.to_bytes()?);
         ret.append(&mut self.handle_payment_costs.to_bytes()?);
@@ -124,6 +151,7 @@ impl ToBytes for SystemConfig {
 
     fn serialized_length(&self) -> usize {
         self.wasmless_transfer_cost.serialized_length()
+            + self.wasmless_transfer_target_cost.serialized_length()
             + self.auction_costs.serialized_length()
             + self.mint_costs.serialized_length()
             + self.handle_payment_costs.serialized_length()
@@ -134,6 +162,7 @@ impl ToBytes for SystemConfig {
 impl FromBytes for SystemConfig {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), casper_types::bytesrepr::Error> {
         let (wasmless_transfer_cost, rem) = FromBytes::from_bytes(bytes)?;
+        let (wasmless_transfer_target_cost, rem) = FromBytes::from_bytes(rem)?;
         let (auction_costs, rem) = FromBytes::from_bytes(rem)?;
         let (mint_costs, rem) = FromBytes::from_bytes(rem)?;
         let (handle_payment_costs, rem) = FromBytes::from_bytes(rem)?;
@@ -141,6 +170,7 @@ impl FromBytes for SystemConfig {
         Ok((
             SystemConfig::new(
                 wasmless_transfer_cost,
+                wasmless_transfer_target_cost,
                 auction_costs,
                 mint_costs,
                 handle_payment_costs,
@@ -165,6 +195,7 @@ pub mod gens {
     prop_compose! {
         pub fn system_config_arb()(
             wasmless_transfer_cost in num::u32::ANY,
+            wasmless_transfer_target_cost in num::u32::ANY,
             auction_costs in auction_costs_arb(),
             mint_costs in mint_costs_arb(),
             handle_payment_costs in handle_payment_costs_arb(),
@@ -172,6 +203,7 @@ pub mod gens {
         ) -> SystemConfig {
             SystemConfig {
                 wasmless_transfer_cost,
+                wasmless_transfer_target_cost,
                 auction_costs,
                 mint_costs,
                 handle_payment_costs,
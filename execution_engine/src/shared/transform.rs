@@ -13,7 +13,8 @@ use num::traits::{AsPrimitive, WrappingAdd};
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     contracts::NamedKeys,
-    CLType, CLTyped, CLValue, CLValueError, StoredValue, StoredValueTypeMismatch, U128, U256, U512,
+    CLType, CLTyped, CLValue, CLValueError, Key, StoredValue, StoredValueTypeMismatch, U128, U256,
+    U512,
 };
 
 /// Error type for applying and combining transforms. A `TypeMismatch`
@@ -90,6 +91,8 @@ pub enum Transform {
     /// Represents the case where applying a transform would cause an error.
     #[data_size(skip)]
     Failure(Error),
+    /// Removes the given key from the global state.
+    Prune(Key),
 }
 
 macro_rules! from_try_from_impl {
@@ -234,6 +237,12 @@ impl Transform {
                 }
             },
             Transform::Failure(error) => Err(error),
+            Transform::Prune(_) => {
+                // A pruned key holds no value, so there is nothing to apply further transforms to.
+                let expected = "no value".to_string();
+                let found = stored_value.type_name();
+                Err(StoredValueTypeMismatch::new(expected, found).into())
+            }
         }
     }
 }
@@ -276,6 +285,7 @@ impl Add for Transform {
             (a @ Transform::Failure(_), _) => a,
             (_, b @ Transform::Failure(_)) => b,
             (_, b @ Transform::Write(_)) => b,
+            (_, b @ Transform::Prune(_)) => b,
             (Transform::Write(v), b) => {
                 // second transform changes value being written
                 match b.apply(v) {
@@ -318,6 +328,9 @@ impl Add for Transform {
                         .into(),
                 ),
             },
+            (Transform::Prune(_), other) => Transform::Failure(
+                StoredValueTypeMismatch::new("Write".to_owned(), format!("{:?}", other)).into(),
+            ),
         }
     }
 }
@@ -384,6 +397,7 @@ impl From<&Transform> for casper_types::Transform {
                     .collect(),
             ),
             Transform::Failure(error) => casper_types::Transform::Failure(error.to_string()),
+            Transform::Prune(key) => casper_types::Transform::Prune(*key),
         }
     }
 }
@@ -393,7 +407,7 @@ impl From<&Transform> for casper_types::Transform {
 pub mod gens {
     use proptest::{collection::vec, prelude::*};
 
-    use casper_types::gens::stored_value_arb;
+    use casper_types::gens::{key_arb, stored_value_arb};
 
     use super::Transform;
 
@@ -414,6 +428,7 @@ pub mod gens {
                 buf.copy_from_slice(&u);
                 Transform::AddUInt512(buf.into())
             }),
+            key_arb().prop_map(Transform::Prune),
         ]
     }
 }
@@ -625,6 +640,24 @@ mod tests {
         assert_yields_type_mismatch_error(cl_tuple3);
     }
 
+    #[test]
+    fn prune_should_override_preceding_transforms() {
+        let key = Key::Hash(ZERO_ARRAY);
+        let cl_value = CLValue::from_t(ONE_U64).expect("should create CLValue");
+        let write = Transform::Write(StoredValue::CLValue(cl_value));
+
+        assert_eq!(write.clone() + Transform::Prune(key), Transform::Prune(key));
+        assert_eq!(
+            Transform::AddUInt64(ONE_U64) + Transform::Prune(key),
+            Transform::Prune(key)
+        );
+        assert_eq!(Transform::Prune(key) + write.clone(), write);
+        assert!(matches!(
+            Transform::Prune(key) + Transform::AddUInt64(ONE_U64),
+            Transform::Failure(Error::TypeMismatch(_))
+        ));
+    }
+
     #[test]
     #[allow(clippy::cognitive_complexity)]
     fn wrapping_addition_should_succeed() {
@@ -7,7 +7,7 @@ use casper_types::bytesrepr;
 
 use crate::storage::{error::in_memory, global_state::CommitError};
 
-/// Error enum representing possible error states in LMDB interactions.
+/// Error enum representing possible error states in LMDB and sled interactions.
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Error {
@@ -15,6 +15,10 @@ pub enum Error {
     #[error(transparent)]
     Lmdb(#[from] lmdb_external::Error),
 
+    /// Sled error returned from underlying `sled` crate.
+    #[error(transparent)]
+    Sled(#[from] sled::Error),
+
     /// (De)serialization error.
     #[error("{0}")]
     BytesRepr(bytesrepr::Error),
@@ -26,6 +30,10 @@ pub enum Error {
     /// Error committing to execution engine.
     #[error(transparent)]
     CommitError(#[from] CommitError),
+
+    /// Compaction would need to mark more tries than its limit allows.
+    #[error("compaction would mark more than {0} tries")]
+    CompactionMarkLimitExceeded(u64),
 }
 
 impl casper_wasmi::HostError for Error {}
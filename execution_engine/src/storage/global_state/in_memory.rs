@@ -21,8 +21,9 @@ use crate::{
         trie_store::{
             in_memory::InMemoryTrieStore,
             operations::{
-                self, delete, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
-                DeleteResult, ReadResult, WriteResult,
+                self, delete, diff, keys_with_prefix, leaves_with_prefix, missing_children,
+                put_trie, read, read_with_proof, DeleteResult, DiffResult, LeavesResult,
+                ReadResult, WriteResult,
             },
         },
     },
@@ -197,6 +198,33 @@ impl StateReader<Key, StoredValue> for InMemoryGlobalStateView {
         txn.commit()?;
         Ok(ret)
     }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = leaves_with_prefix::<
+            Key,
+            StoredValue,
+            InMemoryReadTransaction,
+            InMemoryTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
 }
 
 impl CommitProvider for InMemoryGlobalState {
@@ -308,6 +336,59 @@ impl StateProvider for InMemoryGlobalState {
         txn.commit()?;
         Ok(DeleteResult::Deleted(root))
     }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        before: Digest,
+        after: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result =
+            diff::<Key, StoredValue, InMemoryReadTransaction, InMemoryTrieStore, Self::Error>(
+                correlation_id,
+                &txn,
+                self.trie_store.deref(),
+                &before,
+                &after,
+                prefix,
+                page_start,
+                page_size,
+            )?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = leaves_with_prefix::<
+            Key,
+            StoredValue,
+            InMemoryReadTransaction,
+            InMemoryTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &state_root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
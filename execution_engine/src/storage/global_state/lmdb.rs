@@ -19,14 +19,15 @@ use crate::{
         trie_store::{
             lmdb::{LmdbTrieStore, ScratchTrieStore},
             operations::{
-                delete, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
-                DeleteResult, ReadResult,
+                delete, diff, keys_with_prefix, leaves_with_prefix, missing_children, put_trie,
+                read, read_with_proof, DeleteResult, DiffResult, LeavesResult, ReadResult,
             },
         },
     },
 };
 
 /// Global state implemented against LMDB as a backing data store.
+#[derive(Clone)]
 pub struct LmdbGlobalState {
     /// Environment for LMDB.
     pub(crate) environment: Arc<LmdbEnvironment>,
@@ -80,11 +81,7 @@ impl LmdbGlobalState {
 
     /// Creates an in-memory cache for changes written.
     pub fn create_scratch(&self) -> ScratchGlobalState {
-        ScratchGlobalState::new(
-            Arc::clone(&self.environment),
-            Arc::clone(&self.trie_store),
-            self.empty_root_hash,
-        )
+        ScratchGlobalState::new(self.clone())
     }
 
     /// Write stored values to LMDB.
@@ -208,6 +205,33 @@ impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
         txn.commit()?;
         Ok(ret)
     }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = leaves_with_prefix::<
+            Key,
+            StoredValue,
+            lmdb::RoTransaction,
+            LmdbTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
 }
 
 impl CommitProvider for LmdbGlobalState {
@@ -325,6 +349,58 @@ impl StateProvider for LmdbGlobalState {
         scratch_trie_store.write_root_to_db(state_root_hash)?;
         Ok(DeleteResult::Deleted(state_root_hash))
     }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        before: Digest,
+        after: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = diff::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &before,
+            &after,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = leaves_with_prefix::<
+            Key,
+            StoredValue,
+            lmdb::RoTransaction,
+            LmdbTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &state_root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
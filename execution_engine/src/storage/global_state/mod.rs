@@ -6,15 +6,27 @@ pub mod in_memory;
 /// Lmdb implementation of global state.
 pub mod lmdb;
 
+/// Global state backed by either of the persistent implementations.
+pub mod persistent;
+
 /// Lmdb implementation of global state with cache.
 pub mod scratch;
 
-use std::{collections::HashMap, hash::BuildHasher};
+/// Sled implementation of global state.
+pub mod sled;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::BuildHasher,
+};
 
 use tracing::error;
 
 use casper_hashing::Digest;
-use casper_types::{bytesrepr, Key, StoredValue};
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    Key, StoredValue,
+};
 
 use crate::{
     shared::{
@@ -26,13 +38,13 @@ use crate::{
         transaction_source::{Transaction, TransactionSource},
         trie::{merkle_proof::TrieMerkleProof, Trie, TrieRaw},
         trie_store::{
-            operations::{read, write, ReadResult, WriteResult},
+            operations::{delete, read, write, ReadResult, WriteResult},
             TrieStore,
         },
     },
 };
 
-use super::trie_store::operations::DeleteResult;
+use super::trie_store::operations::{DeleteResult, DiffResult, LeavesResult};
 
 /// A trait expressing the reading of state. This trait is used to abstract the underlying store.
 pub trait StateReader<K, V> {
@@ -55,6 +67,16 @@ pub trait StateReader<K, V> {
         correlation_id: CorrelationId,
         prefix: &[u8],
     ) -> Result<Vec<K>, Self::Error>;
+
+    /// Returns a page of at most `page_size` keys matching `prefix` and their values, starting
+    /// from `page_start` if given.
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&K>,
+        page_size: usize,
+    ) -> Result<LeavesResult<K, V>, Self::Error>;
 }
 
 /// An error emitted by the execution engine on commit
@@ -130,6 +152,92 @@ pub trait StateProvider {
         root: Digest,
         keys_to_delete: &[Key],
     ) -> Result<DeleteResult, Self::Error>;
+
+    /// Returns a page of at most `page_size` keys whose values differ between the global states
+    /// under `before` and `after`, starting from `page_start` if given.
+    ///
+    /// Only keys whose serialized bytes start with `prefix` are included, e.g. a single
+    /// `KeyTag` byte to only include keys of that type.
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        before: Digest,
+        after: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error>;
+
+    /// Returns a page of at most `page_size` keys and their values from the global state under
+    /// `state_root_hash`, starting from `page_start` if given.
+    ///
+    /// Only keys whose serialized bytes start with `prefix` are included.
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error>;
+}
+
+/// Applies changes cached on top of a global state to a page of its leaves obtained via
+/// [`StateReader::leaves_with_prefix`].
+///
+/// Pruned keys are removed from the page, while written keys matching `prefix` which fall within
+/// the page replace or join its leaves.  If the page then holds more than `page_size` leaves, it is
+/// truncated and the next page starts from the first leaf dropped.
+pub(crate) fn overlay_leaves<'a>(
+    result: LeavesResult<Key, StoredValue>,
+    prefix: &[u8],
+    page_start: Option<&Key>,
+    page_size: usize,
+    written: impl Iterator<Item = (&'a Key, &'a StoredValue)>,
+    pruned_keys: &HashSet<Key>,
+) -> LeavesResult<Key, StoredValue> {
+    let (leaves, next_page_start) = match result {
+        LeavesResult::Success {
+            leaves,
+            next_page_start,
+        } => (leaves, next_page_start),
+        other => return other,
+    };
+    let key_bytes = |key: &Key| key.to_bytes().expect("keys should serialize");
+    let page_start_bytes = page_start.map(key_bytes);
+    let next_page_start_bytes = next_page_start.as_ref().map(key_bytes);
+
+    let mut overlaid = BTreeMap::new();
+    for (key, value) in leaves {
+        if !pruned_keys.contains(&key) {
+            overlaid.insert(key_bytes(&key), (key, value));
+        }
+    }
+    for (key, value) in written {
+        let bytes = key_bytes(key);
+        let within_page = bytes.starts_with(prefix)
+            && page_start_bytes
+                .as_ref()
+                .map_or(true, |start| bytes >= *start)
+            && next_page_start_bytes
+                .as_ref()
+                .map_or(true, |next| bytes < *next);
+        if within_page {
+            overlaid.insert(bytes, (*key, value.clone()));
+        }
+    }
+
+    let mut leaves: Vec<(Key, StoredValue)> = overlaid.into_values().collect();
+    let next_page_start = if leaves.len() > page_size {
+        leaves.truncate(page_size + 1);
+        leaves.pop().map(|(key, _value)| key)
+    } else {
+        next_page_start
+    };
+    LeavesResult::Success {
+        leaves,
+        next_page_start,
+    }
 }
 
 /// Write multiple key/stored value pairs to the store in a single rw transaction.
@@ -195,6 +303,25 @@ where
     };
 
     for (key, transform) in effects.into_iter() {
+        if let Transform::Prune(_) = transform {
+            match delete::<_, _, _, _, E>(correlation_id, &mut txn, store, &state_root, &key)? {
+                DeleteResult::Deleted(root_hash) => {
+                    state_root = root_hash;
+                }
+                // The key was written and pruned within the same effects.
+                DeleteResult::DoesNotExist => (),
+                DeleteResult::RootNotFound => {
+                    error!(
+                        ?state_root,
+                        ?key,
+                        "Failed to read state root while pruning key"
+                    );
+                    return Err(CommitError::ReadRootNotFound(state_root).into());
+                }
+            }
+            continue;
+        }
+
         let read_result = read::<_, _, _, _, E>(correlation_id, &txn, store, &state_root, &key)?;
 
         let value = match (read_result, transform) {
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use casper_hashing::Digest;
+use casper_types::{Key, StoredValue};
+
+use crate::{
+    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    storage::{
+        error,
+        global_state::{
+            lmdb::{LmdbGlobalState, LmdbGlobalStateView},
+            scratch::ScratchGlobalState,
+            sled::{SledGlobalState, SledGlobalStateView},
+            CommitProvider, StateProvider, StateReader,
+        },
+        trie::{merkle_proof::TrieMerkleProof, TrieRaw},
+        trie_store::operations::{DeleteResult, DiffResult, LeavesResult},
+    },
+};
+
+/// Global state persisted by one of the supported on-disk backends.
+///
+/// Allows the backend to be chosen at runtime, e.g. from the node's configuration, while
+/// `EngineState` remains generic over a single `StateProvider`.
+#[derive(Clone)]
+pub enum PersistentGlobalState {
+    /// Global state backed by LMDB.
+    Lmdb(LmdbGlobalState),
+    /// Global state backed by sled.
+    Sled(SledGlobalState),
+}
+
+/// Represents a "view" of persistent global state at a particular root hash.
+pub enum PersistentGlobalStateView {
+    /// View of LMDB-backed global state.
+    Lmdb(LmdbGlobalStateView),
+    /// View of sled-backed global state.
+    Sled(SledGlobalStateView),
+}
+
+impl PersistentGlobalState {
+    /// Creates an in-memory cache for changes written.
+    pub fn create_scratch(&self) -> ScratchGlobalState<PersistentGlobalState> {
+        ScratchGlobalState::new(self.clone())
+    }
+
+    /// Write stored values to the backing store.
+    pub fn put_stored_values(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Digest,
+        stored_values: HashMap<Key, StoredValue>,
+    ) -> Result<Digest, error::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => {
+                state.put_stored_values(correlation_id, prestate_hash, stored_values)
+            }
+            PersistentGlobalState::Sled(state) => {
+                state.put_stored_values(correlation_id, prestate_hash, stored_values)
+            }
+        }
+    }
+
+    /// Returns if the backing environment was constructed with manual synchronization enabled.
+    pub fn is_manual_sync_enabled(&self) -> bool {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.environment.is_manual_sync_enabled(),
+            PersistentGlobalState::Sled(state) => state.environment.is_manual_sync_enabled(),
+        }
+    }
+
+    /// Manually synchronize the backing store to disk.
+    pub fn sync(&self) -> Result<(), error::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.environment.sync()?,
+            PersistentGlobalState::Sled(state) => state.environment.sync()?,
+        }
+        Ok(())
+    }
+
+    /// Returns an initial, empty root hash of the underlying trie.
+    pub fn empty_state_root_hash(&self) -> Digest {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.empty_state_root_hash(),
+            PersistentGlobalState::Sled(state) => state.empty_state_root_hash(),
+        }
+    }
+}
+
+impl From<LmdbGlobalState> for PersistentGlobalState {
+    fn from(state: LmdbGlobalState) -> Self {
+        PersistentGlobalState::Lmdb(state)
+    }
+}
+
+impl From<SledGlobalState> for PersistentGlobalState {
+    fn from(state: SledGlobalState) -> Self {
+        PersistentGlobalState::Sled(state)
+    }
+}
+
+impl StateReader<Key, StoredValue> for PersistentGlobalStateView {
+    type Error = error::Error;
+
+    fn read(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<Option<StoredValue>, Self::Error> {
+        match self {
+            PersistentGlobalStateView::Lmdb(view) => view.read(correlation_id, key),
+            PersistentGlobalStateView::Sled(view) => view.read(correlation_id, key),
+        }
+    }
+
+    fn read_with_proof(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<Option<TrieMerkleProof<Key, StoredValue>>, Self::Error> {
+        match self {
+            PersistentGlobalStateView::Lmdb(view) => view.read_with_proof(correlation_id, key),
+            PersistentGlobalStateView::Sled(view) => view.read_with_proof(correlation_id, key),
+        }
+    }
+
+    fn keys_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+    ) -> Result<Vec<Key>, Self::Error> {
+        match self {
+            PersistentGlobalStateView::Lmdb(view) => view.keys_with_prefix(correlation_id, prefix),
+            PersistentGlobalStateView::Sled(view) => view.keys_with_prefix(correlation_id, prefix),
+        }
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        match self {
+            PersistentGlobalStateView::Lmdb(view) => {
+                view.leaves_with_prefix(correlation_id, prefix, page_start, page_size)
+            }
+            PersistentGlobalStateView::Sled(view) => {
+                view.leaves_with_prefix(correlation_id, prefix, page_start, page_size)
+            }
+        }
+    }
+}
+
+impl CommitProvider for PersistentGlobalState {
+    fn commit(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Digest,
+        effects: AdditiveMap<Key, Transform>,
+    ) -> Result<Digest, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => {
+                state.commit(correlation_id, prestate_hash, effects)
+            }
+            PersistentGlobalState::Sled(state) => {
+                state.commit(correlation_id, prestate_hash, effects)
+            }
+        }
+    }
+}
+
+impl StateProvider for PersistentGlobalState {
+    type Error = error::Error;
+
+    type Reader = PersistentGlobalStateView;
+
+    fn checkout(&self, state_hash: Digest) -> Result<Option<Self::Reader>, Self::Error> {
+        let maybe_view = match self {
+            PersistentGlobalState::Lmdb(state) => state
+                .checkout(state_hash)?
+                .map(PersistentGlobalStateView::Lmdb),
+            PersistentGlobalState::Sled(state) => state
+                .checkout(state_hash)?
+                .map(PersistentGlobalStateView::Sled),
+        };
+        Ok(maybe_view)
+    }
+
+    fn empty_root(&self) -> Digest {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.empty_root(),
+            PersistentGlobalState::Sled(state) => state.empty_root(),
+        }
+    }
+
+    fn get_trie_full(
+        &self,
+        correlation_id: CorrelationId,
+        trie_key: &Digest,
+    ) -> Result<Option<TrieRaw>, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.get_trie_full(correlation_id, trie_key),
+            PersistentGlobalState::Sled(state) => state.get_trie_full(correlation_id, trie_key),
+        }
+    }
+
+    fn put_trie(&self, correlation_id: CorrelationId, trie: &[u8]) -> Result<Digest, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.put_trie(correlation_id, trie),
+            PersistentGlobalState::Sled(state) => state.put_trie(correlation_id, trie),
+        }
+    }
+
+    fn missing_children(
+        &self,
+        correlation_id: CorrelationId,
+        trie_raw: &[u8],
+    ) -> Result<Vec<Digest>, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.missing_children(correlation_id, trie_raw),
+            PersistentGlobalState::Sled(state) => state.missing_children(correlation_id, trie_raw),
+        }
+    }
+
+    fn delete_keys(
+        &self,
+        correlation_id: CorrelationId,
+        root: Digest,
+        keys_to_delete: &[Key],
+    ) -> Result<DeleteResult, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => {
+                state.delete_keys(correlation_id, root, keys_to_delete)
+            }
+            PersistentGlobalState::Sled(state) => {
+                state.delete_keys(correlation_id, root, keys_to_delete)
+            }
+        }
+    }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        before: Digest,
+        after: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => {
+                state.diff(correlation_id, before, after, prefix, page_start, page_size)
+            }
+            PersistentGlobalState::Sled(state) => {
+                state.diff(correlation_id, before, after, prefix, page_start, page_size)
+            }
+        }
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        match self {
+            PersistentGlobalState::Lmdb(state) => state.leaves_with_prefix(
+                correlation_id,
+                state_root_hash,
+                prefix,
+                page_start,
+                page_size,
+            ),
+            PersistentGlobalState::Sled(state) => state.leaves_with_prefix(
+                correlation_id,
+                state_root_hash,
+                prefix,
+                page_start,
+                page_size,
+            ),
+        }
+    }
+}
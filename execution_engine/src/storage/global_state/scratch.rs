@@ -1,30 +1,24 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     mem,
-    ops::Deref,
     sync::{Arc, RwLock},
 };
 
 use tracing::error;
 
 use casper_hashing::Digest;
-use casper_types::{Key, StoredValue};
+use casper_types::{bytesrepr::ToBytes, Key, StoredValue};
 
 use crate::{
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::{
         error,
-        global_state::{CommitError, CommitProvider, StateProvider, StateReader},
-        store::Store,
-        transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
-        trie::{merkle_proof::TrieMerkleProof, Trie, TrieRaw},
-        trie_store::{
-            lmdb::LmdbTrieStore,
-            operations::{
-                delete, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
-                DeleteResult, ReadResult,
-            },
+        global_state::{
+            lmdb::{LmdbGlobalState, LmdbGlobalStateView},
+            overlay_leaves, CommitError, CommitProvider, StateProvider, StateReader,
         },
+        trie::{merkle_proof::TrieMerkleProof, TrieRaw},
+        trie_store::operations::{DeleteResult, DiffResult, LeavesResult},
     },
 };
 
@@ -32,19 +26,36 @@ type SharedCache = Arc<RwLock<Cache>>;
 
 struct Cache {
     cached_values: HashMap<Key, (bool, StoredValue)>,
+    /// Keys to be removed from the persistent global state.
+    pruned_keys: HashSet<Key>,
 }
 
 impl Cache {
     fn new() -> Self {
         Cache {
             cached_values: HashMap::new(),
+            pruned_keys: HashSet::new(),
         }
     }
 
     fn insert_write(&mut self, key: Key, value: StoredValue) {
+        self.pruned_keys.remove(&key);
         self.cached_values.insert(key, (true, value));
     }
 
+    /// Removes `key` from the cache, additionally marking it for removal from the persistent
+    /// global state if it is present there.
+    fn insert_prune(&mut self, key: Key, is_persisted: bool) {
+        self.cached_values.remove(&key);
+        if is_persisted {
+            self.pruned_keys.insert(key);
+        }
+    }
+
+    fn is_pruned(&self, key: &Key) -> bool {
+        self.pruned_keys.contains(key)
+    }
+
     fn insert_read(&mut self, key: Key, value: StoredValue) {
         self.cached_values.entry(key).or_insert((false, value));
     }
@@ -53,64 +64,72 @@ impl Cache {
         self.cached_values.get(key).map(|(_dirty, value)| value)
     }
 
-    /// Consumes self and returns only written values as values that were only read must be filtered
-    /// out to prevent unnecessary writes.
-    fn into_dirty_writes(self) -> HashMap<Key, StoredValue> {
+    /// Returns the written values with a key whose serialized bytes start with `prefix`.
+    fn dirty_writes_with_prefix<'a>(
+        &'a self,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = (&'a Key, &'a StoredValue)> + 'a {
         self.cached_values
+            .iter()
+            .filter(|(_key, (dirty, _value))| *dirty)
+            .map(|(key, (_dirty, value))| (key, value))
+            .filter(move |(key, _value)| {
+                key.to_bytes()
+                    .expect("keys should serialize")
+                    .starts_with(prefix)
+            })
+    }
+
+    /// Consumes self and returns only written values as values that were only read must be filtered
+    /// out to prevent unnecessary writes, along with the keys to be pruned.
+    fn into_dirty_writes(self) -> (HashMap<Key, StoredValue>, HashSet<Key>) {
+        let dirty_writes = self
+            .cached_values
             .into_iter()
             .filter_map(|(key, (dirty, value))| if dirty { Some((key, value)) } else { None })
-            .collect()
+            .collect();
+        (dirty_writes, self.pruned_keys)
     }
 }
 
-/// Global state implemented against LMDB as a backing data store.
-pub struct ScratchGlobalState {
+/// Global state which caches changes in memory on top of a persistent global state.
+///
+/// Defaults to caching on top of LMDB, but works with any persistent backend.
+pub struct ScratchGlobalState<S = LmdbGlobalState> {
     /// Underlying, cached stored values.
     cache: SharedCache,
-    /// Environment for LMDB.
-    pub(crate) environment: Arc<LmdbEnvironment>,
-    /// Trie store held within LMDB.
-    pub(crate) trie_store: Arc<LmdbTrieStore>,
-    // TODO: make this a lazy-static
-    /// Empty root hash used for a new trie.
-    pub(crate) empty_root_hash: Digest,
+    /// Persistent global state read through on cache misses.
+    pub(crate) state: S,
 }
 
 /// Represents a "view" of global state at a particular root hash.
-pub struct ScratchGlobalStateView {
+pub struct ScratchGlobalStateView<R = LmdbGlobalStateView> {
     cache: SharedCache,
-    /// Environment for LMDB.
-    pub(crate) environment: Arc<LmdbEnvironment>,
-    /// Trie store held within LMDB.
-    pub(crate) trie_store: Arc<LmdbTrieStore>,
-    /// Root hash of this "view".
-    pub(crate) root_hash: Digest,
+    /// View of the persistent global state at the same root hash.
+    pub(crate) reader: R,
 }
 
-impl ScratchGlobalState {
-    /// Creates a state from an existing environment, store, and root_hash.
-    /// Intended to be used for testing.
-    pub fn new(
-        environment: Arc<LmdbEnvironment>,
-        trie_store: Arc<LmdbTrieStore>,
-        empty_root_hash: Digest,
-    ) -> Self {
+impl<S> ScratchGlobalState<S> {
+    /// Creates a state caching changes on top of an existing persistent state.
+    pub fn new(state: S) -> Self {
         ScratchGlobalState {
             cache: Arc::new(RwLock::new(Cache::new())),
-            environment,
-            trie_store,
-            empty_root_hash,
+            state,
         }
     }
 
-    /// Consume self and return inner cache.
-    pub fn into_inner(self) -> HashMap<Key, StoredValue> {
+    /// Consume self and return inner cache, along with the keys to be pruned from the persistent
+    /// global state.
+    pub fn into_inner(self) -> (HashMap<Key, StoredValue>, HashSet<Key>) {
         let cache = mem::replace(&mut *self.cache.write().unwrap(), Cache::new());
         cache.into_dirty_writes()
     }
 }
 
-impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
+impl<R> StateReader<Key, StoredValue> for ScratchGlobalStateView<R>
+where
+    R: StateReader<Key, StoredValue, Error = error::Error>,
+{
     type Error = error::Error;
 
     fn read(
@@ -118,25 +137,19 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
-        if let Some(value) = self.cache.read().unwrap().get(key) {
-            return Ok(Some(value.clone()));
-        }
-        let txn = self.environment.create_read_txn()?;
-        let ret = match read::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
-            correlation_id,
-            &txn,
-            self.trie_store.deref(),
-            &self.root_hash,
-            key,
-        )? {
-            ReadResult::Found(value) => {
-                self.cache.write().unwrap().insert_read(*key, value.clone());
-                Some(value)
+        {
+            let cache = self.cache.read().unwrap();
+            if cache.is_pruned(key) {
+                return Ok(None);
             }
-            ReadResult::NotFound => None,
-            ReadResult::RootNotFound => panic!("ScratchGlobalState has invalid root"),
-        };
-        txn.commit()?;
+            if let Some(value) = cache.get(key) {
+                return Ok(Some(value.clone()));
+            }
+        }
+        let ret = self.reader.read(correlation_id, key)?;
+        if let Some(value) = &ret {
+            self.cache.write().unwrap().insert_read(*key, value.clone());
+        }
         Ok(ret)
     }
 
@@ -145,26 +158,7 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<TrieMerkleProof<Key, StoredValue>>, Self::Error> {
-        let txn = self.environment.create_read_txn()?;
-        let ret = match read_with_proof::<
-            Key,
-            StoredValue,
-            lmdb::RoTransaction,
-            LmdbTrieStore,
-            Self::Error,
-        >(
-            correlation_id,
-            &txn,
-            self.trie_store.deref(),
-            &self.root_hash,
-            key,
-        )? {
-            ReadResult::Found(value) => Some(value),
-            ReadResult::NotFound => None,
-            ReadResult::RootNotFound => panic!("LmdbWithCacheGlobalState has invalid root"),
-        };
-        txn.commit()?;
-        Ok(ret)
+        self.reader.read_with_proof(correlation_id, key)
     }
 
     fn keys_with_prefix(
@@ -172,29 +166,38 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         correlation_id: CorrelationId,
         prefix: &[u8],
     ) -> Result<Vec<Key>, Self::Error> {
-        let txn = self.environment.create_read_txn()?;
-        let keys_iter = keys_with_prefix::<Key, StoredValue, _, _>(
-            correlation_id,
-            &txn,
-            self.trie_store.deref(),
-            &self.root_hash,
+        self.reader.keys_with_prefix(correlation_id, prefix)
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let result =
+            self.reader
+                .leaves_with_prefix(correlation_id, prefix, page_start, page_size)?;
+        let cache = self.cache.read().unwrap();
+        Ok(overlay_leaves(
+            result,
             prefix,
-        );
-        let mut ret = Vec::new();
-        for result in keys_iter {
-            match result {
-                Ok(key) => ret.push(key),
-                Err(error) => return Err(error),
-            }
-        }
-        txn.commit()?;
-        Ok(ret)
+            page_start,
+            page_size,
+            cache.dirty_writes_with_prefix(prefix),
+            &cache.pruned_keys,
+        ))
     }
 }
 
-impl CommitProvider for ScratchGlobalState {
-    /// State hash returned is the one provided, as we do not write to lmdb with this kind of global
-    /// state. Note that the state hash is NOT used, and simply passed back to the caller.
+impl<S> CommitProvider for ScratchGlobalState<S>
+where
+    S: StateProvider<Error = error::Error>,
+{
+    /// State hash returned is the one provided, as we do not write to the persistent state with
+    /// this kind of global state. Note that the state hash is NOT used, and simply passed back to
+    /// the caller.
     fn commit(
         &self,
         correlation_id: CorrelationId,
@@ -202,36 +205,51 @@ impl CommitProvider for ScratchGlobalState {
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
         for (key, transform) in effects.into_iter() {
-            let cached_value = self.cache.read().unwrap().get(&key).cloned();
+            if let Transform::Prune(_) = transform {
+                let is_persisted = match self.state.checkout(state_hash)? {
+                    Some(reader) => reader.read(correlation_id, &key)?.is_some(),
+                    None => {
+                        error!(root_hash=?state_hash, "root not found");
+                        return Err(CommitError::ReadRootNotFound(state_hash).into());
+                    }
+                };
+                self.cache.write().unwrap().insert_prune(key, is_persisted);
+                continue;
+            }
+
+            let (cached_value, is_pruned) = {
+                let cache = self.cache.read().unwrap();
+                (cache.get(&key).cloned(), cache.is_pruned(&key))
+            };
             let value = match (cached_value, transform) {
                 (None, Transform::Write(new_value)) => new_value,
+                (None, transform) if is_pruned => {
+                    error!(
+                        ?key,
+                        ?transform,
+                        "Key pruned before attempting to apply transform"
+                    );
+                    return Err(CommitError::KeyNotFound(key).into());
+                }
                 (None, transform) => {
                     // It might be the case that for `Add*` operations we don't have the previous
                     // value in cache yet.
-                    let txn = self.environment.create_read_txn()?;
-                    let updated_value = match read::<
-                        Key,
-                        StoredValue,
-                        lmdb::RoTransaction,
-                        LmdbTrieStore,
-                        Self::Error,
-                    >(
-                        correlation_id,
-                        &txn,
-                        self.trie_store.deref(),
-                        &state_hash,
-                        &key,
-                    )? {
-                        ReadResult::Found(current_value) => {
-                            match transform.apply(current_value.clone()) {
-                                Ok(updated_value) => updated_value,
-                                Err(err) => {
-                                    error!(?key, ?err, "Key found, but could not apply transform");
-                                    return Err(CommitError::TransformError(err).into());
-                                }
-                            }
+                    let reader = match self.state.checkout(state_hash)? {
+                        Some(reader) => reader,
+                        None => {
+                            error!(root_hash=?state_hash, "root not found");
+                            return Err(CommitError::ReadRootNotFound(state_hash).into());
                         }
-                        ReadResult::NotFound => {
+                    };
+                    match reader.read(correlation_id, &key)? {
+                        Some(current_value) => match transform.apply(current_value) {
+                            Ok(updated_value) => updated_value,
+                            Err(err) => {
+                                error!(?key, ?err, "Key found, but could not apply transform");
+                                return Err(CommitError::TransformError(err).into());
+                            }
+                        },
+                        None => {
                             error!(
                                 ?key,
                                 ?transform,
@@ -239,13 +257,7 @@ impl CommitProvider for ScratchGlobalState {
                             );
                             return Err(CommitError::KeyNotFound(key).into());
                         }
-                        ReadResult::RootNotFound => {
-                            error!(root_hash=?state_hash, "root not found");
-                            return Err(CommitError::ReadRootNotFound(state_hash).into());
-                        }
-                    };
-                    txn.commit()?;
-                    updated_value
+                    }
                 }
                 (Some(current_value), transform) => match transform.apply(current_value.clone()) {
                     Ok(updated_value) => updated_value,
@@ -262,52 +274,39 @@ impl CommitProvider for ScratchGlobalState {
     }
 }
 
-impl StateProvider for ScratchGlobalState {
+impl<S> StateProvider for ScratchGlobalState<S>
+where
+    S: StateProvider<Error = error::Error>,
+{
     type Error = error::Error;
 
-    type Reader = ScratchGlobalStateView;
+    type Reader = ScratchGlobalStateView<S::Reader>;
 
     fn checkout(&self, state_hash: Digest) -> Result<Option<Self::Reader>, Self::Error> {
-        let txn = self.environment.create_read_txn()?;
-        let maybe_root: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, &state_hash)?;
-        let maybe_state = maybe_root.map(|_| ScratchGlobalStateView {
-            cache: Arc::clone(&self.cache),
-            environment: Arc::clone(&self.environment),
-            trie_store: Arc::clone(&self.trie_store),
-            root_hash: state_hash,
-        });
-        txn.commit()?;
+        let maybe_state = self
+            .state
+            .checkout(state_hash)?
+            .map(|reader| ScratchGlobalStateView {
+                cache: Arc::clone(&self.cache),
+                reader,
+            });
         Ok(maybe_state)
     }
 
     fn empty_root(&self) -> Digest {
-        self.empty_root_hash
+        self.state.empty_root()
     }
 
     fn get_trie_full(
         &self,
-        _correlation_id: CorrelationId,
+        correlation_id: CorrelationId,
         trie_key: &Digest,
     ) -> Result<Option<TrieRaw>, Self::Error> {
-        let txn = self.environment.create_read_txn()?;
-        let ret: Option<TrieRaw> =
-            Store::<Digest, Trie<Digest, StoredValue>>::get_raw(&*self.trie_store, &txn, trie_key)?
-                .map(TrieRaw::new);
-        txn.commit()?;
-        Ok(ret)
+        self.state.get_trie_full(correlation_id, trie_key)
     }
 
     fn put_trie(&self, correlation_id: CorrelationId, trie: &[u8]) -> Result<Digest, Self::Error> {
-        let mut txn = self.environment.create_read_write_txn()?;
-        let trie_hash = put_trie::<
-            Key,
-            StoredValue,
-            lmdb::RwTransaction,
-            LmdbTrieStore,
-            Self::Error,
-        >(correlation_id, &mut txn, &self.trie_store, trie)?;
-        txn.commit()?;
-        Ok(trie_hash)
+        self.state.put_trie(correlation_id, trie)
     }
 
     /// Finds all of the keys of missing directly descendant `Trie<K,V>` values
@@ -316,42 +315,47 @@ impl StateProvider for ScratchGlobalState {
         correlation_id: CorrelationId,
         trie_raw: &[u8],
     ) -> Result<Vec<Digest>, Self::Error> {
-        let txn = self.environment.create_read_txn()?;
-        let missing_descendants =
-            missing_children::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
-                correlation_id,
-                &txn,
-                self.trie_store.deref(),
-                trie_raw,
-            )?;
-        txn.commit()?;
-        Ok(missing_descendants)
+        self.state.missing_children(correlation_id, trie_raw)
     }
 
     fn delete_keys(
         &self,
         correlation_id: CorrelationId,
-        mut state_root_hash: Digest,
+        state_root_hash: Digest,
         keys_to_delete: &[Key],
     ) -> Result<DeleteResult, Self::Error> {
-        let mut txn = self.environment.create_read_write_txn()?;
-        for key in keys_to_delete {
-            let delete_result = delete::<Key, StoredValue, _, _, Self::Error>(
-                correlation_id,
-                &mut txn,
-                self.trie_store.deref(),
-                &state_root_hash,
-                key,
-            );
-            match delete_result? {
-                DeleteResult::Deleted(root) => {
-                    state_root_hash = root;
-                }
-                other => return Ok(other),
-            }
-        }
-        txn.commit()?;
-        Ok(DeleteResult::Deleted(state_root_hash))
+        self.state
+            .delete_keys(correlation_id, state_root_hash, keys_to_delete)
+    }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        before: Digest,
+        after: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        self.state
+            .diff(correlation_id, before, after, prefix, page_start, page_size)
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        self.state.leaves_with_prefix(
+            correlation_id,
+            state_root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )
     }
 }
 
@@ -366,7 +370,11 @@ mod tests {
     use super::*;
     use crate::storage::{
         global_state::{lmdb::LmdbGlobalState, CommitProvider},
-        trie_store::operations::{write, WriteResult},
+        transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
+        trie_store::{
+            lmdb::LmdbTrieStore,
+            operations::{write, WriteResult},
+        },
         DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
     };
 
@@ -510,7 +518,7 @@ mod tests {
             .keys_with_prefix(correlation_id, &[])
             .unwrap();
 
-        let stored_values = scratch.into_inner();
+        let (stored_values, _) = scratch.into_inner();
         assert_eq!(all_keys.len(), stored_values.len());
 
         for key in all_keys {
@@ -0,0 +1,526 @@
+use std::{collections::HashMap, ops::Deref, sync::Arc};
+
+use casper_hashing::Digest;
+use casper_types::{Key, StoredValue};
+
+use crate::{
+    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+    storage::{
+        error,
+        global_state::{
+            commit, put_stored_values, scratch::ScratchGlobalState, CommitProvider, StateProvider,
+            StateReader,
+        },
+        store::Store,
+        transaction_source::{
+            sled::{SledEnvironment, SledReadTransaction, SledReadWriteTransaction},
+            Transaction, TransactionSource,
+        },
+        trie::{
+            merkle_proof::TrieMerkleProof, operations::create_hashed_empty_trie, Trie, TrieRaw,
+        },
+        trie_store::{
+            operations::{
+                delete, diff, keys_with_prefix, leaves_with_prefix, missing_children, put_trie,
+                read, read_with_proof, DeleteResult, DiffResult, LeavesResult, ReadResult,
+            },
+            sled::SledTrieStore,
+        },
+    },
+};
+
+/// Global state implemented against sled as a backing data store.
+#[derive(Clone)]
+pub struct SledGlobalState {
+    /// Environment for sled.
+    pub(crate) environment: Arc<SledEnvironment>,
+    /// Trie store held within sled.
+    pub(crate) trie_store: Arc<SledTrieStore>,
+    /// Empty root hash used for a new trie.
+    pub(crate) empty_root_hash: Digest,
+}
+
+/// Represents a "view" of global state at a particular root hash.
+pub struct SledGlobalStateView {
+    /// Environment for sled.
+    pub(crate) environment: Arc<SledEnvironment>,
+    /// Trie store held within sled.
+    pub(crate) store: Arc<SledTrieStore>,
+    /// Root hash of this "view".
+    pub(crate) root_hash: Digest,
+}
+
+impl SledGlobalState {
+    /// Creates an empty state from an existing environment and trie_store.
+    pub fn empty(
+        environment: Arc<SledEnvironment>,
+        trie_store: Arc<SledTrieStore>,
+    ) -> Result<Self, error::Error> {
+        let root_hash: Digest = {
+            let (root_hash, root) = create_hashed_empty_trie::<Key, StoredValue>()?;
+            let mut txn = environment.create_read_write_txn()?;
+            trie_store.put(&mut txn, &root_hash, &root)?;
+            txn.commit()?;
+            environment.sync()?;
+            root_hash
+        };
+        Ok(SledGlobalState::new(environment, trie_store, root_hash))
+    }
+
+    /// Creates a state from an existing environment, store, and root_hash.
+    /// Intended to be used for testing.
+    pub fn new(
+        environment: Arc<SledEnvironment>,
+        trie_store: Arc<SledTrieStore>,
+        empty_root_hash: Digest,
+    ) -> Self {
+        SledGlobalState {
+            environment,
+            trie_store,
+            empty_root_hash,
+        }
+    }
+
+    /// Creates an in-memory cache for changes written.
+    pub fn create_scratch(&self) -> ScratchGlobalState<SledGlobalState> {
+        ScratchGlobalState::new(self.clone())
+    }
+
+    /// Write stored values to sled.
+    pub fn put_stored_values(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Digest,
+        stored_values: HashMap<Key, StoredValue>,
+    ) -> Result<Digest, error::Error> {
+        put_stored_values::<_, _, error::Error>(
+            self.environment.deref(),
+            self.trie_store.deref(),
+            correlation_id,
+            prestate_hash,
+            stored_values,
+        )
+    }
+
+    /// Get a reference to the sled global state's environment.
+    #[must_use]
+    pub fn environment(&self) -> &SledEnvironment {
+        &self.environment
+    }
+
+    /// Get a reference to the sled global state's trie store.
+    #[must_use]
+    pub fn trie_store(&self) -> &SledTrieStore {
+        &self.trie_store
+    }
+
+    /// Returns an initial, empty root hash of the underlying trie.
+    pub fn empty_state_root_hash(&self) -> Digest {
+        self.empty_root_hash
+    }
+}
+
+impl StateReader<Key, StoredValue> for SledGlobalStateView {
+    type Error = error::Error;
+
+    fn read(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<Option<StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret = match read::<Key, StoredValue, SledReadTransaction, SledTrieStore, Self::Error>(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            key,
+        )? {
+            ReadResult::Found(value) => Some(value),
+            ReadResult::NotFound => None,
+            ReadResult::RootNotFound => panic!("SledGlobalState has invalid root"),
+        };
+        txn.commit()?;
+        Ok(ret)
+    }
+
+    fn read_with_proof(
+        &self,
+        correlation_id: CorrelationId,
+        key: &Key,
+    ) -> Result<Option<TrieMerkleProof<Key, StoredValue>>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret = match read_with_proof::<
+            Key,
+            StoredValue,
+            SledReadTransaction,
+            SledTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            key,
+        )? {
+            ReadResult::Found(value) => Some(value),
+            ReadResult::NotFound => None,
+            ReadResult::RootNotFound => panic!("SledGlobalState has invalid root"),
+        };
+        txn.commit()?;
+        Ok(ret)
+    }
+
+    fn keys_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+    ) -> Result<Vec<Key>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let keys_iter = keys_with_prefix::<Key, StoredValue, _, _>(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+        );
+        let mut ret = Vec::new();
+        for result in keys_iter {
+            match result {
+                Ok(key) => ret.push(key),
+                Err(error) => return Err(error),
+            }
+        }
+        txn.commit()?;
+        Ok(ret)
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = leaves_with_prefix::<
+            Key,
+            StoredValue,
+            SledReadTransaction,
+            SledTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
+}
+
+impl CommitProvider for SledGlobalState {
+    fn commit(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Digest,
+        effects: AdditiveMap<Key, Transform>,
+    ) -> Result<Digest, Self::Error> {
+        commit::<SledEnvironment, SledTrieStore, _, Self::Error>(
+            &self.environment,
+            &self.trie_store,
+            correlation_id,
+            prestate_hash,
+            effects,
+        )
+    }
+}
+
+impl StateProvider for SledGlobalState {
+    type Error = error::Error;
+
+    type Reader = SledGlobalStateView;
+
+    fn checkout(&self, state_hash: Digest) -> Result<Option<Self::Reader>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_root: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, &state_hash)?;
+        let maybe_state = maybe_root.map(|_| SledGlobalStateView {
+            environment: Arc::clone(&self.environment),
+            store: Arc::clone(&self.trie_store),
+            root_hash: state_hash,
+        });
+        txn.commit()?;
+        Ok(maybe_state)
+    }
+
+    fn empty_root(&self) -> Digest {
+        self.empty_root_hash
+    }
+
+    fn get_trie_full(
+        &self,
+        _correlation_id: CorrelationId,
+        trie_key: &Digest,
+    ) -> Result<Option<TrieRaw>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let ret: Option<TrieRaw> =
+            Store::<Digest, Trie<Digest, StoredValue>>::get_raw(&*self.trie_store, &txn, trie_key)?
+                .map(TrieRaw::new);
+        txn.commit()?;
+        Ok(ret)
+    }
+
+    fn put_trie(&self, correlation_id: CorrelationId, trie: &[u8]) -> Result<Digest, Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        let trie_hash = put_trie::<
+            Key,
+            StoredValue,
+            SledReadWriteTransaction,
+            SledTrieStore,
+            Self::Error,
+        >(correlation_id, &mut txn, &self.trie_store, trie)?;
+        txn.commit()?;
+        Ok(trie_hash)
+    }
+
+    /// Finds all of the keys of missing directly descendant `Trie<K,V>` values.
+    fn missing_children(
+        &self,
+        correlation_id: CorrelationId,
+        trie_raw: &[u8],
+    ) -> Result<Vec<Digest>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let missing_hashes = missing_children::<
+            Key,
+            StoredValue,
+            SledReadTransaction,
+            SledTrieStore,
+            Self::Error,
+        >(correlation_id, &txn, self.trie_store.deref(), trie_raw)?;
+        txn.commit()?;
+        Ok(missing_hashes)
+    }
+
+    /// Delete keys.
+    fn delete_keys(
+        &self,
+        correlation_id: CorrelationId,
+        mut state_root_hash: Digest,
+        keys: &[Key],
+    ) -> Result<DeleteResult, Self::Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        for key in keys {
+            let delete_result = delete::<Key, StoredValue, _, _, Self::Error>(
+                correlation_id,
+                &mut txn,
+                self.trie_store.deref(),
+                &state_root_hash,
+                key,
+            );
+            match delete_result? {
+                DeleteResult::Deleted(root) => {
+                    state_root_hash = root;
+                }
+                other => return Ok(other),
+            }
+        }
+        txn.commit()?;
+        Ok(DeleteResult::Deleted(state_root_hash))
+    }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        before: Digest,
+        after: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = diff::<Key, StoredValue, SledReadTransaction, SledTrieStore, Self::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &before,
+            &after,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    fn leaves_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        page_start: Option<&Key>,
+        page_size: usize,
+    ) -> Result<LeavesResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let result = leaves_with_prefix::<
+            Key,
+            StoredValue,
+            SledReadTransaction,
+            SledTrieStore,
+            Self::Error,
+        >(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &state_root_hash,
+            prefix,
+            page_start,
+            page_size,
+        )?;
+        txn.commit()?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::{tempdir, TempDir};
+
+    use casper_hashing::Digest;
+    use casper_types::{account::AccountHash, CLValue};
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct TestPair {
+        key: Key,
+        value: StoredValue,
+    }
+
+    fn create_test_pairs() -> [TestPair; 2] {
+        [
+            TestPair {
+                key: Key::Account(AccountHash::new([1_u8; 32])),
+                value: StoredValue::CLValue(CLValue::from_t(1_i32).unwrap()),
+            },
+            TestPair {
+                key: Key::Account(AccountHash::new([2_u8; 32])),
+                value: StoredValue::CLValue(CLValue::from_t(2_i32).unwrap()),
+            },
+        ]
+    }
+
+    fn create_test_pairs_updated() -> [TestPair; 3] {
+        [
+            TestPair {
+                key: Key::Account(AccountHash::new([1u8; 32])),
+                value: StoredValue::CLValue(CLValue::from_t("one".to_string()).unwrap()),
+            },
+            TestPair {
+                key: Key::Account(AccountHash::new([2u8; 32])),
+                value: StoredValue::CLValue(CLValue::from_t("two".to_string()).unwrap()),
+            },
+            TestPair {
+                key: Key::Account(AccountHash::new([3u8; 32])),
+                value: StoredValue::CLValue(CLValue::from_t(3_i32).unwrap()),
+            },
+        ]
+    }
+
+    fn create_test_state() -> (TempDir, SledGlobalState, Digest) {
+        let correlation_id = CorrelationId::new();
+        let temp_dir = tempdir().unwrap();
+        let environment = Arc::new(SledEnvironment::new(temp_dir.path(), true).unwrap());
+        let trie_store = Arc::new(SledTrieStore::new(&environment, None).unwrap());
+
+        let state = SledGlobalState::empty(environment, trie_store).unwrap();
+        let stored_values = create_test_pairs()
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, value))
+            .collect();
+        let root_hash = state
+            .put_stored_values(correlation_id, state.empty_root_hash, stored_values)
+            .unwrap();
+        (temp_dir, state, root_hash)
+    }
+
+    #[test]
+    fn reads_from_a_checkout_return_expected_values() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state, root_hash) = create_test_state();
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+    }
+
+    #[test]
+    fn checkout_fails_if_unknown_hash_is_given() {
+        let (_temp_dir, state, _) = create_test_state();
+        let fake_hash: Digest = Digest::hash([1u8; 32]);
+        let result = state.checkout(fake_hash).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn commit_updates_state_and_original_state_stays_intact() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (_temp_dir, state, root_hash) = create_test_state();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+
+        let original_checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(
+                Some(value),
+                original_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+        assert_eq!(
+            None,
+            original_checkout
+                .read(correlation_id, &test_pairs_updated[2].key)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn state_survives_reopening_the_environment() {
+        let correlation_id = CorrelationId::new();
+        let (temp_dir, state, root_hash) = create_test_state();
+        let empty_root_hash = state.empty_root_hash;
+        state.environment.sync().unwrap();
+        drop(state);
+
+        let environment = Arc::new(SledEnvironment::new(temp_dir.path(), true).unwrap());
+        let trie_store = Arc::new(SledTrieStore::new(&environment, None).unwrap());
+        let state = SledGlobalState::new(environment, trie_store, empty_root_hash);
+
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs().iter().cloned() {
+            assert_eq!(Some(value), checkout.read(correlation_id, &key).unwrap());
+        }
+    }
+}
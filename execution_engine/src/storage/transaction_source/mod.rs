@@ -4,6 +4,8 @@ use casper_types::bytesrepr::Bytes;
 pub mod in_memory;
 /// LMDB implementation of transaction source.
 pub mod lmdb;
+/// Sled implementation of transaction source.
+pub mod sled;
 
 /// A transaction which can be committed or aborted.
 pub trait Transaction: Sized {
@@ -0,0 +1,254 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use casper_types::bytesrepr::{self, Bytes};
+use sled::{
+    transaction::{TransactionError, TransactionResult, Transactional},
+    Batch, Db, IVec, Tree,
+};
+
+use crate::storage::{
+    error,
+    transaction_source::{Readable, Transaction, TransactionSource, Writable},
+};
+
+/// Directory name for the sled database created by the EE.
+const EE_DB_DIRNAME: &str = "data.sled";
+
+/// Key in the default tree under which the generation of the last committed write is stored.
+const GENERATION_KEY: &[u8] = b"generation";
+
+/// Interval between background flushes when manual sync is disabled.
+const FLUSH_EVERY_MS: u64 = 500;
+
+/// Length of the generation prefix of every stored value.
+const GENERATION_LENGTH: usize = 8;
+
+/// A marker for use in a mutex which represents the capability to perform a
+/// write transaction.
+struct WriteCapability;
+
+type WriteLock<'a> = MutexGuard<'a, WriteCapability>;
+
+/// Reads the value stored under `key` in `tree`, ignoring values committed after `generation`.
+///
+/// Every value is prefixed with the generation of the read-write transaction which committed it,
+/// which is how read transactions are kept isolated from writes committed after they began.
+fn read_committed(tree: &Tree, key: &[u8], generation: u64) -> Result<Option<Bytes>, error::Error> {
+    let stored = match tree.get(key)? {
+        Some(stored) => stored,
+        None => return Ok(None),
+    };
+    if stored.len() < GENERATION_LENGTH {
+        return Err(bytesrepr::Error::EarlyEndOfStream.into());
+    }
+    let (prefix, value) = stored.split_at(GENERATION_LENGTH);
+    // Unwrap is safe as the prefix is exactly `GENERATION_LENGTH` bytes long.
+    let stored_generation = u64::from_be_bytes(prefix.try_into().unwrap());
+    if stored_generation > generation {
+        return Ok(None);
+    }
+    Ok(Some(Bytes::from(value)))
+}
+
+/// A read transaction for the sled-backed trie store.
+///
+/// Only observes writes committed before the transaction was created.
+pub struct SledReadTransaction {
+    generation: u64,
+}
+
+impl Transaction for SledReadTransaction {
+    type Error = error::Error;
+
+    type Handle = Tree;
+
+    fn commit(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl Readable for SledReadTransaction {
+    fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Bytes>, Self::Error> {
+        read_committed(&handle, key, self.generation)
+    }
+}
+
+/// A read-write transaction for the sled-backed trie store.
+///
+/// Writes are buffered in memory and applied atomically across all trees on commit.
+pub struct SledReadWriteTransaction<'a> {
+    env: &'a SledEnvironment,
+    pending: HashMap<IVec, (Tree, BTreeMap<Bytes, Bytes>)>,
+    _write_lock: WriteLock<'a>,
+}
+
+impl<'a> Transaction for SledReadWriteTransaction<'a> {
+    type Error = error::Error;
+
+    type Handle = Tree;
+
+    fn commit(self) -> Result<(), Self::Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let generation = self.env.generation.load(Ordering::SeqCst) + 1;
+        let generation_bytes = generation.to_be_bytes();
+
+        let mut trees = vec![Tree::clone(&self.env.db)];
+        let mut batches = Vec::with_capacity(self.pending.len());
+        for (tree, values) in self.pending.into_values() {
+            let mut batch = Batch::default();
+            for (key, value) in values {
+                // Rewriting an unchanged value would bump its generation and hide it from
+                // readers which began before this commit.
+                if read_committed(&tree, key.as_ref(), u64::MAX)?.as_ref() == Some(&value) {
+                    continue;
+                }
+                let mut stored = Vec::with_capacity(GENERATION_LENGTH + value.len());
+                stored.extend_from_slice(&generation_bytes);
+                stored.extend_from_slice(value.as_ref());
+                batch.insert(key.as_ref(), stored);
+            }
+            trees.push(tree);
+            batches.push(batch);
+        }
+
+        let result: TransactionResult<(), error::Error> = trees.as_slice().transaction(|views| {
+            views[0].insert(GENERATION_KEY, &generation_bytes[..])?;
+            for (view, batch) in views[1..].iter().zip(batches.iter()) {
+                view.apply_batch(batch)?;
+            }
+            Ok(())
+        });
+        match result {
+            Ok(()) => {}
+            Err(TransactionError::Abort(error)) => return Err(error),
+            Err(TransactionError::Storage(error)) => return Err(error.into()),
+        }
+
+        // Only publish the new generation once all of its values are in place, so that readers
+        // never observe a partially applied commit.
+        self.env.generation.store(generation, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl<'a> Readable for SledReadWriteTransaction<'a> {
+    fn read(&self, handle: Self::Handle, key: &[u8]) -> Result<Option<Bytes>, Self::Error> {
+        if let Some((_, values)) = self.pending.get(&handle.name()) {
+            if let Some(value) = values.get(&Bytes::from(key)) {
+                return Ok(Some(value.clone()));
+            }
+        }
+        // Holding the write lock guarantees every committed generation is visible.
+        read_committed(&handle, key, u64::MAX)
+    }
+}
+
+impl<'a> Writable for SledReadWriteTransaction<'a> {
+    fn write(&mut self, handle: Self::Handle, key: &[u8], value: &[u8]) -> Result<(), Self::Error> {
+        let (_, values) = self
+            .pending
+            .entry(handle.name())
+            .or_insert_with(|| (handle, BTreeMap::new()));
+        values.insert(Bytes::from(key), Bytes::from(value));
+        Ok(())
+    }
+}
+
+/// The environment for a sled-backed trie store.
+///
+/// Wraps [`sled::Db`]. Unlike LMDB, the database grows on demand and does not require a maximum
+/// size to be configured up front.
+pub struct SledEnvironment {
+    db: Db,
+    generation: AtomicU64,
+    write_mutex: Mutex<WriteCapability>,
+    manual_sync_enabled: bool,
+}
+
+impl SledEnvironment {
+    /// Constructor for `SledEnvironment`.
+    pub fn new<P: AsRef<Path>>(path: P, manual_sync_enabled: bool) -> Result<Self, error::Error> {
+        let flush_every_ms = if manual_sync_enabled {
+            None
+        } else {
+            Some(FLUSH_EVERY_MS)
+        };
+        let db = sled::Config::new()
+            .path(path.as_ref().join(EE_DB_DIRNAME))
+            .flush_every_ms(flush_every_ms)
+            .open()?;
+        let generation = match db.get(GENERATION_KEY)? {
+            Some(bytes) => {
+                let bytes: [u8; GENERATION_LENGTH] = bytes
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| bytesrepr::Error::Formatting)?;
+                u64::from_be_bytes(bytes)
+            }
+            None => 0,
+        };
+        Ok(SledEnvironment {
+            db,
+            generation: AtomicU64::new(generation),
+            write_mutex: Mutex::new(WriteCapability),
+            manual_sync_enabled,
+        })
+    }
+
+    /// Returns a reference to the wrapped `Db`.
+    pub fn db(&self) -> &Db {
+        &self.db
+    }
+
+    /// Opens the named tree, creating it if it doesn't exist yet.
+    pub fn open_tree(&self, name: &str) -> Result<Tree, error::Error> {
+        Ok(self.db.open_tree(name)?)
+    }
+
+    /// Returns if this environment was constructed with manual synchronization enabled.
+    pub fn is_manual_sync_enabled(&self) -> bool {
+        self.manual_sync_enabled
+    }
+
+    /// Manually synchronize sled to disk.
+    pub fn sync(&self) -> Result<(), sled::Error> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+impl<'a> TransactionSource<'a> for SledEnvironment {
+    type Error = error::Error;
+
+    type Handle = Tree;
+
+    type ReadTransaction = SledReadTransaction;
+
+    type ReadWriteTransaction = SledReadWriteTransaction<'a>;
+
+    fn create_read_txn(&'a self) -> Result<SledReadTransaction, Self::Error> {
+        Ok(SledReadTransaction {
+            generation: self.generation.load(Ordering::SeqCst),
+        })
+    }
+
+    fn create_read_write_txn(&'a self) -> Result<SledReadWriteTransaction<'a>, Self::Error> {
+        let _write_lock = self.write_mutex.lock()?;
+        Ok(SledReadWriteTransaction {
+            env: self,
+            pending: HashMap::new(),
+            _write_lock,
+        })
+    }
+}
@@ -0,0 +1,468 @@
+//! Mark-and-sweep compaction of an LMDB-backed trie store.
+//!
+//! Tries are never deleted when global state is updated or pruned, as they may still be
+//! referenced by the state under other roots.  Compaction deletes every trie which is not
+//! reachable from a chosen set of retained state roots:
+//!
+//! * the mark phase walks the trie under each retained root, recording every trie reached
+//! * the sweep phase iterates the whole store in key order, deleting every unmarked trie.  It is
+//!   split into batches, each run in its own read-write transaction, so compaction can be
+//!   interleaved with other writes to the store.
+//!
+//! The compactor only knows about tries which were in the store when they were marked.  While a
+//! compaction is in progress, any tries written to the store must be marked (e.g. by marking the
+//! root of every newly committed state) before the next sweep batch runs, as a newly written trie
+//! may reference an unmarked trie which would otherwise be deleted.
+//!
+//! The marked tries are held in memory, taking roughly 40 to 80 bytes per trie depending on the
+//! occupancy of the underlying hash set, i.e. a few GB for a store with tens of millions of
+//! reachable tries.  The memory used can be bounded via [`TrieCompactor::with_max_marked_count`],
+//! beyond which marking fails and the compaction must be abandoned.
+use std::{collections::HashSet, convert::TryFrom};
+
+use lmdb::{Cursor, Transaction as _};
+
+use casper_hashing::Digest;
+use casper_types::{Key, StoredValue};
+
+use crate::storage::{
+    error,
+    store::Store,
+    transaction_source::{lmdb::LmdbEnvironment, TransactionSource},
+    trie::{self, LazyTrieLeaf, Trie},
+    trie_store::lmdb::LmdbTrieStore,
+};
+
+/// The outcome of marking the tries reachable from a set of state roots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkSummary {
+    /// The number of tries newly marked.
+    pub marked_count: u64,
+    /// Tries which are referenced by the retained roots but are not in the store, including any
+    /// retained roots themselves.
+    pub missing: Vec<Digest>,
+}
+
+/// The outcome of a single sweep batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepSummary {
+    /// The number of tries examined in this batch.
+    pub examined_count: u64,
+    /// The number of unmarked tries deleted in this batch.
+    pub deleted_count: u64,
+    /// Whether the sweep has reached the end of the store.
+    pub is_finished: bool,
+}
+
+/// A single mark-and-sweep compaction of an LMDB-backed trie store.
+#[derive(Debug, Default)]
+pub struct TrieCompactor {
+    marked: HashSet<Digest>,
+    /// The maximum number of tries which may be marked, if bounded.
+    max_marked_count: Option<u64>,
+    /// The key of the last entry examined by the sweep, if any.
+    resume_after: Option<Vec<u8>>,
+    is_finished: bool,
+    deleted_count: u64,
+}
+
+impl TrieCompactor {
+    /// Creates a new compactor with no tries marked.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new compactor with no tries marked, which fails to mark more than
+    /// `max_marked_count` tries in total.
+    pub fn with_max_marked_count(max_marked_count: u64) -> Self {
+        TrieCompactor {
+            max_marked_count: Some(max_marked_count),
+            ..Self::default()
+        }
+    }
+
+    /// Marks every trie reachable from `roots`, so that they are retained by the sweep.
+    ///
+    /// Tries which are already marked are not visited again, so marking a root which shares most
+    /// of its tries with already marked roots is cheap.
+    ///
+    /// Returns [`error::Error::CompactionMarkLimitExceeded`] if this would mark more tries than
+    /// the compactor's limit, in which case the marked tries are incomplete and the compactor must
+    /// not be used to sweep.
+    pub fn mark(
+        &mut self,
+        environment: &LmdbEnvironment,
+        trie_store: &LmdbTrieStore,
+        roots: &[Digest],
+    ) -> Result<MarkSummary, error::Error> {
+        let txn = environment.create_read_txn()?;
+        let mut summary = MarkSummary::default();
+        let mut tries_to_visit = roots.to_vec();
+        while let Some(trie_hash) = tries_to_visit.pop() {
+            if self.marked.contains(&trie_hash) {
+                continue;
+            }
+            let trie_bytes = match Store::<Digest, Trie<Key, StoredValue>>::get_raw(
+                trie_store, &txn, &trie_hash,
+            )? {
+                Some(trie_bytes) => trie_bytes,
+                None => {
+                    summary.missing.push(trie_hash);
+                    continue;
+                }
+            };
+            if let Some(max_marked_count) = self.max_marked_count {
+                if self.marked.len() as u64 >= max_marked_count {
+                    return Err(error::Error::CompactionMarkLimitExceeded(max_marked_count));
+                }
+            }
+            let lazy_trie: LazyTrieLeaf<Key, StoredValue> =
+                trie::lazy_trie_deserialize(trie_bytes)?;
+            tries_to_visit.extend(
+                trie::lazy_trie_iter_children(&lazy_trie)
+                    .filter(|child| !self.marked.contains(child)),
+            );
+            self.marked.insert(trie_hash);
+            summary.marked_count += 1;
+        }
+        txn.commit()?;
+        Ok(summary)
+    }
+
+    /// Examines up to `batch_size` tries, continuing from where the previous batch finished, and
+    /// deletes those which are not marked.
+    ///
+    /// The batch is run in a single read-write transaction.  `batch_size` must be non-zero.
+    pub fn sweep(
+        &mut self,
+        environment: &LmdbEnvironment,
+        trie_store: &LmdbTrieStore,
+        batch_size: usize,
+    ) -> Result<SweepSummary, error::Error> {
+        if self.is_finished {
+            return Ok(SweepSummary {
+                is_finished: true,
+                ..SweepSummary::default()
+            });
+        }
+
+        let db = trie_store.get_db();
+        let mut txn = environment.create_read_write_txn()?;
+        let mut examined_count = 0;
+        let mut last_examined = None;
+        let mut tries_to_delete = vec![];
+        {
+            let mut cursor = txn.open_ro_cursor(db)?;
+            let iter = match &self.resume_after {
+                Some(resume_after) => cursor.iter_from(resume_after),
+                None => cursor.iter_start(),
+            };
+            for entry in iter {
+                if examined_count == batch_size {
+                    break;
+                }
+                let (key, _) = entry?;
+                // The previous batch's last entry is still present if it was retained.
+                if self.resume_after.as_deref() == Some(key) {
+                    continue;
+                }
+                examined_count += 1;
+                last_examined = Some(key.to_vec());
+                // Every key in the trie store is a trie hash, but anything else is left alone.
+                if let Ok(trie_hash) = Digest::try_from(key) {
+                    if !self.marked.contains(&trie_hash) {
+                        tries_to_delete.push(trie_hash);
+                    }
+                }
+            }
+        }
+        for trie_hash in &tries_to_delete {
+            txn.del(db, trie_hash, None)?;
+        }
+        txn.commit()?;
+
+        let deleted_count = tries_to_delete.len() as u64;
+        self.deleted_count += deleted_count;
+        if last_examined.is_some() {
+            self.resume_after = last_examined;
+        }
+        self.is_finished = examined_count < batch_size;
+        Ok(SweepSummary {
+            examined_count: examined_count as u64,
+            deleted_count,
+            is_finished: self.is_finished,
+        })
+    }
+
+    /// Returns the number of tries marked so far.
+    pub fn marked_count(&self) -> u64 {
+        self.marked.len() as u64
+    }
+
+    /// Returns the total number of tries deleted by the sweep so far.
+    pub fn deleted_count(&self) -> u64 {
+        self.deleted_count
+    }
+
+    /// Returns whether the sweep has reached the end of the store.
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+}
+
+/// Deletes every trie in the store which is not reachable from `retained_roots`, sweeping in
+/// batches of `batch_size` tries.
+///
+/// If `max_marked_count` is given, nothing is deleted if more tries than that are reachable.
+///
+/// Nothing else may write to the store until this returns.
+pub fn compact(
+    environment: &LmdbEnvironment,
+    trie_store: &LmdbTrieStore,
+    retained_roots: &[Digest],
+    batch_size: usize,
+    max_marked_count: Option<u64>,
+) -> Result<(MarkSummary, u64), error::Error> {
+    let mut compactor = match max_marked_count {
+        Some(max_marked_count) => TrieCompactor::with_max_marked_count(max_marked_count),
+        None => TrieCompactor::new(),
+    };
+    let mark_summary = compactor.mark(environment, trie_store, retained_roots)?;
+    while !compactor
+        .sweep(environment, trie_store, batch_size)?
+        .is_finished
+    {}
+    Ok((mark_summary, compactor.deleted_count()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use lmdb::DatabaseFlags;
+    use tempfile::{tempdir, TempDir};
+
+    use casper_types::{account::AccountHash, CLValue};
+
+    use super::*;
+    use crate::{
+        shared::newtypes::CorrelationId,
+        storage::{
+            global_state::{lmdb::LmdbGlobalState, StateProvider, StateReader},
+            DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+        },
+    };
+
+    fn create_test_state() -> (TempDir, LmdbGlobalState) {
+        let temp_dir = tempdir().unwrap();
+        let environment = Arc::new(
+            LmdbEnvironment::new(
+                temp_dir.path(),
+                DEFAULT_TEST_MAX_DB_SIZE,
+                DEFAULT_TEST_MAX_READERS,
+                true,
+            )
+            .unwrap(),
+        );
+        let trie_store =
+            Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        let state = LmdbGlobalState::empty(environment, trie_store).unwrap();
+        (temp_dir, state)
+    }
+
+    fn create_values(first: u8, count: u8, value: i32) -> HashMap<Key, StoredValue> {
+        (first..first + count)
+            .map(|index| {
+                (
+                    Key::Account(AccountHash::new([index; 32])),
+                    StoredValue::CLValue(CLValue::from_t(value).unwrap()),
+                )
+            })
+            .collect()
+    }
+
+    fn put(
+        state: &LmdbGlobalState,
+        prestate_hash: Digest,
+        values: &HashMap<Key, StoredValue>,
+    ) -> Digest {
+        state
+            .put_stored_values(CorrelationId::new(), prestate_hash, values.clone())
+            .unwrap()
+    }
+
+    fn trie_count(state: &LmdbGlobalState) -> usize {
+        let txn = state.environment().create_read_txn().unwrap();
+        let mut cursor = txn.open_ro_cursor(state.trie_store().get_db()).unwrap();
+        cursor.iter_start().count()
+    }
+
+    fn assert_values(state: &LmdbGlobalState, root: Digest, values: &HashMap<Key, StoredValue>) {
+        let view = state.checkout(root).unwrap().unwrap();
+        for (key, value) in values {
+            assert_eq!(
+                view.read(CorrelationId::new(), key).unwrap().as_ref(),
+                Some(value)
+            );
+        }
+    }
+
+    #[test]
+    fn should_delete_tries_unreachable_from_retained_roots() {
+        let (_temp_dir, state) = create_test_state();
+        let old_values = create_values(0, 50, 1);
+        let old_root = put(&state, state.empty_state_root_hash(), &old_values);
+        let new_values = create_values(0, 50, 2);
+        let new_root = put(&state, old_root, &new_values);
+        let tries_before = trie_count(&state);
+
+        let (mark_summary, deleted_count) = compact(
+            state.environment(),
+            state.trie_store(),
+            &[new_root],
+            7,
+            None,
+        )
+        .unwrap();
+
+        assert!(mark_summary.missing.is_empty());
+        assert!(deleted_count > 0);
+        assert_eq!(trie_count(&state), tries_before - deleted_count as usize);
+        assert_eq!(trie_count(&state), mark_summary.marked_count as usize);
+        assert_values(&state, new_root, &new_values);
+        assert!(state
+            .get_trie_full(CorrelationId::new(), &old_root)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn should_retain_all_marked_roots() {
+        let (_temp_dir, state) = create_test_state();
+        let first_values = create_values(0, 20, 1);
+        let first_root = put(&state, state.empty_state_root_hash(), &first_values);
+        let second_values = create_values(0, 20, 2);
+        let second_root = put(&state, first_root, &second_values);
+        let tries_before = trie_count(&state);
+
+        let (mark_summary, deleted_count) = compact(
+            state.environment(),
+            state.trie_store(),
+            &[first_root, second_root],
+            1000,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mark_summary.marked_count as usize,
+            tries_before - deleted_count as usize
+        );
+        assert_values(&state, first_root, &first_values);
+        assert_values(&state, second_root, &second_values);
+    }
+
+    #[test]
+    fn should_report_missing_roots() {
+        let (_temp_dir, state) = create_test_state();
+        let root = put(
+            &state,
+            state.empty_state_root_hash(),
+            &create_values(0, 5, 1),
+        );
+        let unknown_root = Digest::hash([1]);
+
+        let mut compactor = TrieCompactor::new();
+        let summary = compactor
+            .mark(
+                state.environment(),
+                state.trie_store(),
+                &[root, unknown_root],
+            )
+            .unwrap();
+        assert_eq!(summary.missing, vec![unknown_root]);
+        assert_eq!(compactor.marked_count(), summary.marked_count);
+    }
+
+    #[test]
+    fn should_fail_to_mark_more_than_max_marked_count() {
+        let (_temp_dir, state) = create_test_state();
+        let root = put(
+            &state,
+            state.empty_state_root_hash(),
+            &create_values(0, 20, 1),
+        );
+        let reachable_count = TrieCompactor::new()
+            .mark(state.environment(), state.trie_store(), &[root])
+            .unwrap()
+            .marked_count;
+        let tries_before = trie_count(&state);
+
+        let result = compact(
+            state.environment(),
+            state.trie_store(),
+            &[root],
+            1000,
+            Some(reachable_count - 1),
+        );
+        assert_eq!(
+            result,
+            Err(error::Error::CompactionMarkLimitExceeded(
+                reachable_count - 1
+            ))
+        );
+        assert_eq!(trie_count(&state), tries_before);
+
+        let (mark_summary, _) = compact(
+            state.environment(),
+            state.trie_store(),
+            &[root],
+            1000,
+            Some(reachable_count),
+        )
+        .unwrap();
+        assert_eq!(mark_summary.marked_count, reachable_count);
+        assert_values(&state, root, &create_values(0, 20, 1));
+    }
+
+    #[test]
+    fn should_retain_tries_marked_between_sweep_batches() {
+        let (_temp_dir, state) = create_test_state();
+        let old_root = put(
+            &state,
+            state.empty_state_root_hash(),
+            &create_values(0, 30, 1),
+        );
+        let retained_values = create_values(0, 30, 2);
+        let retained_root = put(&state, old_root, &retained_values);
+
+        let mut compactor = TrieCompactor::new();
+        compactor
+            .mark(state.environment(), state.trie_store(), &[retained_root])
+            .unwrap();
+        let first_batch = compactor
+            .sweep(state.environment(), state.trie_store(), 5)
+            .unwrap();
+        assert_eq!(first_batch.examined_count, 5);
+        assert!(!first_batch.is_finished);
+
+        // Commit a new state on top of the retained one, which reuses the retained tries and
+        // restores some of the values of the old state.
+        let mut new_values = retained_values;
+        new_values.extend(create_values(0, 10, 1));
+        new_values.extend(create_values(100, 10, 3));
+        let new_root = put(&state, retained_root, &new_values);
+        compactor
+            .mark(state.environment(), state.trie_store(), &[new_root])
+            .unwrap();
+
+        while !compactor
+            .sweep(state.environment(), state.trie_store(), 5)
+            .unwrap()
+            .is_finished
+        {}
+        assert!(compactor.is_finished());
+        assert_values(&state, new_root, &new_values);
+        assert_eq!(trie_count(&state) as u64, compactor.marked_count());
+    }
+}
@@ -3,7 +3,13 @@ mod tests;
 
 #[cfg(test)]
 use std::collections::HashSet;
-use std::{borrow::Cow, cmp, collections::VecDeque, convert::TryInto, mem};
+use std::{
+    borrow::Cow,
+    cmp,
+    collections::{BTreeMap, VecDeque},
+    convert::TryInto,
+    mem,
+};
 
 use either::Either;
 use num_traits::FromPrimitive;
@@ -1220,6 +1226,396 @@ where
     keys_with_prefix(correlation_id, txn, store, root, &[])
 }
 
+/// A key whose value differs between two tries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieDiffEntry<K, V> {
+    /// The key.
+    pub key: K,
+    /// The value under the key in the first trie, or `None` if the key was added.
+    pub before: Option<V>,
+    /// The value under the key in the second trie, or `None` if the key was removed.
+    pub after: Option<V>,
+}
+
+/// The result of comparing two tries.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffResult<K, V> {
+    /// The root with the given hash was not found.
+    RootNotFound(Digest),
+    /// A trie with the given hash, reachable from one of the roots, was not found.
+    MissingTrie(Digest),
+    /// A page of the keys whose values differ.
+    Success {
+        /// The keys whose values differ, ordered by their serialized bytes.
+        entries: Vec<TrieDiffEntry<K, V>>,
+        /// The first key of the next page, if there are further keys whose values differ.
+        next_page_start: Option<K>,
+    },
+}
+
+/// A subtrie visited by [`diff`] or [`leaves_with_prefix`].
+#[derive(PartialEq, Eq)]
+enum Subtrie<K, V> {
+    /// A trie in the store.
+    Stored(Digest),
+    /// A trie which is not in the store under its own hash, e.g. a leaf moved down from a
+    /// shallower depth or the remainder of an extension.
+    Loaded(Trie<K, V>),
+}
+
+/// The children of a subtrie, each along with its index.
+type SubtrieChildren<K, V> = Vec<(u8, Subtrie<K, V>)>;
+
+/// Returns the children of `trie`, which is `depth` bytes deep, in ascending order of index.
+///
+/// A leaf is its own only child, under the index of its key's byte at `depth`.
+fn subtrie_children<K, V>(
+    trie: Trie<K, V>,
+    depth: usize,
+) -> Result<SubtrieChildren<K, V>, bytesrepr::Error>
+where
+    K: ToBytes,
+{
+    match trie {
+        Trie::Leaf { ref key, .. } => {
+            let index = key
+                .to_bytes()?
+                .get(depth)
+                .copied()
+                .ok_or(bytesrepr::Error::EarlyEndOfStream)?;
+            Ok(vec![(index, Subtrie::Loaded(trie))])
+        }
+        Trie::Node { pointer_block } => Ok(pointer_block
+            .as_indexed_pointers()
+            .map(|(index, pointer)| (index, Subtrie::Stored(pointer.into_hash())))
+            .collect()),
+        Trie::Extension { affix, pointer } => {
+            let (index, remainder) = affix
+                .as_slice()
+                .split_first()
+                .ok_or(bytesrepr::Error::Formatting)?;
+            let child = if remainder.is_empty() {
+                Subtrie::Stored(pointer.into_hash())
+            } else {
+                Subtrie::Loaded(Trie::extension(remainder.to_vec(), pointer))
+            };
+            Ok(vec![(*index, child)])
+        }
+    }
+}
+
+/// The serialized keys which belong in a page: those starting with `prefix`, from `page_start`
+/// onwards.
+struct PageBounds<'a> {
+    prefix: &'a [u8],
+    page_start: Option<Vec<u8>>,
+}
+
+impl<'a> PageBounds<'a> {
+    fn new<K: ToBytes>(prefix: &'a [u8], page_start: Option<&K>) -> Result<Self, bytesrepr::Error> {
+        Ok(PageBounds {
+            prefix,
+            page_start: page_start.map(ToBytes::to_bytes).transpose()?,
+        })
+    }
+
+    /// Returns `true` if the subtrie at `path` may hold keys belonging in the page.
+    fn may_contain(&self, path: &[u8]) -> bool {
+        if !path.starts_with(self.prefix) && !self.prefix.starts_with(path) {
+            return false;
+        }
+        match &self.page_start {
+            // Otherwise every key in the subtrie is before the page start.
+            Some(page_start) => path >= page_start.as_slice() || page_start.starts_with(path),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if the key serialized as `key_bytes` belongs in the page.
+    fn contains(&self, key_bytes: &[u8]) -> bool {
+        key_bytes.starts_with(self.prefix)
+            && self
+                .page_start
+                .as_ref()
+                .map_or(true, |page_start| key_bytes >= page_start.as_slice())
+    }
+}
+
+/// Collects a page of the keys whose values differ between two tries.
+struct DiffPage<'a, K, V> {
+    bounds: PageBounds<'a>,
+    page_size: usize,
+    entries: Vec<TrieDiffEntry<K, V>>,
+    next_page_start: Option<K>,
+}
+
+impl<'a, K, V> DiffPage<'a, K, V>
+where
+    K: ToBytes,
+{
+    fn is_full(&self) -> bool {
+        self.next_page_start.is_some()
+    }
+
+    fn push(&mut self, entry: TrieDiffEntry<K, V>) -> Result<(), bytesrepr::Error> {
+        if !self.bounds.contains(&entry.key.to_bytes()?) {
+            return Ok(());
+        }
+        if self.entries.len() < self.page_size {
+            self.entries.push(entry);
+        } else if self.next_page_start.is_none() {
+            self.next_page_start = Some(entry.key);
+        }
+        Ok(())
+    }
+
+    /// Adds the differences between two subtries, each being a leaf or empty.
+    fn push_leaves(
+        &mut self,
+        before: Option<(K, V)>,
+        after: Option<(K, V)>,
+    ) -> Result<(), bytesrepr::Error>
+    where
+        K: Eq,
+        V: Eq,
+    {
+        match (before, after) {
+            (Some((before_key, before_value)), Some((after_key, after_value))) => {
+                if before_key == after_key {
+                    if before_value != after_value {
+                        self.push(TrieDiffEntry {
+                            key: before_key,
+                            before: Some(before_value),
+                            after: Some(after_value),
+                        })?;
+                    }
+                    return Ok(());
+                }
+                let removed = TrieDiffEntry {
+                    key: before_key,
+                    before: Some(before_value),
+                    after: None,
+                };
+                let added = TrieDiffEntry {
+                    key: after_key,
+                    before: None,
+                    after: Some(after_value),
+                };
+                if removed.key.to_bytes()? < added.key.to_bytes()? {
+                    self.push(removed)?;
+                    self.push(added)
+                } else {
+                    self.push(added)?;
+                    self.push(removed)
+                }
+            }
+            (Some((key, value)), None) => self.push(TrieDiffEntry {
+                key,
+                before: Some(value),
+                after: None,
+            }),
+            (None, Some((key, value))) => self.push(TrieDiffEntry {
+                key,
+                before: None,
+                after: Some(value),
+            }),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Returns a page of the keys whose values differ between the tries under `before` and `after`,
+/// restricted to the keys whose serialized bytes start with `prefix`.
+///
+/// The page holds at most `page_size` keys, in ascending order of their serialized bytes, starting
+/// from `page_start` if given.  Subtries common to both tries are skipped without being read.
+#[allow(clippy::too_many_arguments)]
+pub fn diff<K, V, T, S, E>(
+    _correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    before: &Digest,
+    after: &Digest,
+    prefix: &[u8],
+    page_start: Option<&K>,
+    page_size: usize,
+) -> Result<DiffResult<K, V>, E>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let mut page = DiffPage {
+        bounds: PageBounds::new(prefix, page_start)?,
+        page_size,
+        entries: vec![],
+        next_page_start: None,
+    };
+
+    // The pairs of subtries still to be compared along with their path, popped in ascending order
+    // of path.
+    let mut pending = vec![(
+        vec![],
+        Some(Subtrie::Stored(*before)),
+        Some(Subtrie::Stored(*after)),
+    )];
+    while let Some((path, maybe_before, maybe_after)) = pending.pop() {
+        if page.is_full() {
+            break;
+        }
+        // The roots are always read, so that a missing root is reported even if both are the same.
+        if (!path.is_empty() && maybe_before == maybe_after) || !page.bounds.may_contain(&path) {
+            continue;
+        }
+
+        let mut tries = [None, None];
+        for (maybe_subtrie, trie) in [maybe_before, maybe_after].into_iter().zip(&mut tries) {
+            *trie = match maybe_subtrie {
+                None => None,
+                Some(Subtrie::Loaded(loaded)) => Some(loaded),
+                Some(Subtrie::Stored(hash)) => match store.get(txn, &hash)? {
+                    Some(stored) => Some(stored),
+                    None if path.is_empty() => return Ok(DiffResult::RootNotFound(hash)),
+                    None => {
+                        warn!(trie_hash = ?hash, "no trie value at key while diffing");
+                        return Ok(DiffResult::MissingTrie(hash));
+                    }
+                },
+            };
+        }
+
+        match tries {
+            [None | Some(Trie::Leaf { .. }), None | Some(Trie::Leaf { .. })] => {
+                let [before_leaf, after_leaf] = tries.map(|maybe_trie| match maybe_trie {
+                    Some(Trie::Leaf { key, value }) => Some((key, value)),
+                    _ => None,
+                });
+                page.push_leaves(before_leaf, after_leaf)?;
+            }
+            [maybe_before, maybe_after] => {
+                let depth = path.len();
+                let mut children: BTreeMap<u8, (Option<_>, Option<_>)> = BTreeMap::new();
+                if let Some(before) = maybe_before {
+                    for (index, child) in subtrie_children(before, depth)? {
+                        children.entry(index).or_default().0 = Some(child);
+                    }
+                }
+                if let Some(after) = maybe_after {
+                    for (index, child) in subtrie_children(after, depth)? {
+                        children.entry(index).or_default().1 = Some(child);
+                    }
+                }
+                for (index, (before_child, after_child)) in children.into_iter().rev() {
+                    let mut child_path = path.clone();
+                    child_path.push(index);
+                    pending.push((child_path, before_child, after_child));
+                }
+            }
+        }
+    }
+
+    Ok(DiffResult::Success {
+        entries: page.entries,
+        next_page_start: page.next_page_start,
+    })
+}
+
+/// The result of reading a page of the leaves of a trie.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LeavesResult<K, V> {
+    /// The root was not found.
+    RootNotFound,
+    /// A trie with the given hash, reachable from the root, was not found.
+    MissingTrie(Digest),
+    /// A page of the leaves.
+    Success {
+        /// The keys and values of the leaves, ordered by the serialized bytes of their keys.
+        leaves: Vec<(K, V)>,
+        /// The key of the first leaf of the next page, if there are further leaves.
+        next_page_start: Option<K>,
+    },
+}
+
+/// Returns a page of the leaves of the trie under `root` whose keys' serialized bytes start with
+/// `prefix`.
+///
+/// The page holds at most `page_size` leaves, in ascending order of their keys' serialized bytes,
+/// starting from the key `page_start` if given.  Unlike [`keys_with_prefix`], subtries before
+/// `page_start` are skipped without being read.
+#[allow(clippy::too_many_arguments)]
+pub fn leaves_with_prefix<K, V, T, S, E>(
+    _correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    root: &Digest,
+    prefix: &[u8],
+    page_start: Option<&K>,
+    page_size: usize,
+) -> Result<LeavesResult<K, V>, E>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let bounds = PageBounds::new(prefix, page_start)?;
+    let root_trie = match store.get(txn, root)? {
+        Some(root_trie) => root_trie,
+        None => return Ok(LeavesResult::RootNotFound),
+    };
+
+    let mut leaves = vec![];
+    // The subtries still to be read along with their path, popped in ascending order of path.
+    let mut pending = vec![(vec![], Subtrie::Loaded(root_trie))];
+    while let Some((path, subtrie)) = pending.pop() {
+        if !bounds.may_contain(&path) {
+            continue;
+        }
+        let trie = match subtrie {
+            Subtrie::Loaded(trie) => trie,
+            Subtrie::Stored(hash) => match store.get(txn, &hash)? {
+                Some(trie) => trie,
+                None => {
+                    warn!(trie_hash = ?hash, "no trie value at key while reading leaves");
+                    return Ok(LeavesResult::MissingTrie(hash));
+                }
+            },
+        };
+        match trie {
+            Trie::Leaf { key, value } => {
+                if !bounds.contains(&key.to_bytes()?) {
+                    continue;
+                }
+                if leaves.len() == page_size {
+                    return Ok(LeavesResult::Success {
+                        leaves,
+                        next_page_start: Some(key),
+                    });
+                }
+                leaves.push((key, value));
+            }
+            trie => {
+                let depth = path.len();
+                for (index, child) in subtrie_children(trie, depth)?.into_iter().rev() {
+                    let mut child_path = path.clone();
+                    child_path.push(index);
+                    pending.push((child_path, child));
+                }
+            }
+        }
+    }
+
+    Ok(LeavesResult::Success {
+        leaves,
+        next_page_start: None,
+    })
+}
+
 #[cfg(test)]
 pub fn check_integrity<K, V, T, S, E>(
     _correlation_id: CorrelationId,
@@ -0,0 +1,270 @@
+use super::*;
+use crate::storage::trie_store::operations::{DiffResult, TrieDiffEntry};
+
+type TestDiffEntry = TrieDiffEntry<TestKey, TestValue>;
+
+/// Writes `leaves` on top of `root`, returning the new root.
+fn write_all<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root: &Digest,
+    leaves: &[TestTrie],
+) -> Result<Digest, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let results = write_leaves::<_, _, _, _, E>(correlation_id, environment, store, root, leaves)?;
+    Ok(results
+        .into_iter()
+        .filter_map(|result| match result {
+            WriteResult::Written(root) => Some(root),
+            _ => None,
+        })
+        .last()
+        .unwrap_or(*root))
+}
+
+/// Computes the expected differences between two sets of leaves, each written in order.
+fn expected_diff(before: &[TestTrie], after: &[TestTrie], prefix: &[u8]) -> Vec<TestDiffEntry> {
+    let to_map = |leaves: &[TestTrie]| {
+        leaves
+            .iter()
+            .filter_map(|leaf| match leaf {
+                Trie::Leaf { key, value } => Some((*key, *value)),
+                _ => None,
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    let before = to_map(before);
+    let after = to_map(after);
+    let mut keys = before.keys().chain(after.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| key.0.starts_with(prefix))
+        .filter_map(|key| {
+            let entry = TrieDiffEntry {
+                key: *key,
+                before: before.get(key).copied(),
+                after: after.get(key).copied(),
+            };
+            (entry.before != entry.after).then_some(entry)
+        })
+        .collect()
+}
+
+/// Reads the differences between two roots one page at a time.
+fn diff_pages<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    before: &Digest,
+    after: &Digest,
+    prefix: &[u8],
+    page_size: usize,
+) -> Result<Vec<Vec<TestDiffEntry>>, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let txn = environment.create_read_txn()?;
+    let mut pages = vec![];
+    let mut page_start = None;
+    loop {
+        let result = operations::diff::<_, _, _, _, E>(
+            correlation_id,
+            &txn,
+            store,
+            before,
+            after,
+            prefix,
+            page_start.as_ref(),
+            page_size,
+        )?;
+        let (entries, next_page_start) = match result {
+            DiffResult::Success {
+                entries,
+                next_page_start,
+            } => (entries, next_page_start),
+            other => panic!("unexpected diff result {:?}", other),
+        };
+        assert!(entries.len() <= page_size);
+        pages.push(entries);
+        page_start = match next_page_start {
+            Some(next_page_start) => Some(next_page_start),
+            None => break,
+        };
+    }
+    txn.commit()?;
+    Ok(pages)
+}
+
+fn diff_had_expected_results<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    empty_root: &Digest,
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let before_leaves = TEST_LEAVES[..3].to_vec();
+    let mut after_leaves = before_leaves.clone();
+    after_leaves.extend_from_slice(&TEST_LEAVES_UPDATED[1..2]);
+    after_leaves.extend_from_slice(&TEST_LEAVES[3..]);
+    after_leaves.extend_from_slice(&TEST_LEAVES_ADJACENTS);
+
+    let before = write_all::<_, _, E>(
+        correlation_id,
+        environment,
+        store,
+        empty_root,
+        &before_leaves,
+    )?;
+    let after = write_all::<_, _, E>(
+        correlation_id,
+        environment,
+        store,
+        &before,
+        &after_leaves[before_leaves.len()..],
+    )?;
+
+    let prefixes: [&[u8]; 5] = [&[], &[0], &[0, 0], &[0, 0, 0, 0], &[1]];
+    for prefix in prefixes {
+        let expected = expected_diff(&before_leaves, &after_leaves, prefix);
+        let expected_reversed = expected
+            .iter()
+            .map(|entry| TrieDiffEntry {
+                key: entry.key,
+                before: entry.after,
+                after: entry.before,
+            })
+            .collect::<Vec<_>>();
+        for page_size in 1..=expected.len() + 1 {
+            let pages = diff_pages::<_, _, E>(
+                correlation_id,
+                environment,
+                store,
+                &before,
+                &after,
+                prefix,
+                page_size,
+            )?;
+            assert_eq!(pages.concat(), expected);
+            let pages = diff_pages::<_, _, E>(
+                correlation_id,
+                environment,
+                store,
+                &after,
+                &before,
+                prefix,
+                page_size,
+            )?;
+            assert_eq!(pages.concat(), expected_reversed);
+        }
+    }
+
+    let pages = diff_pages::<_, _, E>(correlation_id, environment, store, &after, &after, &[], 10)?;
+    assert_eq!(pages, vec![vec![]]);
+    Ok(())
+}
+
+fn diff_of_missing_root_is_root_not_found<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    empty_root: &Digest,
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let missing_root = Digest::hash(b"missing root");
+    let txn = environment.create_read_txn()?;
+    for (before, after) in [
+        (empty_root, &missing_root),
+        (&missing_root, empty_root),
+        (&missing_root, &missing_root),
+    ] {
+        let result = operations::diff::<TestKey, TestValue, _, _, E>(
+            correlation_id,
+            &txn,
+            store,
+            before,
+            after,
+            &[],
+            None,
+            10,
+        )?;
+        assert_eq!(result, DiffResult::RootNotFound(missing_root));
+    }
+    txn.commit()?;
+    Ok(())
+}
+
+#[test]
+fn lmdb_diff_had_expected_results() {
+    let correlation_id = CorrelationId::new();
+    let (empty_root, tries) = create_0_leaf_trie().unwrap();
+    let context = LmdbTestContext::new(&tries).unwrap();
+    diff_had_expected_results::<_, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &empty_root,
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_diff_had_expected_results() {
+    let correlation_id = CorrelationId::new();
+    let (empty_root, tries) = create_0_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    diff_had_expected_results::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &empty_root,
+    )
+    .unwrap();
+}
+
+#[test]
+fn lmdb_diff_of_missing_root_is_root_not_found() {
+    let correlation_id = CorrelationId::new();
+    let (empty_root, tries) = create_0_leaf_trie().unwrap();
+    let context = LmdbTestContext::new(&tries).unwrap();
+    diff_of_missing_root_is_root_not_found::<_, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &empty_root,
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_diff_of_missing_root_is_root_not_found() {
+    let correlation_id = CorrelationId::new();
+    let (empty_root, tries) = create_0_leaf_trie().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+    diff_of_missing_root_is_root_not_found::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &empty_root,
+    )
+    .unwrap();
+}
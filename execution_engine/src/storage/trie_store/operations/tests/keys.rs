@@ -314,3 +314,111 @@ mod keys_with_prefix_iterator {
         test_prefix(&[0, 0, 0, 0, 0, 0, 1]); // 1 leaf
     }
 }
+
+mod leaves_with_prefix {
+    use casper_hashing::Digest;
+
+    use crate::{
+        shared::newtypes::CorrelationId,
+        storage::{
+            error::in_memory,
+            transaction_source::{Transaction, TransactionSource},
+            trie::Trie,
+            trie_store::operations::{
+                self,
+                tests::{create_6_leaf_trie, InMemoryTestContext, TestKey, TestValue, TEST_LEAVES},
+                LeavesResult,
+            },
+        },
+    };
+
+    fn expected_leaves(prefix: &[u8]) -> Vec<(TestKey, TestValue)> {
+        let mut tmp = TEST_LEAVES
+            .iter()
+            .filter_map(|leaf| match leaf {
+                Trie::Leaf { key, value } if key.0.starts_with(prefix) => Some((*key, *value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        tmp.sort_by_key(|(key, _)| *key);
+        tmp
+    }
+
+    fn test_prefix(prefix: &[u8]) {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_6_leaf_trie().expect("should create a trie");
+        let context = InMemoryTestContext::new(&tries).expect("should create a new context");
+        let txn = context
+            .environment
+            .create_read_txn()
+            .expect("should create a read txn");
+        let expected = expected_leaves(prefix);
+        for page_size in 1..=expected.len() + 1 {
+            let mut actual = vec![];
+            let mut page_start = None;
+            loop {
+                let result = operations::leaves_with_prefix::<_, _, _, _, in_memory::Error>(
+                    correlation_id,
+                    &txn,
+                    &context.store,
+                    &root_hash,
+                    prefix,
+                    page_start.as_ref(),
+                    page_size,
+                )
+                .expect("should read leaves");
+                let (leaves, next_page_start) = match result {
+                    LeavesResult::Success {
+                        leaves,
+                        next_page_start,
+                    } => (leaves, next_page_start),
+                    other => panic!("unexpected result {:?}", other),
+                };
+                assert!(leaves.len() <= page_size);
+                actual.extend(leaves);
+                page_start = match next_page_start {
+                    Some(next_page_start) => Some(next_page_start),
+                    None => break,
+                };
+            }
+            assert_eq!(expected, actual);
+        }
+        txn.commit().expect("should commit");
+    }
+
+    #[test]
+    fn test_prefixes() {
+        test_prefix(&[]); // 6 leaves
+        test_prefix(&[0]); // 6 leaves
+        test_prefix(&[0, 1]); // 1 leaf
+        test_prefix(&[0, 1, 1]); // 0 leaves
+        test_prefix(&[0, 0]); // 5 leaves
+        test_prefix(&[0, 0, 2]); // 1 leaf
+        test_prefix(&[0, 0, 0, 0]); // 3 leaves, prefix points to an Extension
+        test_prefix(&[0, 0, 0, 0, 0, 0]); // 2 leaves
+        test_prefix(&[0, 0, 0, 0, 0, 0, 1]); // 1 leaf
+    }
+
+    #[test]
+    fn missing_root_is_root_not_found() {
+        let correlation_id = CorrelationId::new();
+        let (_, tries) = create_6_leaf_trie().expect("should create a trie");
+        let context = InMemoryTestContext::new(&tries).expect("should create a new context");
+        let txn = context
+            .environment
+            .create_read_txn()
+            .expect("should create a read txn");
+        let result = operations::leaves_with_prefix::<TestKey, TestValue, _, _, in_memory::Error>(
+            correlation_id,
+            &txn,
+            &context.store,
+            &Digest::hash(b"missing root"),
+            &[],
+            None,
+            10,
+        )
+        .expect("should read leaves");
+        assert_eq!(result, LeavesResult::RootNotFound);
+        txn.commit().expect("should commit");
+    }
+}
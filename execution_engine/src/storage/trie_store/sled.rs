@@ -0,0 +1,84 @@
+//! A sled-backed trie store.
+//!
+//! Sled is a log-structured embedded database which grows as required, so unlike the
+//! [lmdb](../lmdb/index.html) trie store it doesn't need a maximum size to be configured.
+//!
+//! # Usage
+//!
+//! ```
+//! use casper_execution_engine::storage::store::Store;
+//! use casper_execution_engine::storage::transaction_source::{Transaction, TransactionSource};
+//! use casper_execution_engine::storage::transaction_source::sled::SledEnvironment;
+//! use casper_execution_engine::storage::trie::Trie;
+//! use casper_execution_engine::storage::trie_store::sled::SledTrieStore;
+//! use casper_hashing::Digest;
+//! use casper_types::bytesrepr::{ToBytes, Bytes};
+//! use tempfile::tempdir;
+//!
+//! let leaf = Trie::Leaf { key: Bytes::from(vec![0u8, 0, 0]), value: Bytes::from(b"val".to_vec()) };
+//! let leaf_hash = Digest::hash(&leaf.to_bytes().unwrap());
+//!
+//! let tmp_dir = tempdir().unwrap();
+//! let env = SledEnvironment::new(tmp_dir.path(), true).unwrap();
+//! let store = SledTrieStore::new(&env, None).unwrap();
+//!
+//! {
+//!     let mut txn = env.create_read_write_txn().unwrap();
+//!     store.put(&mut txn, &leaf_hash, &leaf).unwrap();
+//!     txn.commit().unwrap();
+//! }
+//!
+//! {
+//!     let txn = env.create_read_txn().unwrap();
+//!     assert_eq!(Some(leaf), store.get(&txn, &leaf_hash).unwrap());
+//!     txn.commit().unwrap();
+//! }
+//!
+//! tmp_dir.close().unwrap();
+//! ```
+use casper_hashing::Digest;
+use sled::Tree;
+
+use crate::storage::{
+    error,
+    store::Store,
+    transaction_source::sled::SledEnvironment,
+    trie::Trie,
+    trie_store::{self, TrieStore},
+};
+
+/// A sled-backed trie store.
+///
+/// Wraps [`sled::Tree`].
+#[derive(Debug, Clone)]
+pub struct SledTrieStore {
+    tree: Tree,
+}
+
+impl SledTrieStore {
+    /// Constructor for `SledTrieStore`, which opens the store's tree or creates it if missing.
+    pub fn new(env: &SledEnvironment, maybe_name: Option<&str>) -> Result<Self, error::Error> {
+        let name = maybe_name
+            .map(|name| format!("{}-{}", trie_store::NAME, name))
+            .unwrap_or_else(|| String::from(trie_store::NAME));
+        let tree = env.open_tree(&name)?;
+        Ok(SledTrieStore { tree })
+    }
+
+    /// Get a handle to the underlying tree.
+    pub fn get_tree(&self) -> Tree {
+        self.tree.clone()
+    }
+}
+
+impl<K, V> Store<Digest, Trie<K, V>> for SledTrieStore {
+    type Error = error::Error;
+
+    type Handle = Tree;
+
+    fn handle(&self) -> Self::Handle {
+        self.tree.clone()
+    }
+}
+
+impl<K, V> TrieStore<K, V> for SledTrieStore {}
@@ -71,6 +71,23 @@ fn lmdb_roundtrip_succeeds(inputs: Vec<Trie<Key, StoredValue>>) -> bool {
     ret
 }
 
+fn sled_roundtrip_succeeds(inputs: Vec<Trie<Key, StoredValue>>) -> bool {
+    use crate::storage::{
+        transaction_source::sled::SledEnvironment, trie_store::sled::SledTrieStore,
+    };
+
+    let tmp_dir = tempdir().unwrap();
+    let env = SledEnvironment::new(tmp_dir.path(), true).unwrap();
+    let store = SledTrieStore::new(&env, None).unwrap();
+
+    let inputs: BTreeMap<Digest, Trie<Key, StoredValue>> = inputs
+        .into_iter()
+        .map(|trie| (Digest::hash(trie.to_bytes().unwrap()), trie))
+        .collect();
+
+    store_tests::roundtrip_succeeds(&env, &store, inputs).unwrap()
+}
+
 proptest! {
     #[test]
     fn prop_in_memory_roundtrip_succeeds_leaf(v in vec(trie_leaf_arb(), get_range())) {
@@ -101,4 +118,19 @@ proptest! {
     fn prop_lmdb_roundtrip_succeeds_extension(v in vec(trie_extension_arb(), get_range())) {
         assert!(lmdb_roundtrip_succeeds(v))
     }
+
+    #[test]
+    fn prop_sled_roundtrip_succeeds_leaf(v in vec(trie_leaf_arb(), get_range())) {
+        assert!(sled_roundtrip_succeeds(v))
+    }
+
+    #[test]
+    fn prop_sled_roundtrip_succeeds_node(v in vec(trie_node_arb(), get_range())) {
+        assert!(sled_roundtrip_succeeds(v))
+    }
+
+    #[test]
+    fn prop_sled_roundtrip_succeeds_extension(v in vec(trie_extension_arb(), get_range())) {
+        assert!(sled_roundtrip_succeeds(v))
+    }
 }
@@ -31,6 +31,7 @@ use casper_execution_engine::{
             DEFAULT_MAX_QUERY_DEPTH,
         },
         execution,
+        runtime::ExecutionTrace,
     },
     shared::{
         additive_map::AdditiveMap,
@@ -738,6 +739,29 @@ where
         Ok(self)
     }
 
+    /// Runs an [`ExecuteRequest`] with tracing enabled and returns the trace of each deploy.
+    ///
+    /// Results and transforms are cached as for [`WasmTestBuilder::exec`].
+    pub fn exec_with_trace(&mut self, mut exec_request: ExecuteRequest) -> Vec<ExecutionTrace> {
+        let hash = self.post_state_hash.expect("expected post_state_hash");
+        exec_request.parent_state_hash = hash;
+
+        let traced_results = self
+            .engine_state
+            .run_execute_with_trace(CorrelationId::new(), exec_request)
+            .expect("should execute");
+        let mut execution_results = Vec::with_capacity(traced_results.len());
+        let mut traces = Vec::with_capacity(traced_results.len());
+        for (execution_result, _phase_costs, trace) in traced_results {
+            self.transforms
+                .push(execution_result.execution_journal().clone());
+            execution_results.push(Rc::new(execution_result));
+            traces.push(trace);
+        }
+        self.exec_results.push(execution_results);
+        traces
+    }
+
     /// Commit effects of previous exec call on the latest post-state hash.
     pub fn commit(&mut self) -> &mut Self {
         let prestate_hash = self.post_state_hash.expect("Should have genesis hash");
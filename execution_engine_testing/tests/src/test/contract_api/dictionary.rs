@@ -1,8 +1,9 @@
 use casper_engine_test_support::{
-    DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
+    utils, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
     DEFAULT_ACCOUNT_ADDR, DEFAULT_ACCOUNT_INITIAL_BALANCE, DEFAULT_ACCOUNT_PUBLIC_KEY,
-    DEFAULT_CHAINSPEC_REGISTRY, DEFAULT_GENESIS_CONFIG, DEFAULT_GENESIS_CONFIG_HASH,
-    DEFAULT_PAYMENT, MINIMUM_ACCOUNT_CREATION_BALANCE, PRODUCTION_RUN_GENESIS_REQUEST,
+    DEFAULT_CHAINSPEC_REGISTRY, DEFAULT_GAS_PRICE, DEFAULT_GENESIS_CONFIG,
+    DEFAULT_GENESIS_CONFIG_HASH, DEFAULT_PAYMENT, MINIMUM_ACCOUNT_CREATION_BALANCE,
+    PRODUCTION_RUN_GENESIS_REQUEST,
 };
 use casper_execution_engine::core::{
     engine_state::{run_genesis_request::RunGenesisRequest, Error as EngineError, GenesisAccount},
@@ -10,7 +11,7 @@ use casper_execution_engine::core::{
 };
 use casper_types::{
     account::AccountHash, runtime_args, system::mint, AccessRights, ApiError, CLType, CLValue,
-    ContractHash, Key, Motes, RuntimeArgs, StoredValue, U512,
+    ContractHash, Gas, Key, Motes, RuntimeArgs, StoredValue, U512,
 };
 use std::{convert::TryFrom, path::PathBuf};
 
@@ -20,6 +21,11 @@ const DICTIONARY_WASM: &str = "dictionary.wasm";
 const DICTIONARY_CALL_WASM: &str = "dictionary_call.wasm";
 const DICTIONARY_ITEM_KEY_CHECK: &str = "dictionary-item-key-check.wasm";
 const DICTIONARY_READ: &str = "dictionary_read.wasm";
+const DICTIONARY_KEYS: &str = "dictionary_keys.wasm";
+const STORAGE_REFUND_LOOP: &str = "storage_refund_loop.wasm";
+const ARG_MODE: &str = "mode";
+const MODE_WRITE: &str = "write";
+const MODE_REMOVE_DICTIONARY_ITEM_IN_LOOP: &str = "remove_dictionary_item_in_loop";
 const READ_FROM_KEY: &str = "read_from_key.wasm";
 const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([1u8; 32]);
 
@@ -683,3 +689,81 @@ fn should_be_able_to_perform_read_from_key() {
         .expect_success()
         .commit();
 }
+
+#[ignore]
+#[test]
+fn should_list_and_remove_dictionary_items() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let dictionary_session_call =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, DICTIONARY_KEYS, RuntimeArgs::new())
+            .build();
+
+    builder
+        .exec(dictionary_session_call)
+        .expect_success()
+        .commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account");
+    let dictionary_seed_uref = account
+        .named_keys()
+        .get("dictionary-name")
+        .and_then(Key::as_uref)
+        .copied()
+        .expect("should have dictionary seed uref");
+
+    assert!(builder
+        .query_dictionary_item(None, dictionary_seed_uref, "item-key-a")
+        .is_ok());
+    assert!(builder
+        .query_dictionary_item(None, dictionary_seed_uref, "item-key-b")
+        .is_err());
+    assert!(builder
+        .query_dictionary_item(None, dictionary_seed_uref, "item-key-c")
+        .is_ok());
+}
+
+#[ignore]
+#[test]
+fn should_run_out_of_gas_after_removing_pre_existing_dictionary_item() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let write_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        STORAGE_REFUND_LOOP,
+        runtime_args! { ARG_MODE => MODE_WRITE },
+    )
+    .build();
+    builder.exec(write_request).expect_success().commit();
+
+    // The storage refund for removing the large item must not pay for further execution.
+    let remove_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        STORAGE_REFUND_LOOP,
+        runtime_args! { ARG_MODE => MODE_REMOVE_DICTIONARY_ITEM_IN_LOOP },
+    )
+    .build();
+    builder.exec(remove_request).commit();
+
+    let response = builder
+        .get_exec_result_owned(1)
+        .expect("there should be a response");
+    let execution_result = utils::get_success_result(&response);
+    let error = execution_result.as_error().expect("should have error");
+    assert!(
+        matches!(error, EngineError::Exec(Error::GasLimit)),
+        "{:?}",
+        error
+    );
+    let session_gas_limit = Gas::from_motes(Motes::new(*DEFAULT_PAYMENT), DEFAULT_GAS_PRICE)
+        .expect("should convert to gas");
+    assert_eq!(
+        execution_result.cost(),
+        session_gas_limit,
+        "cost should equal gas limit"
+    );
+}
@@ -0,0 +1,119 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::{
+    core::{engine_state::Error, execution},
+    shared::storage_costs::StorageCosts,
+};
+use casper_types::{runtime_args, CLValue, ContractEvent, Gas, Key, RuntimeArgs, U512};
+
+const CONTRACT_EMIT_EVENT: &str = "emit_event.wasm";
+const CONTRACT_EMIT_LARGE_EVENTS: &str = "emit_large_events.wasm";
+const ARG_REVERT: &str = "revert";
+const ARG_PAYLOAD_SIZE: &str = "payload_size";
+const ARG_COUNT: &str = "count";
+const EVENT_TOPIC: &str = "counter-incremented";
+const EVENT_PAYLOAD: u64 = 42;
+const LARGE_PAYLOAD_SIZE: u32 = 100_000;
+
+fn emit_event(revert: bool) -> Vec<ContractEvent> {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_EMIT_EVENT,
+        runtime_args! { ARG_REVERT => revert },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+
+    let exec_result = builder
+        .get_last_exec_results()
+        .expect("should have exec results")
+        .pop()
+        .expect("should have exec result");
+    assert_eq!(exec_result.as_error().is_some(), revert);
+    exec_result.events().to_vec()
+}
+
+#[ignore]
+#[test]
+fn should_record_emitted_event() {
+    let expected_event = ContractEvent {
+        emitter: Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        topic: EVENT_TOPIC.to_string(),
+        payload: CLValue::from_t(EVENT_PAYLOAD).unwrap(),
+    };
+    assert_eq!(emit_event(false), vec![expected_event]);
+}
+
+#[ignore]
+#[test]
+fn should_discard_events_emitted_by_reverted_session() {
+    assert!(emit_event(true).is_empty());
+}
+
+fn emit_large_events(payload_size: u32, count: u32) -> (Gas, Option<Error>, usize) {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_EMIT_LARGE_EVENTS,
+        runtime_args! { ARG_PAYLOAD_SIZE => payload_size, ARG_COUNT => count },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+
+    let exec_result = builder
+        .get_last_exec_results()
+        .expect("should have exec results")
+        .pop()
+        .expect("should have exec result");
+    (
+        exec_result.cost(),
+        exec_result.as_error().cloned(),
+        exec_result.events().len(),
+    )
+}
+
+#[ignore]
+#[test]
+fn should_charge_for_event_payload_like_global_state_writes() {
+    let (empty_cost, maybe_error, event_count) = emit_large_events(0, 1);
+    assert!(maybe_error.is_none());
+    assert_eq!(event_count, 1);
+
+    let (large_cost, maybe_error, event_count) = emit_large_events(LARGE_PAYLOAD_SIZE, 1);
+    assert!(maybe_error.is_none());
+    assert_eq!(event_count, 1);
+
+    let gas_per_byte = StorageCosts::default().gas_per_byte();
+    let min_payload_cost = U512::from(LARGE_PAYLOAD_SIZE) * U512::from(gas_per_byte);
+    assert!(
+        large_cost.value() - empty_cost.value() >= min_payload_cost,
+        "payload cost {} should be at least {}",
+        large_cost.value() - empty_cost.value(),
+        min_payload_cost
+    );
+}
+
+#[ignore]
+#[test]
+fn should_fail_when_event_bytes_exceed_payment() {
+    let gas_per_byte = StorageCosts::default().gas_per_byte();
+    let event_cost = U512::from(LARGE_PAYLOAD_SIZE) * U512::from(gas_per_byte);
+    let count = (*DEFAULT_PAYMENT / event_cost).as_u32() + 1;
+
+    let (_cost, maybe_error, event_count) = emit_large_events(LARGE_PAYLOAD_SIZE, count);
+    assert_matches!(maybe_error, Some(Error::Exec(execution::Error::GasLimit)));
+    assert_eq!(event_count, 0);
+}
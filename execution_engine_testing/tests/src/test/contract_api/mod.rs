@@ -1,6 +1,7 @@
 mod account;
 mod create_purse;
 mod dictionary;
+mod emit_event;
 mod get_arg;
 mod get_blocktime;
 mod get_call_stack;
@@ -12,6 +13,7 @@ mod main_purse;
 mod mint_purse;
 mod multisig_authorization;
 mod named_dictionaries;
+mod prune;
 mod revert;
 mod runtime;
 mod subcall;
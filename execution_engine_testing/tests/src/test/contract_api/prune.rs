@@ -0,0 +1,95 @@
+use casper_engine_test_support::{
+    utils, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR, DEFAULT_GAS_PRICE,
+    DEFAULT_PAYMENT, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::{
+    core::{engine_state::Error, execution},
+    shared::transform::Transform,
+};
+use casper_types::{runtime_args, Gas, Key, Motes, RuntimeArgs};
+
+const CONTRACT_PRUNE_UREF: &str = "prune_uref.wasm";
+const CONTRACT_STORAGE_REFUND_LOOP: &str = "storage_refund_loop.wasm";
+const PRUNED_UREF_NAME: &str = "pruned-uref";
+const ARG_MODE: &str = "mode";
+const MODE_WRITE: &str = "write";
+const MODE_PRUNE_IN_LOOP: &str = "prune_in_loop";
+
+#[ignore]
+#[test]
+fn should_prune_uref() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_PRUNE_UREF,
+        RuntimeArgs::new(),
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account");
+    let pruned_key = *account
+        .named_keys()
+        .get(PRUNED_UREF_NAME)
+        .expect("should have pruned uref");
+
+    assert!(builder.query(None, pruned_key, &[]).is_err());
+
+    let execution_journal = builder
+        .get_execution_journals()
+        .pop()
+        .expect("should have execution journal");
+    assert!(execution_journal
+        .iter()
+        .any(|(key, transform)| *key == pruned_key.normalize()
+            && *transform == Transform::Prune(*key)));
+}
+
+#[ignore]
+#[test]
+fn should_run_out_of_gas_after_pruning_pre_existing_uref() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let write_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_STORAGE_REFUND_LOOP,
+        runtime_args! { ARG_MODE => MODE_WRITE },
+    )
+    .build();
+    builder.exec(write_request).expect_success().commit();
+
+    // The storage refund for pruning the large value must not pay for further execution.
+    let prune_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_STORAGE_REFUND_LOOP,
+        runtime_args! { ARG_MODE => MODE_PRUNE_IN_LOOP },
+    )
+    .build();
+    builder.exec(prune_request).commit();
+
+    let response = builder
+        .get_exec_result_owned(1)
+        .expect("there should be a response");
+    let execution_result = utils::get_success_result(&response);
+    let error = execution_result.as_error().expect("should have error");
+    assert!(
+        matches!(error, Error::Exec(execution::Error::GasLimit)),
+        "{:?}",
+        error
+    );
+    let session_gas_limit = Gas::from_motes(Motes::new(*DEFAULT_PAYMENT), DEFAULT_GAS_PRICE)
+        .expect("should convert to gas");
+    assert_eq!(
+        execution_result.cost(),
+        session_gas_limit,
+        "cost should equal gas limit"
+    );
+}
@@ -718,6 +718,7 @@ fn make_engine_config(
 ) -> EngineConfig {
     let new_system_config = SystemConfig::new(
         old_system_config.wasmless_transfer_cost(),
+        old_system_config.wasmless_transfer_target_cost(),
         *old_system_config.auction_costs(),
         new_mint_costs,
         *old_system_config.handle_payment_costs(),
@@ -22,7 +22,7 @@ use casper_execution_engine::{
             handle_payment_costs::HandlePaymentCosts,
             mint_costs::{MintCosts, DEFAULT_TRANSFER_COST},
             standard_payment_costs::StandardPaymentCosts,
-            SystemConfig, DEFAULT_WASMLESS_TRANSFER_COST,
+            SystemConfig, DEFAULT_WASMLESS_TRANSFER_COST, DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
         },
         wasm_config::{WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
     },
@@ -196,6 +196,7 @@ fn upgraded_add_bid_and_withdraw_bid_have_expected_costs() {
 
     let new_system_config = SystemConfig::new(
         new_wasmless_transfer_cost,
+        DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
         new_auction_costs,
         new_mint_costs,
         new_handle_payment_costs,
@@ -487,6 +488,7 @@ fn upgraded_delegate_and_undelegate_have_expected_costs() {
 
     let new_system_config = SystemConfig::new(
         new_wasmless_transfer_cost,
+        DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
         new_auction_costs,
         new_mint_costs,
         new_handle_payment_costs,
@@ -929,7 +931,7 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         current_memory: 0,
         grow_memory: 0,
     };
-    let new_storage_costs = StorageCosts::new(0);
+    let new_storage_costs = StorageCosts::new(0, 0);
 
     // We're elevating cost of `transfer_from_purse_to_purse` while zeroing others.
     // This will verify that user pays for the transfer host function _only_ while host does not
@@ -979,6 +981,10 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         blake2b: HostFunction::fixed(0),
         random_bytes: HostFunction::fixed(0),
         enable_contract_version: HostFunction::fixed(0),
+        dictionary_keys: HostFunction::fixed(0),
+        dictionary_remove: HostFunction::fixed(0),
+        prune: HostFunction::fixed(0),
+        emit_event: HostFunction::fixed(0),
     };
 
     let new_wasm_config = WasmConfig::new(
@@ -998,6 +1004,7 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
 
     let new_system_config = SystemConfig::new(
         new_wasmless_transfer_cost,
+        DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
         new_auction_costs,
         new_mint_costs,
         new_handle_payment_costs,
@@ -16,7 +16,7 @@ use casper_execution_engine::{
     shared::system_config::{
         auction_costs::AuctionCosts, handle_payment_costs::HandlePaymentCosts,
         mint_costs::MintCosts, standard_payment_costs::StandardPaymentCosts, SystemConfig,
-        DEFAULT_WASMLESS_TRANSFER_COST,
+        DEFAULT_WASMLESS_TRANSFER_COST, DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
     },
 };
 use casper_types::{
@@ -983,6 +983,7 @@ fn transfer_wasmless_should_observe_upgraded_cost() {
 
     let new_system_config = SystemConfig::new(
         new_wasmless_transfer_cost_value,
+        DEFAULT_WASMLESS_TRANSFER_TARGET_COST,
         new_auction_costs,
         new_mint_costs,
         new_handle_payment_costs,
@@ -1054,3 +1055,148 @@ fn transfer_wasmless_should_observe_upgraded_cost() {
         default_account_balance_before - default_account_balance_after - transfer_amount
     );
 }
+
+#[ignore]
+#[test]
+fn transfer_wasmless_should_transfer_to_multiple_targets() {
+    let create_account_2: bool = false;
+    let mut builder = init_wasmless_transform_builder(create_account_2);
+    let amounts = vec![U512::from(1000), U512::from(2000)];
+    let targets = vec![*ACCOUNT_2_ADDR, *DEFAULT_ACCOUNT_ADDR];
+
+    let wasmless_transfer_cost = Motes::from_gas(
+        Gas::new(
+            U512::from(DEFAULT_WASMLESS_TRANSFER_COST)
+                + U512::from(DEFAULT_WASMLESS_TRANSFER_TARGET_COST),
+        ),
+        WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+    )
+    .expect("gas overflow");
+
+    let account_1_purse = builder
+        .get_account(*ACCOUNT_1_ADDR)
+        .expect("should get account 1")
+        .main_purse();
+    let default_account_purse = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should get default account")
+        .main_purse();
+    assert_eq!(
+        builder.get_account(*ACCOUNT_2_ADDR),
+        None,
+        "account 2 should not exist"
+    );
+
+    let account_1_starting_balance = builder.get_purse_balance(account_1_purse);
+    let default_account_starting_balance = builder.get_purse_balance(default_account_purse);
+
+    let runtime_args = runtime_args! {
+        "targets" => targets,
+        "amounts" => amounts.clone(),
+        mint::ARG_ID => Some(1u64)
+    };
+
+    let no_wasm_transfer_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*ACCOUNT_1_ADDR)
+            .with_empty_payment_bytes(runtime_args! {})
+            .with_transfer_args(runtime_args)
+            .with_authorization_keys(&[*ACCOUNT_1_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+
+    builder
+        .exec(no_wasm_transfer_request)
+        .expect_success()
+        .commit();
+
+    let result = builder
+        .get_last_exec_results()
+        .expect("should have results")
+        .get(0)
+        .cloned()
+        .expect("should have first result");
+    assert_eq!(result.cost(), Gas::new(wasmless_transfer_cost.value()));
+    let transfers = result.transfers();
+    assert_eq!(transfers.len(), 2, "should record a transfer per target");
+    for (transfer_addr, amount) in transfers.iter().zip(&amounts) {
+        let transfer = builder
+            .get_transfer(*transfer_addr)
+            .expect("should have transfer");
+        assert_eq!(transfer.amount, *amount);
+        assert_eq!(transfer.id, Some(1));
+    }
+
+    let account_2 = builder
+        .get_account(*ACCOUNT_2_ADDR)
+        .expect("account 2 should exist");
+
+    assert_eq!(
+        builder.get_purse_balance(account_1_purse),
+        account_1_starting_balance - amounts[0] - amounts[1] - wasmless_transfer_cost.value(),
+        "account 1 ending balance incorrect"
+    );
+    assert_eq!(
+        builder.get_purse_balance(account_2.main_purse()),
+        amounts[0],
+        "account 2 ending balance incorrect"
+    );
+    assert_eq!(
+        builder.get_purse_balance(default_account_purse),
+        default_account_starting_balance + amounts[1],
+        "default account ending balance incorrect"
+    );
+}
+
+#[ignore]
+#[test]
+fn transfer_wasmless_should_not_transfer_to_multiple_targets_with_mismatched_amounts() {
+    let create_account_2: bool = true;
+    let mut builder = init_wasmless_transform_builder(create_account_2);
+
+    let account_1_purse = builder
+        .get_account(*ACCOUNT_1_ADDR)
+        .expect("should get account 1")
+        .main_purse();
+    let account_2_purse = builder
+        .get_account(*ACCOUNT_2_ADDR)
+        .expect("should get account 2")
+        .main_purse();
+    let account_1_starting_balance = builder.get_purse_balance(account_1_purse);
+    let account_2_starting_balance = builder.get_purse_balance(account_2_purse);
+
+    let runtime_args = runtime_args! {
+        "targets" => vec![*ACCOUNT_2_ADDR, *DEFAULT_ACCOUNT_ADDR],
+        "amounts" => vec![U512::from(1000)],
+        mint::ARG_ID => <Option<u64>>::None
+    };
+
+    let no_wasm_transfer_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*ACCOUNT_1_ADDR)
+            .with_empty_payment_bytes(runtime_args! {})
+            .with_transfer_args(runtime_args)
+            .with_authorization_keys(&[*ACCOUNT_1_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+
+    builder.exec(no_wasm_transfer_request).commit();
+
+    let error = builder.get_error().expect("should have error");
+    let expected_error = CoreError::Exec(ExecError::Revert(ApiError::InvalidArgument));
+    assert_eq!(format!("{}", error), format!("{}", expected_error));
+
+    assert_eq!(
+        builder.get_purse_balance(account_2_purse),
+        account_2_starting_balance,
+        "account 2 balance should not change"
+    );
+    assert!(
+        builder.get_purse_balance(account_1_purse) < account_1_starting_balance,
+        "account 1 should be charged for the failed transfer"
+    );
+}
@@ -7,14 +7,16 @@
 mod tests;
 
 use bytes::Bytes;
+use futures::future;
 use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+use serde::Serialize;
 use serde_json::{json, Map, Value};
 use tracing::{debug, trace, warn};
 use warp::{
     body,
     filters::BoxedFilter,
     reject::{self, Rejection},
-    reply::{self, WithStatus},
+    reply::{self, Reply, WithStatus},
     Filter,
 };
 
@@ -24,6 +26,7 @@ use crate::{
     request::{ErrorOrRejection, Request},
     request_handlers::RequestHandlers,
     response::Response,
+    DEFAULT_MAX_BATCH_SIZE,
 };
 
 const CONTENT_TYPE_VALUE: &str = "application/json";
@@ -66,21 +69,46 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
         .boxed()
 }
 
-/// Handles parsing a JSON-RPC request from the given HTTP body, executing it using the appropriate
+/// The body of a JSON-RPC reply: either the response to a single request, or the array of
+/// responses to a batch of requests.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum ResponseBody {
+    Single(Response),
+    Batch(Vec<Response>),
+}
+
+/// Handles parsing a JSON-RPC request from the given body, executing it using the appropriate
 /// handler, and providing a JSON-RPC response (which could be a success or failure).
 ///
-/// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
+/// If the body is a JSON Array, it is handled as a batch of requests as per the JSON-RPC
+/// specification, and an Array of responses is provided.  The elements of the batch are executed
+/// concurrently, and the responses are provided in the same order as the requests.  If the batch
+/// is empty or contains more than `max_batch_size` elements, a single failure response is
+/// provided.  Notifications within a batch, i.e. elements without an "id" field, are ignored and
+/// get no response, so if every element is a Notification, `Ok(None)` is returned.
+///
+/// Returns an `Err(Rejection)` only if a non-batch request is a Notification as per the JSON-RPC
 /// specification, i.e. the request doesn't contain an "id" field.  In this case, no JSON-RPC
 /// response is sent to the client.
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
-async fn handle_body(
-    body: Bytes,
+pub(crate) async fn handle_body(
+    body: &[u8],
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> Result<Response, Rejection> {
-    let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
+    max_batch_size: usize,
+) -> Result<Option<ResponseBody>, Rejection> {
+    let unvalidated_request = match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(batch)) => {
+            return Ok(handle_batch(batch, handlers, allow_unknown_fields, max_batch_size).await)
+        }
+        Ok(value) => serde_json::from_value::<Map<String, Value>>(value),
+        Err(error) => Err(error),
+    };
+
+    let response = match unvalidated_request {
         Ok(unvalidated_request) => match Request::new(unvalidated_request, allow_unknown_fields) {
             Ok(request) => handlers.handle_request(request).await,
             Err(ErrorOrRejection::Error { id, error }) => {
@@ -98,13 +126,104 @@ async fn handle_body(
             Response::new_failure(Value::Null, error)
         }
     };
-    Ok(response)
+    Ok(Some(ResponseBody::Single(response)))
+}
+
+/// Handles a batch of JSON-RPC requests, providing a response for each element of the batch other
+/// than Notifications.
+///
+/// As per the JSON-RPC specification, an element which is a Notification (i.e. has no "id" field)
+/// gets no response.  Returns `None` if every element of the batch is a Notification.
+async fn handle_batch(
+    batch: Vec<Value>,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+) -> Option<ResponseBody> {
+    if batch.is_empty() {
+        debug!("got an empty batch");
+        let error = Error::new(ReservedErrorCode::InvalidRequest, "Empty batch");
+        return Some(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    if batch.len() > max_batch_size {
+        debug!(
+            batch_size = batch.len(),
+            max_batch_size, "got an oversized batch"
+        );
+        let error = Error::new(
+            ReservedErrorCode::InvalidRequest,
+            format!(
+                "Batch of {} requests exceeds the maximum allowed of {}",
+                batch.len(),
+                max_batch_size
+            ),
+        );
+        return Some(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    let responses = batch.into_iter().map(|element| {
+        let handlers = handlers.clone();
+        async move {
+            let unvalidated_request = match element {
+                Value::Object(unvalidated_request) => unvalidated_request,
+                _ => {
+                    debug!("got a batch element which is not an object");
+                    let error = Error::new(
+                        ReservedErrorCode::InvalidRequest,
+                        "Expected batch element to be an Object",
+                    );
+                    return Some(Response::new_failure(Value::Null, error));
+                }
+            };
+            match Request::new(unvalidated_request, allow_unknown_fields) {
+                Ok(request) => Some(handlers.handle_request(request).await),
+                Err(ErrorOrRejection::Error { id, error }) => {
+                    debug!(?error, "got an invalid request in batch");
+                    Some(Response::new_failure(id, error))
+                }
+                Err(ErrorOrRejection::Rejection(rejection)) => {
+                    if rejection.find::<MissingId>().is_some() {
+                        debug!("ignoring a notification in batch");
+                        return None;
+                    }
+                    debug!(?rejection, "got a rejected request in batch");
+                    let error = Error::new(
+                        ReservedErrorCode::InvalidRequest,
+                        format!("{:?}", rejection),
+                    );
+                    Some(Response::new_failure(Value::Null, error))
+                }
+            }
+        }
+    });
+
+    let responses = future::join_all(responses)
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if responses.is_empty() {
+        debug!("got a batch of notifications only");
+        return None;
+    }
+    Some(ResponseBody::Batch(responses))
 }
 
 /// Returns a boxed warp filter which handles parsing a JSON-RPC request from the given HTTP body,
 /// executing it using the appropriate handler, and providing a reply.
 ///
-/// The reply will normally be built from a JSON-RPC response (which could be a success or failure).
+/// The reply will normally be built from a JSON-RPC response (which could be a success or failure),
+/// or from an array of JSON-RPC responses if the body is a batch of requests.  Batches with more
+/// than [`DEFAULT_MAX_BATCH_SIZE`] elements are not executed; instead a single failure response is
+/// returned.  If every element of a batch is a Notification, the reply has a status of
+/// "204 No Content", so its body is not sent to the client.
 ///
 /// However, the reply could be built from a [`Rejection`] if the request is a Notification as per
 /// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
@@ -116,12 +235,48 @@ pub fn main_filter(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
 ) -> BoxedFilter<(WithStatus<reply::Json>,)> {
+    response_body_filter(handlers, allow_unknown_fields, DEFAULT_MAX_BATCH_SIZE)
+        .map(
+            |maybe_response: Option<ResponseBody>| match maybe_response {
+                Some(response) => reply::with_status(reply::json(&response), StatusCode::OK),
+                None => reply::with_status(reply::json(&()), StatusCode::NO_CONTENT),
+            },
+        )
+        .boxed()
+}
+
+/// Returns a boxed warp filter like [`main_filter`], but accepting batches of up to
+/// `max_batch_size` requests, and replying with an empty body if every element of a batch is a
+/// Notification.
+pub fn main_filter_with_batch_size(
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+) -> BoxedFilter<(reply::Response,)> {
+    response_body_filter(handlers, allow_unknown_fields, max_batch_size)
+        .map(
+            |maybe_response: Option<ResponseBody>| match maybe_response {
+                Some(response) => {
+                    reply::with_status(reply::json(&response), StatusCode::OK).into_response()
+                }
+                None => StatusCode::NO_CONTENT.into_response(),
+            },
+        )
+        .boxed()
+}
+
+/// Returns a boxed warp filter which parses and executes the JSON-RPC request or batch of requests
+/// in the given HTTP body, providing the response body if there is one to be sent.
+fn response_body_filter(
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+) -> BoxedFilter<(Option<ResponseBody>,)> {
     body::bytes()
-        .and_then(move |body| {
+        .and_then(move |body: Bytes| {
             let handlers = handlers.clone();
-            async move { handle_body(body, handlers, allow_unknown_fields).await }
+            async move { handle_body(&body, handlers, allow_unknown_fields, max_batch_size).await }
         })
-        .map(|response| reply::with_status(reply::json(&response), StatusCode::OK))
         .boxed()
 }
 
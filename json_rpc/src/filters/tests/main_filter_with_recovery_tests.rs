@@ -10,12 +10,13 @@ use warp::{filters::BoxedFilter, Filter, Reply};
 
 use super::ResponseBodyOnRejection;
 use crate::{
-    filters::{handle_rejection, main_filter},
-    Error, Params, RequestHandlersBuilder, ReservedErrorCode, Response,
+    filters::{handle_rejection, main_filter, main_filter_with_batch_size},
+    Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode, Response,
 };
 
 const GET_GOOD_THING: &str = "get good thing";
 const GET_BAD_THING: &str = "get bad thing";
+const MAX_BATCH_SIZE: usize = 3;
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 struct GoodThing {
@@ -49,13 +50,20 @@ async fn from_http_response(response: http::Response<hyper::Body>) -> Response {
     serde_json::from_slice(&body_bytes).unwrap()
 }
 
-fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
+async fn batch_from_http_response(response: http::Response<hyper::Body>) -> Vec<Response> {
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
+fn handlers() -> RequestHandlers {
     let mut handlers = RequestHandlersBuilder::new();
     handlers.register_handler(GET_GOOD_THING, Arc::new(get_good_thing));
     handlers.register_handler(GET_BAD_THING, Arc::new(get_bad_thing));
-    let handlers = handlers.build();
+    handlers.build()
+}
 
-    main_filter(handlers, false)
+fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
+    main_filter_with_batch_size(handlers(), false, MAX_BATCH_SIZE)
         .recover(handle_rejection)
         .boxed()
 }
@@ -318,3 +326,205 @@ async fn should_handle_invalid_json() {
         )
     );
 }
+
+#[tokio::test]
+async fn should_handle_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // Each element should be handled independently, with the responses returned in the same order
+    // as the requests, and with an invalid element yielding a failure response rather than causing
+    // the whole batch to fail.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"b","method":"get good thing"},
+                {"jsonrpc":"2.0","id":"c","method":"get good thing","params":["three"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 3);
+
+    assert_eq!(rpc_responses[0].id(), "a");
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "one".to_string()
+        })
+    );
+
+    assert_eq!(rpc_responses[1].id(), "b");
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidParams, "no params")
+    );
+
+    assert_eq!(rpc_responses[2].id(), "c");
+    assert_eq!(
+        rpc_responses[2].result(),
+        Some(GoodThing {
+            good_thing: "three".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_with_invalid_elements() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // Elements which are not objects should yield a failure response with a null ID, while
+    // elements which have no ID are Notifications and should yield no response at all.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                1,
+                {"jsonrpc":"2.0","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"c","method":"get good thing","params":["three"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 2);
+
+    assert_eq!(rpc_responses[0].id(), &Value::Null);
+    assert_eq!(
+        rpc_responses[0].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected batch element to be an Object"
+        )
+    );
+
+    assert_eq!(rpc_responses[1].id(), "c");
+    assert_eq!(
+        rpc_responses[1].result(),
+        Some(GoodThing {
+            good_thing: "three".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_of_notifications() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // A batch consisting only of Notifications should yield no JSON-RPC response at all.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","method":"get good thing","params":["two"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::NO_CONTENT);
+    let body_bytes = hyper::body::to_bytes(http_response.into_body())
+        .await
+        .unwrap();
+    assert!(body_bytes.is_empty());
+}
+
+#[tokio::test]
+async fn should_handle_empty_batch() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // An empty batch should yield a single failure response rather than an empty array.
+    let http_response = warp::test::request()
+        .body(r#"[]"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidRequest, "Empty batch")
+    );
+}
+
+#[tokio::test]
+async fn should_handle_oversized_batch() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // A batch with more than `MAX_BATCH_SIZE` elements should yield a single failure response
+    // without any of the elements being executed.
+    let request = r#"{"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]}"#;
+    let body = format!("[{}]", [request; MAX_BATCH_SIZE + 1].join(","));
+    let http_response = warp::test::request()
+        .body(body)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch of 4 requests exceeds the maximum allowed of 3"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_up_to_default_max_batch_size() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter(handlers(), false)
+        .recover(handle_rejection)
+        .boxed();
+
+    // A batch larger than `MAX_BATCH_SIZE` is executed, as `main_filter` accepts batches of up to
+    // `DEFAULT_MAX_BATCH_SIZE` requests.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"b","method":"get good thing","params":["two"]},
+                {"jsonrpc":"2.0","id":"c","method":"get good thing","params":["three"]},
+                {"jsonrpc":"2.0","id":"d","method":"get good thing","params":["four"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 4);
+    assert!(rpc_responses
+        .iter()
+        .all(|rpc_response| rpc_response.result::<GoodThing>().is_some()));
+}
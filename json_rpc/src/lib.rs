@@ -86,6 +86,8 @@ mod request_handlers;
 mod response;
 
 use http::{header::CONTENT_TYPE, Method};
+use serde_json::Value;
+use tracing::warn;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 pub use error::{Error, ErrorCodeT, ReservedErrorCode};
@@ -95,6 +97,10 @@ pub use response::Response;
 
 const JSON_RPC_VERSION: &str = "2.0";
 
+/// The maximum number of requests in a single JSON-RPC batch accepted by [`route`] and
+/// [`route_with_cors`].
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
 /// Specifies the CORS origin
 pub enum CorsOrigin {
     /// Any (*) origin is allowed.
@@ -118,15 +124,43 @@ pub enum CorsOrigin {
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// Batches of up to [`DEFAULT_MAX_BATCH_SIZE`] requests are accepted; to set a different limit, use
+/// [`route_with_batch_size`].
+///
 /// For further details, see the docs for the [`filters`] functions.
 pub fn route<P: AsRef<str>>(
     path: P,
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+) -> BoxedFilter<(impl Reply,)> {
+    route_with_batch_size(
+        path,
+        max_body_bytes,
+        handlers,
+        allow_unknown_fields,
+        DEFAULT_MAX_BATCH_SIZE,
+    )
+}
+
+/// Constructs a set of warp filters like [`route`], but accepting batches of up to
+/// `max_batch_size` requests.
+///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch.  For
+/// the other parameters, see [`route`].
+pub fn route_with_batch_size<P: AsRef<str>>(
+    path: P,
+    max_body_bytes: u32,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter_with_batch_size(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .boxed()
 }
@@ -146,6 +180,9 @@ pub fn route<P: AsRef<str>>(
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// Batches of up to [`DEFAULT_MAX_BATCH_SIZE`] requests are accepted; to set a different limit, use
+/// [`route_with_cors_and_batch_size`].
+///
 /// Note that this is a convenience function combining the lower-level functions in [`filters`]
 /// along with [a warp CORS filter](https://docs.rs/warp/latest/warp/filters/cors/index.html) which
 ///   * allows any origin or specified origin
@@ -159,9 +196,36 @@ pub fn route_with_cors<P: AsRef<str>>(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
     cors_header: &CorsOrigin,
+) -> BoxedFilter<(impl Reply,)> {
+    route_with_cors_and_batch_size(
+        path,
+        max_body_bytes,
+        handlers,
+        allow_unknown_fields,
+        DEFAULT_MAX_BATCH_SIZE,
+        cors_header,
+    )
+}
+
+/// Constructs a set of warp filters like [`route_with_cors`], but accepting batches of up to
+/// `max_batch_size` requests.
+///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch.  For
+/// the other parameters, see [`route_with_cors`].
+pub fn route_with_cors_and_batch_size<P: AsRef<str>>(
+    path: P,
+    max_body_bytes: u32,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+    cors_header: &CorsOrigin,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter_with_batch_size(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .with(match cors_header {
             CorsOrigin::Any => warp::cors()
@@ -175,3 +239,111 @@ pub fn route_with_cors<P: AsRef<str>>(
         })
         .boxed()
 }
+
+/// Handles a single JSON-RPC message received via a transport other than HTTP, e.g. a WebSocket.
+///
+/// `message` is parsed as either a single JSON-RPC request or a batch of requests, executed using
+/// the appropriate handlers from `handlers`, and the JSON-RPC response (or array of responses in
+/// the case of a batch) is returned.
+///
+/// Returns `None` if `message` is a single Notification as per the JSON-RPC specification, i.e.
+/// the request doesn't contain an "id" field, or a batch consisting only of Notifications, since
+/// no response should be sent to the client.
+///
+/// If `allow_unknown_fields` is `false`, requests with unknown fields will cause an error response.
+///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch.
+pub async fn handle_message(
+    message: &[u8],
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: usize,
+) -> Option<Value> {
+    let response_body =
+        filters::handle_body(message, handlers, allow_unknown_fields, max_batch_size)
+            .await
+            .ok()??;
+    match serde_json::to_value(response_body) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!(%error, "failed to encode json-rpc response");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+
+    async fn get_value(_params: Option<Params>) -> Result<u64, Error> {
+        Ok(1)
+    }
+
+    async fn get_other_value(_params: Option<Params>) -> Result<u64, Error> {
+        Ok(2)
+    }
+
+    fn handlers() -> RequestHandlers {
+        let mut handlers = RequestHandlersBuilder::new();
+        handlers.register_handler("get_value", Arc::new(get_value));
+        handlers.build()
+    }
+
+    #[tokio::test]
+    async fn should_handle_message() {
+        let message = br#"{"jsonrpc":"2.0","id":1,"method":"get_value"}"#;
+        let response = handle_message(message, handlers(), false, 10).await;
+        assert_eq!(response, Some(json!({"jsonrpc":"2.0","id":1,"result":1})));
+    }
+
+    #[tokio::test]
+    async fn should_handle_batch_message() {
+        let message = br#"[{"jsonrpc":"2.0","id":1,"method":"get_value"},
+            {"jsonrpc":"2.0","id":2,"method":"get_other_value"}]"#;
+        let response = handle_message(message, handlers(), false, 10)
+            .await
+            .unwrap();
+        assert_eq!(response[0], json!({"jsonrpc":"2.0","id":1,"result":1}));
+        assert_eq!(
+            response[1]["error"]["code"],
+            json!(ReservedErrorCode::MethodNotFound as i64)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_respond_to_notification() {
+        let message = br#"{"jsonrpc":"2.0","method":"get_value"}"#;
+        assert!(handle_message(message, handlers(), false, 10)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn should_not_respond_to_batch_of_notifications() {
+        let message = br#"[{"jsonrpc":"2.0","method":"get_value"},
+            {"jsonrpc":"2.0","method":"get_value"}]"#;
+        assert!(handle_message(message, handlers(), false, 10)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn should_extend_handlers() {
+        let mut builder = handlers().to_builder();
+        builder.register_handler("get_other_value", Arc::new(get_other_value));
+        let extended_handlers = builder.build();
+
+        let message = br#"{"jsonrpc":"2.0","id":1,"method":"get_other_value"}"#;
+        let response = handle_message(message, extended_handlers.clone(), false, 10).await;
+        assert_eq!(response, Some(json!({"jsonrpc":"2.0","id":1,"result":2})));
+
+        let message = br#"{"jsonrpc":"2.0","id":1,"method":"get_value"}"#;
+        let response = handle_message(message, extended_handlers, false, 10).await;
+        assert_eq!(response, Some(json!({"jsonrpc":"2.0","id":1,"result":1})));
+    }
+}
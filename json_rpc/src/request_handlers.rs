@@ -53,6 +53,14 @@ impl RequestHandlers {
             Err(error) => Response::new_failure(request.id, error),
         }
     }
+
+    /// Returns a new [`RequestHandlersBuilder`] pre-populated with all the handlers in `self`.
+    ///
+    /// This allows a set of handlers to be extended, e.g. with handlers which are only applicable
+    /// to a single client connection.
+    pub fn to_builder(&self) -> RequestHandlersBuilder {
+        RequestHandlersBuilder(self.0.as_ref().clone())
+    }
 }
 
 /// A builder for [`RequestHandlers`].
@@ -0,0 +1,638 @@
+//! Verification of block headers against the finality signatures of era validators.
+//!
+//! A light client starts from the validators of an era it trusts, e.g. those listed in the
+//! chainspec or in a switch block it obtained out of band.  A block of that era is final if it is
+//! signed by validators with a total weight above the quorum derived from the fault tolerance
+//! fraction.  The switch block ending the era lists the validators of the next one, so a chain of
+//! signed switch blocks establishes the validators of any later era.
+
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+
+use num_rational::Ratio;
+#[cfg(any(feature = "std", test))]
+use serde::Deserialize;
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    crypto, EraId, ProtocolVersion, PublicKey, Signature, Timestamp, U512,
+};
+
+use crate::{Digest, Error};
+
+/// Equivocation, reward and inactivity information included in a switch block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(any(feature = "std", test), derive(Deserialize))]
+#[cfg_attr(any(feature = "std", test), serde(from = "json::JsonEraReport"))]
+pub struct EraReport {
+    /// The validators who equivocated in the era.
+    pub equivocators: Vec<PublicKey>,
+    /// The rewards of the validators.
+    pub rewards: BTreeMap<PublicKey, u64>,
+    /// The validators who were inactive in the era.
+    pub inactive_validators: Vec<PublicKey>,
+}
+
+impl ToBytes for EraReport {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.equivocators.serialized_length()
+            + self.rewards.serialized_length()
+            + self.inactive_validators.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.equivocators.write_bytes(writer)?;
+        self.rewards.write_bytes(writer)?;
+        self.inactive_validators.write_bytes(writer)
+    }
+}
+
+impl FromBytes for EraReport {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (equivocators, remainder) = Vec::<PublicKey>::from_bytes(bytes)?;
+        let (rewards, remainder) = BTreeMap::<PublicKey, u64>::from_bytes(remainder)?;
+        let (inactive_validators, remainder) = Vec::<PublicKey>::from_bytes(remainder)?;
+        let era_report = EraReport {
+            equivocators,
+            rewards,
+            inactive_validators,
+        };
+        Ok((era_report, remainder))
+    }
+}
+
+/// The information included in the last block of an era.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(any(feature = "std", test), derive(Deserialize))]
+#[cfg_attr(any(feature = "std", test), serde(from = "json::JsonEraEnd"))]
+pub struct EraEnd {
+    /// Equivocation, reward and inactivity information for the era.
+    pub era_report: EraReport,
+    /// The validators for the upcoming era and their respective weights.
+    pub next_era_validator_weights: BTreeMap<PublicKey, U512>,
+}
+
+impl ToBytes for EraEnd {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.era_report.serialized_length() + self.next_era_validator_weights.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.era_report.write_bytes(writer)?;
+        self.next_era_validator_weights.write_bytes(writer)
+    }
+}
+
+impl FromBytes for EraEnd {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (era_report, remainder) = EraReport::from_bytes(bytes)?;
+        let (next_era_validator_weights, remainder) =
+            BTreeMap::<PublicKey, U512>::from_bytes(remainder)?;
+        let era_end = EraEnd {
+            era_report,
+            next_era_validator_weights,
+        };
+        Ok((era_end, remainder))
+    }
+}
+
+/// The header of a block, from which the block hash is computed.
+///
+/// With the `std` feature enabled, it can be deserialized from the JSON representation of a block
+/// header returned by the node's JSON-RPC.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(any(feature = "std", test), derive(Deserialize))]
+#[cfg_attr(any(feature = "std", test), serde(deny_unknown_fields))]
+pub struct BlockHeader {
+    /// The hash of the parent block.
+    pub parent_hash: Digest,
+    /// The root hash of global state after the execution of the block.
+    pub state_root_hash: Digest,
+    /// The hash of the block body.
+    pub body_hash: Digest,
+    /// The random bit of the block.
+    pub random_bit: bool,
+    /// The seed for the sequence of leaders accumulated from random bits.
+    pub accumulated_seed: Digest,
+    /// The era end, present if and only if this is a switch block.
+    pub era_end: Option<EraEnd>,
+    /// The block timestamp.
+    pub timestamp: Timestamp,
+    /// The era of the block.
+    pub era_id: EraId,
+    /// The height of the block.
+    pub height: u64,
+    /// The protocol version of the block.
+    pub protocol_version: ProtocolVersion,
+}
+
+impl BlockHeader {
+    /// Computes the block hash.
+    pub fn block_hash(&self) -> Result<Digest, Error> {
+        Ok(Digest::hash(self.to_bytes()?))
+    }
+
+    /// Returns `true` if this is the last block of an era.
+    pub fn is_switch_block(&self) -> bool {
+        self.era_end.is_some()
+    }
+}
+
+impl ToBytes for BlockHeader {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.parent_hash.serialized_length()
+            + self.state_root_hash.serialized_length()
+            + self.body_hash.serialized_length()
+            + self.random_bit.serialized_length()
+            + self.accumulated_seed.serialized_length()
+            + self.era_end.serialized_length()
+            + self.timestamp.serialized_length()
+            + self.era_id.serialized_length()
+            + self.height.serialized_length()
+            + self.protocol_version.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.parent_hash.write_bytes(writer)?;
+        self.state_root_hash.write_bytes(writer)?;
+        self.body_hash.write_bytes(writer)?;
+        self.random_bit.write_bytes(writer)?;
+        self.accumulated_seed.write_bytes(writer)?;
+        self.era_end.write_bytes(writer)?;
+        self.timestamp.write_bytes(writer)?;
+        self.era_id.write_bytes(writer)?;
+        self.height.write_bytes(writer)?;
+        self.protocol_version.write_bytes(writer)
+    }
+}
+
+impl FromBytes for BlockHeader {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (parent_hash, remainder) = Digest::from_bytes(bytes)?;
+        let (state_root_hash, remainder) = Digest::from_bytes(remainder)?;
+        let (body_hash, remainder) = Digest::from_bytes(remainder)?;
+        let (random_bit, remainder) = bool::from_bytes(remainder)?;
+        let (accumulated_seed, remainder) = Digest::from_bytes(remainder)?;
+        let (era_end, remainder) = Option::<EraEnd>::from_bytes(remainder)?;
+        let (timestamp, remainder) = Timestamp::from_bytes(remainder)?;
+        let (era_id, remainder) = EraId::from_bytes(remainder)?;
+        let (height, remainder) = u64::from_bytes(remainder)?;
+        let (protocol_version, remainder) = ProtocolVersion::from_bytes(remainder)?;
+        let block_header = BlockHeader {
+            parent_hash,
+            state_root_hash,
+            body_hash,
+            random_bit,
+            accumulated_seed,
+            era_end,
+            timestamp,
+            era_id,
+            height,
+            protocol_version,
+        };
+        Ok((block_header, remainder))
+    }
+}
+
+/// The finality signatures of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockSignatures {
+    /// The hash of the signed block.
+    pub block_hash: Digest,
+    /// The era of the signed block.
+    pub era_id: EraId,
+    /// The signatures, by the public keys of the signing validators.
+    pub proofs: BTreeMap<PublicKey, Signature>,
+}
+
+impl BlockSignatures {
+    /// Creates an empty set of signatures of the given block.
+    pub fn new(block_hash: Digest, era_id: EraId) -> Self {
+        BlockSignatures {
+            block_hash,
+            era_id,
+            proofs: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a validator's signature.
+    pub fn insert_proof(&mut self, public_key: PublicKey, signature: Signature) {
+        let _ = self.proofs.insert(public_key, signature);
+    }
+
+    /// Returns the bytes signed by the validators: the block hash followed by the era ID.
+    fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.block_hash.value().to_vec();
+        bytes.extend_from_slice(&self.era_id.to_le_bytes());
+        bytes
+    }
+}
+
+/// The validators of an era along with their weights, trusted to sign the blocks of the era.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EraValidators {
+    era_id: EraId,
+    weights: BTreeMap<PublicKey, U512>,
+    fault_tolerance_fraction: Ratio<u64>,
+}
+
+impl EraValidators {
+    /// Creates the trusted validators of the given era.
+    ///
+    /// `fault_tolerance_fraction` is the `core.finality_threshold_fraction` of the network's
+    /// chainspec.
+    pub fn new(
+        era_id: EraId,
+        weights: BTreeMap<PublicKey, U512>,
+        fault_tolerance_fraction: Ratio<u64>,
+    ) -> Self {
+        EraValidators {
+            era_id,
+            weights,
+            fault_tolerance_fraction,
+        }
+    }
+
+    /// Returns the era.
+    pub fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    /// Returns the validators and their weights.
+    pub fn weights(&self) -> &BTreeMap<PublicKey, U512> {
+        &self.weights
+    }
+
+    /// Verifies that the block with the given header belongs to this era and is finalized by the
+    /// given signatures, returning the block hash.
+    ///
+    /// All signatures must be valid and by validators of this era, with a total weight exceeding
+    /// the quorum `(fault_tolerance_fraction + 1) / 2` of the total weight.
+    pub fn verify_block(
+        &self,
+        header: &BlockHeader,
+        signatures: &BlockSignatures,
+    ) -> Result<Digest, Error> {
+        if header.era_id != self.era_id {
+            return Err(Error::UnexpectedEra {
+                expected: self.era_id,
+                actual: header.era_id,
+            });
+        }
+        let block_hash = header.block_hash()?;
+        if signatures.block_hash != block_hash || signatures.era_id != header.era_id {
+            return Err(Error::SignaturesForWrongBlock {
+                block_hash,
+                signatures_block_hash: signatures.block_hash,
+            });
+        }
+
+        let signed_bytes = signatures.signed_bytes();
+        let mut signature_weight = U512::zero();
+        for (public_key, signature) in &signatures.proofs {
+            let weight = self
+                .weights
+                .get(public_key)
+                .ok_or_else(|| Error::BogusValidator(Box::new(public_key.clone())))?;
+            crypto::verify(&signed_bytes, signature, public_key).map_err(|error| {
+                Error::InvalidSignature {
+                    public_key: Box::new(public_key.clone()),
+                    error,
+                }
+            })?;
+            signature_weight += *weight;
+        }
+
+        let total_weight: U512 = self.weights.values().copied().sum();
+        let quorum_fraction = (self.fault_tolerance_fraction + 1) / 2;
+        if signature_weight * U512::from(*quorum_fraction.denom())
+            <= total_weight * U512::from(*quorum_fraction.numer())
+        {
+            return Err(Error::InsufficientWeightForFinality {
+                signature_weight: Box::new(signature_weight),
+                total_weight: Box::new(total_weight),
+            });
+        }
+        Ok(block_hash)
+    }
+
+    /// Verifies the switch block ending this era, returning the validators of the next era listed
+    /// in it.
+    pub fn next_era(
+        &self,
+        switch_block: &BlockHeader,
+        signatures: &BlockSignatures,
+    ) -> Result<EraValidators, Error> {
+        let block_hash = self.verify_block(switch_block, signatures)?;
+        let era_end = switch_block
+            .era_end
+            .as_ref()
+            .ok_or(Error::NotSwitchBlock { block_hash })?;
+        Ok(EraValidators {
+            era_id: self.era_id.successor(),
+            weights: era_end.next_era_validator_weights.clone(),
+            fault_tolerance_fraction: self.fault_tolerance_fraction,
+        })
+    }
+}
+
+/// Verifies a chain of signed switch blocks, one per era starting with the era of `trusted`,
+/// returning the validators of the era following the last switch block.
+pub fn verify_switch_block_chain(
+    trusted: EraValidators,
+    switch_blocks: &[(BlockHeader, BlockSignatures)],
+) -> Result<EraValidators, Error> {
+    switch_blocks
+        .iter()
+        .try_fold(trusted, |validators, (switch_block, signatures)| {
+            validators.next_era(switch_block, signatures)
+        })
+}
+
+#[cfg(any(feature = "std", test))]
+mod json {
+    use alloc::vec::Vec;
+
+    use serde::Deserialize;
+
+    use casper_types::{PublicKey, U512};
+
+    use super::{EraEnd, EraReport};
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Reward {
+        validator: PublicKey,
+        amount: u64,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct ValidatorWeight {
+        validator: PublicKey,
+        weight: U512,
+    }
+
+    /// The JSON representation of an era report used by the node.
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) struct JsonEraReport {
+        equivocators: Vec<PublicKey>,
+        rewards: Vec<Reward>,
+        inactive_validators: Vec<PublicKey>,
+    }
+
+    impl From<JsonEraReport> for EraReport {
+        fn from(era_report: JsonEraReport) -> Self {
+            EraReport {
+                equivocators: era_report.equivocators,
+                rewards: era_report
+                    .rewards
+                    .into_iter()
+                    .map(|reward| (reward.validator, reward.amount))
+                    .collect(),
+                inactive_validators: era_report.inactive_validators,
+            }
+        }
+    }
+
+    /// The JSON representation of an era end used by the node.
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) struct JsonEraEnd {
+        era_report: EraReport,
+        next_era_validator_weights: Vec<ValidatorWeight>,
+    }
+
+    impl From<JsonEraEnd> for EraEnd {
+        fn from(era_end: JsonEraEnd) -> Self {
+            EraEnd {
+                era_report: era_end.era_report,
+                next_era_validator_weights: era_end
+                    .next_era_validator_weights
+                    .into_iter()
+                    .map(|validator_weight| (validator_weight.validator, validator_weight.weight))
+                    .collect(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use casper_types::{bytesrepr, testing::TestRng, SecretKey};
+
+    use super::*;
+
+    /// The hash and header of the block in the example response of the node's `chain_get_block`
+    /// JSON-RPC method.
+    const EXAMPLE_BLOCK: &str = r#"{
+        "hash": "13c2d7a68ecdd4b74bf4393c88915c836c863fc4bf11d7f2bd930a1bbccacdcb",
+        "header": {
+            "parent_hash": "0707070707070707070707070707070707070707070707070707070707070707",
+            "state_root_hash": "0808080808080808080808080808080808080808080808080808080808080808",
+            "body_hash": "cd502c5393a3c8b66d6979ad7857507c9baf5a8ba16ba99c28378d3a970fff42",
+            "random_bit": true,
+            "accumulated_seed": "ac979f51525cfd979b14aa7dc0737c5154eabe0db9280eceaa8dc8d2905b20d5",
+            "era_end": {
+                "era_report": {
+                    "equivocators": [
+                        "013b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29"
+                    ],
+                    "rewards": [
+                        {
+                            "validator": "018a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c",
+                            "amount": 1000
+                        }
+                    ],
+                    "inactive_validators": [
+                        "018139770ea87d175f56a35466c34c7ecccb8d8a91b4ee37a25df60f5b8fc9b394"
+                    ]
+                },
+                "next_era_validator_weights": [
+                    {
+                        "validator": "016e7a1cdd29b0b78fd13af4c5598feff4ef2a97166e3ca6f2e4fbfccd80505bf1",
+                        "weight": "456"
+                    },
+                    {
+                        "validator": "018a875fff1eb38451577acd5afee405456568dd7c89e090863a0557bc7af49f17",
+                        "weight": "789"
+                    },
+                    {
+                        "validator": "01d9bf2148748a85c89da5aad8ee0b0fc2d105fd39d41a4c796536354f0ae2900c",
+                        "weight": "123"
+                    }
+                ]
+            },
+            "timestamp": "2020-11-17T00:39:24.072Z",
+            "era_id": 1,
+            "height": 10,
+            "protocol_version": "1.0.0"
+        }
+    }"#;
+
+    #[derive(Deserialize)]
+    struct ExampleBlock {
+        hash: Digest,
+        header: BlockHeader,
+    }
+
+    fn ftt() -> Ratio<u64> {
+        Ratio::new(1, 3)
+    }
+
+    fn sign(block_hash: Digest, era_id: EraId, secret_keys: &[SecretKey]) -> BlockSignatures {
+        let mut signatures = BlockSignatures::new(block_hash, era_id);
+        let signed_bytes = signatures.signed_bytes();
+        for secret_key in secret_keys {
+            let public_key = PublicKey::from(secret_key);
+            let signature = crypto::sign(&signed_bytes, secret_key, &public_key);
+            signatures.insert_proof(public_key, signature);
+        }
+        signatures
+    }
+
+    fn switch_block(
+        rng: &mut TestRng,
+        era_id: EraId,
+        next_era_validator_weights: BTreeMap<PublicKey, U512>,
+    ) -> BlockHeader {
+        BlockHeader {
+            parent_hash: Digest::hash(rng.gen::<[u8; 8]>()),
+            state_root_hash: Digest::hash(rng.gen::<[u8; 8]>()),
+            body_hash: Digest::hash(rng.gen::<[u8; 8]>()),
+            random_bit: rng.gen(),
+            accumulated_seed: Digest::hash(rng.gen::<[u8; 8]>()),
+            era_end: Some(EraEnd {
+                era_report: EraReport {
+                    equivocators: vec![],
+                    rewards: BTreeMap::new(),
+                    inactive_validators: vec![],
+                },
+                next_era_validator_weights,
+            }),
+            timestamp: Timestamp::from(rng.gen::<u32>() as u64),
+            era_id,
+            height: rng.gen(),
+            protocol_version: ProtocolVersion::V1_0_0,
+        }
+    }
+
+    fn validators(rng: &mut TestRng, count: usize) -> (Vec<SecretKey>, BTreeMap<PublicKey, U512>) {
+        let secret_keys: Vec<SecretKey> = (0..count).map(|_| SecretKey::random(rng)).collect();
+        let weights = secret_keys
+            .iter()
+            .map(|secret_key| (PublicKey::from(secret_key), U512::from(100)))
+            .collect();
+        (secret_keys, weights)
+    }
+
+    #[test]
+    fn should_verify_example_block() {
+        let block: ExampleBlock = serde_json::from_str(EXAMPLE_BLOCK).unwrap();
+        assert_eq!(block.header.block_hash().unwrap(), block.hash);
+
+        let bytes = block.header.to_bytes().unwrap();
+        assert_eq!(
+            bytesrepr::deserialize::<BlockHeader>(bytes).unwrap(),
+            block.header
+        );
+
+        let mut rng = TestRng::new();
+        let (secret_keys, weights) = validators(&mut rng, 1);
+        let signatures = sign(block.hash, block.header.era_id, &secret_keys);
+        let validators = EraValidators::new(block.header.era_id, weights, ftt());
+        let next_validators = validators.next_era(&block.header, &signatures).unwrap();
+        assert_eq!(next_validators.era_id(), EraId::new(2));
+        assert_eq!(next_validators.weights().len(), 3);
+    }
+
+    #[test]
+    fn should_verify_switch_block_chain() {
+        let mut rng = TestRng::new();
+        let (mut secret_keys, mut weights) = validators(&mut rng, 4);
+        let trusted = EraValidators::new(EraId::new(5), weights.clone(), ftt());
+
+        let mut chain = vec![];
+        for era in 5..8 {
+            let (next_secret_keys, next_weights) = validators(&mut rng, 4);
+            let header = switch_block(&mut rng, EraId::new(era), next_weights.clone());
+            // Three of four validators are more than the quorum of two thirds.
+            let signatures = sign(
+                header.block_hash().unwrap(),
+                header.era_id,
+                &secret_keys[..3],
+            );
+            chain.push((header, signatures));
+            secret_keys = next_secret_keys;
+            weights = next_weights;
+        }
+
+        let validators = verify_switch_block_chain(trusted.clone(), &chain).unwrap();
+        assert_eq!(validators.era_id(), EraId::new(8));
+        assert_eq!(validators.weights(), &weights);
+
+        // Skipping an era breaks the chain.
+        assert!(matches!(
+            verify_switch_block_chain(trusted, &chain[1..]),
+            Err(Error::UnexpectedEra { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_insufficient_or_bogus_signatures() {
+        let mut rng = TestRng::new();
+        let (secret_keys, weights) = validators(&mut rng, 3);
+        let era_id = EraId::new(1);
+        let validators = EraValidators::new(era_id, weights.clone(), ftt());
+        let header = switch_block(&mut rng, era_id, weights);
+        let block_hash = header.block_hash().unwrap();
+
+        // Two of three validators are exactly the quorum of two thirds, which is not enough.
+        let signatures = sign(block_hash, era_id, &secret_keys[..2]);
+        assert!(matches!(
+            validators.verify_block(&header, &signatures),
+            Err(Error::InsufficientWeightForFinality { .. })
+        ));
+
+        let mut signatures = sign(block_hash, era_id, &secret_keys);
+        assert_eq!(
+            validators.verify_block(&header, &signatures),
+            Ok(block_hash)
+        );
+
+        let outsider = SecretKey::random(&mut rng);
+        signatures
+            .proofs
+            .extend(sign(block_hash, era_id, &[outsider]).proofs);
+        assert!(matches!(
+            validators.verify_block(&header, &signatures),
+            Err(Error::BogusValidator(_))
+        ));
+
+        let other_block_signatures = sign(Digest::hash(b"other"), era_id, &secret_keys);
+        assert!(matches!(
+            validators.verify_block(&header, &other_block_signatures),
+            Err(Error::SignaturesForWrongBlock { .. })
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+//! The hash digests used for global state tries and blocks, computed as by `casper-hashing`.
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    convert::TryFrom,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    checksummed_hex,
+};
+
+use crate::Error;
+
+/// The size of the chunks into which data larger than a chunk is split before hashing.
+const CHUNK_SIZE_BYTES: usize = 8 * 1024 * 1024;
+
+/// A 32-byte BLAKE2b hash digest.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub struct Digest([u8; Digest::LENGTH]);
+
+impl Digest {
+    /// The number of bytes in a `Digest`.
+    pub const LENGTH: usize = 32;
+
+    /// Sentinel hash used by `hash_merkle_tree` in the case of an empty list.
+    const SENTINEL_MERKLE_TREE: Digest = Digest([2u8; Digest::LENGTH]);
+
+    /// Creates a 32-byte BLAKE2b hash digest from a given a piece of data.
+    pub fn hash<T: AsRef<[u8]>>(data: T) -> Digest {
+        let mut ret = [0u8; Digest::LENGTH];
+        // NOTE: Safe to unwrap here because our digest length is constant and valid
+        let mut hasher = VarBlake2b::new(Digest::LENGTH).unwrap();
+        hasher.update(data);
+        hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+        Digest(ret)
+    }
+
+    /// Hashes a pair of byte slices.
+    fn hash_pair<T: AsRef<[u8]>, U: AsRef<[u8]>>(data1: T, data2: U) -> Digest {
+        let mut ret = [0u8; Digest::LENGTH];
+        let mut hasher = VarBlake2b::new(Digest::LENGTH).unwrap();
+        hasher.update(data1);
+        hasher.update(data2);
+        hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+        Digest(ret)
+    }
+
+    /// Hashes a raw Merkle root and leaf count to form the final Merkle hash, prefixed by a zeroed
+    /// chunk to avoid pre-image attacks.
+    fn hash_merkle_root(leaf_count: u64, root: Digest) -> Digest {
+        let mut ret = [0u8; Digest::LENGTH];
+        let mut hasher = VarBlake2b::new(Digest::LENGTH).unwrap();
+        let zeroed = [0u8; 1024];
+        for _ in 0..CHUNK_SIZE_BYTES / zeroed.len() {
+            hasher.update(zeroed);
+        }
+        hasher.update(leaf_count.to_le_bytes());
+        hasher.update(root);
+        hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+        Digest(ret)
+    }
+
+    /// Hashes the leaves of a Merkle tree, pairing adjacent digests level by level.
+    fn hash_merkle_tree(mut leaves: Vec<Digest>) -> Digest {
+        let leaf_count = leaves.len() as u64;
+        if leaves.is_empty() {
+            return Digest::SENTINEL_MERKLE_TREE;
+        }
+        while leaves.len() > 1 {
+            leaves = leaves
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => Digest::hash_pair(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks of two"),
+                })
+                .collect();
+        }
+        Digest::hash_merkle_root(leaf_count, leaves[0])
+    }
+
+    /// Hashes data as a single chunk if it fits in one, or as the Merkle tree of its chunks
+    /// otherwise.  This is how global state trie nodes are hashed.
+    pub fn hash_into_chunks_if_necessary(bytes: &[u8]) -> Digest {
+        if bytes.len() <= CHUNK_SIZE_BYTES {
+            Digest::hash(bytes)
+        } else {
+            Digest::hash_merkle_tree(bytes.chunks(CHUNK_SIZE_BYTES).map(Digest::hash).collect())
+        }
+    }
+
+    /// Returns a `Digest` parsed from a hex-encoded `Digest`.
+    pub fn from_hex<T: AsRef<[u8]>>(hex_input: T) -> Result<Self, Error> {
+        let bytes = checksummed_hex::decode(&hex_input)?;
+        Digest::try_from(bytes.as_slice())
+    }
+
+    /// Returns the underlying BLAKE2b hash bytes.
+    pub fn value(&self) -> [u8; Digest::LENGTH] {
+        self.0
+    }
+}
+
+impl Display for Digest {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", base16::encode_lower(&self.0))
+    }
+}
+
+impl Debug for Digest {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", base16::encode_lower(&self.0))
+    }
+}
+
+impl From<[u8; Digest::LENGTH]> for Digest {
+    fn from(arr: [u8; Digest::LENGTH]) -> Self {
+        Digest(arr)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Digest {
+    type Error = Error;
+
+    fn try_from(slice: &[u8]) -> Result<Digest, Error> {
+        <[u8; Digest::LENGTH]>::try_from(slice)
+            .map(Digest)
+            .map_err(|_| Error::IncorrectDigestLength(slice.len()))
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl ToBytes for Digest {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.0.to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.0.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        writer.extend_from_slice(&self.0);
+        Ok(())
+    }
+}
+
+impl FromBytes for Digest {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        FromBytes::from_bytes(bytes).map(|(arr, rem)| (Digest(arr), rem))
+    }
+}
+
+impl Serialize for Digest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            base16::encode_lower(&self.0).serialize(serializer)
+        } else {
+            self.0[..].serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let hex_string = String::deserialize(deserializer)?;
+            Digest::from_hex(hex_string.as_bytes()).map_err(SerdeError::custom)
+        } else {
+            let data = <Vec<u8>>::deserialize(deserializer)?;
+            Digest::try_from(data.as_slice()).map_err(SerdeError::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::*;
+
+    #[test]
+    fn should_hash_as_casper_hashing() {
+        let mut rng = rand::thread_rng();
+        for length in [0, 1, 100, CHUNK_SIZE_BYTES, 3 * CHUNK_SIZE_BYTES + 1] {
+            let mut data = vec![0u8; length];
+            rng.fill_bytes(&mut data);
+            assert_eq!(
+                Digest::hash_into_chunks_if_necessary(&data).value(),
+                casper_hashing::Digest::hash_into_chunks_if_necessary(&data).value(),
+                "length {}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn should_merkle_hash_as_casper_hashing() {
+        let mut rng = rand::thread_rng();
+        for leaf_count in 0..12 {
+            let leaves: Vec<[u8; Digest::LENGTH]> = (0..leaf_count)
+                .map(|_| {
+                    let mut leaf = [0; Digest::LENGTH];
+                    rng.fill_bytes(&mut leaf);
+                    leaf
+                })
+                .collect();
+            assert_eq!(
+                Digest::hash_merkle_tree(leaves.iter().copied().map(Digest).collect()).value(),
+                casper_hashing::Digest::hash_merkle_tree(
+                    leaves.iter().copied().map(casper_hashing::Digest::from)
+                )
+                .value(),
+                "leaf count {}",
+                leaf_count
+            );
+        }
+    }
+
+    #[test]
+    fn should_parse_hex() {
+        let digest = Digest::hash(b"abc");
+        assert_eq!(Digest::from_hex(digest.to_string()), Ok(digest));
+        assert_eq!(
+            Digest::from_hex("0102"),
+            Err(Error::IncorrectDigestLength(2))
+        );
+    }
+}
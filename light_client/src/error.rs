@@ -0,0 +1,135 @@
+//! Errors in decoding and verifying proofs and blocks.
+
+use alloc::boxed::Box;
+use core::fmt::{self, Display, Formatter};
+
+use casper_types::{bytesrepr, crypto, EraId, PublicKey, U512};
+
+use crate::Digest;
+
+/// Errors in decoding and verifying proofs and blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// There was a hex decoding error.
+    Base16DecodeError(base16::DecodeError),
+    /// The digest length was an incorrect size.
+    IncorrectDigestLength(usize),
+    /// There was a (de)serialization error.
+    BytesRepr(bytesrepr::Error),
+    /// The number of proofs is not one more than the length of the path.
+    PathLengthDifferentThanProofLessOne,
+    /// The key of a proof does not match the expected key.
+    UnexpectedKey,
+    /// The state root hash computed from a proof does not match the expected one.
+    InvalidProofHash,
+    /// A path component could not be resolved via the named keys of the preceding value.
+    PathCold,
+    /// A block header does not belong to the era of the validators verifying it.
+    UnexpectedEra {
+        /// The era of the validators.
+        expected: EraId,
+        /// The era of the block header.
+        actual: EraId,
+    },
+    /// A block header is not a switch block.
+    NotSwitchBlock {
+        /// The hash of the block.
+        block_hash: Digest,
+    },
+    /// The signatures are not for the given block.
+    SignaturesForWrongBlock {
+        /// The hash of the block.
+        block_hash: Digest,
+        /// The hash of the block the signatures are for.
+        signatures_block_hash: Digest,
+    },
+    /// A signature is by a public key which is not one of the era's validators.
+    BogusValidator(Box<PublicKey>),
+    /// A signature is invalid.
+    InvalidSignature {
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+        /// The verification error.
+        error: crypto::Error,
+    },
+    /// The validators who signed a block do not have sufficient weight to finalize it.
+    InsufficientWeightForFinality {
+        /// The total weight of the signers.
+        signature_weight: Box<U512>,
+        /// The total weight of all the validators of the era.
+        total_weight: Box<U512>,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::Base16DecodeError(error) => write!(formatter, "base16 decode error {}", error),
+            Error::IncorrectDigestLength(length) => write!(
+                formatter,
+                "incorrect digest length {}, expected length {}",
+                length,
+                Digest::LENGTH
+            ),
+            Error::BytesRepr(error) => write!(formatter, "serialization error: {}", error),
+            Error::PathLengthDifferentThanProofLessOne => write!(
+                formatter,
+                "the path should not have a different length than the proof less one"
+            ),
+            Error::UnexpectedKey => write!(
+                formatter,
+                "the provided key does not match the key in the proof"
+            ),
+            Error::InvalidProofHash => write!(formatter, "the proof hash is invalid"),
+            Error::PathCold => write!(formatter, "the path went cold"),
+            Error::UnexpectedEra { expected, actual } => write!(
+                formatter,
+                "block of {} can't be verified by validators of {}",
+                actual, expected
+            ),
+            Error::NotSwitchBlock { block_hash } => {
+                write!(formatter, "block {} is not a switch block", block_hash)
+            }
+            Error::SignaturesForWrongBlock {
+                block_hash,
+                signatures_block_hash,
+            } => write!(
+                formatter,
+                "signatures for block {} can't be used for block {}",
+                signatures_block_hash, block_hash
+            ),
+            Error::BogusValidator(public_key) => write!(
+                formatter,
+                "signature by {} who is not a validator of the era",
+                public_key
+            ),
+            Error::InvalidSignature { public_key, error } => {
+                write!(formatter, "invalid signature by {}: {}", public_key, error)
+            }
+            Error::InsufficientWeightForFinality {
+                signature_weight,
+                total_weight,
+            } => write!(
+                formatter,
+                "insufficient weight for finality: signature weight {}, total weight {}",
+                signature_weight, total_weight
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::BytesRepr(error)
+    }
+}
+
+impl From<base16::DecodeError> for Error {
+    fn from(error: base16::DecodeError) -> Self {
+        Error::Base16DecodeError(error)
+    }
+}
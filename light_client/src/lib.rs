@@ -0,0 +1,35 @@
+//! A `no_std` library for light clients of the Casper network.
+//!
+//! It provides:
+//!
+//! * decoding of the Merkle proofs of global state entries returned by the node's JSON-RPC, and
+//!   verification of them against a state root hash via [`verify_query_proof`]
+//! * verification of block headers against the finality signatures of the validators of their era
+//!   via [`EraValidators::verify_block`], and of a chain of switch blocks leading from a trusted
+//!   era to a later one via [`verify_switch_block_chain`]
+//!
+//! Enable the `std` feature to deserialize [`BlockHeader`]s from the JSON returned by the node.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![doc(html_root_url = "https://docs.rs/casper-light-client/0.1.0")]
+#![doc(
+    html_favicon_url = "https://raw.githubusercontent.com/casper-network/casper-node/blob/dev/images/Casper_Logo_Favicon_48.png",
+    html_logo_url = "https://raw.githubusercontent.com/casper-network/casper-node/blob/dev/images/Casper_Logo_Favicon.png",
+    test(attr(forbid(warnings)))
+)]
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+mod block;
+mod digest;
+mod error;
+mod merkle_proof;
+
+pub use block::{
+    verify_switch_block_chain, BlockHeader, BlockSignatures, EraEnd, EraReport, EraValidators,
+};
+pub use digest::Digest;
+pub use error::Error;
+pub use merkle_proof::{
+    decode_proofs, verify_query_proof, Pointer, TrieMerkleProof, TrieMerkleProofStep,
+};
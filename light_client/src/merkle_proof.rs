@@ -0,0 +1,373 @@
+//! Decoding and verification of the Merkle proofs of global state entries.
+//!
+//! The proofs returned by the `state_get_item` and `query_global_state` JSON-RPC methods are
+//! hex-encoded, bytesrepr-serialized lists of proofs, one for the base key of the query and one
+//! for each component of the path, each proving the presence of a single entry in the trie with
+//! the given state root hash.
+
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+
+use casper_types::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    checksummed_hex, Key, StoredValue,
+};
+
+use crate::{Digest, Error};
+
+const RADIX: usize = 256;
+
+const LEAF_POINTER_TAG: u8 = 0;
+const NODE_POINTER_TAG: u8 = 1;
+
+const TRIE_LEAF_TAG: u8 = 0;
+const TRIE_NODE_TAG: u8 = 1;
+const TRIE_EXTENSION_TAG: u8 = 2;
+
+const TRIE_MERKLE_PROOF_STEP_NODE_ID: u8 = 0;
+const TRIE_MERKLE_PROOF_STEP_EXTENSION_ID: u8 = 1;
+
+/// A pointer to a child of a trie node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Pointer {
+    /// Pointer to a leaf.
+    LeafPointer(Digest),
+    /// Pointer to a node or extension.
+    NodePointer(Digest),
+}
+
+impl Pointer {
+    fn tag(&self) -> u8 {
+        match self {
+            Pointer::LeafPointer(_) => LEAF_POINTER_TAG,
+            Pointer::NodePointer(_) => NODE_POINTER_TAG,
+        }
+    }
+
+    fn hash(&self) -> &Digest {
+        match self {
+            Pointer::LeafPointer(hash) | Pointer::NodePointer(hash) => hash,
+        }
+    }
+}
+
+impl FromBytes for Pointer {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        let (hash, rem) = Digest::from_bytes(rem)?;
+        match tag {
+            LEAF_POINTER_TAG => Ok((Pointer::LeafPointer(hash), rem)),
+            NODE_POINTER_TAG => Ok((Pointer::NodePointer(hash), rem)),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// A component of a proof that an entry exists in the Merkle trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieMerkleProofStep {
+    /// A trie node, with all its pointers but the one on the path to the entry.
+    Node {
+        /// The index of the pointer on the path to the entry.
+        hole_index: u8,
+        /// The other pointers of the node, by index.
+        indexed_pointers_with_hole: Vec<(u8, Pointer)>,
+    },
+    /// A trie extension.
+    Extension {
+        /// The affix bytes of the extension.
+        affix: Bytes,
+    },
+}
+
+impl FromBytes for TrieMerkleProofStep {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        match tag {
+            TRIE_MERKLE_PROOF_STEP_NODE_ID => {
+                let (hole_index, rem) = u8::from_bytes(rem)?;
+                let (indexed_pointers_with_hole, rem) = Vec::<(u8, Pointer)>::from_bytes(rem)?;
+                Ok((
+                    TrieMerkleProofStep::Node {
+                        hole_index,
+                        indexed_pointers_with_hole,
+                    },
+                    rem,
+                ))
+            }
+            TRIE_MERKLE_PROOF_STEP_EXTENSION_ID => {
+                let (affix, rem) = Bytes::from_bytes(rem)?;
+                Ok((TrieMerkleProofStep::Extension { affix }, rem))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// A proof that an entry with a given key and value is present in the global state trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieMerkleProof {
+    key: Key,
+    value: StoredValue,
+    proof_steps: VecDeque<TrieMerkleProofStep>,
+}
+
+impl TrieMerkleProof {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// Returns the value of the entry.
+    pub fn value(&self) -> &StoredValue {
+        &self.value
+    }
+
+    /// Returns the steps from the entry's leaf to the root of the trie.
+    pub fn proof_steps(&self) -> &VecDeque<TrieMerkleProofStep> {
+        &self.proof_steps
+    }
+
+    /// Recomputes the state root hash of the trie from the entry and the proof steps, hashing the
+    /// leaf and then each enclosing node or extension in turn.
+    pub fn compute_state_hash(&self) -> Result<Digest, Error> {
+        let mut leaf_bytes =
+            Vec::with_capacity(1 + self.key.serialized_length() + self.value.serialized_length());
+        leaf_bytes.push(TRIE_LEAF_TAG);
+        self.key.write_bytes(&mut leaf_bytes)?;
+        self.value.write_bytes(&mut leaf_bytes)?;
+        let mut hash = Digest::hash_into_chunks_if_necessary(&leaf_bytes);
+
+        for (proof_step_index, proof_step) in self.proof_steps.iter().enumerate() {
+            let pointer = if proof_step_index == 0 {
+                Pointer::LeafPointer(hash)
+            } else {
+                Pointer::NodePointer(hash)
+            };
+            let mut step_bytes = Vec::new();
+            match proof_step {
+                TrieMerkleProofStep::Node {
+                    hole_index,
+                    indexed_pointers_with_hole,
+                } => {
+                    let mut pointer_block: [Option<Pointer>; RADIX] = [None; RADIX];
+                    for (index, indexed_pointer) in indexed_pointers_with_hole {
+                        pointer_block[*index as usize] = Some(*indexed_pointer);
+                    }
+                    pointer_block[*hole_index as usize] = Some(pointer);
+                    step_bytes.push(TRIE_NODE_TAG);
+                    for maybe_pointer in pointer_block.iter() {
+                        match maybe_pointer {
+                            None => step_bytes.push(0),
+                            Some(pointer) => {
+                                step_bytes.push(1);
+                                write_pointer(pointer, &mut step_bytes);
+                            }
+                        }
+                    }
+                }
+                TrieMerkleProofStep::Extension { affix } => {
+                    step_bytes.push(TRIE_EXTENSION_TAG);
+                    affix.write_bytes(&mut step_bytes)?;
+                    write_pointer(&pointer, &mut step_bytes);
+                }
+            }
+            hash = Digest::hash(&step_bytes);
+        }
+        Ok(hash)
+    }
+}
+
+fn write_pointer(pointer: &Pointer, writer: &mut Vec<u8>) {
+    writer.push(pointer.tag());
+    writer.extend_from_slice(pointer.hash().as_ref());
+}
+
+impl FromBytes for TrieMerkleProof {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (key, rem) = Key::from_bytes(bytes)?;
+        let (value, rem) = StoredValue::from_bytes(rem)?;
+        let (proof_steps, rem) = VecDeque::<TrieMerkleProofStep>::from_bytes(rem)?;
+        Ok((
+            TrieMerkleProof {
+                key,
+                value,
+                proof_steps,
+            },
+            rem,
+        ))
+    }
+}
+
+/// Decodes the hex-encoded `merkle_proof` returned by the `state_get_item` and
+/// `query_global_state` JSON-RPC methods.
+pub fn decode_proofs<T: AsRef<[u8]>>(merkle_proof: T) -> Result<Vec<TrieMerkleProof>, Error> {
+    let bytes = checksummed_hex::decode(merkle_proof)?;
+    let proofs: Vec<TrieMerkleProof> = bytesrepr::deserialize(bytes)?;
+    Ok(proofs)
+}
+
+/// Verifies the proofs of a global state query for the value under `base_key` and `path`, as
+/// returned by the `state_get_item` and `query_global_state` JSON-RPC methods, against the given
+/// state root hash.
+///
+/// Each path component is resolved via the named keys of the account or contract found by the
+/// preceding proof.  On success, returns the value proven to be stored at the end of the path.
+pub fn verify_query_proof<'a>(
+    state_root_hash: &Digest,
+    proofs: &'a [TrieMerkleProof],
+    base_key: &Key,
+    path: &[String],
+) -> Result<&'a StoredValue, Error> {
+    if proofs.len() != path.len() + 1 {
+        return Err(Error::PathLengthDifferentThanProofLessOne);
+    }
+
+    let mut expected_key = base_key.normalize();
+    let mut proofs_iter = proofs.iter();
+    // The length check above means there is always a first proof.
+    let mut proof = proofs_iter
+        .next()
+        .ok_or(Error::PathLengthDifferentThanProofLessOne)?;
+    let mut path_iter = path.iter();
+    loop {
+        if proof.key() != &expected_key {
+            return Err(Error::UnexpectedKey);
+        }
+        if state_root_hash != &proof.compute_state_hash()? {
+            return Err(Error::InvalidProofHash);
+        }
+
+        let (next_proof, path_component) = match (proofs_iter.next(), path_iter.next()) {
+            (Some(next_proof), Some(path_component)) => (next_proof, path_component),
+            _ => return Ok(proof.value()),
+        };
+        let named_keys = match proof.value() {
+            StoredValue::Account(account) => account.named_keys(),
+            StoredValue::Contract(contract) => contract.named_keys(),
+            _ => return Err(Error::PathCold),
+        };
+        expected_key = named_keys
+            .get(path_component)
+            .ok_or(Error::PathCold)?
+            .normalize();
+        proof = next_proof;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use casper_execution_engine::{
+        shared::newtypes::CorrelationId,
+        storage::global_state::{in_memory::InMemoryGlobalState, StateProvider, StateReader},
+    };
+    use casper_types::{
+        account::{Account, AccountHash},
+        bytesrepr::ToBytes,
+        AccessRights, CLValue, URef,
+    };
+
+    use super::*;
+
+    fn query_proofs(
+        pairs: &[(Key, StoredValue)],
+        keys: &[Key],
+    ) -> (
+        Digest,
+        Vec<
+            casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof<Key, StoredValue>,
+        >,
+    ) {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = InMemoryGlobalState::from_pairs(correlation_id, pairs).unwrap();
+        let reader = state.checkout(root_hash).unwrap().unwrap();
+        let proofs = keys
+            .iter()
+            .map(|key| {
+                reader
+                    .read_with_proof(correlation_id, &key.normalize())
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect();
+        (Digest::from(root_hash.value()), proofs)
+    }
+
+    fn test_state() -> (Vec<(Key, StoredValue)>, Key, Key) {
+        let account_hash = AccountHash::new([1; 32]);
+        let uref = URef::new([2; 32], AccessRights::READ_ADD_WRITE);
+        let mut named_keys = BTreeMap::new();
+        named_keys.insert(String::from("counter"), Key::URef(uref));
+        let account = Account::create(account_hash, named_keys, uref);
+        let mut pairs = vec![
+            (Key::Account(account_hash), StoredValue::Account(account)),
+            (
+                Key::URef(uref),
+                StoredValue::CLValue(CLValue::from_t(7u64).unwrap()),
+            ),
+        ];
+        for i in 3..40u8 {
+            pairs.push((
+                Key::Hash([i; 32]),
+                StoredValue::CLValue(CLValue::from_t(i).unwrap()),
+            ));
+        }
+        (pairs, Key::Account(account_hash), Key::URef(uref))
+    }
+
+    #[test]
+    fn should_verify_query_proof() {
+        let (pairs, account_key, uref_key) = test_state();
+        let (root_hash, proofs) = query_proofs(&pairs, &[account_key, uref_key]);
+        let encoded = base16::encode_lower(&proofs.to_bytes().unwrap());
+
+        let decoded = decode_proofs(&encoded).unwrap();
+        assert_eq!(decoded.len(), 2);
+        for (decoded, proof) in decoded.iter().zip(proofs.iter()) {
+            assert_eq!(
+                decoded.compute_state_hash().unwrap().value(),
+                proof.compute_state_hash().unwrap().value()
+            );
+        }
+
+        let value = verify_query_proof(
+            &root_hash,
+            &decoded,
+            &account_key,
+            &[String::from("counter")],
+        )
+        .unwrap();
+        assert_eq!(value, &StoredValue::CLValue(CLValue::from_t(7u64).unwrap()));
+    }
+
+    #[test]
+    fn should_reject_wrong_root_or_path() {
+        let (pairs, account_key, uref_key) = test_state();
+        let (root_hash, proofs) = query_proofs(&pairs, &[account_key, uref_key]);
+        let decoded = decode_proofs(base16::encode_lower(&proofs.to_bytes().unwrap())).unwrap();
+        let path = [String::from("counter")];
+
+        assert_eq!(
+            verify_query_proof(&Digest::hash(b"other"), &decoded, &account_key, &path),
+            Err(Error::InvalidProofHash)
+        );
+        assert_eq!(
+            verify_query_proof(&root_hash, &decoded, &uref_key, &path),
+            Err(Error::UnexpectedKey)
+        );
+        assert_eq!(
+            verify_query_proof(
+                &root_hash,
+                &decoded,
+                &account_key,
+                &[String::from("missing")]
+            ),
+            Err(Error::PathCold)
+        );
+        assert_eq!(
+            verify_query_proof(&root_hash, &decoded, &account_key, &[]),
+            Err(Error::PathLengthDifferentThanProofLessOne)
+        );
+    }
+}
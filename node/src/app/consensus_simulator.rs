@@ -0,0 +1,65 @@
+//! # Casper consensus simulator
+//!
+//! Runs the Highway or Zug consensus protocol deterministically in a simulated network of
+//! validators, as described by a scenario file, and writes statistics about the run as JSON.  Run
+//! with `--help` to see available command-line arguments.
+
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use structopt::StructOpt;
+
+use casper_node::{
+    consensus::simulator::{self, Scenario},
+    types::{chainspec::ChainspecRawBytes, Chainspec},
+    utils::Loadable,
+};
+
+/// Command-line arguments.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "casper-consensus-simulator")]
+struct Args {
+    /// Path to the TOML scenario file.
+    #[structopt(parse(from_os_str))]
+    scenario: PathBuf,
+    /// Path to the directory containing the chainspec.toml file defining the protocol parameters.
+    #[structopt(long, parse(from_os_str))]
+    chainspec: PathBuf,
+    /// Overrides the seed given in the scenario file.
+    #[structopt(long)]
+    seed: Option<u64>,
+    /// Path to the file the JSON report is written to.  If omitted, it is written to stdout.
+    #[structopt(long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+/// Main function.
+fn main() -> anyhow::Result<()> {
+    let args = Args::from_args();
+
+    let scenario_toml = fs::read_to_string(&args.scenario)
+        .with_context(|| format!("failed to read scenario {}", args.scenario.display()))?;
+    let mut scenario: Scenario = toml::from_str(&scenario_toml)
+        .with_context(|| format!("failed to parse scenario {}", args.scenario.display()))?;
+    if let Some(seed) = args.seed {
+        scenario.seed = seed;
+    }
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(&args.chainspec)
+        .with_context(|| format!("failed to load chainspec from {}", args.chainspec.display()))?;
+
+    let report = simulator::run(&scenario, chainspec)?;
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?,
+        ),
+        None => Box::new(io::stdout()),
+    };
+    serde_json::to_writer_pretty(&mut output, &report)?;
+    writeln!(output)?;
+    Ok(())
+}
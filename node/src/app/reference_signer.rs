@@ -0,0 +1,58 @@
+//! # Casper reference signer
+//!
+//! A reference implementation of an external signer for the Casper node.  It holds a validator's
+//! secret key and signs the messages requested by the node via a Unix socket, refusing to sign
+//! consensus messages conflicting with ones it signed before.  Run with `--help` to see available
+//! command-line arguments.
+
+use std::{
+    fs,
+    os::unix::{fs::FileTypeExt, net::UnixListener},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::{bail, Context};
+use structopt::StructOpt;
+
+use casper_node::signer::reference::ReferenceSigner;
+use casper_types::SecretKey;
+
+/// Command-line arguments.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "casper-reference-signer")]
+struct Args {
+    /// Path to the validator's secret key file.
+    #[structopt(long, parse(from_os_str))]
+    secret_key: PathBuf,
+    /// Path to the Unix socket to listen on.
+    #[structopt(long, parse(from_os_str))]
+    socket: PathBuf,
+    /// Path to the slashing protection journal recording the signed consensus messages.  If
+    /// omitted, the record is only kept in memory and is lost when the signer is restarted.
+    #[structopt(long, parse(from_os_str))]
+    state: Option<PathBuf>,
+}
+
+/// Main function.
+fn main() -> anyhow::Result<()> {
+    casper_node::logging::init_with_config(&Default::default())?;
+    let args = Args::from_args();
+
+    let secret_key = SecretKey::from_file(&args.secret_key)
+        .with_context(|| format!("failed to load secret key {}", args.secret_key.display()))?;
+    let mut signer = ReferenceSigner::new(Arc::new(secret_key), args.state.as_deref())?;
+
+    // Remove a socket left behind by a previous run, but never any other kind of file.
+    if let Ok(metadata) = fs::symlink_metadata(&args.socket) {
+        if !metadata.file_type().is_socket() {
+            bail!("{} exists and is not a socket", args.socket.display());
+        }
+        fs::remove_file(&args.socket)?;
+    }
+    let listener = UnixListener::bind(&args.socket)
+        .with_context(|| format!("failed to bind to {}", args.socket.display()))?;
+
+    signer.serve(listener)?;
+    Ok(())
+}
@@ -13,6 +13,8 @@ use std::{
 };
 
 use anyhow::{self, bail, Context};
+use casper_hashing::Digest;
+use casper_types::{AsymmetricType, EraId, PublicKey};
 use prometheus::Registry;
 use regex::Regex;
 use stats_alloc::{StatsAlloc, INSTRUMENTED_SYSTEM};
@@ -25,6 +27,7 @@ use crate::{
     logging,
     reactor::{main_reactor, Runner},
     setup_signal_hooks,
+    signer::slashing_protection::SlashingProtectionDb,
     types::{Chainspec, ChainspecRawBytes, ExitCode},
     utils::{Loadable, WithDir},
 };
@@ -78,6 +81,102 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Export the global state under a given state root hash to a snapshot file.
+    ExportGlobalState {
+        /// Path to configuration file of the node whose global state is exported.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Hex-encoded state root hash of the global state to export.
+        #[structopt(long, parse(try_from_str = Digest::from_hex))]
+        state_root_hash: Digest,
+        /// Path of the snapshot file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Verify and import a global state snapshot file into the node's global state store.
+    ImportGlobalState {
+        /// Path to configuration file of the node whose global state store is written to.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Path of the snapshot file to import.
+        #[structopt(long)]
+        input: PathBuf,
+        /// Hex-encoded state root hash the snapshot is required to hold.
+        #[structopt(long, parse(try_from_str = Digest::from_hex))]
+        state_root_hash: Option<Digest>,
+    },
+    /// Delete global state which is unreachable from the state roots of recent blocks.
+    ///
+    /// The node must not be running.
+    CompactGlobalState {
+        /// Path to configuration file of the node whose global state store is compacted.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Number of eras preceding the current one whose global state is retained.  Defaults to
+        /// the contract runtime's `global_state_compaction_retained_eras`.
+        #[structopt(long)]
+        retained_eras: Option<u64>,
+    },
+    /// Export the node's slashing protection record to a portable JSON file.
+    ///
+    /// The file can be imported by another node, e.g. when migrating a validator to a new machine.
+    ExportSlashingProtection {
+        /// Path to configuration file of the node whose slashing protection record is exported.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Path of the JSON file to create.
+        #[structopt(long)]
+        output: PathBuf,
+        /// Hex-encoded public key of the validator whose record is exported.  Defaults to all
+        /// validators in the record.
+        #[structopt(long, parse(try_from_str = PublicKey::from_hex))]
+        public_key: Option<PublicKey>,
+    },
+    /// Import a slashing protection record exported from another node, merging it into the node's
+    /// own record.
+    ///
+    /// The node must not be running.
+    ImportSlashingProtection {
+        /// Path to configuration file of the node whose slashing protection record is written to.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Path of the JSON file to import.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+    /// Decode a Zug write-ahead log file to JSON, validating its entries against the era's
+    /// validators.
+    InspectZugWal {
+        /// Path to configuration file of the node whose storage holds the era's switch blocks.
+        #[structopt(long)]
+        config: PathBuf,
+        /// The era the write-ahead log belongs to.
+        #[structopt(long)]
+        era_id: u64,
+        /// Path of the write-ahead log file to inspect.
+        #[structopt(long)]
+        input: PathBuf,
+        /// Path of the JSON file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Write a copy of a Zug write-ahead log file without corrupted, invalid or redundant entries.
+    ///
+    /// The node must not be running when the original file is replaced with the copy.
+    CompactZugWal {
+        /// Path to configuration file of the node whose storage holds the era's switch blocks.
+        #[structopt(long)]
+        config: PathBuf,
+        /// The era the write-ahead log belongs to.
+        #[structopt(long)]
+        era_id: u64,
+        /// Path of the write-ahead log file to compact.
+        #[structopt(long)]
+        input: PathBuf,
+        /// Path of the compacted write-ahead log file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
 }
 
 #[derive(Debug)]
@@ -237,6 +336,122 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::ExportGlobalState {
+                config,
+                state_root_hash,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "exporting global state");
+                crate::global_state_snapshot::export_global_state(
+                    config,
+                    state_root_hash,
+                    &output,
+                )?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportGlobalState {
+                config,
+                input,
+                state_root_hash,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "importing global state");
+                crate::global_state_snapshot::import_global_state(config, &input, state_root_hash)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::CompactGlobalState {
+                config,
+                retained_eras,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "compacting global state");
+                crate::global_state_compaction::compact_global_state(config, retained_eras)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ExportSlashingProtection {
+                config,
+                output,
+                public_key,
+            } => {
+                let config = Self::init(&config, vec![])?;
+                let path = Self::slashing_protection_path(&config)?;
+
+                info!(
+                    build_version = %crate::VERSION_STRING.as_str(),
+                    "exporting slashing protection record"
+                );
+                let interchange = SlashingProtectionDb::read(path)?.export(public_key.as_ref());
+                fs::write(&output, serde_json::to_vec_pretty(&interchange)?)
+                    .with_context(|| format!("could not write {}", output.display()))?;
+                info!(
+                    entries = interchange.entries.len(),
+                    "exported slashing protection record"
+                );
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportSlashingProtection { config, input } => {
+                let config = Self::init(&config, vec![])?;
+                let path = Self::slashing_protection_path(&config)?;
+
+                info!(
+                    build_version = %crate::VERSION_STRING.as_str(),
+                    "importing slashing protection record"
+                );
+                let encoded = fs::read(&input)
+                    .with_context(|| format!("could not read {}", input.display()))?;
+                let imported =
+                    SlashingProtectionDb::open(path)?.import(serde_json::from_slice(&encoded)?)?;
+                info!(imported, "imported slashing protection record");
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::InspectZugWal {
+                config,
+                era_id,
+                input,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "inspecting Zug WAL");
+                let inspection =
+                    crate::zug_wal::inspect_zug_wal(config, EraId::from(era_id), &input)?;
+                fs::write(&output, serde_json::to_vec_pretty(&inspection)?)
+                    .with_context(|| format!("could not write {}", output.display()))?;
+                info!(
+                    entries = inspection.entries.len(),
+                    corruptions = inspection.corruptions.len(),
+                    "inspected Zug WAL"
+                );
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::CompactZugWal {
+                config,
+                era_id,
+                input,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "compacting Zug WAL");
+                crate::zug_wal::compact_zug_wal(config, EraId::from(era_id), &input, &output)?;
+                Ok(ExitCode::Success as i32)
+            }
+        }
+    }
+
+    /// Returns the path to the slashing protection journal configured for the node.
+    fn slashing_protection_path(config: &WithDir<main_reactor::Config>) -> anyhow::Result<PathBuf> {
+        match config
+            .value()
+            .consensus
+            .slashing_protection_path(config.dir())
+        {
+            Some(path) => Ok(path),
+            None => bail!("consensus.slashing_protection_path is not configured"),
         }
     }
 
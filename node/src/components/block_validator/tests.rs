@@ -114,6 +114,15 @@ pub(super) fn new_proposed_block(
 }
 
 pub(super) fn new_deploy(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff) -> Deploy {
+    new_deploy_with_gas_price(rng, timestamp, ttl, 1)
+}
+
+pub(super) fn new_deploy_with_gas_price(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    gas_price: u64,
+) -> Deploy {
     let secret_key = SecretKey::random(rng);
     let chain_name = "chain".to_string();
     let payment = ExecutableDeployItem::ModuleBytes {
@@ -125,7 +134,6 @@ pub(super) fn new_deploy(rng: &mut TestRng, timestamp: Timestamp, ttl: TimeDiff)
         args: RuntimeArgs::new(),
     };
     let dependencies = vec![];
-    let gas_price = 1;
 
     Deploy::new(
         timestamp,
@@ -316,6 +324,30 @@ async fn transfer_deploy_mixup_and_replay() {
     assert!(!validate_block(&mut rng, timestamp, deploys, transfers).await);
 }
 
+/// Verifies that the block validator accepts deploys in any order of gas price, as long as each
+/// meets the chainspec's minimum gas price.
+#[tokio::test]
+async fn gas_price() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from_millis(200);
+    let timestamp = Timestamp::from(1000);
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let min_gas_price = chainspec.deploy_config.min_gas_price;
+    let cheap_deploy = new_deploy_with_gas_price(&mut rng, timestamp, ttl, min_gas_price);
+    let pricey_deploy = new_deploy_with_gas_price(&mut rng, timestamp, ttl, min_gas_price + 10);
+
+    // Deploys ordered by descending or ascending gas price are valid.
+    let deploys = vec![pricey_deploy.clone(), cheap_deploy.clone()];
+    assert!(validate_block(&mut rng, timestamp, deploys, vec![]).await);
+    let deploys = vec![cheap_deploy.clone(), pricey_deploy.clone()];
+    assert!(validate_block(&mut rng, timestamp, deploys, vec![]).await);
+
+    // A deploy offering less than the minimum gas price is invalid.
+    let too_cheap_deploy = new_deploy_with_gas_price(&mut rng, timestamp, ttl, min_gas_price - 1);
+    let deploys = vec![pricey_deploy, cheap_deploy, too_cheap_deploy];
+    assert!(!validate_block(&mut rng, timestamp, deploys, vec![]).await);
+}
+
 /// Verifies that the block validator fetches from multiple peers.
 #[tokio::test]
 async fn should_fetch_from_multiple_peers() {
@@ -12,11 +12,13 @@ pub(crate) mod error;
 mod leader_sequence;
 mod metrics;
 pub mod protocols;
+pub mod simulator;
 #[cfg(test)]
 pub(crate) mod tests;
 mod traits;
 pub mod utils;
 mod validator_change;
+mod validator_performance;
 
 use std::{
     borrow::Cow,
@@ -30,7 +32,7 @@ use derive_more::From;
 use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
-use casper_types::{EraId, Timestamp};
+use casper_types::{EraId, Signature, Timestamp};
 
 use crate::{
     components::Component,
@@ -58,9 +60,9 @@ use protocols::{highway::HighwayProtocol, zug::Zug};
 use traits::Context;
 
 pub use cl_context::ClContext;
-pub(crate) use config::{ChainspecConsensusExt, Config};
+pub(crate) use config::{ChainspecConsensusExt, Config, LoadSignerError};
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
-pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor, SerializedMessage};
+pub(crate) use era_supervisor::{debug::EraDump, instance_id, EraSupervisor, SerializedMessage};
 #[cfg(test)]
 pub(crate) use highway_core::highway::Vertex as HighwayVertex;
 pub(crate) use leader_sequence::LeaderSequence;
@@ -68,6 +70,7 @@ pub(crate) use protocols::highway::max_rounds_per_era;
 #[cfg(test)]
 pub(crate) use protocols::highway::HighwayMessage;
 pub(crate) use validator_change::ValidatorChange;
+pub(crate) use validator_performance::{ValidatorParticipation, ValidatorPerformance};
 
 const COMPONENT_NAME: &str = "consensus";
 
@@ -124,6 +127,11 @@ pub struct TimerId(pub u8);
 #[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct ActionId(pub u8);
 
+/// An ID to match a signature to the consensus protocol's request for it. What the IDs refer to is
+/// specific to each consensus protocol implementation.
+#[derive(DataSize, Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SignatureId(pub u64);
+
 /// Payload for a block to be proposed.
 #[derive(DataSize, Debug, From)]
 pub struct NewBlockPayload {
@@ -161,6 +169,13 @@ pub(crate) enum Event {
     },
     /// A queued action to be handled by a specific era.
     Action { era_id: EraId, action_id: ActionId },
+    /// The signer returned a signature requested by the consensus protocol in the given era, or
+    /// `None` if it failed or refused to sign.
+    Signed {
+        era_id: EraId,
+        signature_id: SignatureId,
+        signature: Option<Signature>,
+    },
     /// We are receiving the data we require to propose a new block.
     NewBlockPayload(NewBlockPayload),
     #[from]
@@ -258,6 +273,18 @@ impl Display for Event {
             Event::Action { era_id, action_id } => {
                 write!(f, "action (ID {}) for {}", action_id.0, era_id)
             }
+            Event::Signed {
+                era_id,
+                signature_id,
+                signature,
+            } => match signature {
+                Some(signature) => write!(
+                    f,
+                    "signature (ID {}) for {}: {}",
+                    signature_id.0, era_id, signature
+                ),
+                None => write!(f, "failed signature (ID {}) for {}", signature_id.0, era_id),
+            },
             Event::NewBlockPayload(NewBlockPayload {
                 era_id,
                 block_payload,
@@ -433,6 +460,11 @@ where
             Event::Action { era_id, action_id } => {
                 self.handle_action(effect_builder, rng, era_id, action_id)
             }
+            Event::Signed {
+                era_id,
+                signature_id,
+                signature,
+            } => self.handle_signature(effect_builder, rng, era_id, signature_id, signature),
             Event::Incoming(ConsensusMessageIncoming { sender, message }) => {
                 let delay_by = self.message_delay_failpoint.fire(rng).cloned();
                 if let Some(delay) = delay_by {
@@ -473,6 +505,10 @@ where
                 let validator_changes = self.get_validator_changes();
                 responder.respond(validator_changes).ignore()
             }
+            Event::ConsensusRequest(ConsensusRequest::ValidatorPerformance(responder)) => {
+                let validator_performance = self.get_validator_performance(Timestamp::now());
+                responder.respond(validator_performance).ignore()
+            }
             Event::DumpState(req @ DumpConsensusStateRequest { era_id, .. }) => {
                 let current_era = match self.current_era() {
                     None => {
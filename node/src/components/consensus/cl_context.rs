@@ -2,27 +2,33 @@ use std::sync::Arc;
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 
 use casper_hashing::Digest;
-use casper_types::{crypto, PublicKey, SecretKey, Signature};
+use casper_types::{crypto, EraId, PublicKey, SecretKey, Signature};
 
 use crate::{
-    components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
+    components::consensus::traits::{ConsensusValueT, Context, SignatureKind, ValidatorSecret},
+    signer::{LocalSigner, MessageKind, Signer, SigningRequest},
     types::BlockPayload,
 };
 
 #[derive(DataSize)]
 pub struct Keypair {
-    secret_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_key: PublicKey,
+    /// The era of the consensus protocol instance the keypair is used in.
+    era_id: EraId,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
+    pub(crate) fn new(signer: Arc<dyn Signer>, era_id: EraId) -> Self {
+        let public_key = signer.public_key().clone();
         Self {
-            secret_key,
+            signer,
             public_key,
+            era_id,
         }
     }
 
@@ -30,21 +36,97 @@ impl Keypair {
     pub(crate) fn public_key(&self) -> &PublicKey {
         &self.public_key
     }
+
+    /// Signs the hash, logging an error if the signer fails or refuses to sign.
+    fn sign_as(&self, kind: MessageKind, hash: &Digest) -> Option<Signature> {
+        match self.signer.sign(SigningRequest::new(kind, hash)) {
+            Ok(signature) => Some(signature),
+            Err(error) => {
+                warn!(%error, %hash, "failed to sign consensus message");
+                None
+            }
+        }
+    }
+}
+
+impl SignatureKind<Digest> {
+    /// Returns the kind of message to pass to the signer, for a message in the given era and
+    /// consensus protocol instance.
+    pub(crate) fn message_kind(self, era_id: EraId, instance_id: Digest) -> MessageKind {
+        match self {
+            SignatureKind::HighwayUnit { seq_number } => MessageKind::HighwayUnit {
+                era_id,
+                instance_id,
+                seq_number,
+            },
+            SignatureKind::ZugEcho {
+                round_id,
+                proposal_hash,
+            } => MessageKind::ZugEcho {
+                era_id,
+                instance_id,
+                round_id,
+                proposal_hash,
+            },
+            SignatureKind::ZugVote { round_id, vote } => MessageKind::ZugVote {
+                era_id,
+                instance_id,
+                round_id,
+                vote,
+            },
+            SignatureKind::Other => MessageKind::Other,
+        }
+    }
 }
 
 impl From<Arc<SecretKey>> for Keypair {
+    /// Creates a keypair signing with the given secret key, for use in era 0.
     fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+        Self::new(Arc::new(LocalSigner::new(secret_key)), EraId::new(0))
     }
 }
 
 impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
+    type InstanceId = Digest;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn may_block(&self) -> bool {
+        self.signer.may_block()
+    }
+
+    fn sign(&self, hash: &Digest) -> Option<Signature> {
+        self.sign_as(MessageKind::Other, hash)
+    }
+
+    fn sign_unit(&self, hash: &Digest, instance_id: &Digest, seq_number: u64) -> Option<Signature> {
+        let kind = SignatureKind::HighwayUnit { seq_number };
+        self.sign_as(kind.message_kind(self.era_id, *instance_id), hash)
+    }
+
+    fn sign_echo(
+        &self,
+        hash: &Digest,
+        instance_id: &Digest,
+        round_id: u32,
+        proposal_hash: &Digest,
+    ) -> Option<Signature> {
+        let kind = SignatureKind::ZugEcho {
+            round_id,
+            proposal_hash: *proposal_hash,
+        };
+        self.sign_as(kind.message_kind(self.era_id, *instance_id), hash)
+    }
+
+    fn sign_vote(
+        &self,
+        hash: &Digest,
+        instance_id: &Digest,
+        round_id: u32,
+        vote: bool,
+    ) -> Option<Signature> {
+        let kind = SignatureKind::ZugVote { round_id, vote };
+        self.sign_as(kind.message_kind(self.era_id, *instance_id), hash)
     }
 }
 
@@ -82,17 +164,13 @@ impl Context for ClContext {
 mod specimen_support {
     use super::Keypair;
     use crate::utils::specimen::{Cache, LargestSpecimen, SizeEstimator};
-    use casper_types::{PublicKey, SecretKey};
+    use casper_types::SecretKey;
     use std::sync::Arc;
 
     impl LargestSpecimen for Keypair {
         fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
             let secret_key = SecretKey::largest_specimen(estimator, cache);
-            let public_key = PublicKey::from(&secret_key);
-            Keypair {
-                secret_key: Arc::new(secret_key),
-                public_key,
-            }
+            Keypair::from(Arc::new(secret_key))
         }
     }
 }
@@ -1,9 +1,13 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use casper_types::{PublicKey, SecretKey};
+use casper_types::SecretKey;
 
 use crate::{
     components::consensus::{
@@ -11,6 +15,11 @@ use crate::{
         protocols::{highway::config::Config as HighwayConfig, zug::config::Config as ZugConfig},
         EraId,
     },
+    signer::{
+        self,
+        slashing_protection::{self, SlashingProtectedSigner, SlashingProtectionDb},
+        LocalSigner, RemoteSigner, RemoteSignerConfig, Signer,
+    },
     types::Chainspec,
     utils::{External, LoadError, Loadable},
 };
@@ -22,8 +31,16 @@ const DEFAULT_MAX_EXECUTION_DELAY: u64 = 3;
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub struct Config {
-    /// Path to secret key file.
+    /// Path to secret key file.  Ignored if a remote signer is configured.
     pub secret_key_path: External,
+    /// The external signer holding the secret key, if any.
+    #[serde(default)]
+    pub remote_signer: Option<RemoteSignerConfig>,
+    /// Path to the slashing protection journal recording the consensus messages signed by this
+    /// node, if any.  Every signature is checked against it first, so the node never signs
+    /// conflicting messages, even across restarts.
+    #[serde(default)]
+    pub slashing_protection_path: Option<PathBuf>,
     /// The maximum number of blocks by which execution is allowed to lag behind finalization.
     /// If it is more than that, consensus will pause, and resume once the executor has caught up.
     pub max_execution_delay: u64,
@@ -39,6 +56,8 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            remote_signer: None,
+            slashing_protection_path: None,
             max_execution_delay: DEFAULT_MAX_EXECUTION_DELAY,
             highway: HighwayConfig::default(),
             zug: ZugConfig::default(),
@@ -48,15 +67,54 @@ impl Default for Config {
 
 type LoadKeyError = LoadError<<Arc<SecretKey> as Loadable>::Error>;
 
+/// Error while setting up the signer.
+#[derive(Debug, Error)]
+pub(crate) enum LoadSignerError {
+    /// Error loading the secret key.
+    #[error("secret key load error: {0}")]
+    SecretKey(#[from] LoadKeyError),
+    /// Error connecting to the remote signer.
+    #[error("remote signer error: {0}")]
+    RemoteSigner(#[from] signer::Error),
+    /// Error opening the slashing protection journal.
+    #[error("slashing protection error: {0}")]
+    SlashingProtection(#[from] slashing_protection::Error),
+}
+
 impl Config {
-    /// Loads the secret key from the configuration file and derives the public key.
-    pub(crate) fn load_keys<P: AsRef<Path>>(
+    /// Connects to the remote signer if one is configured, otherwise loads the secret key from the
+    /// configuration file.
+    ///
+    /// If a slashing protection journal is configured, the signer is wrapped so that every
+    /// signature is checked against it.
+    pub(crate) fn load_signer<P: AsRef<Path>>(
         &self,
         root: P,
-    ) -> Result<(Arc<SecretKey>, PublicKey), LoadKeyError> {
-        let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
-        let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
-        Ok((secret_signing_key, public_key))
+    ) -> Result<Arc<dyn Signer>, LoadSignerError> {
+        let signer: Arc<dyn Signer> = if let Some(remote_signer) = &self.remote_signer {
+            let socket_path = root.as_ref().join(&remote_signer.socket_path);
+            let signer = RemoteSigner::connect(socket_path, remote_signer.request_timeout.into())?;
+            Arc::new(signer)
+        } else {
+            let secret_signing_key: Arc<SecretKey> =
+                self.secret_key_path.clone().load(root.as_ref())?;
+            Arc::new(LocalSigner::new(secret_signing_key))
+        };
+        match self.slashing_protection_path(root) {
+            Some(path) => {
+                let db = SlashingProtectionDb::open(path)?;
+                Ok(Arc::new(SlashingProtectedSigner::new(signer, db)))
+            }
+            None => Ok(signer),
+        }
+    }
+
+    /// Returns the path to the slashing protection journal, if one is configured, relative to the
+    /// given root directory.
+    pub(crate) fn slashing_protection_path<P: AsRef<Path>>(&self, root: P) -> Option<PathBuf> {
+        self.slashing_protection_path
+            .as_ref()
+            .map(|path| root.as_ref().join(path))
     }
 }
 
@@ -12,7 +12,10 @@ use casper_hashing::Digest;
 use casper_types::{bytesrepr::ToBytes, TimeDiff, Timestamp};
 
 use crate::{
-    components::consensus::{traits::Context, ActionId, TimerId},
+    components::consensus::{
+        traits::{Context, SignatureKind},
+        ActionId, SignatureId, TimerId, ValidatorPerformance,
+    },
     types::NodeId,
     NodeRng,
 };
@@ -201,6 +204,15 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     QueueAction(ActionId),
     /// Request deploys for a new block, providing the necessary context.
     CreateNewBlock(BlockContext<C>, Timestamp),
+    /// Sign the hash with our validator's key and pass the signature to `handle_signature`.
+    ///
+    /// This is only returned if signing can block, e.g. because the key is held by an external
+    /// signer, so that the protocol doesn't wait for the signature.
+    SignHash {
+        signature_id: SignatureId,
+        hash: C::Hash,
+        kind: SignatureKind<C::Hash>,
+    },
     /// A block was finalized.
     FinalizedBlock(FinalizedBlock<C>),
     /// Request validation of the consensus value, contained in a message received from the given
@@ -273,6 +285,15 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Triggers a queued action.
     fn handle_action(&mut self, action_id: ActionId, now: Timestamp) -> ProtocolOutcomes<C>;
 
+    /// Handles the signature requested via `ProtocolOutcome::SignHash`, or `None` if signing
+    /// failed.
+    fn handle_signature(
+        &mut self,
+        signature_id: SignatureId,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C>;
+
     /// Proposes a new value for consensus.
     fn propose(&mut self, proposed_block: ProposedBlock<C>, now: Timestamp) -> ProtocolOutcomes<C>;
 
@@ -315,6 +336,10 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Returns the list of all validators that were observed as faulty in this consensus instance.
     fn validators_with_evidence(&self) -> Vec<&C::ValidatorId>;
 
+    /// Returns the performance of every validator in this consensus instance, as observed by this
+    /// node, or an empty list if the instance only keeps evidence.
+    fn validator_performance(&self, now: Timestamp) -> Vec<(C::ValidatorId, ValidatorPerformance)>;
+
     /// Returns whether this instance of a protocol is an active validator.
     fn is_active(&self) -> bool;
 
@@ -31,7 +31,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp};
+use casper_types::{AsymmetricType, EraId, PublicKey, Signature, TimeDiff, Timestamp};
 
 use crate::{
     components::{
@@ -44,7 +44,8 @@ use crate::{
             metrics::Metrics,
             validator_change::{ValidatorChange, ValidatorChanges},
             ActionId, ChainspecConsensusExt, Config, ConsensusMessage, ConsensusRequestMessage,
-            Event, HighwayProtocol, NewBlockPayload, ReactorEventT, ResolveValidity, TimerId, Zug,
+            Event, HighwayProtocol, NewBlockPayload, ReactorEventT, ResolveValidity, SignatureId,
+            TimerId, ValidatorPerformance, Zug,
         },
         network::blocklist::BlocklistJustification,
     },
@@ -55,6 +56,7 @@ use crate::{
     },
     failpoints::Failpoint,
     fatal, protocol,
+    signer::{self, Signer, SigningRequest},
     types::{
         chainspec::ConsensusProtocolName, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash,
         DeployOrTransferHash, FinalizedApprovals, FinalizedBlock, MetaBlockState, NodeId,
@@ -96,7 +98,8 @@ pub struct EraSupervisor {
     /// Since eras at or before the most recent activation point are never instantiated, shortly
     /// after that there can temporarily be fewer than three entries in the map.
     open_eras: BTreeMap<EraId, Era>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_signing_key: PublicKey,
     chainspec: Arc<Chainspec>,
     config: Config,
@@ -132,20 +135,20 @@ impl EraSupervisor {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         storage_dir: &Path,
-        secret_signing_key: Arc<SecretKey>,
-        public_signing_key: PublicKey,
+        signer: Arc<dyn Signer>,
         config: Config,
         chainspec: Arc<Chainspec>,
         registry: &Registry,
     ) -> Result<Self, Error> {
         let unit_files_folder = storage_dir.join("unit_files");
         std::fs::create_dir_all(&unit_files_folder)?;
+        let public_signing_key = signer.public_key().clone();
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics = Metrics::new(registry)?;
 
         let era_supervisor = Self {
             open_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             chainspec,
             config,
@@ -259,6 +262,21 @@ impl EraSupervisor {
         result
     }
 
+    /// Returns the performance of each validator in the open eras, as observed by this node.
+    pub(super) fn get_validator_performance(
+        &self,
+        now: Timestamp,
+    ) -> BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>> {
+        self.open_eras
+            .iter()
+            .map(|(era_id, era)| {
+                let performance = era.consensus.validator_performance(now);
+                (*era_id, performance.into_iter().collect::<BTreeMap<_, _>>())
+            })
+            .filter(|(_, performance)| !performance.is_empty())
+            .collect()
+    }
+
     fn era_seed(booking_block_hash: BlockHash, key_block_seed: Digest) -> u64 {
         let result = Digest::hash_pair(booking_block_hash, key_block_seed).value();
         u64::from_le_bytes(result[0..std::mem::size_of::<u64>()].try_into().unwrap())
@@ -384,7 +402,7 @@ impl EraSupervisor {
             vec![]
         } else {
             info!(era = era_id.value(), %our_id, "start voting");
-            let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
+            let secret = Keypair::new(self.signer.clone(), era_id);
             let instance_id = self.era(era_id).consensus.instance_id();
             let unit_hash_file = self.unit_file(instance_id);
             self.era_mut(era_id).consensus.activate_validator(
@@ -691,6 +709,19 @@ impl EraSupervisor {
         })
     }
 
+    pub(super) fn handle_signature<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        era_id: EraId,
+        signature_id: SignatureId,
+        signature: Option<Signature>,
+    ) -> Effects<Event> {
+        self.delegate_to_era(effect_builder, rng, era_id, move |consensus, _| {
+            consensus.handle_signature(signature_id, signature, Timestamp::now())
+        })
+    }
+
     pub(super) fn handle_message<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -1033,6 +1064,29 @@ impl EraSupervisor {
             ProtocolOutcome::QueueAction(action_id) => effect_builder
                 .immediately()
                 .event(move |()| Event::Action { era_id, action_id }),
+            ProtocolOutcome::SignHash {
+                signature_id,
+                hash,
+                kind,
+            } => {
+                // The signer may be remote or write to disk, so we sign on a blocking thread and
+                // pass the signature to the protocol when it's done.
+                let instance_id = *self.era(era_id).consensus.instance_id();
+                let request = SigningRequest::new(kind.message_kind(era_id, instance_id), hash);
+                signer::sign_blocking(Arc::clone(&self.signer), request)
+                    .map(move |result| match result {
+                        Ok(signature) => Some(signature),
+                        Err(error) => {
+                            warn!(%error, %hash, "failed to sign consensus message");
+                            None
+                        }
+                    })
+                    .event(move |signature| Event::Signed {
+                        era_id,
+                        signature_id,
+                        signature,
+                    })
+            }
             ProtocolOutcome::CreateNewBlock(block_context, proposal_expiry) => {
                 let accusations = self
                     .iter_past(era_id, PAST_EVIDENCE_ERAS)
@@ -1377,7 +1431,11 @@ async fn execute_finalized_block<REv>(
 }
 
 /// Computes the instance ID for an era, given the era ID and the chainspec hash.
-fn instance_id(chainspec_hash: Digest, era_id: EraId, key_block_hash: BlockHash) -> Digest {
+pub(crate) fn instance_id(
+    chainspec_hash: Digest,
+    era_id: EraId,
+    key_block_hash: BlockHash,
+) -> Digest {
     Digest::hash_pair(
         key_block_hash.inner().value(),
         Digest::hash_pair(chainspec_hash, era_id.to_le_bytes()).value(),
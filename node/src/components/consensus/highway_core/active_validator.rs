@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -14,7 +15,7 @@ use casper_types::{TimeDiff, Timestamp};
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Ping, ValidVertex, Vertex, WireUnit},
+    highway::{HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
     state::{self, Panorama, State, Unit},
     ENABLE_ENDORSEMENTS,
 };
@@ -22,8 +23,9 @@ use super::{
 use crate::components::consensus::{
     consensus_protocol::BlockContext,
     highway_core::{highway::SignedWireUnit, state::Fault},
-    traits::{Context, ValidatorSecret},
+    traits::{Context, SignatureKind, ValidatorSecret},
     utils::{ValidatorIndex, Weight},
+    SignatureId,
 };
 
 /// An action taken by a validator.
@@ -42,6 +44,22 @@ pub(crate) enum Effect<C: Context> {
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
+    /// The hash needs to be signed, and the signature passed to `handle_signature`.
+    RequestSignature(SignatureId, C::Hash, SignatureKind<C::Hash>),
+}
+
+/// A vertex created by this validator that still needs to be signed.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq)]
+enum UnsignedVertex<C>
+where
+    C: Context,
+{
+    /// A new unit.
+    Unit(HashedWireUnit<C>),
+    /// A ping with the given timestamp.
+    Ping(Timestamp, C::InstanceId),
+    /// An endorsement.
+    Endorsement(Endorsement<C>),
 }
 
 /// A validator that actively participates in consensus by creating new vertices.
@@ -82,6 +100,10 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// The vertices waiting for a signature from a secret that can block while signing.
+    pending_signatures: BTreeMap<SignatureId, UnsignedVertex<C>>,
+    /// The ID for the next signature we request.
+    next_signature_id: u64,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -128,9 +150,11 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            pending_signatures: BTreeMap::new(),
+            next_signature_id: 0,
         };
         let mut effects = av.schedule_timer(start_time, state);
-        effects.push(av.send_ping(current_time, instance_id));
+        effects.extend(av.send_ping(current_time, instance_id));
         (av, effects)
     }
 
@@ -199,7 +223,7 @@ impl<C: Context> ActiveValidator<C> {
                 return effects;
             } else if timestamp == r_id.saturating_add(self.witness_offset(r_len)) {
                 let panorama = self.panorama_at(state, timestamp);
-                if let Some(witness_unit) =
+                if let Some(witness_effect) =
                     self.new_unit(panorama, timestamp, None, state, instance_id)
                 {
                     if self
@@ -208,7 +232,7 @@ impl<C: Context> ActiveValidator<C> {
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
-                    effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                    effects.push(witness_effect);
                     return effects;
                 }
             }
@@ -221,15 +245,18 @@ impl<C: Context> ActiveValidator<C> {
             one_max_round_ago.saturating_add(TimeDiff::from_millis(1)),
         ) {
             warn!(%timestamp, "too many validators offline, sending ping");
-            effects.push(self.send_ping(timestamp, instance_id));
+            effects.extend(self.send_ping(timestamp, instance_id));
         }
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, unless signing it fails.
+    pub(crate) fn send_ping(
+        &mut self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Option<Effect<C>> {
+        self.sign_vertex(UnsignedVertex::Ping(timestamp, instance_id))
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -264,17 +291,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.extend(self.endorse(uhash));
         }
         effects
     }
@@ -297,8 +318,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
+            .filter_map(|v| self.endorse(v))
             .collect()
     }
 
@@ -321,9 +341,7 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -366,7 +384,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -415,7 +432,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns the effect for a new unit with the given data, and the correct sequence number:
+    /// either the signed unit, or a request to sign it.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -425,7 +443,7 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if value.is_none() && !panorama.has_correct() {
             return None; // Wait for the first proposal before creating a unit without a value.
         }
@@ -433,6 +451,11 @@ impl<C: Context> ActiveValidator<C> {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
         }
+        if self.has_pending_unit() {
+            // The new unit would have the same sequence number as the pending one.
+            info!("not voting - waiting for the signature of our previous unit");
+            return None;
+        }
         if let Some((prop_context, _)) = self.next_proposal.take() {
             warn!(?prop_context, "canceling proposal due to unit");
         }
@@ -468,14 +491,122 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
-        write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
-            panic!(
-                "should successfully write unit's hash to {:?}, got {:?}",
-                self.unit_file, err
-            )
-        });
-        Some(swunit)
+        self.sign_vertex(UnsignedVertex::Unit(hwunit))
+    }
+
+    /// Returns whether we are waiting for the signature of one of our units.
+    fn has_pending_unit(&self) -> bool {
+        self.pending_signatures
+            .values()
+            .any(|vertex| matches!(vertex, UnsignedVertex::Unit(_)))
+    }
+
+    /// Signs the vertex and returns the effect to add it to the state and gossip it, or returns
+    /// `None` if signing fails.
+    ///
+    /// If signing can block, a `RequestSignature` effect is returned instead, and the vertex is
+    /// completed once the signature is passed to `handle_signature`.
+    fn sign_vertex(&mut self, vertex: UnsignedVertex<C>) -> Option<Effect<C>> {
+        if self.secret.may_block() {
+            let (hash, kind) = match &vertex {
+                UnsignedVertex::Unit(hwunit) => {
+                    let seq_number = hwunit.wire_unit().seq_number;
+                    (hwunit.hash(), SignatureKind::HighwayUnit { seq_number })
+                }
+                UnsignedVertex::Ping(timestamp, instance_id) => (
+                    Ping::<C>::hash(self.vidx, *timestamp, *instance_id),
+                    SignatureKind::Other,
+                ),
+                UnsignedVertex::Endorsement(endorsement) => {
+                    (endorsement.hash(), SignatureKind::Other)
+                }
+            };
+            let signature_id = SignatureId(self.next_signature_id);
+            self.next_signature_id = self.next_signature_id.saturating_add(1);
+            self.pending_signatures.insert(signature_id, vertex);
+            return Some(Effect::RequestSignature(signature_id, hash, kind));
+        }
+        let signed_vertex = match vertex {
+            UnsignedVertex::Unit(hwunit) => {
+                Vertex::Unit(SignedWireUnit::new(hwunit, &self.secret)?)
+            }
+            UnsignedVertex::Ping(timestamp, instance_id) => {
+                Vertex::Ping(Ping::new(self.vidx, timestamp, instance_id, &self.secret)?)
+            }
+            UnsignedVertex::Endorsement(endorsement) => {
+                let signature = self.secret.sign(&endorsement.hash())?;
+                Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+            }
+        };
+        Some(self.new_vertex(signed_vertex))
+    }
+
+    /// Returns actions a validator needs to take upon receiving a requested signature, or `None`
+    /// if signing failed.
+    pub(crate) fn handle_signature(
+        &mut self,
+        signature_id: SignatureId,
+        maybe_signature: Option<C::Signature>,
+        state: &State<C>,
+    ) -> Vec<Effect<C>> {
+        let vertex = match self.pending_signatures.remove(&signature_id) {
+            Some(vertex) => vertex,
+            None => {
+                warn!(?signature_id, "received signature that wasn't requested");
+                return vec![];
+            }
+        };
+        let signature = match maybe_signature {
+            Some(signature) => signature,
+            None => {
+                warn!(?vertex, "dropping vertex that couldn't be signed");
+                return vec![];
+            }
+        };
+        let signed_vertex = match vertex {
+            UnsignedVertex::Unit(hashed_wire_unit) => {
+                // The protocol state could have changed while we were waiting for the signature.
+                let wire_unit = hashed_wire_unit.wire_unit();
+                if self.is_faulty(state)
+                    || wire_unit.panorama[self.vidx] != state.panorama()[self.vidx]
+                {
+                    warn!(
+                        ?hashed_wire_unit,
+                        "dropping signed unit: our last unit has changed"
+                    );
+                    return vec![];
+                }
+                Vertex::Unit(SignedWireUnit {
+                    hashed_wire_unit,
+                    signature,
+                })
+            }
+            UnsignedVertex::Ping(timestamp, instance_id) => Vertex::Ping(Ping::with_signature(
+                self.vidx,
+                timestamp,
+                instance_id,
+                signature,
+            )),
+            UnsignedVertex::Endorsement(endorsement) => {
+                Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+            }
+        };
+        vec![self.new_vertex(signed_vertex)]
+    }
+
+    /// Returns the effect to add our newly signed vertex to the protocol state and gossip it.
+    ///
+    /// A new unit is first written to the unit file, so that we don't equivocate after a restart.
+    fn new_vertex(&self, vertex: Vertex<C>) -> Effect<C> {
+        if let Vertex::Unit(swunit) = &vertex {
+            write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
+                panic!(
+                    "should successfully write unit's hash to {:?}, got {:?}",
+                    self.unit_file, err
+                )
+            });
+        }
+        Effect::NewVertex(ValidVertex(vertex))
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -581,11 +712,10 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
+    /// Creates endorsement of the `vhash`, unless signing it fails.
+    fn endorse(&mut self, vhash: &C::Hash) -> Option<Effect<C>> {
         let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+        self.sign_vertex(UnsignedVertex::Endorsement(endorsement))
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -1010,7 +1140,8 @@ mod tests {
             TEST_INSTANCE_ID,
         );
 
-        let ping = Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping =
+            Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
 
         // The ping is suspicious if it is newer than the latest ping (or unit) that has been added
         // to the state.
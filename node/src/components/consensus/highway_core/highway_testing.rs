@@ -27,12 +27,12 @@ use super::{
 use crate::{
     components::consensus::{
         consensus_protocol::FinalizedBlock,
-        tests::{
-            consensus_des_testing::{
+        simulator::{
+            queue::QueueEntry,
+            virtual_net::{
                 DeliverySchedule, Fault as DesFault, Message, Node, Target, TargetedMessage,
                 ValidatorId, VirtualNet,
             },
-            queue::QueueEntry,
         },
         traits::{ConsensusValueT, Context, ValidatorSecret},
         utils::{Validators, Weight},
@@ -124,6 +124,9 @@ impl From<Effect<TestContext>> for HighwayMessage {
                 HighwayMessage::RequestBlock(block_context)
             }
             Effect::WeAreFaulty(fault) => HighwayMessage::WeAreFaulty(Box::new(fault)),
+            Effect::RequestSignature(..) => {
+                unreachable!("test validators sign without blocking")
+            }
         }
     }
 }
@@ -285,7 +288,7 @@ impl HighwayValidator {
                                 }
                                 let secret = TestSecret(wunit2.creator.0.into());
                                 let hwunit2 = wunit2.into_hashed();
-                                let swunit2 = SignedWireUnit::new(hwunit2, &secret);
+                                let swunit2 = SignedWireUnit::new(hwunit2, &secret).unwrap();
                                 let vertex2 = Box::new(Vertex::Unit(swunit2));
                                 vec![msg, HighwayMessage::NewVertex(vertex2)]
                             }
@@ -1038,9 +1041,10 @@ impl Display for HashWrapper {
 impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
+    type InstanceId = u64;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
@@ -1081,7 +1085,7 @@ mod test_harness {
         TEST_MIN_ROUND_LEN,
     };
     use crate::{
-        components::consensus::tests::consensus_des_testing::{Fault as DesFault, ValidatorId},
+        components::consensus::simulator::virtual_net::{Fault as DesFault, ValidatorId},
         logging,
     };
     use logging::{LoggingConfig, LoggingFormat};
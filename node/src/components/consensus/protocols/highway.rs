@@ -41,7 +41,7 @@ use crate::{
         protocols,
         traits::{ConsensusValueT, Context},
         utils::ValidatorIndex,
-        ActionId, TimerId,
+        ActionId, SignatureId, TimerId, ValidatorPerformance,
     },
     types::{Chainspec, NodeId},
     NodeRng,
@@ -258,6 +258,13 @@ impl<C: Context + 'static> HighwayProtocol<C> {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
             }
+            AvEffect::RequestSignature(signature_id, hash, kind) => {
+                vec![ProtocolOutcome::SignHash {
+                    signature_id,
+                    hash,
+                    kind,
+                }]
+            }
         }
     }
 
@@ -377,7 +384,7 @@ impl<C: Context + 'static> HighwayProtocol<C> {
     fn calculate_round_length(&mut self, vv: &ValidVertex<C>, now: Timestamp) {
         let new_round_len = self
             .round_success_meter
-            .calculate_new_length(self.highway.state());
+            .calculate_new_length(self.highway.state(), now);
         // If the vertex contains a proposal, register it in the success meter.
         // It's important to do this _after_ the calculation above - otherwise we might try to
         // register the proposal before the meter is aware that a new round has started, and it
@@ -1011,6 +1018,16 @@ where
         }
     }
 
+    fn handle_signature(
+        &mut self,
+        signature_id: SignatureId,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let effects = self.highway.handle_signature(signature_id, signature, now);
+        self.process_av_effects(effects, now)
+    }
+
     fn propose(&mut self, proposed_block: ProposedBlock<C>, now: Timestamp) -> ProtocolOutcomes<C> {
         let (value, block_context) = proposed_block.destructure();
         let effects = self.highway.propose(value, block_context);
@@ -1121,6 +1138,13 @@ where
         self.highway.validators_with_evidence().collect()
     }
 
+    fn validator_performance(&self, now: Timestamp) -> Vec<(C::ValidatorId, ValidatorPerformance)> {
+        if self.evidence_only {
+            return vec![];
+        }
+        participation::validator_performance(&self.highway, now)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
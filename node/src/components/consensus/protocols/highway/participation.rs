@@ -1,4 +1,4 @@
-use std::cmp::Reverse;
+use std::{cmp::Reverse, collections::BTreeMap};
 
 use casper_types::Timestamp;
 
@@ -6,10 +6,11 @@ use crate::{
     components::consensus::{
         highway_core::{
             highway::Highway,
-            state::{Fault, State},
+            state::{self, Fault, State},
         },
         traits::Context,
         utils::ValidatorIndex,
+        ValidatorParticipation, ValidatorPerformance,
     },
     utils::div_round,
 };
@@ -49,6 +50,17 @@ impl Status {
         }
         None
     }
+
+    /// Returns the participation status corresponding to the result of `for_index`.
+    fn participation(status: Option<Status>) -> ValidatorParticipation {
+        match status {
+            None => ValidatorParticipation::Active,
+            Some(Status::LastSeenSecondsAgo(_)) => ValidatorParticipation::Offline,
+            Some(Status::Inactive) => ValidatorParticipation::Inactive,
+            Some(Status::Equivocated) => ValidatorParticipation::Equivocated,
+            Some(Status::EquivocatedInOtherEra) => ValidatorParticipation::EquivocatedInOtherEra,
+        }
+    }
 }
 
 /// A map of status (faulty, inactive) by validator ID.
@@ -103,3 +115,51 @@ impl<C: Context> Participation<C> {
         }
     }
 }
+
+/// Returns every validator's performance as seen by the Highway instance.
+///
+/// Proposals are only counted in rounds in which the validator created units: Highway rounds
+/// depend on the creator's round length, which is unknown while they are offline.
+pub(crate) fn validator_performance<C: Context>(
+    highway: &Highway<C>,
+    now: Timestamp,
+) -> Vec<(C::ValidatorId, ValidatorPerformance)> {
+    let state = highway.state();
+    let min_round_len = state.params().min_round_length();
+    highway
+        .validators()
+        .enumerate_ids()
+        .map(|(idx, v_id)| {
+            let participation = Status::participation(Status::for_index(idx, state, now));
+            let last_seen = (!state.panorama()[idx].is_none()).then(|| state.last_seen(idx));
+            let mut performance = ValidatorPerformance {
+                participation,
+                last_seen,
+                proposals_made: 0,
+                proposals_missed: 0,
+                round_exponent: None,
+            };
+            if let Some(latest_hash) = state.panorama()[idx].correct() {
+                performance.round_exponent = state
+                    .unit(latest_hash)
+                    .round_len()
+                    .millis()
+                    .checked_div(min_round_len.millis())
+                    .map(|ratio| ratio.trailing_zeros() as u8);
+                // Whether the validator made a proposal, by round in which they were the leader.
+                let mut led_rounds = BTreeMap::new();
+                for (hash, unit) in state.swimlane(latest_hash) {
+                    let round_id = state::round_id(unit.timestamp, unit.round_len());
+                    if state.leader(round_id) == idx {
+                        let proposed = led_rounds.entry(round_id).or_insert(false);
+                        *proposed = *proposed || state.maybe_block(hash).is_some();
+                    }
+                }
+                let made = led_rounds.values().filter(|proposed| **proposed).count();
+                performance.proposals_made = made as u64;
+                performance.proposals_missed = led_rounds.len().saturating_sub(made) as u64;
+            }
+            (v_id.clone(), performance)
+        })
+        .collect()
+}
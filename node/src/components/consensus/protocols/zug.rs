@@ -92,9 +92,9 @@ use crate::{
         },
         era_supervisor::SerializedMessage,
         protocols,
-        traits::{ConsensusValueT, Context},
+        traits::{ConsensusValueT, Context, SignatureKind, ValidatorSecret},
         utils::{ValidatorIndex, ValidatorMap, Validators, Weight},
-        ActionId, LeaderSequence, TimerId,
+        ActionId, LeaderSequence, SignatureId, TimerId, ValidatorPerformance,
     },
     types::{Chainspec, NodeId},
     utils, NodeRng,
@@ -102,12 +102,13 @@ use crate::{
 use fault::Fault;
 use message::{Content, SignedMessage, SyncResponse};
 use params::Params;
-use participation::{Participation, ParticipationStatus};
+use participation::{validator_performance, Participation, ParticipationStatus};
 use proposal::{HashedProposal, Proposal};
 use round::Round;
 use wal::{Entry, ReadWal, WriteWal};
 
 pub(crate) use message::{Message, SyncRequest};
+pub(crate) use wal::{ReadWalError, WalCompactionSummary, WalInspection, WriteWalError};
 
 /// The timer for syncing with a random peer.
 const TIMER_ID_SYNC_PEER: TimerId = TimerId(0);
@@ -135,6 +136,21 @@ where
 {
     idx: ValidatorIndex,
     secret: C::ValidatorSecret,
+    /// Our messages that are waiting for their signatures, if the secret signs asynchronously.
+    pending_signatures: BTreeMap<SignatureId, PendingMessage<C>>,
+    /// The ID of the next signature we request.
+    next_signature_id: u64,
+}
+
+impl<C: Context> ActiveValidator<C> {
+    fn new(idx: ValidatorIndex, secret: C::ValidatorSecret) -> Self {
+        ActiveValidator {
+            idx,
+            secret,
+            pending_signatures: BTreeMap::new(),
+            next_signature_id: 0,
+        }
+    }
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -143,10 +159,23 @@ impl<C: Context> Debug for ActiveValidator<C> {
             .debug_struct("ActiveValidator")
             .field("idx", &self.idx)
             .field("secret", &"<REDACTED>")
+            .field("pending_signatures", &self.pending_signatures)
             .finish()
     }
 }
 
+/// One of our own messages whose signature has been requested but not received yet.
+#[derive(Debug, DataSize)]
+struct PendingMessage<C>
+where
+    C: Context,
+{
+    round_id: RoundId,
+    content: Content<C>,
+    /// Our proposal, if the message is its echo.
+    proposal: Option<HashedProposal<C>>,
+}
+
 /// Contains the state required for the protocol.
 #[derive(Debug, DataSize)]
 pub(crate) struct Zug<C>
@@ -182,6 +211,8 @@ where
     config: config::Config,
     /// This is a signed message for every validator we have received a signature from.
     active: ValidatorMap<Option<SignedMessage<C>>>,
+    /// The time we last received a valid signed message from each validator.
+    last_seen: ValidatorMap<Option<Timestamp>>,
     /// The lowest round ID of a block that could still be finalized in the future.
     first_non_finalized_round_id: RoundId,
     /// The lowest round that needs to be considered in `upgrade`.
@@ -213,6 +244,7 @@ impl<C: Context + 'static> Zug<C> {
     ) -> Zug<C> {
         let weights = protocols::common::validator_weights::<C>(&validators);
         let active: ValidatorMap<_> = weights.iter().map(|_| None).collect();
+        let last_seen: ValidatorMap<_> = weights.iter().map(|_| None).collect();
 
         // Use the estimate from the previous era as the proposal timeout. Start with one minimum
         // timeout times the grace period factor: This is what we would settle on if proposals
@@ -257,6 +289,7 @@ impl<C: Context + 'static> Zug<C> {
             evidence_only: false,
             faults,
             active,
+            last_seen,
             config: config.clone(),
             params,
             proposal_timeout_millis,
@@ -626,47 +659,143 @@ impl<C: Context + 'static> Zug<C> {
         self.leader_sequence.leader(u64::from(round_id))
     }
 
+    /// If we are an active validator and it would be safe for us to sign this message and we
+    /// haven't signed it before, we sign it, add it to our state and gossip it to the network,
+    /// together with our `proposal` if the message is its echo.
+    ///
+    /// If our secret may block while signing, the signature is requested instead, and the message
+    /// is only added and gossiped once `handle_signature` receives it.
+    ///
+    /// Does not call `update`!
     fn create_message(
         &mut self,
         round_id: RoundId,
         content: Content<C>,
-    ) -> Option<SignedMessage<C>> {
-        let (validator_idx, secret_key) = if let Some(active_validator) = &self.active_validator {
-            (active_validator.idx, &active_validator.secret)
+        proposal: Option<HashedProposal<C>>,
+    ) -> ProtocolOutcomes<C> {
+        let validator_idx = if let Some(active_validator) = &self.active_validator {
+            active_validator.idx
         } else {
-            return None;
+            return vec![];
         };
         if self.paused {
-            return None;
+            return vec![];
         }
         let already_signed = match &content {
             Content::Echo(_) => self.has_echoed(round_id, validator_idx),
             Content::Vote(_) => self.has_voted(round_id, validator_idx),
         };
-        if already_signed {
-            return None;
+        let is_echo = matches!(content, Content::Echo(_));
+        if already_signed || self.is_signing(round_id, is_echo) {
+            return vec![];
         }
-        let signed_msg = SignedMessage::sign_new(
+        let instance_id = *self.instance_id();
+        let active_validator = match &mut self.active_validator {
+            Some(active_validator) => active_validator,
+            None => return vec![],
+        };
+        if active_validator.secret.may_block() {
+            let hash = SignedMessage::hash_fields(round_id, &instance_id, &content, validator_idx);
+            let kind = match content {
+                Content::Echo(proposal_hash) => SignatureKind::ZugEcho {
+                    round_id,
+                    proposal_hash,
+                },
+                Content::Vote(vote) => SignatureKind::ZugVote { round_id, vote },
+            };
+            let signature_id = SignatureId(active_validator.next_signature_id);
+            active_validator.next_signature_id =
+                active_validator.next_signature_id.saturating_add(1);
+            let pending = PendingMessage {
+                round_id,
+                content,
+                proposal,
+            };
+            active_validator
+                .pending_signatures
+                .insert(signature_id, pending);
+            return vec![ProtocolOutcome::SignHash {
+                signature_id,
+                hash,
+                kind,
+            }];
+        }
+        let maybe_signed_msg = SignedMessage::sign_new(
             round_id,
-            *self.instance_id(),
+            instance_id,
             content,
             validator_idx,
-            secret_key,
+            &active_validator.secret,
         );
-        // We only return the new message if we are able to record it. If that fails we
+        match maybe_signed_msg {
+            Some(signed_msg) => self.add_own_message(signed_msg, proposal),
+            None => vec![],
+        }
+    }
+
+    /// Returns whether we are waiting for the signature of our own echo (if `is_echo`) or vote in
+    /// the given round.
+    fn is_signing(&self, round_id: RoundId, is_echo: bool) -> bool {
+        self.active_validator
+            .iter()
+            .flat_map(|active_validator| active_validator.pending_signatures.values())
+            .any(|pending| {
+                pending.round_id == round_id
+                    && matches!(pending.content, Content::Echo(_)) == is_echo
+            })
+    }
+
+    /// Adds our own newly signed message to our state and gossips it to the network, together
+    /// with our `proposal` if the message is its echo.
+    ///
+    /// Does not call `update`!
+    fn add_own_message(
+        &mut self,
+        signed_msg: SignedMessage<C>,
+        proposal: Option<HashedProposal<C>>,
+    ) -> ProtocolOutcomes<C> {
+        let round_id = signed_msg.round_id;
+        // We only gossip the new message if we are able to record it. If that fails we
         // wouldn't know about our own message after a restart and risk double-signing.
-        if self.record_entry(&Entry::SignedMessage(signed_msg.clone()))
-            && self.add_content(signed_msg.clone())
+        if !self.record_entry(&Entry::SignedMessage(signed_msg.clone()))
+            || !self.add_content(signed_msg.clone())
         {
-            Some(signed_msg)
-        } else {
             debug!(
                 our_idx = self.our_idx(),
                 %round_id,
-                ?content,
+                content = ?signed_msg.content,
                 "couldn't record a signed message in the WAL or add it to the protocol state"
             );
-            None
+            return vec![];
+        }
+        let hashed_prop = match proposal {
+            Some(hashed_prop) => hashed_prop,
+            None => {
+                let message = Message::Signed(signed_msg);
+                return vec![ProtocolOutcome::CreatedGossipMessage(
+                    SerializedMessage::from_message(&message),
+                )];
+            }
+        };
+        let prop_msg = Message::Proposal {
+            round_id,
+            proposal: hashed_prop.inner().clone(),
+            instance_id: *self.instance_id(),
+            echo: signed_msg,
+        };
+        if !self.record_entry(&Entry::Proposal(hashed_prop.inner().clone(), round_id)) {
+            error!(
+                our_idx = self.our_idx(),
+                "could not record own proposal in WAL"
+            );
+            vec![]
+        } else if self.round_mut(round_id).insert_proposal(hashed_prop) {
+            self.mark_dirty(round_id);
+            vec![ProtocolOutcome::CreatedGossipMessage(
+                SerializedMessage::from_message(&prop_msg),
+            )]
+        } else {
+            vec![]
         }
     }
 
@@ -679,14 +808,7 @@ impl<C: Context + 'static> Zug<C> {
         round_id: RoundId,
         content: Content<C>,
     ) -> ProtocolOutcomes<C> {
-        let maybe_signed_msg = self.create_message(round_id, content);
-        maybe_signed_msg
-            .into_iter()
-            .map(|signed_msg| {
-                let message = Message::Signed(signed_msg);
-                ProtocolOutcome::CreatedGossipMessage(SerializedMessage::from_message(&message))
-            })
-            .collect()
+        self.create_message(round_id, content, None)
     }
 
     /// When we receive evidence for a fault, we must notify the rest of the network of this
@@ -1052,6 +1174,7 @@ impl<C: Context + 'static> Zug<C> {
             warn!(our_idx, ?signed_msg, %sender, "invalid signature",);
             return vec![ProtocolOutcome::Disconnect(sender)];
         }
+        self.last_seen[validator_idx] = Some(now);
 
         if let Some((content2, signature2)) = self.detect_fault(&signed_msg) {
             let evidence_msg = Message::Evidence(signed_msg.clone(), content2, signature2);
@@ -1427,6 +1550,7 @@ impl<C: Context + 'static> Zug<C> {
                             | ProtocolOutcome::ScheduleTimer(_, _)
                             | ProtocolOutcome::QueueAction(_)
                             | ProtocolOutcome::CreateNewBlock(_, _)
+                            | ProtocolOutcome::SignHash { .. }
                             | ProtocolOutcome::DoppelgangerDetected
                             | ProtocolOutcome::Disconnect(_) => false,
                         }));
@@ -1867,8 +1991,10 @@ impl<C: Context + 'static> Zug<C> {
             Some((_, round_id, _)) if round_id == self.current_round => return vec![],
             _ => {}
         }
-        if self.round_mut(self.current_round).has_proposal() {
-            return vec![]; // We already made a proposal.
+        if self.round_mut(self.current_round).has_proposal()
+            || self.is_signing(self.current_round, true)
+        {
+            return vec![]; // We already made a proposal, or are signing its echo.
         }
         let ancestor_values = match maybe_parent_round_id {
             Some(parent_round_id)
@@ -1900,33 +2026,9 @@ impl<C: Context + 'static> Zug<C> {
     /// inserts them into our protocol state and gossips them.
     fn create_echo_and_proposal(&mut self, proposal: Proposal<C>) -> ProtocolOutcomes<C> {
         let round_id = self.current_round;
-        let hashed_prop = HashedProposal::new(proposal.clone());
+        let hashed_prop = HashedProposal::new(proposal);
         let echo_content = Content::Echo(*hashed_prop.hash());
-        let echo = if let Some(echo) = self.create_message(round_id, echo_content) {
-            echo
-        } else {
-            return vec![];
-        };
-        let prop_msg = Message::Proposal {
-            round_id,
-            proposal,
-            instance_id: *self.instance_id(),
-            echo,
-        };
-        if !self.record_entry(&Entry::Proposal(hashed_prop.inner().clone(), round_id)) {
-            error!(
-                our_idx = self.our_idx(),
-                "could not record own proposal in WAL"
-            );
-            vec![]
-        } else if self.round_mut(round_id).insert_proposal(hashed_prop) {
-            self.mark_dirty(round_id);
-            vec![ProtocolOutcome::CreatedGossipMessage(
-                SerializedMessage::from_message(&prop_msg),
-            )]
-        } else {
-            vec![]
-        }
+        self.create_message(round_id, echo_content, Some(hashed_prop))
     }
 
     /// Returns a parent if a block with that parent could be proposed in the current round, and the
@@ -2232,6 +2334,55 @@ where
         vec![]
     }
 
+    fn handle_signature(
+        &mut self,
+        signature_id: SignatureId,
+        maybe_signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let (validator_idx, pending) = match &mut self.active_validator {
+            Some(active_validator) => {
+                match active_validator.pending_signatures.remove(&signature_id) {
+                    Some(pending) => (active_validator.idx, pending),
+                    None => {
+                        warn!(
+                            our_idx = active_validator.idx.0,
+                            ?signature_id,
+                            "received a signature that was not requested"
+                        );
+                        return vec![];
+                    }
+                }
+            }
+            None => {
+                debug!(?signature_id, "ignoring signature; validator deactivated");
+                return vec![];
+            }
+        };
+        let signature = match maybe_signature {
+            Some(signature) => signature,
+            None => {
+                warn!(
+                    our_idx = validator_idx.0,
+                    round_id = pending.round_id,
+                    content = ?pending.content,
+                    "failed to sign our message; dropping it"
+                );
+                return vec![];
+            }
+        };
+        let signed_msg = SignedMessage {
+            round_id: pending.round_id,
+            instance_id: *self.instance_id(),
+            content: pending.content,
+            validator_idx,
+            signature,
+        };
+        let mut outcomes = self.add_own_message(signed_msg, pending.proposal);
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
     fn propose(&mut self, proposed_block: ProposedBlock<C>, now: Timestamp) -> ProtocolOutcomes<C> {
         let maybe_parent_round_id = if let Some((block_context, round_id, maybe_parent_round_id)) =
             self.pending_proposal.take()
@@ -2324,7 +2475,7 @@ where
                 return outcomes;
             }
             info!(our_idx = idx.0, "start voting");
-            self.active_validator = Some(ActiveValidator { idx, secret });
+            self.active_validator = Some(ActiveValidator::new(idx, secret));
             debug!(
                 our_idx = idx.0,
                 %now,
@@ -2415,6 +2566,16 @@ where
             .collect()
     }
 
+    fn validator_performance(
+        &self,
+        _now: Timestamp,
+    ) -> Vec<(C::ValidatorId, ValidatorPerformance)> {
+        if self.evidence_only {
+            return vec![];
+        }
+        validator_performance(self)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -2556,6 +2717,7 @@ mod specimen_support {
                 LargestSpecimen::largest_specimen(estimator, cache),
                 &LargestSpecimen::largest_specimen(estimator, cache),
             )
+            .expect("should sign with local key")
         }
     }
 
@@ -25,12 +25,12 @@ use crate::{
         consensus_protocol::{
             ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
         },
-        tests::{
-            consensus_des_testing::{
+        simulator::{
+            queue::QueueEntry,
+            virtual_net::{
                 DeliverySchedule, Fault as DesFault, Message, Node, Target, TargetedMessage,
                 ValidatorId, VirtualNet,
             },
-            queue::QueueEntry,
         },
         traits::{ConsensusValueT, Context, ValidatorSecret},
         utils::{Validators, Weight},
@@ -153,6 +153,9 @@ impl From<ProtocolOutcome<TestContext>> for ZugMessage {
             ProtocolOutcome::HandledProposedBlock(proposed_block) => {
                 ZugMessage::HandledProposedBlock(proposed_block)
             }
+            ProtocolOutcome::SignHash { .. } => {
+                unreachable!("test validators sign without blocking")
+            }
         }
     }
 }
@@ -315,7 +318,8 @@ impl ZugValidator {
                                     )),
                                     signed_msg.validator_idx,
                                     &TestSecret(signed_msg.validator_idx.0.into()),
-                                );
+                                )
+                                .unwrap();
                                 vec![
                                     ZugMessage::GossipMessage(SerializedMessage::from_message(
                                         &ZugProtocolMessage::Signed(conflicting_message),
@@ -330,7 +334,8 @@ impl ZugValidator {
                                     Content::<TestContext>::Vote(!vote),
                                     signed_msg.validator_idx,
                                     &TestSecret(signed_msg.validator_idx.0.into()),
-                                );
+                                )
+                                .unwrap();
                                 vec![
                                     ZugMessage::GossipMessage(SerializedMessage::from_message(
                                         &ZugProtocolMessage::Signed(conflicting_message),
@@ -1010,9 +1015,10 @@ impl Display for HashWrapper {
 impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
+    type InstanceId = u64;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
@@ -1050,7 +1056,7 @@ mod test_harness {
     use crate::{
         components::consensus::{
             consensus_protocol::ConsensusProtocol,
-            tests::consensus_des_testing::{Fault as DesFault, ValidatorId},
+            simulator::virtual_net::{Fault as DesFault, ValidatorId},
         },
         logging,
     };
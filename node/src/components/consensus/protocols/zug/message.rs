@@ -128,22 +128,29 @@ where
 }
 
 impl<C: Context> SignedMessage<C> {
-    /// Creates a new signed message with a valid signature.
+    /// Creates a new signed message with a valid signature, or returns `None` if the signer refused
+    /// or failed to sign it.
     pub(crate) fn sign_new(
         round_id: RoundId,
         instance_id: C::InstanceId,
         content: Content<C>,
         validator_idx: ValidatorIndex,
         secret: &C::ValidatorSecret,
-    ) -> SignedMessage<C> {
+    ) -> Option<SignedMessage<C>> {
         let hash = Self::hash_fields(round_id, &instance_id, &content, validator_idx);
-        SignedMessage {
+        let signature = match content {
+            Content::Echo(proposal_hash) => {
+                secret.sign_echo(&hash, &instance_id, round_id, &proposal_hash)?
+            }
+            Content::Vote(vote) => secret.sign_vote(&hash, &instance_id, round_id, vote)?,
+        };
+        Some(SignedMessage {
             round_id,
             instance_id,
             content,
             validator_idx,
-            signature: secret.sign(&hash),
-        }
+            signature,
+        })
     }
 
     /// Creates a new signed message with the alternative content and signature.
@@ -167,7 +174,7 @@ impl<C: Context> SignedMessage<C> {
     }
 
     /// Returns the hash of all fields except the signature.
-    fn hash_fields(
+    pub(super) fn hash_fields(
         round_id: RoundId,
         instance_id: &C::InstanceId,
         content: &Content<C>,
@@ -1,9 +1,10 @@
 use std::fmt::Debug;
 
 use crate::components::consensus::{
-    protocols::zug::{Fault, RoundId, Zug},
+    protocols::zug::{Fault, Round, RoundId, Zug},
     traits::Context,
-    utils::ValidatorIndex,
+    utils::{ValidatorIndex, ValidatorMap},
+    ValidatorParticipation, ValidatorPerformance,
 };
 
 /// A map of status (faulty, inactive) by validator ID.
@@ -57,4 +58,51 @@ impl ParticipationStatus {
         }
         Some(ParticipationStatus::Inactive)
     }
+
+    /// Returns the participation status corresponding to the result of `for_index`.
+    fn participation(status: Option<ParticipationStatus>) -> ValidatorParticipation {
+        match status {
+            None => ValidatorParticipation::Active,
+            Some(ParticipationStatus::LastSeenInRound(_)) => ValidatorParticipation::Offline,
+            Some(ParticipationStatus::Inactive) => ValidatorParticipation::Inactive,
+            Some(ParticipationStatus::Equivocated) => ValidatorParticipation::Equivocated,
+            Some(ParticipationStatus::EquivocatedInOtherEra) => {
+                ValidatorParticipation::EquivocatedInOtherEra
+            }
+        }
+    }
+}
+
+/// Returns every validator's performance as seen by the Zug instance.
+///
+/// A proposal counts as missed if we have none in a round before the current one, i.e. the round
+/// was skipped or we haven't received the leader's proposal yet.
+pub(super) fn validator_performance<C: Context + 'static>(
+    zug: &Zug<C>,
+) -> Vec<(C::ValidatorId, ValidatorPerformance)> {
+    // The number of proposals made and missed by each validator.
+    let mut proposals: ValidatorMap<(u64, u64)> = zug.validators.iter().map(|_| (0, 0)).collect();
+    for round_id in 0..zug.current_round {
+        let (made, missed) = &mut proposals[zug.leader(round_id)];
+        if zug.round(round_id).and_then(Round::proposal).is_some() {
+            *made = made.saturating_add(1);
+        } else {
+            *missed = missed.saturating_add(1);
+        }
+    }
+    zug.validators
+        .enumerate_ids()
+        .map(|(idx, v_id)| {
+            let status = ParticipationStatus::for_index(idx, zug);
+            let (proposals_made, proposals_missed) = proposals[idx];
+            let performance = ValidatorPerformance {
+                participation: ParticipationStatus::participation(status),
+                last_seen: zug.last_seen[idx],
+                proposals_made,
+                proposals_missed,
+                round_exponent: None,
+            };
+            (v_id.clone(), performance)
+        })
+        .collect()
 }
@@ -2,7 +2,7 @@ use super::*;
 
 use std::{collections::BTreeSet, sync::Arc};
 
-use casper_types::{PublicKey, SecretKey, Timestamp, U512};
+use casper_types::{EraId, PublicKey, SecretKey, Signature, Timestamp, U512};
 use tempfile::tempdir;
 use tracing::info;
 
@@ -18,7 +18,9 @@ use crate::{
             BOB_SECRET_KEY, CAROL_PUBLIC_KEY, CAROL_SECRET_KEY,
         },
         traits::Context,
+        ValidatorParticipation,
     },
+    signer::{self, LocalSigner, MessageKind, Signer, SigningRequest},
     testing,
     types::BlockPayload,
 };
@@ -77,7 +79,7 @@ fn create_signed_message(
 ) -> SignedMessage<ClContext> {
     let validator_idx = validators.get_index(keypair.public_key()).unwrap();
     let instance_id = ClContext::hash(INSTANCE_ID_DATA);
-    SignedMessage::sign_new(round_id, instance_id, content, validator_idx, keypair)
+    SignedMessage::sign_new(round_id, instance_id, content, validator_idx, keypair).unwrap()
 }
 
 /// Creates a `Message::Signed`.
@@ -1037,3 +1039,165 @@ fn update_proposal_timeout() {
         );
     }
 }
+
+/// Tests that the validators' participation, last seen time and proposals are reported.
+///
+/// Carol is the leader in round 0 but is offline, Alice makes proposals in rounds 1 and 2, and Bob
+/// equivocates.
+#[test]
+fn zug_validator_performance() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 10, 30);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+
+    // The first round leaders are Carol, Alice, Alice.
+    let mut zug = new_test_zug(weights, vec![], &[carol_idx, alice_idx, alice_idx]);
+
+    let alice_kp = Keypair::from(ALICE_SECRET_KEY.clone());
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+
+    let sender = *ALICE_NODE_ID;
+    let mut timestamp = Timestamp::now();
+
+    let proposal1 = Proposal {
+        timestamp,
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+
+    let proposal2 = Proposal {
+        timestamp: timestamp + zug.params.min_block_time(),
+        maybe_block: Some(new_payload(true)),
+        maybe_parent_round_id: Some(1),
+        inactive: Some(iter::once(carol_idx).collect()),
+    };
+
+    timestamp += zug.params.min_block_time();
+
+    // Alice makes proposals in rounds 1 and 2 and votes for them, and to skip round 0. Bob
+    // equivocates, so that rounds 0 to 2 are decided.
+    let msg = create_proposal_message(1, &proposal1, &validators, &alice_kp);
+    expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
+    let msg = create_message(&validators, 1, vote(true), &alice_kp);
+    expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
+    let msg = create_proposal_message(2, &proposal2, &validators, &alice_kp);
+    expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
+    let msg = create_message(&validators, 2, vote(true), &alice_kp);
+    expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
+    let msg = create_message(&validators, 0, vote(false), &alice_kp);
+    expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
+    let msg = create_message(&validators, 3, vote(true), &bob_kp);
+    expect_no_gossip_block_finalized(zug.handle_message(&mut rng, sender, msg, timestamp));
+    let msg = create_message(&validators, 3, vote(false), &bob_kp);
+    let outcomes = zug.handle_message(&mut rng, sender, msg, timestamp);
+    expect_finalized(&outcomes, &[(&proposal1, 0), (&proposal2, 1)]);
+
+    let performance: BTreeMap<_, _> = zug.validator_performance(timestamp).into_iter().collect();
+
+    let alice = &performance[&*ALICE_PUBLIC_KEY];
+    assert_eq!(alice.participation, ValidatorParticipation::Active);
+    assert_eq!(alice.last_seen, Some(timestamp));
+    assert_eq!((alice.proposals_made, alice.proposals_missed), (2, 0));
+
+    let bob = &performance[&*BOB_PUBLIC_KEY];
+    assert_eq!(bob.participation, ValidatorParticipation::Equivocated);
+    assert_eq!(bob.last_seen, Some(timestamp));
+    assert_eq!((bob.proposals_made, bob.proposals_missed), (0, 0));
+
+    let carol = &performance[&*CAROL_PUBLIC_KEY];
+    assert_eq!(carol.participation, ValidatorParticipation::Inactive);
+    assert_eq!(carol.last_seen, None);
+    assert_eq!((carol.proposals_made, carol.proposals_missed), (0, 1));
+    assert_eq!(carol.round_exponent, None);
+}
+
+/// A signer using a local secret key which, like a remote signer, may block.
+struct BlockingSigner(LocalSigner);
+
+impl Signer for BlockingSigner {
+    fn public_key(&self) -> &PublicKey {
+        self.0.public_key()
+    }
+
+    fn sign(&self, request: SigningRequest) -> Result<Signature, signer::Error> {
+        self.0.sign(request)
+    }
+}
+
+/// Tests that a validator whose signer may block requests signatures instead of signing on the
+/// spot, and only adds and gossips its message once the signature arrives.
+#[test]
+fn zug_requests_signature_if_signer_may_block() {
+    testing::init_logging();
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    // Bob is the first round leader.
+    let mut sc_c = new_test_zug(weights, vec![], &[bob_idx]);
+    let dir = tempdir().unwrap();
+    sc_c.open_wal(dir.path().join("wal"), timestamp);
+
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let carol_signer = Arc::new(BlockingSigner(LocalSigner::new(CAROL_SECRET_KEY.clone())));
+    let carol_kp = Keypair::new(carol_signer.clone(), EraId::new(0));
+
+    sc_c.activate_validator(CAROL_PUBLIC_KEY.clone(), carol_kp, timestamp, None);
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // Carol receives Bob's proposal and requests a signature for her echo instead of gossiping it.
+    let msg = create_proposal_message(0, &proposal0, &validators, &bob_kp);
+    let mut outcomes = sc_c.handle_message(&mut rng, sender, msg.clone(), timestamp);
+    let mut sign_requests = vec![];
+    outcomes.retain(|outcome| match outcome {
+        ProtocolOutcome::SignHash {
+            signature_id,
+            hash,
+            kind,
+        } => {
+            sign_requests.push((*signature_id, *hash, *kind));
+            false
+        }
+        _ => true,
+    });
+    expect_no_gossip_block_finalized(outcomes);
+    let (signature_id, hash, kind) = match sign_requests.as_slice() {
+        [sign_request] => *sign_request,
+        _ => panic!("expected one signature request: {:?}", sign_requests),
+    };
+    let expected_kind = SignatureKind::ZugEcho {
+        round_id: 0,
+        proposal_hash: hash0,
+    };
+    assert_eq!(expected_kind, kind);
+
+    // While the signature is pending, she doesn't request another one for the same round.
+    let outcomes = sc_c.handle_message(&mut rng, sender, msg, timestamp);
+    assert!(!outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::SignHash { .. })));
+    expect_no_gossip_block_finalized(outcomes);
+
+    // Once the signature arrives, she adds her echo and gossips it.
+    let signature = carol_signer
+        .sign(SigningRequest::new(MessageKind::Other, hash))
+        .unwrap();
+    let mut outcomes = sc_c.handle_signature(signature_id, Some(signature), timestamp);
+    let mut gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(remove_signed(&mut gossip, 0, carol_idx, echo(hash0)));
+    assert!(gossip.is_empty(), "unexpected gossip: {:?}", gossip);
+    expect_no_gossip_block_finalized(outcomes);
+    assert!(sc_c.has_echoed(0, carol_idx));
+}
@@ -1,3 +1,5 @@
+mod inspection;
+
 use std::{
     fs::{File, OpenOptions},
     io::{self, BufReader, BufWriter, Read, Seek, Write},
@@ -18,6 +20,13 @@ use crate::components::consensus::{
 
 use super::RoundId;
 
+pub(crate) use inspection::{WalCompactionSummary, WalInspection};
+
+/// The size of the largest entry looked for after an entry with a corrupted size.
+///
+/// Entries are consensus messages, so they are bounded by the maximum network message size.
+const MAX_ENTRY_SIZE: u64 = 32 * 1024 * 1024;
+
 /// An entry in the Write-Ahead Log, storing a message we had added to our protocol state.
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
 #[serde(bound(
@@ -107,8 +116,111 @@ pub(crate) enum ReadWalError {
     FileCouldntBeCreated(PathBuf, io::Error),
     #[error(transparent)]
     OtherIOError(#[from] io::Error),
-    #[error("could not deserialize WAL entry: {0}")]
-    CouldNotDeserialize(bincode::Error),
+    #[error("WAL entry is corrupted but followed by valid entries: {0}")]
+    CorruptedEntry(Corruption),
+}
+
+/// A corrupted section of a Write-Ahead Log file.
+#[derive(Clone, Debug, Error, PartialEq, Eq, Serialize)]
+pub(crate) enum Corruption {
+    /// The file ends in the middle of an entry's size prefix.
+    #[error("incomplete entry size at offset {offset}")]
+    TruncatedSize {
+        /// The offset of the size prefix.
+        offset: u64,
+    },
+    /// The file ends before the end of the entry, or the size prefix itself is corrupted.
+    #[error("entry at offset {offset} has {size} bytes, but only {available} remain in the file")]
+    TruncatedEntry {
+        /// The offset of the entry's size prefix.
+        offset: u64,
+        /// The entry size according to its prefix.
+        size: u64,
+        /// The number of bytes in the file after the size prefix.
+        available: u64,
+    },
+    /// The entry is complete but could not be deserialized.
+    #[error("entry at offset {offset} with {size} bytes could not be decoded: {error}")]
+    Undecodable {
+        /// The offset of the entry's size prefix.
+        offset: u64,
+        /// The entry size according to its prefix.
+        size: u64,
+        /// The deserialization error.
+        error: String,
+    },
+}
+
+/// The result of reading an entry from a Write-Ahead Log file.
+enum NextEntry<C: Context> {
+    /// There are no bytes left in the file.
+    End,
+    /// An entry was read successfully.
+    Entry(Entry<C>),
+    /// The next entry is corrupted. If it is `Undecodable`, the reader is positioned at the
+    /// following entry.
+    Corrupt(Corruption),
+}
+
+/// Reads the entry starting at `offset` from a file of size `file_size`. The reader must be
+/// positioned at `offset`.
+fn read_entry<C: Context>(
+    reader: &mut impl Read,
+    offset: u64,
+    file_size: u64,
+) -> io::Result<NextEntry<C>> {
+    let remaining = file_size.saturating_sub(offset);
+    if remaining == 0 {
+        return Ok(NextEntry::End);
+    }
+
+    // Deserialize the size of the entry, in bytes, as a u64.
+    let mut entry_size_buf = [0u8; mem::size_of::<u64>()];
+    let Some(available) = remaining.checked_sub(entry_size_buf.len() as u64) else {
+        return Ok(NextEntry::Corrupt(Corruption::TruncatedSize { offset }));
+    };
+    reader.read_exact(&mut entry_size_buf)?;
+    let size = u64::from_le_bytes(entry_size_buf);
+
+    // Check the size before allocating a buffer for it: If the file ends before the entry does,
+    // either the entry was not completely written or the size itself is corrupted.
+    if size > available {
+        return Ok(NextEntry::Corrupt(Corruption::TruncatedEntry {
+            offset,
+            size,
+            available,
+        }));
+    }
+
+    // Read the serialized entry itself.
+    let mut entry_buf = vec![0; size as usize];
+    reader.read_exact(&mut entry_buf)?;
+
+    // Deserialize and return the entry.
+    match bincode::deserialize(&entry_buf) {
+        Ok(entry) => Ok(NextEntry::Entry(entry)),
+        Err(err) => Ok(NextEntry::Corrupt(Corruption::Undecodable {
+            offset,
+            size,
+            error: err.to_string(),
+        })),
+    }
+}
+
+/// Returns whether `bytes` start with a size prefix followed by a decodable entry of that size.
+fn entry_decodable_at<C: Context>(bytes: &[u8]) -> bool {
+    let size_len = mem::size_of::<u64>();
+    if bytes.len() < size_len {
+        return false;
+    }
+    let (size_bytes, rest) = bytes.split_at(size_len);
+    let mut size_buf = [0u8; mem::size_of::<u64>()];
+    size_buf.copy_from_slice(size_bytes);
+    let size = u64::from_le_bytes(size_buf);
+    match usize::try_from(size).ok().and_then(|size| rest.get(..size)) {
+        Some(entry_bytes) => bincode::deserialize::<Entry<C>>(entry_bytes).is_ok(),
+        None => false,
+    }
 }
 
 impl<C: Context> ReadWal<C> {
@@ -129,47 +241,85 @@ impl<C: Context> ReadWal<C> {
 impl<C: Context> ReadWal<C> {
     /// Reads the next entry from the WAL, or returns an error.
     /// If there are 0 bytes left it returns `Ok(None)`.
+    ///
+    /// If the rest of the file contains no valid entries, it is a corrupted tail, e.g. left by a
+    /// crash while writing: It is removed from the file and `Ok(None)` is returned. A corrupted
+    /// entry followed by valid ones is an error, as is an entry exceeding the rest of the file
+    /// unless no entry can be decoded within `MAX_ENTRY_SIZE` after its size prefix.
     pub(crate) fn read_next_entry(&mut self) -> Result<Option<Entry<C>>, ReadWalError> {
         // Remember the current position: If we encounter an unreadable entry we trim the file at
         // this point so we can continue appending entries after it.
         let position = self.reader.stream_position()?;
-
-        // Deserialize the size of the entry, in bytes, as a u64.
-        let mut entry_size_buf = [0u8; mem::size_of::<u64>()];
-        if let Err(err) = self.reader.read_exact(&mut entry_size_buf) {
-            if err.kind() == io::ErrorKind::UnexpectedEof {
-                self.trim_file(position)?;
-                return Ok(None);
+        let file_size = self.reader.get_ref().metadata()?.len();
+
+        match read_entry(&mut self.reader, position, file_size)? {
+            NextEntry::End => Ok(None),
+            NextEntry::Entry(entry) => Ok(Some(entry)),
+            NextEntry::Corrupt(corruption) => {
+                let valid_entry_follows = match corruption {
+                    // The size is intact, so valid entries could follow the corrupted one.
+                    Corruption::Undecodable { .. } => self.valid_entry_follows(file_size)?,
+                    Corruption::TruncatedEntry { offset, .. } => {
+                        self.entry_decodable_after(offset, file_size)?
+                    }
+                    // There is no room for another entry.
+                    Corruption::TruncatedSize { .. } => false,
+                };
+                if valid_entry_follows {
+                    return Err(ReadWalError::CorruptedEntry(corruption));
+                }
+                self.trim_file(position, &corruption)?;
+                Ok(None)
             }
-            return Err(ReadWalError::OtherIOError(err));
         }
-        let entry_size = u64::from_le_bytes(entry_size_buf) as usize;
-
-        // Read the serialized entry itself.
-        let mut entry_buf = vec![0; entry_size];
-        if let Err(err) = self.reader.read_exact(&mut entry_buf) {
-            if err.kind() == io::ErrorKind::UnexpectedEof {
-                self.trim_file(position)?;
-                return Ok(None);
+    }
+
+    /// Returns whether any valid entry follows the reader's current position.
+    fn valid_entry_follows(&mut self, file_size: u64) -> Result<bool, ReadWalError> {
+        loop {
+            let position = self.reader.stream_position()?;
+            match read_entry::<C>(&mut self.reader, position, file_size)? {
+                NextEntry::Entry(_) => return Ok(true),
+                NextEntry::End | NextEntry::Corrupt(Corruption::TruncatedSize { .. }) => {
+                    return Ok(false)
+                }
+                NextEntry::Corrupt(Corruption::TruncatedEntry { offset, .. }) => {
+                    return self.entry_decodable_after(offset, file_size)
+                }
+                NextEntry::Corrupt(Corruption::Undecodable { .. }) => {}
             }
-            return Err(ReadWalError::OtherIOError(err));
         }
+    }
 
-        // Deserialize and return the entry.
-        let entry = bincode::deserialize(&entry_buf).map_err(ReadWalError::CouldNotDeserialize)?;
-        Ok(Some(entry))
+    /// Returns whether an entry can be decoded at any offset less than `MAX_ENTRY_SIZE` after the
+    /// size prefix at `offset`.
+    ///
+    /// An entry exceeding the rest of the file is either the last one, cut short by a crash while
+    /// writing, or its size prefix is corrupted.  In the latter case the entries after it are
+    /// intact, but their offsets are unknown.  Since the corrupted entry itself is no larger than
+    /// `MAX_ENTRY_SIZE`, the next entry starts within that distance, so only that window is read.
+    fn entry_decodable_after(&mut self, offset: u64, file_size: u64) -> Result<bool, ReadWalError> {
+        let size_len = mem::size_of::<u64>() as u64;
+        let start = offset.saturating_add(size_len);
+        let window_len = file_size
+            .saturating_sub(start)
+            .min(MAX_ENTRY_SIZE.saturating_mul(2).saturating_add(size_len));
+        let mut window = vec![0; window_len as usize];
+        self.reader.seek(io::SeekFrom::Start(start))?;
+        self.reader.read_exact(&mut window)?;
+        let candidates = window.len().min(MAX_ENTRY_SIZE as usize);
+        Ok((0..candidates).any(|index| entry_decodable_at::<C>(&window[index..])))
     }
 
-    /// Trims the file to the given length and logs a warning if any bytes were removed.
+    /// Trims the file to the given length and logs a warning.
     ///
-    /// This should be called with the position where the last complete entry ended. Incomplete
-    /// entries can safely be removed because we only send messages after writing them and
-    /// flushing the buffer, so we won't remove any messages that we already sent.
-    fn trim_file(&mut self, position: u64) -> Result<(), ReadWalError> {
-        if self.reader.stream_position()? > position {
-            warn!("removing incomplete entry from WAL");
-            self.reader.get_mut().set_len(position)?;
-        }
+    /// This should be called with the position where the last complete entry ended. Corrupted
+    /// entries at the end can safely be removed because we only send messages after writing them
+    /// and flushing the buffer, so we won't remove any messages that we already sent.
+    fn trim_file(&mut self, position: u64, corruption: &Corruption) -> Result<(), ReadWalError> {
+        warn!(%corruption, "removing corrupted tail from WAL");
+        self.reader.get_mut().set_len(position)?;
+        self.reader.seek(io::SeekFrom::Start(position))?;
         Ok(())
     }
 }
@@ -181,11 +331,12 @@ mod tests {
     use crate::components::consensus::{
         cl_context::{ClContext, Keypair},
         protocols::common,
+        utils::{ValidatorIndex, Validators},
     };
     use casper_types::{PublicKey, SecretKey, Timestamp, U512};
     use tempfile::tempdir;
 
-    use super::*;
+    use super::{inspection::EntryProblem, *};
     use once_cell::sync::Lazy;
     const INSTANCE_ID_DATA: &[u8; 1] = &[123u8; 1];
     const ALICE_WEIGHT: u64 = 1000000;
@@ -195,17 +346,21 @@ mod tests {
     static ALICE_PUBLIC_KEY: Lazy<PublicKey> =
         Lazy::new(|| PublicKey::from(Lazy::force(&ALICE_SECRET_KEY)));
 
-    fn create_message_fn() -> Box<dyn Fn(RoundId, Content<ClContext>) -> SignedMessage<ClContext>> {
-        let alice_keypair = Keypair::from(std::sync::Arc::new(
-            SecretKey::ed25519_from_bytes(ALICE_SECRET_KEY_BYTES).unwrap(),
-        ));
+    fn validators() -> Validators<PublicKey> {
         let weights: Vec<(PublicKey, U512)> =
             vec![(ALICE_PUBLIC_KEY.clone(), U512::from(ALICE_WEIGHT))];
-        let validators = common::validators::<ClContext>(
+        common::validators::<ClContext>(
             &Default::default(),
             &Default::default(),
             weights.iter().cloned().collect(),
-        );
+        )
+    }
+
+    fn create_message_fn() -> Box<dyn Fn(RoundId, Content<ClContext>) -> SignedMessage<ClContext>> {
+        let alice_keypair = Keypair::from(std::sync::Arc::new(
+            SecretKey::ed25519_from_bytes(ALICE_SECRET_KEY_BYTES).unwrap(),
+        ));
+        let validators = validators();
         let instance_id = ClContext::hash(INSTANCE_ID_DATA);
         Box::new(move |round_id, content: Content<ClContext>| {
             let validator_idx = validators.get_index(alice_keypair.public_key()).unwrap();
@@ -216,6 +371,7 @@ mod tests {
                 validator_idx,
                 &alice_keypair,
             )
+            .unwrap()
         })
     }
 
@@ -290,4 +446,209 @@ mod tests {
         // Finally, we assure that there are no more entries at all in the WAL
         assert_eq!(entries, read_entries());
     }
+
+    fn read_all_entries(path: &PathBuf) -> Vec<Entry<ClContext>> {
+        let mut read_wal: ReadWal<ClContext> = ReadWal::new(path).unwrap();
+        from_fn(move || read_wal.read_next_entry().unwrap()).collect()
+    }
+
+    /// Appends an entry with the given size prefix and content to the file.
+    fn append_raw_entry(path: &PathBuf, size: u64, bytes: &[u8]) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(&size.to_le_bytes()).unwrap();
+        file.write_all(bytes).unwrap();
+    }
+
+    #[test]
+    fn should_remove_corrupted_tail() {
+        let create_message = create_message_fn();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal");
+        let mut write_wal: WriteWal<ClContext> = WriteWal::new(&path).unwrap();
+        write_wal
+            .record_entry(&Entry::SignedMessage(create_message(
+                0,
+                Content::Vote(true),
+            )))
+            .unwrap();
+        let valid_size = path.metadata().unwrap().len();
+        let expected = vec![Entry::SignedMessage(create_message(0, Content::Vote(true)))];
+
+        // An entry that can't be decoded, followed by a size that exceeds the rest of the file.
+        append_raw_entry(&path, 3, &[255; 3]);
+        append_raw_entry(&path, u64::MAX, &[1, 2, 3]);
+
+        assert_eq!(expected, read_all_entries(&path));
+        assert_eq!(valid_size, path.metadata().unwrap().len());
+        assert_eq!(expected, read_all_entries(&path));
+    }
+
+    #[test]
+    fn should_not_remove_corrupted_entry_followed_by_valid_ones() {
+        let create_message = create_message_fn();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal");
+        let mut write_wal: WriteWal<ClContext> = WriteWal::new(&path).unwrap();
+        write_wal
+            .record_entry(&Entry::SignedMessage(create_message(
+                0,
+                Content::Vote(true),
+            )))
+            .unwrap();
+        let corrupted_offset = path.metadata().unwrap().len();
+        append_raw_entry(&path, 3, &[255; 3]);
+        write_wal
+            .record_entry(&Entry::SignedMessage(create_message(
+                1,
+                Content::Vote(true),
+            )))
+            .unwrap();
+        let file_size = path.metadata().unwrap().len();
+
+        let mut read_wal: ReadWal<ClContext> = ReadWal::new(&path).unwrap();
+        assert!(read_wal.read_next_entry().unwrap().is_some());
+        match read_wal.read_next_entry() {
+            Err(ReadWalError::CorruptedEntry(Corruption::Undecodable { offset, size, .. })) => {
+                assert_eq!(corrupted_offset, offset);
+                assert_eq!(3, size);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(file_size, path.metadata().unwrap().len());
+
+        // The inspection skips the corrupted entry and decodes the one after it.
+        let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+        let inspection =
+            WalInspection::<ClContext>::read(&path, &validators(), instance_id).unwrap();
+        assert_eq!(2, inspection.entries.len());
+        assert_eq!(1, inspection.corruptions.len());
+        assert!(!inspection.is_valid());
+    }
+
+    #[test]
+    fn should_not_remove_entry_with_corrupted_size_followed_by_valid_ones() {
+        let create_message = create_message_fn();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal");
+        let mut write_wal: WriteWal<ClContext> = WriteWal::new(&path).unwrap();
+        write_wal
+            .record_entry(&Entry::SignedMessage(create_message(
+                0,
+                Content::Vote(true),
+            )))
+            .unwrap();
+        let corrupted_offset = path.metadata().unwrap().len();
+        append_raw_entry(&path, u64::MAX, &[255; 3]);
+        write_wal
+            .record_entry(&Entry::SignedMessage(create_message(
+                1,
+                Content::Vote(true),
+            )))
+            .unwrap();
+        let file_size = path.metadata().unwrap().len();
+
+        let mut read_wal: ReadWal<ClContext> = ReadWal::new(&path).unwrap();
+        assert!(read_wal.read_next_entry().unwrap().is_some());
+        match read_wal.read_next_entry() {
+            Err(ReadWalError::CorruptedEntry(Corruption::TruncatedEntry { offset, .. })) => {
+                assert_eq!(corrupted_offset, offset);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(file_size, path.metadata().unwrap().len());
+    }
+
+    #[test]
+    fn should_inspect_and_compact_wal() {
+        let create_message = create_message_fn();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("wal");
+        let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+
+        let vote = create_message(0, Content::Vote(true));
+        let echo0 = create_message(1, Content::Echo(ClContext::hash(&[0])));
+        let echo1 = create_message(1, Content::Echo(ClContext::hash(&[1])));
+        let invalid_signature = vote.with(Content::Vote(false), vote.signature);
+        let unknown_validator = SignedMessage {
+            validator_idx: ValidatorIndex(1),
+            ..vote.clone()
+        };
+        let entries = vec![
+            Entry::SignedMessage(vote.clone()),
+            Entry::SignedMessage(vote.clone()),
+            Entry::SignedMessage(invalid_signature),
+            Entry::SignedMessage(unknown_validator),
+            Entry::Proposal(Proposal::dummy(Timestamp::zero(), 0), 1),
+            Entry::Proposal(Proposal::dummy(Timestamp::zero(), 0), 1),
+            Entry::Proposal(Proposal::dummy(Timestamp::zero(), 2), 2),
+            Entry::SignedMessage(echo0.clone()),
+            Entry::Evidence(echo0.clone(), echo1.content, echo1.signature),
+            Entry::Evidence(echo0.clone(), echo1.content, echo1.signature),
+            Entry::SignedMessage(create_message(2, Content::Vote(false))),
+        ];
+        let mut write_wal: WriteWal<ClContext> = WriteWal::new(&path).unwrap();
+        for entry in &entries {
+            write_wal.record_entry(entry).unwrap();
+        }
+
+        let inspection =
+            WalInspection::<ClContext>::read(&path, &validators(), instance_id).unwrap();
+        assert!(inspection.corruptions.is_empty());
+        let problems: Vec<_> = inspection
+            .entries
+            .iter()
+            .map(|inspected| inspected.problems.clone())
+            .collect();
+        assert_eq!(
+            vec![
+                vec![],
+                vec![EntryProblem::Duplicate],
+                vec![EntryProblem::InvalidSignature],
+                vec![EntryProblem::UnknownValidator(1)],
+                vec![],
+                vec![EntryProblem::Duplicate],
+                vec![EntryProblem::InvalidParent {
+                    round: 2,
+                    parent: 2
+                }],
+                vec![],
+                vec![],
+                vec![EntryProblem::Duplicate],
+                vec![EntryProblem::FaultyValidator],
+            ],
+            problems
+        );
+
+        let other_instance =
+            WalInspection::<ClContext>::read(&path, &validators(), ClContext::hash(&[0])).unwrap();
+        assert_eq!(
+            vec![EntryProblem::WrongInstance],
+            other_instance.entries[0].problems
+        );
+
+        let compacted_path = dir.path().join("compacted");
+        let summary = inspection.write_compacted(&compacted_path).unwrap();
+        assert_eq!(
+            WalCompactionSummary {
+                retained_entry_count: 4,
+                removed_entry_count: 7,
+                corruption_count: 0,
+            },
+            summary
+        );
+        assert!(inspection.write_compacted(&compacted_path).is_err());
+
+        let expected = vec![
+            Entry::SignedMessage(vote),
+            Entry::Proposal(Proposal::dummy(Timestamp::zero(), 0), 1),
+            Entry::SignedMessage(echo0.clone()),
+            Entry::Evidence(echo0, echo1.content, echo1.signature),
+        ];
+        assert_eq!(expected, read_all_entries(&compacted_path));
+        assert!(
+            WalInspection::<ClContext>::read(&compacted_path, &validators(), instance_id)
+                .unwrap()
+                .is_valid()
+        );
+    }
 }
@@ -0,0 +1,271 @@
+//! Decoding, validation and compaction of Write-Ahead Log files.
+
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{BufReader, BufWriter, Seek},
+    marker::PhantomData,
+    path::Path,
+};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::components::consensus::{
+    protocols::zug::{RoundId, SignedMessage},
+    traits::Context,
+    utils::Validators,
+};
+
+use super::{read_entry, Corruption, Entry, NextEntry, ReadWalError, WriteWal, WriteWalError};
+
+/// A reason for an entry to be left out of a compacted Write-Ahead Log.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq, Serialize)]
+pub(crate) enum EntryProblem {
+    /// The entry refers to a validator index that is not in the era's validator set.
+    #[error("validator index {0} is out of range")]
+    UnknownValidator(u32),
+    /// A signed message belongs to a different protocol instance.
+    #[error("the instance ID doesn't match the era")]
+    WrongInstance,
+    /// A signature doesn't match the validator's public key.
+    #[error("invalid signature")]
+    InvalidSignature,
+    /// The two messages in an evidence entry don't conflict with each other.
+    #[error("the evidence's messages don't conflict")]
+    NoConflict,
+    /// A proposal's parent is not in an earlier round.
+    #[error("the parent round {parent} is not earlier than the proposal's round {round}")]
+    InvalidParent {
+        /// The proposal's round.
+        round: RoundId,
+        /// The parent's round.
+        parent: RoundId,
+    },
+    /// The entry has no effect because an equivalent one was recorded earlier.
+    #[error("duplicate of an earlier entry")]
+    Duplicate,
+    /// A signed message by a validator that earlier evidence proves to be faulty.
+    #[error("message from a validator that is known to be faulty")]
+    FaultyValidator,
+}
+
+/// An entry read from a Write-Ahead Log file.
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = "C::Hash: Serialize"))]
+pub(crate) struct InspectedEntry<C: Context> {
+    /// The entry's offset in the file.
+    pub(crate) offset: u64,
+    /// The decoded entry.
+    pub(crate) entry: Entry<C>,
+    /// The reasons why the entry is invalid or redundant, if any.
+    pub(crate) problems: Vec<EntryProblem>,
+}
+
+/// The validated contents of a Write-Ahead Log file.
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = "C::Hash: Serialize"))]
+pub(crate) struct WalInspection<C: Context> {
+    /// The instance ID of the era the entries were checked against.
+    pub(crate) instance_id: C::InstanceId,
+    /// The size of the file in bytes.
+    pub(crate) file_size: u64,
+    /// All entries that could be decoded, in the order they were recorded.
+    pub(crate) entries: Vec<InspectedEntry<C>>,
+    /// The corrupted sections of the file.
+    pub(crate) corruptions: Vec<Corruption>,
+}
+
+/// Summary of a Write-Ahead Log compaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct WalCompactionSummary {
+    /// The number of entries written to the compacted file.
+    pub(crate) retained_entry_count: usize,
+    /// The number of decoded entries left out because they were invalid or redundant.
+    pub(crate) removed_entry_count: usize,
+    /// The number of corrupted sections left out.
+    pub(crate) corruption_count: usize,
+}
+
+impl<C: Context> WalInspection<C> {
+    /// Reads all entries of the Write-Ahead Log file at `path` and checks them against the era's
+    /// validators and instance ID. The file is not modified.
+    ///
+    /// Corrupted entries are skipped as long as their size is intact, so that entries after them
+    /// can still be decoded.
+    pub(crate) fn read(
+        path: &Path,
+        validators: &Validators<C::ValidatorId>,
+        instance_id: C::InstanceId,
+    ) -> Result<Self, ReadWalError> {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let mut decoded = vec![];
+        let mut corruptions = vec![];
+        loop {
+            let offset = reader.stream_position()?;
+            match read_entry(&mut reader, offset, file_size)? {
+                NextEntry::End => break,
+                NextEntry::Entry(entry) => decoded.push((offset, entry)),
+                NextEntry::Corrupt(corruption @ Corruption::Undecodable { .. }) => {
+                    corruptions.push(corruption)
+                }
+                NextEntry::Corrupt(corruption) => {
+                    corruptions.push(corruption);
+                    break;
+                }
+            }
+        }
+
+        let problems = find_problems(
+            decoded.iter().map(|(_, entry)| entry),
+            validators,
+            &instance_id,
+        );
+        let entries = decoded
+            .into_iter()
+            .zip(problems)
+            .map(|((offset, entry), problems)| InspectedEntry {
+                offset,
+                entry,
+                problems,
+            })
+            .collect();
+        Ok(WalInspection {
+            instance_id,
+            file_size,
+            entries,
+            corruptions,
+        })
+    }
+
+    /// Returns whether the file contains no corruption and no invalid or redundant entries.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.corruptions.is_empty() && self.entries.iter().all(|e| e.problems.is_empty())
+    }
+
+    /// Writes every entry without problems to a new Write-Ahead Log file at `path`, in the
+    /// original order. Fails if the file already exists.
+    ///
+    /// Replaying the new file results in the same protocol state as replaying the original one,
+    /// except that invalid entries are missing. Messages by faulty validators that were recorded
+    /// before the evidence against them are kept: Later proposals may need them to be accepted
+    /// during the replay.
+    pub(crate) fn write_compacted(
+        &self,
+        path: &Path,
+    ) -> Result<WalCompactionSummary, WriteWalError> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(WriteWalError::FileCouldntBeOpened)?;
+        let mut write_wal = WriteWal::<C> {
+            writer: BufWriter::new(file),
+            phantom_context: PhantomData,
+        };
+        let retained: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|inspected| inspected.problems.is_empty())
+            .collect();
+        for inspected in &retained {
+            write_wal.record_entry(&inspected.entry)?;
+        }
+        Ok(WalCompactionSummary {
+            retained_entry_count: retained.len(),
+            removed_entry_count: self.entries.len().saturating_sub(retained.len()),
+            corruption_count: self.corruptions.len(),
+        })
+    }
+}
+
+/// Returns the problems of each entry, in the same order.
+fn find_problems<'a, C: Context + 'a>(
+    entries: impl Iterator<Item = &'a Entry<C>>,
+    validators: &Validators<C::ValidatorId>,
+    instance_id: &C::InstanceId,
+) -> Vec<Vec<EntryProblem>> {
+    let mut messages = HashSet::new();
+    let mut proposals = HashSet::new();
+    let mut faulty = HashSet::new();
+    entries
+        .map(|entry| match entry {
+            Entry::SignedMessage(signed_msg) => {
+                let mut problems = check_signed_message(signed_msg, validators, instance_id);
+                if problems.is_empty() {
+                    let key = (
+                        signed_msg.round_id,
+                        &signed_msg.content,
+                        signed_msg.validator_idx,
+                    );
+                    if faulty.contains(&signed_msg.validator_idx) {
+                        problems.push(EntryProblem::FaultyValidator);
+                    } else if !messages.insert(key) {
+                        problems.push(EntryProblem::Duplicate);
+                    }
+                }
+                problems
+            }
+            Entry::Proposal(proposal, round_id) => {
+                let mut problems: Vec<_> = proposal
+                    .inactive
+                    .iter()
+                    .flatten()
+                    .filter(|idx| validators.id(**idx).is_none())
+                    .map(|idx| EntryProblem::UnknownValidator(idx.0))
+                    .collect();
+                if let Some(parent) = proposal.maybe_parent_round_id {
+                    if parent >= *round_id {
+                        problems.push(EntryProblem::InvalidParent {
+                            round: *round_id,
+                            parent,
+                        });
+                    }
+                }
+                if problems.is_empty() && !proposals.insert((*round_id, proposal)) {
+                    problems.push(EntryProblem::Duplicate);
+                }
+                problems
+            }
+            Entry::Evidence(signed_msg, content2, signature2) => {
+                let mut problems = check_signed_message(signed_msg, validators, instance_id);
+                if let Some(validator_id) = validators.id(signed_msg.validator_idx) {
+                    let signed_msg2 = signed_msg.with(*content2, *signature2);
+                    if !signed_msg2.verify_signature(validator_id)
+                        && !problems.contains(&EntryProblem::InvalidSignature)
+                    {
+                        problems.push(EntryProblem::InvalidSignature);
+                    }
+                }
+                if !signed_msg.content.contradicts(content2) {
+                    problems.push(EntryProblem::NoConflict);
+                }
+                if problems.is_empty() && !faulty.insert(signed_msg.validator_idx) {
+                    problems.push(EntryProblem::Duplicate);
+                }
+                problems
+            }
+        })
+        .collect()
+}
+
+/// Returns the problems with a signed message's validator index, instance ID and signature.
+fn check_signed_message<C: Context>(
+    signed_msg: &SignedMessage<C>,
+    validators: &Validators<C::ValidatorId>,
+    instance_id: &C::InstanceId,
+) -> Vec<EntryProblem> {
+    let Some(validator_id) = validators.id(signed_msg.validator_idx) else {
+        return vec![EntryProblem::UnknownValidator(signed_msg.validator_idx.0)];
+    };
+    let mut problems = vec![];
+    if signed_msg.instance_id != *instance_id {
+        problems.push(EntryProblem::WrongInstance);
+    }
+    if !signed_msg.verify_signature(validator_id) {
+        problems.push(EntryProblem::InvalidSignature);
+    }
+    problems
+}
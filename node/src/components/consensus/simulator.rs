@@ -0,0 +1,803 @@
+//! A deterministic simulator for the consensus protocols.
+//!
+//! The simulator runs Highway or Zug with a configurable network of validators on a virtual clock,
+//! based on the discrete event simulation used by the protocols' tests. Message delays, network
+//! partitions, crashes and equivocations are defined by a [`Scenario`], and all random choices are
+//! made by a single RNG seeded from it, so running the same scenario always produces the same
+//! [`Report`].
+
+mod context;
+pub(crate) mod queue;
+mod report;
+mod scenario;
+#[cfg(test)]
+mod tests;
+pub(crate) mod virtual_net;
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashSet},
+    hash::{Hash, Hasher},
+    io, mem,
+};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use tempfile::TempDir;
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{TimeDiff, Timestamp, U512};
+
+use self::{
+    context::{SimContext, SimSecret, SimValue},
+    queue::QueueEntry,
+    virtual_net::{
+        DeliverySchedule, Message, Node, Target, TargetedMessage, ValidatorId, VirtualNet,
+    },
+};
+pub use self::{
+    report::{EraSummary, FaultReport, LatencyStats, Report},
+    scenario::{Crash, Latency, Partition, Scenario},
+};
+use super::{
+    config::Config,
+    consensus_protocol::{
+        BlockContext, ConsensusProtocol, FinalizedBlock, ProposedBlock, ProtocolOutcome,
+        ProtocolOutcomes,
+    },
+    era_supervisor::{SerializedMessage, PAST_EVIDENCE_ERAS},
+    protocols::{highway::HighwayProtocol, zug::Zug},
+    ActionId, TimerId,
+};
+use crate::{
+    tls::KeyFingerprint,
+    types::{chainspec::ConsensusProtocolName, Chainspec, NodeId},
+    NodeRng,
+};
+
+/// An error running a simulation.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The scenario is inconsistent.
+    #[error("invalid scenario: {0}")]
+    InvalidScenario(String),
+    /// The directory for the nodes' write-ahead logs could not be created.
+    #[error("failed to create temporary directory: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Runs the given scenario and returns the results.
+///
+/// The protocol parameters are taken from the chainspec, unless the scenario overrides them.
+pub fn run(scenario: &Scenario, mut chainspec: Chainspec) -> Result<Report, Error> {
+    scenario.validate().map_err(Error::InvalidScenario)?;
+    let core_config = &mut chainspec.core_config;
+    if let Some(protocol) = scenario.protocol {
+        core_config.consensus_protocol = protocol;
+    }
+    if let Some(era_duration) = scenario.era_duration {
+        core_config.era_duration = era_duration;
+    }
+    if let Some(minimum_era_height) = scenario.minimum_era_height {
+        core_config.minimum_era_height = minimum_era_height;
+    }
+    if let Some(minimum_block_time) = scenario.minimum_block_time {
+        core_config.minimum_block_time = minimum_block_time;
+    }
+    Ok(Simulation::new(scenario, chainspec)?.run())
+}
+
+/// A message or event handled by a simulated node.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+enum SimMessage {
+    /// A consensus protocol message from another node.
+    Protocol {
+        era: u64,
+        payload: SerializedMessage,
+    },
+    /// A consensus protocol request from another node.
+    Request {
+        era: u64,
+        payload: SerializedMessage,
+    },
+    /// A timer scheduled by the node itself.
+    Timer {
+        era: u64,
+        timestamp: Timestamp,
+        timer_id: TimerId,
+    },
+    /// An action queued by the node itself.
+    Action { era: u64, action_id: ActionId },
+    /// The node is supposed to propose a block.
+    NewBlock {
+        era: u64,
+        block_context: BlockContext<SimContext>,
+    },
+    /// The node is supposed to validate a block proposed by another node.
+    Validate {
+        era: u64,
+        proposed_block: ProposedBlock<SimContext>,
+    },
+}
+
+impl SimMessage {
+    /// Returns the era this message belongs to.
+    fn era(&self) -> u64 {
+        match self {
+            SimMessage::Protocol { era, .. }
+            | SimMessage::Request { era, .. }
+            | SimMessage::Timer { era, .. }
+            | SimMessage::Action { era, .. }
+            | SimMessage::NewBlock { era, .. }
+            | SimMessage::Validate { era, .. } => *era,
+        }
+    }
+
+    /// Returns whether this message was sent over the network, rather than created locally.
+    fn is_network_message(&self) -> bool {
+        matches!(
+            self,
+            SimMessage::Protocol { .. } | SimMessage::Request { .. }
+        )
+    }
+}
+
+impl PartialOrd for SimMessage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SimMessage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut hasher0 = DefaultHasher::new();
+        let mut hasher1 = DefaultHasher::new();
+        self.hash(&mut hasher0);
+        other.hash(&mut hasher1);
+        hasher0.finish().cmp(&hasher1.finish())
+    }
+}
+
+/// Identifies a block by era, proposing node and timestamp.
+type BlockKey = (u64, u64, Timestamp);
+
+/// A block finalized by a node.
+#[derive(Clone, Debug)]
+struct Finalized {
+    era: u64,
+    proposer: u64,
+    timestamp: Timestamp,
+    /// The time at which the node finalized the block.
+    finalized_at: Timestamp,
+}
+
+impl Finalized {
+    fn key(&self) -> BlockKey {
+        (self.era, self.proposer, self.timestamp)
+    }
+}
+
+/// A simulated node, running one consensus protocol instance per era.
+struct SimNode {
+    /// The validator whose key the node signs with.
+    validator_id: ValidatorId,
+    /// The protocol instances, by era.
+    eras: BTreeMap<u64, Box<dyn ConsensusProtocol<SimContext>>>,
+    /// Messages for eras the node hasn't started yet.
+    pending: Vec<Message<SimMessage>>,
+    /// The number of eras in which the node finalized the terminal block.
+    completed_eras: u64,
+}
+
+impl SimNode {
+    fn new(validator_id: ValidatorId) -> Self {
+        SimNode {
+            validator_id,
+            eras: BTreeMap::new(),
+            pending: Vec::new(),
+            completed_eras: 0,
+        }
+    }
+
+    /// Returns the most recent era the node has started.
+    fn current_era(&self) -> Option<u64> {
+        self.eras.keys().next_back().copied()
+    }
+}
+
+/// The state of a running simulation.
+///
+/// Nodes are identified by a `ValidatorId` containing their index: the first ones correspond to
+/// the scenario's validators, followed by the equivocators' twins. The twins don't receive any
+/// messages from other nodes, so they time out and sign votes that conflict with the originals'.
+struct Simulation<'a> {
+    scenario: &'a Scenario,
+    protocol: ConsensusProtocolName,
+    chainspec: Chainspec,
+    config: Config,
+    /// The directory containing the nodes' write-ahead logs.
+    wal_dir: TempDir,
+    rng: NodeRng,
+    net: VirtualNet<Finalized, SimMessage, SimNode>,
+    validator_stakes: BTreeMap<ValidatorId, U512>,
+    /// The validator each node signs as, by node index.
+    node_validators: Vec<ValidatorId>,
+    /// The network address of each node, by node index.
+    node_ids: Vec<NodeId>,
+    /// The node index of each network address.
+    node_indices: BTreeMap<NodeId, ValidatorId>,
+    /// The current simulated time.
+    now: Timestamp,
+    /// The time at which the first honest node started each era.
+    era_starts: BTreeMap<u64, Timestamp>,
+    /// The time at which the first honest node finalized each era's terminal block, and the
+    /// equivocators listed in it.
+    era_ends: BTreeMap<u64, (Timestamp, Vec<u64>)>,
+    /// All proposed blocks.
+    proposals: BTreeSet<BlockKey>,
+    faults: Vec<FaultReport>,
+    ftt_exceeded: bool,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(scenario: &'a Scenario, chainspec: Chainspec) -> Result<Self, Error> {
+        let node_validators: Vec<ValidatorId> = (0..scenario.weights.len() as u64)
+            .chain(scenario.equivocators.iter().copied())
+            .map(ValidatorId)
+            .collect();
+        let node_ids: Vec<NodeId> = (0..node_validators.len() as u64).map(node_id).collect();
+        let node_indices = node_ids
+            .iter()
+            .enumerate()
+            .map(|(index, node_id)| (*node_id, ValidatorId(index as u64)))
+            .collect();
+        let nodes = node_validators
+            .iter()
+            .enumerate()
+            .map(|(index, validator_id)| {
+                Node::new(ValidatorId(index as u64), SimNode::new(*validator_id))
+            });
+        let net = VirtualNet::new(nodes, vec![]);
+        let validator_stakes = scenario
+            .weights
+            .iter()
+            .enumerate()
+            .map(|(index, weight)| (ValidatorId(index as u64), U512::from(*weight)))
+            .collect();
+        Ok(Simulation {
+            scenario,
+            protocol: chainspec.core_config.consensus_protocol,
+            chainspec,
+            config: Config::default(),
+            wal_dir: tempfile::tempdir()?,
+            rng: NodeRng::seed_from_u64(scenario.seed),
+            net,
+            validator_stakes,
+            node_validators,
+            node_ids,
+            node_indices,
+            now: Timestamp::zero(),
+            era_starts: BTreeMap::new(),
+            era_ends: BTreeMap::new(),
+            proposals: BTreeSet::new(),
+            faults: Vec::new(),
+            ftt_exceeded: false,
+        })
+    }
+
+    /// Handles events until all eras are complete or the maximum duration is reached.
+    fn run(mut self) -> Report {
+        let nodes: Vec<ValidatorId> = self.net.validators_ids().copied().collect();
+        for node in nodes {
+            self.start_era(node, 0, Timestamp::zero(), &HashSet::new(), &HashSet::new());
+        }
+        let end = Timestamp::zero().saturating_add(self.scenario.max_duration);
+        while !self.is_complete() {
+            if self
+                .net
+                .peek_message()
+                .map_or(true, |entry| entry.delivery_time > end)
+            {
+                break;
+            }
+            let Some(QueueEntry {
+                delivery_time,
+                recipient,
+                message,
+            }) = self.net.pop_message()
+            else {
+                break;
+            };
+            self.now = delivery_time;
+            self.handle_message(recipient, message);
+        }
+        self.report()
+    }
+
+    /// Returns whether the node is not an equivocator.
+    fn is_honest(&self, node: ValidatorId) -> bool {
+        self.is_original(node) && !self.scenario.equivocators.contains(&node.0)
+    }
+
+    /// Returns whether the node is one of the scenario's validators rather than a twin.
+    fn is_original(&self, node: ValidatorId) -> bool {
+        node.0 < self.scenario.weights.len() as u64
+    }
+
+    /// Returns the time since the start of the simulation.
+    fn elapsed(&self) -> TimeDiff {
+        TimeDiff::from_millis(self.now.millis())
+    }
+
+    /// Returns whether all honest nodes that don't crash permanently have completed all eras.
+    fn is_complete(&self) -> bool {
+        self.net
+            .validators()
+            .filter(|node| {
+                let validator = node.validator().validator_id.0;
+                self.is_honest(node.id) && !self.scenario.is_permanently_crashed(validator)
+            })
+            .all(|node| node.validator().completed_eras >= self.scenario.eras)
+    }
+
+    /// Creates the node's protocol instance for the given era and activates it as a validator.
+    fn start_era(
+        &mut self,
+        node: ValidatorId,
+        era: u64,
+        start_time: Timestamp,
+        faulty: &HashSet<ValidatorId>,
+        inactive: &HashSet<ValidatorId>,
+    ) {
+        let now = self.now;
+        let instance_id = Digest::hash(era.to_le_bytes());
+        let seed = self.scenario.seed.wrapping_add(era);
+        let unit_file = self
+            .wal_dir
+            .path()
+            .join(format!("node_{}_era_{}.dat", node.0, era));
+        let Some(sim_node) = self.net.node_mut(&node).map(Node::validator_mut) else {
+            return;
+        };
+        let prev_era = era.checked_sub(1).and_then(|prev| sim_node.eras.get(&prev));
+        let prev_cp = prev_era.map(|consensus| &**consensus);
+        let (mut consensus, mut outcomes) = match self.protocol {
+            ConsensusProtocolName::Highway => HighwayProtocol::new_boxed(
+                instance_id,
+                self.validator_stakes.clone(),
+                faulty,
+                inactive,
+                &self.chainspec,
+                &self.config,
+                prev_cp,
+                start_time,
+                seed,
+                now,
+            ),
+            ConsensusProtocolName::Zug => Zug::new_boxed(
+                instance_id,
+                self.validator_stakes.clone(),
+                faulty,
+                inactive,
+                &self.chainspec,
+                &self.config,
+                prev_cp,
+                start_time,
+                seed,
+                now,
+                unit_file.clone(),
+            ),
+        };
+        let validators_with_evidence: Vec<ValidatorId> = prev_era
+            .into_iter()
+            .flat_map(|consensus| consensus.validators_with_evidence())
+            .copied()
+            .collect();
+        for validator_id in validators_with_evidence {
+            consensus.mark_faulty(&validator_id);
+        }
+        outcomes.extend(consensus.handle_is_current(now));
+        let our_id = sim_node.validator_id;
+        outcomes.extend(consensus.activate_validator(
+            our_id,
+            SimSecret(our_id),
+            now,
+            Some(unit_file),
+        ));
+        let evidence_only_era = era.checked_sub(PAST_EVIDENCE_ERAS.saturating_add(1));
+        if let Some(old) = evidence_only_era.and_then(|old| sim_node.eras.get_mut(&old)) {
+            old.set_evidence_only();
+        }
+        let _ = sim_node.eras.insert(era, consensus);
+        let (ready, pending): (Vec<_>, Vec<_>) = mem::take(&mut sim_node.pending)
+            .into_iter()
+            .partition(|message| message.payload().era() <= era);
+        sim_node.pending = pending;
+        if self.is_honest(node) {
+            let _ = self.era_starts.entry(era).or_insert(now);
+        }
+        self.handle_outcomes(node, era, outcomes);
+        let requeued = ready
+            .into_iter()
+            .map(|message| {
+                let target = Target::SingleValidator(node);
+                (TargetedMessage::new(message, target), now)
+            })
+            .collect();
+        self.net.dispatch_messages(requeued);
+    }
+
+    /// Passes a message or event to the recipient's protocol instance.
+    fn handle_message(&mut self, recipient: ValidatorId, message: Message<SimMessage>) {
+        let now = self.now;
+        let Message { sender, payload } = message;
+        if payload.is_network_message() && self.is_crashed(recipient) {
+            return; // Crashed nodes don't receive any messages.
+        }
+        let era = payload.era();
+        let Some(sim_node) = self.net.node_mut(&recipient).map(Node::validator_mut) else {
+            return;
+        };
+        if sim_node.current_era().map_or(true, |current| era > current) {
+            sim_node.pending.push(Message::new(sender, payload));
+            return;
+        }
+        let Some(consensus) = sim_node.eras.get_mut(&era) else {
+            return;
+        };
+        let sender_id = self.node_ids[sender.0 as usize];
+        let outcomes = match payload {
+            SimMessage::Protocol { payload, .. } => {
+                consensus.handle_message(&mut self.rng, sender_id, payload, now)
+            }
+            SimMessage::Request { payload, .. } => {
+                let (outcomes, response) =
+                    consensus.handle_request_message(&mut self.rng, sender_id, payload, now);
+                if let Some(payload) = response {
+                    self.send(
+                        recipient,
+                        vec![(sender, SimMessage::Protocol { era, payload })],
+                    );
+                }
+                outcomes
+            }
+            SimMessage::Timer {
+                timestamp,
+                timer_id,
+                ..
+            } => consensus.handle_timer(timestamp, now, timer_id, &mut self.rng),
+            SimMessage::Action { action_id, .. } => consensus.handle_action(action_id, now),
+            SimMessage::NewBlock { block_context, .. } => {
+                let value = SimValue {
+                    proposer: recipient.0,
+                    height: block_context.ancestor_values().len() as u64,
+                };
+                let _ = self
+                    .proposals
+                    .insert((era, recipient.0, block_context.timestamp()));
+                consensus.propose(ProposedBlock::new(value, block_context), now)
+            }
+            SimMessage::Validate { proposed_block, .. } => {
+                consensus.resolve_validity(proposed_block, true, now)
+            }
+        };
+        self.handle_outcomes(recipient, era, outcomes);
+    }
+
+    /// Handles the outcomes of a node's protocol instance in the given era.
+    fn handle_outcomes(
+        &mut self,
+        node: ValidatorId,
+        era: u64,
+        outcomes: ProtocolOutcomes<SimContext>,
+    ) {
+        let mut messages = Vec::new();
+        for outcome in outcomes {
+            match outcome {
+                ProtocolOutcome::CreatedGossipMessage(payload) => {
+                    messages.extend(self.net.validators_ids().filter(|id| **id != node).map(
+                        |id| {
+                            let payload = payload.clone();
+                            (*id, SimMessage::Protocol { era, payload })
+                        },
+                    ));
+                }
+                ProtocolOutcome::CreatedTargetedMessage(payload, to) => {
+                    if let Some(recipient) = self.node_indices.get(&to) {
+                        messages.push((*recipient, SimMessage::Protocol { era, payload }));
+                    }
+                }
+                ProtocolOutcome::CreatedMessageToRandomPeer(payload) => {
+                    if let Some(peer) = self.random_peer(node) {
+                        messages.push((peer, SimMessage::Protocol { era, payload }));
+                    }
+                }
+                ProtocolOutcome::CreatedRequestToRandomPeer(payload) => {
+                    if let Some(peer) = self.random_peer(node) {
+                        messages.push((peer, SimMessage::Request { era, payload }));
+                    }
+                }
+                ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => {
+                    let timer = SimMessage::Timer {
+                        era,
+                        timestamp,
+                        timer_id,
+                    };
+                    self.schedule(node, timestamp, timer);
+                }
+                ProtocolOutcome::QueueAction(action_id) => {
+                    self.schedule(node, self.now, SimMessage::Action { era, action_id });
+                }
+                ProtocolOutcome::CreateNewBlock(block_context, _) => {
+                    let new_block = SimMessage::NewBlock { era, block_context };
+                    self.schedule(node, self.now, new_block);
+                }
+                ProtocolOutcome::FinalizedBlock(finalized_block) => {
+                    self.handle_finalized_block(node, era, finalized_block)
+                }
+                ProtocolOutcome::ValidateConsensusValue { proposed_block, .. } => {
+                    let validate = SimMessage::Validate {
+                        era,
+                        proposed_block,
+                    };
+                    self.schedule(node, self.now, validate);
+                }
+                ProtocolOutcome::NewEvidence(validator_id) => {
+                    self.handle_new_evidence(node, era, validator_id)
+                }
+                ProtocolOutcome::SendEvidence(sender, validator_id) => {
+                    self.send_evidence(node, era, sender, validator_id)
+                }
+                ProtocolOutcome::FttExceeded => {
+                    if self.is_honest(node) {
+                        self.ftt_exceeded = true;
+                    }
+                }
+                ProtocolOutcome::WeAreFaulty
+                | ProtocolOutcome::DoppelgangerDetected
+                | ProtocolOutcome::Disconnect(_)
+                | ProtocolOutcome::HandledProposedBlock(_) => {}
+                ProtocolOutcome::SignHash { .. } => {
+                    unreachable!("simulated validators sign without blocking")
+                }
+            }
+        }
+        self.send(node, messages);
+    }
+
+    /// Records a finalized block, and starts the next era if it is a terminal block.
+    fn handle_finalized_block(
+        &mut self,
+        node: ValidatorId,
+        era: u64,
+        finalized_block: FinalizedBlock<SimContext>,
+    ) {
+        let now = self.now;
+        let honest = self.is_honest(node);
+        let FinalizedBlock {
+            value,
+            timestamp,
+            equivocators,
+            terminal_block_data,
+            ..
+        } = finalized_block;
+        let Some(sim_node) = self.net.node_mut(&node) else {
+            return;
+        };
+        if sim_node.validator().current_era() != Some(era) {
+            return; // Finalized block in an old era.
+        }
+        sim_node.push_finalized(Finalized {
+            era,
+            proposer: value.proposer,
+            timestamp,
+            finalized_at: now,
+        });
+        let Some(terminal_block_data) = terminal_block_data else {
+            return;
+        };
+        let completed_eras = &mut sim_node.validator_mut().completed_eras;
+        *completed_eras = completed_eras.saturating_add(1);
+        if honest {
+            let _ = self.era_ends.entry(era).or_insert_with(|| {
+                let equivocators = equivocators.iter().map(|validator_id| validator_id.0);
+                (now, equivocators.collect())
+            });
+        }
+        let next_era = era.saturating_add(1);
+        if next_era < self.scenario.eras {
+            let faulty = equivocators.into_iter().collect();
+            let inactive = terminal_block_data
+                .inactive_validators
+                .into_iter()
+                .collect();
+            self.start_era(node, next_era, timestamp, &faulty, &inactive);
+        }
+    }
+
+    /// Marks the validator as faulty in the node's later eras, and records the fault if it is the
+    /// first time an honest node detected it.
+    fn handle_new_evidence(&mut self, node: ValidatorId, era: u64, validator_id: ValidatorId) {
+        if let Some(sim_node) = self.net.node_mut(&node).map(Node::validator_mut) {
+            let later_eras = era.saturating_add(1)..=era.saturating_add(PAST_EVIDENCE_ERAS);
+            for consensus in sim_node.eras.range_mut(later_eras).map(|(_, cp)| cp) {
+                consensus.mark_faulty(&validator_id);
+            }
+        }
+        let already_detected = self
+            .faults
+            .iter()
+            .any(|fault| fault.validator == validator_id.0 && fault.era == era);
+        if self.is_honest(node) && !already_detected {
+            self.faults.push(FaultReport {
+                validator: validator_id.0,
+                era,
+                detected_at_millis: self.now.millis(),
+                detected_by: node.0,
+            });
+        }
+    }
+
+    /// Sends the evidence against the validator from the node's earlier eras to the peer.
+    fn send_evidence(
+        &mut self,
+        node: ValidatorId,
+        era: u64,
+        peer: NodeId,
+        validator_id: ValidatorId,
+    ) {
+        let Some(sim_node) = self.net.node_mut(&node).map(Node::validator_mut) else {
+            return;
+        };
+        let earlier_eras = era.saturating_sub(PAST_EVIDENCE_ERAS)..era;
+        let outcomes: Vec<_> = sim_node
+            .eras
+            .range(earlier_eras)
+            .map(|(era, consensus)| (*era, consensus.send_evidence(peer, &validator_id)))
+            .collect();
+        for (era, outcomes) in outcomes {
+            self.handle_outcomes(node, era, outcomes);
+        }
+    }
+
+    /// Returns a random node other than the given one.
+    fn random_peer(&mut self, node: ValidatorId) -> Option<ValidatorId> {
+        let peers: Vec<ValidatorId> = self
+            .net
+            .validators_ids()
+            .filter(|id| **id != node)
+            .copied()
+            .collect();
+        peers.choose(&mut self.rng).copied()
+    }
+
+    /// Returns whether the node's validator is crashed at the current time.
+    fn is_crashed(&self, node: ValidatorId) -> bool {
+        let validator = self.node_validators[node.0 as usize];
+        self.scenario.is_crashed(validator.0, self.elapsed())
+    }
+
+    /// Schedules a local event for the node, at the given time or now, whichever is later.
+    fn schedule(&mut self, node: ValidatorId, timestamp: Timestamp, event: SimMessage) {
+        let message = Message::new(node, event);
+        let target = Target::SingleValidator(node);
+        let delivery_time = timestamp.max(self.now);
+        self.net
+            .dispatch_messages(vec![(TargetedMessage::new(message, target), delivery_time)]);
+    }
+
+    /// Sends the messages over the simulated network, with random delays.
+    fn send(&mut self, sender: ValidatorId, mut messages: Vec<(ValidatorId, SimMessage)>) {
+        // Sort the messages so the delays are drawn in a deterministic order.
+        messages.sort();
+        let targeted = messages
+            .into_iter()
+            .filter_map(
+                |(recipient, payload)| match self.delivery_schedule(sender, recipient) {
+                    DeliverySchedule::Drop => None,
+                    DeliverySchedule::AtInstant(delivery_time) => {
+                        let message = Message::new(sender, payload);
+                        let target = Target::SingleValidator(recipient);
+                        Some((TargetedMessage::new(message, target), delivery_time))
+                    }
+                },
+            )
+            .collect();
+        self.net.dispatch_messages(targeted);
+    }
+
+    /// Returns when a message from the sender to the recipient will arrive, if at all.
+    fn delivery_schedule(
+        &mut self,
+        sender: ValidatorId,
+        recipient: ValidatorId,
+    ) -> DeliverySchedule {
+        if !self.is_original(recipient) {
+            return DeliverySchedule::Drop; // Twins are isolated so that they equivocate.
+        }
+        let elapsed = self.elapsed();
+        let sender = self.node_validators[sender.0 as usize].0;
+        let recipient = self.node_validators[recipient.0 as usize].0;
+        if self.scenario.is_crashed(sender, elapsed)
+            || self.scenario.is_partitioned(sender, recipient, elapsed)
+        {
+            return DeliverySchedule::Drop;
+        }
+        let delay = self.scenario.latency.sample(&mut self.rng);
+        DeliverySchedule::AtInstant(self.now.saturating_add(delay))
+    }
+
+    /// Computes the statistics about the simulation.
+    fn report(self) -> Report {
+        let honest_nodes: Vec<_> = self
+            .net
+            .validators()
+            .filter(|node| self.is_honest(node.id))
+            .collect();
+
+        let mut finalized = BTreeSet::new();
+        let mut latencies = Vec::new();
+        let mut era_latencies: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+        for block in honest_nodes.iter().flat_map(|node| node.finalized_values()) {
+            let latency = block.finalized_at.saturating_diff(block.timestamp).millis();
+            latencies.push(latency);
+            era_latencies.entry(block.era).or_default().push(latency);
+            let _ = finalized.insert(block.key());
+        }
+
+        // The nodes agree if every node's sequence of finalized blocks is a prefix of the longest.
+        let sequences: Vec<Vec<BlockKey>> = honest_nodes
+            .iter()
+            .map(|node| node.finalized_values().map(Finalized::key).collect())
+            .collect();
+        let longest = sequences.iter().max_by_key(|sequence| sequence.len());
+        let agreement = sequences
+            .iter()
+            .all(|sequence| longest.map_or(true, |longest| longest.starts_with(sequence)));
+
+        let eras: Vec<EraSummary> = self
+            .era_starts
+            .iter()
+            .map(|(era, start)| {
+                let era_end = self.era_ends.get(era);
+                let in_era = |key: &&BlockKey| key.0 == *era;
+                let orphaned_rounds = era_end.map(|_| {
+                    let proposals = self.proposals.iter().filter(in_era);
+                    proposals.filter(|key| !finalized.contains(*key)).count() as u64
+                });
+                EraSummary {
+                    era: *era,
+                    start_millis: start.millis(),
+                    end_millis: era_end.map(|(end, _)| end.millis()),
+                    finalized_blocks: finalized.iter().filter(in_era).count() as u64,
+                    proposals: self.proposals.iter().filter(in_era).count() as u64,
+                    orphaned_rounds,
+                    finality_latency: era_latencies.remove(era).and_then(LatencyStats::new),
+                    equivocators: era_end
+                        .map(|(_, equivocators)| equivocators.clone())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        Report {
+            protocol: self.protocol,
+            seed: self.scenario.seed,
+            duration_millis: self.now.millis(),
+            completed: self.is_complete(),
+            agreement,
+            finality_latency: LatencyStats::new(latencies),
+            orphaned_rounds: eras.iter().filter_map(|era| era.orphaned_rounds).sum(),
+            faults: self.faults,
+            ftt_exceeded: self.ftt_exceeded,
+            eras,
+        }
+    }
+}
+
+/// Returns the network address of the node with the given index.
+fn node_id(index: u64) -> NodeId {
+    let mut bytes = [0; KeyFingerprint::LENGTH];
+    bytes[..8].copy_from_slice(&index.to_le_bytes());
+    NodeId::from(KeyFingerprint::from(bytes))
+}
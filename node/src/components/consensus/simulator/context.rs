@@ -0,0 +1,74 @@
+//! The consensus context used in simulations: cheap, deterministic hashes and signatures.
+
+use std::fmt::{self, Display, Formatter};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_hashing::Digest;
+
+use super::virtual_net::ValidatorId;
+use crate::components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret};
+
+/// A simulated block, identified by the node that proposed it and its height in the era.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, DataSize)]
+pub(crate) struct SimValue {
+    /// The index of the node that proposed the block.
+    pub(crate) proposer: u64,
+    /// The number of the block's ancestors in the same era.
+    pub(crate) height: u64,
+}
+
+impl ConsensusValueT for SimValue {
+    fn needs_validation(&self) -> bool {
+        true
+    }
+}
+
+impl Display for SimValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "block {} by node {}", self.height, self.proposer)
+    }
+}
+
+/// A validator's "secret key": signatures are just hashes of the message and the validator ID.
+#[derive(Clone, Debug, DataSize)]
+pub(crate) struct SimSecret(pub(crate) ValidatorId);
+
+impl ValidatorSecret for SimSecret {
+    type Hash = Digest;
+    type Signature = Digest;
+    type InstanceId = Digest;
+
+    fn sign(&self, hash: &Digest) -> Option<Digest> {
+        Some(signature(hash, &self.0))
+    }
+}
+
+/// The context of a simulated consensus protocol instance.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) struct SimContext;
+
+impl Context for SimContext {
+    type ConsensusValue = SimValue;
+    type ValidatorId = ValidatorId;
+    type ValidatorSecret = SimSecret;
+    type Signature = Digest;
+    type Hash = Digest;
+    type InstanceId = Digest;
+
+    fn hash(data: &[u8]) -> Digest {
+        Digest::hash(data)
+    }
+
+    fn verify_signature(hash: &Digest, public_key: &ValidatorId, signature: &Digest) -> bool {
+        self::signature(hash, public_key) == *signature
+    }
+}
+
+/// Returns the signature of the given hash by the given validator.
+fn signature(hash: &Digest, validator_id: &ValidatorId) -> Digest {
+    let mut data = hash.value().to_vec();
+    data.extend_from_slice(&validator_id.0.to_le_bytes());
+    Digest::hash(data)
+}
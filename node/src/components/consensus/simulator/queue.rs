@@ -2,7 +2,7 @@ use std::{cmp::Ordering, collections::BinaryHeap, fmt::Debug};
 
 use casper_types::Timestamp;
 
-use super::consensus_des_testing::{Message, ValidatorId};
+use super::virtual_net::{Message, ValidatorId};
 
 pub(crate) trait MessageT: PartialEq + Eq + Ord + Clone + Debug {}
 impl<T> MessageT for T where T: PartialEq + Eq + Ord + Clone + Debug {}
@@ -118,6 +118,7 @@ where
         self.0.push(item)
     }
 
+    #[cfg(test)]
     pub(crate) fn clear(&mut self) {
         self.0.clear();
     }
@@ -0,0 +1,104 @@
+//! The results of a simulation.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::chainspec::ConsensusProtocolName;
+
+/// The outcome of a simulation.  All times are in milliseconds since the start of the simulation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// The simulated consensus protocol.
+    pub protocol: ConsensusProtocolName,
+    /// The seed the simulation was run with.
+    pub seed: u64,
+    /// The simulated time until the last event was handled.
+    pub duration_millis: u64,
+    /// Whether all honest validators that didn't crash permanently completed all eras.
+    pub completed: bool,
+    /// Whether the honest nodes agree on the finalized blocks, i.e. each node's sequence of
+    /// finalized blocks is a prefix of another one's.
+    pub agreement: bool,
+    /// The time from a block's timestamp until an honest node finalized it, over all honest nodes
+    /// and blocks.
+    pub finality_latency: Option<LatencyStats>,
+    /// The number of proposals in completed eras that never got finalized.
+    pub orphaned_rounds: u64,
+    /// The faults detected by honest nodes, in the order of detection.
+    pub faults: Vec<FaultReport>,
+    /// Whether an honest node observed that the fault tolerance threshold was exceeded.
+    pub ftt_exceeded: bool,
+    /// Per-era statistics.
+    pub eras: Vec<EraSummary>,
+}
+
+/// Statistics about the distribution of finality latencies.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// The number of finalized blocks the statistics are computed from.
+    pub samples: u64,
+    /// The minimum latency.
+    pub min_millis: u64,
+    /// The arithmetic mean of the latencies.
+    pub mean_millis: u64,
+    /// The median latency.
+    pub median_millis: u64,
+    /// The 90th percentile of the latencies.
+    pub p90_millis: u64,
+    /// The maximum latency.
+    pub max_millis: u64,
+}
+
+impl LatencyStats {
+    /// Returns the statistics of the given latencies, or `None` if there are none.
+    pub(super) fn new(mut latencies: Vec<u64>) -> Option<Self> {
+        latencies.sort_unstable();
+        let samples = latencies.len() as u64;
+        let sum: u128 = latencies.iter().map(|latency| u128::from(*latency)).sum();
+        let percentile = |percent: usize| {
+            let index = latencies.len().saturating_mul(percent) / 100;
+            latencies[index.min(latencies.len().saturating_sub(1))]
+        };
+        Some(LatencyStats {
+            samples,
+            min_millis: *latencies.first()?,
+            mean_millis: sum.checked_div(u128::from(samples))? as u64,
+            median_millis: percentile(50),
+            p90_millis: percentile(90),
+            max_millis: *latencies.last()?,
+        })
+    }
+}
+
+/// A fault detected during the simulation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultReport {
+    /// The index of the faulty validator.
+    pub validator: u64,
+    /// The era in which the fault was committed.
+    pub era: u64,
+    /// The time at which the first honest node detected the fault.
+    pub detected_at_millis: u64,
+    /// The index of the first honest node that detected the fault.
+    pub detected_by: u64,
+}
+
+/// Statistics about a single era.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EraSummary {
+    /// The era number, starting at 0.
+    pub era: u64,
+    /// The time at which the first honest node started the era.
+    pub start_millis: u64,
+    /// The time at which the first honest node finalized the era's terminal block, if any.
+    pub end_millis: Option<u64>,
+    /// The number of blocks finalized in this era.
+    pub finalized_blocks: u64,
+    /// The number of blocks proposed in this era.
+    pub proposals: u64,
+    /// The number of proposals that never got finalized, if the era is complete.
+    pub orphaned_rounds: Option<u64>,
+    /// The finality latencies of the blocks in this era.
+    pub finality_latency: Option<LatencyStats>,
+    /// The validators that were known to be faulty in the era's terminal block.
+    pub equivocators: Vec<u64>,
+}
@@ -0,0 +1,216 @@
+//! Simulation scenarios.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_types::TimeDiff;
+
+use crate::{types::chainspec::ConsensusProtocolName, NodeRng};
+
+/// A simulation scenario, usually loaded from a TOML file.
+///
+/// Validators are referred to by their index in `weights`.  Times are relative to the start of the
+/// simulation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+// Disallow unknown fields to ensure scenario files contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// The seed for all random choices.  Running the same scenario with the same seed always has
+    /// the same outcome.
+    pub seed: u64,
+    /// The consensus protocol to run.  Defaults to the chainspec's `core.consensus_protocol`.
+    #[serde(default)]
+    pub protocol: Option<ConsensusProtocolName>,
+    /// The validators' weights.
+    pub weights: Vec<u64>,
+    /// The number of eras to simulate.
+    pub eras: u64,
+    /// The simulation stops after this much simulated time, even if not all eras are complete.
+    pub max_duration: TimeDiff,
+    /// The minimum duration of an era.  Defaults to the chainspec's `core.era_duration`.
+    #[serde(default)]
+    pub era_duration: Option<TimeDiff>,
+    /// The minimum number of blocks per era.  Defaults to the chainspec's
+    /// `core.minimum_era_height`.
+    #[serde(default)]
+    pub minimum_era_height: Option<u64>,
+    /// The minimum time between blocks.  Defaults to the chainspec's `core.minimum_block_time`.
+    #[serde(default)]
+    pub minimum_block_time: Option<TimeDiff>,
+    /// The distribution of the delays of messages between validators.
+    pub latency: Latency,
+    /// Intervals in which the network is partitioned.
+    #[serde(default)]
+    pub partitions: Vec<Partition>,
+    /// Intervals in which validators are crashed.
+    #[serde(default)]
+    pub crashes: Vec<Crash>,
+    /// Validators which run a second, isolated node with the same key, equivocating.
+    #[serde(default)]
+    pub equivocators: Vec<u64>,
+}
+
+impl Scenario {
+    /// Returns an error message if the scenario is inconsistent.
+    pub(super) fn validate(&self) -> Result<(), String> {
+        if self.weights.is_empty() {
+            return Err("no validators".to_string());
+        }
+        if self.weights.contains(&0) {
+            return Err("validator weights must be positive".to_string());
+        }
+        if self.eras == 0 {
+            return Err("at least one era must be simulated".to_string());
+        }
+        self.latency.validate()?;
+        let validator_count = self.weights.len() as u64;
+        let check_validator = |validator: &u64| {
+            if *validator >= validator_count {
+                return Err(format!("unknown validator {}", validator));
+            }
+            Ok(())
+        };
+        for partition in &self.partitions {
+            if partition.from >= partition.until {
+                return Err("partitions must end after they start".to_string());
+            }
+            partition
+                .groups
+                .iter()
+                .flatten()
+                .try_for_each(check_validator)?;
+        }
+        for crash in &self.crashes {
+            if crash.until.map_or(false, |until| crash.from >= until) {
+                return Err("crashed validators must recover after they crash".to_string());
+            }
+            check_validator(&crash.validator)?;
+        }
+        self.equivocators.iter().try_for_each(check_validator)
+    }
+
+    /// Returns whether the validator is crashed at the given time.
+    pub(super) fn is_crashed(&self, validator: u64, time: TimeDiff) -> bool {
+        self.crashes
+            .iter()
+            .any(|crash| crash.validator == validator && crash.is_active(time))
+    }
+
+    /// Returns whether the validator crashes at some point and never recovers.
+    pub(super) fn is_permanently_crashed(&self, validator: u64) -> bool {
+        self.crashes
+            .iter()
+            .any(|crash| crash.validator == validator && crash.until.is_none())
+    }
+
+    /// Returns whether the two validators are in different groups of a partition at the given time.
+    pub(super) fn is_partitioned(&self, validator0: u64, validator1: u64, time: TimeDiff) -> bool {
+        self.partitions
+            .iter()
+            .any(|partition| partition.separates(validator0, validator1, time))
+    }
+}
+
+/// The distribution of message delays.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "distribution")]
+pub enum Latency {
+    /// Every message is delayed by the same amount.
+    Fixed {
+        /// The delay.
+        delay: TimeDiff,
+    },
+    /// Delays are uniformly distributed in the given range.
+    Uniform {
+        /// The minimum delay.
+        min: TimeDiff,
+        /// The maximum delay.
+        max: TimeDiff,
+    },
+    /// Delays are a minimum plus an exponentially distributed value, i.e. most messages arrive
+    /// quickly, but some take much longer.
+    Exponential {
+        /// The minimum delay.
+        min: TimeDiff,
+        /// The mean of the delay in addition to the minimum.
+        mean: TimeDiff,
+    },
+}
+
+impl Latency {
+    /// Returns an error message if the distribution is invalid.
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Latency::Uniform { min, max } if min > max => {
+                Err("the minimum latency must not exceed the maximum".to_string())
+            }
+            Latency::Fixed { .. } | Latency::Uniform { .. } | Latency::Exponential { .. } => Ok(()),
+        }
+    }
+
+    /// Returns a random delay.
+    pub(super) fn sample(&self, rng: &mut NodeRng) -> TimeDiff {
+        match self {
+            Latency::Fixed { delay } => *delay,
+            Latency::Uniform { min, max } => {
+                TimeDiff::from_millis(rng.gen_range(min.millis()..=max.millis()))
+            }
+            Latency::Exponential { min, mean } => {
+                let uniform: f64 = rng.gen();
+                // The inverse of the exponential distribution's cumulative distribution function.
+                let extra = -(1.0 - uniform).ln() * mean.millis() as f64;
+                min.saturating_add(extra as u64)
+            }
+        }
+    }
+}
+
+/// An interval in which the network is split into groups of validators which can't communicate
+/// with each other.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Partition {
+    /// The time at which the partition starts.
+    pub from: TimeDiff,
+    /// The time at which the partition heals.
+    pub until: TimeDiff,
+    /// The groups of validators which can only communicate within their group.  Validators not
+    /// listed in any group can't communicate with anyone.
+    pub groups: Vec<Vec<u64>>,
+}
+
+impl Partition {
+    /// Returns whether messages sent between the two validators at the given time are dropped.
+    fn separates(&self, validator0: u64, validator1: u64, time: TimeDiff) -> bool {
+        if time < self.from || time >= self.until || validator0 == validator1 {
+            return false;
+        }
+        let group = |validator| self.groups.iter().position(|g| g.contains(&validator));
+        match (group(validator0), group(validator1)) {
+            (Some(group0), Some(group1)) => group0 != group1,
+            (None, _) | (_, None) => true,
+        }
+    }
+}
+
+/// An interval in which a validator is crashed: it neither sends nor receives any messages.
+///
+/// The validator keeps its state, as if it was restarted from its write-ahead log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Crash {
+    /// The crashed validator.
+    pub validator: u64,
+    /// The time at which the validator crashes.
+    pub from: TimeDiff,
+    /// The time at which the validator recovers.  If not given, it never does.
+    #[serde(default)]
+    pub until: Option<TimeDiff>,
+}
+
+impl Crash {
+    /// Returns whether the validator is crashed at the given time.
+    fn is_active(&self, time: TimeDiff) -> bool {
+        self.from <= time && self.until.map_or(true, |until| time < until)
+    }
+}
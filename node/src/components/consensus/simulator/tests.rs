@@ -0,0 +1,108 @@
+use std::thread;
+
+use casper_types::TimeDiff;
+
+use super::{Error, Latency, Report, Scenario};
+use crate::{
+    types::{
+        chainspec::{ChainspecRawBytes, ConsensusProtocolName},
+        Chainspec,
+    },
+    utils::Loadable,
+};
+
+fn scenario(protocol: ConsensusProtocolName) -> Scenario {
+    Scenario {
+        seed: 42,
+        protocol: Some(protocol),
+        weights: vec![10, 20, 30, 40],
+        eras: 2,
+        max_duration: TimeDiff::from_seconds(600),
+        era_duration: Some(TimeDiff::from_seconds(10)),
+        minimum_era_height: Some(3),
+        minimum_block_time: Some(TimeDiff::from_millis(1024)),
+        latency: Latency::Uniform {
+            min: TimeDiff::from_millis(10),
+            max: TimeDiff::from_millis(100),
+        },
+        partitions: vec![],
+        crashes: vec![],
+        equivocators: vec![],
+    }
+}
+
+/// Runs the scenario in a separate thread, since only one `TestRng` can be created per thread.
+fn run(scenario: Scenario) -> Result<Report, Error> {
+    thread::spawn(move || {
+        let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("production");
+        super::run(&scenario, chainspec)
+    })
+    .join()
+    .expect("simulation panicked")
+}
+
+fn assert_success(report: &Report) {
+    assert!(report.completed, "not all eras completed: {:?}", report);
+    assert!(
+        report.agreement,
+        "nodes finalized different blocks: {:?}",
+        report
+    );
+    assert!(!report.ftt_exceeded);
+    assert_eq!(2, report.eras.len());
+    assert!(report.finality_latency.is_some());
+}
+
+#[test]
+fn highway_completes_eras() {
+    let report = run(scenario(ConsensusProtocolName::Highway)).expect("valid scenario");
+    assert_success(&report);
+    assert!(report.faults.is_empty());
+}
+
+#[test]
+fn zug_completes_eras() {
+    let report = run(scenario(ConsensusProtocolName::Zug)).expect("valid scenario");
+    assert_success(&report);
+    assert!(report.faults.is_empty());
+}
+
+#[test]
+fn same_seed_same_report() {
+    let mut scenario = scenario(ConsensusProtocolName::Zug);
+    scenario.latency = Latency::Exponential {
+        min: TimeDiff::from_millis(10),
+        mean: TimeDiff::from_millis(200),
+    };
+    let report0 = run(scenario.clone()).expect("valid scenario");
+    let report1 = run(scenario.clone()).expect("valid scenario");
+    assert_eq!(report0, report1);
+
+    scenario.seed = 43;
+    let report2 = run(scenario).expect("valid scenario");
+    assert_ne!(report0, report2);
+}
+
+#[test]
+fn equivocator_is_reported() {
+    let mut scenario = scenario(ConsensusProtocolName::Zug);
+    scenario.equivocators = vec![0];
+    let report = run(scenario).expect("valid scenario");
+    assert_success(&report);
+    assert!(
+        report.faults.iter().any(|fault| fault.validator == 0),
+        "equivocation not detected: {:?}",
+        report
+    );
+}
+
+#[test]
+fn invalid_scenario_is_rejected() {
+    let mut scenario = scenario(ConsensusProtocolName::Zug);
+    scenario.crashes = vec![super::Crash {
+        validator: 4,
+        from: TimeDiff::from_seconds(1),
+        until: None,
+    }];
+    assert!(matches!(run(scenario), Err(Error::InvalidScenario(_))));
+}
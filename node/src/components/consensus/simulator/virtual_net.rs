@@ -2,6 +2,7 @@ use std::{
     collections::BTreeMap,
     fmt::{Debug, Display, Formatter},
     hash::Hash,
+    marker::PhantomData,
 };
 
 use datasize::DataSize;
@@ -14,6 +15,9 @@ use super::queue::{MessageT, Queue, QueueEntry};
 #[derive(Debug)]
 pub(crate) enum Target {
     SingleValidator(ValidatorId),
+    /// Only used by the test harnesses: the simulator schedules the delivery to each recipient
+    /// separately.
+    #[cfg(test)]
     AllExcept(ValidatorId),
 }
 
@@ -63,6 +67,7 @@ impl Display for ValidatorId {
     }
 }
 
+#[cfg(test)]
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum Fault {
     /// The validator does not send any messages within the interval between the timestamps.
@@ -83,10 +88,14 @@ where
     /// Vector of consensus values finalized by the validator.
     finalized_values: Vec<C>,
     /// Messages received by the validator.
+    #[cfg(test)]
     messages_received: Vec<Message<M>>,
     /// Messages produced by the validator.
+    #[cfg(test)]
     messages_produced: Vec<M>,
     validator: V,
+    /// Outside of tests, the messages are not recorded.
+    _message: PhantomData<M>,
 }
 
 impl<C, M, V> Node<C, M, V>
@@ -97,9 +106,12 @@ where
         Node {
             id,
             finalized_values: Vec::new(),
+            #[cfg(test)]
             messages_received: Vec::new(),
+            #[cfg(test)]
             messages_produced: Vec::new(),
             validator,
+            _message: PhantomData,
         }
     }
 
@@ -109,11 +121,13 @@ where
     }
 
     /// Adds messages to validator's collection of received messages.
+    #[cfg(test)]
     pub(crate) fn push_messages_received(&mut self, messages: Vec<Message<M>>) {
         self.messages_received.extend(messages);
     }
 
     /// Adds messages to validator's collection of produced messages.
+    #[cfg(test)]
     pub(crate) fn push_messages_produced(&mut self, messages: Vec<M>) {
         self.messages_produced.extend(messages);
     }
@@ -123,10 +137,12 @@ where
         self.finalized_values.iter()
     }
 
+    #[cfg(test)]
     pub(crate) fn messages_produced(&self) -> impl Iterator<Item = &M> {
         self.messages_produced.iter()
     }
 
+    #[cfg(test)]
     pub(crate) fn finalized_count(&self) -> usize {
         self.finalized_values.len()
     }
@@ -142,7 +158,6 @@ where
 
 pub(crate) enum DeliverySchedule {
     AtInstant(Timestamp),
-    #[allow(dead_code)] // Drop variant used in tests.
     Drop,
 }
 
@@ -202,6 +217,7 @@ where
     pub(crate) fn dispatch_messages(&mut self, messages: Vec<(TargetedMessage<M>, Timestamp)>) {
         for (TargetedMessage { message, target }, delivery_time) in messages {
             let recipients = match target {
+                #[cfg(test)]
                 Target::AllExcept(creator) => self
                     .validators_ids()
                     .filter(|id| **id != creator)
@@ -233,6 +249,7 @@ where
         self.validators_map.get_mut(validator_id)
     }
 
+    #[cfg(test)]
     pub(crate) fn validator(&self, validator_id: &ValidatorId) -> Option<&Node<C, M, V>> {
         self.validators_map.get(validator_id)
     }
@@ -266,11 +283,13 @@ where
 
     /// Drops all messages from the queue.
     /// Should never be called during normal operation of the test.
+    #[cfg(test)]
     pub(crate) fn empty_queue(&mut self) {
         self.msg_queue.clear();
     }
 }
 
+#[cfg(test)]
 mod virtual_net_tests {
     use super::{Message, Node, Target, TargetedMessage, Timestamp, ValidatorId, VirtualNet};
 
@@ -39,13 +39,87 @@ impl<H> HashT for H where
 {
 }
 
+/// The kind of a message signed by a validator, which determines whether it can conflict with
+/// other messages.
+#[derive(Clone, Copy, DataSize, Debug, Eq, PartialEq, Hash)]
+pub enum SignatureKind<H> {
+    /// A Highway unit with the given sequence number.
+    HighwayUnit {
+        /// The unit's sequence number.
+        seq_number: u64,
+    },
+    /// A Zug echo of the given proposal.
+    ZugEcho {
+        /// The round in which the proposal is echoed.
+        round_id: u32,
+        /// The hash of the echoed proposal.
+        proposal_hash: H,
+    },
+    /// A Zug vote.
+    ZugVote {
+        /// The round in which the vote is cast.
+        round_id: u32,
+        /// Whether the vote is in favor of the round's proposal.
+        vote: bool,
+    },
+    /// Any other message, which cannot conflict with other messages.
+    Other,
+}
+
 /// A validator's secret signing key.
+///
+/// Signing can fail, e.g. if the key is held by an external signer which refuses to sign a message
+/// conflicting with one it signed before, in which case `None` is returned.
 pub trait ValidatorSecret: Send + DataSize {
     type Hash: DataSize;
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    type InstanceId;
+
+    /// Returns whether signing can block, e.g. because the key is held by an external signer.
+    ///
+    /// The consensus protocols don't call the `sign` methods of such a secret. Instead they return
+    /// a `ProtocolOutcome::SignHash` and continue once the signature is passed to
+    /// `ConsensusProtocol::handle_signature`.
+    fn may_block(&self) -> bool {
+        false
+    }
+
+    /// Signs a message that cannot conflict with any other message.
+    fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
+
+    /// Signs the hash of a Highway unit with the given sequence number.
+    fn sign_unit(
+        &self,
+        hash: &Self::Hash,
+        _instance_id: &Self::InstanceId,
+        _seq_number: u64,
+    ) -> Option<Self::Signature> {
+        self.sign(hash)
+    }
+
+    /// Signs the hash of a Zug echo of the given proposal.
+    fn sign_echo(
+        &self,
+        hash: &Self::Hash,
+        _instance_id: &Self::InstanceId,
+        _round_id: u32,
+        _proposal_hash: &Self::Hash,
+    ) -> Option<Self::Signature> {
+        self.sign(hash)
+    }
+
+    /// Signs the hash of a Zug vote.
+    fn sign_vote(
+        &self,
+        hash: &Self::Hash,
+        _instance_id: &Self::InstanceId,
+        _round_id: u32,
+        _vote: bool,
+    ) -> Option<Self::Signature> {
+        self.sign(hash)
+    }
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
@@ -57,7 +131,11 @@ pub trait Context: Clone + DataSize + Debug + Eq + Ord + Hash + Send {
     /// Unique identifiers for validators.
     type ValidatorId: ValidatorIdT;
     /// A validator's secret signing key.
-    type ValidatorSecret: ValidatorSecret<Hash = Self::Hash, Signature = Self::Signature>;
+    type ValidatorSecret: ValidatorSecret<
+        Hash = Self::Hash,
+        Signature = Self::Signature,
+        InstanceId = Self::InstanceId,
+    >;
     /// A signature type.
     type Signature: Copy
         + Clone
@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_types::Timestamp;
+
+/// A validator's participation in an era, as observed by this node.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
+pub enum ValidatorParticipation {
+    /// The validator has recently sent messages.
+    Active,
+    /// The validator has sent messages in this era, but not recently.
+    Offline,
+    /// We haven't received any messages from the validator in this era.
+    Inactive,
+    /// We saw the validator misbehave in this era.
+    Equivocated,
+    /// The validator is banned because they misbehaved in an earlier era.
+    EquivocatedInOtherEra,
+}
+
+/// A validator's performance in an era, as observed by this node.
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorPerformance {
+    /// The validator's participation status.
+    pub participation: ValidatorParticipation,
+    /// The time we last received a message from the validator in this era, if any.
+    pub last_seen: Option<Timestamp>,
+    /// The number of rounds in which the validator was the leader and made a proposal.
+    pub proposals_made: u64,
+    /// The number of rounds in which the validator was the leader but we received no proposal.
+    pub proposals_missed: u64,
+    /// The validator's current round exponent: Its round length is the minimum round length
+    /// times two to the power of the exponent. This is only used by Highway.
+    pub round_exponent: Option<u8>,
+}
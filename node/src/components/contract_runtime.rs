@@ -1,5 +1,6 @@
 //! Contract Runtime component.
 
+mod compaction;
 mod config;
 mod error;
 mod metrics;
@@ -25,24 +26,32 @@ use once_cell::sync::Lazy;
 use prometheus::Registry;
 use serde::Serialize;
 use thiserror::Error;
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
 use casper_execution_engine::{
-    core::engine_state::{
-        self,
-        engine_config::{FeeHandling, RefundHandling},
-        genesis::GenesisError,
-        ChainspecRegistry, DeployItem, EngineConfigBuilder, EngineState, GenesisSuccess,
-        SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
+    core::{
+        engine_state::{
+            self,
+            engine_config::{FeeHandling, RefundHandling},
+            genesis::GenesisError,
+            ChainspecRegistry, DeployItem, EngineConfigBuilder, EngineState, ExecutionPhaseCosts,
+            GenesisSuccess, SystemContractRegistry, UpgradeConfig, UpgradeSuccess,
+        },
+        runtime::ExecutionTrace,
     },
     shared::{newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig},
     storage::{
-        global_state::lmdb::LmdbGlobalState, transaction_source::lmdb::LmdbEnvironment,
-        trie_store::lmdb::LmdbTrieStore,
+        global_state::{
+            lmdb::LmdbGlobalState, persistent::PersistentGlobalState, sled::SledGlobalState,
+        },
+        transaction_source::{lmdb::LmdbEnvironment, sled::SledEnvironment},
+        trie_store::{lmdb::LmdbTrieStore, sled::SledTrieStore},
     },
 };
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::Bytes, EraId, ProtocolVersion, PublicKey, Timestamp};
+use casper_types::{
+    bytesrepr::Bytes, EraId, ExecutionResult, ProtocolVersion, PublicKey, Timestamp, U512,
+};
 
 use crate::{
     components::{fetcher::FetchResponse, Component, ComponentState},
@@ -63,11 +72,12 @@ use crate::{
     },
     NodeRng,
 };
-pub(crate) use config::Config;
+use compaction::GlobalStateCompaction;
+pub(crate) use config::{Config, GlobalStateBackend};
 pub(crate) use error::{BlockExecutionError, ConfigError};
 use metrics::Metrics;
 pub use operations::execute_finalized_block;
-use operations::execute_only;
+use operations::{estimate_gas, execute_only, execute_only_batch};
 pub(crate) use types::{
     BlockAndExecutionResults, EraValidatorsRequest, StepEffectAndUpcomingEraValidators,
 };
@@ -128,6 +138,14 @@ pub struct SpeculativeExecutionState {
     pub protocol_version: ProtocolVersion,
 }
 
+/// The result of speculatively executing a deploy, along with a trace of the execution if one was
+/// requested.
+pub type SpeculativeExecutionResult = (ExecutionResult, Option<ExecutionTrace>);
+
+/// The results of speculatively executing a batch of deploys, in order, each along with the gas
+/// consumed by the payment and session phases of the deploy.
+pub type SpeculativeBatchExecutionResults = Vec<(ExecutionResult, ExecutionPhaseCosts)>;
+
 /// State to use to construct the next block in the blockchain. Includes the state root hash for the
 /// execution engine as well as certain values the next header will be based on.
 #[derive(DataSize, Debug, Clone, Serialize)]
@@ -202,7 +220,7 @@ impl Display for Event {
 pub(crate) struct ContractRuntime {
     state: ComponentState,
     execution_pre_state: Arc<Mutex<ExecutionPreState>>,
-    engine_state: Arc<EngineState<LmdbGlobalState>>,
+    engine_state: Arc<EngineState<PersistentGlobalState>>,
     metrics: Arc<Metrics>,
     protocol_version: ProtocolVersion,
 
@@ -212,6 +230,11 @@ pub(crate) struct ContractRuntime {
     system_contract_registry: Option<SystemContractRegistry>,
     activation_point: ActivationPoint,
     prune_batch_size: u64,
+    /// The maximum gas which a deploy may consume when estimating its gas.
+    max_gas_estimate: u64,
+    /// Online compaction of the global state, if enabled.
+    #[data_size(skip)]
+    global_state_compaction: Option<Arc<GlobalStateCompaction>>,
 }
 
 impl Debug for ContractRuntime {
@@ -438,17 +461,28 @@ impl ContractRuntime {
                 trace!(?trie_bytes, "put_trie request");
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
+                let global_state_compaction = self.global_state_compaction.clone();
                 async move {
                     let correlation_id = CorrelationId::new();
                     let start = Instant::now();
-                    let result = engine_state
-                        .put_trie_if_all_children_present(correlation_id, trie_bytes.inner());
+                    let put_trie = || {
+                        engine_state
+                            .put_trie_if_all_children_present(correlation_id, trie_bytes.inner())
+                    };
+                    let result = match &global_state_compaction {
+                        Some(compaction) => compaction.write(|| {
+                            let result = put_trie();
+                            let maybe_trie_key = result.as_ref().ok().copied();
+                            (result, maybe_trie_key)
+                        }),
+                        None => put_trie(),
+                    };
                     // PERF: this *could* be called only periodically.
-                    if let Err(lmdb_error) = engine_state.flush_environment() {
+                    if let Err(storage_error) = engine_state.flush_environment() {
                         fatal!(
                             effect_builder,
-                            "error flushing lmdb environment {:?}",
-                            lmdb_error
+                            "error flushing global state environment {:?}",
+                            storage_error
                         )
                         .await;
                     } else {
@@ -494,6 +528,7 @@ impl ContractRuntime {
                         let shared_pre_state = Arc::clone(&self.execution_pre_state);
                         let activation_point = self.activation_point;
                         let prune_batch_size = self.prune_batch_size;
+                        let global_state_compaction = self.global_state_compaction.clone();
                         effects.extend(
                             Self::execute_finalized_block_or_requeue(
                                 engine_state,
@@ -509,6 +544,7 @@ impl ContractRuntime {
                                 key_block_height_for_activation_point,
                                 prune_batch_size,
                                 meta_block_state,
+                                global_state_compaction,
                             )
                             .ignore(),
                         )
@@ -551,6 +587,38 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::GetKeys {
+                get_keys_request,
+                responder,
+            } => {
+                trace!(?get_keys_request, "get keys request");
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        let correlation_id = CorrelationId::new();
+                        engine_state.get_keys(correlation_id, get_keys_request)
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::GetStateDiff {
+                state_diff_request,
+                responder,
+            } => {
+                trace!(?state_diff_request, "get state diff request");
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        let correlation_id = CorrelationId::new();
+                        engine_state.get_state_diff(correlation_id, state_diff_request)
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::GetExecutionResultsChecksum {
                 state_root_hash,
                 responder,
@@ -569,6 +637,7 @@ impl ContractRuntime {
             ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                enable_trace,
                 responder,
             } => {
                 let engine_state = Arc::clone(&self.engine_state);
@@ -578,6 +647,53 @@ impl ContractRuntime {
                             engine_state.as_ref(),
                             execution_prestate,
                             DeployItem::from((*deploy).clone()),
+                            enable_trace,
+                        )
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::GasEstimation {
+                execution_prestate,
+                deploy,
+                payment_amount,
+                responder,
+            } => {
+                let engine_state = Arc::clone(&self.engine_state);
+                let max_payment_amount = U512::from(self.max_gas_estimate)
+                    .saturating_mul(U512::from(deploy.header().gas_price()));
+                let payment_amount = payment_amount.min(max_payment_amount);
+                async move {
+                    let result = run_intensive_task(move || {
+                        estimate_gas(
+                            engine_state.as_ref(),
+                            execution_prestate,
+                            &deploy,
+                            payment_amount,
+                        )
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::SpeculativeDeployBatchExecution {
+                execution_prestate,
+                deploys,
+                responder,
+            } => {
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        execute_only_batch(
+                            engine_state.as_ref(),
+                            execution_prestate,
+                            deploys
+                                .iter()
+                                .map(|deploy| DeployItem::from((**deploy).clone()))
+                                .collect(),
                         )
                     })
                     .await;
@@ -620,22 +736,53 @@ impl ContractRuntime {
             parent_seed: Default::default(),
         }));
 
-        let environment = Arc::new(LmdbEnvironment::new(
-            storage_dir,
-            contract_runtime_config.max_global_state_size_or_default(),
-            contract_runtime_config.max_readers_or_default(),
-            contract_runtime_config.manual_sync_enabled_or_default(),
-        )?);
+        let mut global_state_compaction = None;
+        let global_state: PersistentGlobalState =
+            match contract_runtime_config.global_state_backend_or_default() {
+                GlobalStateBackend::Lmdb => {
+                    let environment = Arc::new(LmdbEnvironment::new(
+                        storage_dir,
+                        contract_runtime_config.max_global_state_size_or_default(),
+                        contract_runtime_config.max_readers_or_default(),
+                        contract_runtime_config.manual_sync_enabled_or_default(),
+                    )?);
+
+                    let trie_store = Arc::new(LmdbTrieStore::new(
+                        &environment,
+                        None,
+                        DatabaseFlags::empty(),
+                    )?);
+
+                    let global_state = LmdbGlobalState::empty(environment, trie_store)?;
+                    if contract_runtime_config.global_state_compaction_enabled_or_default() {
+                        let config = contract_runtime_config;
+                        global_state_compaction = Some(Arc::new(GlobalStateCompaction::new(
+                            global_state.clone(),
+                            config.global_state_compaction_retained_eras_or_default(),
+                            config.global_state_compaction_batch_size_or_default(),
+                            config.global_state_compaction_max_marked_tries_or_default(),
+                        )));
+                    }
+                    global_state.into()
+                }
+                GlobalStateBackend::Sled => {
+                    let environment = Arc::new(SledEnvironment::new(
+                        storage_dir,
+                        contract_runtime_config.manual_sync_enabled_or_default(),
+                    )?);
 
-        let trie_store = Arc::new(LmdbTrieStore::new(
-            &environment,
-            None,
-            DatabaseFlags::empty(),
-        )?);
+                    let trie_store = Arc::new(SledTrieStore::new(&environment, None)?);
+
+                    if contract_runtime_config.global_state_compaction_enabled_or_default() {
+                        warn!("global state compaction is not supported by the sled backend");
+                    }
 
-        let global_state = LmdbGlobalState::empty(environment, trie_store)?;
+                    SledGlobalState::empty(environment, trie_store)?.into()
+                }
+            };
         let engine_config = EngineConfigBuilder::new()
             .with_max_query_depth(contract_runtime_config.max_query_depth_or_default())
+            .with_max_keys_page_size(contract_runtime_config.max_keys_page_size_or_default())
             .with_max_associated_keys(max_associated_keys)
             .with_max_runtime_call_stack_height(max_runtime_call_stack_height)
             .with_minimum_delegation_amount(minimum_delegation_amount)
@@ -665,6 +812,8 @@ impl ContractRuntime {
             system_contract_registry: None,
             activation_point,
             prune_batch_size,
+            max_gas_estimate: contract_runtime_config.max_gas_estimate_or_default(),
+            global_state_compaction,
         })
     }
 
@@ -692,13 +841,21 @@ impl ContractRuntime {
                 })?,
         );
 
-        let result = self.engine_state.commit_genesis(
-            correlation_id,
-            genesis_config_hash,
-            protocol_version,
-            &ee_config,
-            chainspec_registry,
-        );
+        let commit_genesis = || {
+            let result = self.engine_state.commit_genesis(
+                correlation_id,
+                genesis_config_hash,
+                protocol_version,
+                &ee_config,
+                chainspec_registry,
+            );
+            let maybe_post_state_hash = result
+                .as_ref()
+                .ok()
+                .map(|genesis_success| genesis_success.post_state_hash);
+            (result, maybe_post_state_hash)
+        };
+        let result = self.write_global_state(commit_genesis);
         self.engine_state.flush_environment()?;
         result
     }
@@ -712,9 +869,13 @@ impl ContractRuntime {
         let scratch_state = self.engine_state.get_scratch_engine_state();
         let pre_state_hash = upgrade_config.pre_state_hash();
         let mut result = scratch_state.commit_upgrade(CorrelationId::new(), upgrade_config)?;
-        result.post_state_hash = self
-            .engine_state
-            .write_scratch_to_db(pre_state_hash, scratch_state.into_inner())?;
+        result.post_state_hash = self.write_global_state(|| {
+            let result = self
+                .engine_state
+                .write_scratch_to_db(pre_state_hash, scratch_state.into_inner());
+            let maybe_post_state_hash = result.as_ref().ok().copied();
+            (result, maybe_post_state_hash)
+        })?;
         self.engine_state.flush_environment()?;
         self.metrics
             .commit_upgrade
@@ -723,6 +884,69 @@ impl ContractRuntime {
         Ok(result)
     }
 
+    /// Runs `write`, which writes to the global state and returns the new state root hash if
+    /// successful, ensuring the written global state is not deleted by a compaction in progress.
+    fn write_global_state<T>(&self, write: impl FnOnce() -> (T, Option<Digest>)) -> T {
+        match &self.global_state_compaction {
+            Some(compaction) => compaction.write(write),
+            None => write().0,
+        }
+    }
+
+    /// The number of eras preceding the current one whose global state should be retained by
+    /// online compaction, or `None` if online compaction is disabled.
+    pub(crate) fn global_state_compaction_retained_eras(&self) -> Option<u64> {
+        self.global_state_compaction
+            .as_ref()
+            .map(|compaction| compaction.retained_eras())
+    }
+
+    /// Returns `true` if online compaction is enabled and a compaction can be started for the era
+    /// of the highest switch block, `era_id`.
+    pub(crate) fn should_start_global_state_compaction(&self, era_id: EraId) -> bool {
+        self.global_state_compaction
+            .as_ref()
+            .map_or(false, |compaction| compaction.should_start(era_id))
+    }
+
+    /// Starts an online compaction retaining the global state under `retained_roots` and the
+    /// current execution pre-state, unless one is already in progress.
+    ///
+    /// The retained tries are marked in the background, after which unreachable tries are deleted
+    /// in batches, one after each block is executed.
+    pub(crate) fn start_global_state_compaction(
+        &self,
+        era_id: EraId,
+        mut retained_roots: Vec<Digest>,
+    ) -> Effects<Event> {
+        let compaction = match &self.global_state_compaction {
+            Some(compaction) => Arc::clone(compaction),
+            None => return Effects::new(),
+        };
+        if !compaction.start(era_id) {
+            return Effects::new();
+        }
+        retained_roots.push(
+            self.execution_pre_state
+                .lock()
+                .expect("ContractRuntime: execution_pre_state poisoned mutex")
+                .pre_state_root_hash,
+        );
+        info!(
+            %era_id,
+            retained_root_count = retained_roots.len(),
+            "starting global state compaction"
+        );
+        async move {
+            if let Err(error) =
+                tokio::task::spawn_blocking(move || compaction.mark(&retained_roots)).await
+            {
+                error!(%error, "global state compaction: marking task failed");
+            }
+        }
+        .ignore()
+    }
+
     pub(crate) fn set_initial_state(&mut self, sequential_block_state: ExecutionPreState) {
         let next_block_height = sequential_block_state.next_block_height;
         let mut execution_pre_state = self.execution_pre_state.lock().unwrap();
@@ -744,7 +968,7 @@ impl ContractRuntime {
 
     #[allow(clippy::too_many_arguments)]
     async fn execute_finalized_block_or_requeue<REv>(
-        engine_state: Arc<EngineState<LmdbGlobalState>>,
+        engine_state: Arc<EngineState<PersistentGlobalState>>,
         metrics: Arc<Metrics>,
         exec_queue: ExecQueue,
         shared_pre_state: Arc<Mutex<ExecutionPreState>>,
@@ -757,6 +981,7 @@ impl ContractRuntime {
         key_block_height_for_activation_point: u64,
         prune_batch_size: u64,
         mut meta_block_state: MetaBlockState,
+        global_state_compaction: Option<Arc<GlobalStateCompaction>>,
     ) where
         REv: From<ContractRuntimeRequest>
             + From<ContractRuntimeAnnouncement>
@@ -774,7 +999,7 @@ impl ContractRuntime {
             maybe_step_effect_and_upcoming_era_validators,
         } = match run_intensive_task(move || {
             debug!("ContractRuntime: execute_finalized_block");
-            execute_finalized_block(
+            let result = execute_finalized_block(
                 engine_state.as_ref(),
                 Some(contract_runtime_metrics),
                 protocol_version,
@@ -784,7 +1009,15 @@ impl ContractRuntime {
                 activation_point.era_id(),
                 key_block_height_for_activation_point,
                 prune_batch_size,
-            )
+            );
+            if let (Ok(block_and_execution_results), Some(compaction)) =
+                (&result, global_state_compaction)
+            {
+                compaction.retain_and_sweep(
+                    *block_and_execution_results.block.header().state_root_hash(),
+                );
+            }
+            result
         })
         .await
         {
@@ -922,7 +1155,7 @@ impl ContractRuntime {
     }
 
     fn do_get_trie(
-        engine_state: &EngineState<LmdbGlobalState>,
+        engine_state: &EngineState<PersistentGlobalState>,
         metrics: &Metrics,
         trie_or_chunk_id: TrieOrChunkId,
     ) -> Result<Option<TrieOrChunk>, ContractRuntimeError> {
@@ -938,7 +1171,7 @@ impl ContractRuntime {
     }
 
     fn get_trie_full(
-        engine_state: &EngineState<LmdbGlobalState>,
+        engine_state: &EngineState<PersistentGlobalState>,
         metrics: &Metrics,
         trie_key: Digest,
     ) -> Result<Option<Bytes>, engine_state::Error> {
@@ -953,7 +1186,7 @@ impl ContractRuntime {
 
     /// Returns the engine state, for testing only.
     #[cfg(test)]
-    pub(crate) fn engine_state(&self) -> &Arc<EngineState<LmdbGlobalState>> {
+    pub(crate) fn engine_state(&self) -> &Arc<EngineState<PersistentGlobalState>> {
         &self.engine_state
     }
 
@@ -992,11 +1225,11 @@ mod trie_chunking_tests {
         account::AccountHash, bytesrepr, CLValue, EraId, Key, ProtocolVersion, StoredValue,
     };
     use prometheus::Registry;
-    use tempfile::tempdir;
+    use tempfile::{tempdir, TempDir};
 
     use crate::{
         components::fetcher::FetchResponse,
-        contract_runtime::{Config as ContractRuntimeConfig, ContractRuntime},
+        contract_runtime::{Config as ContractRuntimeConfig, ContractRuntime, GlobalStateBackend},
         types::{ActivationPoint, ChunkingError, TrieOrChunk, TrieOrChunkId, ValueOrChunk},
     };
 
@@ -1050,11 +1283,26 @@ mod trie_chunking_tests {
     // Creates a test ContractRuntime and feeds the underlying GlobalState with `test_pair`.
     // Returns [`ContractRuntime`] instance and the new Merkle root after applying the `test_pair`.
     fn create_test_state(test_pair: [TestPair; 2]) -> (ContractRuntime, Digest) {
+        let (_temp_dir, contract_runtime, post_state_hash) =
+            create_test_state_with_backend(test_pair, GlobalStateBackend::Lmdb);
+        (contract_runtime, post_state_hash)
+    }
+
+    // As `create_test_state`, but backed by the given global state store.  The returned temporary
+    // directory holds the store and must outlive the [`ContractRuntime`].
+    fn create_test_state_with_backend(
+        test_pair: [TestPair; 2],
+        global_state_backend: GlobalStateBackend,
+    ) -> (TempDir, ContractRuntime, Digest) {
         let temp_dir = tempdir().unwrap();
+        let contract_runtime_config = ContractRuntimeConfig {
+            global_state_backend: Some(global_state_backend),
+            ..Default::default()
+        };
         let contract_runtime = ContractRuntime::new(
             ProtocolVersion::default(),
             temp_dir.path(),
-            &ContractRuntimeConfig::default(),
+            &contract_runtime_config,
             WasmConfig::default(),
             SystemConfig::default(),
             10,
@@ -1085,7 +1333,7 @@ mod trie_chunking_tests {
             .engine_state()
             .apply_effect(CorrelationId::new(), empty_state_root, effects)
             .expect("applying effects to succeed");
-        (contract_runtime, post_state_hash)
+        (temp_dir, contract_runtime, post_state_hash)
     }
 
     fn read_trie(contract_runtime: &ContractRuntime, id: TrieOrChunkId) -> TrieOrChunk {
@@ -1160,4 +1408,19 @@ mod trie_chunking_tests {
         // should be deserialized to a leaf
         assert!(matches!(trie, Trie::Leaf { .. }));
     }
+
+    #[test]
+    fn returns_trie_from_sled_backed_state() {
+        let (_temp_dir, contract_runtime, root_hash) = create_test_state_with_backend(
+            create_test_pairs_with_large_data(),
+            GlobalStateBackend::Sled,
+        );
+
+        let trie = read_trie(&contract_runtime, TrieOrChunkId(0, root_hash));
+        assert!(matches!(trie, TrieOrChunk::Value(_)));
+
+        let next_hash = extract_next_hash_from_trie(trie);
+        let trie = read_trie(&contract_runtime, TrieOrChunkId(0, next_hash));
+        assert!(matches!(trie, TrieOrChunk::Value(_)));
+    }
 }
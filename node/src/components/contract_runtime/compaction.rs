@@ -0,0 +1,212 @@
+//! Online compaction of the global state store.
+//!
+//! Deletes the tries which are not reachable from the global state of any recent block while the
+//! node is running.  The tries reachable from the retained state roots are marked in the
+//! background, after which the rest of the store is swept in small batches, one after each block
+//! is executed.
+//!
+//! The tries marked are held in memory, which is bounded by abandoning any compaction which would
+//! mark more than the configured maximum number of tries.
+//!
+//! A sweep batch never runs concurrently with a trie being written via `put_trie`, and the root of
+//! every global state written while a compaction is in progress is marked before the next sweep
+//! batch, so no trie which is still needed is deleted.
+
+use std::sync::{Mutex, MutexGuard};
+
+use tracing::{error, info};
+
+use casper_execution_engine::storage::{
+    global_state::lmdb::LmdbGlobalState, trie_store::compaction::TrieCompactor,
+};
+use casper_hashing::Digest;
+use casper_types::EraId;
+
+/// The phase of a compaction.
+enum Phase {
+    /// No compaction is in progress.
+    Idle,
+    /// The tries reachable from the retained state roots are being marked.  The roots of any
+    /// global state written meanwhile are marked once that's done.
+    Marking { pending_roots: Vec<Digest> },
+    /// Unmarked tries are being deleted.
+    Sweeping(TrieCompactor),
+}
+
+struct State {
+    phase: Phase,
+    /// The era of the highest switch block when the most recent compaction was started.
+    last_started_era_id: Option<EraId>,
+    /// The root of the most recently committed global state.
+    ///
+    /// It may not be in storage yet when the retained roots are read, so is always retained.
+    latest_state_root_hash: Option<Digest>,
+}
+
+/// Online compaction of an LMDB-backed global state store.
+pub(super) struct GlobalStateCompaction {
+    global_state: LmdbGlobalState,
+    retained_eras: u64,
+    batch_size: usize,
+    /// The maximum number of tries which may be marked, bounding the memory used by a compaction.
+    max_marked_count: u64,
+    state: Mutex<State>,
+}
+
+impl GlobalStateCompaction {
+    pub(super) fn new(
+        global_state: LmdbGlobalState,
+        retained_eras: u64,
+        batch_size: usize,
+        max_marked_count: u64,
+    ) -> Self {
+        GlobalStateCompaction {
+            global_state,
+            retained_eras,
+            batch_size,
+            max_marked_count,
+            state: Mutex::new(State {
+                phase: Phase::Idle,
+                last_started_era_id: None,
+                latest_state_root_hash: None,
+            }),
+        }
+    }
+
+    /// The number of eras preceding the current one whose global state is retained.
+    pub(super) fn retained_eras(&self) -> u64 {
+        self.retained_eras
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .expect("components::contract_runtime: global state compaction mutex poisoned")
+    }
+
+    /// Returns `true` if no compaction is in progress and none has been started since the switch
+    /// block of `era_id`.
+    pub(super) fn should_start(&self, era_id: EraId) -> bool {
+        Self::can_start(&self.lock(), era_id)
+    }
+
+    fn can_start(state: &State, era_id: EraId) -> bool {
+        matches!(state.phase, Phase::Idle)
+            && state
+                .last_started_era_id
+                .map_or(true, |last_started| last_started < era_id)
+    }
+
+    /// Starts a compaction if [`Self::should_start`] allows it, returning whether one was started.
+    ///
+    /// The most recently committed global state is retained along with the retained roots passed
+    /// to [`Self::mark`], which must be called next.
+    pub(super) fn start(&self, era_id: EraId) -> bool {
+        let mut state = self.lock();
+        if !Self::can_start(&state, era_id) {
+            return false;
+        }
+        state.phase = Phase::Marking {
+            pending_roots: state.latest_state_root_hash.into_iter().collect(),
+        };
+        state.last_started_era_id = Some(era_id);
+        true
+    }
+
+    /// Marks the tries reachable from `retained_roots`, then begins sweeping.
+    ///
+    /// This may take a long time, so should be run as a blocking task.
+    pub(super) fn mark(&self, retained_roots: &[Digest]) {
+        let mut compactor = TrieCompactor::with_max_marked_count(self.max_marked_count);
+        let summary = match compactor.mark(
+            self.global_state.environment(),
+            self.global_state.trie_store(),
+            retained_roots,
+        ) {
+            Ok(summary) => summary,
+            Err(error) => {
+                error!(%error, "global state compaction: failed to mark retained tries");
+                self.lock().phase = Phase::Idle;
+                return;
+            }
+        };
+        info!(
+            retained_root_count = retained_roots.len(),
+            marked_count = summary.marked_count,
+            missing_count = summary.missing.len(),
+            "global state compaction: marked retained tries"
+        );
+
+        let mut state = self.lock();
+        let pending_roots = match &mut state.phase {
+            Phase::Marking { pending_roots } => std::mem::take(pending_roots),
+            Phase::Idle | Phase::Sweeping(_) => {
+                error!("global state compaction: unexpected phase after marking");
+                return;
+            }
+        };
+        state.phase = Phase::Sweeping(compactor);
+        self.retain(&mut state, &pending_roots);
+    }
+
+    /// Ensures the tries under `roots` are not deleted by the compaction in progress, if any.
+    fn retain(&self, state: &mut State, roots: &[Digest]) {
+        match &mut state.phase {
+            Phase::Idle => {}
+            Phase::Marking { pending_roots } => pending_roots.extend_from_slice(roots),
+            Phase::Sweeping(compactor) => {
+                if let Err(error) = compactor.mark(
+                    self.global_state.environment(),
+                    self.global_state.trie_store(),
+                    roots,
+                ) {
+                    // Abandoning the compaction is always safe.
+                    error!(%error, "global state compaction: failed to mark new tries; abandoning");
+                    state.phase = Phase::Idle;
+                }
+            }
+        }
+    }
+
+    /// Runs `write`, which may write tries to the store, without any sweep batch running
+    /// concurrently, then retains the tries under the root returned by `write`, if any.
+    pub(super) fn write<T>(&self, write: impl FnOnce() -> (T, Option<Digest>)) -> T {
+        let mut state = self.lock();
+        let (output, maybe_root) = write();
+        if let Some(root) = maybe_root {
+            self.retain(&mut state, &[root]);
+        }
+        output
+    }
+
+    /// Retains the tries under the newly committed `state_root_hash`, then runs a sweep batch if
+    /// the retained tries have been marked.
+    pub(super) fn retain_and_sweep(&self, state_root_hash: Digest) {
+        let mut state = self.lock();
+        state.latest_state_root_hash = Some(state_root_hash);
+        self.retain(&mut state, &[state_root_hash]);
+        let compactor = match &mut state.phase {
+            Phase::Sweeping(compactor) => compactor,
+            Phase::Idle | Phase::Marking { .. } => return,
+        };
+        match compactor.sweep(
+            self.global_state.environment(),
+            self.global_state.trie_store(),
+            self.batch_size,
+        ) {
+            Ok(summary) if summary.is_finished => {
+                info!(
+                    retained_count = compactor.marked_count(),
+                    deleted_count = compactor.deleted_count(),
+                    "global state compaction: finished"
+                );
+                state.phase = Phase::Idle;
+            }
+            Ok(_) => {}
+            Err(error) => {
+                error!(%error, "global state compaction: failed to sweep; abandoning");
+                state.phase = Phase::Idle;
+            }
+        }
+    }
+}
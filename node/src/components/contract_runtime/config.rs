@@ -6,7 +6,24 @@ use casper_execution_engine::shared::utils;
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+const DEFAULT_MAX_KEYS_PAGE_SIZE: u32 = 1_000;
+const DEFAULT_MAX_GAS_ESTIMATE: u64 = 1_000_000_000_000;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_GLOBAL_STATE_COMPACTION_ENABLED: bool = false;
+const DEFAULT_GLOBAL_STATE_COMPACTION_RETAINED_ERAS: u64 = 10;
+const DEFAULT_GLOBAL_STATE_COMPACTION_BATCH_SIZE: usize = 10_000;
+const DEFAULT_GLOBAL_STATE_COMPACTION_MAX_MARKED_TRIES: u64 = 50_000_000;
+
+/// The persistent store backing the global state.
+#[derive(Clone, Copy, DataSize, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GlobalStateBackend {
+    /// LMDB, which is memory-mapped and limited to `max_global_state_size`.
+    #[default]
+    Lmdb,
+    /// Sled, a log-structured store which grows as required.
+    Sled,
+}
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -27,10 +44,44 @@ pub struct Config {
     ///
     /// Defaults to 5.
     pub max_query_depth: Option<u64>,
+    /// The maximum number of keys examined for a single page when listing global state keys by
+    /// prefix.
+    ///
+    /// Defaults to 1,000.
+    pub max_keys_page_size: Option<u32>,
+    /// The maximum gas which a deploy may consume when estimating its gas via the speculative
+    /// execution server.  The estimate is further limited by the block gas limit.
+    ///
+    /// Defaults to 1,000,000,000,000.
+    pub max_gas_estimate: Option<u64>,
     /// Enable synchronizing to disk only after each block is written.
     ///
     /// Defaults to `true`.
     pub enable_manual_sync: Option<bool>,
+    /// The persistent store backing the global state.
+    ///
+    /// Defaults to `lmdb`.
+    pub global_state_backend: Option<GlobalStateBackend>,
+    /// Enable deleting global state which is unreachable from the state roots of recent blocks
+    /// while the node is running.  Only supported by the `lmdb` backend.
+    ///
+    /// Defaults to `false`.
+    pub enable_global_state_compaction: Option<bool>,
+    /// The number of eras preceding the current one whose global state is retained by compaction.
+    ///
+    /// Defaults to 10.
+    pub global_state_compaction_retained_eras: Option<u64>,
+    /// The maximum number of tries examined by each compaction batch, one of which is run after
+    /// each block is executed.
+    ///
+    /// Defaults to 10,000.
+    pub global_state_compaction_batch_size: Option<usize>,
+    /// The maximum number of tries reachable from the retained state roots for compaction to
+    /// proceed.  The reachable tries are held in memory while compacting, taking roughly 40 to 80
+    /// bytes each, and compaction is abandoned if there are more of them.
+    ///
+    /// Defaults to 50,000,000, i.e. up to roughly 4 GB.
+    pub global_state_compaction_max_marked_tries: Option<u64>,
 }
 
 impl Config {
@@ -53,11 +104,53 @@ impl Config {
         self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH)
     }
 
+    /// Max keys examined per page when listing keys by prefix.
+    pub fn max_keys_page_size_or_default(&self) -> u32 {
+        self.max_keys_page_size
+            .unwrap_or(DEFAULT_MAX_KEYS_PAGE_SIZE)
+            .max(1)
+    }
+
+    /// Max gas consumed by a deploy when estimating its gas.
+    pub fn max_gas_estimate_or_default(&self) -> u64 {
+        self.max_gas_estimate.unwrap_or(DEFAULT_MAX_GAS_ESTIMATE)
+    }
+
     /// Is manual sync enabled.
     pub fn manual_sync_enabled_or_default(&self) -> bool {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    /// Global state backend.
+    pub fn global_state_backend_or_default(&self) -> GlobalStateBackend {
+        self.global_state_backend.unwrap_or_default()
+    }
+
+    /// Is global state compaction enabled.
+    pub fn global_state_compaction_enabled_or_default(&self) -> bool {
+        self.enable_global_state_compaction
+            .unwrap_or(DEFAULT_GLOBAL_STATE_COMPACTION_ENABLED)
+    }
+
+    /// Number of eras whose global state is retained by compaction.
+    pub fn global_state_compaction_retained_eras_or_default(&self) -> u64 {
+        self.global_state_compaction_retained_eras
+            .unwrap_or(DEFAULT_GLOBAL_STATE_COMPACTION_RETAINED_ERAS)
+    }
+
+    /// Max tries examined per compaction batch.
+    pub fn global_state_compaction_batch_size_or_default(&self) -> usize {
+        self.global_state_compaction_batch_size
+            .unwrap_or(DEFAULT_GLOBAL_STATE_COMPACTION_BATCH_SIZE)
+            .max(1)
+    }
+
+    /// Max tries reachable from the retained state roots for compaction to proceed.
+    pub fn global_state_compaction_max_marked_tries_or_default(&self) -> u64 {
+        self.global_state_compaction_max_marked_tries
+            .unwrap_or(DEFAULT_GLOBAL_STATE_COMPACTION_MAX_MARKED_TRIES)
+    }
 }
 
 impl Default for Config {
@@ -66,7 +159,18 @@ impl Default for Config {
             max_global_state_size: Some(DEFAULT_MAX_GLOBAL_STATE_SIZE),
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
+            max_keys_page_size: Some(DEFAULT_MAX_KEYS_PAGE_SIZE),
+            max_gas_estimate: Some(DEFAULT_MAX_GAS_ESTIMATE),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            global_state_backend: Some(GlobalStateBackend::default()),
+            enable_global_state_compaction: Some(DEFAULT_GLOBAL_STATE_COMPACTION_ENABLED),
+            global_state_compaction_retained_eras: Some(
+                DEFAULT_GLOBAL_STATE_COMPACTION_RETAINED_ERAS,
+            ),
+            global_state_compaction_batch_size: Some(DEFAULT_GLOBAL_STATE_COMPACTION_BATCH_SIZE),
+            global_state_compaction_max_marked_tries: Some(
+                DEFAULT_GLOBAL_STATE_COMPACTION_MAX_MARKED_TRIES,
+            ),
         }
     }
 }
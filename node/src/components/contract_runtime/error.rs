@@ -17,8 +17,8 @@ use casper_execution_engine::core::engine_state::GetEraValidatorsError;
 /// An error returned from mis-configuring the contract runtime component.
 #[derive(Debug, Error)]
 pub(crate) enum ConfigError {
-    /// Error initializing the LMDB environment.
-    #[error("failed to initialize LMDB environment for contract runtime: {0}")]
+    /// Error initializing the global state environment.
+    #[error("failed to initialize global state environment for contract runtime: {0}")]
     Lmdb(#[from] StorageLmdbError),
     /// Error initializing metrics.
     #[error("failed to initialize metrics for contract runtime: {0}")]
@@ -69,6 +69,13 @@ pub enum BlockExecutionError {
         #[serde(skip_serializing)]
         lmdb::Error,
     ),
+    /// An error that occurred while interacting with the global state store.
+    #[error(transparent)]
+    Storage(
+        #[from]
+        #[serde(skip_serializing)]
+        StorageLmdbError,
+    ),
     /// An error that occurred while getting era validators.
     #[error(transparent)]
     GetEraValidators(
@@ -1,4 +1,10 @@
-use std::{cmp, collections::BTreeMap, ops::Range, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    sync::Arc,
+    time::Instant,
+};
 
 use itertools::Itertools;
 use tracing::{debug, error, info, trace, warn};
@@ -7,18 +13,20 @@ use casper_execution_engine::{
     core::{
         engine_state::{
             self, execution_result::ExecutionResults, step::EvictItem, ChecksumRegistry,
-            DeployItem, EngineState, ExecuteRequest, ExecutionResult as EngineExecutionResult,
-            GetEraValidatorsRequest, PruneConfig, PruneResult, RewardItem, StepError, StepRequest,
-            StepSuccess,
+            DeployItem, EngineState, ExecutableDeployItem, ExecuteRequest, ExecutionPhaseCosts,
+            ExecutionResult as EngineExecutionResult, GetEraValidatorsRequest, PruneConfig,
+            PruneResult, QueryRequest, QueryResult, RewardItem, StepError, StepRequest,
+            StepSuccess, MAX_PAYMENT,
         },
         execution,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
-    storage::global_state::{lmdb::LmdbGlobalState, CommitProvider, StateProvider},
+    storage::global_state::{persistent::PersistentGlobalState, CommitProvider, StateProvider},
 };
 use casper_hashing::Digest;
 use casper_types::{
-    CLValue, DeployHash, EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, U512,
+    bytesrepr::Bytes, runtime_args, system::standard_payment::ARG_AMOUNT, CLValue, DeployHash,
+    EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, U512,
 };
 
 use crate::{
@@ -26,8 +34,9 @@ use crate::{
         consensus::EraReport,
         contract_runtime::{
             error::BlockExecutionError, types::StepEffectAndUpcomingEraValidators,
-            BlockAndExecutionResults, ExecutionPreState, Metrics, SpeculativeExecutionState,
-            APPROVALS_CHECKSUM_NAME, EXECUTION_RESULTS_CHECKSUM_NAME,
+            BlockAndExecutionResults, ExecutionPreState, Metrics, SpeculativeBatchExecutionResults,
+            SpeculativeExecutionResult, SpeculativeExecutionState, APPROVALS_CHECKSUM_NAME,
+            EXECUTION_RESULTS_CHECKSUM_NAME,
         },
         fetcher::FetchItem,
     },
@@ -90,7 +99,7 @@ fn calculate_prune_eras(
 /// Executes a finalized block.
 #[allow(clippy::too_many_arguments)]
 pub fn execute_finalized_block(
-    engine_state: &EngineState<LmdbGlobalState>,
+    engine_state: &EngineState<PersistentGlobalState>,
     metrics: Option<Arc<Metrics>>,
     protocol_version: ProtocolVersion,
     execution_pre_state: ExecutionPreState,
@@ -407,12 +416,14 @@ where
 /// Execute the transaction without commiting the effects.
 /// Intended to be used for discovery operations on read-only nodes.
 ///
-/// Returns effects of the execution.
+/// Returns effects of the execution, along with a trace of the host functions invoked if
+/// `enable_trace` is set.
 pub fn execute_only<S>(
     engine_state: &EngineState<S>,
     execution_state: SpeculativeExecutionState,
     deploy: DeployItem,
-) -> Result<Option<ExecutionResult>, engine_state::Error>
+    enable_trace: bool,
+) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error>
 where
     S: StateProvider + CommitProvider,
     S::Error: Into<execution::Error>,
@@ -430,23 +441,173 @@ where
         protocol_version,
         PublicKey::System,
     );
-    let results = execute(engine_state, None, execute_request);
-    results.map(|mut execution_results| {
-        let len = execution_results.len();
-        if len != 1 {
-            warn!(
-                ?deploy_hash,
-                "got more ({}) execution results from a single transaction", len
-            );
-            None
-        } else {
-            // We know it must be 1, we could unwrap and then wrap
-            // with `Some(_)` but `pop_front` already returns an `Option`.
-            // We need to transform the `engine_state::ExecutionResult` into
-            // `casper_types::ExecutionResult` as well.
-            execution_results.pop_front().map(Into::into)
+    // We need to transform the `engine_state::ExecutionResult`s into
+    // `casper_types::ExecutionResult`s as well.
+    let mut execution_results: Vec<SpeculativeExecutionResult> = if enable_trace {
+        engine_state
+            .run_execute_with_trace(CorrelationId::new(), execute_request)?
+            .into_iter()
+            .map(|(execution_result, _, trace)| (execution_result.into(), Some(trace)))
+            .collect()
+    } else {
+        execute(engine_state, None, execute_request)?
+            .into_iter()
+            .map(|execution_result| (execution_result.into(), None))
+            .collect()
+    };
+    let len = execution_results.len();
+    if len != 1 {
+        warn!(
+            ?deploy_hash,
+            "got more ({}) execution results from a single transaction", len
+        );
+        Ok(None)
+    } else {
+        // We know it must be 1, we could unwrap and then wrap
+        // with `Some(_)` but `pop` already returns an `Option`.
+        Ok(execution_results.pop())
+    }
+}
+
+/// Execute the deploy with a standard payment of `payment_amount` in place of its own payment,
+/// without commiting the effects.  Intended to be used to estimate the gas consumed by the deploy.
+///
+/// The deploy need not be signed: it is authorized by all the keys associated with its account.
+/// The account's main purse is credited with enough motes to cover the payment in the scratch
+/// state used for execution.
+///
+/// Returns the result of the execution along with the gas consumed by its payment and session
+/// phases.
+pub fn estimate_gas(
+    engine_state: &EngineState<PersistentGlobalState>,
+    execution_state: SpeculativeExecutionState,
+    deploy: &Deploy,
+    payment_amount: U512,
+) -> Result<Option<(ExecutionResult, ExecutionPhaseCosts)>, engine_state::Error> {
+    let SpeculativeExecutionState {
+        mut state_root_hash,
+        block_time,
+        protocol_version,
+    } = execution_state;
+    let deploy_hash = *deploy.hash();
+    let account_hash = deploy.header().account().to_account_hash();
+
+    // Create a new EngineState that reads from LMDB but only caches changes in memory.
+    let scratch_state = engine_state.get_scratch_engine_state();
+
+    let query_request = QueryRequest::new(state_root_hash, Key::Account(account_hash), vec![]);
+    let authorization_keys = match engine_state.run_query(CorrelationId::new(), query_request)? {
+        QueryResult::Success { value, .. } => match *value {
+            StoredValue::Account(account) => {
+                let mut effects = AdditiveMap::new();
+                let _ = effects.insert(
+                    Key::Balance(account.main_purse().addr()),
+                    Transform::AddUInt512(payment_amount.saturating_add(*MAX_PAYMENT)),
+                );
+                state_root_hash =
+                    scratch_state.apply_effect(CorrelationId::new(), state_root_hash, effects)?;
+                account
+                    .associated_keys()
+                    .iter()
+                    .map(|(key, _weight)| *key)
+                    .collect()
+            }
+            _ => BTreeSet::from([account_hash]),
+        },
+        QueryResult::RootNotFound => {
+            return Err(engine_state::Error::RootNotFound(state_root_hash));
         }
-    })
+        // The engine reports the missing account as a failed execution.
+        QueryResult::ValueNotFound(_)
+        | QueryResult::CircularReference(_)
+        | QueryResult::DepthLimit { .. } => BTreeSet::from([account_hash]),
+    };
+
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { ARG_AMOUNT => payment_amount },
+    };
+    let deploy_item = DeployItem::new(
+        account_hash,
+        deploy.session().clone(),
+        payment,
+        deploy.header().gas_price(),
+        authorization_keys,
+        DeployHash::new(deploy_hash.inner().value()),
+    );
+    let execute_request = ExecuteRequest::new(
+        state_root_hash,
+        block_time.millis(),
+        vec![deploy_item],
+        protocol_version,
+        PublicKey::System,
+    );
+    let mut execution_results =
+        scratch_state.run_execute_with_phase_costs(CorrelationId::new(), execute_request)?;
+    let len = execution_results.len();
+    if len != 1 {
+        warn!(
+            ?deploy_hash,
+            "got more ({}) execution results from a single transaction", len
+        );
+        return Ok(None);
+    }
+    Ok(execution_results
+        .pop()
+        .map(|(ee_execution_result, phase_costs)| {
+            (ExecutionResult::from(&ee_execution_result), phase_costs)
+        }))
+}
+
+/// Execute the transactions in order without commiting the effects, each one on top of the
+/// effects of the previous ones.  Intended to be used for discovery operations on read-only nodes.
+///
+/// Returns the results of the executions along with the gas consumed by their payment and session
+/// phases.
+pub fn execute_only_batch(
+    engine_state: &EngineState<PersistentGlobalState>,
+    execution_state: SpeculativeExecutionState,
+    deploys: Vec<DeployItem>,
+) -> Result<Option<SpeculativeBatchExecutionResults>, engine_state::Error> {
+    let SpeculativeExecutionState {
+        mut state_root_hash,
+        block_time,
+        protocol_version,
+    } = execution_state;
+
+    // Create a new EngineState that reads from LMDB but only caches changes in memory.  The changes
+    // are dropped along with it once the batch has been executed.
+    let scratch_state = engine_state.get_scratch_engine_state();
+
+    let mut results = Vec::with_capacity(deploys.len());
+    for deploy in deploys {
+        let deploy_hash = deploy.deploy_hash;
+        let execute_request = ExecuteRequest::new(
+            state_root_hash,
+            block_time.millis(),
+            vec![deploy],
+            protocol_version,
+            PublicKey::System,
+        );
+        let mut execution_results =
+            scratch_state.run_execute_with_phase_costs(CorrelationId::new(), execute_request)?;
+        let len = execution_results.len();
+        let (ee_execution_result, phase_costs) = match execution_results.pop() {
+            Some(result) if len == 1 => result,
+            _ => {
+                warn!(
+                    ?deploy_hash,
+                    "got more ({}) execution results from a single transaction", len
+                );
+                return Ok(None);
+            }
+        };
+        let effects = ee_execution_result.execution_journal().clone().into();
+        state_root_hash =
+            scratch_state.apply_effect(CorrelationId::new(), state_root_hash, effects)?;
+        results.push((ExecutionResult::from(&ee_execution_result), phase_costs));
+    }
+    Ok(Some(results))
 }
 
 fn execute<S>(
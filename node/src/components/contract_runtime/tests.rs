@@ -295,13 +295,7 @@ async fn should_not_set_shared_pre_state_to_lower_block_height() {
 
     // Prepare to create a block which will take a while to execute, i.e. loaded with many deploys
     // transferring from node-1's main account to new random public keys.
-    let node_1_secret_key = SecretKey::from_file(
-        RESOURCES_PATH
-            .join("local")
-            .join("secret_keys")
-            .join("node-1.pem"),
-    )
-    .unwrap();
+    let node_1_secret_key = node_1_secret_key();
     let timestamp = Timestamp::now();
     let ttl = TimeDiff::from_seconds(100);
     let gas_price = 1;
@@ -392,3 +386,272 @@ async fn should_not_set_shared_pre_state_to_lower_block_height() {
         next_block_height
     );
 }
+
+/// A wasm module whose `call` export executes a single `nop`, consuming a little gas.
+const NOP_WASM: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+    0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: `fn()`
+    0x03, 0x02, 0x01, 0x00, // function section
+    0x05, 0x03, 0x01, 0x00, 0x01, // memory section: one page
+    0x07, 0x08, 0x01, 0x04, 0x63, 0x61, 0x6c, 0x6c, 0x00, 0x00, // export section: `call`
+    0x0a, 0x05, 0x01, 0x03, 0x00, 0x01, 0x0b, // code section: `nop`, `end`
+];
+
+/// A wasm module whose `call` export executes a `nop` and then traps.
+const UNREACHABLE_WASM: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+    0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: `fn()`
+    0x03, 0x02, 0x01, 0x00, // function section
+    0x05, 0x03, 0x01, 0x00, 0x01, // memory section: one page
+    0x07, 0x08, 0x01, 0x04, 0x63, 0x61, 0x6c, 0x6c, 0x00, 0x00, // export section: `call`
+    0x0a, 0x06, 0x01, 0x04, 0x00, 0x01, 0x00, 0x0b, // code section: `nop`, `unreachable`
+];
+
+/// Creates a contract runtime with genesis committed, returning it along with the local chainspec
+/// and the post-genesis state on top of which to speculatively execute deploys.
+async fn runner_after_genesis(
+    rng: &mut NodeRng,
+) -> (
+    Runner<ConditionCheckReactor<Reactor>>,
+    Arc<Chainspec>,
+    SpeculativeExecutionState,
+) {
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let chainspec = Arc::new(chainspec);
+    let chainspec_raw_bytes = Arc::new(chainspec_raw_bytes);
+
+    let runner: Runner<ConditionCheckReactor<Reactor>> = Runner::new(
+        Config::default(),
+        Arc::clone(&chainspec),
+        Arc::clone(&chainspec_raw_bytes),
+        rng,
+    )
+    .await
+    .unwrap();
+
+    let state_root_hash = runner
+        .reactor()
+        .inner()
+        .contract_runtime
+        .commit_genesis(chainspec.as_ref(), chainspec_raw_bytes.as_ref())
+        .unwrap()
+        .post_state_hash;
+    let execution_state = SpeculativeExecutionState {
+        state_root_hash,
+        block_time: Timestamp::now(),
+        protocol_version: chainspec.protocol_version(),
+    };
+
+    (runner, chainspec, execution_state)
+}
+
+fn node_1_secret_key() -> SecretKey {
+    SecretKey::from_file(
+        RESOURCES_PATH
+            .join("local")
+            .join("secret_keys")
+            .join("node-1.pem"),
+    )
+    .unwrap()
+}
+
+/// Creates a deploy using standard payment of `payment_amount` at a gas price of 1.
+fn new_deploy(
+    chainspec: &Chainspec,
+    payment_amount: U512,
+    session: ExecutableDeployItem,
+    secret_key: &SecretKey,
+) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { "amount" => payment_amount },
+    };
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(100),
+        1,
+        vec![],
+        chainspec.network_config.name.clone(),
+        payment,
+        session,
+        secret_key,
+        None,
+    )
+}
+
+fn module_bytes_session(module_bytes: &[u8]) -> ExecutableDeployItem {
+    ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::from(module_bytes.to_vec()),
+        args: RuntimeArgs::new(),
+    }
+}
+
+#[tokio::test]
+async fn should_estimate_gas_of_successful_deploy() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+    let (runner, chainspec, execution_state) = runner_after_genesis(&mut rng).await;
+    let deploy = new_deploy(
+        &chainspec,
+        U512::from(10_000_000_000_u64),
+        module_bytes_session(NOP_WASM),
+        &node_1_secret_key(),
+    );
+
+    let (execution_result, phase_costs) = estimate_gas(
+        runner.reactor().inner().contract_runtime.engine_state(),
+        execution_state,
+        &deploy,
+        U512::from(chainspec.deploy_config.block_gas_limit),
+    )
+    .unwrap()
+    .expect("should have an execution result");
+
+    let cost = match execution_result {
+        ExecutionResult::Success { cost, .. } => cost,
+        ExecutionResult::Failure { error_message, .. } => {
+            panic!("execution should succeed: {}", error_message)
+        }
+    };
+    assert!(phase_costs.session.value() > U512::zero());
+    // At a gas price of 1 the cost in motes is the total gas consumed by both phases.
+    assert_eq!(
+        cost,
+        phase_costs.payment.value() + phase_costs.session.value()
+    );
+}
+
+#[tokio::test]
+async fn should_estimate_gas_of_failed_deploy() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+    let (runner, chainspec, execution_state) = runner_after_genesis(&mut rng).await;
+    let deploy = new_deploy(
+        &chainspec,
+        U512::from(10_000_000_000_u64),
+        module_bytes_session(UNREACHABLE_WASM),
+        &node_1_secret_key(),
+    );
+
+    let (execution_result, phase_costs) = estimate_gas(
+        runner.reactor().inner().contract_runtime.engine_state(),
+        execution_state,
+        &deploy,
+        U512::from(chainspec.deploy_config.block_gas_limit),
+    )
+    .unwrap()
+    .expect("should have an execution result");
+
+    assert!(
+        matches!(execution_result, ExecutionResult::Failure { .. }),
+        "execution should fail: {:?}",
+        execution_result
+    );
+    // The session code is still charged for the gas it consumed before trapping.
+    assert!(phase_costs.session.value() > U512::zero());
+}
+
+fn transfer_session(amount: U512, target: PublicKey) -> ExecutableDeployItem {
+    ExecutableDeployItem::Transfer {
+        args: runtime_args! {
+          "amount" => amount,
+          "target" => target,
+          "id" => Some(9_u64),
+        },
+    }
+}
+
+fn execution_succeeded(execution_result: &ExecutionResult) -> bool {
+    matches!(execution_result, ExecutionResult::Success { .. })
+}
+
+#[tokio::test]
+async fn should_execute_batch_on_top_of_previous_deploys() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+    let (runner, chainspec, execution_state) = runner_after_genesis(&mut rng).await;
+    let engine_state = runner.reactor().inner().contract_runtime.engine_state();
+
+    // Fund a new account from node-1's account, then transfer from the new account, which only
+    // succeeds on top of the first transfer.
+    let transfer_cost = U512::from(chainspec.system_costs_config.wasmless_transfer_cost());
+    let transfer_amount = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+    let new_account_secret_key = SecretKey::random(&mut rng);
+    let fund_new_account = new_deploy(
+        &chainspec,
+        transfer_cost,
+        transfer_session(
+            transfer_amount * 10,
+            PublicKey::from(&new_account_secret_key),
+        ),
+        &node_1_secret_key(),
+    );
+    let transfer_from_new_account = new_deploy(
+        &chainspec,
+        transfer_cost,
+        transfer_session(transfer_amount, PublicKey::random(&mut rng)),
+        &new_account_secret_key,
+    );
+    let wasm_deploy = new_deploy(
+        &chainspec,
+        U512::from(10_000_000_000_u64),
+        module_bytes_session(NOP_WASM),
+        &node_1_secret_key(),
+    );
+
+    let execute_alone = || {
+        execute_only_batch(
+            engine_state,
+            execution_state.clone(),
+            vec![DeployItem::from(transfer_from_new_account.clone())],
+        )
+        .unwrap()
+        .expect("should have execution results")
+    };
+    let results = execute_alone();
+    assert_eq!(results.len(), 1);
+    assert!(!execution_succeeded(&results[0].0));
+
+    let results = execute_only_batch(
+        engine_state,
+        execution_state.clone(),
+        vec![
+            DeployItem::from(fund_new_account),
+            DeployItem::from(transfer_from_new_account.clone()),
+            DeployItem::from(wasm_deploy),
+        ],
+    )
+    .unwrap()
+    .expect("should have execution results");
+    assert_eq!(results.len(), 3);
+    for (execution_result, _) in &results {
+        assert!(
+            execution_succeeded(execution_result),
+            "execution should succeed: {:?}",
+            execution_result
+        );
+    }
+
+    // Native transfers skip the payment phase and are charged the fixed transfer cost as session.
+    for (_, phase_costs) in &results[..2] {
+        assert_eq!(phase_costs.payment.value(), U512::zero());
+        assert_eq!(phase_costs.session.value(), transfer_cost);
+    }
+    let (wasm_result, wasm_phase_costs) = &results[2];
+    assert!(wasm_phase_costs.session.value() > U512::zero());
+    match wasm_result {
+        ExecutionResult::Success { cost, .. } => assert_eq!(
+            *cost,
+            wasm_phase_costs.payment.value() + wasm_phase_costs.session.value()
+        ),
+        ExecutionResult::Failure { .. } => unreachable!(),
+    }
+
+    // The effects of the batch are not committed.
+    let results = execute_alone();
+    assert!(!execution_succeeded(&results[0].0));
+}
@@ -49,6 +49,7 @@ pub(crate) use event::{Event, EventMetadata};
 const COMPONENT_NAME: &str = "deploy_acceptor";
 
 const ARG_TARGET: &str = "target";
+const ARG_TARGETS: &str = "targets";
 
 #[derive(Debug, Error, Serialize)]
 pub(crate) enum Error {
@@ -639,7 +640,7 @@ impl DeployAcceptor {
                 // We rely on the `Deploy::is_config_compliant` to check
                 // that the transfer amount arg is present and is a valid U512.
 
-                if args.get(ARG_TARGET).is_none() {
+                if args.get(ARG_TARGET).is_none() && args.get(ARG_TARGETS).is_none() {
                     let error = Error::parameter_failure(
                         &block_header,
                         DeployParameterFailure::MissingTransferTarget,
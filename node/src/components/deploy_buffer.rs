@@ -5,7 +5,8 @@ mod metrics;
 mod tests;
 
 use std::{
-    collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    cmp::Reverse,
+    collections::{btree_map, BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet},
     convert::TryInto,
     iter::FromIterator,
     mem,
@@ -15,6 +16,7 @@ use datasize::DataSize;
 use futures::FutureExt;
 use itertools::Itertools;
 use prometheus::Registry;
+use rand::{seq::SliceRandom, thread_rng};
 use smallvec::smallvec;
 use tracing::{debug, error, info, warn};
 
@@ -52,6 +54,13 @@ const COMPONENT_NAME: &str = "deploy_buffer";
 
 type FootprintAndApprovals = (DeployFootprint, BTreeSet<Approval>);
 
+/// The priority with which the next deploy is taken from a bucket when filling a block.
+///
+/// Deploys offering a higher gas price come first, regardless of their bucket, with ties going to
+/// the bucket ranked first in a random order drawn for each block, so that blocks proposed over
+/// the same buffer differ.
+type BucketPriority = (u64, Reverse<usize>, Digest);
+
 #[derive(DataSize, Debug)]
 pub(crate) struct DeployBuffer {
     state: ComponentState,
@@ -336,10 +345,32 @@ impl DeployBuffer {
                 .and_modify(|vec| vec.push((with_approvals.clone(), footprint.clone())))
                 .or_insert(vec![(with_approvals, footprint)]);
         }
+        // order each bucket so that popping yields the highest gas price, earliest deploy first
+        for bucket in buckets.values_mut() {
+            bucket.sort_by_key(|(_, footprint)| {
+                (
+                    footprint.header.gas_price(),
+                    Reverse(footprint.header.timestamp()),
+                )
+            });
+        }
         buckets
     }
 
-    /// Returns a right-sized payload of deploys that can be proposed.
+    /// Returns the priority of the next deploy to be taken from `bucket`, or `None` if the bucket
+    /// is empty.
+    fn bucket_priority(
+        rank: usize,
+        body_hash: Digest,
+        bucket: &[(DeployHashWithApprovals, DeployFootprint)],
+    ) -> Option<BucketPriority> {
+        bucket
+            .last()
+            .map(|(_, footprint)| (footprint.header.gas_price(), Reverse(rank), body_hash))
+    }
+
+    /// Returns a right-sized payload of deploys that can be proposed, preferring those offering a
+    /// higher gas price.
     fn appendable_block(
         &mut self,
         timestamp: Timestamp,
@@ -354,14 +385,26 @@ impl DeployBuffer {
         let mut have_hit_deploy_limit = false;
 
         let mut buckets = self.buckets();
-        let mut body_hashes_queue: VecDeque<_> = buckets.keys().cloned().collect();
+        let mut body_hashes = buckets.keys().copied().collect_vec();
+        body_hashes.shuffle(&mut thread_rng());
+        let ranks: HashMap<Digest, usize> = body_hashes
+            .into_iter()
+            .enumerate()
+            .map(|(rank, body_hash)| (body_hash, rank))
+            .collect();
+        let mut body_hashes_queue: BinaryHeap<_> = buckets
+            .iter()
+            .filter_map(|(body_hash, bucket)| {
+                Self::bucket_priority(ranks[body_hash], *body_hash, bucket)
+            })
+            .collect();
 
         #[cfg(test)]
         let mut iter_counter = 0;
         #[cfg(test)]
         let iter_limit = self.buffer.len() * 4;
 
-        while let Some(body_hash) = body_hashes_queue.pop_front() {
+        while let Some((_, _, body_hash)) = body_hashes_queue.pop() {
             if Timestamp::now() > request_expiry {
                 break;
             }
@@ -374,14 +417,17 @@ impl DeployBuffer {
                 );
             }
 
-            let Some((with_approvals, footprint)) =
-                buckets.get_mut(&body_hash).and_then(Vec::<_>::pop)
-            else {
+            let Some(bucket) = buckets.get_mut(&body_hash) else {
                 continue;
             };
-            // bucket wasn't empty - push the hash back into the queue to be processed again on the
-            // next pass
-            body_hashes_queue.push_back(body_hash);
+            let Some((with_approvals, footprint)) = bucket.pop() else {
+                continue;
+            };
+            // if the bucket isn't empty, push the hash back into the queue to be ranked by the
+            // gas price of its next deploy
+            if let Some(priority) = Self::bucket_priority(ranks[&body_hash], body_hash, bucket) {
+                body_hashes_queue.push(priority);
+            }
             if footprint.is_transfer && have_hit_transfer_limit {
                 continue;
             }
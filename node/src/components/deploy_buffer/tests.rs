@@ -3,7 +3,10 @@ use std::iter;
 use prometheus::Registry;
 use rand::Rng;
 
-use casper_types::{testing::TestRng, EraId, SecretKey, TimeDiff};
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{
+    bytesrepr::Bytes, runtime_args, testing::TestRng, EraId, RuntimeArgs, SecretKey, TimeDiff, U512,
+};
 
 use super::*;
 use crate::{
@@ -673,6 +676,119 @@ fn should_have_diverse_proposable_blocks_with_stocked_buffer() {
     );
 }
 
+#[test]
+fn should_propose_deploys_with_highest_gas_price() {
+    let rng = &mut TestRng::new();
+    let max_deploy_count = 3;
+    let deploy_config = DeployConfig {
+        block_max_deploy_count: max_deploy_count,
+        block_max_transfer_count: 0,
+        block_max_approval_count: max_deploy_count,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+
+    // each deploy has a distinct body, so is in its own bucket
+    let timestamp = Timestamp::now();
+    let deploys = (1..=10u64)
+        .map(|gas_price| {
+            let payment = ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: runtime_args! { "amount" => U512::from(gas_price) },
+            };
+            let session = ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: runtime_args! { "gas_price" => gas_price },
+            };
+            Deploy::new(
+                timestamp,
+                TimeDiff::from_seconds(60),
+                gas_price,
+                vec![],
+                "chain".to_string(),
+                payment,
+                session,
+                &SecretKey::random(rng),
+                None,
+            )
+        })
+        .collect_vec();
+    for deploy in deploys.iter().rev() {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+    let expected: HashSet<_> = deploys
+        .iter()
+        .rev()
+        .take(max_deploy_count as usize)
+        .map(|deploy| *deploy.hash())
+        .collect();
+    assert_eq!(appendable_block.deploy_and_transfer_set(), &expected);
+}
+
+#[test]
+fn should_order_deploys_by_gas_price_across_buckets() {
+    let rng = &mut TestRng::new();
+    let max_deploy_count = 8;
+    let deploy_config = DeployConfig {
+        block_max_deploy_count: max_deploy_count,
+        block_max_transfer_count: 0,
+        block_max_approval_count: max_deploy_count,
+        ..Default::default()
+    };
+    let mut deploy_buffer =
+        DeployBuffer::new(deploy_config, Config::default(), &Registry::new()).unwrap();
+
+    let timestamp = Timestamp::now();
+    let mut new_deploy = |body_id: u64, gas_price: u64| {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! { "amount" => U512::from(body_id) },
+        };
+        let session = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! { "body_id" => body_id },
+        };
+        Deploy::new(
+            timestamp,
+            TimeDiff::from_seconds(60),
+            gas_price,
+            vec![],
+            "chain".to_string(),
+            payment,
+            session,
+            &SecretKey::random(rng),
+            None,
+        )
+    };
+
+    // three deploys sharing a body outbid seven deploys with distinct bodies
+    let shared_body_deploys = (8..=10)
+        .map(|gas_price| new_deploy(0, gas_price))
+        .collect_vec();
+    let distinct_body_deploys = (1..=7)
+        .map(|gas_price| new_deploy(gas_price, gas_price))
+        .collect_vec();
+    for deploy in shared_body_deploys.iter().chain(&distinct_body_deploys) {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+
+    let expiry = timestamp.saturating_add(TimeDiff::from_seconds(1));
+    let appendable_block = deploy_buffer.appendable_block(timestamp, expiry);
+
+    // all deploys sharing a body are included ahead of the two worst paying deploys with a
+    // distinct body
+    let expected: HashSet<_> = shared_body_deploys
+        .iter()
+        .chain(&distinct_body_deploys[2..])
+        .map(|deploy| *deploy.hash())
+        .collect();
+    assert_eq!(appendable_block.deploy_and_transfer_set(), &expected);
+}
+
 /// Event for the mock reactor.
 #[derive(Debug)]
 enum ReactorEvent {
@@ -16,39 +16,47 @@
 //! This component uses a ring buffer for outbound events providing some robustness against
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
 //! has advanced past their last received event.
+//!
+//! Optionally, all outbound events are also written to a persistent journal, allowing subscribers
+//! to request events from arbitrarily far back.  The events of blocks which have been removed from
+//! the journal are rebuilt from storage.
 
 mod config;
 mod event;
 mod event_indexer;
+mod event_journal;
 mod http_server;
 mod sse_server;
 #[cfg(test)]
 mod tests;
 
-use std::{fmt::Debug, net::SocketAddr, path::PathBuf};
+use std::{fmt::Debug, iter, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use datasize::DataSize;
+use futures::{future::BoxFuture, FutureExt};
 use tokio::sync::{
+    broadcast,
     mpsc::{self, UnboundedSender},
     oneshot,
 };
 use tracing::{error, info, warn};
 use warp::Filter;
 
-use casper_types::ProtocolVersion;
+use casper_types::{ExecutionResult, ProtocolVersion};
 
 use super::Component;
 use crate::{
     components::{ComponentState, InitializedComponent, PortBoundComponent},
-    effect::{EffectBuilder, Effects},
+    effect::{requests::StorageRequest, EffectBuilder, Effects},
     reactor::main_reactor::MainEvent,
-    types::JsonBlock,
+    types::{Block, BlockHash, DeployHash, DeployHeader, JsonBlock},
     utils::{self, ListeningError},
     NodeRng,
 };
 pub use config::Config;
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
+use event_journal::EventJournal;
 use sse_server::ChannelsAndFilter;
 pub(crate) use sse_server::SseData;
 
@@ -66,9 +74,14 @@ const ADDITIONAL_PERCENT_FOR_BROADCAST_CHANNEL_SIZE: u32 = 20;
 
 /// A helper trait whose bounds represent the requirements for a reactor event that `run_server` can
 /// work with.
-pub trait ReactorEventT: From<Event> + Send {}
+pub(crate) trait ReactorEventT: From<Event> + From<StorageRequest> + Send {}
+
+impl<REv> ReactorEventT for REv where REv: From<Event> + From<StorageRequest> + Send + 'static {}
 
-impl<REv> ReactorEventT for REv where REv: From<Event> + Send + 'static {}
+/// A function which rebuilds the `BlockAdded` event followed by the `DeployProcessed` events for
+/// the block at the given height from storage, or returns `None` if the block is not available.
+type BlockEventsRebuilder =
+    Arc<dyn Fn(u64) -> BoxFuture<'static, Option<Vec<SseData>>> + Send + Sync>;
 
 #[derive(DataSize, Debug)]
 struct InnerServer {
@@ -87,20 +100,39 @@ pub(crate) struct EventStreamServer {
     storage_path: PathBuf,
     api_version: ProtocolVersion,
     sse_server: Option<InnerServer>,
+    /// Channel sender to pass event-stream data to subscribers outside of the event-stream server,
+    /// e.g. clients of the JSON-RPC WebSocket server.
+    #[data_size(skip)]
+    event_broadcaster: broadcast::Sender<SseData>,
 }
 
 impl EventStreamServer {
     pub(crate) fn new(config: Config, storage_path: PathBuf, api_version: ProtocolVersion) -> Self {
+        let (event_broadcaster, _) =
+            broadcast::channel(broadcast_channel_size(config.event_stream_buffer_length));
         EventStreamServer {
             state: ComponentState::Uninitialized,
             config,
             storage_path,
             api_version,
             sse_server: None,
+            event_broadcaster,
         }
     }
 
-    fn listen(&mut self) -> Result<(), ListeningError> {
+    /// Returns a sender from which receivers of all subsequently-broadcast event-stream data can be
+    /// created.
+    ///
+    /// Events are provided via this channel regardless of whether the event-stream server itself is
+    /// enabled.
+    pub(crate) fn event_broadcaster(&self) -> broadcast::Sender<SseData> {
+        self.event_broadcaster.clone()
+    }
+
+    fn listen(
+        &mut self,
+        maybe_rebuilder: Option<BlockEventsRebuilder>,
+    ) -> Result<(), ListeningError> {
         let required_address = utils::resolve_address(&self.config.address).map_err(|error| {
             warn!(
                 %error,
@@ -111,16 +143,12 @@ impl EventStreamServer {
         })?;
 
         // Event stream channels and filter.
-        let broadcast_channel_size = self.config.event_stream_buffer_length
-            * (100 + ADDITIONAL_PERCENT_FOR_BROADCAST_CHANNEL_SIZE)
-            / 100;
-
         let ChannelsAndFilter {
             event_broadcaster,
             new_subscriber_info_receiver,
             sse_filter,
         } = ChannelsAndFilter::new(
-            broadcast_channel_size as usize,
+            broadcast_channel_size(self.config.event_stream_buffer_length),
             self.config.max_concurrent_subscribers,
         );
 
@@ -128,6 +156,22 @@ impl EventStreamServer {
 
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
 
+        let maybe_journal = if self.config.enable_event_journal {
+            match EventJournal::open(
+                &self.storage_path,
+                self.config.event_journal_max_bytes,
+                self.config.event_journal_max_eras,
+            ) {
+                Ok(journal) => Some(journal),
+                Err(error) => {
+                    warn!(%error, "failed to open sse journal: running without it");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let listening_address = match self.config.cors_origin.as_str() {
             "" => {
                 let (listening_address, server_with_shutdown) = warp::serve(sse_filter)
@@ -147,6 +191,8 @@ impl EventStreamServer {
                     sse_data_receiver,
                     event_broadcaster,
                     new_subscriber_info_receiver,
+                    maybe_journal,
+                    maybe_rebuilder,
                 ));
                 listening_address
             }
@@ -169,6 +215,8 @@ impl EventStreamServer {
                     sse_data_receiver,
                     event_broadcaster,
                     new_subscriber_info_receiver,
+                    maybe_journal,
+                    maybe_rebuilder,
                 ));
                 listening_address
             }
@@ -192,6 +240,8 @@ impl EventStreamServer {
                     sse_data_receiver,
                     event_broadcaster,
                     new_subscriber_info_receiver,
+                    maybe_journal,
+                    maybe_rebuilder,
                 ));
                 listening_address
             }
@@ -211,6 +261,12 @@ impl EventStreamServer {
 
     /// Broadcasts the SSE data to all clients connected to the event stream.
     fn broadcast(&mut self, sse_data: SseData) -> Effects<Event> {
+        // Only clone the data if there are subscribers outside the event-stream server, e.g. if the
+        // JSON-RPC WebSocket server is enabled.  Sending can still validly fail if they have all
+        // gone since, so don't log the error.
+        if self.event_broadcaster.receiver_count() > 0 {
+            let _ = self.event_broadcaster.send(sse_data.clone());
+        }
         if let Some(server) = self.sse_server.as_mut() {
             let event_index = server.event_indexer.next_index();
             let _ = server.sse_data_sender.send((event_index, sse_data));
@@ -219,6 +275,74 @@ impl EventStreamServer {
     }
 }
 
+/// Returns the `BlockAdded` SSE data for the given block.
+fn block_added_data(block: &Block) -> SseData {
+    SseData::BlockAdded {
+        block_hash: *block.hash(),
+        block: Box::new(JsonBlock::new(block, None)),
+    }
+}
+
+/// Returns the `DeployProcessed` SSE data for the given deploy.
+fn deploy_processed_data(
+    deploy_hash: DeployHash,
+    deploy_header: &DeployHeader,
+    block_hash: BlockHash,
+    execution_result: Box<ExecutionResult>,
+) -> SseData {
+    SseData::DeployProcessed {
+        deploy_hash: Box::new(deploy_hash),
+        account: Box::new(deploy_header.account().clone()),
+        timestamp: deploy_header.timestamp(),
+        ttl: deploy_header.ttl(),
+        dependencies: deploy_header.dependencies().clone(),
+        block_hash: Box::new(block_hash),
+        execution_result,
+    }
+}
+
+/// Returns a `BlockEventsRebuilder` which reads blocks and their execution results from storage.
+fn block_events_rebuilder<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+) -> BlockEventsRebuilder {
+    Arc::new(move |height| {
+        async move {
+            let block = effect_builder
+                .get_block_at_height_with_metadata_from_storage(height, false)
+                .await?
+                .block;
+            let block_hash = *block.hash();
+            let execution_results = effect_builder
+                .get_execution_results_from_storage(block_hash)
+                .await
+                .unwrap_or_default();
+            let events = iter::once(block_added_data(&block))
+                .chain(execution_results.into_iter().map(
+                    |(deploy_hash, deploy_header, execution_result)| {
+                        deploy_processed_data(
+                            deploy_hash,
+                            &deploy_header,
+                            block_hash,
+                            Box::new(execution_result),
+                        )
+                    },
+                ))
+                .collect();
+            Some(events)
+        }
+        .boxed()
+    })
+}
+
+/// Returns the size of the broadcast channels for the given event stream buffer length.
+///
+/// A tokio broadcast channel must have a non-zero capacity, so this is at least 1.
+fn broadcast_channel_size(event_stream_buffer_length: u32) -> usize {
+    let size =
+        event_stream_buffer_length * (100 + ADDITIONAL_PERCENT_FOR_BROADCAST_CHANNEL_SIZE) / 100;
+    (size as usize).max(1)
+}
+
 impl Drop for EventStreamServer {
     fn drop(&mut self) {
         let _ = self.broadcast(SseData::Shutdown);
@@ -285,25 +409,19 @@ where
                     );
                     Effects::new()
                 }
-                Event::BlockAdded(block) => self.broadcast(SseData::BlockAdded {
-                    block_hash: *block.hash(),
-                    block: Box::new(JsonBlock::new(&block, None)),
-                }),
+                Event::BlockAdded(block) => self.broadcast(block_added_data(&block)),
                 Event::DeployAccepted(deploy) => self.broadcast(SseData::DeployAccepted { deploy }),
                 Event::DeployProcessed {
                     deploy_hash,
                     deploy_header,
                     block_hash,
                     execution_result,
-                } => self.broadcast(SseData::DeployProcessed {
-                    deploy_hash: Box::new(deploy_hash),
-                    account: Box::new(deploy_header.account().clone()),
-                    timestamp: deploy_header.timestamp(),
-                    ttl: deploy_header.ttl(),
-                    dependencies: deploy_header.dependencies().clone(),
-                    block_hash: Box::new(block_hash),
+                } => self.broadcast(deploy_processed_data(
+                    deploy_hash,
+                    &deploy_header,
+                    block_hash,
                     execution_result,
-                }),
+                )),
                 Event::DeploysExpired(deploy_hashes) => deploy_hashes
                     .into_iter()
                     .flat_map(|deploy_hash| self.broadcast(SseData::DeployExpired { deploy_hash }))
@@ -362,9 +480,9 @@ where
 
     fn listen(
         &mut self,
-        _effect_builder: EffectBuilder<REv>,
+        effect_builder: EffectBuilder<REv>,
     ) -> Result<Effects<Self::ComponentEvent>, Self::Error> {
-        self.listen()?;
+        self.listen(Some(block_events_rebuilder(effect_builder)))?;
         Ok(Effects::new())
     }
 }
@@ -15,6 +15,12 @@ const DEFAULT_MAX_CONCURRENT_SUBSCRIBERS: u32 = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
+/// Default maximum size in bytes of the event journal.
+const DEFAULT_EVENT_JOURNAL_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Default maximum number of eras covered by the event journal (0 means unlimited).
+const DEFAULT_EVENT_JOURNAL_MAX_ERAS: u64 = 0;
+
 /// SSE HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -34,6 +40,20 @@ pub struct Config {
 
     /// CORS origin.
     pub cors_origin: String,
+
+    /// Setting to enable the persistent on-disk journal of events, allowing clients to request
+    /// events older than those held in the in-memory buffer via `start_from`.
+    #[serde(default)]
+    pub enable_event_journal: bool,
+
+    /// Maximum size in bytes of the event journal, or 0 for unlimited.
+    #[serde(default = "default_event_journal_max_bytes")]
+    pub event_journal_max_bytes: u64,
+
+    /// Maximum number of most recent eras for which events are kept in the event journal, or 0
+    /// for unlimited.
+    #[serde(default = "default_event_journal_max_eras")]
+    pub event_journal_max_eras: u64,
 }
 
 impl Config {
@@ -45,10 +65,23 @@ impl Config {
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
             max_concurrent_subscribers: DEFAULT_MAX_CONCURRENT_SUBSCRIBERS,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            enable_event_journal: false,
+            event_journal_max_bytes: DEFAULT_EVENT_JOURNAL_MAX_BYTES,
+            event_journal_max_eras: DEFAULT_EVENT_JOURNAL_MAX_ERAS,
         }
     }
 }
 
+/// The default for `Config::event_journal_max_bytes`.
+fn default_event_journal_max_bytes() -> u64 {
+    DEFAULT_EVENT_JOURNAL_MAX_BYTES
+}
+
+/// The default for `Config::event_journal_max_eras`.
+fn default_event_journal_max_eras() -> u64 {
+    DEFAULT_EVENT_JOURNAL_MAX_ERAS
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config::new()
@@ -0,0 +1,911 @@
+//! A persistent on-disk journal of the events emitted by the event stream server.
+//!
+//! Events are appended to a sequence of segment files held in the `sse_journal` folder under the
+//! storage path.  Each segment file is named after the ID of the first event it holds, and
+//! contains a series of records, each comprising a header of the event ID, the era (if any)
+//! associated with the event and the length of the JSON-encoded event, followed by the JSON itself.
+//!
+//! The oldest segments are removed once the journal exceeds its configured size or covers more
+//! than its configured number of eras.  The active segment is never removed, so the journal can
+//! exceed its configured size by up to one segment.
+//!
+//! Alongside the segments, a block index records the event ID of every `BlockAdded` event and the
+//! height of the corresponding block, allowing the events for blocks no longer held in the journal
+//! to be rebuilt from storage.  The block index is trimmed to the most recent
+//! `MAX_BLOCK_INDEX_ENTRIES` blocks.
+//!
+//! The event IDs held in the journal are always consecutive.  If an event is appended which
+//! doesn't directly follow the last journalled one (e.g. if the persisted event index has been
+//! lost, or the IDs have wrapped), the journal and block index are cleared.
+//!
+//! As all access to the journal involves blocking file I/O, it is owned by a task running on a
+//! blocking thread (see [`spawn`]), and is accessed via [`JournalRequest`]s.
+
+use std::{
+    collections::VecDeque,
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::{self, JoinHandle},
+};
+use tracing::{debug, info, warn};
+
+use casper_types::EraId;
+
+use super::{
+    sse_server::{Id, ServerSentEvent},
+    SseData,
+};
+
+const JOURNAL_DIR_NAME: &str = "sse_journal";
+const SEGMENT_EXTENSION: &str = "events";
+const BLOCK_INDEX_FILENAME: &str = "block_index";
+const BLOCK_INDEX_TEMP_FILENAME: &str = "block_index.tmp";
+
+/// The maximum size of a single segment file.
+const MAX_SEGMENT_BYTES: u64 = 16 * 1024 * 1024;
+/// The length of a record header: the `u32` event ID, `u64` era ID and `u32` data length.
+const RECORD_HEADER_LENGTH: usize = 16;
+/// The value written in place of the era ID for events with no associated era.
+const NO_ERA: u64 = u64::MAX;
+/// The length of an entry in the block index: the `u32` event ID and `u64` block height.
+const BLOCK_INDEX_ENTRY_LENGTH: usize = 12;
+/// The maximum number of entries kept in the block index, i.e. the number of most recent blocks
+/// whose events can be rebuilt from storage.
+///
+/// To avoid rewriting the block index file for every block, it is only trimmed once it exceeds
+/// this by a tenth.
+const MAX_BLOCK_INDEX_ENTRIES: usize = 1_000_000;
+
+/// Error returned when writing to or reading from the event journal.
+#[derive(Debug, Error)]
+pub(super) enum Error {
+    /// Error accessing a journal file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Error encoding an event as JSON.
+    #[error("failed to encode event: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+/// A single segment file of the journal.
+#[derive(Clone, Debug)]
+struct Segment {
+    path: PathBuf,
+    first_id: Id,
+    last_id: Id,
+    byte_count: u64,
+    /// The highest era ID of any event held in this or an earlier segment.
+    latest_era: Option<EraId>,
+}
+
+/// An entry in the block index, mapping a `BlockAdded` event to its block.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub(super) struct BlockIndexEntry {
+    /// The ID of the `BlockAdded` event.
+    pub(super) id: Id,
+    /// The height of the added block.
+    pub(super) height: u64,
+}
+
+impl BlockIndexEntry {
+    fn to_bytes(self) -> [u8; BLOCK_INDEX_ENTRY_LENGTH] {
+        let mut bytes = [0; BLOCK_INDEX_ENTRY_LENGTH];
+        bytes[..4].copy_from_slice(&self.id.to_le_bytes());
+        bytes[4..].copy_from_slice(&self.height.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        BlockIndexEntry {
+            id: Id::from_le_bytes(bytes[..4].try_into().unwrap()),
+            height: u64::from_le_bytes(bytes[4..BLOCK_INDEX_ENTRY_LENGTH].try_into().unwrap()),
+        }
+    }
+}
+
+/// The parsed header of a record in a segment file.
+struct RecordHeader {
+    id: Id,
+    era: Option<EraId>,
+    data_length: usize,
+}
+
+impl RecordHeader {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let header = bytes.get(..RECORD_HEADER_LENGTH)?;
+        let era = u64::from_le_bytes(header[4..12].try_into().unwrap());
+        Some(RecordHeader {
+            id: Id::from_le_bytes(header[..4].try_into().unwrap()),
+            era: (era != NO_ERA).then(|| EraId::new(era)),
+            data_length: u32::from_le_bytes(header[12..].try_into().unwrap()) as usize,
+        })
+    }
+}
+
+/// The details required to replay the journalled events for a new subscriber.
+#[derive(Debug)]
+pub(super) struct ReplayPlan {
+    /// The ID of the first event to be replayed.
+    pub(super) start_from: Id,
+    /// The blocks whose events need to be rebuilt from storage, as they're no longer held in the
+    /// journal.
+    pub(super) blocks_to_rebuild: Vec<BlockIndexEntry>,
+    /// Rebuilt events with this or a higher ID are held in the journal and should be skipped.
+    pub(super) rebuild_end: Id,
+    /// The segment files holding events to be replayed.
+    pub(super) segments: Vec<PathBuf>,
+    /// Journalled events with this or a higher ID should be skipped.
+    pub(super) end_before: Id,
+}
+
+/// The persistent event journal.
+#[derive(Debug)]
+pub(super) struct EventJournal {
+    dir: PathBuf,
+    /// The maximum total size of the segments, or 0 for unlimited.
+    max_bytes: u64,
+    /// The maximum number of eras covered by the segments, or 0 for unlimited.
+    max_eras: u64,
+    segment_max_bytes: u64,
+    segments: VecDeque<Segment>,
+    active_segment_file: Option<File>,
+    max_block_index_entries: usize,
+    block_index: Vec<BlockIndexEntry>,
+    block_index_file: File,
+}
+
+impl EventJournal {
+    /// Opens the journal held under `storage_path`, creating it if it doesn't exist.
+    ///
+    /// Any corrupt or truncated records at the end of the segments are removed.
+    pub(super) fn open(storage_path: &Path, max_bytes: u64, max_eras: u64) -> io::Result<Self> {
+        let dir = storage_path.join(JOURNAL_DIR_NAME);
+        fs::create_dir_all(&dir)?;
+
+        let mut segment_paths = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(SEGMENT_EXTENSION)
+            {
+                continue;
+            }
+            match path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<Id>().ok())
+            {
+                Some(first_id) => segment_paths.push((first_id, path)),
+                None => warn!(file = %path.display(), "ignoring unrecognized sse journal file"),
+            }
+        }
+        segment_paths.sort_unstable();
+
+        let mut segments: VecDeque<Segment> = VecDeque::new();
+        for (first_id, path) in segment_paths {
+            let previous_era = segments.back().and_then(|segment| segment.latest_era);
+            let maybe_segment = recover_segment(path.clone(), first_id, previous_era)?;
+            let segment = match maybe_segment {
+                Some(segment) => segment,
+                None => {
+                    fs::remove_file(&path)?;
+                    continue;
+                }
+            };
+            let follows_previous = segments.back().map_or(true, |previous| {
+                previous.last_id.checked_add(1) == Some(segment.first_id)
+            });
+            if !follows_previous {
+                // Only keep the most recent run of consecutive events.
+                warn!(file = %segment.path.display(), "discontinuity in sse journal");
+                for stale_segment in segments.drain(..) {
+                    fs::remove_file(&stale_segment.path)?;
+                }
+            }
+            segments.push_back(segment);
+        }
+
+        let block_index_path = dir.join(BLOCK_INDEX_FILENAME);
+        let block_index_bytes = match fs::read(&block_index_path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => vec![],
+            Err(error) => return Err(error),
+        };
+        let mut block_index: Vec<BlockIndexEntry> = block_index_bytes
+            .chunks_exact(BLOCK_INDEX_ENTRY_LENGTH)
+            .map(BlockIndexEntry::from_bytes)
+            .collect();
+        // Drop any entries for events which were lost from the end of the segments.
+        if let Some(next_id) = segments
+            .back()
+            .and_then(|segment| segment.last_id.checked_add(1))
+        {
+            block_index.truncate(block_index.partition_point(|entry| entry.id < next_id));
+        }
+        let block_index_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&block_index_path)?;
+        block_index_file.set_len((block_index.len() * BLOCK_INDEX_ENTRY_LENGTH) as u64)?;
+
+        let segment_max_bytes = if max_bytes == 0 {
+            MAX_SEGMENT_BYTES
+        } else {
+            (max_bytes / 4).clamp(1, MAX_SEGMENT_BYTES)
+        };
+
+        let mut journal = EventJournal {
+            dir,
+            max_bytes,
+            max_eras,
+            segment_max_bytes,
+            segments,
+            active_segment_file: None,
+            max_block_index_entries: MAX_BLOCK_INDEX_ENTRIES,
+            block_index,
+            block_index_file,
+        };
+        journal.trim()?;
+
+        info!(
+            first_id = ?journal.first_id(),
+            next_id = ?journal.next_id(),
+            segment_count = journal.segments.len(),
+            block_count = journal.block_index.len(),
+            "opened sse journal"
+        );
+        Ok(journal)
+    }
+
+    /// Returns the ID of the oldest event held in the journal.
+    pub(super) fn first_id(&self) -> Option<Id> {
+        self.segments.front().map(|segment| segment.first_id)
+    }
+
+    /// Returns the ID which the next event appended to the journal is expected to have.
+    pub(super) fn next_id(&self) -> Option<Id> {
+        self.segments
+            .back()
+            .and_then(|segment| segment.last_id.checked_add(1))
+    }
+
+    /// Appends the given event to the journal, removing the oldest segments if the journal has
+    /// grown beyond its configured limits.
+    pub(super) fn append(&mut self, id: Id, data: &SseData) -> Result<(), Error> {
+        let follows_journal = self.segments.is_empty() || self.next_id() == Some(id);
+        let follows_block_index = self.block_index.last().map_or(true, |entry| entry.id < id);
+        if !follows_journal || !follows_block_index {
+            info!(
+                %id,
+                expected_id = ?self.next_id(),
+                "sse event id doesn't follow journalled events: clearing sse journal"
+            );
+            self.clear()?;
+        }
+
+        let json = serde_json::to_vec(data)?;
+        let era = event_era(data);
+        let mut record = Vec::with_capacity(RECORD_HEADER_LENGTH + json.len());
+        record.extend_from_slice(&id.to_le_bytes());
+        record.extend_from_slice(&era.map_or(NO_ERA, |era_id| era_id.value()).to_le_bytes());
+        record.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        record.extend_from_slice(&json);
+
+        let needs_new_segment = self.active_segment_file.is_none()
+            || self
+                .segments
+                .back()
+                .map_or(true, |segment| segment.byte_count >= self.segment_max_bytes);
+        if needs_new_segment {
+            self.open_active_segment(id)?;
+        }
+        let file = self
+            .active_segment_file
+            .as_mut()
+            .expect("should have active segment");
+        file.write_all(&record)?;
+        file.flush()?;
+
+        let segment = self
+            .segments
+            .back_mut()
+            .expect("should have active segment");
+        segment.last_id = id;
+        segment.byte_count += record.len() as u64;
+        segment.latest_era = segment.latest_era.max(era);
+
+        if let SseData::BlockAdded { block, .. } = data {
+            let entry = BlockIndexEntry {
+                id,
+                height: block.header.height,
+            };
+            self.block_index_file.write_all(&entry.to_bytes())?;
+            self.block_index_file.flush()?;
+            self.block_index.push(entry);
+        }
+
+        self.trim()?;
+        Ok(())
+    }
+
+    /// Returns the details required to replay the events from `start_from` up to but excluding
+    /// `end_before`.
+    pub(super) fn replay_plan(&self, start_from: Id, end_before: Id) -> ReplayPlan {
+        let journal_start = self.first_id().unwrap_or(end_before).min(end_before);
+
+        let blocks_to_rebuild = if start_from < journal_start {
+            // Include the block preceding `start_from`, since its `DeployProcessed` events may
+            // follow `start_from`.
+            let first = self
+                .block_index
+                .partition_point(|entry| entry.id <= start_from)
+                .saturating_sub(1);
+            let last = self
+                .block_index
+                .partition_point(|entry| entry.id < journal_start);
+            self.block_index[first..last.max(first)].to_vec()
+        } else {
+            vec![]
+        };
+
+        let segments = self
+            .segments
+            .iter()
+            .filter(|segment| segment.last_id >= start_from && segment.first_id < end_before)
+            .map(|segment| segment.path.clone())
+            .collect();
+
+        ReplayPlan {
+            start_from,
+            blocks_to_rebuild,
+            rebuild_end: journal_start,
+            segments,
+            end_before,
+        }
+    }
+
+    /// Creates a new segment for events starting at `first_id`, or reopens the last segment if it
+    /// still has space.
+    fn open_active_segment(&mut self, first_id: Id) -> io::Result<()> {
+        if let Some(segment) = self.segments.back() {
+            if segment.byte_count < self.segment_max_bytes {
+                self.active_segment_file =
+                    Some(OpenOptions::new().append(true).open(&segment.path)?);
+                return Ok(());
+            }
+        }
+
+        let path = self
+            .dir
+            .join(format!("{:010}.{}", first_id, SEGMENT_EXTENSION));
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)?;
+        let latest_era = self.segments.back().and_then(|segment| segment.latest_era);
+        debug!(file = %path.display(), "created new sse journal segment");
+        self.segments.push_back(Segment {
+            path,
+            first_id,
+            last_id: first_id,
+            byte_count: 0,
+            latest_era,
+        });
+        self.active_segment_file = Some(file);
+        Ok(())
+    }
+
+    /// Removes the oldest segments while the journal exceeds its configured limits, and the oldest
+    /// entries of the block index if it has grown too large.
+    fn trim(&mut self) -> io::Result<()> {
+        while self.segments.len() > 1 && self.should_remove_oldest_segment() {
+            if let Some(segment) = self.segments.pop_front() {
+                debug!(file = %segment.path.display(), "removing sse journal segment");
+                fs::remove_file(&segment.path)?;
+            }
+        }
+
+        let max_entries = self.max_block_index_entries;
+        if self.block_index.len() > max_entries.saturating_add(max_entries / 10) {
+            let excess = self.block_index.len() - max_entries;
+            self.block_index.drain(..excess);
+            self.rewrite_block_index()?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the block index file with the current contents of the block index.
+    fn rewrite_block_index(&mut self) -> io::Result<()> {
+        let bytes: Vec<u8> = self
+            .block_index
+            .iter()
+            .flat_map(|entry| entry.to_bytes())
+            .collect();
+        let temp_path = self.dir.join(BLOCK_INDEX_TEMP_FILENAME);
+        let path = self.dir.join(BLOCK_INDEX_FILENAME);
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, &path)?;
+        self.block_index_file = OpenOptions::new().append(true).open(&path)?;
+        debug!(
+            block_count = self.block_index.len(),
+            "trimmed sse journal block index"
+        );
+        Ok(())
+    }
+
+    fn should_remove_oldest_segment(&self) -> bool {
+        let oldest = match self.segments.front() {
+            Some(segment) => segment,
+            None => return false,
+        };
+
+        let total_bytes: u64 = self.segments.iter().map(|segment| segment.byte_count).sum();
+        if self.max_bytes != 0 && total_bytes > self.max_bytes {
+            return true;
+        }
+
+        let latest_era = self.segments.back().and_then(|segment| segment.latest_era);
+        match (oldest.latest_era, latest_era) {
+            (_, None) => false,
+            _ if self.max_eras == 0 => false,
+            (None, Some(_)) => true,
+            (Some(oldest_era), Some(latest_era)) => {
+                oldest_era.value().saturating_add(self.max_eras) <= latest_era.value()
+            }
+        }
+    }
+
+    /// Removes all segments and clears the block index.
+    fn clear(&mut self) -> io::Result<()> {
+        self.active_segment_file = None;
+        for segment in self.segments.drain(..) {
+            fs::remove_file(&segment.path)?;
+        }
+        self.block_index.clear();
+        self.block_index_file.set_len(0)?;
+        Ok(())
+    }
+}
+
+/// A request to the task which owns the event journal.
+#[derive(Debug)]
+pub(super) enum JournalRequest {
+    /// Appends the given event to the journal.
+    Append { id: Id, data: SseData },
+    /// Provides the plan to replay the events from `start_from` up to but excluding `end_before`,
+    /// or up to the end of the journal if `end_before` is `None`.
+    ///
+    /// `None` is provided if there are no such events.
+    ReplayPlan {
+        start_from: Id,
+        end_before: Option<Id>,
+        responder: oneshot::Sender<Option<ReplayPlan>>,
+    },
+}
+
+/// Moves the journal to a blocking thread, where it handles the requests received via the returned
+/// sender in order, until all the senders have been dropped.
+///
+/// If writing to the journal fails, it is closed and the receiver is dropped.
+pub(super) fn spawn(
+    mut journal: EventJournal,
+) -> (mpsc::UnboundedSender<JournalRequest>, JoinHandle<()>) {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let join_handle = task::spawn_blocking(move || {
+        while let Some(request) = receiver.blocking_recv() {
+            match request {
+                JournalRequest::Append { id, data } => {
+                    if let Err(error) = journal.append(id, &data) {
+                        warn!(%error, "failed to write to sse journal: disabling it");
+                        return;
+                    }
+                }
+                JournalRequest::ReplayPlan {
+                    start_from,
+                    end_before,
+                    responder,
+                } => {
+                    let maybe_plan = end_before
+                        .or_else(|| journal.next_id())
+                        .filter(|end_before| start_from < *end_before)
+                        .map(|end_before| journal.replay_plan(start_from, end_before));
+                    let _ = responder.send(maybe_plan);
+                }
+            }
+        }
+    });
+    (sender, join_handle)
+}
+
+/// Reads the events with IDs in the range [`start_from`, `end_before`) from the given segment
+/// file.
+///
+/// Events which can't be decoded are skipped.  This performs blocking file I/O.
+pub(super) fn read_segment(
+    path: &Path,
+    start_from: Id,
+    end_before: Id,
+) -> io::Result<Vec<ServerSentEvent>> {
+    let bytes = fs::read(path)?;
+    let mut events = vec![];
+    let mut offset = 0;
+    while let Some(header) = RecordHeader::parse(&bytes[offset..]) {
+        let data_start = offset + RECORD_HEADER_LENGTH;
+        let data = match bytes.get(data_start..data_start + header.data_length) {
+            Some(data) => data,
+            None => break,
+        };
+        offset = data_start + header.data_length;
+        if header.id < start_from || header.id >= end_before {
+            continue;
+        }
+        match serde_json::from_slice(data) {
+            Ok(data) => events.push(ServerSentEvent {
+                id: Some(header.id),
+                data,
+            }),
+            Err(error) => warn!(
+                file = %path.display(),
+                id = %header.id,
+                %error,
+                "failed to decode journalled sse event"
+            ),
+        }
+    }
+    Ok(events)
+}
+
+/// Scans the given segment file, truncating any incomplete or out-of-sequence records at its end.
+///
+/// Returns `None` if the segment holds no valid records.
+fn recover_segment(
+    path: PathBuf,
+    first_id: Id,
+    previous_era: Option<EraId>,
+) -> io::Result<Option<Segment>> {
+    let bytes = fs::read(&path)?;
+    let mut offset = 0;
+    let mut last_id = None;
+    let mut latest_era = previous_era;
+    while let Some(header) = RecordHeader::parse(&bytes[offset..]) {
+        let expected_id = match last_id {
+            None => Some(first_id),
+            Some(id) => Id::checked_add(id, 1),
+        };
+        let record_end = offset + RECORD_HEADER_LENGTH + header.data_length;
+        if Some(header.id) != expected_id || record_end > bytes.len() {
+            break;
+        }
+        last_id = Some(header.id);
+        latest_era = latest_era.max(header.era);
+        offset = record_end;
+    }
+
+    if offset < bytes.len() {
+        warn!(
+            file = %path.display(),
+            valid_byte_count = offset,
+            byte_count = bytes.len(),
+            "truncating corrupt sse journal segment"
+        );
+        OpenOptions::new()
+            .write(true)
+            .open(&path)?
+            .set_len(offset as u64)?;
+    }
+
+    Ok(last_id.map(|last_id| Segment {
+        path,
+        first_id,
+        last_id,
+        byte_count: offset as u64,
+        latest_era,
+    }))
+}
+
+/// Returns the era associated with the given event, if any.
+fn event_era(data: &SseData) -> Option<EraId> {
+    match data {
+        SseData::BlockAdded { block, .. } => Some(block.header.era_id),
+        SseData::Fault { era_id, .. } | SseData::Step { era_id, .. } => Some(*era_id),
+        SseData::FinalitySignature(signature) => Some(signature.era_id),
+        SseData::ApiVersion(_)
+        | SseData::DeployAccepted { .. }
+        | SseData::DeployProcessed { .. }
+        | SseData::DeployExpired { .. }
+        | SseData::Shutdown => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+    use crate::{logging, types::JsonBlock};
+
+    fn random_events(rng: &mut TestRng, count: usize) -> Vec<SseData> {
+        (0..count)
+            .map(|index| match index % 3 {
+                0 => SseData::random_block_added(rng),
+                1 => SseData::random_deploy_processed(rng),
+                _ => SseData::random_deploy_expired(rng),
+            })
+            .collect()
+    }
+
+    fn block_added_in_era(rng: &mut TestRng, era: u64, height: u64) -> SseData {
+        match SseData::random_block_added(rng) {
+            SseData::BlockAdded { block_hash, block } => {
+                let mut block: JsonBlock = *block;
+                block.header.era_id = EraId::new(era);
+                block.header.height = height;
+                SseData::BlockAdded {
+                    block_hash,
+                    block: Box::new(block),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn read_all(journal: &EventJournal) -> Vec<ServerSentEvent> {
+        journal
+            .segments
+            .iter()
+            .flat_map(|segment| read_segment(&segment.path, 0, Id::MAX).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn should_persist_and_replay_events() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 30);
+
+        {
+            let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+            for (id, data) in events.iter().take(20).enumerate() {
+                journal.append(id as Id, data).unwrap();
+            }
+        }
+
+        // Reopening should continue from where the previous session left off.
+        let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        assert_eq!(journal.first_id(), Some(0));
+        assert_eq!(journal.next_id(), Some(20));
+        for (id, data) in events.iter().enumerate().skip(20) {
+            journal.append(id as Id, data).unwrap();
+        }
+
+        let replayed = read_all(&journal);
+        assert_eq!(replayed.len(), events.len());
+        for (id, (event, data)) in replayed.iter().zip(events.iter()).enumerate() {
+            assert_eq!(event.id, Some(id as Id));
+            assert_eq!(&event.data, data);
+        }
+        assert_eq!(journal.block_index.len(), 10);
+        assert!(journal.block_index.iter().all(|entry| entry.id % 3 == 0));
+    }
+
+    #[test]
+    fn should_trim_by_size() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        const MAX_BYTES: u64 = 20_000;
+
+        let mut journal = EventJournal::open(tempdir.path(), MAX_BYTES, 0).unwrap();
+        for (id, data) in random_events(&mut rng, 300).iter().enumerate() {
+            journal.append(id as Id, data).unwrap();
+        }
+
+        assert!(journal.segments.len() > 1);
+        assert!(journal.first_id().unwrap() > 0);
+        assert_eq!(journal.next_id(), Some(300));
+        let total_bytes: u64 = journal.segments.iter().map(|s| s.byte_count).sum();
+        assert!(total_bytes <= MAX_BYTES + journal.segment_max_bytes * 2);
+
+        // The block index should still cover all blocks.
+        assert_eq!(journal.block_index.len(), 100);
+        assert_eq!(journal.block_index[0].id, 0);
+    }
+
+    #[test]
+    fn should_trim_by_era() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let mut journal = EventJournal::open(tempdir.path(), 400_000, 2).unwrap();
+        // Force every event into its own segment so that trimming is exact.
+        journal.segment_max_bytes = 1;
+        let mut id = 0;
+        for era in 0..5 {
+            for height in 0..3 {
+                journal
+                    .append(id, &block_added_in_era(&mut rng, era, era * 3 + height))
+                    .unwrap();
+                id += 1;
+            }
+        }
+
+        let eras: Vec<u64> = read_all(&journal)
+            .into_iter()
+            .filter_map(|event| event_era(&event.data).map(|era_id| era_id.value()))
+            .collect();
+        assert_eq!(eras.first(), Some(&3));
+        assert_eq!(eras.last(), Some(&4));
+    }
+
+    #[test]
+    fn should_clear_on_discontinuity() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 10);
+
+        let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        for (id, data) in events.iter().take(5).enumerate() {
+            journal.append(id as Id, data).unwrap();
+        }
+        journal.append(100, &events[5]).unwrap();
+
+        assert_eq!(journal.first_id(), Some(100));
+        assert_eq!(journal.next_id(), Some(101));
+        assert!(journal.block_index.is_empty());
+        assert_eq!(read_all(&journal).len(), 1);
+    }
+
+    #[test]
+    fn should_truncate_corrupt_tail() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 10);
+
+        let segment_path = {
+            let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+            for (id, data) in events.iter().enumerate() {
+                journal.append(id as Id, data).unwrap();
+            }
+            journal.segments[0].path.clone()
+        };
+
+        // Chop the final record in half.
+        let length = fs::metadata(&segment_path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&segment_path)
+            .unwrap()
+            .set_len(length - 10)
+            .unwrap();
+
+        let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        assert_eq!(journal.next_id(), Some(9));
+        journal.append(9, &events[9]).unwrap();
+        let replayed = read_all(&journal);
+        assert_eq!(replayed.len(), 10);
+        assert_eq!(replayed[9].data, events[9]);
+    }
+
+    #[test]
+    fn should_plan_replay() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let mut journal = EventJournal::open(tempdir.path(), 10_000, 0).unwrap();
+        for (id, data) in random_events(&mut rng, 300).iter().enumerate() {
+            journal.append(id as Id, data).unwrap();
+        }
+        let journal_start = journal.first_id().unwrap();
+        assert!(journal_start > 10);
+
+        // Starting within the journal shouldn't require any rebuilding.
+        let plan = journal.replay_plan(journal_start, 300);
+        assert!(plan.blocks_to_rebuild.is_empty());
+        assert_eq!(plan.segments.len(), journal.segments.len());
+
+        // Starting before the journal should rebuild from the block preceding `start_from`.
+        let plan = journal.replay_plan(10, 300);
+        assert_eq!(plan.rebuild_end, journal_start);
+        assert_eq!(plan.blocks_to_rebuild[0].id, 9);
+        assert!(plan
+            .blocks_to_rebuild
+            .iter()
+            .all(|entry| entry.id < journal_start));
+        assert_eq!(
+            plan.blocks_to_rebuild.last().unwrap().id,
+            (journal_start - 1) / 3 * 3
+        );
+
+        // Events at or after `end_before` shouldn't be replayed.
+        let plan = journal.replay_plan(journal_start, journal_start);
+        assert!(plan.segments.is_empty());
+    }
+
+    #[test]
+    fn should_trim_block_index() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 90);
+
+        {
+            let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+            journal.max_block_index_entries = 10;
+            for (id, data) in events.iter().enumerate() {
+                journal.append(id as Id, data).unwrap();
+                assert!(journal.block_index.len() <= 11);
+            }
+            // Each time the index of the 30 blocks grew to 12 entries, it was trimmed back to 10.
+            assert_eq!(journal.block_index.len(), 10);
+            assert_eq!(journal.block_index[0].id, 60);
+        }
+
+        // The trimmed block index should be persisted, and appended to after reopening.
+        let mut journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        let ids: Vec<Id> = journal.block_index.iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, (60..90).step_by(3).collect::<Vec<Id>>());
+        journal.append(90, &events[0]).unwrap();
+        assert_eq!(journal.block_index.last().unwrap().id, 90);
+        drop(journal);
+        let journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        assert_eq!(journal.block_index.len(), 11);
+    }
+
+    #[tokio::test]
+    async fn should_handle_requests_in_order() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 10);
+
+        let journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        let (sender, join_handle) = spawn(journal);
+        for (id, data) in events.iter().enumerate() {
+            let request = JournalRequest::Append {
+                id: id as Id,
+                data: data.clone(),
+            };
+            sender.send(request).unwrap();
+        }
+
+        // The plan should cover the events appended before it was requested.
+        let request_plan = |start_from, end_before| {
+            let (responder, receiver) = oneshot::channel();
+            let request = JournalRequest::ReplayPlan {
+                start_from,
+                end_before,
+                responder,
+            };
+            sender.send(request).unwrap();
+            receiver
+        };
+        let plan = request_plan(2, None).await.unwrap().unwrap();
+        assert_eq!(plan.start_from, 2);
+        assert_eq!(plan.end_before, 10);
+        let replayed = read_segment(&plan.segments[0], plan.start_from, plan.end_before).unwrap();
+        assert_eq!(replayed.len(), 8);
+        assert_eq!(replayed[0].data, events[2]);
+
+        let plan = request_plan(2, Some(5)).await.unwrap().unwrap();
+        assert_eq!(plan.end_before, 5);
+        assert!(request_plan(10, None).await.unwrap().is_none());
+
+        // Dropping the sender should stop the task once the pending requests have been handled.
+        drop(sender);
+        join_handle.await.unwrap();
+        let journal = EventJournal::open(tempdir.path(), 0, 0).unwrap();
+        assert_eq!(journal.next_id(), Some(10));
+    }
+}
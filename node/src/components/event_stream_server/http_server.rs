@@ -1,19 +1,31 @@
+use std::iter;
+
 use futures::{future, Future, FutureExt};
 use tokio::{
     select,
     sync::{broadcast, mpsc, oneshot},
     task,
 };
-use tracing::{info, trace};
+use tracing::{debug, info, trace, warn};
 use wheelbuf::WheelBuf;
 
 use casper_types::ProtocolVersion;
 
 use super::{
+    event_journal::{self, EventJournal, JournalRequest, ReplayPlan},
     sse_server::{BroadcastChannelMessage, Id, NewSubscriberInfo, ServerSentEvent},
-    Config, EventIndex, SseData,
+    BlockEventsRebuilder, Config, EventIndex, SseData,
 };
 
+/// A subscriber which is being sent events from the journal, and which will be provided with a
+/// receiver of the ongoing events once it has caught up with the buffered events.
+struct CatchingUpSubscriber {
+    /// The ID of the next event to be sent to the subscriber.
+    next_id: Id,
+    initial_events_sender: mpsc::Sender<ServerSentEvent>,
+    ongoing_events_sender: oneshot::Sender<broadcast::Receiver<BroadcastChannelMessage>>,
+}
+
 /// Run the HTTP server.
 ///
 /// * `server_with_shutdown` is the actual server as a future which can be gracefully shut down.
@@ -25,6 +37,10 @@ use super::{
 /// * `new_subscriber_info_receiver` is used to notify the server of the details of a new client
 ///   having subscribed to the event stream.  It allows the server to populate that client's stream
 ///   with the requested number of historical events.
+/// * `maybe_journal` is the persistent event journal, if enabled, to which every event is appended
+///   and from which historical events no longer buffered are replayed.
+/// * `maybe_rebuilder` is used to rebuild the events of blocks no longer held in the journal.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run(
     config: Config,
     api_version: ProtocolVersion,
@@ -33,66 +49,125 @@ pub(super) async fn run(
     mut data_receiver: mpsc::UnboundedReceiver<(EventIndex, SseData)>,
     broadcaster: broadcast::Sender<BroadcastChannelMessage>,
     mut new_subscriber_info_receiver: mpsc::UnboundedReceiver<NewSubscriberInfo>,
+    maybe_journal: Option<EventJournal>,
+    maybe_rebuilder: Option<BlockEventsRebuilder>,
 ) {
     let server_joiner = task::spawn(server_with_shutdown);
 
+    // The journal's file I/O is blocking, so it runs on its own thread.
+    let (mut maybe_journal_sender, maybe_journal_joiner) =
+        maybe_journal.map(event_journal::spawn).unzip();
+
+    // Subscribers which have been sent events from the journal are passed back via this channel to
+    // be caught up with the buffered events.
+    let (catch_up_sender, mut catch_up_receiver) = mpsc::unbounded_channel();
+
     // Initialize the index and buffer for the SSEs.
     let mut buffer = WheelBuf::new(vec![
         ServerSentEvent::initial_event(api_version);
         config.event_stream_buffer_length as usize
     ]);
 
-    // Start handling received messages from the two channels; info on new client subscribers and
-    // incoming events announced by node components.
+    // Start handling received messages from the three channels; info on new client subscribers,
+    // subscribers which have been sent events from the journal, and incoming events announced by
+    // node components.
     let event_stream_fut = async {
         loop {
             select! {
                 maybe_new_subscriber = new_subscriber_info_receiver.recv() => {
                     if let Some(subscriber) = maybe_new_subscriber {
-                        // First send the client the `ApiVersion` event.  We don't care if this
-                        // errors - the client may have disconnected already.
-                        let _ = subscriber
-                            .initial_events_sender
-                            .send(ServerSentEvent::initial_event(api_version));
-                        // If the client supplied a "start_from" index, provide the buffered events.
-                        // If they requested more than is buffered, just provide the whole buffer.
-                        if let Some(start_index) = subscriber.start_from {
-                            // If the buffer's first event ID is in the range [0, buffer size) or
-                            // (Id::MAX - buffer size, Id::MAX], then the events in the buffer are
-                            // considered to have their IDs wrapping round, or that was recently the
-                            // case.  In this case, we add `buffer.capacity()` to `start_index` and
-                            // the buffered events' IDs when considering which events to include in
-                            // the requested initial events, effectively shifting all the IDs past
-                            // the wrapping transition.
-                            let buffer_size = buffer.capacity() as Id;
-                            let in_wraparound_zone = buffer
-                                .iter()
-                                .next()
-                                .map(|event| {
-                                    let id = event.id.unwrap();
-                                    id > Id::MAX - buffer_size || id < buffer_size
-                                })
-                                .unwrap_or_default();
-                            for event in buffer.iter().skip_while(|event| {
-                                if in_wraparound_zone {
-                                    event.id.unwrap().wrapping_add(buffer_size)
-                                        < start_index.wrapping_add(buffer_size)
-                                } else {
-                                    event.id.unwrap() < start_index
-                                }
-                            }) {
-                                // As per sending `SSE_INITIAL_EVENT`, we don't care if this errors.
-                                let _ = subscriber.initial_events_sender.send(event.clone());
+                        let NewSubscriberInfo {
+                            start_from,
+                            initial_events_sender,
+                            ongoing_events_sender,
+                        } = subscriber;
+                        let initial_event = ServerSentEvent::initial_event(api_version);
+
+                        // If the client supplied a "start_from" index older than any buffered
+                        // event, replay the older events from the journal before catching up with
+                        // the buffered ones.
+                        if let (Some(start_index), Some(journal_sender)) =
+                            (start_from, maybe_journal_sender.as_ref())
+                        {
+                            if precedes_buffer(&buffer, start_index) {
+                                let subscriber = CatchingUpSubscriber {
+                                    next_id: start_index,
+                                    initial_events_sender,
+                                    ongoing_events_sender,
+                                };
+                                tokio::spawn(start_journal_replay(
+                                    initial_event,
+                                    subscriber,
+                                    first_buffered_id(&buffer),
+                                    journal_sender.clone(),
+                                    maybe_rebuilder.clone(),
+                                    catch_up_sender.clone(),
+                                ));
+                                continue;
                             }
                         }
+
+                        // Otherwise provide the buffered events from the requested starting point,
+                        // or the whole buffer if they requested more than is buffered.  The client
+                        // keeps the receiver of ongoing events it subscribed with, so
+                        // `ongoing_events_sender` is dropped.
+                        let buffered_events = start_from
+                            .map(|start_index| buffered_events_from(&buffer, start_index))
+                            .unwrap_or_default();
+                        tokio::spawn(send_events(
+                            iter::once(initial_event).chain(buffered_events),
+                            initial_events_sender,
+                        ));
                     }
                 }
 
+                Some(subscriber) = catch_up_receiver.recv() => {
+                    // Events may have been removed from the buffer while the subscriber was being
+                    // sent the journalled ones, in which case these are replayed from the journal
+                    // first.
+                    if let Some(journal_sender) = maybe_journal_sender.as_ref() {
+                        if precedes_buffer(&buffer, subscriber.next_id) {
+                            tokio::spawn(replay_journal(
+                                subscriber,
+                                first_buffered_id(&buffer),
+                                journal_sender.clone(),
+                                maybe_rebuilder.clone(),
+                                catch_up_sender.clone(),
+                            ));
+                            continue;
+                        }
+                    }
+
+                    // Subscribe on the client's behalf now, so that it receives all events
+                    // following the buffered ones without having lagged while being sent the
+                    // journalled ones.
+                    let CatchingUpSubscriber {
+                        next_id,
+                        initial_events_sender,
+                        ongoing_events_sender,
+                    } = subscriber;
+                    let _ = ongoing_events_sender.send(broadcaster.subscribe());
+                    tokio::spawn(send_events(
+                        buffered_events_from(&buffer, next_id),
+                        initial_events_sender,
+                    ));
+                }
+
                 maybe_data = data_receiver.recv() => {
                     match maybe_data {
                         Some((event_index, data)) => {
                             // Buffer the data and broadcast it to subscribed clients.
                             trace!("Event stream server received {:?}", data);
+                            if let Some(journal_sender) = maybe_journal_sender.as_ref() {
+                                let request = JournalRequest::Append {
+                                    id: event_index,
+                                    data: data.clone(),
+                                };
+                                if journal_sender.send(request).is_err() {
+                                    // The journal has been closed after failing to write.
+                                    maybe_journal_sender = None;
+                                }
+                            }
                             let event = ServerSentEvent { id: Some(event_index), data };
                             buffer.push(event.clone());
                             let message = BroadcastChannelMessage::ServerSentEvent(event);
@@ -119,5 +194,215 @@ pub(super) async fn run(
     let _ = broadcaster.send(BroadcastChannelMessage::Shutdown);
     let _ = server_shutdown_sender.send(());
 
+    // Let the journal finish writing the events it has already been sent.
+    drop(maybe_journal_sender);
+    if let Some(journal_joiner) = maybe_journal_joiner {
+        if let Err(error) = journal_joiner.await {
+            warn!(%error, "failed to join sse journal task");
+        }
+    }
+
     trace!("Event stream server stopped");
 }
+
+/// Returns the ID of the oldest buffered event, if any.
+fn first_buffered_id(buffer: &WheelBuf<Vec<ServerSentEvent>, ServerSentEvent>) -> Option<Id> {
+    buffer.iter().next().map(|event| event.id.unwrap())
+}
+
+/// Returns whether the buffered events are considered to have their IDs wrapping round.
+///
+/// If the buffer's first event ID is in the range [0, buffer size) or (Id::MAX - buffer size,
+/// Id::MAX], then the events in the buffer are considered to have their IDs wrapping round, or
+/// that was recently the case.
+fn in_wraparound_zone(buffer: &WheelBuf<Vec<ServerSentEvent>, ServerSentEvent>) -> bool {
+    let buffer_size = buffer.capacity() as Id;
+    first_buffered_id(buffer)
+        .map(|id| id > Id::MAX - buffer_size || id < buffer_size)
+        .unwrap_or_default()
+}
+
+/// Returns whether `id` precedes the buffered events, i.e. whether older events need to be replayed
+/// from the journal.
+///
+/// If the buffer's first event ID is in the range (Id::MAX - buffer size, Id::MAX], the IDs are
+/// about to wrap round, so a lower `id` may refer to a future event and isn't considered to precede
+/// the buffer.
+fn precedes_buffer(buffer: &WheelBuf<Vec<ServerSentEvent>, ServerSentEvent>, id: Id) -> bool {
+    let buffer_size = buffer.capacity() as Id;
+    first_buffered_id(buffer).map_or(true, |first_id| {
+        first_id <= Id::MAX - buffer_size && id < first_id
+    })
+}
+
+/// Returns the buffered events from `start_index` onwards, or the whole buffer if `start_index`
+/// precedes the buffered events.
+fn buffered_events_from(
+    buffer: &WheelBuf<Vec<ServerSentEvent>, ServerSentEvent>,
+    start_index: Id,
+) -> Vec<ServerSentEvent> {
+    // In the wraparound zone, we add `buffer.capacity()` to `start_index` and the buffered events'
+    // IDs when considering which events to include, effectively shifting all the IDs past the
+    // wrapping transition.
+    let buffer_size = buffer.capacity() as Id;
+    let in_wraparound_zone = in_wraparound_zone(buffer);
+    buffer
+        .iter()
+        .skip_while(|event| {
+            if in_wraparound_zone {
+                event.id.unwrap().wrapping_add(buffer_size) < start_index.wrapping_add(buffer_size)
+            } else {
+                event.id.unwrap() < start_index
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Sends the given events to a subscriber, stopping early if the client disconnects.
+async fn send_events(
+    events: impl IntoIterator<Item = ServerSentEvent>,
+    sender: mpsc::Sender<ServerSentEvent>,
+) {
+    for event in events {
+        // We don't care if sending errors - the client may have disconnected already.
+        if sender.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Sends the initial `ApiVersion` event to a new subscriber, followed by the journalled events as
+/// per `replay_journal`.
+async fn start_journal_replay(
+    initial_event: ServerSentEvent,
+    subscriber: CatchingUpSubscriber,
+    end_before: Option<Id>,
+    journal_sender: mpsc::UnboundedSender<JournalRequest>,
+    maybe_rebuilder: Option<BlockEventsRebuilder>,
+    catch_up_sender: mpsc::UnboundedSender<CatchingUpSubscriber>,
+) {
+    // We don't care if sending errors - the client may have disconnected already.
+    if subscriber
+        .initial_events_sender
+        .send(initial_event)
+        .await
+        .is_ok()
+    {
+        replay_journal(
+            subscriber,
+            end_before,
+            journal_sender,
+            maybe_rebuilder,
+            catch_up_sender,
+        )
+        .await;
+    }
+}
+
+/// Sends the journalled events from `subscriber.next_id` up to but excluding `end_before` (or the
+/// end of the journal if `None`) to the subscriber, then passes it back to the server via
+/// `catch_up_sender` to be caught up with the buffered events.
+///
+/// Events of blocks which are no longer held in the journal are rebuilt via `maybe_rebuilder`; the
+/// `DeployProcessed` events of such a block are assumed to directly follow its `BlockAdded` event,
+/// in the order in which their execution results are held in storage.
+///
+/// Sending stops early if the client disconnects.
+async fn replay_journal(
+    mut subscriber: CatchingUpSubscriber,
+    end_before: Option<Id>,
+    journal_sender: mpsc::UnboundedSender<JournalRequest>,
+    maybe_rebuilder: Option<BlockEventsRebuilder>,
+    catch_up_sender: mpsc::UnboundedSender<CatchingUpSubscriber>,
+) {
+    let (responder, plan_receiver) = oneshot::channel();
+    let request = JournalRequest::ReplayPlan {
+        start_from: subscriber.next_id,
+        end_before,
+        responder,
+    };
+    let maybe_plan = match journal_sender.send(request) {
+        Ok(()) => plan_receiver.await.ok().flatten(),
+        Err(_) => None,
+    };
+
+    match maybe_plan {
+        Some(plan) => {
+            let plan_end = plan.end_before;
+            if !send_journalled_events(plan, maybe_rebuilder, &subscriber.initial_events_sender)
+                .await
+            {
+                return;
+            }
+            subscriber.next_id = plan_end;
+        }
+        None => {
+            // The journal doesn't hold the requested events, so skip them rather than asking for
+            // them again.
+            if let Some(end_before) = end_before {
+                subscriber.next_id = end_before;
+            }
+        }
+    }
+
+    // This can only fail if the server is shutting down.
+    let _ = catch_up_sender.send(subscriber);
+}
+
+/// Sends the events from the journal as per `plan` to a subscriber.
+///
+/// Returns `false` if the client disconnected.
+async fn send_journalled_events(
+    plan: ReplayPlan,
+    maybe_rebuilder: Option<BlockEventsRebuilder>,
+    sender: &mpsc::Sender<ServerSentEvent>,
+) -> bool {
+    if let Some(rebuilder) = maybe_rebuilder {
+        for entry in plan.blocks_to_rebuild {
+            let rebuilt_data = match rebuilder(entry.height).await {
+                Some(rebuilt_data) => rebuilt_data,
+                None => {
+                    debug!(height = %entry.height, "block not available to rebuild sse events");
+                    continue;
+                }
+            };
+            for (offset, data) in rebuilt_data.into_iter().enumerate() {
+                let id = match entry.id.checked_add(offset as Id) {
+                    Some(id) if id >= plan.start_from && id < plan.rebuild_end => id,
+                    _ => continue,
+                };
+                let event = ServerSentEvent { id: Some(id), data };
+                if sender.send(event).await.is_err() {
+                    return false;
+                }
+            }
+        }
+    }
+
+    for path in plan.segments {
+        let (start_from, end_before) = (plan.start_from, plan.end_before);
+        let read_result = task::spawn_blocking(move || {
+            event_journal::read_segment(&path, start_from, end_before)
+        })
+        .await;
+        let events = match read_result {
+            Ok(Ok(events)) => events,
+            Ok(Err(error)) => {
+                // The segment may have been removed by trimming since the plan was made.
+                debug!(%error, "failed to read sse journal segment");
+                continue;
+            }
+            Err(error) => {
+                warn!(%error, "failed to join sse journal reading task");
+                continue;
+            }
+        };
+        for event in events {
+            if sender.send(event).await.is_err() {
+                return false;
+            }
+        }
+    }
+    true
+}
@@ -7,7 +7,7 @@ use std::{
 };
 
 use datasize::DataSize;
-use futures::{future, Stream, StreamExt};
+use futures::{future, stream, Stream, StreamExt};
 use http::StatusCode;
 use hyper::Body;
 #[cfg(test)]
@@ -16,11 +16,9 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{
     broadcast::{self, error::RecvError},
-    mpsc,
-};
-use tokio_stream::wrappers::{
-    errors::BroadcastStreamRecvError, BroadcastStream, UnboundedReceiverStream,
+    mpsc, oneshot,
 };
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, ReceiverStream};
 use tracing::{debug, error, info, warn};
 use warp::{
     addr,
@@ -32,10 +30,12 @@ use warp::{
     Filter, Reply,
 };
 
+use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::testing::TestRng;
 use casper_types::{
-    EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+    account::AccountHash, AsymmetricType, EraId, ExecutionEffect, ExecutionResult, Key,
+    ProtocolVersion, PublicKey, TimeDiff, Timestamp,
 };
 
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
@@ -53,6 +53,12 @@ pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name to filter events by account.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name to filter events by a key in their execution effects.
+pub const KEY_QUERY_FIELD: &str = "key";
+/// The URL query string field name to filter events by deploy hash.
+pub const DEPLOY_QUERY_FIELD: &str = "deploy";
 
 /// The filter associated with `/events/main` path.
 const MAIN_FILTER: [EventFilter; 5] = [
@@ -67,6 +73,9 @@ const DEPLOYS_FILTER: [EventFilter; 1] = [EventFilter::DeployAccepted];
 /// The filter associated with `/events/sigs` path.
 const SIGNATURES_FILTER: [EventFilter; 1] = [EventFilter::FinalitySignature];
 
+/// The capacity of the channel used to send the initial events to each new client's handler.
+const INITIAL_EVENTS_CHANNEL_SIZE: usize = 1000;
+
 /// The "id" field of the events sent on the event stream to clients.
 pub type Id = u32;
 
@@ -259,8 +268,14 @@ pub(super) struct NewSubscriberInfo {
     /// The event ID from which the stream should start for this client.
     pub(super) start_from: Option<Id>,
     /// A channel to send the initial events to the client's handler.  This will always send the
-    /// ApiVersion as the first event, and then any buffered events as indicated by `start_from`.
-    pub(super) initial_events_sender: mpsc::UnboundedSender<ServerSentEvent>,
+    /// ApiVersion as the first event, and then any journalled and buffered events as indicated by
+    /// `start_from`.
+    pub(super) initial_events_sender: mpsc::Sender<ServerSentEvent>,
+    /// A channel to provide the client's handler with a new receiver of ongoing events, replacing
+    /// the one it subscribed with.  This is used once a client which has been replaying events from
+    /// the journal has caught up, since its original receiver may have lagged in the meantime.  If
+    /// dropped instead, the original receiver is used.
+    pub(super) ongoing_events_sender: oneshot::Sender<broadcast::Receiver<BroadcastChannelMessage>>,
 }
 
 /// A filter for event types a client has subscribed to receive.
@@ -275,12 +290,115 @@ pub(super) enum EventFilter {
     Step,
 }
 
+/// A filter on the content of events a client has subscribed to receive, provided via the URL
+/// query string.
+///
+/// Every criterion which is set must be satisfied for an event to be sent to the client.  Events
+/// to which a criterion is not applicable (e.g. a `Fault` when filtering by deploy hash) don't
+/// satisfy it.  `ApiVersion` and `Shutdown` events are never filtered out.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub(super) struct ContentFilter {
+    /// Matches the account of a deploy, the proposer of a block, the validator in a fault and the
+    /// signer of a finality signature.
+    account: Option<AccountHash>,
+    /// The formatted string of the key, matching the keys of the transforms in an execution
+    /// effect.
+    key: Option<String>,
+    /// Matches a deploy by its hash, and a block by the hashes of the deploys it includes.
+    deploy: Option<DeployHash>,
+}
+
+impl ContentFilter {
+    /// Returns `true` if `data` satisfies all criteria of this filter.
+    pub(super) fn matches(&self, data: &SseData) -> bool {
+        if matches!(data, SseData::ApiVersion(_) | SseData::Shutdown) {
+            return true;
+        }
+        self.matches_account(data) && self.matches_key(data) && self.matches_deploy(data)
+    }
+
+    fn matches_account(&self, data: &SseData) -> bool {
+        let account = match &self.account {
+            Some(account) => *account,
+            None => return true,
+        };
+        let public_key = match data {
+            SseData::BlockAdded { block, .. } => block.proposer(),
+            SseData::DeployAccepted { deploy } => deploy.header().account(),
+            SseData::DeployProcessed { account, .. } => account,
+            SseData::Fault { public_key, .. } => public_key,
+            SseData::FinalitySignature(finality_signature) => &finality_signature.public_key,
+            SseData::ApiVersion(_)
+            | SseData::DeployExpired { .. }
+            | SseData::Step { .. }
+            | SseData::Shutdown => return false,
+        };
+        public_key.to_account_hash() == account
+    }
+
+    fn matches_key(&self, data: &SseData) -> bool {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return true,
+        };
+        let execution_effect = match data {
+            SseData::DeployProcessed {
+                execution_result, ..
+            } => match execution_result.as_ref() {
+                ExecutionResult::Success { effect, .. }
+                | ExecutionResult::Failure { effect, .. } => effect,
+            },
+            SseData::Step {
+                execution_effect, ..
+            } => execution_effect,
+            SseData::ApiVersion(_)
+            | SseData::BlockAdded { .. }
+            | SseData::DeployAccepted { .. }
+            | SseData::DeployExpired { .. }
+            | SseData::Fault { .. }
+            | SseData::FinalitySignature(_)
+            | SseData::Shutdown => return false,
+        };
+        execution_effect
+            .transforms
+            .iter()
+            .any(|transform_entry| transform_entry.key == *key)
+    }
+
+    fn matches_deploy(&self, data: &SseData) -> bool {
+        let deploy_hash = match &self.deploy {
+            Some(deploy_hash) => deploy_hash,
+            None => return true,
+        };
+        match data {
+            SseData::BlockAdded { block, .. } => {
+                block.deploy_hashes().contains(deploy_hash)
+                    || block.transfer_hashes().contains(deploy_hash)
+            }
+            SseData::DeployAccepted { deploy } => deploy.hash() == deploy_hash,
+            SseData::DeployProcessed {
+                deploy_hash: processed_deploy_hash,
+                ..
+            } => **processed_deploy_hash == *deploy_hash,
+            SseData::DeployExpired {
+                deploy_hash: expired_deploy_hash,
+            } => expired_deploy_hash == deploy_hash,
+            SseData::ApiVersion(_)
+            | SseData::Fault { .. }
+            | SseData::FinalitySignature(_)
+            | SseData::Step { .. }
+            | SseData::Shutdown => false,
+        }
+    }
+}
+
 /// Filters the `event`, mapping it to a warp event, or `None` if it should be filtered out.
 async fn filter_map_server_sent_event(
     event: &ServerSentEvent,
     event_filter: &[EventFilter],
+    content_filter: &ContentFilter,
 ) -> Option<Result<WarpServerSentEvent, RecvError>> {
-    if !event.data.should_include(event_filter) {
+    if !event.data.should_include(event_filter) || !content_filter.matches(&event.data) {
         return None;
     }
 
@@ -345,26 +463,44 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the starting event ID and the content filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
-    }
-
-    if query.len() > 1 {
-        return Err(create_422());
+/// The starting event ID is `None` if "start_from" is not provided, and the content filter has no
+/// criteria set if none of "account", "key" or "deploy" are provided.
+///
+/// Returns a 422 response if `query` has any other entries, or if any value cannot be parsed.
+fn parse_query(query: HashMap<String, String>) -> Result<(Option<Id>, ContentFilter), Response> {
+    let mut start_from = None;
+    let mut content_filter = ContentFilter::default();
+    for (field, value) in query {
+        match field.as_str() {
+            QUERY_FIELD => {
+                start_from = Some(value.parse::<Id>().map_err(|_| create_422())?);
+            }
+            ACCOUNT_QUERY_FIELD => {
+                content_filter.account = Some(parse_account(&value).ok_or_else(create_422)?);
+            }
+            KEY_QUERY_FIELD => {
+                let key = Key::from_formatted_str(&value).map_err(|_| create_422())?;
+                content_filter.key = Some(key.to_formatted_string());
+            }
+            DEPLOY_QUERY_FIELD => {
+                let digest = Digest::from_hex(&value).map_err(|_| create_422())?;
+                content_filter.deploy = Some(DeployHash::new(digest));
+            }
+            _ => return Err(create_422()),
+        }
     }
+    Ok((start_from, content_filter))
+}
 
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
-    }
+/// Parses an account hash from either its formatted string or the hex-encoded public key.
+fn parse_account(value: &str) -> Option<AccountHash> {
+    AccountHash::from_formatted_str(value).ok().or_else(|| {
+        PublicKey::from_hex(value)
+            .ok()
+            .map(|key| key.to_account_hash())
+    })
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -384,8 +520,9 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected any of '{}=<EVENT ID>', '{}=<ACCOUNT HASH OR PUBLIC KEY>', \
+        '{}=<KEY>' or '{}=<DEPLOY HASH>'\n",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, KEY_QUERY_FIELD, DEPLOY_QUERY_FIELD
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -441,19 +578,24 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
-            let start_from = match parse_query(query) {
-                Ok(maybe_id) => maybe_id,
+            let (start_from, content_filter) = match parse_query(query) {
+                Ok(parsed_query) => parsed_query,
                 Err(error_response) => return error_response,
             };
 
             // Create a channel for the client's handler to receive the stream of initial events.
-            let (initial_events_sender, initial_events_receiver) = mpsc::unbounded_channel();
+            let (initial_events_sender, initial_events_receiver) =
+                mpsc::channel(INITIAL_EVENTS_CHANNEL_SIZE);
+
+            // Create a channel for the server to replace the client's receiver of ongoing events.
+            let (ongoing_events_sender, ongoing_events_replacement) = oneshot::channel();
 
-            // Supply the server with the sender part of the channel along with the client's
+            // Supply the server with the sender parts of the channels along with the client's
             // requested starting point.
             let new_subscriber_info = NewSubscriberInfo {
                 start_from,
                 initial_events_sender,
+                ongoing_events_sender,
             };
             if new_subscriber_info_sender
                 .send(new_subscriber_info)
@@ -468,7 +610,9 @@ impl ChannelsAndFilter {
             sse::reply(sse::keep_alive().stream(stream_to_client(
                 initial_events_receiver,
                 ongoing_events_receiver,
+                ongoing_events_replacement,
                 event_filter,
+                content_filter,
                 remote_address,
             )))
             .into_response()
@@ -502,23 +646,34 @@ impl ChannelsAndFilter {
 /// The ongoing events channel (a broadcast receiver) is then consumed, and will remain in use until
 /// either the client disconnects, or the server shuts down (indicated by sending a `Shutdown`
 /// variant via the channel).  This channel will receive all SSEs created from the moment the client
-/// subscribed to the server's event stream.
+/// subscribed to the server's event stream.  If the server provides a replacement receiver via
+/// `ongoing_events_replacement`, that is consumed instead.
 ///
-/// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
+/// It also takes an `EventFilter` and a `ContentFilter` which cause events to which the client
+/// didn't subscribe to be skipped.
 fn stream_to_client(
-    initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
+    initial_events: mpsc::Receiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
+    ongoing_events_replacement: oneshot::Receiver<broadcast::Receiver<BroadcastChannelMessage>>,
     event_filter: &'static [EventFilter],
+    content_filter: ContentFilter,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
+    let content_filter = Arc::new(content_filter);
+
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
     let initial_stream_ids = Arc::new(RwLock::new(HashSet::new()));
     let cloned_initial_ids = Arc::clone(&initial_stream_ids);
 
+    // The replacement receiver, if any, is only provided once the initial stream has been sent, so
+    // don't wait for it until then.
+    let ongoing_events =
+        stream::once(async move { ongoing_events_replacement.await.unwrap_or(ongoing_events) });
+
     // Map the events arriving after the initial stream to the correct error type, filtering out any
     // that have already been sent in the initial stream.
-    let ongoing_stream = BroadcastStream::new(ongoing_events)
+    let ongoing_stream = ongoing_events
+        .flat_map(BroadcastStream::new)
         .filter_map(move |result| {
             let cloned_initial_ids = Arc::clone(&cloned_initial_ids);
             let remote_address = remote_address.clone();
@@ -549,7 +704,7 @@ fn stream_to_client(
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
     // `event_filter`.
-    UnboundedReceiverStream::new(initial_events)
+    ReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
                 let _ = initial_stream_ids.write().unwrap().insert(id);
@@ -557,10 +712,15 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
-        .filter_map(move |result| async move {
-            match result {
-                Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
-                Err(error) => Some(Err(error)),
+        .filter_map(move |result| {
+            let content_filter = Arc::clone(&content_filter);
+            async move {
+                match result {
+                    Ok(event) => {
+                        filter_map_server_sent_event(&event, event_filter, &content_filter).await
+                    }
+                    Err(error) => Some(Err(error)),
+                }
             }
         })
 }
@@ -569,14 +729,16 @@ fn stream_to_client(
 mod tests {
     use std::iter;
 
-    use casper_types::testing::TestRng;
+    use casper_types::{testing::TestRng, Transform, TransformEntry};
 
     use super::*;
     use crate::logging;
 
     async fn should_filter_out(event: &ServerSentEvent, filter: &'static [EventFilter]) {
         assert!(
-            filter_map_server_sent_event(event, filter).await.is_none(),
+            filter_map_server_sent_event(event, filter, &ContentFilter::default())
+                .await
+                .is_none(),
             "should filter out {:?} with {:?}",
             event,
             filter
@@ -585,7 +747,9 @@ mod tests {
 
     async fn should_not_filter_out(event: &ServerSentEvent, filter: &'static [EventFilter]) {
         assert!(
-            filter_map_server_sent_event(event, filter).await.is_some(),
+            filter_map_server_sent_event(event, filter, &ContentFilter::default())
+                .await
+                .is_some(),
             "should not filter out {:?} with {:?}",
             event,
             filter
@@ -742,6 +906,113 @@ mod tests {
         }
     }
 
+    /// This test checks that events are filtered by the content criteria provided via the query
+    /// string.
+    #[test]
+    fn should_filter_events_by_content() {
+        fn content_filter(fields: &[(&str, String)]) -> ContentFilter {
+            let query = fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), value.clone()))
+                .collect();
+            let (start_from, content_filter) = parse_query(query).unwrap();
+            assert!(start_from.is_none());
+            content_filter
+        }
+
+        let mut rng = crate::new_rng();
+
+        let deploy = Deploy::random(&mut rng);
+        let account = deploy.header().account().to_account_hash();
+        let key = Key::Hash(rng.gen());
+        let mut execution_result: ExecutionResult = rng.gen();
+        match &mut execution_result {
+            ExecutionResult::Success { effect, .. } | ExecutionResult::Failure { effect, .. } => {
+                effect.transforms.push(TransformEntry {
+                    key: key.to_formatted_string(),
+                    transform: Transform::Identity,
+                })
+            }
+        }
+        let deploy_processed = SseData::DeployProcessed {
+            deploy_hash: Box::new(*deploy.hash()),
+            account: Box::new(deploy.header().account().clone()),
+            timestamp: deploy.header().timestamp(),
+            ttl: deploy.header().ttl(),
+            dependencies: deploy.header().dependencies().clone(),
+            block_hash: Box::new(BlockHash::random(&mut rng)),
+            execution_result: Box::new(execution_result),
+        };
+        let deploy_accepted = SseData::DeployAccepted {
+            deploy: Arc::new(deploy.clone()),
+        };
+        let api_version = SseData::random_api_version(&mut rng);
+        let other_deploy_processed = SseData::random_deploy_processed(&mut rng);
+        let fault = SseData::random_fault(&mut rng);
+
+        // Filtering by account should only include events related to that account.
+        let by_account = content_filter(&[(ACCOUNT_QUERY_FIELD, account.to_formatted_string())]);
+        assert!(by_account.matches(&api_version));
+        assert!(by_account.matches(&deploy_processed));
+        assert!(by_account.matches(&deploy_accepted));
+        assert!(!by_account.matches(&other_deploy_processed));
+        assert!(!by_account.matches(&fault));
+
+        // The account can also be provided as a hex-encoded public key.
+        let by_public_key =
+            content_filter(&[(ACCOUNT_QUERY_FIELD, deploy.header().account().to_hex())]);
+        assert_eq!(by_public_key, by_account);
+
+        // Filtering by key should only include events whose execution effects include that key.
+        let by_key = content_filter(&[(KEY_QUERY_FIELD, key.to_formatted_string())]);
+        assert!(by_key.matches(&api_version));
+        assert!(by_key.matches(&deploy_processed));
+        assert!(!by_key.matches(&deploy_accepted));
+        assert!(!by_key.matches(&other_deploy_processed));
+        assert!(!by_key.matches(&fault));
+
+        // Filtering by deploy hash should only include events related to that deploy.
+        let by_deploy = content_filter(&[(
+            DEPLOY_QUERY_FIELD,
+            base16::encode_lower(deploy.hash().inner()),
+        )]);
+        assert!(by_deploy.matches(&api_version));
+        assert!(by_deploy.matches(&deploy_processed));
+        assert!(by_deploy.matches(&deploy_accepted));
+        assert!(!by_deploy.matches(&other_deploy_processed));
+        assert!(!by_deploy.matches(&fault));
+
+        // All criteria provided must be satisfied.
+        let by_account_and_other_deploy = content_filter(&[
+            (ACCOUNT_QUERY_FIELD, account.to_formatted_string()),
+            (
+                DEPLOY_QUERY_FIELD,
+                base16::encode_lower(DeployHash::random(&mut rng).inner()),
+            ),
+        ]);
+        assert!(!by_account_and_other_deploy.matches(&deploy_processed));
+        assert!(!by_account_and_other_deploy.matches(&deploy_accepted));
+
+        // No criteria should include everything.
+        let unfiltered = content_filter(&[]);
+        assert!(unfiltered.matches(&other_deploy_processed));
+        assert!(unfiltered.matches(&fault));
+    }
+
+    /// This test checks that invalid content criteria in the query string are rejected.
+    #[test]
+    fn should_fail_to_parse_invalid_content_filter() {
+        for (field, value) in [
+            (ACCOUNT_QUERY_FIELD, "not-an-account"),
+            (KEY_QUERY_FIELD, "not-a-key"),
+            (DEPLOY_QUERY_FIELD, "not-a-deploy-hash"),
+        ] {
+            let query = iter::once((field.to_string(), value.to_string())).collect();
+            let response = parse_query(query).unwrap_err();
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
     async fn should_filter_duplicate_events(path_filter: &str) {
         // Returns `count` random SSE events, all of a single variant defined by `path_filter`.  The
         // events will have sequential IDs starting from `start_id`, and if the path filter
@@ -833,13 +1104,14 @@ mod tests {
                 &mut deploys,
             );
 
-            let (initial_events_sender, initial_events_receiver) = mpsc::unbounded_channel();
+            let (initial_events_sender, initial_events_receiver) =
+                mpsc::channel(NUM_INITIAL_EVENTS + 1);
             let (ongoing_events_sender, ongoing_events_receiver) =
                 broadcast::channel(NUM_INITIAL_EVENTS + NUM_ONGOING_EVENTS + 1);
 
             // Send all the events.
             for event in initial_events.iter().cloned() {
-                initial_events_sender.send(event).unwrap();
+                initial_events_sender.try_send(event).unwrap();
             }
             for event in ongoing_events.iter().cloned() {
                 let _ = ongoing_events_sender
@@ -851,10 +1123,13 @@ mod tests {
             drop(ongoing_events_sender);
 
             // Collect the events emitted by `stream_to_client()` - should not contain duplicates.
+            let (_, ongoing_events_replacement) = oneshot::channel();
             let received_events: Vec<Result<WarpServerSentEvent, RecvError>> = stream_to_client(
                 initial_events_receiver,
                 ongoing_events_receiver,
+                ongoing_events_replacement,
                 get_filter(path_filter).unwrap(),
+                ContentFilter::default(),
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
@@ -920,4 +1195,67 @@ mod tests {
     async fn should_filter_duplicate_signature_events() {
         should_filter_duplicate_events(SSE_API_SIGNATURES_PATH).await
     }
+
+    /// This test checks that once the initial stream has been sent, a replacement receiver of the
+    /// ongoing events is used in place of the original one, even if the original has lagged.
+    #[tokio::test]
+    async fn should_use_replacement_ongoing_events_receiver() {
+        let mut rng = TestRng::new();
+        let event = |rng: &mut TestRng, id| ServerSentEvent {
+            id: Some(id),
+            data: SseData::random_block_added(rng),
+        };
+
+        let (initial_events_sender, initial_events_receiver) = mpsc::channel(2);
+        initial_events_sender
+            .try_send(ServerSentEvent::initial_event(ProtocolVersion::V1_0_0))
+            .unwrap();
+        initial_events_sender.try_send(event(&mut rng, 0)).unwrap();
+        drop(initial_events_sender);
+
+        // Overflow the original receiver so that it would lag if it were used.
+        let (original_sender, original_receiver) = broadcast::channel(1);
+        for id in 1..4 {
+            let _ = original_sender
+                .send(BroadcastChannelMessage::ServerSentEvent(event(
+                    &mut rng, id,
+                )))
+                .unwrap();
+        }
+
+        let (replacement_sender, replacement_receiver) = broadcast::channel(2);
+        let ongoing_events: Vec<ServerSentEvent> = (1..3).map(|id| event(&mut rng, id)).collect();
+        for event in ongoing_events.iter().cloned() {
+            let _ = replacement_sender
+                .send(BroadcastChannelMessage::ServerSentEvent(event))
+                .unwrap();
+        }
+        drop(replacement_sender);
+        let (ongoing_events_sender, ongoing_events_replacement) = oneshot::channel();
+        ongoing_events_sender.send(replacement_receiver).unwrap();
+
+        let received_events: Vec<Result<WarpServerSentEvent, RecvError>> = stream_to_client(
+            initial_events_receiver,
+            original_receiver,
+            ongoing_events_replacement,
+            get_filter(SSE_API_MAIN_PATH).unwrap(),
+            ContentFilter::default(),
+            "127.0.0.1:3456".to_string(),
+        )
+        .collect()
+        .await;
+
+        let received_ids: Vec<String> = received_events
+            .into_iter()
+            .map(|result| {
+                let event = result.expect("should not lag");
+                event
+                    .to_string()
+                    .lines()
+                    .find_map(|line| line.strip_prefix("id:").map(str::to_string))
+                    .unwrap_or_default()
+            })
+            .collect();
+        assert_eq!(received_ids, vec!["", "0", "1", "2"]);
+    }
 }
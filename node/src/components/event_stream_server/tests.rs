@@ -27,9 +27,9 @@ use casper_types::testing::TestRng;
 use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
-    SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    DeployAccepted, Id, ACCOUNT_QUERY_FIELD, DEPLOY_QUERY_FIELD, KEY_QUERY_FIELD, QUERY_FIELD,
+    SSE_API_DEPLOYS_PATH as DEPLOYS_PATH, SSE_API_MAIN_PATH as MAIN_PATH,
+    SSE_API_ROOT_PATH as ROOT_PATH, SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
@@ -82,6 +82,8 @@ struct ServerBehavior {
     /// If `Some`, sets the `max_concurrent_subscribers` server config value, otherwise uses the
     /// config default.
     max_concurrent_subscribers: Option<u32>,
+    /// Whether the server should write events to the persistent event journal.
+    enable_event_journal: bool,
     clients: Vec<ClientSyncBehavior>,
 }
 
@@ -94,6 +96,7 @@ impl ServerBehavior {
             has_delay_between_events: true,
             repeat_events: false,
             max_concurrent_subscribers: None,
+            enable_event_journal: false,
             clients: Vec::new(),
         }
     }
@@ -107,6 +110,7 @@ impl ServerBehavior {
             has_delay_between_events: false,
             repeat_events: true,
             max_concurrent_subscribers: None,
+            enable_event_journal: false,
             clients: Vec::new(),
         }
     }
@@ -124,6 +128,11 @@ impl ServerBehavior {
         self.max_concurrent_subscribers = Some(count);
     }
 
+    /// Enables the server's persistent event journal.
+    fn enable_event_journal(&mut self) {
+        self.enable_event_journal = true;
+    }
+
     /// Waits for all clients which specified they wanted to join just before the given event ID.
     async fn wait_for_clients(&self, id: Id) {
         for client_behavior in &self.clients {
@@ -262,6 +271,7 @@ impl TestFixture {
             max_concurrent_subscribers: server_behavior
                 .max_concurrent_subscribers
                 .unwrap_or(Config::default().max_concurrent_subscribers),
+            enable_event_journal: server_behavior.enable_event_journal,
             ..Default::default()
         };
         let mut server = EventStreamServer::new(
@@ -269,7 +279,7 @@ impl TestFixture {
             self.storage_dir.path().to_path_buf(),
             self.protocol_version,
         );
-        server.listen().unwrap();
+        server.listen(None).unwrap();
         assert!(server.sse_server.is_some());
 
         self.first_event_id = server
@@ -700,6 +710,49 @@ async fn should_serve_remaining_signature_events_with_query() {
     should_serve_remaining_events_with_query(SIGS_PATH).await;
 }
 
+/// Client setup:
+///   * `<IP:port>/events/<path>?start_from=0`
+///   * connected just before event ID 75
+///   * server has the event journal enabled
+///
+/// Expected to receive all main, deploy-accepted or signature events (depending on `path`), as
+/// events 0 to 24 should be replayed from the journal, followed by the buffered events and those
+/// emitted during the replay.
+async fn should_serve_journalled_events_with_query(path: &str) {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    let connect_at_event_id = BUFFER_LENGTH * 3 / 2;
+    let start_from_event_id = 0;
+
+    let mut server_behavior = ServerBehavior::new();
+    server_behavior.enable_event_journal();
+    let barrier = server_behavior.add_client_sync_before_event(connect_at_event_id);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    let url = url(server_address, path, Some(start_from_event_id));
+    let (expected_events, final_id) = fixture.all_filtered_events(path);
+    let received_events = subscribe(&url, barrier, final_id, "client").await.unwrap();
+    fixture.stop_server().await;
+
+    assert_eq!(received_events, expected_events);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_journalled_main_events_with_query() {
+    should_serve_journalled_events_with_query(MAIN_PATH).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_journalled_deploy_accepted_events_with_query() {
+    should_serve_journalled_events_with_query(DEPLOYS_PATH).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_journalled_signature_events_with_query() {
+    should_serve_journalled_events_with_query(SIGS_PATH).await;
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=25`
 ///   * connected before first event
@@ -955,11 +1008,15 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=not-an-account", main_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=not-a-key", main_url, KEY_QUERY_FIELD),
+        format!("{}?{}=not-a-deploy-hash", deploys_url, DEPLOY_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected any of '{}=<EVENT ID>', '{}=<ACCOUNT HASH OR PUBLIC KEY>', \
+        '{}=<KEY>' or '{}=<DEPLOY HASH>'",
+        QUERY_FIELD, ACCOUNT_QUERY_FIELD, KEY_QUERY_FIELD, DEPLOY_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
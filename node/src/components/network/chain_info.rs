@@ -9,11 +9,7 @@ use casper_hashing::Digest;
 use casper_types::ProtocolVersion;
 use datasize::DataSize;
 
-use super::{
-    counting_format::ConnectionId,
-    message::{ConsensusCertificate, NodeKeyPair},
-    Message,
-};
+use super::{message::ConsensusCertificate, Message};
 use crate::types::Chainspec;
 
 /// Data retained from the chainspec by the networking component.
@@ -49,16 +45,14 @@ impl ChainInfo {
     pub(super) fn create_handshake<P>(
         &self,
         public_addr: SocketAddr,
-        consensus_keys: Option<&NodeKeyPair>,
-        connection_id: ConnectionId,
+        consensus_certificate: Option<ConsensusCertificate>,
         is_syncing: bool,
     ) -> Message<P> {
         Message::Handshake {
             network_name: self.network_name.clone(),
             public_addr,
             protocol_version: self.protocol_version,
-            consensus_certificate: consensus_keys
-                .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
+            consensus_certificate,
             is_syncing,
             chainspec_hash: Some(self.chainspec_hash),
         }
@@ -11,16 +11,20 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
 };
 use strum::EnumDiscriminants;
+use tracing::warn;
 
 use casper_hashing::Digest;
+use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, Signature};
 #[cfg(test)]
-use casper_types::testing::TestRng;
-use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+use casper_types::{testing::TestRng, SecretKey};
 
 use super::{counting_format::ConnectionId, health::Nonce, BincodeFormat};
+#[cfg(test)]
+use crate::signer::LocalSigner;
 use crate::{
     effect::EffectBuilder,
     protocol,
+    signer::{self, Signer, SigningRequest},
     types::{Chainspec, NodeId},
     utils::{
         opt_display::OptDisplay,
@@ -136,24 +140,26 @@ impl<P: Payload> Message<P> {
     }
 }
 
-/// A pair of secret keys used by consensus.
+/// The signer holding the secret key used by consensus.
 pub(super) struct NodeKeyPair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    signer: Arc<dyn Signer>,
 }
 
 impl NodeKeyPair {
     /// Creates a new key pair for consensus signing.
-    pub(super) fn new(key_pair: (Arc<SecretKey>, PublicKey)) -> Self {
-        Self {
-            secret_key: key_pair.0,
-            public_key: key_pair.1,
-        }
+    pub(super) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
+    }
+
+    /// Sign a value using this keypair, on a blocking thread if the signer may block.
+    async fn sign<T: AsRef<[u8]>>(&self, value: T) -> Result<Signature, signer::Error> {
+        let request = SigningRequest::new(signer::MessageKind::Other, value);
+        signer::sign_blocking(Arc::clone(&self.signer), request).await
     }
 
-    /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
+    /// Returns the public key of this keypair.
+    fn public_key(&self) -> &PublicKey {
+        self.signer.public_key()
     }
 }
 
@@ -170,11 +176,21 @@ pub(crate) struct ConsensusCertificate {
 
 impl ConsensusCertificate {
     /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &NodeKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
-            public_key: key_pair.public_key.clone(),
-            signature,
+    ///
+    /// Returns `None` if signing the connection ID fails.
+    pub(super) async fn create(
+        connection_id: ConnectionId,
+        key_pair: &NodeKeyPair,
+    ) -> Option<Self> {
+        match key_pair.sign(connection_id.as_bytes()).await {
+            Ok(signature) => Some(ConsensusCertificate {
+                public_key: key_pair.public_key().clone(),
+                signature,
+            }),
+            Err(error) => {
+                warn!(%error, "failed to sign consensus certificate");
+                None
+            }
         }
     }
 
@@ -188,11 +204,12 @@ impl ConsensusCertificate {
     #[cfg(test)]
     fn random(rng: &mut TestRng) -> Self {
         let secret_key = SecretKey::random(rng);
-        let public_key = PublicKey::from(&secret_key);
-        ConsensusCertificate::create(
+        let signer = LocalSigner::new(Arc::new(secret_key));
+        futures::executor::block_on(ConsensusCertificate::create(
             ConnectionId::random(rng),
-            &NodeKeyPair::new((Arc::new(secret_key), public_key)),
-        )
+            &NodeKeyPair::new(Arc::new(signer)),
+        ))
+        .expect("should sign with local key")
     }
 }
 
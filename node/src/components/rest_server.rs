@@ -357,7 +357,7 @@ mod schema_tests {
     use crate::{
         rpcs::{
             docs::OpenRpcSchema,
-            info::{GetChainspecResult, GetValidatorChangesResult},
+            info::{GetChainspecResult, GetValidatorChangesResult, GetValidatorPerformanceResult},
         },
         testing::assert_schema,
         types::GetStatusResult,
@@ -388,6 +388,18 @@ mod schema_tests {
         );
     }
 
+    #[test]
+    fn schema_validator_performance() {
+        let schema_path = format!(
+            "{}/../resources/test/rest_schema_validator_performance.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        assert_schema(
+            schema_path,
+            serde_json::to_string_pretty(&schema_for!(GetValidatorPerformanceResult)).unwrap(),
+        );
+    }
+
     #[test]
     fn schema_rpc_schema() {
         let schema_path = format!(
@@ -16,7 +16,7 @@ use super::ReactorEventT;
 use crate::{
     effect::{requests::RestRequest, EffectBuilder},
     reactor::QueueKind,
-    rpcs::info::{GetChainspecResult, GetValidatorChangesResult},
+    rpcs::info::{GetChainspecResult, GetValidatorChangesResult, GetValidatorPerformanceResult},
     types::GetStatusResult,
 };
 
@@ -32,6 +32,9 @@ pub const JSON_RPC_SCHEMA_API_PATH: &str = "rpc-schema";
 /// The validator information URL path.
 pub const VALIDATOR_CHANGES_API_PATH: &str = "validator-changes";
 
+/// The validator performance URL path.
+pub const VALIDATOR_PERFORMANCE_API_PATH: &str = "validator-performance";
+
 /// The chainspec file URL path.
 pub const CHAINSPEC_API_PATH: &str = "chainspec";
 
@@ -118,6 +121,23 @@ pub(super) fn create_validator_changes_filter<REv: ReactorEventT>(
         .boxed()
 }
 
+pub(super) fn create_validator_performance_filter<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+) -> BoxedFilter<(Response<Body>,)> {
+    warp::get()
+        .and(warp::path(VALIDATOR_PERFORMANCE_API_PATH))
+        .and_then(move || {
+            effect_builder
+                .get_consensus_validator_performance()
+                .map(move |performance| {
+                    let result = GetValidatorPerformanceResult::new(api_version, performance);
+                    Ok::<_, Rejection>(reply::json(&result).into_response())
+                })
+        })
+        .boxed()
+}
+
 pub(super) fn create_chainspec_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
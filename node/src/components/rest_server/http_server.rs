@@ -29,6 +29,8 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rest_open_rpc = filters::create_rpc_schema_filter(effect_builder);
     let rest_validator_changes =
         filters::create_validator_changes_filter(effect_builder, api_version);
+    let rest_validator_performance =
+        filters::create_validator_performance_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
 
     let service = warp::service(
@@ -36,6 +38,7 @@ pub(super) async fn run<REv: ReactorEventT>(
             .or(rest_metrics)
             .or(rest_open_rpc)
             .or(rest_validator_changes)
+            .or(rest_validator_performance)
             .or(rest_chainspec_filter),
     );
 
@@ -78,6 +81,8 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
     let rest_open_rpc = filters::create_rpc_schema_filter(effect_builder);
     let rest_validator_changes =
         filters::create_validator_changes_filter(effect_builder, api_version);
+    let rest_validator_performance =
+        filters::create_validator_performance_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
 
     let service = warp::service(
@@ -85,6 +90,7 @@ pub(super) async fn run_with_cors<REv: ReactorEventT>(
             .or(rest_metrics)
             .or(rest_open_rpc)
             .or(rest_validator_changes)
+            .or(rest_validator_performance)
             .or(rest_chainspec_filter)
             .with(match cors_origin {
                 CorsOrigin::Any => warp::cors().allow_any_origin(),
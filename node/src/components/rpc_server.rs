@@ -17,11 +17,13 @@ mod http_server;
 pub mod rpcs;
 mod speculative_exec_config;
 mod speculative_exec_server;
+mod ws_server;
 
 use std::{fmt::Debug, time::Instant};
 
 use datasize::DataSize;
 use futures::join;
+use tokio::sync::broadcast;
 use tracing::{error, info, warn};
 
 use casper_execution_engine::core::engine_state::{
@@ -31,11 +33,12 @@ use casper_execution_engine::core::engine_state::{
 use casper_hashing::Digest;
 use casper_types::{system::auction::EraValidators, Key, ProtocolVersion, URef};
 
+use self::rpcs::RequestLimiter;
 use super::Component;
 use crate::{
     components::{
-        contract_runtime::EraValidatorsRequest, ComponentState, InitializedComponent,
-        PortBoundComponent,
+        contract_runtime::EraValidatorsRequest, event_stream_server::SseData, ComponentState,
+        InitializedComponent, PortBoundComponent,
     },
     effect::{
         requests::{
@@ -112,6 +115,9 @@ pub(crate) struct RpcServer {
     /// no fields and no methods because all that is needed to operate it is the
     /// spawned tokio task, so a unit struct will suffice here.
     speculative_exec: Option<()>,
+    /// The source of event-stream data for subscriptions made via the WebSocket endpoint.
+    #[data_size(skip)]
+    event_broadcaster: broadcast::Sender<SseData>,
 }
 
 impl RpcServer {
@@ -121,6 +127,7 @@ impl RpcServer {
         api_version: ProtocolVersion,
         network_name: String,
         node_startup_instant: Instant,
+        event_broadcaster: broadcast::Sender<SseData>,
     ) -> Self {
         RpcServer {
             state: ComponentState::Uninitialized,
@@ -130,6 +137,7 @@ impl RpcServer {
             network_name,
             node_startup_instant,
             speculative_exec: None,
+            event_broadcaster,
         }
     }
 }
@@ -451,8 +459,9 @@ where
                 builder,
                 effect_builder,
                 self.api_version,
-                cfg.qps_limit,
+                RequestLimiter::new(cfg.qps_limit, cfg.max_request_delay.into()),
                 cfg.max_body_bytes,
+                cfg.max_batch_size,
                 cfg.cors_origin.clone(),
             ));
             Some(())
@@ -462,13 +471,17 @@ where
 
         let cfg = &self.config;
         let builder = utils::start_listening(&cfg.address)?;
+        let maybe_event_broadcaster = cfg.enable_websocket.then(|| self.event_broadcaster.clone());
         tokio::spawn(http_server::run(
             builder,
             effect_builder,
             self.api_version,
-            cfg.qps_limit,
+            RequestLimiter::new(cfg.qps_limit, cfg.max_request_delay.into()),
             cfg.max_body_bytes,
+            cfg.max_batch_size,
             cfg.cors_origin.clone(),
+            maybe_event_broadcaster,
+            cfg.max_websocket_subscriptions,
         ));
 
         Ok(Effects::new())
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::TimeDiff;
+
 /// Default binding address for the JSON-RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -10,8 +12,14 @@ const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default max body bytes.  This is 2.5MB which should be able to accommodate the largest valid
 /// JSON-RPC request, which would be an "account_put_deploy".
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default maximum number of requests in a single JSON-RPC batch.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
+/// Default maximum time a request is delayed by the rate limit before being rejected.
+const DEFAULT_MAX_REQUEST_DELAY_SECS: u32 = 10;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
+/// Default maximum number of concurrent event subscriptions per WebSocket connection.
+const DEFAULT_MAX_WEBSOCKET_SUBSCRIPTIONS: u32 = 10;
 
 /// JSON-RPC HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -26,8 +34,21 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of requests to accept in a single JSON-RPC batch.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: u32,
+    /// Maximum time a request is delayed by the rate limit.  Requests which would be delayed for
+    /// longer are rejected.
+    #[serde(default = "default_max_request_delay")]
+    pub max_request_delay: TimeDiff,
     /// CORS origin.
     pub cors_origin: String,
+    /// Setting to enable the WebSocket endpoint of the JSON-RPC server.
+    #[serde(default)]
+    pub enable_websocket: bool,
+    /// Maximum number of concurrent event subscriptions per WebSocket connection.
+    #[serde(default = "default_max_websocket_subscriptions")]
+    pub max_websocket_subscriptions: u32,
 }
 
 impl Config {
@@ -38,11 +59,30 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_request_delay: default_max_request_delay(),
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
+            enable_websocket: false,
+            max_websocket_subscriptions: DEFAULT_MAX_WEBSOCKET_SUBSCRIPTIONS,
         }
     }
 }
 
+/// The default for `Config::max_batch_size`.
+fn default_max_batch_size() -> u32 {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
+/// The default for `Config::max_request_delay`.
+fn default_max_request_delay() -> TimeDiff {
+    TimeDiff::from_seconds(DEFAULT_MAX_REQUEST_DELAY_SECS)
+}
+
+/// The default for `Config::max_websocket_subscriptions`.
+fn default_max_websocket_subscriptions() -> u32 {
+    DEFAULT_MAX_WEBSOCKET_SUBSCRIPTIONS
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config::new()
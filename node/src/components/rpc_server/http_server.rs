@@ -1,4 +1,5 @@
 use hyper::server::{conn::AddrIncoming, Builder};
+use tokio::sync::broadcast;
 
 use casper_json_rpc::{CorsOrigin, RequestHandlersBuilder};
 use casper_types::ProtocolVersion;
@@ -10,16 +11,20 @@ use super::{
             GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
         },
         docs::ListRpcs,
-        info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+        info::{
+            GetAccountDeploys, GetChainspec, GetContractCalls, GetDeploy, GetPeers, GetStatus,
+            GetValidatorChanges, GetValidatorPerformance,
+        },
         state::{
-            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem,
+            GetKeysByPrefix, GetStateDiff, GetTrie, QueryBalance, QueryGlobalState,
         },
-        RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
+        RequestLimiter, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
+    ws_server::{self, WsContext},
     ReactorEventT,
 };
-use crate::effect::EffectBuilder;
+use crate::{components::event_stream_server::SseData, effect::EffectBuilder};
 
 /// The URL path for all JSON-RPC requests.
 pub const RPC_API_PATH: &str = "rpc";
@@ -27,46 +32,135 @@ pub const RPC_API_PATH: &str = "rpc";
 pub const RPC_API_SERVER_NAME: &str = "JSON RPC";
 
 /// Run the JSON-RPC server.
+///
+/// If `maybe_event_broadcaster` is `Some`, the server also accepts WebSocket connections on
+/// `ws_server::RPC_WS_API_PATH`.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
-    qps_limit: u64,
+    request_limiter: RequestLimiter,
     max_body_bytes: u32,
+    max_batch_size: u32,
     cors_origin: String,
+    maybe_event_broadcaster: Option<broadcast::Sender<SseData>>,
+    max_websocket_subscriptions: u32,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraSummary::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
-    ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
+    PutDeploy::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetBlock::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetBlockTransfers::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetStateRootHash::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetItem::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    QueryGlobalState::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetBalance::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetAccountInfo::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetDeploy::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetPeers::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetStatus::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetEraInfoBySwitchBlock::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetEraSummary::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetAuctionInfo::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetTrie::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetValidatorChanges::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetValidatorPerformance::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    ListRpcs::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetDictionaryItem::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetChainspec::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    QueryBalance::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetAccountDeploys::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetContractCalls::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    GetStateDiff::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
+    GetKeysByPrefix::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
     let handlers = handlers.build();
 
+    let maybe_ws_route = maybe_event_broadcaster.map(|event_broadcaster| {
+        ws_server::route(WsContext {
+            handlers: handlers.clone(),
+            event_broadcaster,
+            api_version,
+            max_message_bytes: max_body_bytes,
+            max_batch_size,
+            max_subscriptions: max_websocket_subscriptions,
+        })
+    });
+
     match cors_origin.as_str() {
         "" => {
             super::rpcs::run(
                 builder,
                 handlers,
-                qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
+                maybe_ws_route,
             )
             .await
         }
@@ -74,10 +168,11 @@ pub(super) async fn run<REv: ReactorEventT>(
             super::rpcs::run_with_cors(
                 builder,
                 handlers,
-                qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
+                maybe_ws_route,
                 CorsOrigin::Any,
             )
             .await
@@ -86,10 +181,11 @@ pub(super) async fn run<REv: ReactorEventT>(
             super::rpcs::run_with_cors(
                 builder,
                 handlers,
-                qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
+                maybe_ws_route,
                 CorsOrigin::Specified(cors_origin),
             )
             .await
@@ -11,7 +11,11 @@ pub mod info;
 pub mod speculative_exec;
 pub mod state;
 
-use std::{str, sync::Arc, time::Duration};
+use std::{
+    str,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use http::header::ACCEPT_ENCODING;
@@ -20,9 +24,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::oneshot;
-use tower::ServiceBuilder;
 use tracing::info;
-use warp::Filter;
+use warp::{filters::BoxedFilter, reject::Rejection, reply::Response, Filter, Reply};
 
 use casper_json_rpc::{
     CorsOrigin, Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode,
@@ -39,7 +42,107 @@ pub use error_code::ErrorCode;
 /// standard 'id', 'jsonrpc', 'method', and 'params' fields.
 ///
 /// It will be changed to `false` for casper-node v2.0.0.
-const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
+pub(super) const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
+
+/// Limits the rate at which individual JSON-RPC requests are handled.
+///
+/// Every element of a batch request counts as a separate request.  Requests exceeding the limit
+/// are delayed rather than rejected, and a burst of up to `qps_limit` requests is allowed after a
+/// period of inactivity.  Requests which would be delayed by more than `max_delay` are rejected.
+#[derive(Clone, Debug)]
+pub(super) struct RequestLimiter {
+    /// The minimum interval between consecutive requests once the burst allowance is used up.
+    interval: Duration,
+    /// The maximum time a request is delayed before it is rejected instead.
+    max_delay: Duration,
+    /// The time slot allocated to the most recent request plus `interval`.
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+impl RequestLimiter {
+    /// Returns a new limiter allowing `qps_limit` requests per second, each delayed by at most
+    /// `max_delay`.
+    pub(super) fn new(qps_limit: u64, max_delay: Duration) -> Self {
+        let qps_limit = u32::try_from(qps_limit).unwrap_or(u32::MAX).max(1);
+        let now = Instant::now();
+        RequestLimiter {
+            interval: Duration::from_secs(1) / qps_limit,
+            max_delay,
+            next_slot: Arc::new(Mutex::new(
+                now.checked_sub(Duration::from_secs(1)).unwrap_or(now),
+            )),
+        }
+    }
+
+    fn lock_next_slot(&self) -> MutexGuard<'_, Instant> {
+        match self.next_slot.lock() {
+            Ok(next_slot) => next_slot,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Waits until the next request can be handled without exceeding the limit.
+    ///
+    /// Returns an error without waiting if the request would be delayed by more than `max_delay`.
+    /// If the returned future is dropped before completing, the time slot reserved for the request
+    /// is released.
+    async fn acquire(&self) -> Result<(), Error> {
+        let (delay, delay_slot) = {
+            let now = Instant::now();
+            let mut next_slot = self.lock_next_slot();
+            // Slots older than one second are unused burst allowance.
+            let earliest_slot = now.checked_sub(Duration::from_secs(1)).unwrap_or(now);
+            let slot = (*next_slot).max(earliest_slot);
+            let delay = slot.saturating_duration_since(now);
+            if delay > self.max_delay {
+                return Err(Error::new(
+                    ErrorCode::RateLimited,
+                    format!(
+                        "request would be delayed by {}ms, exceeding the limit of {}ms",
+                        delay.as_millis(),
+                        self.max_delay.as_millis()
+                    ),
+                ));
+            }
+            *next_slot = slot + self.interval;
+            (delay, slot)
+        };
+        if !delay.is_zero() {
+            let mut reservation = SlotReservation {
+                request_limiter: self,
+                slot: delay_slot,
+                release_on_drop: true,
+            };
+            tokio::time::sleep(delay).await;
+            reservation.release_on_drop = false;
+        }
+        Ok(())
+    }
+}
+
+/// A time slot reserved by a request waiting on the `RequestLimiter`.
+///
+/// If dropped while still waiting, the slot is handed back so that it doesn't count against the
+/// limit, provided no later slot has been reserved since.  Otherwise the slot is left as a gap, as
+/// releasing it would let a later request share the slot of a request still waiting.
+struct SlotReservation<'a> {
+    request_limiter: &'a RequestLimiter,
+    /// The reserved time slot.
+    slot: Instant,
+    release_on_drop: bool,
+}
+
+impl<'a> Drop for SlotReservation<'a> {
+    fn drop(&mut self) {
+        if !self.release_on_drop {
+            return;
+        }
+        let mut next_slot = self.request_limiter.lock_next_slot();
+        if *next_slot == self.slot + self.request_limiter.interval {
+            *next_slot = self.slot;
+        }
+    }
+}
 
 /// A JSON-RPC requiring the "params" field to be present.
 #[async_trait]
@@ -85,14 +188,23 @@ pub(super) trait RpcWithParams {
 
     /// Registers this RPC as the handler for JSON-RPC requests whose "method" field is the same as
     /// `Self::METHOD`.
+    ///
+    /// Each request handled waits on `request_limiter` first, and is rejected if it would have to
+    /// wait for too long.
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        request_limiter: &RequestLimiter,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let request_limiter = request_limiter.clone();
+        let handler = move |maybe_params| {
+            let request_limiter = request_limiter.clone();
+            async move {
+                request_limiter.acquire().await?;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -144,14 +256,23 @@ pub(super) trait RpcWithoutParams {
 
     /// Registers this RPC as the handler for JSON-RPC requests whose "method" field is the same as
     /// `Self::METHOD`.
+    ///
+    /// Each request handled waits on `request_limiter` first, and is rejected if it would have to
+    /// wait for too long.
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        request_limiter: &RequestLimiter,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            Self::check_no_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version).await
+        let request_limiter = request_limiter.clone();
+        let handler = move |maybe_params| {
+            let request_limiter = request_limiter.clone();
+            async move {
+                request_limiter.acquire().await?;
+                Self::check_no_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -224,14 +345,23 @@ pub(super) trait RpcWithOptionalParams {
 
     /// Registers this RPC as the handler for JSON-RPC requests whose "method" field is the same as
     /// `Self::METHOD`.
+    ///
+    /// Each request handled waits on `request_limiter` first, and is rejected if it would have to
+    /// wait for too long.
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        request_limiter: &RequestLimiter,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let request_limiter = request_limiter.clone();
+        let handler = move |maybe_params| {
+            let request_limiter = request_limiter.clone();
+            async move {
+                request_limiter.acquire().await?;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -254,22 +384,41 @@ pub(super) trait RpcWithOptionalParams {
     ) -> Result<Self::ResponseResult, Error>;
 }
 
+/// Combines the optional WebSocket route with the HTTP JSON-RPC routes.
+///
+/// The WebSocket route is tried first, since the JSON-RPC routes recover from all rejections.
+fn with_ws_route<T: Reply + 'static>(
+    maybe_ws_route: Option<BoxedFilter<(Response,)>>,
+    routes: impl Filter<Extract = (T,), Error = Rejection> + Clone + Send + Sync + 'static,
+) -> BoxedFilter<(Response,)> {
+    let routes = routes.map(Reply::into_response).boxed();
+    match maybe_ws_route {
+        Some(ws_route) => ws_route.or(routes).unify().boxed(),
+        None => routes,
+    }
+}
+
 /// Start JSON RPC server with CORS enabled in a background.
+///
+/// Requests are rate limited by the `RequestLimiter` with which their handlers were registered.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run_with_cors(
     builder: Builder<AddrIncoming>,
     handlers: RequestHandlers,
-    qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
     api_path: &'static str,
     server_name: &'static str,
+    maybe_ws_route: Option<BoxedFilter<(Response,)>>,
     cors_header: CorsOrigin,
 ) {
     let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route_with_cors(
+        let service_routes = casper_json_rpc::route_with_cors_and_batch_size(
             api_path,
             max_body_bytes,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            max_batch_size as usize,
             &cors_header,
         );
 
@@ -279,14 +428,13 @@ pub(super) async fn run_with_cors(
             .and(service_routes.clone())
             .with(warp::compression::gzip());
 
-        let service = warp::service(service_routes_gzip.or(service_routes));
+        let service = warp::service(with_ws_route(
+            maybe_ws_route.clone(),
+            service_routes_gzip.or(service_routes),
+        ));
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
-    let make_svc = ServiceBuilder::new()
-        .rate_limit(qps_limit, Duration::from_secs(1))
-        .service(make_svc);
-
     let server = builder.serve(make_svc);
     info!(address = %server.local_addr(), "started {} server", server_name);
 
@@ -301,20 +449,24 @@ pub(super) async fn run_with_cors(
 }
 
 /// Start JSON RPC server in a background.
+///
+/// Requests are rate limited by the `RequestLimiter` with which their handlers were registered.
 pub(super) async fn run(
     builder: Builder<AddrIncoming>,
     handlers: RequestHandlers,
-    qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
     api_path: &'static str,
     server_name: &'static str,
+    maybe_ws_route: Option<BoxedFilter<(Response,)>>,
 ) {
     let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route(
+        let service_routes = casper_json_rpc::route_with_batch_size(
             api_path,
             max_body_bytes,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            max_batch_size as usize,
         );
 
         // Supports content negotiation for gzip responses. This is an interim fix until
@@ -323,14 +475,13 @@ pub(super) async fn run(
             .and(service_routes.clone())
             .with(warp::compression::gzip());
 
-        let service = warp::service(service_routes_gzip.or(service_routes));
+        let service = warp::service(with_ws_route(
+            maybe_ws_route.clone(),
+            service_routes_gzip.or(service_routes),
+        ));
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
-    let make_svc = ServiceBuilder::new()
-        .rate_limit(qps_limit, Duration::from_secs(1))
-        .service(make_svc);
-
     let server = builder.serve(make_svc);
     info!(address = %server.local_addr(), "started {} server", server_name);
 
@@ -381,6 +532,91 @@ mod tests {
         serde_json::from_slice(&body_bytes).unwrap()
     }
 
+    #[tokio::test]
+    async fn request_limiter_should_delay_requests_once_burst_is_used_up() {
+        let qps_limit = 10;
+        let request_limiter = RequestLimiter::new(qps_limit, Duration::from_secs(10));
+
+        // The burst allowance covers a full second's worth of requests.
+        for _ in 0..qps_limit {
+            request_limiter.acquire().await.unwrap();
+        }
+
+        // Once used up, requests are spaced out by a tenth of a second.
+        request_limiter.acquire().await.unwrap();
+        let start = Instant::now();
+        request_limiter.acquire().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn request_limiter_should_reject_requests_delayed_for_too_long() {
+        let qps_limit = 10;
+        let request_limiter = RequestLimiter::new(qps_limit, Duration::from_millis(150));
+
+        for _ in 0..qps_limit {
+            request_limiter.acquire().await.unwrap();
+        }
+
+        // The next request is handled straight away, the one after that waits for 100ms.
+        request_limiter.acquire().await.unwrap();
+        let pending = request_limiter.acquire();
+        futures::pin_mut!(pending);
+        assert!(futures::poll!(pending.as_mut()).is_pending());
+
+        // The next would have to wait for 200ms.
+        let error = request_limiter.acquire().await.unwrap_err();
+        assert_eq!(
+            serde_json::to_value(error).unwrap()["code"],
+            ErrorCode::RateLimited as i64
+        );
+    }
+
+    #[tokio::test]
+    async fn request_limiter_should_release_slots_of_dropped_requests() {
+        let qps_limit = 10;
+        let request_limiter = RequestLimiter::new(qps_limit, Duration::from_millis(150));
+
+        for _ in 0..qps_limit {
+            request_limiter.acquire().await.unwrap();
+        }
+
+        // Requests which are cancelled while waiting don't use up capacity.
+        request_limiter.acquire().await.unwrap();
+        for _ in 0..10 {
+            let pending = request_limiter.acquire();
+            futures::pin_mut!(pending);
+            let _ = futures::poll!(pending.as_mut());
+        }
+        request_limiter.acquire().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn request_limiter_should_not_release_slots_followed_by_later_reservations() {
+        let qps_limit = 10;
+        let request_limiter = RequestLimiter::new(qps_limit, Duration::from_millis(250));
+
+        for _ in 0..qps_limit {
+            request_limiter.acquire().await.unwrap();
+        }
+
+        // The next request is handled straight away, the ones after that wait for 100ms and 200ms.
+        request_limiter.acquire().await.unwrap();
+        let mut first_pending = Box::pin(request_limiter.acquire());
+        let mut second_pending = Box::pin(request_limiter.acquire());
+        assert!(futures::poll!(first_pending.as_mut()).is_pending());
+        assert!(futures::poll!(second_pending.as_mut()).is_pending());
+
+        // Dropping the first waiting request leaves a gap rather than handing its slot to the next
+        // request, which would then share a slot with the second waiting request.
+        drop(first_pending);
+        let error = request_limiter.acquire().await.unwrap_err();
+        assert_eq!(
+            serde_json::to_value(error).unwrap()["code"],
+            ErrorCode::RateLimited as i64
+        );
+    }
+
     mod rpc_with_params {
         use super::*;
         use crate::components::rpc_server::rpcs::info::{
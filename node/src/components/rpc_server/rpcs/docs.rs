@@ -20,10 +20,13 @@ use super::{
     chain::{
         GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
     },
-    info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+    info::{
+        GetAccountDeploys, GetChainspec, GetContractCalls, GetDeploy, GetPeers, GetStatus,
+        GetValidatorChanges, GetValidatorPerformance,
+    },
     state::{
-        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetKeysByPrefix,
+        GetStateDiff, QueryBalance, QueryGlobalState,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -78,10 +81,28 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_params::<QueryBalance>(
         "query for a balance using a purse identifier and a state identifier",
     );
+    schema.push_with_params::<GetAccountDeploys>(
+        "returns a page of the deploys sent by an account, if the node indexes deploy history",
+    );
+    schema.push_with_params::<GetContractCalls>(
+        "returns a page of the deploys which called a contract by hash, if the node indexes \
+        deploy history",
+    );
+    schema.push_with_params::<GetStateDiff>(
+        "returns a page of the keys whose values differ between the global state under two state \
+        root hashes",
+    );
+    schema.push_with_params::<GetKeysByPrefix>(
+        "returns a page of the keys in global state with a given prefix, e.g. all keys of a given \
+        type",
+    );
     schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
         .push_without_params::<GetValidatorChanges>("returns status changes of active validators");
+    schema.push_without_params::<GetValidatorPerformance>(
+        "returns the participation and proposals of validators in the current and recent eras",
+    );
     schema.push_without_params::<GetChainspec>(
         "returns the raw bytes of the chainspec.toml, genesis accounts.toml, and \
         global_state.toml files",
@@ -573,4 +594,12 @@ mod tests {
         let incorrect_optional_params = check_optional_params_fields::<GetAuctionInfo>();
         assert!(incorrect_optional_params.is_empty())
     }
+
+    #[test]
+    fn check_state_get_auction_info_example_params() {
+        // Params which aren't set should be omitted from the example rather than `null`.
+        let example = Example::from_rpc_with_optional_params::<GetAuctionInfo>();
+        assert_eq!(example.params.len(), 1);
+        assert_eq!(example.params[0].name, "block_identifier");
+    }
 }
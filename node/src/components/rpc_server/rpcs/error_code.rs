@@ -35,6 +35,16 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The global state for the requested block is not available on this node.
+    StateNotAvailable = -32013,
+    /// The requested subscription was not found.
+    NoSuchSubscription = -32014,
+    /// The maximum number of subscriptions for this connection has been reached.
+    TooManySubscriptions = -32015,
+    /// The deploy history index is not enabled on this node.
+    DeployHistoryNotIndexed = -32016,
+    /// The request would be delayed for too long by the rate limit.
+    RateLimited = -32017,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,6 +69,11 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::StateNotAvailable => (error_code as i64, "State not available"),
+            ErrorCode::NoSuchSubscription => (error_code as i64, "No such subscription"),
+            ErrorCode::TooManySubscriptions => (error_code as i64, "Too many subscriptions"),
+            ErrorCode::DeployHistoryNotIndexed => (error_code as i64, "Deploy history not indexed"),
+            ErrorCode::RateLimited => (error_code as i64, "Rate limited"),
         }
     }
 }
@@ -11,14 +11,21 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_types::{
+    ContractHash, ContractPackageHash, EraId, ExecutionResult, ProtocolVersion, PublicKey,
+    Timestamp,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
+    state::AccountIdentifier,
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithoutParams,
 };
 use crate::{
-    components::consensus::ValidatorChange,
+    components::{
+        consensus::{ValidatorChange, ValidatorParticipation, ValidatorPerformance},
+        storage::{DeployHistoryCursor, DeployHistoryEntry, DeployHistoryKey, DeployHistoryPage},
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
@@ -27,6 +34,10 @@ use crate::{
     },
 };
 
+/// The maximum number of entries returned in a single page by "info_get_account_deploys" and
+/// "info_get_contract_calls".
+pub const MAX_DEPLOY_HISTORY_PAGE_SIZE: u32 = 100;
+
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
     deploy_hash: *Deploy::doc_example().hash(),
     finalized_approvals: true,
@@ -53,6 +64,49 @@ static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new
         changes,
     }
 });
+static GET_VALIDATOR_PERFORMANCE_RESULT: Lazy<GetValidatorPerformanceResult> = Lazy::new(|| {
+    let performance = ValidatorPerformance {
+        participation: ValidatorParticipation::Active,
+        last_seen: Some(*Timestamp::doc_example()),
+        proposals_made: 9,
+        proposals_missed: 1,
+        round_exponent: Some(4),
+    };
+    let public_key = PublicKey::doc_example().clone();
+    let validators = vec![JsonValidatorPerformance::new(public_key, performance)];
+    GetValidatorPerformanceResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        eras: vec![JsonEraValidatorPerformance::new(EraId::new(1), validators)],
+    }
+});
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        account_identifier: AccountIdentifier::PublicKey(PublicKey::doc_example().clone()),
+        page_start: None,
+        page_size: Some(MAX_DEPLOY_HISTORY_PAGE_SIZE),
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![DeployHistoryEntry {
+            deploy_hash: *Deploy::doc_example().hash(),
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().height(),
+        }],
+        next_page_start: None,
+    });
+static GET_CONTRACT_CALLS_PARAMS: Lazy<GetContractCallsParams> =
+    Lazy::new(|| GetContractCallsParams {
+        contract_identifier: ContractCallsIdentifier::ContractHash(ContractHash::new([42; 32])),
+        page_start: None,
+        page_size: Some(MAX_DEPLOY_HISTORY_PAGE_SIZE),
+    });
+static GET_CONTRACT_CALLS_RESULT: Lazy<GetContractCallsResult> =
+    Lazy::new(|| GetContractCallsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: GET_ACCOUNT_DEPLOYS_RESULT.deploys.clone(),
+        next_page_start: None,
+    });
 static GET_CHAINSPEC_RESULT: Lazy<GetChainspecResult> = Lazy::new(|| GetChainspecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     chainspec_bytes: ChainspecRawBytes::new(vec![42, 42].into(), None, None),
@@ -348,6 +402,97 @@ impl RpcWithoutParams for GetValidatorChanges {
     }
 }
 
+/// A validator's performance in a particular era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonValidatorPerformance {
+    /// The public key of the validator.
+    public_key: PublicKey,
+    /// The validator's performance.
+    performance: ValidatorPerformance,
+}
+
+impl JsonValidatorPerformance {
+    pub(crate) fn new(public_key: PublicKey, performance: ValidatorPerformance) -> Self {
+        JsonValidatorPerformance {
+            public_key,
+            performance,
+        }
+    }
+}
+
+/// The performance of all validators in the given era.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonEraValidatorPerformance {
+    /// The era.
+    era_id: EraId,
+    /// The performance of each validator in the era.
+    validators: Vec<JsonValidatorPerformance>,
+}
+
+impl JsonEraValidatorPerformance {
+    pub(crate) fn new(era_id: EraId, validators: Vec<JsonValidatorPerformance>) -> Self {
+        JsonEraValidatorPerformance { era_id, validators }
+    }
+}
+
+/// Result for the "info_get_validator_performance" RPC.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetValidatorPerformanceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The validators' performance in the current and recent eras.
+    pub eras: Vec<JsonEraValidatorPerformance>,
+}
+
+impl GetValidatorPerformanceResult {
+    pub(crate) fn new(
+        api_version: ProtocolVersion,
+        performance: BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>>,
+    ) -> Self {
+        let eras = performance
+            .into_iter()
+            .map(|(era_id, validators)| {
+                let validators = validators
+                    .into_iter()
+                    .map(|(public_key, performance)| {
+                        JsonValidatorPerformance::new(public_key, performance)
+                    })
+                    .collect();
+                JsonEraValidatorPerformance::new(era_id, validators)
+            })
+            .collect();
+        GetValidatorPerformanceResult { api_version, eras }
+    }
+}
+
+impl DocExample for GetValidatorPerformanceResult {
+    fn doc_example() -> &'static Self {
+        &GET_VALIDATOR_PERFORMANCE_RESULT
+    }
+}
+
+/// "info_get_validator_performance" RPC.
+pub struct GetValidatorPerformance {}
+
+#[async_trait]
+impl RpcWithoutParams for GetValidatorPerformance {
+    const METHOD: &'static str = "info_get_validator_performance";
+    type ResponseResult = GetValidatorPerformanceResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+    ) -> Result<Self::ResponseResult, Error> {
+        let performance = effect_builder.get_consensus_validator_performance().await;
+        let result = Self::ResponseResult::new(api_version, performance);
+        Ok(result)
+    }
+}
+
 /// Result for the "info_get_chainspec" RPC.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct GetChainspecResult {
@@ -390,3 +535,184 @@ impl RpcWithoutParams for GetChainspec {
         Ok(result)
     }
 }
+
+/// Params for "info_get_account_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The account whose sent deploys are to be returned.
+    pub account_identifier: AccountIdentifier,
+    /// The entry from which to start the page, as given by the previous page's
+    /// `next_page_start`.  If omitted, the page starts from the oldest deploy.
+    pub page_start: Option<DeployHistoryCursor>,
+    /// The maximum number of deploys to return, capped at `MAX_DEPLOY_HISTORY_PAGE_SIZE`.  If
+    /// omitted, `MAX_DEPLOY_HISTORY_PAGE_SIZE` is used.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "info_get_account_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys sent by the account, ordered by the height of the block in which they were
+    /// executed, then by deploy hash.
+    pub deploys: Vec<DeployHistoryEntry>,
+    /// The start of the next page, if there are further deploys.
+    pub next_page_start: Option<DeployHistoryCursor>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_account_deploys" RPC.
+pub struct GetAccountDeploys {}
+
+#[async_trait]
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "info_get_account_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let account_hash = match params.account_identifier {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => account_hash,
+        };
+        let page = get_deploy_history(
+            effect_builder,
+            DeployHistoryKey::Account(account_hash),
+            params.page_start,
+            params.page_size,
+        )
+        .await?;
+        Ok(Self::ResponseResult {
+            api_version,
+            deploys: page.entries,
+            next_page_start: page.next_page_start,
+        })
+    }
+}
+
+/// Identifier of a contract whose calls are to be returned.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields, untagged)]
+pub enum ContractCallsIdentifier {
+    /// The hash of a contract, matching deploys which call it via `StoredContractByHash`.
+    ContractHash(ContractHash),
+    /// The hash of a contract package, matching deploys which call any of its versions via
+    /// `StoredVersionedContractByHash`.
+    ContractPackageHash(ContractPackageHash),
+}
+
+/// Params for "info_get_contract_calls" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetContractCallsParams {
+    /// The contract whose calls are to be returned.
+    pub contract_identifier: ContractCallsIdentifier,
+    /// The entry from which to start the page, as given by the previous page's
+    /// `next_page_start`.  If omitted, the page starts from the oldest deploy.
+    pub page_start: Option<DeployHistoryCursor>,
+    /// The maximum number of deploys to return, capped at `MAX_DEPLOY_HISTORY_PAGE_SIZE`.  If
+    /// omitted, `MAX_DEPLOY_HISTORY_PAGE_SIZE` is used.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetContractCallsParams {
+    fn doc_example() -> &'static Self {
+        &GET_CONTRACT_CALLS_PARAMS
+    }
+}
+
+/// Result for "info_get_contract_calls" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetContractCallsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys which called the contract, ordered by the height of the block in which they
+    /// were executed, then by deploy hash.
+    pub deploys: Vec<DeployHistoryEntry>,
+    /// The start of the next page, if there are further deploys.
+    pub next_page_start: Option<DeployHistoryCursor>,
+}
+
+impl DocExample for GetContractCallsResult {
+    fn doc_example() -> &'static Self {
+        &GET_CONTRACT_CALLS_RESULT
+    }
+}
+
+/// "info_get_contract_calls" RPC.
+pub struct GetContractCalls {}
+
+#[async_trait]
+impl RpcWithParams for GetContractCalls {
+    const METHOD: &'static str = "info_get_contract_calls";
+    type RequestParams = GetContractCallsParams;
+    type ResponseResult = GetContractCallsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let history_key = match params.contract_identifier {
+            ContractCallsIdentifier::ContractHash(contract_hash) => {
+                DeployHistoryKey::Contract(contract_hash)
+            }
+            ContractCallsIdentifier::ContractPackageHash(contract_package_hash) => {
+                DeployHistoryKey::ContractPackage(contract_package_hash)
+            }
+        };
+        let page = get_deploy_history(
+            effect_builder,
+            history_key,
+            params.page_start,
+            params.page_size,
+        )
+        .await?;
+        Ok(Self::ResponseResult {
+            api_version,
+            deploys: page.entries,
+            next_page_start: page.next_page_start,
+        })
+    }
+}
+
+/// Retrieves a page of the given deploy history from storage.
+async fn get_deploy_history<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    history_key: DeployHistoryKey,
+    page_start: Option<DeployHistoryCursor>,
+    page_size: Option<u32>,
+) -> Result<DeployHistoryPage, Error> {
+    let page_size = page_size
+        .unwrap_or(MAX_DEPLOY_HISTORY_PAGE_SIZE)
+        .min(MAX_DEPLOY_HISTORY_PAGE_SIZE);
+    effect_builder
+        .get_deploy_history_from_storage(history_key, page_start, page_size as usize)
+        .await
+        .ok_or_else(|| {
+            let message = "the deploy history index is not enabled on this node".to_string();
+            info!("{}", message);
+            Error::new(ErrorCode::DeployHistoryNotIndexed, message)
+        })
+}
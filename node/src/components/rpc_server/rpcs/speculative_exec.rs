@@ -10,9 +10,9 @@ use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use casper_execution_engine::core::engine_state::Error as EngineStateError;
+use casper_execution_engine::core::engine_state::{Error as EngineStateError, ExecutionPhaseCosts};
 use casper_json_rpc::ReservedErrorCode;
-use casper_types::{ExecutionResult, ProtocolVersion};
+use casper_types::{ExecutionResult, ProtocolVersion, Timestamp, U512};
 
 use super::{
     chain::BlockIdentifier,
@@ -23,17 +23,59 @@ use super::{
 use crate::{
     components::contract_runtime::SpeculativeExecutionState,
     effect::EffectBuilder,
-    types::{Block, BlockHash, Deploy},
+    types::{
+        chainspec::DeployConfig, json_compatibility::ExecutionTrace, Block, BlockHash, Chainspec,
+        Deploy, DeployConfigurationFailure, DeployHash,
+    },
 };
 
+/// The maximum number of deploys which can be executed in a single "speculative_exec_batch"
+/// request.
+pub const MAX_SPECULATIVE_EXEC_BATCH_SIZE: usize = 10;
+
+/// The margin, as a percentage of the gas consumed, by which the payment recommended by an
+/// "estimate_gas" request exceeds the cost of the execution, to allow for changes to the global
+/// state before the deploy is executed.
+pub const ESTIMATE_GAS_MARGIN_PERCENT: u64 = 20;
+
 static SPECULATIVE_EXEC_PARAMS: Lazy<SpeculativeExecParams> = Lazy::new(|| SpeculativeExecParams {
     block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
     deploy: Deploy::doc_example().clone(),
+    trace: false,
 });
 static SPECULATIVE_EXEC_RESULT: Lazy<SpeculativeExecResult> = Lazy::new(|| SpeculativeExecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     block_hash: *Block::doc_example().hash(),
     execution_result: ExecutionResult::example().clone(),
+    trace: None,
+});
+static SPECULATIVE_EXEC_BATCH_PARAMS: Lazy<SpeculativeExecBatchParams> =
+    Lazy::new(|| SpeculativeExecBatchParams {
+        block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        deploys: vec![Deploy::doc_example().clone()],
+    });
+static SPECULATIVE_EXEC_BATCH_RESULT: Lazy<SpeculativeExecBatchResult> =
+    Lazy::new(|| SpeculativeExecBatchResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *Block::doc_example().hash(),
+        execution_results: vec![SpeculativeDeployExecutionResult {
+            deploy_hash: *Deploy::doc_example().hash(),
+            execution_result: ExecutionResult::example().clone(),
+            payment_cost: U512::from(123_456),
+            session_cost: U512::from(333_000),
+        }],
+    });
+static ESTIMATE_GAS_PARAMS: Lazy<EstimateGasParams> = Lazy::new(|| EstimateGasParams {
+    block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+    deploy: Deploy::doc_example().clone(),
+});
+static ESTIMATE_GAS_RESULT: Lazy<EstimateGasResult> = Lazy::new(|| EstimateGasResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    block_hash: *Block::doc_example().hash(),
+    payment_cost: U512::from(123_456),
+    session_cost: U512::from(333_000),
+    recommended_payment: Some(U512::from(547_748)),
+    execution_result: ExecutionResult::example().clone(),
 });
 
 /// Params for "speculative_exec" RPC request.
@@ -44,6 +86,9 @@ pub struct SpeculativeExecParams {
     pub block_identifier: Option<BlockIdentifier>,
     /// Deploy to execute.
     pub deploy: Deploy,
+    /// Whether to return a trace of the host functions invoked during execution.
+    #[serde(default)]
+    pub trace: bool,
 }
 
 impl DocExample for SpeculativeExecParams {
@@ -63,6 +108,9 @@ pub struct SpeculativeExecResult {
     pub block_hash: BlockHash,
     /// Result of the execution.
     pub execution_result: ExecutionResult,
+    /// Trace of the host functions invoked during execution, if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ExecutionTrace>,
 }
 
 impl DocExample for SpeculativeExecResult {
@@ -71,6 +119,103 @@ impl DocExample for SpeculativeExecResult {
     }
 }
 
+/// Params for "speculative_exec_batch" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecBatchParams {
+    /// Block hash on top of which to execute the deploys.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// Deploys to execute, in order.  Each deploy is executed on top of the effects of the
+    /// preceding ones.
+    pub deploys: Vec<Deploy>,
+}
+
+impl DocExample for SpeculativeExecBatchParams {
+    fn doc_example() -> &'static Self {
+        &SPECULATIVE_EXEC_BATCH_PARAMS
+    }
+}
+
+/// The result of executing a single deploy of a "speculative_exec_batch" RPC request.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeDeployExecutionResult {
+    /// Hash of the executed deploy.
+    pub deploy_hash: DeployHash,
+    /// Result of the execution, including the transforms it made to global state.
+    pub execution_result: ExecutionResult,
+    /// Gas consumed by the payment code, or charged for a failure prior to running session code.
+    pub payment_cost: U512,
+    /// Gas consumed by the session code.
+    pub session_cost: U512,
+}
+
+/// Result for "speculative_exec_batch" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecBatchResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the deploys were executed.
+    pub block_hash: BlockHash,
+    /// Results of the executions, in the order of the deploys in the request.
+    pub execution_results: Vec<SpeculativeDeployExecutionResult>,
+}
+
+impl DocExample for SpeculativeExecBatchResult {
+    fn doc_example() -> &'static Self {
+        &SPECULATIVE_EXEC_BATCH_RESULT
+    }
+}
+
+/// Params for "estimate_gas" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EstimateGasParams {
+    /// Block hash on top of which to execute the deploy.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// Deploy for which to estimate the gas.  It need not be signed, and its payment is replaced by
+    /// a standard payment of the maximum amount allowed by the chainspec, limited by the node's
+    /// `max_gas_estimate` setting.  Otherwise, it must comply with the chainspec.
+    pub deploy: Deploy,
+}
+
+impl DocExample for EstimateGasParams {
+    fn doc_example() -> &'static Self {
+        &ESTIMATE_GAS_PARAMS
+    }
+}
+
+/// Result for "estimate_gas" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EstimateGasResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// Gas consumed by the standard payment code, or charged for a failure prior to running
+    /// session code.
+    pub payment_cost: U512,
+    /// Gas consumed by the session code.
+    pub session_cost: U512,
+    /// The standard payment amount in motes sufficient to cover the execution of the deploy at its
+    /// gas price, including a margin of `ESTIMATE_GAS_MARGIN_PERCENT` percent of the gas consumed.
+    /// Absent if the execution failed, in which case no payment amount is known to be sufficient.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended_payment: Option<U512>,
+    /// Result of the execution.
+    pub execution_result: ExecutionResult,
+}
+
+impl DocExample for EstimateGasResult {
+    fn doc_example() -> &'static Self {
+        &ESTIMATE_GAS_RESULT
+    }
+}
+
 /// "speculative_exec" RPC
 pub struct SpeculativeExec {}
 
@@ -88,6 +233,7 @@ impl RpcWithParams for SpeculativeExec {
         let SpeculativeExecParams {
             block_identifier: maybe_block_id,
             deploy,
+            trace,
         } = params;
         let deploy = Arc::new(deploy);
         let only_from_available_block_range = true;
@@ -114,15 +260,16 @@ impl RpcWithParams for SpeculativeExec {
         }
 
         let result = effect_builder
-            .speculative_execute_deploy(execution_prestate, Arc::clone(&deploy))
+            .speculative_execute_deploy(execution_prestate, Arc::clone(&deploy), trace)
             .await;
 
         match result {
-            Ok(Some(execution_result)) => {
+            Ok(Some((execution_result, maybe_trace))) => {
                 let result = Self::ResponseResult {
                     api_version,
                     block_hash,
                     execution_result,
+                    trace: maybe_trace.map(Into::into),
                 };
                 Ok(result)
             }
@@ -130,50 +277,410 @@ impl RpcWithParams for SpeculativeExec {
                 ErrorCode::NoSuchBlock,
                 "block hash not found".to_string(),
             )),
-            Err(error) => {
-                let rpc_error = match error {
-                    EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
-                    EngineStateError::WasmPreprocessing(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, error.to_string())
-                    }
-                    EngineStateError::InvalidDeployItemVariant(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, error)
-                    }
-                    EngineStateError::InvalidProtocolVersion(_) => Error::new(
-                        ErrorCode::InvalidDeploy,
-                        format!("deploy used invalid protocol version {}", error),
-                    ),
-                    EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
-                    EngineStateError::Genesis(_)
-                    | EngineStateError::WasmSerialization(_)
-                    | EngineStateError::Exec(_)
-                    | EngineStateError::Storage(_)
-                    | EngineStateError::Authorization
-                    | EngineStateError::InsufficientPayment
-                    | EngineStateError::GasConversionOverflow
-                    | EngineStateError::Finalization
-                    | EngineStateError::Bytesrepr(_)
-                    | EngineStateError::Mint(_)
-                    | EngineStateError::InvalidKeyVariant
-                    | EngineStateError::ProtocolUpgrade(_)
-                    | EngineStateError::CommitError(_)
-                    | EngineStateError::MissingSystemContractRegistry
-                    | EngineStateError::MissingSystemContractHash(_)
-                    | EngineStateError::RuntimeStackOverflow
-                    | EngineStateError::FailedToGetWithdrawKeys
-                    | EngineStateError::FailedToGetStoredWithdraws
-                    | EngineStateError::FailedToGetWithdrawPurses
-                    | EngineStateError::FailedToRetrieveUnbondingDelay
-                    | EngineStateError::FailedToRetrieveEraId => {
-                        Error::new(ReservedErrorCode::InternalError, error.to_string())
-                    }
-                    _ => Error::new(
-                        ReservedErrorCode::InternalError,
-                        format!("Unhandled engine state error: {}", error),
-                    ),
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// "speculative_exec_batch" RPC
+pub struct SpeculativeExecBatch {}
+
+#[async_trait]
+impl RpcWithParams for SpeculativeExecBatch {
+    const METHOD: &'static str = "speculative_exec_batch";
+    type RequestParams = SpeculativeExecBatchParams;
+    type ResponseResult = SpeculativeExecBatchResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let SpeculativeExecBatchParams {
+            block_identifier: maybe_block_id,
+            deploys,
+        } = params;
+        if deploys.is_empty() || deploys.len() > MAX_SPECULATIVE_EXEC_BATCH_SIZE {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                format!(
+                    "batch must contain between 1 and {} deploys",
+                    MAX_SPECULATIVE_EXEC_BATCH_SIZE
+                ),
+            ));
+        }
+        let deploys: Vec<_> = deploys.into_iter().map(Arc::new).collect();
+        let only_from_available_block_range = true;
+
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let block_hash = *block.hash();
+        let execution_prestate = SpeculativeExecutionState {
+            state_root_hash: *block.state_root_hash(),
+            block_time: block.timestamp(),
+            protocol_version: block.protocol_version(),
+        };
+
+        // The deploys are only checked against the chainspec and for valid signatures up front.
+        // Their accounts, balances and contracts aren't checked against the state of the given
+        // block, since they may depend on the effects of the deploys preceding them in the batch;
+        // any such failures are reported in the execution results instead.
+        let chainspec = effect_builder.get_chainspec().await;
+        let now = Timestamp::now();
+        for deploy in &deploys {
+            check_batch_deploy(deploy, &chainspec, now).map_err(|error| {
+                Error::new(
+                    ErrorCode::InvalidDeploy,
+                    format!("deploy {}: {}", deploy.hash(), error),
+                )
+            })?;
+        }
+
+        let result = effect_builder
+            .speculative_execute_deploy_batch(execution_prestate, deploys.clone())
+            .await;
+
+        match result {
+            Ok(Some(results)) => {
+                let execution_results = deploys
+                    .iter()
+                    .zip(results)
+                    .map(|(deploy, (execution_result, phase_costs))| {
+                        SpeculativeDeployExecutionResult {
+                            deploy_hash: *deploy.hash(),
+                            execution_result,
+                            payment_cost: phase_costs.payment.value(),
+                            session_cost: phase_costs.session.value(),
+                        }
+                    })
+                    .collect();
+                let result = Self::ResponseResult {
+                    api_version,
+                    block_hash,
+                    execution_results,
+                };
+                Ok(result)
+            }
+            Ok(None) => Err(Error::new(
+                ErrorCode::NoSuchBlock,
+                "block hash not found".to_string(),
+            )),
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// Checks that a deploy of a "speculative_exec_batch" request complies with the chainspec and is
+/// validly signed, without checking it against global state.
+fn check_batch_deploy(
+    deploy: &Deploy,
+    chainspec: &Chainspec,
+    at: Timestamp,
+) -> Result<(), DeployConfigurationFailure> {
+    deploy.is_config_compliant(
+        &chainspec.network_config.name,
+        &chainspec.deploy_config,
+        chainspec.core_config.max_associated_keys,
+        chainspec.deploy_config.max_timestamp_leeway,
+        at,
+    )?;
+    deploy.is_valid()
+}
+
+/// "estimate_gas" RPC
+pub struct EstimateGas {}
+
+#[async_trait]
+impl RpcWithParams for EstimateGas {
+    const METHOD: &'static str = "estimate_gas";
+    type RequestParams = EstimateGasParams;
+    type ResponseResult = EstimateGasResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let EstimateGasParams {
+            block_identifier: maybe_block_id,
+            deploy,
+        } = params;
+        // The deploy need not be signed and its payment is replaced, so only the rest of it is
+        // checked against the chainspec.
+        let chainspec = effect_builder.get_chainspec().await;
+        if let Err(error) = deploy.is_config_compliant_ignoring_payment(
+            &chainspec.network_config.name,
+            &chainspec.deploy_config,
+            chainspec.core_config.max_associated_keys,
+            chainspec.deploy_config.max_timestamp_leeway,
+            Timestamp::now(),
+        ) {
+            return Err(Error::new(ErrorCode::InvalidDeploy, error.to_string()));
+        }
+
+        let gas_price = deploy.header().gas_price();
+        let deploy = Arc::new(deploy);
+        let only_from_available_block_range = true;
+
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let block_hash = *block.hash();
+        let execution_prestate = SpeculativeExecutionState {
+            state_root_hash: *block.state_root_hash(),
+            block_time: block.timestamp(),
+            protocol_version: block.protocol_version(),
+        };
+
+        let payment_amount = max_payment_amount(&chainspec.deploy_config, gas_price);
+
+        let result = effect_builder
+            .estimate_gas(execution_prestate, deploy, payment_amount)
+            .await;
+
+        match result {
+            Ok(Some((execution_result, phase_costs))) => {
+                let recommended_payment =
+                    recommended_payment(&execution_result, &phase_costs, gas_price, payment_amount);
+                let result = Self::ResponseResult {
+                    api_version,
+                    block_hash,
+                    payment_cost: phase_costs.payment.value(),
+                    session_cost: phase_costs.session.value(),
+                    recommended_payment,
+                    execution_result,
                 };
-                Err(rpc_error)
+                Ok(result)
             }
+            Ok(None) => Err(Error::new(
+                ReservedErrorCode::InternalError,
+                "no execution result".to_string(),
+            )),
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// Returns the largest standard payment amount for a deploy with the given gas price.
+///
+/// A deploy can consume at most the block gas limit, so this is the amount buying that much gas,
+/// capped at the chainspec's maximum payment cost if one is set.
+fn max_payment_amount(deploy_config: &DeployConfig, gas_price: u64) -> U512 {
+    let amount = U512::from(deploy_config.block_gas_limit).saturating_mul(U512::from(gas_price));
+    let max_payment_cost = deploy_config.max_payment_cost.value();
+    if max_payment_cost.is_zero() {
+        amount
+    } else {
+        amount.min(max_payment_cost)
+    }
+}
+
+/// Returns the standard payment amount recommended for a deploy with the given gas price, based
+/// on the gas consumed by its execution with a payment of `max_payment_amount`.
+///
+/// Returns `None` if the execution failed, since the gas consumed up to the failure, e.g. running
+/// out of gas, says nothing about the amount needed for the deploy to succeed.
+fn recommended_payment(
+    execution_result: &ExecutionResult,
+    phase_costs: &ExecutionPhaseCosts,
+    gas_price: u64,
+    max_payment_amount: U512,
+) -> Option<U512> {
+    if let ExecutionResult::Failure { .. } = execution_result {
+        return None;
+    }
+    let gas = phase_costs
+        .payment
+        .value()
+        .saturating_add(phase_costs.session.value());
+    // Round the margin up, so that it is never zero for a non-zero cost.
+    let margin = gas
+        .saturating_mul(U512::from(ESTIMATE_GAS_MARGIN_PERCENT))
+        .saturating_add(U512::from(99))
+        / 100;
+    let payment = gas
+        .saturating_add(margin)
+        .saturating_mul(U512::from(gas_price));
+    Some(payment.min(max_payment_amount))
+}
+
+fn engine_state_error_to_rpc_error(error: EngineStateError) -> Error {
+    match error {
+        EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
+        EngineStateError::WasmPreprocessing(error) => {
+            Error::new(ErrorCode::InvalidDeploy, error.to_string())
+        }
+        EngineStateError::InvalidDeployItemVariant(error) => {
+            Error::new(ErrorCode::InvalidDeploy, error)
+        }
+        EngineStateError::InvalidProtocolVersion(_) => Error::new(
+            ErrorCode::InvalidDeploy,
+            format!("deploy used invalid protocol version {}", error),
+        ),
+        EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
+        EngineStateError::Genesis(_)
+        | EngineStateError::WasmSerialization(_)
+        | EngineStateError::Exec(_)
+        | EngineStateError::Storage(_)
+        | EngineStateError::Authorization
+        | EngineStateError::InsufficientPayment
+        | EngineStateError::GasConversionOverflow
+        | EngineStateError::Finalization
+        | EngineStateError::Bytesrepr(_)
+        | EngineStateError::Mint(_)
+        | EngineStateError::InvalidKeyVariant
+        | EngineStateError::ProtocolUpgrade(_)
+        | EngineStateError::CommitError(_)
+        | EngineStateError::MissingSystemContractRegistry
+        | EngineStateError::MissingSystemContractHash(_)
+        | EngineStateError::RuntimeStackOverflow
+        | EngineStateError::FailedToGetWithdrawKeys
+        | EngineStateError::FailedToGetStoredWithdraws
+        | EngineStateError::FailedToGetWithdrawPurses
+        | EngineStateError::FailedToRetrieveUnbondingDelay
+        | EngineStateError::FailedToRetrieveEraId => {
+            Error::new(ReservedErrorCode::InternalError, error.to_string())
+        }
+        _ => Error::new(
+            ReservedErrorCode::InternalError,
+            format!("Unhandled engine state error: {}", error),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use casper_types::{testing::TestRng, ExecutionEffect, Gas, Motes};
+
+    use super::*;
+    use crate::{
+        reactor::{main_reactor::MainEvent, EventQueueHandle, QueueKind, Scheduler},
+        utils,
+    };
+
+    fn phase_costs(payment: u64, session: u64) -> ExecutionPhaseCosts {
+        ExecutionPhaseCosts {
+            payment: Gas::new(U512::from(payment)),
+            session: Gas::new(U512::from(session)),
+        }
+    }
+
+    fn success() -> ExecutionResult {
+        ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::zero(),
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn should_buy_block_gas_limit_at_gas_price() {
+        let mut deploy_config = DeployConfig::default();
+        let block_gas_limit = U512::from(deploy_config.block_gas_limit);
+        assert_eq!(max_payment_amount(&deploy_config, 1), block_gas_limit);
+        assert_eq!(max_payment_amount(&deploy_config, 3), block_gas_limit * 3);
+
+        deploy_config.max_payment_cost = Motes::new(U512::from(1_000));
+        assert_eq!(max_payment_amount(&deploy_config, 3), U512::from(1_000));
+    }
+
+    #[test]
+    fn should_recommend_payment_with_margin_at_gas_price() {
+        let max_payment_amount = U512::from(1_000_000);
+        // 20% of 1000 gas is 200 gas, at 2 motes per gas.
+        assert_eq!(
+            recommended_payment(&success(), &phase_costs(100, 900), 2, max_payment_amount),
+            Some(U512::from(2_400))
+        );
+        // The margin is rounded up.
+        assert_eq!(
+            recommended_payment(&success(), &phase_costs(0, 1), 1, max_payment_amount),
+            Some(U512::from(2))
+        );
+    }
+
+    #[test]
+    fn should_not_recommend_payment_above_max() {
+        let max_payment_amount = U512::from(1_100);
+        assert_eq!(
+            recommended_payment(&success(), &phase_costs(100, 900), 1, max_payment_amount),
+            Some(max_payment_amount)
+        );
+    }
+
+    #[test]
+    fn should_not_recommend_payment_for_failed_execution() {
+        let failure = ExecutionResult::Failure {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(1_000),
+            error_message: "Out of gas error".to_string(),
+            events: vec![],
+        };
+        let max_payment_amount = U512::from(1_000_000);
+        assert_eq!(
+            recommended_payment(&failure, &phase_costs(100, 900), 1, max_payment_amount),
+            None
+        );
+    }
+
+    #[test]
+    fn should_reject_batch_deploy_for_other_chain() {
+        let mut rng = TestRng::new();
+        let chainspec = Chainspec::random(&mut rng);
+        let deploy = Deploy::random(&mut rng);
+        assert!(deploy.header().chain_name() != chainspec.network_config.name);
+        assert!(matches!(
+            check_batch_deploy(&deploy, &chainspec, deploy.header().timestamp()),
+            Err(DeployConfigurationFailure::InvalidChainName { .. })
+        ));
+    }
+
+    async fn speculative_exec_batch(
+        rng: &mut TestRng,
+        deploy_count: usize,
+    ) -> Result<SpeculativeExecBatchResult, Error> {
+        let scheduler = utils::leak(Scheduler::<MainEvent>::new(QueueKind::weights(), None));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+        let params = SpeculativeExecBatchParams {
+            block_identifier: None,
+            deploys: iter::repeat_with(|| Deploy::random(rng))
+                .take(deploy_count)
+                .collect(),
+        };
+        SpeculativeExecBatch::do_handle_request(
+            effect_builder,
+            DOCS_EXAMPLE_PROTOCOL_VERSION,
+            params,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn should_reject_batch_of_invalid_size() {
+        let mut rng = TestRng::new();
+        let expected_error = Error::new(
+            ReservedErrorCode::InvalidParams,
+            format!(
+                "batch must contain between 1 and {} deploys",
+                MAX_SPECULATIVE_EXEC_BATCH_SIZE
+            ),
+        );
+        for deploy_count in [0, MAX_SPECULATIVE_EXEC_BATCH_SIZE + 1] {
+            let error = speculative_exec_batch(&mut rng, deploy_count)
+                .await
+                .unwrap_err();
+            assert_eq!(error, expected_error, "deploy count {}", deploy_count);
         }
     }
 }
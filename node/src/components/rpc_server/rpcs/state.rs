@@ -12,7 +12,10 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 
 use casper_execution_engine::{
-    core::engine_state::{self, BalanceResult, GetBidsResult, QueryResult},
+    core::engine_state::{
+        self, BalanceResult, GetBidsResult, GetKeysRequest, GetKeysResult, KeyPrefix, QueryResult,
+        StateDiffEntry, StateDiffRequest, StateDiffResult,
+    },
     storage::trie::merkle_proof::TrieMerkleProof,
 };
 use casper_hashing::Digest;
@@ -20,8 +23,8 @@ use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
     bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    CLValue, Key, KeyTag, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue,
+    URef, U512,
 };
 
 use crate::{
@@ -31,14 +34,18 @@ use crate::{
         chain::BlockIdentifier,
         common::{self, MERKLE_PROOF},
         docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-        Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithParams,
+        Error, ErrorCode, ErrorData, ReactorEventT, RpcRequest, RpcWithOptionalParams,
+        RpcWithParams,
     },
     types::{
         json_compatibility::{Account as JsonAccount, AuctionState, StoredValue},
-        Block, BlockHash, JsonBlockHeader,
+        AvailableBlockRange, Block, BlockHash, BlockHeader, JsonBlockHeader,
     },
 };
 
+/// The maximum number of entries returned in a single page by "state_get_diff".
+pub const MAX_STATE_DIFF_PAGE_SIZE: u32 = 100;
+
 static GET_ITEM_PARAMS: Lazy<GetItemParams> = Lazy::new(|| GetItemParams {
     state_root_hash: *Block::doc_example().header().state_root_hash(),
     key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
@@ -60,7 +67,8 @@ static GET_BALANCE_RESULT: Lazy<GetBalanceResult> = Lazy::new(|| GetBalanceResul
     merkle_proof: MERKLE_PROOF.clone(),
 });
 static GET_AUCTION_INFO_PARAMS: Lazy<GetAuctionInfoParams> = Lazy::new(|| GetAuctionInfoParams {
-    block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
+    block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+    state_identifier: None,
 });
 static GET_AUCTION_INFO_RESULT: Lazy<GetAuctionInfoResult> = Lazy::new(|| GetAuctionInfoResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
@@ -72,6 +80,7 @@ static GET_ACCOUNT_INFO_PARAMS: Lazy<GetAccountInfoParams> = Lazy::new(|| {
     GetAccountInfoParams {
         account_identifier: AccountIdentifier::PublicKey(public_key),
         block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        state_identifier: None,
     }
 });
 static GET_ACCOUNT_INFO_RESULT: Lazy<GetAccountInfoResult> = Lazy::new(|| GetAccountInfoResult {
@@ -81,7 +90,8 @@ static GET_ACCOUNT_INFO_RESULT: Lazy<GetAccountInfoResult> = Lazy::new(|| GetAcc
 });
 static GET_DICTIONARY_ITEM_PARAMS: Lazy<GetDictionaryItemParams> =
     Lazy::new(|| GetDictionaryItemParams {
-        state_root_hash: *Block::doc_example().header().state_root_hash(),
+        state_root_hash: Some(*Block::doc_example().header().state_root_hash()),
+        state_identifier: None,
         dictionary_identifier: DictionaryIdentifier::URef {
             seed_uref: "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007"
                 .to_string(),
@@ -129,6 +139,37 @@ static QUERY_BALANCE_RESULT: Lazy<QueryBalanceResult> = Lazy::new(|| QueryBalanc
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     balance: U512::from(123_456),
 });
+static GET_KEYS_BY_PREFIX_PARAMS: Lazy<GetKeysByPrefixParams> =
+    Lazy::new(|| GetKeysByPrefixParams {
+        state_identifier: Some(GlobalStateIdentifier::BlockHash(
+            *Block::doc_example().hash(),
+        )),
+        key_prefix: KeyPrefixIdentifier::KeyTag(KeyTag::DeployInfo as u8),
+        page_start: None,
+        page_size: Some(100),
+    });
+static GET_KEYS_BY_PREFIX_RESULT: Lazy<GetKeysByPrefixResult> =
+    Lazy::new(|| GetKeysByPrefixResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        keys: vec![GET_ITEM_PARAMS.key.clone()],
+        next_page_start: None,
+    });
+static GET_STATE_DIFF_PARAMS: Lazy<GetStateDiffParams> = Lazy::new(|| GetStateDiffParams {
+    before_state_root_hash: *Block::doc_example().header().state_root_hash(),
+    after_state_root_hash: Digest::from([1; Digest::LENGTH]),
+    key_prefix: Some(Bytes::from(vec![KeyTag::DeployInfo as u8])),
+    page_start: None,
+    page_size: Some(MAX_STATE_DIFF_PAGE_SIZE),
+});
+static GET_STATE_DIFF_RESULT: Lazy<GetStateDiffResult> = Lazy::new(|| GetStateDiffResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    entries: vec![StateDiff {
+        key: GET_ITEM_PARAMS.key.clone(),
+        before: None,
+        after: Some(StoredValue::CLValue(CLValue::from_t(1u64).unwrap())),
+    }],
+    next_page_start: None,
+});
 
 /// Params for "state_get_item" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -330,7 +371,16 @@ impl RpcWithParams for GetBalance {
 #[serde(deny_unknown_fields)]
 pub struct GetAuctionInfoParams {
     /// The block identifier.
-    pub block_identifier: BlockIdentifier,
+    // `schemars(with)` keeps the param schemas non-nullable, i.e. those of the identifiers
+    // themselves, as an omitted param is listed as not required rather than as `null`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "BlockIdentifier")]
+    pub block_identifier: Option<BlockIdentifier>,
+    /// The global state identifier, as an alternative to `block_identifier`.  If neither is
+    /// given, the state of the highest complete block is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "GlobalStateIdentifier")]
+    pub state_identifier: Option<GlobalStateIdentifier>,
 }
 
 impl DocExample for GetAuctionInfoParams {
@@ -370,23 +420,18 @@ impl RpcWithOptionalParams for GetAuctionInfo {
         api_version: ProtocolVersion,
         maybe_params: Option<Self::OptionalRequestParams>,
     ) -> Result<Self::ResponseResult, Error> {
-        // This RPC request is restricted by the block availability index.
-        let only_from_available_block_range = true;
-
-        let maybe_block_id = maybe_params.map(|params| params.block_identifier);
-        let block = common::get_block(
-            maybe_block_id,
-            only_from_available_block_range,
-            effect_builder,
-        )
-        .await?;
+        let maybe_state_identifier = match maybe_params {
+            Some(params) => merge_identifiers(params.block_identifier, params.state_identifier)?,
+            None => None,
+        };
+        let (state_root_hash, maybe_block_header) =
+            get_state_root_hash_and_optional_header(effect_builder, maybe_state_identifier).await?;
 
         let protocol_version = api_version;
 
-        // the global state hash of the last block
-        let state_root_hash = *block.header().state_root_hash();
-        // the block height of the last added block
-        let block_height = block.header().height();
+        // the block height of the requested block, unknown if identified by state root hash
+        let maybe_block_height = maybe_block_header.as_ref().map(BlockHeader::height);
+        let maybe_block_hash = maybe_block_header.as_ref().map(BlockHeader::block_hash);
 
         let get_bids_result = effect_builder
             .make_request(
@@ -401,22 +446,36 @@ impl RpcWithOptionalParams for GetAuctionInfo {
         let bids = match get_bids_result {
             Ok(GetBidsResult::Success { bids }) => bids,
             Ok(GetBidsResult::RootNotFound) => {
-                error!(
-                    block_hash=?block.hash(),
+                info!(
+                    block_hash=?maybe_block_hash,
                     ?state_root_hash,
                     "root not found while trying to get bids"
                 );
-                return Err(Error::new(
-                    ReservedErrorCode::InternalError,
+                if maybe_block_header.is_none() {
+                    let available_block_range = effect_builder
+                        .get_available_block_range_from_storage()
+                        .await;
+                    return Err(state_not_available_error(
+                        format!(
+                            "global state under state root hash {} is not available on this node",
+                            state_root_hash
+                        ),
+                        available_block_range,
+                    ));
+                }
+                return Err(common::missing_block_or_state_root_error(
+                    effect_builder,
+                    ErrorCode::NoSuchStateRoot,
                     format!(
-                        "root not found when getting bids at block {:?}",
-                        block.hash().inner()
+                        "root not found when getting bids at state root hash {}",
+                        state_root_hash
                     ),
-                ));
+                )
+                .await);
             }
             Err(error) => {
                 error!(
-                    block_hash=?block.hash(),
+                    block_hash=?maybe_block_hash,
                     ?state_root_hash,
                     ?error,
                     "failed to get bids"
@@ -424,9 +483,8 @@ impl RpcWithOptionalParams for GetAuctionInfo {
                 return Err(Error::new(
                     ReservedErrorCode::InternalError,
                     format!(
-                        "error getting bids at block {:?}: {}",
-                        block.hash().inner(),
-                        error
+                        "error getting bids at state root hash {}: {}",
+                        state_root_hash, error
                     ),
                 ));
             }
@@ -446,19 +504,24 @@ impl RpcWithOptionalParams for GetAuctionInfo {
         let era_validators = match era_validators_result {
             Ok(validators) => validators,
             Err(error) => {
-                error!(block_hash=?block.hash(), ?state_root_hash, ?error, "failed to get era validators");
+                error!(
+                    block_hash=?maybe_block_hash,
+                    ?state_root_hash,
+                    ?error,
+                    "failed to get era validators"
+                );
                 return Err(Error::new(
                     ReservedErrorCode::InternalError,
                     format!(
-                        "failed to get validators at block {:?}: {}",
-                        block.hash().inner(),
-                        error
+                        "failed to get validators at state root hash {}: {}",
+                        state_root_hash, error
                     ),
                 ));
             }
         };
 
-        let auction_state = AuctionState::new(state_root_hash, block_height, era_validators, bids);
+        let auction_state =
+            AuctionState::new(state_root_hash, maybe_block_height, era_validators, bids);
 
         let result = Self::ResponseResult {
             api_version,
@@ -487,6 +550,10 @@ pub struct GetAccountInfoParams {
     pub account_identifier: AccountIdentifier,
     /// The block identifier.
     pub block_identifier: Option<BlockIdentifier>,
+    /// The global state identifier, as an alternative to `block_identifier`.  If neither is
+    /// given, the state of the highest complete block is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_identifier: Option<GlobalStateIdentifier>,
 }
 
 impl DocExample for GetAccountInfoParams {
@@ -528,18 +595,11 @@ impl RpcWithParams for GetAccountInfo {
         api_version: ProtocolVersion,
         params: Self::RequestParams,
     ) -> Result<Self::ResponseResult, Error> {
-        // This RPC request is restricted by the block availability index.
-        let only_from_available_block_range = true;
-
-        let maybe_block_id = params.block_identifier;
-        let block = common::get_block(
-            maybe_block_id,
-            only_from_available_block_range,
-            effect_builder,
-        )
-        .await?;
+        let maybe_state_identifier =
+            merge_identifiers(params.block_identifier, params.state_identifier)?;
+        let (state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, maybe_state_identifier).await?;
 
-        let state_root_hash = *block.header().state_root_hash();
         let base_key = {
             let account_hash = match params.account_identifier {
                 AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
@@ -684,7 +744,11 @@ impl DictionaryIdentifier {
 #[serde(deny_unknown_fields)]
 pub struct GetDictionaryItemParams {
     /// Hash of the state root
-    pub state_root_hash: Digest,
+    pub state_root_hash: Option<Digest>,
+    /// The global state identifier, as an alternative to `state_root_hash`.  If neither is given,
+    /// the state of the highest complete block is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_identifier: Option<GlobalStateIdentifier>,
     /// The Dictionary query identifier.
     pub dictionary_identifier: DictionaryIdentifier,
 }
@@ -730,6 +794,21 @@ impl RpcWithParams for GetDictionaryItem {
         api_version: ProtocolVersion,
         params: Self::RequestParams,
     ) -> Result<Self::ResponseResult, Error> {
+        let maybe_state_identifier = match (params.state_root_hash, params.state_identifier) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    ReservedErrorCode::InvalidParams,
+                    "only one of 'state_root_hash' and 'state_identifier' may be provided",
+                ))
+            }
+            (Some(state_root_hash), None) => {
+                Some(GlobalStateIdentifier::StateRootHash(state_root_hash))
+            }
+            (None, maybe_state_identifier) => maybe_state_identifier,
+        };
+        let (state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, maybe_state_identifier).await?;
+
         let dictionary_query_key = match params.dictionary_identifier {
             DictionaryIdentifier::AccountNamedKey { ref key, .. }
             | DictionaryIdentifier::ContractNamedKey { ref key, .. } => {
@@ -745,7 +824,7 @@ impl RpcWithParams for GetDictionaryItem {
 
                 let empty_path = Vec::new();
                 let (value, _proofs) =
-                    run_query(effect_builder, params.state_root_hash, base_key, empty_path).await?;
+                    run_query(effect_builder, state_root_hash, base_key, empty_path).await?;
                 params
                     .dictionary_identifier
                     .get_dictionary_address(Some(value))?
@@ -757,7 +836,7 @@ impl RpcWithParams for GetDictionaryItem {
 
         let (stored_value, merkle_proof) = common::run_query_and_encode(
             effect_builder,
-            params.state_root_hash,
+            state_root_hash,
             dictionary_query_key,
             vec![],
         )
@@ -785,6 +864,17 @@ pub enum GlobalStateIdentifier {
     StateRootHash(Digest),
 }
 
+impl From<BlockIdentifier> for GlobalStateIdentifier {
+    fn from(block_identifier: BlockIdentifier) -> Self {
+        match block_identifier {
+            BlockIdentifier::Hash(block_hash) => GlobalStateIdentifier::BlockHash(block_hash),
+            BlockIdentifier::Height(block_height) => {
+                GlobalStateIdentifier::BlockHeight(block_height)
+            }
+        }
+    }
+}
+
 /// Params for "query_global_state" RPC
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -840,29 +930,9 @@ impl RpcWithParams for QueryGlobalState {
         api_version: ProtocolVersion,
         params: Self::RequestParams,
     ) -> Result<Self::ResponseResult, Error> {
-        let (state_root_hash, maybe_block_header) = match params.state_identifier {
-            None => match effect_builder
-                .get_highest_complete_block_header_from_storage()
-                .await
-            {
-                None => {
-                    return Err(Error::new(
-                        ErrorCode::NoSuchBlock,
-                        "query-global-state failed to retrieve highest block header",
-                    ))
-                }
-                Some(block_header) => (
-                    *block_header.state_root_hash(),
-                    Some(JsonBlockHeader::from(block_header.clone())),
-                ),
-            },
-            Some(state_identifier) => {
-                let (state_root_hash, maybe_block_header) =
-                    get_state_root_hash_and_optional_header(effect_builder, state_identifier)
-                        .await?;
-                (state_root_hash, maybe_block_header)
-            }
-        };
+        let (state_root_hash, maybe_block_header) =
+            get_state_root_hash_and_optional_header(effect_builder, params.state_identifier)
+                .await?;
 
         let base_key = match Key::from_formatted_str(&params.key)
             .map_err(|error| format!("failed to parse key: {}", error))
@@ -880,7 +950,7 @@ impl RpcWithParams for QueryGlobalState {
 
         let result = Self::ResponseResult {
             api_version,
-            block_header: maybe_block_header,
+            block_header: maybe_block_header.map(JsonBlockHeader::from),
             stored_value,
             merkle_proof,
         };
@@ -946,26 +1016,9 @@ impl RpcWithParams for QueryBalance {
         api_version: ProtocolVersion,
         params: Self::RequestParams,
     ) -> Result<Self::ResponseResult, Error> {
-        let state_root_hash = match params.state_identifier {
-            None => match effect_builder
-                .get_highest_complete_block_header_from_storage()
-                .await
-            {
-                None => {
-                    return Err(Error::new(
-                        ErrorCode::NoSuchBlock,
-                        "query-balance failed to retrieve highest block header",
-                    ))
-                }
-                Some(block_header) => *block_header.state_root_hash(),
-            },
-            Some(state_identifier) => {
-                let (state_root_hash, _) =
-                    get_state_root_hash_and_optional_header(effect_builder, state_identifier)
-                        .await?;
-                state_root_hash
-            }
-        };
+        let (state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, params.state_identifier)
+                .await?;
 
         let purse_uref = match params.purse_identifier {
             PurseIdentifier::MainPurseUnderPublicKey(account_public_key) => {
@@ -1098,6 +1151,315 @@ impl RpcWithParams for GetTrie {
     }
 }
 
+/// Identifier of the keys to be listed by "state_get_keys_by_prefix".
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum KeyPrefixIdentifier {
+    /// The keys of the type with this tag, e.g. 5 for era info keys or 7 for bid keys.
+    KeyTag(u8),
+    /// The keys whose bytesrepr serialization starts with these hex-encoded bytes.
+    Bytes(#[schemars(with = "String")] Bytes),
+    /// The dictionary items of the dictionary with this seed URef.  As the keys of dictionary
+    /// items are hashes, this examines every dictionary item in global state.
+    DictionaryItems(URef),
+}
+
+impl From<KeyPrefixIdentifier> for KeyPrefix {
+    fn from(key_prefix: KeyPrefixIdentifier) -> Self {
+        match key_prefix {
+            KeyPrefixIdentifier::KeyTag(key_tag) => KeyPrefix::Bytes(vec![key_tag]),
+            KeyPrefixIdentifier::Bytes(bytes) => KeyPrefix::Bytes(bytes.into()),
+            KeyPrefixIdentifier::DictionaryItems(seed_uref) => {
+                KeyPrefix::DictionaryItems(seed_uref.addr())
+            }
+        }
+    }
+}
+
+/// Params for "state_get_keys_by_prefix" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysByPrefixParams {
+    /// The state identifier used for the query, if none is passed the tip of the chain will be
+    /// used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The keys to be listed.
+    pub key_prefix: KeyPrefixIdentifier,
+    /// The `casper_types::Key` as formatted string from which to start the page, as given by the
+    /// previous page's `next_page_start`.  If omitted, the page starts from the first key.
+    pub page_start: Option<String>,
+    /// The maximum number of keys to examine, capped at the node's configured
+    /// `max_keys_page_size`.  If omitted, `max_keys_page_size` is used.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetKeysByPrefixParams {
+    fn doc_example() -> &'static Self {
+        &GET_KEYS_BY_PREFIX_PARAMS
+    }
+}
+
+/// Result for "state_get_keys_by_prefix" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysByPrefixResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The `casper_types::Key`s as formatted strings, ordered by their bytesrepr serialization.
+    /// There may be fewer than `page_size`, even none, while `next_page_start` is set if keys were
+    /// examined but not listed, e.g. dictionary items of other dictionaries.
+    pub keys: Vec<String>,
+    /// The `casper_types::Key` as formatted string from which the next page starts, if there are
+    /// further keys to examine.
+    pub next_page_start: Option<String>,
+}
+
+impl DocExample for GetKeysByPrefixResult {
+    fn doc_example() -> &'static Self {
+        &GET_KEYS_BY_PREFIX_RESULT
+    }
+}
+
+/// "state_get_keys_by_prefix" RPC.
+pub struct GetKeysByPrefix {}
+
+#[async_trait]
+impl RpcWithParams for GetKeysByPrefix {
+    const METHOD: &'static str = "state_get_keys_by_prefix";
+    type RequestParams = GetKeysByPrefixParams;
+    type ResponseResult = GetKeysByPrefixResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let page_start = match params.page_start.as_deref().map(Key::from_formatted_str) {
+            None => None,
+            Some(Ok(key)) => Some(key),
+            Some(Err(error)) => {
+                let error_msg = format!("failed to parse page start key: {}", error);
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::FailedToParseQueryKey, error_msg));
+            }
+        };
+        let (state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, params.state_identifier)
+                .await?;
+        let get_keys_request = GetKeysRequest::new(
+            state_root_hash,
+            params.key_prefix.into(),
+            page_start,
+            params.page_size,
+        );
+
+        let (keys, next_page_start) = match effect_builder.get_keys(get_keys_request).await {
+            Ok(GetKeysResult::Success {
+                keys,
+                next_page_start,
+            }) => (keys, next_page_start),
+            Ok(GetKeysResult::RootNotFound) => {
+                info!(%state_root_hash, "get keys by prefix failed: root not found");
+                let error = common::missing_block_or_state_root_error(
+                    effect_builder,
+                    ErrorCode::NoSuchStateRoot,
+                    format!("failed to get state root at {:?}", state_root_hash),
+                )
+                .await;
+                return Err(error);
+            }
+            Ok(GetKeysResult::MissingTrie(trie_key)) => {
+                let error_msg = format!(
+                    "global state is incomplete on this node; missing trie {}",
+                    trie_key
+                );
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::StateNotAvailable, error_msg));
+            }
+            Err(error) => {
+                info!(?error, "get keys by prefix failed to execute");
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ));
+            }
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            keys: keys.into_iter().map(Key::to_formatted_string).collect(),
+            next_page_start: next_page_start.map(|key| key.to_formatted_string()),
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "state_get_diff" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStateDiffParams {
+    /// The state root hash of the earlier global state.
+    pub before_state_root_hash: Digest,
+    /// The state root hash of the later global state.
+    pub after_state_root_hash: Digest,
+    /// If given, only keys whose bytesrepr serialization starts with these bytes are compared.
+    #[schemars(
+        with = "Option<String>",
+        description = "If given, only keys whose bytesrepr serialization starts with these \
+        hex-encoded bytes are compared.  The first byte of a serialized key is its tag, e.g. \
+        \"04\" for deploy info keys."
+    )]
+    pub key_prefix: Option<Bytes>,
+    /// The `casper_types::Key` as formatted string from which to start the page, as given by the
+    /// previous page's `next_page_start`.  If omitted, the page starts from the first key.
+    pub page_start: Option<String>,
+    /// The maximum number of entries to return, capped at `MAX_STATE_DIFF_PAGE_SIZE`.  If
+    /// omitted, `MAX_STATE_DIFF_PAGE_SIZE` is used.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetStateDiffParams {
+    fn doc_example() -> &'static Self {
+        &GET_STATE_DIFF_PARAMS
+    }
+}
+
+/// A key whose value differs between two global states.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StateDiff {
+    /// `casper_types::Key` as formatted string.
+    pub key: String,
+    /// The value under the key in the earlier global state, or `null` if the key was added.
+    pub before: Option<StoredValue>,
+    /// The value under the key in the later global state, or `null` if the key was removed.
+    pub after: Option<StoredValue>,
+}
+
+impl StateDiff {
+    fn try_from_entry(entry: StateDiffEntry) -> Result<Self, Error> {
+        let encode = |maybe_value: Option<DomainStoredValue>| {
+            maybe_value
+                .map(StoredValue::try_from)
+                .transpose()
+                .map_err(|error| {
+                    warn!(?error, "failed to encode stored value");
+                    Error::new(
+                        ReservedErrorCode::InternalError,
+                        format!("failed to encode stored value: {}", error),
+                    )
+                })
+        };
+        Ok(StateDiff {
+            key: entry.key.to_formatted_string(),
+            before: encode(entry.before)?,
+            after: encode(entry.after)?,
+        })
+    }
+}
+
+/// Result for "state_get_diff" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStateDiffResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The keys whose values differ, ordered by their bytesrepr serialization.
+    pub entries: Vec<StateDiff>,
+    /// The `casper_types::Key` as formatted string from which the next page starts, if there are
+    /// further keys whose values differ.
+    pub next_page_start: Option<String>,
+}
+
+impl DocExample for GetStateDiffResult {
+    fn doc_example() -> &'static Self {
+        &GET_STATE_DIFF_RESULT
+    }
+}
+
+/// "state_get_diff" RPC.
+pub struct GetStateDiff {}
+
+#[async_trait]
+impl RpcWithParams for GetStateDiff {
+    const METHOD: &'static str = "state_get_diff";
+    type RequestParams = GetStateDiffParams;
+    type ResponseResult = GetStateDiffResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let page_start = match params.page_start.as_deref().map(Key::from_formatted_str) {
+            None => None,
+            Some(Ok(key)) => Some(key),
+            Some(Err(error)) => {
+                let error_msg = format!("failed to parse page start key: {}", error);
+                info!("{}", error_msg);
+                return Err(Error::new(ErrorCode::FailedToParseQueryKey, error_msg));
+            }
+        };
+        let page_size = params
+            .page_size
+            .unwrap_or(MAX_STATE_DIFF_PAGE_SIZE)
+            .min(MAX_STATE_DIFF_PAGE_SIZE);
+        let state_diff_request = StateDiffRequest::new(
+            params.before_state_root_hash,
+            params.after_state_root_hash,
+            params.key_prefix.map(Vec::from).unwrap_or_default(),
+            page_start,
+            page_size as usize,
+        );
+
+        let (entries, next_page_start) =
+            match effect_builder.get_state_diff(state_diff_request).await {
+                Ok(StateDiffResult::Success {
+                    entries,
+                    next_page_start,
+                }) => (entries, next_page_start),
+                Ok(StateDiffResult::RootNotFound(state_root_hash)) => {
+                    info!(%state_root_hash, "state diff failed: root not found");
+                    let error = common::missing_block_or_state_root_error(
+                        effect_builder,
+                        ErrorCode::NoSuchStateRoot,
+                        format!("failed to get state root at {:?}", state_root_hash),
+                    )
+                    .await;
+                    return Err(error);
+                }
+                Ok(StateDiffResult::MissingTrie(trie_key)) => {
+                    let error_msg = format!(
+                        "global state is incomplete on this node; missing trie {}",
+                        trie_key
+                    );
+                    info!("{}", error_msg);
+                    return Err(Error::new(ErrorCode::StateNotAvailable, error_msg));
+                }
+                Err(error) => {
+                    info!(?error, "state diff failed to execute");
+                    return Err(Error::new(
+                        ErrorCode::QueryFailedToExecute,
+                        format!("{:?}", error),
+                    ));
+                }
+            };
+
+        let entries = entries
+            .into_iter()
+            .map(StateDiff::try_from_entry)
+            .collect::<Result<_, _>>()?;
+        let result = Self::ResponseResult {
+            api_version,
+            entries,
+            next_page_start: next_page_start.map(|key| key.to_formatted_string()),
+        };
+        Ok(result)
+    }
+}
+
 type QuerySuccess = (
     DomainStoredValue,
     Vec<TrieMerkleProof<Key, DomainStoredValue>>,
@@ -1186,30 +1548,69 @@ async fn get_account<REv: ReactorEventT>(
     }
 }
 
+/// Combines a block identifier with a global state identifier, at most one of which may be
+/// provided.
+fn merge_identifiers(
+    maybe_block_identifier: Option<BlockIdentifier>,
+    maybe_state_identifier: Option<GlobalStateIdentifier>,
+) -> Result<Option<GlobalStateIdentifier>, Error> {
+    match (maybe_block_identifier, maybe_state_identifier) {
+        (Some(_), Some(_)) => Err(Error::new(
+            ReservedErrorCode::InvalidParams,
+            "only one of 'block_identifier' and 'state_identifier' may be provided",
+        )),
+        (Some(block_identifier), None) => Ok(Some(block_identifier.into())),
+        (None, maybe_state_identifier) => Ok(maybe_state_identifier),
+    }
+}
+
+/// Resolves the given state identifier to a state root hash and, if the identifier refers to a
+/// block, the header of that block.
+///
+/// If no identifier is given, the state of the highest complete block is used.  If the identified
+/// block is known to this node but its global state lies outside the available block range, a
+/// `StateNotAvailable` error is returned.
 pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
-    state_identifier: GlobalStateIdentifier,
-) -> Result<(Digest, Option<JsonBlockHeader>), Error> {
-    // This RPC request is restricted by the block availability index.
-    let only_from_available_block_range = true;
-    match state_identifier {
-        GlobalStateIdentifier::BlockHash(block_hash) => {
+    maybe_state_identifier: Option<GlobalStateIdentifier>,
+) -> Result<(Digest, Option<BlockHeader>), Error> {
+    // The block header itself may be stored even if its global state isn't, so we don't restrict
+    // the lookup by the block availability index, but check it afterwards instead.
+    let only_from_available_block_range = false;
+    let block_header = match maybe_state_identifier {
+        None => {
             match effect_builder
-                .get_block_header_from_storage(block_hash, only_from_available_block_range)
+                .get_highest_complete_block_header_from_storage()
                 .await
             {
+                Some(block_header) => block_header,
                 None => {
-                    let error_msg =
-                        format!("failed to retrieve specified block header {}", block_hash);
-                    Err(Error::new(ErrorCode::NoSuchBlock, error_msg))
+                    return Err(common::missing_block_or_state_root_error(
+                        effect_builder,
+                        ErrorCode::NoSuchBlock,
+                        "failed to retrieve highest complete block header".to_string(),
+                    )
+                    .await)
                 }
-                Some(block_header) => {
-                    let json_block_header = JsonBlockHeader::from(block_header.clone());
-                    Ok((*block_header.state_root_hash(), Some(json_block_header)))
+            }
+        }
+        Some(GlobalStateIdentifier::BlockHash(block_hash)) => {
+            match effect_builder
+                .get_block_header_from_storage(block_hash, only_from_available_block_range)
+                .await
+            {
+                Some(block_header) => block_header,
+                None => {
+                    return Err(common::missing_block_or_state_root_error(
+                        effect_builder,
+                        ErrorCode::NoSuchBlock,
+                        format!("failed to retrieve specified block header {}", block_hash),
+                    )
+                    .await)
                 }
             }
         }
-        GlobalStateIdentifier::BlockHeight(block_height) => {
+        Some(GlobalStateIdentifier::BlockHeight(block_height)) => {
             match effect_builder
                 .get_block_header_at_height_from_storage(
                     block_height,
@@ -1217,17 +1618,84 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
                 )
                 .await
             {
+                Some(block_header) => block_header,
                 None => {
-                    let error_msg =
-                        format!("failed to retrieve block header at height {}", block_height);
-                    Err(Error::new(ErrorCode::NoSuchBlock, error_msg))
-                }
-                Some(block_header) => {
-                    let json_block_header = JsonBlockHeader::from(block_header.clone());
-                    Ok((*block_header.state_root_hash(), Some(json_block_header)))
+                    return Err(common::missing_block_or_state_root_error(
+                        effect_builder,
+                        ErrorCode::NoSuchBlock,
+                        format!("failed to retrieve block header at height {}", block_height),
+                    )
+                    .await)
                 }
             }
         }
-        GlobalStateIdentifier::StateRootHash(state_root_hash) => Ok((state_root_hash, None)),
+        Some(GlobalStateIdentifier::StateRootHash(state_root_hash)) => {
+            return Ok((state_root_hash, None))
+        }
+    };
+
+    let available_block_range = effect_builder
+        .get_available_block_range_from_storage()
+        .await;
+    check_state_available(&block_header, available_block_range)?;
+
+    Ok((*block_header.state_root_hash(), Some(block_header)))
+}
+
+/// Returns a `StateNotAvailable` error if the given block lies outside the available block range.
+fn check_state_available(
+    block_header: &BlockHeader,
+    available_block_range: AvailableBlockRange,
+) -> Result<(), Error> {
+    if available_block_range.contains(block_header.height()) {
+        return Ok(());
+    }
+    let message = format!(
+        "global state of block {} at height {} is not available on this node",
+        block_header.block_hash(),
+        block_header.height()
+    );
+    Err(state_not_available_error(message, available_block_range))
+}
+
+/// Returns a `StateNotAvailable` error with the given message and available block range.
+fn state_not_available_error(message: String, available_block_range: AvailableBlockRange) -> Error {
+    info!(%available_block_range, "{}", message);
+    Error::new(
+        ErrorCode::StateNotAvailable,
+        ErrorData::MissingBlockOrStateRoot {
+            message,
+            available_block_range,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_report_state_not_available_outside_available_block_range() {
+        let mut rng = TestRng::new();
+        let block = Block::random(&mut rng);
+        let height = block.header().height();
+
+        let available_block_range = AvailableBlockRange::new(height, height);
+        assert!(check_state_available(block.header(), available_block_range).is_ok());
+
+        let available_block_range = match height.checked_sub(1) {
+            Some(previous_height) => AvailableBlockRange::new(0, previous_height),
+            None => AvailableBlockRange::new(1, 1),
+        };
+        let error = check_state_available(block.header(), available_block_range)
+            .expect_err("state should not be available");
+        let error_json = serde_json::to_value(error).unwrap();
+        assert_eq!(error_json["code"], ErrorCode::StateNotAvailable as i64);
+        assert_eq!(
+            error_json["data"]["available_block_range"],
+            serde_json::to_value(available_block_range).unwrap()
+        );
     }
 }
@@ -6,7 +6,10 @@ use casper_types::ProtocolVersion;
 use super::ReactorEventT;
 use crate::{
     effect::EffectBuilder,
-    rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
+    rpcs::{
+        speculative_exec::{EstimateGas, SpeculativeExec, SpeculativeExecBatch},
+        RequestLimiter, RpcWithParams,
+    },
 };
 
 /// The URL path for all JSON-RPC requests.
@@ -19,12 +22,25 @@ pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
-    qps_limit: u64,
+    request_limiter: RequestLimiter,
     max_body_bytes: u32,
+    max_batch_size: u32,
     cors_origin: String,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
+    SpeculativeExec::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    SpeculativeExecBatch::register_as_handler(
+        effect_builder,
+        api_version,
+        &request_limiter,
+        &mut handlers,
+    );
+    EstimateGas::register_as_handler(effect_builder, api_version, &request_limiter, &mut handlers);
     let handlers = handlers.build();
 
     match cors_origin.as_str() {
@@ -32,10 +48,11 @@ pub(super) async fn run<REv: ReactorEventT>(
             super::rpcs::run(
                 builder,
                 handlers,
-                qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
+                None,
             )
             .await;
         }
@@ -43,10 +60,11 @@ pub(super) async fn run<REv: ReactorEventT>(
             super::rpcs::run_with_cors(
                 builder,
                 handlers,
-                qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
+                None,
                 CorsOrigin::Any,
             )
             .await
@@ -55,10 +73,11 @@ pub(super) async fn run<REv: ReactorEventT>(
             super::rpcs::run_with_cors(
                 builder,
                 handlers,
-                qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
+                None,
                 CorsOrigin::Specified(cors_origin),
             )
             .await
@@ -0,0 +1,515 @@
+//! WebSocket transport for the JSON-RPC server.
+//!
+//! Clients connected via WebSocket can call all the JSON-RPC methods available via HTTP.  They can
+//! additionally call `subscribe` to receive event-stream data of a given type as JSON-RPC
+//! notifications on the same connection, and `unsubscribe` to stop receiving them.
+
+use std::{
+    collections::HashMap,
+    future,
+    sync::{Arc, Mutex},
+};
+
+use futures::{SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc,
+    },
+    task::JoinHandle,
+};
+use tracing::{debug, info};
+use warp::{
+    filters::BoxedFilter,
+    reply::Response,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
+
+use casper_json_rpc::{Error, Params, RequestHandlers, ReservedErrorCode};
+use casper_types::ProtocolVersion;
+
+use super::rpcs::{ErrorCode, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST};
+use crate::components::event_stream_server::SseData;
+
+/// The URL path for JSON-RPC requests over WebSocket.
+pub const RPC_WS_API_PATH: &str = "ws";
+
+/// The JSON-RPC "method" to subscribe to event-stream data.
+const SUBSCRIBE_METHOD: &str = "subscribe";
+/// The JSON-RPC "method" to cancel a subscription.
+const UNSUBSCRIBE_METHOD: &str = "unsubscribe";
+/// The JSON-RPC "method" of the notifications sent to subscribed clients.
+const SUBSCRIPTION_NOTIFICATION_METHOD: &str = "subscription";
+
+/// The maximum number of outbound messages buffered per connection before the handling of
+/// further requests and events is paused.
+const OUTBOUND_BUFFER_SIZE: usize = 64;
+
+/// The identifier of a subscription, unique per connection.
+pub type SubscriptionId = u64;
+
+/// The types of event-stream data to which a client can subscribe.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum EventType {
+    /// Blocks added to the linear chain.
+    BlockAdded,
+    /// Deploys executed and committed as part of a block.
+    DeployProcessed,
+    /// Finality signatures received.
+    FinalitySignature,
+    /// The execution effects produced by a step.
+    Step,
+    /// Validator faults.
+    Fault,
+}
+
+impl EventType {
+    /// Returns `true` if `data` is of this event type.
+    fn matches(self, data: &SseData) -> bool {
+        matches!(
+            (self, data),
+            (EventType::BlockAdded, SseData::BlockAdded { .. })
+                | (EventType::DeployProcessed, SseData::DeployProcessed { .. })
+                | (EventType::FinalitySignature, SseData::FinalitySignature(_))
+                | (EventType::Step, SseData::Step { .. })
+                | (EventType::Fault, SseData::Fault { .. })
+        )
+    }
+}
+
+/// Params for "subscribe" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SubscribeParams {
+    /// The type of event-stream data to subscribe to.
+    pub event_type: EventType,
+}
+
+/// Result for "subscribe" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SubscribeResult {
+    /// The RPC API version.
+    pub api_version: ProtocolVersion,
+    /// The identifier of the new subscription, provided in each of its notifications.
+    pub subscription_id: SubscriptionId,
+}
+
+/// Params for "unsubscribe" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UnsubscribeParams {
+    /// The identifier of the subscription to cancel.
+    pub subscription_id: SubscriptionId,
+}
+
+/// Result for "unsubscribe" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UnsubscribeResult {
+    /// The RPC API version.
+    pub api_version: ProtocolVersion,
+}
+
+/// The settings and shared state applicable to every WebSocket connection.
+#[derive(Clone)]
+pub(super) struct WsContext {
+    /// The handlers for the JSON-RPC methods available via HTTP.
+    pub(super) handlers: RequestHandlers,
+    /// The source of event-stream data for subscriptions.
+    pub(super) event_broadcaster: broadcast::Sender<SseData>,
+    /// The RPC API version.
+    pub(super) api_version: ProtocolVersion,
+    /// Maximum number of bytes to accept in a single message.
+    pub(super) max_message_bytes: u32,
+    /// Maximum number of requests to accept in a single JSON-RPC batch.
+    pub(super) max_batch_size: u32,
+    /// Maximum number of concurrent subscriptions per connection.
+    pub(super) max_subscriptions: u32,
+}
+
+/// The subscriptions of a single connection.
+#[derive(Default)]
+struct Subscriptions {
+    next_id: SubscriptionId,
+    tasks: HashMap<SubscriptionId, JoinHandle<()>>,
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        for task in self.tasks.values() {
+            task.abort();
+        }
+    }
+}
+
+/// Returns a boxed warp filter which upgrades requests on `RPC_WS_API_PATH` to WebSocket
+/// connections handling JSON-RPC requests.
+pub(super) fn route(context: WsContext) -> BoxedFilter<(Response,)> {
+    warp::path(RPC_WS_API_PATH)
+        .and(warp::path::end())
+        .and(warp::ws())
+        .map(move |ws: Ws| {
+            let context = context.clone();
+            ws.max_message_size(context.max_message_bytes as usize)
+                .on_upgrade(move |socket| handle_connection(socket, context))
+                .into_response()
+        })
+        .boxed()
+}
+
+/// Handles a single WebSocket connection until it is closed by either side.
+///
+/// Each message received is handled as a JSON-RPC request (or batch of requests), with responses
+/// and subscription notifications being sent as text messages.
+async fn handle_connection(socket: WebSocket, context: WsContext) {
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let (outbound_sender, mut outbound_receiver) = mpsc::channel::<Message>(OUTBOUND_BUFFER_SIZE);
+
+    let forwarder = tokio::spawn(async move {
+        while let Some(message) = outbound_receiver.recv().await {
+            let is_close = message.is_close();
+            if ws_sender.send(message).await.is_err() || is_close {
+                break;
+            }
+        }
+    });
+
+    let subscriptions = Arc::new(Mutex::new(Subscriptions::default()));
+    let handlers = connection_handlers(&context, &subscriptions, &outbound_sender);
+
+    while let Some(result) = ws_receiver.next().await {
+        let message = match result {
+            Ok(message) => message,
+            Err(error) => {
+                debug!(%error, "websocket connection error");
+                break;
+            }
+        };
+        if message.is_close() {
+            break;
+        }
+        // Pings are answered automatically, and other non-data messages can be ignored.
+        if !message.is_text() && !message.is_binary() {
+            continue;
+        }
+        if let Some(response) = casper_json_rpc::handle_message(
+            message.as_bytes(),
+            handlers.clone(),
+            ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+            context.max_batch_size as usize,
+        )
+        .await
+        {
+            if outbound_sender
+                .send(Message::text(response.to_string()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+
+    // Dropping the handlers and subscriptions cancels all of this connection's subscriptions.
+    drop(handlers);
+    drop(subscriptions);
+    forwarder.abort();
+}
+
+/// Returns the JSON-RPC handlers available via HTTP, extended with "subscribe" and "unsubscribe"
+/// handlers applicable to a single connection.
+fn connection_handlers(
+    context: &WsContext,
+    subscriptions: &Arc<Mutex<Subscriptions>>,
+    outbound_sender: &mpsc::Sender<Message>,
+) -> RequestHandlers {
+    let mut handlers = context.handlers.to_builder();
+
+    let subscribe_handler = {
+        let context = context.clone();
+        let subscriptions = Arc::clone(subscriptions);
+        let outbound_sender = outbound_sender.clone();
+        move |maybe_params| {
+            future::ready(subscribe(
+                maybe_params,
+                &context,
+                &subscriptions,
+                &outbound_sender,
+            ))
+        }
+    };
+    handlers.register_handler(SUBSCRIBE_METHOD, Arc::new(subscribe_handler));
+
+    let unsubscribe_handler = {
+        let api_version = context.api_version;
+        let subscriptions = Arc::clone(subscriptions);
+        move |maybe_params| future::ready(unsubscribe(maybe_params, api_version, &subscriptions))
+    };
+    handlers.register_handler(UNSUBSCRIBE_METHOD, Arc::new(unsubscribe_handler));
+
+    handlers.build()
+}
+
+fn subscribe(
+    maybe_params: Option<Params>,
+    context: &WsContext,
+    subscriptions: &Mutex<Subscriptions>,
+    outbound_sender: &mpsc::Sender<Message>,
+) -> Result<SubscribeResult, Error> {
+    let SubscribeParams { event_type } = try_parse_params(maybe_params)?;
+
+    let mut subscriptions = subscriptions.lock().unwrap();
+    subscriptions.tasks.retain(|_, task| !task.is_finished());
+    if subscriptions.tasks.len() >= context.max_subscriptions as usize {
+        return Err(Error::new(
+            ErrorCode::TooManySubscriptions,
+            format!(
+                "at most {} concurrent subscriptions are allowed per connection",
+                context.max_subscriptions
+            ),
+        ));
+    }
+
+    let subscription_id = subscriptions.next_id;
+    subscriptions.next_id += 1;
+    let task = tokio::spawn(send_notifications(
+        subscription_id,
+        event_type,
+        context.event_broadcaster.subscribe(),
+        outbound_sender.clone(),
+    ));
+    let _ = subscriptions.tasks.insert(subscription_id, task);
+
+    Ok(SubscribeResult {
+        api_version: context.api_version,
+        subscription_id,
+    })
+}
+
+fn unsubscribe(
+    maybe_params: Option<Params>,
+    api_version: ProtocolVersion,
+    subscriptions: &Mutex<Subscriptions>,
+) -> Result<UnsubscribeResult, Error> {
+    let UnsubscribeParams { subscription_id } = try_parse_params(maybe_params)?;
+
+    match subscriptions.lock().unwrap().tasks.remove(&subscription_id) {
+        Some(task) => {
+            task.abort();
+            Ok(UnsubscribeResult { api_version })
+        }
+        None => Err(Error::new(
+            ErrorCode::NoSuchSubscription,
+            format!("no subscription with id {}", subscription_id),
+        )),
+    }
+}
+
+/// Tries to parse the incoming JSON-RPC request's "params" field as `T`.
+fn try_parse_params<T: DeserializeOwned>(maybe_params: Option<Params>) -> Result<T, Error> {
+    let params = match maybe_params {
+        Some(params) => Value::from(params),
+        None => {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                "Missing 'params' field",
+            ))
+        }
+    };
+    serde_json::from_value::<T>(params).map_err(|error| {
+        Error::new(
+            ReservedErrorCode::InvalidParams,
+            format!("Failed to parse 'params' field: {}", error),
+        )
+    })
+}
+
+/// Sends a notification for each item of event-stream data of the given type until the node shuts
+/// down or the subscription is cancelled.
+///
+/// If the client falls too far behind, the connection is closed, as is done for clients of the
+/// event-stream server.
+async fn send_notifications(
+    subscription_id: SubscriptionId,
+    event_type: EventType,
+    mut event_receiver: broadcast::Receiver<SseData>,
+    outbound_sender: mpsc::Sender<Message>,
+) {
+    loop {
+        let data = match event_receiver.recv().await {
+            Ok(SseData::Shutdown) | Err(RecvError::Closed) => return,
+            Ok(data) => data,
+            Err(RecvError::Lagged(lagged_count)) => {
+                info!(
+                    %subscription_id,
+                    %lagged_count,
+                    "client lagged: dropping websocket connection to client"
+                );
+                let _ = outbound_sender.send(Message::close()).await;
+                return;
+            }
+        };
+        if !event_type.matches(&data) {
+            continue;
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": SUBSCRIPTION_NOTIFICATION_METHOD,
+            "params": {
+                "subscription_id": subscription_id,
+                "event": data,
+            }
+        });
+        if outbound_sender
+            .send(Message::text(notification.to_string()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use warp::test::WsClient;
+
+    use casper_json_rpc::RequestHandlersBuilder;
+    use casper_types::{testing::TestRng, EraId, PublicKey, Timestamp};
+
+    use super::*;
+    use crate::types::{Block, JsonBlock};
+
+    const GET_NOTHING: &str = "get_nothing";
+
+    async fn get_nothing(_params: Option<Params>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn context(event_broadcaster: broadcast::Sender<SseData>) -> WsContext {
+        let mut handlers = RequestHandlersBuilder::new();
+        handlers.register_handler(GET_NOTHING, Arc::new(get_nothing));
+        WsContext {
+            handlers: handlers.build(),
+            event_broadcaster,
+            api_version: ProtocolVersion::V1_0_0,
+            max_message_bytes: 1024,
+            max_batch_size: 10,
+            max_subscriptions: 1,
+        }
+    }
+
+    async fn connect(event_broadcaster: broadcast::Sender<SseData>) -> WsClient {
+        warp::test::ws()
+            .path(&format!("/{}", RPC_WS_API_PATH))
+            .handshake(route(context(event_broadcaster)))
+            .await
+            .expect("should connect")
+    }
+
+    async fn call(client: &mut WsClient, id: u64, method: &str, params: Value) -> Value {
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        client.send_text(request.to_string()).await;
+        receive(client).await
+    }
+
+    async fn receive(client: &mut WsClient) -> Value {
+        let message = client.recv().await.expect("should receive");
+        serde_json::from_slice(message.as_bytes()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_handle_request() {
+        let (event_broadcaster, _) = broadcast::channel(10);
+        let mut client = connect(event_broadcaster).await;
+
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": GET_NOTHING});
+        client.send_text(request.to_string()).await;
+        let response = receive(&mut client).await;
+        assert_eq!(response, json!({"jsonrpc": "2.0", "id": 1, "result": null}));
+    }
+
+    #[tokio::test]
+    async fn should_send_notifications_for_subscribed_event_type() {
+        let mut rng = TestRng::new();
+        let (event_broadcaster, _) = broadcast::channel(10);
+        let mut client = connect(event_broadcaster.clone()).await;
+
+        let response = call(
+            &mut client,
+            1,
+            SUBSCRIBE_METHOD,
+            json!({"event_type": "BlockAdded"}),
+        )
+        .await;
+        let subscription_id = response["result"]["subscription_id"].clone();
+        assert_eq!(subscription_id, json!(0));
+
+        let fault = SseData::Fault {
+            era_id: EraId::new(1),
+            public_key: Box::new(PublicKey::random(&mut rng)),
+            timestamp: Timestamp::now(),
+        };
+        let block = Block::random(&mut rng);
+        let block_added = SseData::BlockAdded {
+            block_hash: *block.hash(),
+            block: Box::new(JsonBlock::new(&block, None)),
+        };
+        event_broadcaster.send(fault).unwrap();
+        event_broadcaster.send(block_added.clone()).unwrap();
+
+        let notification = receive(&mut client).await;
+        assert_eq!(notification["method"], SUBSCRIPTION_NOTIFICATION_METHOD);
+        assert_eq!(notification["params"]["subscription_id"], subscription_id);
+        let received: SseData =
+            serde_json::from_value(notification["params"]["event"].clone()).unwrap();
+        assert_eq!(received, block_added);
+
+        let response = call(
+            &mut client,
+            2,
+            UNSUBSCRIBE_METHOD,
+            json!({ "subscription_id": subscription_id }),
+        )
+        .await;
+        assert!(response.get("result").is_some(), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn should_limit_subscriptions() {
+        let (event_broadcaster, _) = broadcast::channel(10);
+        let mut client = connect(event_broadcaster).await;
+
+        let params = json!({"event_type": "Step"});
+        let response = call(&mut client, 1, SUBSCRIBE_METHOD, params.clone()).await;
+        assert!(response.get("result").is_some(), "{}", response);
+
+        let response = call(&mut client, 2, SUBSCRIBE_METHOD, params).await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::TooManySubscriptions as i64)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fail_to_unsubscribe_unknown_subscription() {
+        let (event_broadcaster, _) = broadcast::channel(10);
+        let mut client = connect(event_broadcaster).await;
+
+        let response = call(
+            &mut client,
+            1,
+            UNSUBSCRIBE_METHOD,
+            json!({"subscription_id": 7}),
+        )
+        .await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::NoSuchSubscription as i64)
+        );
+    }
+}
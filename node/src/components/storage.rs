@@ -6,7 +6,8 @@
 //! * storing and loading blocks,
 //! * storing and loading deploys,
 //! * [temporary until refactored] holding `DeployMetadata` for each deploy,
-//! * keeping an index of blocks by height and
+//! * keeping an index of blocks by height,
+//! * optionally keeping an index of deploys by sending account and called contract and
 //! * [unimplemented] managing disk usage by pruning blocks and deploys from storage.
 //!
 //! Any I/O performed by the component is done on the event handling thread, this is on purpose as
@@ -30,6 +31,7 @@
 //! The storage component itself is panic free and in general reports three classes of errors:
 //! Corruption, temporary resource exhaustion and potential bugs.
 
+mod deploy_history;
 pub(crate) mod disjoint_sequences;
 mod error;
 mod lmdb_ext;
@@ -93,7 +95,7 @@ use crate::{
         ApprovalsHash, ApprovalsHashes, AvailableBlockRange, Block, BlockAndDeploys, BlockBody,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
         BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
-        BlockSignatures, BlockWithMetadata, Deploy, DeployHash, DeployHeader, DeployId,
+        BlockSignatures, BlockWithMetadata, Chainspec, Deploy, DeployHash, DeployHeader, DeployId,
         DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
         FinalizedApprovals, FinalizedBlock, LegacyDeploy, MaxTtl, NodeId, SyncLeap,
         SyncLeapIdentifier, ValueOrChunk,
@@ -101,6 +103,8 @@ use crate::{
     utils::{display_error, WithDir},
     NodeRng,
 };
+pub use deploy_history::{DeployHistoryCursor, DeployHistoryEntry};
+pub(crate) use deploy_history::{DeployHistoryKey, DeployHistoryPage};
 use disjoint_sequences::{DisjointSequences, Sequence};
 pub use error::FatalStorageError;
 use error::GetRequestError;
@@ -129,7 +133,7 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 9;
+const MAX_DB_COUNT: u32 = 10;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
 /// Name of the file created when initializing a force resync.
@@ -192,6 +196,9 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The deploy history index database.
+    #[data_size(skip)]
+    deploy_history_db: Database,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -206,6 +213,8 @@ pub struct Storage {
     key_block_height_for_activation_point: Option<u64>,
     /// Whether or not memory deduplication is enabled.
     enable_mem_deduplication: bool,
+    /// Whether or not the deploy history index is updated when execution results are written.
+    enable_deploy_history_index: bool,
     /// An in-memory pool of already loaded serialized items.
     ///
     /// Keyed by serialized item ID, contains the serialized item.
@@ -417,6 +426,7 @@ impl Storage {
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
         let approvals_hashes_db =
             env.create_db(Some("approvals_hashes"), DatabaseFlags::empty())?;
+        let deploy_history_db = env.create_db(Some("deploy_history"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
@@ -493,6 +503,7 @@ impl Storage {
 
         initialize_block_metadata_db(&env, &block_metadata_db, &deleted_block_hashes_raw)?;
         initialize_deploy_metadata_db(&env, &deploy_metadata_db, &deleted_deploy_hashes)?;
+        initialize_deploy_history_db(&env, &deploy_history_db, &deleted_block_hashes_raw)?;
 
         let metrics = registry.map(Metrics::new).transpose()?;
 
@@ -508,6 +519,7 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            deploy_history_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
@@ -515,6 +527,7 @@ impl Storage {
             activation_era,
             key_block_height_for_activation_point: None,
             enable_mem_deduplication: config.enable_mem_deduplication,
+            enable_deploy_history_index: config.enable_deploy_history_index,
             serialized_item_pool: ObjectPool::new(config.mem_pool_prune_interval),
             recent_era_count,
             max_ttl,
@@ -601,6 +614,25 @@ impl Storage {
         Ok(component)
     }
 
+    /// Opens the storage of a node which is not running, as used by the offline maintenance
+    /// commands.
+    pub(crate) fn open_offline(
+        cfg: &WithDir<Config>,
+        chainspec: &Chainspec,
+    ) -> Result<Self, FatalStorageError> {
+        Storage::new(
+            cfg,
+            None,
+            chainspec.protocol_version(),
+            chainspec.protocol_config.activation_point.era_id(),
+            &chainspec.network_config.name,
+            chainspec.deploy_config.max_ttl.into(),
+            chainspec.core_config.recent_era_count(),
+            None,
+            false,
+        )
+    }
+
     /// Reads from the state storage database.
     ///
     /// If key is non-empty, returns bytes from under the key. Otherwise returns `Ok(None)`.
@@ -908,6 +940,19 @@ impl Storage {
                 let has_deploy = txn.value_exists(self.deploy_db, deploy_id.deploy_hash())?;
                 responder.respond(has_deploy).ignore()
             }
+            StorageRequest::GetDeployHistory {
+                history_key,
+                page_start,
+                page_size,
+                responder,
+            } => {
+                if !self.enable_deploy_history_index {
+                    return Ok(responder.respond(None).ignore());
+                }
+                let page =
+                    self.read_deploy_history(*history_key, page_start.as_deref(), page_size)?;
+                responder.respond(Some(page)).ignore()
+            }
             StorageRequest::GetExecutionResults {
                 block_hash,
                 responder,
@@ -1239,6 +1284,26 @@ impl Storage {
         Ok(is_new)
     }
 
+    /// Removes the blocks below `lowest_height` from the `completed_blocks` index and stores it to
+    /// disk, so that they are no longer reported as available.
+    ///
+    /// Used before deleting the global state of those blocks.
+    pub(crate) fn raise_lowest_available_block_height(
+        &mut self,
+        lowest_height: u64,
+    ) -> Result<(), FatalStorageError> {
+        if !self.completed_blocks.remove_below(lowest_height) {
+            return Ok(());
+        }
+        self.persist_completed_blocks()?;
+        info!(
+            "Storage: removed blocks below {} from available block range: {}",
+            lowest_height,
+            self.get_available_block_range()
+        );
+        Ok(())
+    }
+
     /// Persists the completed blocks disjoint sequences state to the database.
     fn persist_completed_blocks(&mut self) -> Result<(), FatalStorageError> {
         let serialized = self
@@ -1470,10 +1535,26 @@ impl Storage {
         block_hash: &BlockHash,
         execution_results: HashMap<DeployHash, ExecutionResult>,
     ) -> Result<bool, FatalStorageError> {
+        let maybe_block_height = if self.enable_deploy_history_index {
+            let maybe_block_height = self
+                .get_single_block_header(txn, block_hash)?
+                .map(|block_header| block_header.height());
+            if maybe_block_height.is_none() {
+                warn!(%block_hash, "block header missing: not updating deploy history index");
+            }
+            maybe_block_height
+        } else {
+            None
+        };
+
         let mut transfers: Vec<Transfer> = vec![];
         for (deploy_hash, execution_result) in execution_results {
             transfers.extend(successful_transfers(&execution_result));
 
+            if let Some(block_height) = maybe_block_height {
+                self.write_deploy_history_entries(txn, block_hash, block_height, &deploy_hash)?;
+            }
+
             let mut metadata = self
                 .get_deploy_metadata(txn, &deploy_hash)?
                 .unwrap_or_default();
@@ -1507,6 +1588,75 @@ impl Storage {
         Ok(was_written)
     }
 
+    /// Adds the given deploy to the histories of its sending account and of any contracts it calls
+    /// by hash.
+    fn write_deploy_history_entries(
+        &self,
+        txn: &mut RwTransaction,
+        block_hash: &BlockHash,
+        block_height: u64,
+        deploy_hash: &DeployHash,
+    ) -> Result<(), FatalStorageError> {
+        let deploy = match txn.get_value::<_, Deploy>(self.deploy_db, deploy_hash)? {
+            Some(deploy) => deploy,
+            None => {
+                warn!(
+                    %block_hash,
+                    %deploy_hash,
+                    "deploy missing: not updating deploy history index"
+                );
+                return Ok(());
+            }
+        };
+        for history_key in DeployHistoryKey::for_deploy(&deploy) {
+            txn.put(
+                self.deploy_history_db,
+                &history_key.index_key(block_height, deploy_hash),
+                block_hash,
+                WriteFlags::empty(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Reads a page of at most `page_size` entries of the given deploy history, starting from
+    /// `maybe_page_start` or from the oldest entry if `None`.
+    fn read_deploy_history(
+        &self,
+        history_key: DeployHistoryKey,
+        maybe_page_start: Option<&DeployHistoryCursor>,
+        page_size: usize,
+    ) -> Result<DeployHistoryPage, FatalStorageError> {
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.deploy_history_db)?;
+        let mut entries = Vec::with_capacity(page_size);
+        let mut next_page_start = None;
+        for row in cursor.iter_from(history_key.page_start_key(maybe_page_start)) {
+            let (raw_key, raw_value) = match row {
+                Ok(row) => row,
+                // The cursor couldn't be positioned as there are no further entries.
+                Err(lmdb::Error::NotFound) => break,
+                Err(error) => return Err(error.into()),
+            };
+            let entry = match history_key.parse_entry(raw_key, raw_value) {
+                Some(entry) => entry,
+                None => break,
+            };
+            if entries.len() == page_size {
+                next_page_start = Some(DeployHistoryCursor {
+                    block_height: entry.block_height,
+                    deploy_hash: entry.deploy_hash,
+                });
+                break;
+            }
+            entries.push(entry);
+        }
+        Ok(DeployHistoryPage {
+            entries,
+            next_page_start,
+        })
+    }
+
     /// Writes approvals hashes to storage.
     fn write_approvals_hashes(
         &mut self,
@@ -1653,6 +1803,41 @@ impl Storage {
         Ok(result)
     }
 
+    /// Returns the height of the switch block `era_count` eras before the highest switch block, or
+    /// 0 if there are fewer switch blocks than that.
+    pub(crate) fn read_lowest_recent_block_height(
+        &self,
+        era_count: u64,
+    ) -> Result<u64, FatalStorageError> {
+        let switch_block_headers = self.read_highest_switch_block_headers(era_count + 1)?;
+        match switch_block_headers.first() {
+            Some(oldest) if switch_block_headers.len() as u64 > era_count => Ok(oldest.height()),
+            _ => Ok(0),
+        }
+    }
+
+    /// Returns the state root hashes of all stored blocks from the switch block `era_count` eras
+    /// before the highest switch block onwards, i.e. the global states of the current era and the
+    /// `era_count` eras preceding it.
+    ///
+    /// If there are fewer switch blocks than that, the state root hashes of all stored blocks are
+    /// returned.
+    pub(crate) fn read_recent_state_root_hashes(
+        &self,
+        era_count: u64,
+    ) -> Result<Vec<Digest>, FatalStorageError> {
+        let lowest_height = self.read_lowest_recent_block_height(era_count)?;
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut state_root_hashes = vec![];
+        for (_, block_hash) in self.block_height_index.range(lowest_height..) {
+            if let Some(block_header) = self.get_single_block_header(&mut txn, block_hash)? {
+                state_root_hashes.push(*block_header.state_root_hash());
+            }
+        }
+        txn.commit()?;
+        Ok(state_root_hashes)
+    }
+
     /// Retrieves the highest block header from the storage, if one exists.
     pub fn read_highest_block_height(&self) -> Option<u64> {
         self.block_height_index.keys().last().copied()
@@ -2813,6 +2998,12 @@ pub struct Config {
     pub enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     pub mem_pool_prune_interval: u16,
+    /// Whether or not to maintain an index of deploys by their sending account and by the
+    /// contracts they call.
+    ///
+    /// Only deploys whose execution results are stored while this is enabled are indexed.
+    #[serde(default)]
+    pub enable_deploy_history_index: bool,
 }
 
 impl Default for Config {
@@ -2826,6 +3017,7 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            enable_deploy_history_index: false,
         }
     }
 }
@@ -3038,6 +3230,31 @@ fn initialize_block_metadata_db(
     Ok(())
 }
 
+/// Purges entries for deleted blocks from the deploy history database.
+fn initialize_deploy_history_db(
+    env: &Environment,
+    deploy_history_db: &Database,
+    deleted_block_hashes: &HashSet<&[u8]>,
+) -> Result<(), FatalStorageError> {
+    if deleted_block_hashes.is_empty() {
+        return Ok(());
+    }
+
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.open_rw_cursor(*deploy_history_db)?;
+    for row in cursor.iter() {
+        let (_, raw_val) = row?;
+        if deleted_block_hashes.contains(raw_val) {
+            cursor.del(WriteFlags::empty())?;
+        }
+    }
+    drop(cursor);
+    txn.commit()?;
+
+    info!("deploy history database initialized");
+    Ok(())
+}
+
 /// Purges stale entries from the deploy metadata database.
 fn initialize_deploy_metadata_db(
     env: &Environment,
@@ -0,0 +1,174 @@
+//! A secondary index of deploys by the account which sent them and by the contracts they called.
+//!
+//! Each entry of the index is held in the `deploy_history` database under a key comprising the
+//! history the entry belongs to (a one-byte tag followed by the 32-byte account hash, contract hash
+//! or contract package hash), the big-endian height of the block in which the deploy was executed
+//! and the deploy hash.  The value is the block hash.  Entries of a single history are hence
+//! ordered by block height, then by deploy hash.
+//!
+//! Only calls made via `StoredContractByHash` and `StoredVersionedContractByHash` are indexed, as
+//! calls by name can't be resolved to a contract without reading global state.
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_execution_engine::core::engine_state::executable_deploy_item::{
+    ContractIdentifier, ContractPackageIdentifier, ExecutableDeployItemIdentifier,
+};
+use casper_hashing::Digest;
+use casper_types::{account::AccountHash, ContractHash, ContractPackageHash, KEY_HASH_LENGTH};
+
+use crate::types::{BlockHash, Deploy, DeployHash};
+
+const ACCOUNT_TAG: u8 = 0;
+const CONTRACT_TAG: u8 = 1;
+const CONTRACT_PACKAGE_TAG: u8 = 2;
+
+/// The length of the part of an index key identifying the history.
+const HISTORY_PREFIX_LENGTH: usize = 1 + KEY_HASH_LENGTH;
+/// The length of a full index key.
+const INDEX_KEY_LENGTH: usize = HISTORY_PREFIX_LENGTH + 8 + Digest::LENGTH;
+
+/// Identifies one of the histories held in the deploy history index.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, DataSize)]
+pub(crate) enum DeployHistoryKey {
+    /// The deploys sent by the given account.
+    Account(AccountHash),
+    /// The deploys which called the given contract by its hash.
+    Contract(ContractHash),
+    /// The deploys which called a version of the given contract package by its hash.
+    ContractPackage(ContractPackageHash),
+}
+
+impl DeployHistoryKey {
+    /// Returns the keys of all the histories to which the given deploy belongs.
+    pub(super) fn for_deploy(deploy: &Deploy) -> Vec<DeployHistoryKey> {
+        let mut keys = vec![DeployHistoryKey::Account(
+            deploy.header().account().to_account_hash(),
+        )];
+        for item in [deploy.payment(), deploy.session()] {
+            let maybe_key = match item.identifier() {
+                ExecutableDeployItemIdentifier::Contract(ContractIdentifier::Hash(
+                    contract_hash,
+                )) => Some(DeployHistoryKey::Contract(contract_hash)),
+                ExecutableDeployItemIdentifier::Package(ContractPackageIdentifier::Hash {
+                    contract_package_hash,
+                    ..
+                }) => Some(DeployHistoryKey::ContractPackage(contract_package_hash)),
+                ExecutableDeployItemIdentifier::Module
+                | ExecutableDeployItemIdentifier::Contract(ContractIdentifier::Name(_))
+                | ExecutableDeployItemIdentifier::Package(ContractPackageIdentifier::Name {
+                    ..
+                })
+                | ExecutableDeployItemIdentifier::Transfer => None,
+            };
+            if let Some(key) = maybe_key {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys
+    }
+
+    /// Returns the prefix shared by the index keys of all entries in this history.
+    fn prefix(&self) -> [u8; HISTORY_PREFIX_LENGTH] {
+        let (tag, hash) = match self {
+            DeployHistoryKey::Account(account_hash) => (ACCOUNT_TAG, account_hash.value()),
+            DeployHistoryKey::Contract(contract_hash) => (CONTRACT_TAG, contract_hash.value()),
+            DeployHistoryKey::ContractPackage(contract_package_hash) => {
+                (CONTRACT_PACKAGE_TAG, contract_package_hash.value())
+            }
+        };
+        let mut prefix = [0; HISTORY_PREFIX_LENGTH];
+        prefix[0] = tag;
+        prefix[1..].copy_from_slice(&hash);
+        prefix
+    }
+
+    /// Returns the index key for the given deploy in this history.
+    pub(super) fn index_key(&self, block_height: u64, deploy_hash: &DeployHash) -> Vec<u8> {
+        let mut key = Vec::with_capacity(INDEX_KEY_LENGTH);
+        key.extend_from_slice(&self.prefix());
+        key.extend_from_slice(&block_height.to_be_bytes());
+        key.extend_from_slice(deploy_hash.inner().as_ref());
+        key
+    }
+
+    /// Returns the index key from which to start reading a page of this history.
+    pub(super) fn page_start_key(&self, maybe_page_start: Option<&DeployHistoryCursor>) -> Vec<u8> {
+        match maybe_page_start {
+            Some(cursor) => self.index_key(cursor.block_height, &cursor.deploy_hash),
+            None => self.prefix().to_vec(),
+        }
+    }
+
+    /// Parses the given index entry, returning `None` if it doesn't belong to this history.
+    pub(super) fn parse_entry(&self, key: &[u8], value: &[u8]) -> Option<DeployHistoryEntry> {
+        if key.len() != INDEX_KEY_LENGTH || key[..HISTORY_PREFIX_LENGTH] != self.prefix() {
+            return None;
+        }
+        let mut height_bytes = [0; 8];
+        height_bytes.copy_from_slice(&key[HISTORY_PREFIX_LENGTH..HISTORY_PREFIX_LENGTH + 8]);
+        let deploy_hash = Digest::try_from(&key[HISTORY_PREFIX_LENGTH + 8..]).ok()?;
+        let block_hash = Digest::try_from(value).ok()?;
+        Some(DeployHistoryEntry {
+            deploy_hash: DeployHash::new(deploy_hash),
+            block_hash: BlockHash::new(block_hash),
+            block_height: u64::from_be_bytes(height_bytes),
+        })
+    }
+}
+
+impl Display for DeployHistoryKey {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DeployHistoryKey::Account(account_hash) => {
+                write!(formatter, "deploys sent by {}", account_hash)
+            }
+            DeployHistoryKey::Contract(contract_hash) => {
+                write!(formatter, "deploys calling {}", contract_hash)
+            }
+            DeployHistoryKey::ContractPackage(contract_package_hash) => {
+                write!(formatter, "deploys calling {}", contract_package_hash)
+            }
+        }
+    }
+}
+
+/// A deploy in the history of an account or contract.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeployHistoryEntry {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The hash of the block in which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// The height of the block in which the deploy was executed.
+    pub block_height: u64,
+}
+
+/// The position in the history of an account or contract from which a page of entries starts.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize, JsonSchema, DataSize)]
+#[serde(deny_unknown_fields)]
+pub struct DeployHistoryCursor {
+    /// The height of the block in which the first deploy of the page was executed.
+    pub block_height: u64,
+    /// The hash of the first deploy of the page.
+    pub deploy_hash: DeployHash,
+}
+
+/// A page of the history of an account or contract.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct DeployHistoryPage {
+    /// The entries of the page, ordered by block height, then by deploy hash.
+    pub(crate) entries: Vec<DeployHistoryEntry>,
+    /// The start of the following page, if there are further entries.
+    pub(crate) next_page_start: Option<DeployHistoryCursor>,
+}
@@ -208,6 +208,30 @@ impl DisjointSequences {
             true
         })
     }
+
+    /// Reduces the sequence(s), keeping all entries above and including `min_value`.
+    ///
+    /// Returns `true` if any entries were removed.
+    pub(super) fn remove_below(&mut self, min_value: u64) -> bool {
+        let mut removed_any = false;
+        self.sequences.retain_mut(|sequence| {
+            if sequence.low >= min_value {
+                // Keep this sequence unchanged.
+                return true;
+            }
+
+            removed_any = true;
+            if sequence.high < min_value {
+                // Delete this entire sequence.
+                return false;
+            }
+
+            // This sequence contains `min_value`, so keep the sequence, but raise its low value.
+            sequence.low = min_value;
+            true
+        });
+        removed_any
+    }
 }
 #[cfg(test)]
 impl DisjointSequences {
@@ -452,6 +476,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_remove_below() {
+        const SEQ_HIGH: Sequence = Sequence { high: 11, low: 9 };
+        const SEQ_MID: Sequence = Sequence { high: 6, low: 6 };
+        const SEQ_LOW: Sequence = Sequence { high: 3, low: 1 };
+        let initial_sequences = DisjointSequences {
+            sequences: vec![SEQ_HIGH, SEQ_MID, SEQ_LOW],
+        };
+
+        // Removing below the current lowest value should be a no-op.
+        let mut disjoint_sequences = initial_sequences.clone();
+        assert!(!disjoint_sequences.remove_below(SEQ_LOW.low));
+        assert_eq!(disjoint_sequences.sequences, initial_sequences.sequences);
+
+        // Removing below a value between two sequences should remove the lower sequences.
+        disjoint_sequences = initial_sequences.clone();
+        assert!(disjoint_sequences.remove_below(SEQ_MID.low));
+        assert_eq!(disjoint_sequences.sequences, vec![SEQ_HIGH, SEQ_MID]);
+
+        // Removing below a value within a sequence should raise that sequence's low value.
+        disjoint_sequences = initial_sequences.clone();
+        assert!(disjoint_sequences.remove_below(SEQ_HIGH.low + 1));
+        assert_eq!(
+            disjoint_sequences.sequences,
+            vec![new_sequence(SEQ_HIGH.high, SEQ_HIGH.low + 1)]
+        );
+
+        // Removing below a value higher than the highest value should remove all sequences.
+        disjoint_sequences = initial_sequences;
+        assert!(disjoint_sequences.remove_below(SEQ_HIGH.high + 1));
+        assert!(disjoint_sequences.sequences.is_empty());
+    }
+
     #[test]
     fn should_truncate() {
         const SEQ_HIGH: Sequence = Sequence { high: 11, low: 9 };
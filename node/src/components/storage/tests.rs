@@ -13,17 +13,20 @@ use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
+use casper_execution_engine::core::engine_state::ExecutableDeployItem;
+use casper_hashing::Digest;
 use casper_types::{
-    generate_ed25519_keypair, system::auction::UnbondingPurse, testing::TestRng, AccessRights,
-    EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
-    Transfer, Transform, TransformEntry, URef, U512,
+    account::AccountHash, bytesrepr::Bytes, generate_ed25519_keypair,
+    system::auction::UnbondingPurse, testing::TestRng, AccessRights, ContractHash, EraId,
+    ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
+    Timestamp, Transfer, Transform, TransformEntry, URef, U512,
 };
 
 use super::{
     initialize_block_metadata_db,
     lmdb_ext::{deserialize_internal, serialize_internal, TransactionExt, WriteTransactionExt},
     move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage, FORCE_RESYNC_FILE_NAME,
+    DeployHistoryCursor, DeployHistoryKey, DeployHistoryPage, Storage, FORCE_RESYNC_FILE_NAME,
 };
 use crate::{
     components::fetcher::{FetchItem, FetchResponse},
@@ -57,6 +60,7 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        enable_deploy_history_index: false,
     }
 }
 
@@ -1045,6 +1049,181 @@ fn store_execution_results_for_two_blocks() {
     );
 }
 
+/// Retrieves a page of a deploy history from a storage component.
+fn get_deploy_history(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    history_key: DeployHistoryKey,
+    page_start: Option<DeployHistoryCursor>,
+    page_size: usize,
+) -> Option<DeployHistoryPage> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetDeployHistory {
+            history_key: Box::new(history_key),
+            page_start: page_start.map(Box::new),
+            page_size,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+#[test]
+fn should_index_deploy_history() {
+    let mut harness = ComponentHarness::default();
+    let mut cfg = new_config(&harness);
+    cfg.enable_deploy_history_index = true;
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        None,
+        false,
+    )
+    .expect("could not create storage component fixture");
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let account_hash = PublicKey::from(&secret_key).to_account_hash();
+    let contract_hash = ContractHash::new([7; 32]);
+    let make_deploy = |rng: &mut TestRng, calls_contract: bool| {
+        let session = if calls_contract {
+            ExecutableDeployItem::StoredContractByHash {
+                hash: contract_hash,
+                entry_point: "call".to_string(),
+                args: Default::default(),
+            }
+        } else {
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: Default::default(),
+            }
+        };
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from_seconds(rng.gen_range(60..3600)),
+            1,
+            vec![],
+            "test_chain".to_string(),
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: Default::default(),
+            },
+            session,
+            &secret_key,
+            None,
+        )
+    };
+
+    // Store five blocks, each with two deploys from the account, one of which calls the contract.
+    let mut expected_account_deploys = vec![];
+    let mut expected_contract_calls = vec![];
+    for height in 0..5 {
+        let deploys = vec![
+            make_deploy(&mut harness.rng, true),
+            make_deploy(&mut harness.rng, false),
+        ];
+        let block = Arc::new(Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::new(0),
+            height,
+            ProtocolVersion::from_parts(1, 0, 0),
+            false,
+            deploys.iter(),
+        ));
+        let mut execution_results = HashMap::new();
+        for deploy in &deploys {
+            put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+            execution_results.insert(*deploy.hash(), harness.rng.gen());
+        }
+        put_complete_block(&mut harness, &mut storage, Arc::clone(&block));
+        put_execution_results(&mut harness, &mut storage, *block.hash(), execution_results);
+
+        let mut block_deploy_hashes: Vec<DeployHash> =
+            deploys.iter().map(|deploy| *deploy.hash()).collect();
+        block_deploy_hashes.sort();
+        expected_account_deploys.extend(
+            block_deploy_hashes
+                .into_iter()
+                .map(|deploy_hash| (deploy_hash, *block.hash(), height)),
+        );
+        expected_contract_calls.push((*deploys[0].hash(), *block.hash(), height));
+    }
+
+    // Page through the account's history, three deploys at a time.
+    let mut account_deploys = vec![];
+    let mut page_start = None;
+    loop {
+        let page = get_deploy_history(
+            &mut harness,
+            &mut storage,
+            DeployHistoryKey::Account(account_hash),
+            page_start,
+            3,
+        )
+        .expect("deploy history should be indexed");
+        assert!(page.entries.len() <= 3);
+        account_deploys.extend(page.entries);
+        page_start = page.next_page_start;
+        if page_start.is_none() {
+            break;
+        }
+    }
+    let account_deploys: Vec<_> = account_deploys
+        .into_iter()
+        .map(|entry| (entry.deploy_hash, entry.block_hash, entry.block_height))
+        .collect();
+    assert_eq!(account_deploys, expected_account_deploys);
+
+    let contract_calls: Vec<_> = get_deploy_history(
+        &mut harness,
+        &mut storage,
+        DeployHistoryKey::Contract(contract_hash),
+        None,
+        100,
+    )
+    .expect("deploy history should be indexed")
+    .entries
+    .into_iter()
+    .map(|entry| (entry.deploy_hash, entry.block_hash, entry.block_height))
+    .collect();
+    assert_eq!(contract_calls, expected_contract_calls);
+
+    // An unknown account should have an empty history.
+    let page = get_deploy_history(
+        &mut harness,
+        &mut storage,
+        DeployHistoryKey::Account(AccountHash::new([255; 32])),
+        None,
+        100,
+    )
+    .expect("deploy history should be indexed");
+    assert!(page.entries.is_empty());
+    assert!(page.next_page_start.is_none());
+}
+
+#[test]
+fn should_not_provide_deploy_history_if_not_indexed() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Deploy::random(&mut harness.rng);
+    let account_hash = deploy.header().account().to_account_hash();
+    assert!(get_deploy_history(
+        &mut harness,
+        &mut storage,
+        DeployHistoryKey::Account(account_hash),
+        None,
+        100,
+    )
+    .is_none());
+}
+
 #[test]
 fn store_random_execution_results() {
     let mut harness = ComponentHarness::default();
@@ -1199,6 +1378,7 @@ fn prepare_exec_result_with_transfer(
         effect,
         transfers: vec![],
         cost: rng.gen(),
+        events: vec![],
     };
     (exec_result, transfer)
 }
@@ -1719,6 +1899,33 @@ fn should_get_trusted_ancestor_headers() {
     assert_eq!(get_results(5), &[4]);
 }
 
+#[test]
+fn should_read_recent_state_root_hashes() {
+    let (storage, _, blocks) = create_sync_leap_test_chain(&[], false, None);
+
+    let state_root_hashes_from = |lowest_height: usize| -> Vec<Digest> {
+        blocks[lowest_height..]
+            .iter()
+            .map(|block| *block.header().state_root_hash())
+            .collect()
+    };
+
+    // The highest switch block is S10, so the two eras before it start after S4.
+    assert_eq!(
+        storage.read_recent_state_root_hashes(2).unwrap(),
+        state_root_hashes_from(4)
+    );
+    assert_eq!(
+        storage.read_recent_state_root_hashes(0).unwrap(),
+        state_root_hashes_from(10)
+    );
+    // With fewer switch blocks than requested, all blocks are included.
+    assert_eq!(
+        storage.read_recent_state_root_hashes(10).unwrap(),
+        state_root_hashes_from(0)
+    );
+}
+
 #[test]
 fn should_get_signed_block_headers() {
     let (storage, _, blocks) = create_sync_leap_test_chain(&[], false, None);
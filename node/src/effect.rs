@@ -116,8 +116,9 @@ use tracing::{debug, error, warn};
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult, GetBidsRequest,
-        GetBidsResult, QueryRequest, QueryResult,
+        self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult,
+        ExecutionPhaseCosts, GetBidsRequest, GetBidsResult, GetKeysRequest, GetKeysResult,
+        QueryRequest, QueryResult, StateDiffRequest, StateDiffResult,
     },
     shared::execution_journal::ExecutionJournal,
     storage::trie::TrieRaw,
@@ -135,23 +136,26 @@ use crate::{
             BlockSynchronizerStatus, GlobalStateSynchronizerError, GlobalStateSynchronizerResponse,
             TrieAccumulatorError, TrieAccumulatorResponse,
         },
-        consensus::{ClContext, EraDump, ProposedBlock, ValidatorChange},
+        consensus::{ClContext, EraDump, ProposedBlock, ValidatorChange, ValidatorPerformance},
         contract_runtime::{ContractRuntimeError, EraValidatorsRequest},
         deploy_acceptor,
         diagnostics_port::StopAtSpec,
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
         network::{blocklist::BlocklistJustification, FromIncoming, NetworkInsights},
+        storage::{DeployHistoryCursor, DeployHistoryKey, DeployHistoryPage},
         upgrade_watcher::NextUpgrade,
     },
-    contract_runtime::SpeculativeExecutionState,
+    contract_runtime::{
+        SpeculativeBatchExecutionResults, SpeculativeExecutionResult, SpeculativeExecutionState,
+    },
     failpoints::FailpointActivation,
     reactor::{main_reactor::ReactorState, EventQueueHandle, QueueKind},
     types::{
         appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
+        BlockSignatures, BlockWithMetadata, Chainspec, ChainspecRawBytes, Deploy, DeployHash,
+        DeployHeader, DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
         FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlock,
         MetaBlockState, NodeId, TrieOrChunk, TrieOrChunkId,
     },
@@ -1219,6 +1223,31 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets a page of the history of deploys sent by an account or calling a contract from
+    /// storage.
+    ///
+    /// Returns `None` if the deploy history index is not enabled.
+    pub(crate) async fn get_deploy_history_from_storage(
+        self,
+        history_key: DeployHistoryKey,
+        page_start: Option<DeployHistoryCursor>,
+        page_size: usize,
+    ) -> Option<DeployHistoryPage>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetDeployHistory {
+                history_key: Box::new(history_key),
+                page_start: page_start.map(Box::new),
+                page_size,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     pub(crate) async fn get_execution_results_from_storage(
         self,
         block_hash: BlockHash,
@@ -2029,6 +2058,42 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests a page of the keys in global state with a given prefix.
+    pub(crate) async fn get_keys(
+        self,
+        get_keys_request: GetKeysRequest,
+    ) -> Result<GetKeysResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetKeys {
+                get_keys_request,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests a page of the differences between the global state under two state root hashes.
+    pub(crate) async fn get_state_diff(
+        self,
+        state_diff_request: StateDiffRequest,
+    ) -> Result<StateDiffResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetStateDiff {
+                state_diff_request,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
     /// Returns the value of the execution results checksum stored in the ChecksumRegistry for the
     /// given state root hash.
     pub(crate) async fn get_execution_results_checksum(
@@ -2068,6 +2133,17 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Returns the performance of each validator in the current and recent eras, by era.
+    pub(crate) async fn get_consensus_validator_performance(
+        self,
+    ) -> BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>>
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::ValidatorPerformance, QueueKind::Consensus)
+            .await
+    }
+
     /// Dump consensus state for a specific era, using the supplied function to serialize the
     /// output.
     pub(crate) async fn diagnostics_port_dump_consensus_state(
@@ -2177,6 +2253,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the chainspec.
+    pub(crate) async fn get_chainspec(self) -> Arc<Chainspec>
+    where
+        REv: From<ChainspecRawBytesRequest> + Send,
+    {
+        self.make_request(
+            ChainspecRawBytesRequest::GetChainspec,
+            QueueKind::NetworkInfo,
+        )
+        .await
+    }
+
     /// Stores a set of given finalized approvals in storage.
     ///
     /// Any previously stored finalized approvals for the given hash are quietly overwritten
@@ -2205,7 +2293,8 @@ impl<REv> EffectBuilder<REv> {
         self,
         execution_prestate: SpeculativeExecutionState,
         deploy: Arc<Deploy>,
-    ) -> Result<Option<ExecutionResult>, engine_state::Error>
+        enable_trace: bool,
+    ) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error>
     where
         REv: From<ContractRuntimeRequest>,
     {
@@ -2213,6 +2302,51 @@ impl<REv> EffectBuilder<REv> {
             |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                enable_trace,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests execution of a deploy with the given standard payment amount in place of its own
+    /// payment, without commiting its effects.  The deploy need not be signed.
+    pub(crate) async fn estimate_gas(
+        self,
+        execution_prestate: SpeculativeExecutionState,
+        deploy: Arc<Deploy>,
+        payment_amount: U512,
+    ) -> Result<Option<(ExecutionResult, ExecutionPhaseCosts)>, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GasEstimation {
+                execution_prestate,
+                deploy,
+                payment_amount,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests execution of a sequence of deploys, each on top of the effects of the previous
+    /// ones, without commiting their effects.
+    pub(crate) async fn speculative_execute_deploy_batch(
+        self,
+        execution_prestate: SpeculativeExecutionState,
+        deploys: Vec<Arc<Deploy>>,
+    ) -> Result<Option<SpeculativeBatchExecutionResults>, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SpeculativeDeployBatchExecution {
+                execution_prestate,
+                deploys,
                 responder,
             },
             QueueKind::ContractRuntime,
@@ -20,15 +20,18 @@ use casper_execution_engine::{
         self,
         balance::{BalanceRequest, BalanceResult},
         era_validators::GetEraValidatorsError,
+        execution_result::ExecutionPhaseCosts,
         get_bids::{GetBidsRequest, GetBidsResult},
+        get_keys::{GetKeysRequest, GetKeysResult},
         query::{QueryRequest, QueryResult},
+        state_diff::{StateDiffRequest, StateDiffResult},
     },
     storage::trie::TrieRaw,
 };
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, TimeDiff, Timestamp, Transfer, URef,
+    PublicKey, TimeDiff, Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
@@ -37,24 +40,28 @@ use crate::{
             BlockSynchronizerStatus, GlobalStateSynchronizerError, GlobalStateSynchronizerResponse,
             TrieAccumulatorError, TrieAccumulatorResponse,
         },
-        consensus::{ClContext, ProposedBlock, ValidatorChange},
+        consensus::{ClContext, ProposedBlock, ValidatorChange, ValidatorPerformance},
         contract_runtime::EraValidatorsRequest,
         deploy_acceptor,
         diagnostics_port::StopAtSpec,
         fetcher::{FetchItem, FetchResult},
         gossiper::GossipItem,
         network::NetworkInsights,
+        storage::{DeployHistoryCursor, DeployHistoryKey, DeployHistoryPage},
         upgrade_watcher::NextUpgrade,
     },
-    contract_runtime::{ContractRuntimeError, SpeculativeExecutionState},
+    contract_runtime::{
+        ContractRuntimeError, SpeculativeBatchExecutionResults, SpeculativeExecutionResult,
+        SpeculativeExecutionState,
+    },
     effect::{AutoClosingResponder, Responder},
     reactor::main_reactor::ReactorState,
     rpcs::docs::OpenRpcSchema,
     types::{
         appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
-        BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
-        DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
+        BlockSignatures, BlockWithMetadata, Chainspec, ChainspecRawBytes, Deploy, DeployHash,
+        DeployHeader, DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
         FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlockState,
         NodeId, StatusFeed, TrieOrChunk, TrieOrChunkId,
     },
@@ -396,6 +403,18 @@ pub(crate) enum StorageRequest {
         /// Responder to call when done storing.
         responder: Responder<()>,
     },
+    /// Retrieve a page of the history of deploys sent by an account or calling a contract.
+    GetDeployHistory {
+        /// The history to retrieve.
+        history_key: Box<DeployHistoryKey>,
+        /// The entry from which to start the page, or the oldest entry if `None`.
+        page_start: Option<Box<DeployHistoryCursor>>,
+        /// The maximum number of entries in the page.
+        page_size: usize,
+        /// Responder to call with the result.  Returns `None` if the deploy history index is not
+        /// enabled.
+        responder: Responder<Option<DeployHistoryPage>>,
+    },
     GetExecutionResults {
         block_hash: BlockHash,
         responder: Responder<Option<Vec<(DeployHash, DeployHeader, ExecutionResult)>>>,
@@ -552,6 +571,9 @@ impl Display for StorageRequest {
             StorageRequest::PutExecutionResults { block_hash, .. } => {
                 write!(formatter, "put execution results for {}", block_hash)
             }
+            StorageRequest::GetDeployHistory { history_key, .. } => {
+                write!(formatter, "get history of {}", history_key)
+            }
             StorageRequest::GetExecutionResults { block_hash, .. } => {
                 write!(formatter, "get execution results for {}", block_hash)
             }
@@ -894,6 +916,22 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Returns a page of the keys in global state with a given prefix.
+    GetKeys {
+        /// Get keys request.
+        #[serde(skip_serializing)]
+        get_keys_request: GetKeysRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetKeysResult, engine_state::Error>>,
+    },
+    /// Returns a page of the differences between the global state under two state root hashes.
+    GetStateDiff {
+        /// Get state diff request.
+        #[serde(skip_serializing)]
+        state_diff_request: StateDiffRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<StateDiffResult, engine_state::Error>>,
+    },
     /// Returns the value of the execution results checksum stored in the ChecksumRegistry for the
     /// given state root hash.
     GetExecutionResultsChecksum {
@@ -927,8 +965,32 @@ pub(crate) enum ContractRuntimeRequest {
         execution_prestate: SpeculativeExecutionState,
         /// Deploy to execute.
         deploy: Arc<Deploy>,
+        /// Whether to record a trace of the host functions invoked.
+        enable_trace: bool,
         /// Results
-        responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
+        responder: Responder<Result<Option<SpeculativeExecutionResult>, engine_state::Error>>,
+    },
+    /// Execute a deploy with a maximal standard payment without commiting results, to estimate the
+    /// gas it consumes
+    GasEstimation {
+        /// Hash of a block on top of which to execute the deploy.
+        execution_prestate: SpeculativeExecutionState,
+        /// Deploy to execute, which need not be signed.
+        deploy: Arc<Deploy>,
+        /// Amount of the standard payment replacing the payment of the deploy.
+        payment_amount: U512,
+        /// Results, along with the gas consumed by the payment and session phases of the deploy.
+        responder:
+            Responder<Result<Option<(ExecutionResult, ExecutionPhaseCosts)>, engine_state::Error>>,
+    },
+    /// Execute a sequence of deploys, each on top of the previous ones, without commiting results
+    SpeculativeDeployBatchExecution {
+        /// Hash of a block on top of which to execute the deploys.
+        execution_prestate: SpeculativeExecutionState,
+        /// Deploys to execute, in order.
+        deploys: Vec<Arc<Deploy>>,
+        /// Results, along with the gas consumed by the payment and session phases of each deploy.
+        responder: Responder<Result<Option<SpeculativeBatchExecutionResults>, engine_state::Error>>,
     },
 }
 
@@ -954,6 +1016,20 @@ impl Display for ContractRuntimeRequest {
             } => {
                 write!(formatter, "get bids request: {:?}", get_bids_request)
             }
+            ContractRuntimeRequest::GetKeys {
+                get_keys_request, ..
+            } => {
+                write!(formatter, "get keys request: {:?}", get_keys_request)
+            }
+            ContractRuntimeRequest::GetStateDiff {
+                state_diff_request, ..
+            } => {
+                write!(
+                    formatter,
+                    "get state diff request: {:?}",
+                    state_diff_request
+                )
+            }
             ContractRuntimeRequest::GetExecutionResultsChecksum {
                 state_root_hash, ..
             } => write!(
@@ -984,6 +1060,30 @@ impl Display for ContractRuntimeRequest {
                     execution_prestate.state_root_hash
                 )
             }
+            ContractRuntimeRequest::GasEstimation {
+                execution_prestate,
+                deploy,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "Estimate gas of {} on {}",
+                    deploy.hash(),
+                    execution_prestate.state_root_hash
+                )
+            }
+            ContractRuntimeRequest::SpeculativeDeployBatchExecution {
+                execution_prestate,
+                deploys,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "Execute batch of {} deploys on {}",
+                    deploys.len(),
+                    execution_prestate.state_root_hash
+                )
+            }
         }
     }
 }
@@ -1076,6 +1176,8 @@ pub(crate) enum ConsensusRequest {
     Status(Responder<Option<(PublicKey, Option<TimeDiff>)>>),
     /// Request for a list of validator status changes, by public key.
     ValidatorChanges(Responder<BTreeMap<PublicKey, Vec<(EraId, ValidatorChange)>>>),
+    /// Request for the performance of each validator in the current and recent eras.
+    ValidatorPerformance(Responder<BTreeMap<EraId, BTreeMap<PublicKey, ValidatorPerformance>>>),
 }
 
 /// ChainspecLoader component requests.
@@ -1084,6 +1186,8 @@ pub(crate) enum ChainspecRawBytesRequest {
     /// Request for the chainspec file bytes with the genesis_accounts and global_state bytes, if
     /// they are present.
     GetChainspecRawBytes(Responder<Arc<ChainspecRawBytes>>),
+    /// Request for the chainspec.
+    GetChainspec(Responder<Arc<Chainspec>>),
 }
 
 impl Display for ChainspecRawBytesRequest {
@@ -1092,6 +1196,7 @@ impl Display for ChainspecRawBytesRequest {
             ChainspecRawBytesRequest::GetChainspecRawBytes(_) => {
                 write!(f, "get chainspec raw bytes")
             }
+            ChainspecRawBytesRequest::GetChainspec(_) => write!(f, "get chainspec"),
         }
     }
 }
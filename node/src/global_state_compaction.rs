@@ -0,0 +1,141 @@
+//! Offline compaction of the global state store.
+//!
+//! Deletes every trie which is not reachable from the global state of a block in the most recent
+//! eras, reclaiming the space taken by global state which is no longer needed after pruning.  The
+//! node must not be running while its store is compacted.
+
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_execution_engine::storage::{
+    error::Error as StorageError,
+    transaction_source::lmdb::LmdbEnvironment,
+    trie_store::{compaction, lmdb::LmdbTrieStore},
+};
+
+use crate::{
+    components::{
+        contract_runtime::GlobalStateBackend,
+        storage::{FatalStorageError, Storage},
+    },
+    reactor::main_reactor::Config,
+    types::{chainspec, Chainspec, ChainspecRawBytes},
+    utils::{Loadable, WithDir},
+};
+
+/// Error returned as a result of compacting the global state store.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error reading the block store.
+    #[error("block storage error: {0}")]
+    BlockStorage(#[from] FatalStorageError),
+
+    /// Error accessing the global state store.
+    #[error("global state storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    /// The configured global state backend does not support compaction.
+    #[error("global state compaction is not supported by the {0:?} backend")]
+    UnsupportedBackend(GlobalStateBackend),
+
+    /// There are no blocks whose global state could be retained.
+    #[error("no blocks found in storage; refusing to delete all global state")]
+    NoRetainedBlocks,
+}
+
+impl From<lmdb::Error> for Error {
+    fn from(error: lmdb::Error) -> Self {
+        Error::Storage(error.into())
+    }
+}
+
+/// Summary of a compaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct CompactionSummary {
+    /// The number of state root hashes whose global state was retained.
+    pub(crate) retained_root_count: usize,
+    /// The number of tries retained.
+    pub(crate) retained_trie_count: u64,
+    /// The number of tries deleted.
+    pub(crate) deleted_trie_count: u64,
+}
+
+/// Compacts the global state store of the node configured by `config`, retaining the global state
+/// of every block in the last `retained_eras` eras.
+///
+/// If `retained_eras` is `None`, the contract runtime's `global_state_compaction_retained_eras` is
+/// used.
+pub(crate) fn compact_global_state(
+    config: WithDir<Config>,
+    retained_eras: Option<u64>,
+) -> Result<CompactionSummary, Error> {
+    let (chainspec, _) =
+        <(Chainspec, ChainspecRawBytes)>::from_path(config.dir()).map_err(Error::LoadChainspec)?;
+    let (root_dir, config) = config.into_parts();
+
+    let backend = config.contract_runtime.global_state_backend_or_default();
+    if backend != GlobalStateBackend::Lmdb {
+        return Err(Error::UnsupportedBackend(backend));
+    }
+    let retained_eras = retained_eras.unwrap_or_else(|| {
+        config
+            .contract_runtime
+            .global_state_compaction_retained_eras_or_default()
+    });
+
+    let mut storage =
+        Storage::open_offline(&WithDir::new(root_dir, config.storage.clone()), &chainspec)?;
+    let retained_roots = storage.read_recent_state_root_hashes(retained_eras)?;
+    if retained_roots.is_empty() {
+        return Err(Error::NoRetainedBlocks);
+    }
+    // The global state of older blocks is about to be deleted, so stop reporting them as
+    // available.
+    let lowest_retained_height = storage.read_lowest_recent_block_height(retained_eras)?;
+    storage.raise_lowest_available_block_height(lowest_retained_height)?;
+
+    let environment = LmdbEnvironment::new(
+        storage.root_path(),
+        config.contract_runtime.max_global_state_size_or_default(),
+        config.contract_runtime.max_readers_or_default(),
+        false,
+    )?;
+    let trie_store = LmdbTrieStore::open(&environment, None)?;
+    info!(retained_eras, "compacting global state");
+    let (mark_summary, deleted_trie_count) = compaction::compact(
+        &environment,
+        &trie_store,
+        &retained_roots,
+        config
+            .contract_runtime
+            .global_state_compaction_batch_size_or_default(),
+        Some(
+            config
+                .contract_runtime
+                .global_state_compaction_max_marked_tries_or_default(),
+        ),
+    )?;
+    if !mark_summary.missing.is_empty() {
+        warn!(
+            missing_count = mark_summary.missing.len(),
+            "global state of some retained blocks is incomplete"
+        );
+    }
+
+    let summary = CompactionSummary {
+        retained_root_count: retained_roots.len(),
+        retained_trie_count: mark_summary.marked_count,
+        deleted_trie_count,
+    };
+    info!(
+        retained_root_count = summary.retained_root_count,
+        retained_trie_count = summary.retained_trie_count,
+        deleted_trie_count = summary.deleted_trie_count,
+        "compacted global state"
+    );
+    Ok(summary)
+}
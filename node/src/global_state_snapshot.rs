@@ -0,0 +1,751 @@
+//! Export and import of global state snapshots.
+//!
+//! A snapshot holds every trie node reachable from a single state root hash, allowing a node's
+//! global state store to be populated offline rather than via trie-by-trie synchronization.
+//!
+//! The snapshot file consists of:
+//!
+//! * a header: the magic bytes [`SNAPSHOT_MAGIC`], the little-endian `u32` format version and the
+//!   state root hash
+//! * a sequence of chunks, each being the little-endian `u32` count of tries in the chunk, the
+//!   little-endian `u32` length of the chunk's payload, the payload itself and the BLAKE2b hash of
+//!   the payload.  The payload is the concatenation of the tries, each prefixed by its
+//!   little-endian `u32` length
+//! * a terminating chunk holding no tries.
+//!
+//! Tries are written in post-order, i.e. every trie's children precede it, and the root comes
+//! last.  This allows the importer to verify every trie's hash as it is written, and ensures an
+//! interrupted import never leaves a trie in the store with missing descendants.
+
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use lmdb::DatabaseFlags;
+use thiserror::Error;
+use tracing::info;
+
+use casper_execution_engine::storage::{
+    error::Error as StorageError,
+    store::Store,
+    transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
+    trie::Trie,
+    trie_store::lmdb::LmdbTrieStore,
+};
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    Key, StoredValue,
+};
+
+use crate::{
+    reactor::main_reactor::Config,
+    types::{chainspec, Chainspec, ChainspecRawBytes},
+    utils::{Loadable, WithDir},
+};
+
+/// The magic bytes at the start of every snapshot file.
+const SNAPSHOT_MAGIC: [u8; 8] = *b"CSPRGSS\0";
+/// The current version of the snapshot file format.
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+/// The payload size at which a chunk is completed and written out.
+///
+/// Chunks can exceed this if a single trie is larger than it.
+const CHUNK_SIZE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Error returned as a result of exporting or importing a global state snapshot.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error accessing the global state store.
+    #[error("global state storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    /// Error opening the snapshot file.
+    #[error("error opening snapshot file {path}: {error}")]
+    OpenSnapshot {
+        /// The file path.
+        path: String,
+        /// The IO error.
+        error: io::Error,
+    },
+
+    /// Error reading or writing the snapshot file.
+    #[error("snapshot I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The state root hash to export is not in the global state store.
+    #[error("state root hash {0} not found in global state")]
+    RootNotFound(Digest),
+
+    /// A trie reachable from the state root hash is not in the global state store.
+    #[error("trie {0} not found in global state")]
+    MissingTrie(Digest),
+
+    /// A trie could not be parsed.
+    #[error("error parsing trie {trie_hash}: {error}")]
+    ParseTrie {
+        /// The hash of the trie.
+        trie_hash: Digest,
+        /// The parsing error.
+        error: bytesrepr::Error,
+    },
+
+    /// A trie or chunk is too large to be written to a snapshot.
+    #[error("trie of {0} bytes is too large for a snapshot")]
+    TrieTooLarge(usize),
+
+    /// The file is not a snapshot.
+    #[error("not a global state snapshot")]
+    NotASnapshot,
+
+    /// The snapshot has an unsupported format version.
+    #[error("unsupported snapshot format version {0}")]
+    UnsupportedVersion(u32),
+
+    /// The snapshot ended before its terminating chunk.
+    #[error("snapshot is truncated")]
+    Truncated,
+
+    /// The snapshot has data after its terminating chunk.
+    #[error("snapshot has trailing data after its final chunk")]
+    TrailingData,
+
+    /// A chunk's payload does not match its checksum.
+    #[error("checksum mismatch in chunk {chunk_index}")]
+    ChecksumMismatch {
+        /// The zero-based index of the chunk.
+        chunk_index: u64,
+    },
+
+    /// A chunk's payload does not hold the number of tries given in its header.
+    #[error("malformed chunk {chunk_index}")]
+    MalformedChunk {
+        /// The zero-based index of the chunk.
+        chunk_index: u64,
+    },
+
+    /// A trie references children which neither precede it in the snapshot nor are in the store.
+    #[error("trie {trie_hash} has {} missing children", missing_children.len())]
+    MissingChildren {
+        /// The hash of the trie.
+        trie_hash: Digest,
+        /// The hashes of the missing children.
+        missing_children: Vec<Digest>,
+    },
+
+    /// The snapshot's tries do not form a single trie under the expected state root hash.
+    #[error(
+        "snapshot tries do not form the global state under {expected}; {unreferenced_count} tries \
+        are unreferenced"
+    )]
+    RootMismatch {
+        /// The state root hash the snapshot should hold.
+        expected: Digest,
+        /// The number of imported tries which are not referenced by any other imported trie.
+        unreferenced_count: usize,
+    },
+
+    /// The snapshot is of a different state root hash than the one required.
+    #[error("snapshot is of state root hash {actual}, expected {expected}")]
+    UnexpectedStateRootHash {
+        /// The required state root hash.
+        expected: Digest,
+        /// The state root hash of the snapshot.
+        actual: Digest,
+    },
+}
+
+impl From<lmdb::Error> for Error {
+    fn from(error: lmdb::Error) -> Self {
+        Error::Storage(error.into())
+    }
+}
+
+/// Summary of an exported or imported snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SnapshotSummary {
+    /// The state root hash of the snapshot.
+    pub(crate) state_root_hash: Digest,
+    /// The number of tries in the snapshot.
+    pub(crate) trie_count: u64,
+    /// The number of non-terminating chunks in the snapshot.
+    pub(crate) chunk_count: u64,
+}
+
+/// Exports the global state under `state_root_hash` from the store of the node configured by
+/// `config` to a new snapshot file at `output`.
+pub(crate) fn export_global_state(
+    config: WithDir<Config>,
+    state_root_hash: Digest,
+    output: &Path,
+) -> Result<SnapshotSummary, Error> {
+    let (environment, trie_store) = open_global_state_store(config, false)?;
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(output)
+        .map_err(|error| Error::OpenSnapshot {
+            path: output.display().to_string(),
+            error,
+        })?;
+    let summary = write_snapshot(
+        &environment,
+        &trie_store,
+        state_root_hash,
+        BufWriter::new(file),
+    )?;
+    info!(
+        path = %output.display(),
+        %state_root_hash,
+        trie_count = summary.trie_count,
+        chunk_count = summary.chunk_count,
+        "exported global state snapshot"
+    );
+    Ok(summary)
+}
+
+/// Imports the snapshot file at `input` into the global state store of the node configured by
+/// `config`, which is created if it doesn't exist.
+///
+/// If `expected_state_root_hash` is given, the snapshot must be of that state root hash.
+pub(crate) fn import_global_state(
+    config: WithDir<Config>,
+    input: &Path,
+    expected_state_root_hash: Option<Digest>,
+) -> Result<SnapshotSummary, Error> {
+    let file = File::open(input).map_err(|error| Error::OpenSnapshot {
+        path: input.display().to_string(),
+        error,
+    })?;
+    let (environment, trie_store) = open_global_state_store(config, true)?;
+    let summary = read_snapshot(
+        &environment,
+        &trie_store,
+        BufReader::new(file),
+        expected_state_root_hash,
+    )?;
+    info!(
+        path = %input.display(),
+        state_root_hash = %summary.state_root_hash,
+        trie_count = summary.trie_count,
+        chunk_count = summary.chunk_count,
+        "imported global state snapshot"
+    );
+    Ok(summary)
+}
+
+/// Opens the global state store of the node configured by `config`, creating its trie database
+/// if `create` is true.
+fn open_global_state_store(
+    config: WithDir<Config>,
+    create: bool,
+) -> Result<(LmdbEnvironment, LmdbTrieStore), Error> {
+    let (chainspec, _) =
+        <(Chainspec, ChainspecRawBytes)>::from_path(config.dir()).map_err(Error::LoadChainspec)?;
+    let storage_dir = config
+        .with_dir(config.value().storage.path.clone())
+        .join(chainspec.network_config.name);
+    let config = config.value();
+
+    let environment = LmdbEnvironment::new(
+        storage_dir,
+        config.contract_runtime.max_global_state_size_or_default(),
+        config.contract_runtime.max_readers_or_default(),
+        false,
+    )?;
+    let trie_store = if create {
+        LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())?
+    } else {
+        LmdbTrieStore::open(&environment, None)?
+    };
+    Ok((environment, trie_store))
+}
+
+/// Returns the hashes of the children of the given trie.
+fn trie_children(trie_hash: Digest, trie_bytes: &[u8]) -> Result<Vec<Digest>, Error> {
+    let trie: Trie<Key, StoredValue> = bytesrepr::deserialize_from_slice(trie_bytes)
+        .map_err(|error| Error::ParseTrie { trie_hash, error })?;
+    Ok(trie.iter_children().collect())
+}
+
+/// Writes the snapshot chunks, buffering tries until a chunk is full.
+struct ChunkWriter<W: Write> {
+    writer: W,
+    payload: Vec<u8>,
+    payload_trie_count: u32,
+    trie_count: u64,
+    chunk_count: u64,
+}
+
+impl<W: Write> ChunkWriter<W> {
+    fn new(mut writer: W, state_root_hash: Digest) -> Result<Self, Error> {
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(state_root_hash.as_ref())?;
+        Ok(ChunkWriter {
+            writer,
+            payload: Vec::with_capacity(CHUNK_SIZE_BYTES),
+            payload_trie_count: 0,
+            trie_count: 0,
+            chunk_count: 0,
+        })
+    }
+
+    fn push(&mut self, trie_bytes: &[u8]) -> Result<(), Error> {
+        let length =
+            u32::try_from(trie_bytes.len()).map_err(|_| Error::TrieTooLarge(trie_bytes.len()))?;
+        self.payload.extend_from_slice(&length.to_le_bytes());
+        self.payload.extend_from_slice(trie_bytes);
+        self.payload_trie_count += 1;
+        self.trie_count += 1;
+        if self.payload.len() >= CHUNK_SIZE_BYTES {
+            self.write_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn write_chunk(&mut self) -> Result<(), Error> {
+        let payload_length = u32::try_from(self.payload.len())
+            .map_err(|_| Error::TrieTooLarge(self.payload.len()))?;
+        self.writer
+            .write_all(&self.payload_trie_count.to_le_bytes())?;
+        self.writer.write_all(&payload_length.to_le_bytes())?;
+        self.writer.write_all(&self.payload)?;
+        self.writer
+            .write_all(Digest::hash(&self.payload).as_ref())?;
+        if self.payload_trie_count > 0 {
+            self.chunk_count += 1;
+        }
+        self.payload.clear();
+        self.payload_trie_count = 0;
+        Ok(())
+    }
+
+    /// Writes any buffered tries followed by the terminating chunk.
+    fn finish(mut self) -> Result<(u64, u64), Error> {
+        if self.payload_trie_count > 0 {
+            self.write_chunk()?;
+        }
+        self.write_chunk()?;
+        self.writer.flush()?;
+        Ok((self.trie_count, self.chunk_count))
+    }
+}
+
+/// The traversal steps of the post-order walk of the trie.
+enum Visit {
+    /// The trie's children are yet to be visited.
+    Enter(Digest),
+    /// The trie's children have all been written; the trie itself is next.
+    Exit(Bytes),
+}
+
+/// Writes a snapshot of the global state under `state_root_hash` to `writer`.
+///
+/// The whole trie is read in a single read transaction, so the snapshot is consistent even if the
+/// store is being written to concurrently.
+fn write_snapshot<W: Write>(
+    environment: &LmdbEnvironment,
+    trie_store: &LmdbTrieStore,
+    state_root_hash: Digest,
+    writer: W,
+) -> Result<SnapshotSummary, Error> {
+    let txn = environment.create_read_txn()?;
+    let mut chunk_writer = ChunkWriter::new(writer, state_root_hash)?;
+    let mut stack = vec![Visit::Enter(state_root_hash)];
+    while let Some(visit) = stack.pop() {
+        match visit {
+            Visit::Enter(trie_hash) => {
+                let trie_bytes =
+                    Store::<Digest, Trie<Key, StoredValue>>::get_raw(trie_store, &txn, &trie_hash)?
+                        .ok_or(if trie_hash == state_root_hash {
+                            Error::RootNotFound(trie_hash)
+                        } else {
+                            Error::MissingTrie(trie_hash)
+                        })?;
+                let children = trie_children(trie_hash, &trie_bytes)?;
+                stack.push(Visit::Exit(trie_bytes));
+                stack.extend(children.into_iter().rev().map(Visit::Enter));
+            }
+            Visit::Exit(trie_bytes) => chunk_writer.push(&trie_bytes)?,
+        }
+    }
+    txn.commit()?;
+    let (trie_count, chunk_count) = chunk_writer.finish()?;
+    Ok(SnapshotSummary {
+        state_root_hash,
+        trie_count,
+        chunk_count,
+    })
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    read_exact(reader, &mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_digest<R: Read>(reader: &mut R) -> Result<Digest, Error> {
+    let mut bytes = [0; Digest::LENGTH];
+    read_exact(reader, &mut bytes)?;
+    Ok(Digest::from(bytes))
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<(), Error> {
+    reader.read_exact(buffer).map_err(|error| {
+        if error.kind() == io::ErrorKind::UnexpectedEof {
+            Error::Truncated
+        } else {
+            Error::Io(error)
+        }
+    })
+}
+
+/// Splits a chunk's payload into its tries.
+fn split_payload(payload: &[u8], trie_count: u32, chunk_index: u64) -> Result<Vec<&[u8]>, Error> {
+    let malformed = || Error::MalformedChunk { chunk_index };
+    let mut tries = Vec::with_capacity(trie_count as usize);
+    let mut remainder = payload;
+    for _ in 0..trie_count {
+        if remainder.len() < 4 {
+            return Err(malformed());
+        }
+        let (length_bytes, rest) = remainder.split_at(4);
+        let length = u32::from_le_bytes(<[u8; 4]>::try_from(length_bytes).unwrap()) as usize;
+        if rest.len() < length {
+            return Err(malformed());
+        }
+        let (trie_bytes, rest) = rest.split_at(length);
+        tries.push(trie_bytes);
+        remainder = rest;
+    }
+    if !remainder.is_empty() {
+        return Err(malformed());
+    }
+    Ok(tries)
+}
+
+/// Reads a snapshot from `reader`, verifying and writing its tries to the trie store.
+///
+/// Each chunk is written in a single read-write transaction after its checksum has been verified.
+/// Every trie's children must already be in the store, so every trie written is complete.  Once
+/// all chunks are written, the tries must form a single trie under the snapshot's state root hash.
+fn read_snapshot<R: Read>(
+    environment: &LmdbEnvironment,
+    trie_store: &LmdbTrieStore,
+    mut reader: R,
+    expected_state_root_hash: Option<Digest>,
+) -> Result<SnapshotSummary, Error> {
+    let mut magic = [0; SNAPSHOT_MAGIC.len()];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|_| Error::NotASnapshot)?;
+    if magic != SNAPSHOT_MAGIC {
+        return Err(Error::NotASnapshot);
+    }
+    let version = read_u32(&mut reader)?;
+    if version != SNAPSHOT_FORMAT_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    let state_root_hash = read_digest(&mut reader)?;
+    if let Some(expected) = expected_state_root_hash {
+        if expected != state_root_hash {
+            return Err(Error::UnexpectedStateRootHash {
+                expected,
+                actual: state_root_hash,
+            });
+        }
+    }
+
+    // The hashes of tries written so far which are not yet referenced by a subsequent trie.
+    // Since tries are in post-order, this never holds more than a few tries per level of the trie.
+    let mut unreferenced = HashSet::new();
+    let mut trie_count = 0;
+    let mut chunk_index = 0;
+    loop {
+        let chunk_trie_count = read_u32(&mut reader)?;
+        let payload_length = read_u32(&mut reader)?;
+        let mut payload = vec![0; payload_length as usize];
+        read_exact(&mut reader, &mut payload)?;
+        let checksum = read_digest(&mut reader)?;
+        if Digest::hash(&payload) != checksum {
+            return Err(Error::ChecksumMismatch { chunk_index });
+        }
+        if chunk_trie_count == 0 {
+            if !payload.is_empty() {
+                return Err(Error::MalformedChunk { chunk_index });
+            }
+            break;
+        }
+
+        let mut txn = environment.create_read_write_txn()?;
+        for trie_bytes in split_payload(&payload, chunk_trie_count, chunk_index)? {
+            let trie_hash = Digest::hash_into_chunks_if_necessary(trie_bytes);
+            let mut missing_children = vec![];
+            for child in trie_children(trie_hash, trie_bytes)? {
+                if !unreferenced.remove(&child)
+                    && Store::<Digest, Trie<Key, StoredValue>>::get_raw(trie_store, &txn, &child)?
+                        .is_none()
+                {
+                    missing_children.push(child);
+                }
+            }
+            if !missing_children.is_empty() {
+                return Err(Error::MissingChildren {
+                    trie_hash,
+                    missing_children,
+                });
+            }
+            Store::<Digest, Trie<Key, StoredValue>>::put_raw(
+                trie_store,
+                &mut txn,
+                &trie_hash,
+                trie_bytes.into(),
+            )?;
+            unreferenced.insert(trie_hash);
+        }
+        txn.commit()?;
+        trie_count += u64::from(chunk_trie_count);
+        chunk_index += 1;
+    }
+
+    let mut trailing = [0; 1];
+    if reader.read(&mut trailing)? != 0 {
+        return Err(Error::TrailingData);
+    }
+    environment.sync()?;
+
+    if unreferenced.len() != 1 || !unreferenced.contains(&state_root_hash) {
+        return Err(Error::RootMismatch {
+            expected: state_root_hash,
+            unreferenced_count: unreferenced.len(),
+        });
+    }
+
+    Ok(SnapshotSummary {
+        state_root_hash,
+        trie_count,
+        chunk_count: chunk_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use casper_execution_engine::{
+        shared::newtypes::CorrelationId,
+        storage::global_state::{lmdb::LmdbGlobalState, StateProvider, StateReader},
+    };
+    use casper_types::{CLValue, URef};
+    use rand::Rng;
+
+    use super::*;
+    use crate::NodeRng;
+
+    const MAP_SIZE: usize = 64 * 1024 * 1024;
+
+    struct TestStore {
+        _tempdir: tempfile::TempDir,
+        global_state: LmdbGlobalState,
+    }
+
+    impl TestStore {
+        fn new() -> Self {
+            let tempdir = tempfile::tempdir().unwrap();
+            let environment =
+                Arc::new(LmdbEnvironment::new(tempdir.path(), MAP_SIZE, 512, false).unwrap());
+            let trie_store =
+                Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+            let global_state = LmdbGlobalState::empty(environment, trie_store).unwrap();
+            TestStore {
+                _tempdir: tempdir,
+                global_state,
+            }
+        }
+
+        fn environment(&self) -> &LmdbEnvironment {
+            self.global_state.environment()
+        }
+
+        fn trie_store(&self) -> &LmdbTrieStore {
+            self.global_state.trie_store()
+        }
+    }
+
+    fn random_values(rng: &mut NodeRng, count: usize) -> HashMap<Key, StoredValue> {
+        (0..count)
+            .map(|_| {
+                let key = Key::URef(URef::new(rng.gen(), Default::default()));
+                let value = StoredValue::CLValue(CLValue::from_t(rng.gen::<u64>()).unwrap());
+                (key, value)
+            })
+            .collect()
+    }
+
+    fn populated_store(rng: &mut NodeRng) -> (TestStore, Digest, HashMap<Key, StoredValue>) {
+        let store = TestStore::new();
+        let values = random_values(rng, 300);
+        let state_root_hash = store
+            .global_state
+            .put_stored_values(
+                CorrelationId::new(),
+                store.global_state.empty_state_root_hash(),
+                values.clone(),
+            )
+            .unwrap();
+        (store, state_root_hash, values)
+    }
+
+    fn export(store: &TestStore, state_root_hash: Digest) -> Vec<u8> {
+        let mut snapshot = vec![];
+        write_snapshot(
+            store.environment(),
+            store.trie_store(),
+            state_root_hash,
+            &mut snapshot,
+        )
+        .unwrap();
+        snapshot
+    }
+
+    fn import(store: &TestStore, snapshot: &[u8]) -> Result<SnapshotSummary, Error> {
+        read_snapshot(store.environment(), store.trie_store(), snapshot, None)
+    }
+
+    #[test]
+    fn should_export_then_import() {
+        let mut rng = crate::new_rng();
+        let (source, state_root_hash, values) = populated_store(&mut rng);
+        let snapshot = export(&source, state_root_hash);
+
+        let target = TestStore::new();
+        let summary = read_snapshot(
+            target.environment(),
+            target.trie_store(),
+            snapshot.as_slice(),
+            Some(state_root_hash),
+        )
+        .unwrap();
+        assert_eq!(summary.state_root_hash, state_root_hash);
+        assert!(summary.trie_count > values.len() as u64);
+        assert_eq!(summary.chunk_count, 1);
+
+        let reader = target
+            .global_state
+            .checkout(state_root_hash)
+            .unwrap()
+            .unwrap();
+        for (key, value) in values {
+            assert_eq!(
+                reader.read(CorrelationId::new(), &key).unwrap(),
+                Some(value)
+            );
+        }
+    }
+
+    #[test]
+    fn should_fail_to_export_unknown_root() {
+        let store = TestStore::new();
+        let unknown = Digest::hash([1]);
+        let result = write_snapshot(store.environment(), store.trie_store(), unknown, vec![]);
+        assert!(matches!(result, Err(Error::RootNotFound(hash)) if hash == unknown));
+    }
+
+    #[test]
+    fn should_reject_unexpected_state_root_hash() {
+        let mut rng = crate::new_rng();
+        let (source, state_root_hash, _) = populated_store(&mut rng);
+        let snapshot = export(&source, state_root_hash);
+
+        let target = TestStore::new();
+        let expected = Digest::hash([1]);
+        let result = read_snapshot(
+            target.environment(),
+            target.trie_store(),
+            snapshot.as_slice(),
+            Some(expected),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedStateRootHash { actual, .. }) if actual == state_root_hash
+        ));
+    }
+
+    #[test]
+    fn should_reject_corrupt_or_truncated_snapshot() {
+        let mut rng = crate::new_rng();
+        let (source, state_root_hash, _) = populated_store(&mut rng);
+        let snapshot = export(&source, state_root_hash);
+        let header_length = SNAPSHOT_MAGIC.len() + 4 + Digest::LENGTH;
+
+        // Flipping a byte of the first trie's payload should fail the chunk checksum.
+        let mut corrupt = snapshot.clone();
+        corrupt[header_length + 12] ^= 0xff;
+        assert!(matches!(
+            import(&TestStore::new(), &corrupt),
+            Err(Error::ChecksumMismatch { chunk_index: 0 })
+        ));
+
+        // Missing the terminating chunk.
+        let truncated = &snapshot[..snapshot.len() - 1];
+        assert!(matches!(
+            import(&TestStore::new(), truncated),
+            Err(Error::Truncated)
+        ));
+
+        let mut not_a_snapshot = snapshot.clone();
+        not_a_snapshot[0] = b'X';
+        assert!(matches!(
+            import(&TestStore::new(), &not_a_snapshot),
+            Err(Error::NotASnapshot)
+        ));
+
+        let mut trailing = snapshot;
+        trailing.push(0);
+        assert!(matches!(
+            import(&TestStore::new(), &trailing),
+            Err(Error::TrailingData)
+        ));
+    }
+
+    #[test]
+    fn should_reject_tries_not_forming_the_state_root() {
+        let mut rng = crate::new_rng();
+        let (source, state_root_hash, _) = populated_store(&mut rng);
+        let (other_source, other_state_root_hash, _) = populated_store(&mut rng);
+
+        // A snapshot whose header claims a different root than its tries form.
+        let mut snapshot = export(&other_source, other_state_root_hash);
+        let root_offset = SNAPSHOT_MAGIC.len() + 4;
+        snapshot[root_offset..root_offset + Digest::LENGTH]
+            .copy_from_slice(state_root_hash.as_ref());
+        assert!(matches!(
+            import(&TestStore::new(), &snapshot),
+            Err(Error::RootMismatch { expected, .. }) if expected == state_root_hash
+        ));
+
+        // A snapshot with a trie preceding its children.
+        let root_bytes = source
+            .global_state
+            .get_trie_full(CorrelationId::new(), &state_root_hash)
+            .unwrap()
+            .unwrap();
+        let mut snapshot = vec![];
+        let mut chunk_writer = ChunkWriter::new(&mut snapshot, state_root_hash).unwrap();
+        chunk_writer.push(root_bytes.inner()).unwrap();
+        chunk_writer.finish().unwrap();
+        assert!(matches!(
+            import(&TestStore::new(), &snapshot),
+            Err(Error::MissingChildren { trie_hash, .. }) if trie_hash == state_root_hash
+        ));
+    }
+}
@@ -29,10 +29,14 @@ mod data_migration;
 pub(crate) mod effect;
 #[cfg_attr(not(feature = "failpoints"), path = "failpoints_disabled.rs")]
 pub(crate) mod failpoints;
+mod global_state_compaction;
+mod global_state_snapshot;
+mod zug_wal;
 
 pub mod logging;
 pub(crate) mod protocol;
 pub(crate) mod reactor;
+pub mod signer;
 #[cfg(test)]
 pub(crate) mod testing;
 pub(crate) mod tls;
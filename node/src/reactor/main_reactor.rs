@@ -61,7 +61,7 @@ use crate::{
         },
         incoming::{NetResponseIncoming, TrieResponseIncoming},
         requests::{AcceptDeployRequest, ChainspecRawBytesRequest},
-        EffectBuilder, EffectExt, Effects, GossipTarget,
+        EffectBuilder, EffectExt, EffectOptionExt, Effects, GossipTarget,
     },
     failpoints::FailpointActivation,
     fatal,
@@ -324,6 +324,9 @@ impl reactor::Reactor for MainReactor {
             MainEvent::ChainspecRawBytesRequest(
                 ChainspecRawBytesRequest::GetChainspecRawBytes(responder),
             ) => responder.respond(self.chainspec_raw_bytes.clone()).ignore(),
+            MainEvent::ChainspecRawBytesRequest(ChainspecRawBytesRequest::GetChainspec(
+                responder,
+            )) => responder.respond(self.chainspec.clone()).ignore(),
             MainEvent::EventStreamServer(event) => reactor::wrap_effects(
                 MainEvent::EventStreamServer,
                 self.event_stream_server
@@ -963,6 +966,10 @@ impl reactor::Reactor for MainReactor {
                 effects
             }
 
+            MainEvent::CreatedFinalitySignature(finality_signature) => {
+                self.handle_created_finality_signature(effect_builder, rng, *finality_signature)
+            }
+
             // DELEGATE ALL FETCHER RELEVANT EVENTS to self.fetchers.dispatch_fetcher_event(..)
             MainEvent::LegacyDeployFetcher(..)
             | MainEvent::LegacyDeployFetcherRequest(..)
@@ -1009,7 +1016,7 @@ impl reactor::Reactor for MainReactor {
 
         let trusted_hash = config.value().node.trusted_hash;
         let (root_dir, config) = config.into_parts();
-        let (our_secret_key, our_public_key) = config.consensus.load_keys(&root_dir)?;
+        let signer = config.consensus.load_signer(&root_dir)?;
         let validator_matrix = ValidatorMatrix::new(
             chainspec.core_config.finality_threshold_fraction,
             chainspec
@@ -1018,8 +1025,7 @@ impl reactor::Reactor for MainReactor {
                 .as_ref()
                 .and_then(|global_state_update| global_state_update.validators.clone()),
             chainspec.protocol_config.activation_point.era_id(),
-            our_secret_key.clone(),
-            our_public_key.clone(),
+            signer.clone(),
             chainspec.core_config.auction_delay,
         );
 
@@ -1070,7 +1076,7 @@ impl reactor::Reactor for MainReactor {
         let network = Network::new(
             config.network.clone(),
             network_identity,
-            Some((our_secret_key.clone(), our_public_key.clone())),
+            Some(signer.clone()),
             registry,
             chainspec.as_ref(),
             validator_matrix.clone(),
@@ -1082,12 +1088,18 @@ impl reactor::Reactor for MainReactor {
             registry,
         )?;
 
+        let event_stream_server = EventStreamServer::new(
+            config.event_stream_server.clone(),
+            storage.root_path().to_path_buf(),
+            protocol_version,
+        );
         let rpc_server = RpcServer::new(
             config.rpc_server.clone(),
             config.speculative_exec_server.clone(),
             protocol_version,
             chainspec.network_config.name.clone(),
             node_startup_instant,
+            event_stream_server.event_broadcaster(),
         );
         let rest_server = RestServer::new(
             config.rest_server.clone(),
@@ -1095,11 +1107,6 @@ impl reactor::Reactor for MainReactor {
             chainspec.network_config.name.clone(),
             node_startup_instant,
         );
-        let event_stream_server = EventStreamServer::new(
-            config.event_stream_server.clone(),
-            storage.root_path().to_path_buf(),
-            protocol_version,
-        );
         let diagnostics_port =
             DiagnosticsPort::new(WithDir::new(&root_dir, config.diagnostics_port));
         let shutdown_trigger = ShutdownTrigger::new();
@@ -1129,8 +1136,7 @@ impl reactor::Reactor for MainReactor {
         // consensus
         let consensus = EraSupervisor::new(
             storage.root_path(),
-            our_secret_key,
-            our_public_key,
+            signer,
             config.consensus,
             chainspec.clone(),
             registry,
@@ -1362,43 +1368,18 @@ impl MainReactor {
 
         if state.register_we_have_tried_to_sign().was_updated() {
             // When this node is a validator in this era, sign and announce.
-            if let Some(finality_signature) = self
+            if let Some(signing) = self
                 .validator_matrix
                 .create_finality_signature(block.header())
             {
                 debug!(
-                    %finality_signature,
-                    "MetaBlock: registering finality signature: {} {}",
+                    "MetaBlock: creating finality signature: {} {}",
                     block.height(),
                     block.hash(),
                 );
-
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::Storage,
-                    effect_builder
-                        .put_finality_signature_to_storage(finality_signature.clone())
-                        .ignore(),
-                ));
-
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::BlockAccumulator,
-                    self.block_accumulator.handle_event(
-                        effect_builder,
-                        rng,
-                        block_accumulator::Event::CreatedFinalitySignature {
-                            finality_signature: Box::new(finality_signature.clone()),
-                        },
-                    ),
-                ));
-
-                let era_id = finality_signature.era_id;
-                let payload = Message::FinalitySignature(Box::new(finality_signature));
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::Network,
-                    effect_builder
-                        .broadcast_message_to_validators(payload, era_id)
-                        .ignore(),
-                ));
+                effects.extend(signing.map_some(|finality_signature| {
+                    MainEvent::CreatedFinalitySignature(Box::new(finality_signature))
+                }));
             }
         }
 
@@ -1584,6 +1565,46 @@ impl MainReactor {
             ));
         }
     }
+
+    /// Stores, registers and broadcasts our own new finality signature.
+    fn handle_created_finality_signature(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        finality_signature: FinalitySignature,
+    ) -> Effects<MainEvent> {
+        debug!(
+            %finality_signature,
+            "MetaBlock: registering finality signature"
+        );
+        let mut effects = reactor::wrap_effects(
+            MainEvent::Storage,
+            effect_builder
+                .put_finality_signature_to_storage(finality_signature.clone())
+                .ignore(),
+        );
+
+        effects.extend(reactor::wrap_effects(
+            MainEvent::BlockAccumulator,
+            self.block_accumulator.handle_event(
+                effect_builder,
+                rng,
+                block_accumulator::Event::CreatedFinalitySignature {
+                    finality_signature: Box::new(finality_signature.clone()),
+                },
+            ),
+        ));
+
+        let era_id = finality_signature.era_id;
+        let payload = Message::FinalitySignature(Box::new(finality_signature));
+        effects.extend(reactor::wrap_effects(
+            MainEvent::Network,
+            effect_builder
+                .broadcast_message_to_validators(payload, era_id)
+                .ignore(),
+        ));
+        effects
+    }
 }
 
 // TEST ENABLEMENT -- used by integration tests elsewhere
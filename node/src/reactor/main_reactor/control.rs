@@ -12,11 +12,14 @@ use crate::{
     },
     effect::{EffectBuilder, EffectExt, Effects},
     fatal,
-    reactor::main_reactor::{
-        catch_up::CatchUpInstruction, genesis_instruction::GenesisInstruction,
-        keep_up::KeepUpInstruction, upgrade_shutdown::UpgradeShutdownInstruction,
-        upgrading_instruction::UpgradingInstruction, utils, validate::ValidateInstruction,
-        MainEvent, MainReactor, ReactorState,
+    reactor::{
+        self,
+        main_reactor::{
+            catch_up::CatchUpInstruction, genesis_instruction::GenesisInstruction,
+            keep_up::KeepUpInstruction, upgrade_shutdown::UpgradeShutdownInstruction,
+            upgrading_instruction::UpgradingInstruction, utils, validate::ValidateInstruction,
+            MainEvent, MainReactor, ReactorState,
+        },
     },
     types::{BlockHash, BlockHeader, BlockPayload, FinalizedBlock, MetaBlockState},
     NodeRng,
@@ -32,6 +35,7 @@ impl MainReactor {
             return fatal!(effect_builder, "exceeded reattempt tolerance").ignore();
         }
         let (delay, mut effects) = self.do_crank(effect_builder, rng);
+        effects.extend(self.maybe_start_global_state_compaction());
         effects.extend(
             async move {
                 if !delay.is_zero() {
@@ -570,6 +574,79 @@ impl MainReactor {
         }
     }
 
+    /// Starts an online compaction of the global state once per era, if enabled and the node is
+    /// keeping up with the network without syncing historical blocks.
+    fn maybe_start_global_state_compaction(&mut self) -> Effects<MainEvent> {
+        let retained_eras = match self
+            .contract_runtime
+            .global_state_compaction_retained_eras()
+        {
+            Some(retained_eras) => retained_eras,
+            None => return Effects::new(),
+        };
+        if !matches!(self.state, ReactorState::KeepUp | ReactorState::Validate) {
+            return Effects::new();
+        }
+        // The global state of historical blocks is older than the retained eras, so would be
+        // deleted as soon as it was synced.
+        if let BlockSynchronizerProgress::Syncing(..) =
+            self.block_synchronizer.historical_progress()
+        {
+            return Effects::new();
+        }
+
+        let era_id = match self.storage.read_highest_switch_block_headers(1) {
+            Ok(headers) => match headers.last() {
+                Some(header) => header.era_id(),
+                None => return Effects::new(),
+            },
+            Err(error) => {
+                error!(
+                    "{:?}: error getting recent switch block headers: {}",
+                    self.state, error
+                );
+                return Effects::new();
+            }
+        };
+        if !self
+            .contract_runtime
+            .should_start_global_state_compaction(era_id)
+        {
+            return Effects::new();
+        }
+        let retained_roots = match self.storage.read_recent_state_root_hashes(retained_eras) {
+            Ok(retained_roots) => retained_roots,
+            Err(error) => {
+                error!(
+                    "{:?}: error getting recent state root hashes: {}",
+                    self.state, error
+                );
+                return Effects::new();
+            }
+        };
+        // The global state of older blocks is about to be deleted, so stop advertising them as
+        // available before any of it is.
+        if let Err(error) = self
+            .storage
+            .read_lowest_recent_block_height(retained_eras)
+            .and_then(|lowest_height| {
+                self.storage
+                    .raise_lowest_available_block_height(lowest_height)
+            })
+        {
+            error!(
+                "{:?}: error raising the lowest available block height: {}",
+                self.state, error
+            );
+            return Effects::new();
+        }
+        reactor::wrap_effects(
+            MainEvent::ContractRuntime,
+            self.contract_runtime
+                .start_global_state_compaction(era_id, retained_roots),
+        )
+    }
+
     fn deactivate_consensus_voting(&mut self) -> Result<(), String> {
         let deactivated_era_id = self.consensus.deactivate_current_era()?;
         info!(
@@ -0,0 +1,201 @@
+//! Signing with the validator's secret key.
+//!
+//! Consensus messages, finality signatures and the consensus certificates sent in network
+//! handshakes are all signed via a [`Signer`].  The secret key is either loaded into the node
+//! process and used by a [`LocalSigner`], or held by an external signing process which the node
+//! talks to over a Unix socket via a [`RemoteSigner`].
+//!
+//! Signers which can block, i.e. all but the [`LocalSigner`], are only called on blocking threads,
+//! never on the reactor thread: consensus protocols request signatures from them asynchronously
+//! and continue once they arrive.
+//!
+//! Each request tells the signer what kind of message it is asked to sign, so that a signer can
+//! refuse to sign a Highway unit or Zug vote conflicting with one it signed before, as recorded by
+//! [`slashing_protection`].  The [`reference`] module contains a reference implementation of an
+//! external signer.
+
+mod protocol;
+pub mod reference;
+mod remote;
+pub mod slashing_protection;
+#[cfg(test)]
+mod tests;
+
+use std::{io, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{bytesrepr::Bytes, crypto, EraId, PublicKey, SecretKey, Signature};
+
+pub use remote::{RemoteSigner, RemoteSignerConfig};
+
+/// The kind of message to be signed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum MessageKind {
+    /// A Highway unit with the given sequence number.
+    HighwayUnit {
+        /// The era of the consensus protocol instance.
+        era_id: EraId,
+        /// The ID of the consensus protocol instance.
+        instance_id: Digest,
+        /// The unit's sequence number.
+        seq_number: u64,
+    },
+    /// A Zug echo of the proposal with the given hash.
+    ZugEcho {
+        /// The era of the consensus protocol instance.
+        era_id: EraId,
+        /// The ID of the consensus protocol instance.
+        instance_id: Digest,
+        /// The round in which the proposal is echoed.
+        round_id: u32,
+        /// The hash of the echoed proposal.
+        proposal_hash: Digest,
+    },
+    /// A Zug vote.
+    ZugVote {
+        /// The era of the consensus protocol instance.
+        era_id: EraId,
+        /// The ID of the consensus protocol instance.
+        instance_id: Digest,
+        /// The round in which the vote is cast.
+        round_id: u32,
+        /// Whether the vote is in favor of the round's proposal.
+        vote: bool,
+    },
+    /// A finality signature of a block.
+    FinalitySignature {
+        /// The era in which the block was created.
+        era_id: EraId,
+        /// The hash of the block.
+        block_hash: Digest,
+    },
+    /// Any other message, which cannot conflict with other messages: Highway endorsements and
+    /// pings, and consensus certificates.
+    Other,
+}
+
+/// A request to sign a message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningRequest {
+    /// The kind of message to be signed.
+    pub kind: MessageKind,
+    /// The bytes to be signed.
+    pub message: Bytes,
+}
+
+impl SigningRequest {
+    /// Creates a new request to sign the given bytes.
+    pub fn new<T: AsRef<[u8]>>(kind: MessageKind, message: T) -> Self {
+        SigningRequest {
+            kind,
+            message: Bytes::from(message.as_ref()),
+        }
+    }
+}
+
+/// Error returned when a message could not be signed.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The signer refused to sign the message.
+    #[error("signer refused to sign: {0}")]
+    Refused(String),
+
+    /// The signer failed to sign the message.
+    #[error("signer failed: {0}")]
+    Failed(String),
+
+    /// Error communicating with the signer.
+    #[error("signer I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error encoding or decoding a message exchanged with the signer.
+    #[error("invalid message exchanged with signer: {0}")]
+    InvalidMessage(#[from] serde_json::Error),
+
+    /// The signer sent a message of the wrong type.
+    #[error("unexpected response from signer")]
+    UnexpectedResponse,
+
+    /// The signature returned by the signer is invalid.
+    #[error("invalid signature from signer: {0}")]
+    InvalidSignature(crypto::Error),
+
+    /// Error reading or writing the slashing protection record.
+    #[error(transparent)]
+    SlashingProtection(#[from] slashing_protection::Error),
+}
+
+/// Signs messages with a validator's secret key.
+pub trait Signer: Send + Sync {
+    /// Returns the public key corresponding to the secret key.
+    fn public_key(&self) -> &PublicKey;
+
+    /// Returns whether signing can block, e.g. while waiting for another process or for a record
+    /// to be written to disk.
+    ///
+    /// Such a signer must not be called on the reactor thread: use [`sign_blocking`] instead.
+    fn may_block(&self) -> bool {
+        true
+    }
+
+    /// Signs the message of the given request.
+    fn sign(&self, request: SigningRequest) -> Result<Signature, Error>;
+}
+
+/// Signs the message of the given request on a blocking thread, so that the calling task doesn't
+/// block while waiting for the signer.
+///
+/// Signers which don't block sign right away, without spawning a task.
+pub(crate) async fn sign_blocking(
+    signer: Arc<dyn Signer>,
+    request: SigningRequest,
+) -> Result<Signature, Error> {
+    if !signer.may_block() {
+        return signer.sign(request);
+    }
+    tokio::task::spawn_blocking(move || signer.sign(request))
+        .await
+        .unwrap_or_else(|error| Err(Error::Failed(format!("signing task failed: {}", error))))
+}
+
+/// A signer holding the secret key in this process.
+///
+/// It signs every message it is asked to sign.
+pub struct LocalSigner {
+    secret_key: Arc<SecretKey>,
+    public_key: PublicKey,
+}
+
+impl LocalSigner {
+    /// Creates a new signer using the given secret key.
+    pub fn new(secret_key: Arc<SecretKey>) -> Self {
+        let public_key = PublicKey::from(secret_key.as_ref());
+        LocalSigner {
+            secret_key,
+            public_key,
+        }
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn may_block(&self) -> bool {
+        false
+    }
+
+    fn sign(&self, request: SigningRequest) -> Result<Signature, Error> {
+        Ok(crypto::sign(
+            request.message,
+            &self.secret_key,
+            &self.public_key,
+        ))
+    }
+}
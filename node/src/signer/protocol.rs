@@ -0,0 +1,61 @@
+//! The messages exchanged between the node and an external signer.
+//!
+//! Each message is a single line of JSON.  The node opens a new connection for every request, and
+//! the signer answers each request it reads with a single response.
+
+use std::io::{BufRead, Read, Write};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use casper_types::{PublicKey, Signature};
+
+use super::{Error, SigningRequest};
+
+/// The maximum length in bytes of a single message, including the terminating newline.
+const MAX_MESSAGE_LENGTH: u64 = 64 * 1024;
+
+/// A request sent by the node to the signer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum Request {
+    /// Requests the signer's public key.
+    PublicKey,
+    /// Requests a signature.
+    Sign(SigningRequest),
+}
+
+/// A response sent by the signer to the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) enum Response {
+    /// The signer's public key.
+    PublicKey(PublicKey),
+    /// The requested signature.
+    Signature(Signature),
+    /// The signer refused to sign, for the given reason.
+    Refused(String),
+    /// The signer failed to handle the request.
+    Error(String),
+}
+
+/// Writes the given message as a single line of JSON.
+pub(super) fn write_message<W: Write, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> Result<(), Error> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a single line of JSON, returning `None` if the stream ended before any bytes were read.
+pub(super) fn read_message<R: BufRead, T: DeserializeOwned>(
+    reader: &mut R,
+) -> Result<Option<T>, Error> {
+    let mut line = String::new();
+    let read = reader.take(MAX_MESSAGE_LENGTH).read_line(&mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(line.trim_end())?))
+}
@@ -0,0 +1,100 @@
+//! A reference implementation of an external signer.
+//!
+//! It holds the secret key, serves requests from the node on a Unix socket and refuses to sign
+//! consensus messages conflicting with ones it signed before.  The record of signed messages is
+//! optionally persisted in a [`SlashingProtectionDb`] journal, which is written to disk before a
+//! signature is returned, so that the protection holds across restarts of the signer.
+
+use std::{
+    io::{BufReader, BufWriter},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    sync::Arc,
+};
+
+use tracing::{debug, info, warn};
+
+use casper_types::{PublicKey, SecretKey};
+
+use super::{
+    protocol::{self, Request, Response},
+    slashing_protection::SlashingProtectionDb,
+    Error, LocalSigner, Signer, SigningRequest,
+};
+
+/// A signer serving signing requests on a Unix socket.
+pub struct ReferenceSigner {
+    signer: LocalSigner,
+    db: SlashingProtectionDb,
+}
+
+impl ReferenceSigner {
+    /// Creates a new signer using the given secret key.
+    ///
+    /// If `state_path` is given, the record of signed messages is kept in the slashing protection
+    /// journal at that path, otherwise it is only kept in memory.
+    pub fn new(secret_key: Arc<SecretKey>, state_path: Option<&Path>) -> Result<Self, Error> {
+        let db = match state_path {
+            Some(path) => SlashingProtectionDb::open(path)?,
+            None => SlashingProtectionDb::in_memory(),
+        };
+        Ok(ReferenceSigner {
+            signer: LocalSigner::new(secret_key),
+            db,
+        })
+    }
+
+    /// Returns the public key corresponding to the secret key.
+    pub fn public_key(&self) -> &PublicKey {
+        self.signer.public_key()
+    }
+
+    /// Serves the connections accepted by the given listener, one at a time.
+    ///
+    /// Only returns if accepting a connection fails.
+    pub fn serve(&mut self, listener: UnixListener) -> Result<(), Error> {
+        info!(public_key = %self.public_key(), "serving signing requests");
+        loop {
+            let (stream, _) = listener.accept()?;
+            if let Err(error) = self.handle_connection(stream) {
+                warn!(%error, "error handling connection");
+            }
+        }
+    }
+
+    /// Answers the requests read from the given connection until it is closed.
+    fn handle_connection(&mut self, stream: UnixStream) -> Result<(), Error> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+        while let Some(request) = protocol::read_message(&mut reader)? {
+            let response = self.handle_request(request);
+            protocol::write_message(&mut writer, &response)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the response to the given request.
+    fn handle_request(&mut self, request: Request) -> Response {
+        match request {
+            Request::PublicKey => Response::PublicKey(self.public_key().clone()),
+            Request::Sign(signing_request) => match self.sign(signing_request) {
+                Ok(response) => response,
+                Err(error) => Response::Error(error.to_string()),
+            },
+        }
+    }
+
+    /// Signs the request's message unless it conflicts with a message signed before.
+    fn sign(&mut self, request: SigningRequest) -> Result<Response, Error> {
+        match self.db.check_and_record(self.signer.public_key(), &request) {
+            Ok(()) => (),
+            Err(Error::Refused(reason)) => {
+                warn!(kind = ?request.kind, %reason, "refusing to sign");
+                return Ok(Response::Refused(reason));
+            }
+            Err(error) => return Err(error),
+        }
+        debug!(kind = ?request.kind, "signing");
+        Ok(Response::Signature(self.signer.sign(request)?))
+    }
+}
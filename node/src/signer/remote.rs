@@ -0,0 +1,102 @@
+//! A signer running in a separate process, reached via a Unix socket.
+
+use std::{
+    io::BufReader,
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{crypto, PublicKey, Signature, TimeDiff};
+
+use super::{
+    protocol::{self, Request, Response},
+    Error, Signer, SigningRequest,
+};
+
+/// The default timeout for a single request to the remote signer.
+const DEFAULT_REQUEST_TIMEOUT: TimeDiff = TimeDiff::from_seconds(2);
+
+/// Remote signer configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct RemoteSignerConfig {
+    /// Path to the Unix socket the signer listens on.
+    pub socket_path: PathBuf,
+    /// The timeout for a single request to the signer.
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: TimeDiff,
+}
+
+fn default_request_timeout() -> TimeDiff {
+    DEFAULT_REQUEST_TIMEOUT
+}
+
+/// A signer holding the secret key in a separate process.
+///
+/// Every signature returned by the signer is verified against its public key before being used.
+#[derive(Debug)]
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    timeout: Duration,
+    public_key: PublicKey,
+}
+
+impl RemoteSigner {
+    /// Connects to the signer listening on the given socket and retrieves its public key.
+    pub fn connect<P: AsRef<Path>>(socket_path: P, timeout: Duration) -> Result<Self, Error> {
+        let socket_path = socket_path.as_ref().to_path_buf();
+        match request(&socket_path, timeout, &Request::PublicKey)? {
+            Response::PublicKey(public_key) => Ok(RemoteSigner {
+                socket_path,
+                timeout,
+                public_key,
+            }),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, signing_request: SigningRequest) -> Result<Signature, Error> {
+        let message = signing_request.message.clone();
+        match request(
+            &self.socket_path,
+            self.timeout,
+            &Request::Sign(signing_request),
+        )? {
+            Response::Signature(signature) => {
+                crypto::verify(message, &signature, &self.public_key)
+                    .map_err(Error::InvalidSignature)?;
+                Ok(signature)
+            }
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+/// Sends a single request to the signer over a new connection and returns its response.
+fn request(socket_path: &Path, timeout: Duration, request: &Request) -> Result<Response, Error> {
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    protocol::write_message(&mut stream, request)?;
+    protocol::read_message(&mut BufReader::new(stream))?.ok_or(Error::UnexpectedResponse)
+}
+
+/// Converts a response which is not the expected one into an error.
+fn unexpected(response: Response) -> Error {
+    match response {
+        Response::Refused(reason) => Error::Refused(reason),
+        Response::Error(error) => Error::Failed(error),
+        Response::PublicKey(_) | Response::Signature(_) => Error::UnexpectedResponse,
+    }
+}
@@ -0,0 +1,525 @@
+//! Slashing protection: a persistent record of the consensus messages signed by validators.
+//!
+//! Before a Highway unit, Zug echo or Zug vote is signed, it is checked against the messages the
+//! same validator signed before in the same consensus protocol instance, and signing is refused if
+//! it conflicts with any of them.  Otherwise the message is recorded, and the record is flushed to
+//! disk before the signature is created.
+//!
+//! The record is kept in a journal file with one JSON-encoded [`Entry`] per line, which is
+//! compacted whenever it is opened and whenever a new era is started.  For each validator, only the
+//! eras within [`MAX_TRACKED_ERAS`] of the latest one are retained, and signing messages in eras
+//! older than that is refused.
+//!
+//! The record can be exported to and imported from an [`Interchange`] file, allowing a validator's
+//! key to be moved to a different machine without the risk of equivocating.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{bytesrepr::Bytes, EraId, PublicKey, Signature};
+
+use super::{MessageKind, Signer, SigningRequest};
+
+/// The version of the interchange format.
+pub const INTERCHANGE_FORMAT_VERSION: u32 = 1;
+
+/// The number of eras preceding a validator's latest signed era for which signed messages are
+/// retained.
+pub const MAX_TRACKED_ERAS: u64 = 10;
+
+/// Error reading or writing the slashing protection record.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error reading or writing a file.
+    #[error("slashing protection I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error encoding or decoding an entry.
+    #[error("invalid slashing protection data: {0}")]
+    InvalidData(#[from] serde_json::Error),
+
+    /// The interchange file has an unsupported format version.
+    #[error("unsupported slashing protection interchange format version {0}")]
+    UnsupportedVersion(u32),
+}
+
+/// A signed consensus message which could conflict with other messages.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum SignedMessage {
+    /// A Highway unit.
+    HighwayUnit {
+        /// The unit's sequence number.
+        seq_number: u64,
+        /// The unit's hash.
+        hash: Bytes,
+    },
+    /// A Zug echo.
+    ZugEcho {
+        /// The round in which the proposal was echoed.
+        round_id: u32,
+        /// The hash of the echoed proposal.
+        proposal_hash: Digest,
+    },
+    /// A Zug vote.
+    ZugVote {
+        /// The round in which the vote was cast.
+        round_id: u32,
+        /// Whether the vote was in favor of the round's proposal.
+        vote: bool,
+    },
+}
+
+/// A record of a message signed by a validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Entry {
+    /// The validator's public key.
+    pub public_key: PublicKey,
+    /// The era of the consensus protocol instance.
+    pub era_id: EraId,
+    /// The ID of the consensus protocol instance.
+    pub instance_id: Digest,
+    /// The signed message.
+    pub message: SignedMessage,
+}
+
+impl Entry {
+    /// Returns the entry recording the given request, or `None` if the message to be signed cannot
+    /// conflict with other messages.
+    fn from_request(public_key: &PublicKey, request: &SigningRequest) -> Option<Self> {
+        let (era_id, instance_id, message) = match &request.kind {
+            MessageKind::HighwayUnit {
+                era_id,
+                instance_id,
+                seq_number,
+            } => {
+                let message = SignedMessage::HighwayUnit {
+                    seq_number: *seq_number,
+                    hash: request.message.clone(),
+                };
+                (*era_id, *instance_id, message)
+            }
+            MessageKind::ZugEcho {
+                era_id,
+                instance_id,
+                round_id,
+                proposal_hash,
+            } => {
+                let message = SignedMessage::ZugEcho {
+                    round_id: *round_id,
+                    proposal_hash: *proposal_hash,
+                };
+                (*era_id, *instance_id, message)
+            }
+            MessageKind::ZugVote {
+                era_id,
+                instance_id,
+                round_id,
+                vote,
+            } => {
+                let message = SignedMessage::ZugVote {
+                    round_id: *round_id,
+                    vote: *vote,
+                };
+                (*era_id, *instance_id, message)
+            }
+            MessageKind::FinalitySignature { .. } | MessageKind::Other => return None,
+        };
+        Some(Entry {
+            public_key: public_key.clone(),
+            era_id,
+            instance_id,
+            message,
+        })
+    }
+}
+
+/// The portable representation of a slashing protection record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Interchange {
+    /// The format version, [`INTERCHANGE_FORMAT_VERSION`].
+    pub version: u32,
+    /// The messages signed: for each consensus protocol instance, the latest Highway unit, and all
+    /// Zug echoes and votes.
+    pub entries: Vec<Entry>,
+}
+
+/// The messages signed by a validator in a single consensus protocol instance.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct InstanceRecord {
+    /// The sequence number and hash of the latest Highway unit signed.
+    latest_unit: Option<(u64, Bytes)>,
+    /// The hash of the proposal echoed in each Zug round.
+    echoes: BTreeMap<u32, Digest>,
+    /// The vote cast in each Zug round.
+    votes: BTreeMap<u32, bool>,
+}
+
+impl InstanceRecord {
+    /// Returns whether signing the message would add to the record, or the reason for refusing to
+    /// sign it if it conflicts with a message signed before.
+    fn check(&self, message: &SignedMessage) -> Result<bool, String> {
+        match message {
+            SignedMessage::HighwayUnit { seq_number, hash } => match &self.latest_unit {
+                Some((latest_seq_number, _)) if seq_number < latest_seq_number => Err(format!(
+                    "already signed unit {}, refusing to sign unit {}",
+                    latest_seq_number, seq_number
+                )),
+                Some((latest_seq_number, latest_hash)) if seq_number == latest_seq_number => {
+                    if latest_hash == hash {
+                        Ok(false)
+                    } else {
+                        Err(format!("already signed a different unit {}", seq_number))
+                    }
+                }
+                _ => Ok(true),
+            },
+            SignedMessage::ZugEcho {
+                round_id,
+                proposal_hash,
+            } => match self.echoes.get(round_id) {
+                Some(echoed) if echoed != proposal_hash => Err(format!(
+                    "already echoed proposal {} in round {}",
+                    echoed, round_id
+                )),
+                Some(_) => Ok(false),
+                None => Ok(true),
+            },
+            SignedMessage::ZugVote { round_id, vote } => match self.votes.get(round_id) {
+                Some(voted) if voted != vote => {
+                    Err(format!("already voted {} in round {}", voted, round_id))
+                }
+                Some(_) => Ok(false),
+                None => Ok(true),
+            },
+        }
+    }
+
+    /// Adds the message to the record.
+    ///
+    /// If it conflicts with a message signed before, the record is left as it is: either way, any
+    /// message conflicting with both of them will be refused.  Only a later unit replaces the
+    /// latest one.
+    fn merge(&mut self, message: SignedMessage) {
+        match message {
+            SignedMessage::HighwayUnit { seq_number, hash } => {
+                if self
+                    .latest_unit
+                    .as_ref()
+                    .map_or(true, |(latest_seq_number, _)| {
+                        seq_number > *latest_seq_number
+                    })
+                {
+                    self.latest_unit = Some((seq_number, hash));
+                }
+            }
+            SignedMessage::ZugEcho {
+                round_id,
+                proposal_hash,
+            } => {
+                self.echoes.entry(round_id).or_insert(proposal_hash);
+            }
+            SignedMessage::ZugVote { round_id, vote } => {
+                self.votes.entry(round_id).or_insert(vote);
+            }
+        }
+    }
+
+    /// Returns the signed messages, as recorded in an interchange file.
+    fn messages(&self) -> impl Iterator<Item = SignedMessage> + '_ {
+        let unit = self
+            .latest_unit
+            .iter()
+            .map(|(seq_number, hash)| SignedMessage::HighwayUnit {
+                seq_number: *seq_number,
+                hash: hash.clone(),
+            });
+        let echoes = self
+            .echoes
+            .iter()
+            .map(|(round_id, proposal_hash)| SignedMessage::ZugEcho {
+                round_id: *round_id,
+                proposal_hash: *proposal_hash,
+            });
+        let votes = self
+            .votes
+            .iter()
+            .map(|(round_id, vote)| SignedMessage::ZugVote {
+                round_id: *round_id,
+                vote: *vote,
+            });
+        unit.chain(echoes).chain(votes)
+    }
+}
+
+/// The records of a validator's consensus protocol instances, by era and instance ID.
+type ValidatorRecord = BTreeMap<(EraId, Digest), InstanceRecord>;
+
+/// The record of the consensus messages signed by any number of validators.
+#[derive(Debug, Default)]
+pub struct SlashingProtectionDb {
+    validators: BTreeMap<PublicKey, ValidatorRecord>,
+    /// The journal file and its path, unless the record is only kept in memory.
+    journal: Option<(PathBuf, File)>,
+}
+
+impl SlashingProtectionDb {
+    /// Creates a new record which is only kept in memory.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the journal file at the given path, creating it if it doesn't exist.
+    ///
+    /// The journal is compacted, so it must not be open in any other process.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut db = Self::read(&path)?;
+        db.prune();
+        let file = db.compact(&path)?;
+        db.journal = Some((path, file));
+        Ok(db)
+    }
+
+    /// Reads the journal file at the given path without modifying it, e.g. to export it while it is
+    /// in use.  Nothing is written to the returned record's journal.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut db = Self::in_memory();
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(db),
+            Err(error) => return Err(error.into()),
+        };
+        let mut lines: Vec<&str> = contents.split('\n').collect();
+        // The last line is incomplete if writing it was interrupted, in which case no signature
+        // was created for it.
+        if let Some(incomplete) = lines.pop().filter(|line| !line.is_empty()) {
+            warn!(path = %path.display(), %incomplete, "ignoring incomplete journal entry");
+        }
+        for line in lines {
+            db.merge(serde_json::from_str(line)?);
+        }
+        Ok(db)
+    }
+
+    /// Checks whether the validator with the given public key can sign the request's message
+    /// without conflicting with a message it signed before, and if so, records it.
+    pub fn check_and_record(
+        &mut self,
+        public_key: &PublicKey,
+        request: &SigningRequest,
+    ) -> Result<(), super::Error> {
+        let entry = match Entry::from_request(public_key, request) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+        let latest_era = self.latest_era(public_key);
+        if let Some(latest_era) = latest_era {
+            if entry.era_id.value().saturating_add(MAX_TRACKED_ERAS) < latest_era.value() {
+                return Err(super::Error::Refused(format!(
+                    "era {} is too old, already signed messages in era {}",
+                    entry.era_id, latest_era
+                )));
+            }
+        }
+        let key = (entry.era_id, entry.instance_id);
+        if let Some(record) = self.validators.get(public_key).and_then(|vr| vr.get(&key)) {
+            let is_new = record.check(&entry.message).map_err(|reason| {
+                super::Error::Refused(format!("{} in era {}", reason, entry.era_id))
+            })?;
+            if !is_new {
+                return Ok(());
+            }
+        }
+        let starts_new_era = latest_era.map_or(true, |latest_era| entry.era_id > latest_era);
+        self.append(&entry)?;
+        self.merge(entry);
+        if starts_new_era {
+            self.prune();
+            self.recompact()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the record of the messages signed by the validator with the given public key, or by
+    /// all validators if `None`.
+    pub fn export(&self, public_key: Option<&PublicKey>) -> Interchange {
+        Interchange {
+            version: INTERCHANGE_FORMAT_VERSION,
+            entries: self
+                .validators
+                .iter()
+                .filter(|(pk, _)| public_key.map_or(true, |public_key| *pk == public_key))
+                .flat_map(|(pk, validator_record)| self.entries(pk, validator_record))
+                .collect(),
+        }
+    }
+
+    /// Adds the messages in the given interchange record to this one, and returns the number of
+    /// entries which were not in this record yet.
+    pub fn import(&mut self, interchange: Interchange) -> Result<usize, Error> {
+        if interchange.version != INTERCHANGE_FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion(interchange.version));
+        }
+        let mut imported = 0;
+        for entry in interchange.entries {
+            let key = (entry.era_id, entry.instance_id);
+            let record = self
+                .validators
+                .get(&entry.public_key)
+                .and_then(|validator_record| validator_record.get(&key));
+            match record.map(|record| record.check(&entry.message)) {
+                Some(Ok(false)) => continue,
+                Some(Err(reason)) => {
+                    warn!(public_key = %entry.public_key, era_id = %entry.era_id, %reason,
+                        "imported entry conflicts with recorded one");
+                }
+                Some(Ok(true)) | None => (),
+            }
+            self.append(&entry)?;
+            self.merge(entry);
+            imported += 1;
+        }
+        self.prune();
+        self.recompact()?;
+        info!(imported, "imported slashing protection entries");
+        Ok(imported)
+    }
+
+    /// Returns the latest era in which the validator with the given public key signed a message.
+    fn latest_era(&self, public_key: &PublicKey) -> Option<EraId> {
+        let validator_record = self.validators.get(public_key)?;
+        validator_record
+            .keys()
+            .next_back()
+            .map(|(era_id, _)| *era_id)
+    }
+
+    /// Adds the entry to the in-memory record.
+    fn merge(&mut self, entry: Entry) {
+        self.validators
+            .entry(entry.public_key)
+            .or_default()
+            .entry((entry.era_id, entry.instance_id))
+            .or_default()
+            .merge(entry.message);
+    }
+
+    /// Forgets the eras preceding each validator's latest one by more than [`MAX_TRACKED_ERAS`].
+    fn prune(&mut self) {
+        for validator_record in self.validators.values_mut() {
+            let oldest_era = match validator_record.keys().next_back() {
+                Some((latest_era, _)) => latest_era.saturating_sub(MAX_TRACKED_ERAS),
+                None => continue,
+            };
+            validator_record.retain(|(era_id, _), _| *era_id >= oldest_era);
+        }
+    }
+
+    /// Returns the entries of the given validator's record.
+    fn entries<'a>(
+        &'a self,
+        public_key: &'a PublicKey,
+        validator_record: &'a ValidatorRecord,
+    ) -> impl Iterator<Item = Entry> + 'a {
+        validator_record
+            .iter()
+            .flat_map(move |((era_id, instance_id), record)| {
+                record.messages().map(move |message| Entry {
+                    public_key: public_key.clone(),
+                    era_id: *era_id,
+                    instance_id: *instance_id,
+                    message,
+                })
+            })
+    }
+
+    /// Appends the entry to the journal, if any, and flushes it to disk.
+    fn append(&mut self, entry: &Entry) -> Result<(), Error> {
+        if let Some((_, file)) = &mut self.journal {
+            let mut line = serde_json::to_vec(entry)?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Compacts the journal, if any.
+    fn recompact(&mut self) -> Result<(), Error> {
+        if let Some(path) = self.journal.as_ref().map(|(path, _)| path.clone()) {
+            let file = self.compact(&path)?;
+            self.journal = Some((path, file));
+        }
+        Ok(())
+    }
+
+    /// Atomically replaces the journal at the given path with one holding only the entries of the
+    /// in-memory record, and returns it opened for appending.
+    fn compact(&self, path: &Path) -> Result<File, Error> {
+        let temp_path = path.with_extension("tmp");
+        let mut temp_file = File::create(&temp_path)?;
+        for entry in self.export(None).entries {
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            temp_file.write_all(&line)?;
+        }
+        temp_file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        Ok(OpenOptions::new().append(true).open(path)?)
+    }
+}
+
+/// A signer refusing to sign messages conflicting with ones it signed before, as recorded in a
+/// [`SlashingProtectionDb`], and passing all other requests on to another signer.
+///
+/// Each request is written to the journal and flushed to disk before it is passed on, so signing
+/// blocks and must only be done on a blocking thread, via [`sign_blocking`](super::sign_blocking).
+pub struct SlashingProtectedSigner {
+    signer: Arc<dyn Signer>,
+    db: Mutex<SlashingProtectionDb>,
+}
+
+impl SlashingProtectedSigner {
+    /// Creates a new signer protecting the given one using the given record.
+    pub fn new(signer: Arc<dyn Signer>, db: SlashingProtectionDb) -> Self {
+        SlashingProtectedSigner {
+            signer,
+            db: Mutex::new(db),
+        }
+    }
+}
+
+impl Signer for SlashingProtectedSigner {
+    fn public_key(&self) -> &PublicKey {
+        self.signer.public_key()
+    }
+
+    fn may_block(&self) -> bool {
+        // Even if the inner signer doesn't block, writing the journal does.
+        true
+    }
+
+    fn sign(&self, request: SigningRequest) -> Result<Signature, super::Error> {
+        // The request is recorded before it is signed, so that we never return a signature that
+        // the journal doesn't know about.
+        self.db
+            .lock()
+            .expect("slashing protection lock poisoned")
+            .check_and_record(self.signer.public_key(), &request)?;
+        self.signer.sign(request)
+    }
+}
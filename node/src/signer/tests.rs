@@ -0,0 +1,263 @@
+use std::{fs, os::unix::net::UnixListener, path::Path, sync::Arc, thread, time::Duration};
+
+use rand::Rng;
+use tempfile::tempdir;
+
+use casper_hashing::Digest;
+use casper_types::{crypto, testing::TestRng, EraId, PublicKey, SecretKey};
+
+use super::{
+    reference::ReferenceSigner,
+    sign_blocking,
+    slashing_protection::{SlashingProtectedSigner, SlashingProtectionDb, MAX_TRACKED_ERAS},
+    Error, LocalSigner, MessageKind, RemoteSigner, Signer, SigningRequest,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Returns the ID of the consensus protocol instance in the given era.
+fn instance_id(era_id: u64) -> Digest {
+    Digest::hash(era_id.to_le_bytes())
+}
+
+fn unit(era_id: u64, seq_number: u64, hash: &[u8]) -> SigningRequest {
+    let kind = MessageKind::HighwayUnit {
+        era_id: EraId::new(era_id),
+        instance_id: instance_id(era_id),
+        seq_number,
+    };
+    SigningRequest::new(kind, hash)
+}
+
+fn echo(era_id: u64, round_id: u32, proposal_hash: Digest) -> SigningRequest {
+    let kind = MessageKind::ZugEcho {
+        era_id: EraId::new(era_id),
+        instance_id: instance_id(era_id),
+        round_id,
+        proposal_hash,
+    };
+    SigningRequest::new(kind, proposal_hash)
+}
+
+fn vote(era_id: u64, round_id: u32, vote: bool) -> SigningRequest {
+    let kind = MessageKind::ZugVote {
+        era_id: EraId::new(era_id),
+        instance_id: instance_id(era_id),
+        round_id,
+        vote,
+    };
+    SigningRequest::new(kind, [u8::from(vote)])
+}
+
+/// Starts a reference signer on a new thread, listening on the given socket.
+fn start_reference_signer(
+    secret_key: Arc<SecretKey>,
+    socket_path: &Path,
+    state_path: &Path,
+) -> RemoteSigner {
+    let mut reference_signer = ReferenceSigner::new(secret_key, Some(state_path)).unwrap();
+    let listener = UnixListener::bind(socket_path).unwrap();
+    thread::spawn(move || reference_signer.serve(listener));
+    RemoteSigner::connect(socket_path, TIMEOUT).unwrap()
+}
+
+#[test]
+fn should_refuse_conflicting_units() {
+    let mut rng = TestRng::new();
+    let public_key = PublicKey::random(&mut rng);
+    let mut db = SlashingProtectionDb::in_memory();
+    let mut check = |request: SigningRequest| db.check_and_record(&public_key, &request);
+
+    assert!(check(unit(1, 0, b"a")).is_ok());
+    assert!(check(unit(1, 1, b"b")).is_ok());
+    // Signing the same unit again is fine.
+    assert!(check(unit(1, 1, b"b")).is_ok());
+    // A different unit with the same sequence number is a double-sign.
+    assert!(matches!(check(unit(1, 1, b"c")), Err(Error::Refused(_))));
+    // So is a unit with a lower sequence number.
+    assert!(matches!(check(unit(1, 0, b"a")), Err(Error::Refused(_))));
+    // Units in other eras don't conflict.
+    assert!(check(unit(2, 0, b"c")).is_ok());
+    assert!(check(unit(1, 2, b"c")).is_ok());
+}
+
+#[test]
+fn should_refuse_conflicting_echoes_and_votes() {
+    let mut rng = TestRng::new();
+    let public_key = PublicKey::random(&mut rng);
+    let other_public_key = PublicKey::random(&mut rng);
+    let proposal_hash = Digest::hash(rng.gen::<[u8; 32]>());
+    let mut db = SlashingProtectionDb::in_memory();
+    let mut check = |public_key: &PublicKey, request: SigningRequest| {
+        db.check_and_record(public_key, &request).is_ok()
+    };
+
+    assert!(check(&public_key, echo(1, 0, proposal_hash)));
+    assert!(check(&public_key, echo(1, 0, proposal_hash)));
+    assert!(!check(
+        &public_key,
+        echo(1, 0, Digest::hash(rng.gen::<[u8; 32]>()))
+    ));
+    assert!(check(
+        &public_key,
+        echo(1, 1, Digest::hash(rng.gen::<[u8; 32]>()))
+    ));
+
+    assert!(check(&public_key, vote(1, 0, true)));
+    assert!(check(&public_key, vote(1, 0, true)));
+    assert!(!check(&public_key, vote(1, 0, false)));
+    assert!(check(&public_key, vote(1, 1, false)));
+    assert!(check(&public_key, vote(2, 0, false)));
+
+    // Other validators' messages don't conflict.
+    assert!(check(&other_public_key, vote(1, 0, false)));
+}
+
+#[test]
+fn should_refuse_signing_in_forgotten_eras() {
+    let mut rng = TestRng::new();
+    let public_key = PublicKey::random(&mut rng);
+    let mut db = SlashingProtectionDb::in_memory();
+    let mut check = |request: SigningRequest| db.check_and_record(&public_key, &request).is_ok();
+
+    let latest_era = MAX_TRACKED_ERAS + 5;
+    assert!(check(vote(5, 0, true)));
+    assert!(check(vote(latest_era, 0, true)));
+    // Era 5 is still tracked, but the messages in eras before it were forgotten.
+    assert!(!check(vote(5, 0, false)));
+    assert!(!check(vote(4, 0, false)));
+}
+
+#[test]
+fn should_persist_record_across_restarts() {
+    let mut rng = TestRng::new();
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.jsonl");
+    let public_key = PublicKey::random(&mut rng);
+
+    let mut db = SlashingProtectionDb::open(&path).unwrap();
+    db.check_and_record(&public_key, &unit(1, 3, b"a")).unwrap();
+    db.check_and_record(&public_key, &vote(2, 7, true)).unwrap();
+    drop(db);
+
+    // Simulate a crash while writing an entry.
+    let mut contents = fs::read(&path).unwrap();
+    contents.extend_from_slice(b"{\"public_key\":");
+    fs::write(&path, contents).unwrap();
+
+    let mut db = SlashingProtectionDb::open(&path).unwrap();
+    assert!(db.check_and_record(&public_key, &unit(1, 3, b"b")).is_err());
+    assert!(db
+        .check_and_record(&public_key, &vote(2, 7, false))
+        .is_err());
+    assert!(db.check_and_record(&public_key, &unit(1, 4, b"b")).is_ok());
+}
+
+#[tokio::test]
+async fn slashing_protected_signer_should_persist_record_before_signing() {
+    let mut rng = TestRng::new();
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.jsonl");
+    let secret_key = Arc::new(SecretKey::random(&mut rng));
+    let public_key = PublicKey::from(&*secret_key);
+    let db = SlashingProtectionDb::open(&path).unwrap();
+    let signer: Arc<dyn Signer> = Arc::new(SlashingProtectedSigner::new(
+        Arc::new(LocalSigner::new(secret_key)),
+        db,
+    ));
+    assert!(signer.may_block());
+
+    let signature = sign_blocking(Arc::clone(&signer), unit(1, 0, b"a"))
+        .await
+        .unwrap();
+    crypto::verify(b"a", &signature, &public_key).unwrap();
+
+    // The unit is already on disk once the signature is returned.
+    let mut db = SlashingProtectionDb::read(&path).unwrap();
+    assert!(matches!(
+        db.check_and_record(&public_key, &unit(1, 0, b"b")),
+        Err(Error::Refused(_))
+    ));
+}
+
+#[test]
+fn should_export_and_import_record() {
+    let mut rng = TestRng::new();
+    let public_key = PublicKey::random(&mut rng);
+    let other_public_key = PublicKey::random(&mut rng);
+    let mut db = SlashingProtectionDb::in_memory();
+    db.check_and_record(&public_key, &unit(1, 3, b"a")).unwrap();
+    db.check_and_record(
+        &public_key,
+        &echo(1, 2, Digest::hash(rng.gen::<[u8; 32]>())),
+    )
+    .unwrap();
+    db.check_and_record(&other_public_key, &vote(1, 2, false))
+        .unwrap();
+
+    let interchange = db.export(Some(&public_key));
+    assert_eq!(interchange.entries.len(), 2);
+    let json = serde_json::to_string(&interchange).unwrap();
+
+    let dir = tempdir().unwrap();
+    let mut new_db = SlashingProtectionDb::open(dir.path().join("new.jsonl")).unwrap();
+    let imported = new_db.import(serde_json::from_str(&json).unwrap()).unwrap();
+    assert_eq!(imported, 2);
+    assert_eq!(new_db.export(None), interchange);
+    assert!(new_db
+        .check_and_record(&public_key, &unit(1, 3, b"b"))
+        .is_err());
+    // Importing the same record again adds nothing.
+    assert_eq!(new_db.import(interchange).unwrap(), 0);
+}
+
+#[test]
+fn remote_signer_should_sign_via_reference_signer() {
+    let mut rng = TestRng::new();
+    let dir = tempdir().unwrap();
+    let secret_key = Arc::new(SecretKey::random(&mut rng));
+    let remote_signer = start_reference_signer(
+        secret_key.clone(),
+        &dir.path().join("signer.sock"),
+        &dir.path().join("state.jsonl"),
+    );
+    assert_eq!(*remote_signer.public_key(), PublicKey::from(&*secret_key));
+
+    let request = vote(1, 0, true);
+    let signature = remote_signer.sign(request.clone()).unwrap();
+    crypto::verify(&request.message, &signature, remote_signer.public_key()).unwrap();
+
+    let message = b"certificate";
+    let signature = remote_signer
+        .sign(SigningRequest::new(MessageKind::Other, message))
+        .unwrap();
+    crypto::verify(message, &signature, remote_signer.public_key()).unwrap();
+}
+
+#[test]
+fn reference_signer_should_refuse_double_signing_across_restarts() {
+    let mut rng = TestRng::new();
+    let dir = tempdir().unwrap();
+    let secret_key = Arc::new(SecretKey::random(&mut rng));
+    let state_path = dir.path().join("state.jsonl");
+
+    let remote_signer = start_reference_signer(
+        secret_key.clone(),
+        &dir.path().join("signer1.sock"),
+        &state_path,
+    );
+    remote_signer.sign(vote(1, 3, true)).unwrap();
+    assert!(matches!(
+        remote_signer.sign(vote(1, 3, false)),
+        Err(Error::Refused(_))
+    ));
+
+    // A restarted signer must still refuse the conflicting vote.
+    let remote_signer =
+        start_reference_signer(secret_key, &dir.path().join("signer2.sock"), &state_path);
+    remote_signer.sign(vote(1, 3, true)).unwrap();
+    assert!(matches!(
+        remote_signer.sign(vote(1, 3, false)),
+        Err(Error::Refused(_))
+    ));
+}
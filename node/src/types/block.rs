@@ -16,6 +16,7 @@ use std::{
     error::Error as StdError,
     fmt::{self, Debug, Display, Formatter},
     hash::{Hash, Hasher},
+    sync::Arc,
 };
 
 use datasize::DataSize;
@@ -47,6 +48,7 @@ use crate::{
     },
     effect::GossipTarget,
     rpcs::docs::DocExample,
+    signer::{self, MessageKind, Signer, SigningRequest},
     types::{
         error::{BlockCreationError, BlockHeaderWithMetadataValidationError, BlockValidationError},
         Approval, Chunkable, Deploy, DeployHash, DeployHashWithApprovals, DeployId,
@@ -2303,6 +2305,11 @@ pub(crate) mod json_compatibility {
         pub fn transfer_hashes(&self) -> &Vec<DeployHash> {
             &self.body.transfer_hashes
         }
+
+        /// Returns the public key of the validator which proposed the `Block`.
+        pub fn proposer(&self) -> &PublicKey {
+            &self.body.proposer
+        }
     }
 
     impl DocExample for JsonBlock {
@@ -2433,8 +2440,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -2445,6 +2451,28 @@ impl FinalitySignature {
         }
     }
 
+    /// Create an instance of `FinalitySignature`, signed by the given signer on a blocking thread.
+    pub(crate) async fn sign(
+        block_hash: BlockHash,
+        era_id: EraId,
+        signer: Arc<dyn Signer>,
+    ) -> Result<Self, signer::Error> {
+        let kind = MessageKind::FinalitySignature {
+            era_id,
+            block_hash: *block_hash.inner(),
+        };
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
+        let public_key = signer.public_key().clone();
+        let signature = signer::sign_blocking(signer, SigningRequest::new(kind, bytes)).await?;
+        Ok(FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key,
+            is_verified: OnceCell::with_value(Ok(())),
+        })
+    }
+
     /// Create an instance of `FinalitySignature`.
     pub fn new(
         block_hash: BlockHash,
@@ -2465,14 +2493,19 @@ impl FinalitySignature {
     pub fn is_verified(&self) -> Result<(), crypto::Error> {
         self.is_verified
             .get_or_init(|| {
-                // NOTE: This needs to be in sync with the `new` constructor.
-                let mut bytes = self.block_hash.inner().into_vec();
-                bytes.extend_from_slice(&self.era_id.to_le_bytes());
+                let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
                 crypto::verify(bytes, &self.signature, &self.public_key)
             })
             .clone()
     }
 
+    /// Returns the bytes signed by a finality signature for the given block.
+    fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     /// Returns a random `FinalitySignature` for the provided `block_hash` and `era_id`.
     #[cfg(any(feature = "testing", test))]
     pub fn random_for_block(block_hash: BlockHash, era_id: u64) -> Self {
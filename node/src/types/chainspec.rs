@@ -363,6 +363,10 @@ mod tests {
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
             random_bytes: HostFunction::new(123, [0, 1]),
             enable_contract_version: HostFunction::new(142, [0, 1, 2, 3]),
+            dictionary_keys: HostFunction::new(143, [0, 1, 2, 3, 4, 5]),
+            dictionary_remove: HostFunction::new(144, [0, 1, 2, 3]),
+            prune: HostFunction::new(145, [0, 1]),
+            emit_event: HostFunction::new(146, [0, 1, 2, 3]),
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
@@ -374,7 +378,7 @@ mod tests {
         )
     });
 
-    const EXPECTED_GENESIS_STORAGE_COSTS: StorageCosts = StorageCosts::new(101);
+    const EXPECTED_GENESIS_STORAGE_COSTS: StorageCosts = StorageCosts::new(101, 50);
 
     const EXPECTED_GENESIS_COSTS: OpcodeCosts = OpcodeCosts {
         bit: 13,
@@ -35,6 +35,8 @@ pub struct DeployConfig {
     pub(crate) session_args_max_length: u32,
     pub(crate) native_transfer_minimum_motes: u64,
     pub(crate) max_timestamp_leeway: TimeDiff,
+    pub(crate) max_transfer_targets: u32,
+    pub(crate) min_gas_price: u64,
 }
 
 impl DeployConfig {
@@ -71,6 +73,8 @@ impl DeployConfig {
         let native_transfer_minimum_motes =
             rng.gen_range(MAX_PAYMENT_AMOUNT..1_000_000_000_000_000);
         let max_timestamp_leeway = TimeDiff::from_seconds(rng.gen_range(0..6));
+        let max_transfer_targets = rng.gen_range(1..100);
+        let min_gas_price = rng.gen_range(0..2);
 
         DeployConfig {
             max_payment_cost,
@@ -86,6 +90,8 @@ impl DeployConfig {
             session_args_max_length,
             native_transfer_minimum_motes,
             max_timestamp_leeway,
+            max_transfer_targets,
+            min_gas_price,
         }
     }
 }
@@ -107,6 +113,8 @@ impl Default for DeployConfig {
             session_args_max_length: 1024,
             native_transfer_minimum_motes: MAX_PAYMENT_AMOUNT,
             max_timestamp_leeway: TimeDiff::from_str("5sec").unwrap(),
+            max_transfer_targets: 25,
+            min_gas_price: 1,
         }
     }
 }
@@ -127,6 +135,8 @@ impl ToBytes for DeployConfig {
         buffer.extend(self.session_args_max_length.to_bytes()?);
         buffer.extend(self.native_transfer_minimum_motes.to_bytes()?);
         buffer.extend(self.max_timestamp_leeway.to_bytes()?);
+        buffer.extend(self.max_transfer_targets.to_bytes()?);
+        buffer.extend(self.min_gas_price.to_bytes()?);
         Ok(buffer)
     }
 
@@ -144,6 +154,8 @@ impl ToBytes for DeployConfig {
             + self.session_args_max_length.serialized_length()
             + self.native_transfer_minimum_motes.serialized_length()
             + self.max_timestamp_leeway.serialized_length()
+            + self.max_transfer_targets.serialized_length()
+            + self.min_gas_price.serialized_length()
     }
 }
 
@@ -163,6 +175,8 @@ impl FromBytes for DeployConfig {
         let (session_args_max_length, remainder) = u32::from_bytes(remainder)?;
         let (native_transfer_minimum_motes, remainder) = u64::from_bytes(remainder)?;
         let (max_timestamp_leeway, remainder) = TimeDiff::from_bytes(remainder)?;
+        let (max_transfer_targets, remainder) = u32::from_bytes(remainder)?;
+        let (min_gas_price, remainder) = u64::from_bytes(remainder)?;
         let config = DeployConfig {
             max_payment_cost,
             max_ttl,
@@ -177,6 +191,8 @@ impl FromBytes for DeployConfig {
             session_args_max_length,
             native_transfer_minimum_motes,
             max_timestamp_leeway,
+            max_transfer_targets,
+            min_gas_price,
         };
         Ok((config, remainder))
     }
@@ -45,7 +45,7 @@ use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     crypto, runtime_args,
     system::standard_payment::ARG_AMOUNT,
-    PublicKey, RuntimeArgs, SecretKey, TimeDiff, Timestamp, U512,
+    CLType, CLValue, PublicKey, RuntimeArgs, SecretKey, TimeDiff, Timestamp, U512,
 };
 
 use crate::{
@@ -72,6 +72,11 @@ pub use id::Id as DeployId;
 pub(crate) use legacy_deploy::LegacyDeploy;
 pub(crate) use metadata::{Metadata as DeployMetadata, MetadataExt as DeployMetadataExt};
 
+/// The name of the runtime argument listing the targets of a native transfer to multiple targets.
+const ARG_TARGETS: &str = "targets";
+/// The name of the runtime argument listing the amounts of a native transfer to multiple targets.
+const ARG_AMOUNTS: &str = "amounts";
+
 static DEPLOY: Lazy<Deploy> = Lazy::new(|| {
     let payment_args = runtime_args! {
         "amount" => 1000
@@ -304,6 +309,47 @@ impl Deploy {
         max_associated_keys: u32,
         timestamp_leeway: TimeDiff,
         at: Timestamp,
+    ) -> Result<(), DeployConfigurationFailure> {
+        self.check_config_compliance(
+            chain_name,
+            config,
+            max_associated_keys,
+            timestamp_leeway,
+            at,
+            true,
+        )
+    }
+
+    /// Returns Ok if and only if the deploy is config compliant as per `is_config_compliant`,
+    /// except for its payment, which isn't checked.
+    ///
+    /// Intended for deploys whose payment is replaced before they are executed.
+    pub(crate) fn is_config_compliant_ignoring_payment(
+        &self,
+        chain_name: &str,
+        config: &DeployConfig,
+        max_associated_keys: u32,
+        timestamp_leeway: TimeDiff,
+        at: Timestamp,
+    ) -> Result<(), DeployConfigurationFailure> {
+        self.check_config_compliance(
+            chain_name,
+            config,
+            max_associated_keys,
+            timestamp_leeway,
+            at,
+            false,
+        )
+    }
+
+    fn check_config_compliance(
+        &self,
+        chain_name: &str,
+        config: &DeployConfig,
+        max_associated_keys: u32,
+        timestamp_leeway: TimeDiff,
+        at: Timestamp,
+        check_payment: bool,
     ) -> Result<(), DeployConfigurationFailure> {
         self.is_valid_size(config.max_deploy_size)?;
 
@@ -339,7 +385,7 @@ impl Deploy {
         // Transfers have a fixed cost and won't blow the block gas limit.
         // Other deploys can, therefore, statically check the payment amount
         // associated with the deploy.
-        if !self.session().is_transfer() {
+        if check_payment && !self.session().is_transfer() {
             let value = self
                 .payment()
                 .args()
@@ -363,7 +409,7 @@ impl Deploy {
         }
 
         let payment_args_length = self.payment().args().serialized_length();
-        if payment_args_length > config.payment_args_max_length as usize {
+        if check_payment && payment_args_length > config.payment_args_max_length as usize {
             debug!(
                 payment_args_length,
                 payment_args_max_length = config.payment_args_max_length,
@@ -389,31 +435,38 @@ impl Deploy {
         }
 
         if self.session().is_transfer() {
-            let item = self.session().clone();
-            let attempted = item
-                .args()
-                .get(ARG_AMOUNT)
-                .ok_or_else(|| {
-                    debug!("missing transfer 'amount' runtime argument");
-                    DeployConfigurationFailure::MissingTransferAmount
-                })?
-                .clone()
-                .into_t::<U512>()
-                .map_err(|_| {
-                    debug!("failed to parse transfer 'amount' runtime argument as a U512");
-                    DeployConfigurationFailure::FailedToParseTransferAmount
-                })?;
+            let args = self.session().args();
+            let attempted_amounts = match args.get(ARG_TARGETS) {
+                Some(targets) => transfer_amounts(targets, args, config.max_transfer_targets)?,
+                None => {
+                    let attempted = args
+                        .get(ARG_AMOUNT)
+                        .ok_or_else(|| {
+                            debug!("missing transfer 'amount' runtime argument");
+                            DeployConfigurationFailure::MissingTransferAmount
+                        })?
+                        .clone()
+                        .into_t::<U512>()
+                        .map_err(|_| {
+                            debug!("failed to parse transfer 'amount' runtime argument as a U512");
+                            DeployConfigurationFailure::FailedToParseTransferAmount
+                        })?;
+                    vec![attempted]
+                }
+            };
             let minimum = U512::from(config.native_transfer_minimum_motes);
-            if attempted < minimum {
-                debug!(
-                    minimum = %config.native_transfer_minimum_motes,
-                    amount = %attempted,
-                    "insufficient transfer amount"
-                );
-                return Err(DeployConfigurationFailure::InsufficientTransferAmount {
-                    minimum: Box::new(minimum),
-                    attempted: Box::new(attempted),
-                });
+            for attempted in attempted_amounts {
+                if attempted < minimum {
+                    debug!(
+                        minimum = %config.native_transfer_minimum_motes,
+                        amount = %attempted,
+                        "insufficient transfer amount"
+                    );
+                    return Err(DeployConfigurationFailure::InsufficientTransferAmount {
+                        minimum: Box::new(minimum),
+                        attempted: Box::new(attempted),
+                    });
+                }
             }
         }
 
@@ -651,6 +704,67 @@ fn validate_deploy(deploy: &Deploy) -> Result<(), DeployConfigurationFailure> {
     Ok(())
 }
 
+/// Returns the amounts of a native transfer to the multiple targets listed in `targets`.
+///
+/// The targets must be a non-empty list of at most `max_transfer_targets` elements, and the
+/// "amounts" argument must be a list of [`U512`] of the same length.
+fn transfer_amounts(
+    targets: &CLValue,
+    args: &RuntimeArgs,
+    max_transfer_targets: u32,
+) -> Result<Vec<U512>, DeployConfigurationFailure> {
+    // Any list is serialized with its length as a `u32` prefix.
+    let target_count = match targets.cl_type() {
+        CLType::List(_) => u32::from_bytes(targets.inner_bytes())
+            .ok()
+            .map(|(target_count, _)| target_count),
+        _ => None,
+    }
+    .filter(|target_count| *target_count > 0)
+    .ok_or_else(|| {
+        debug!("failed to parse transfer 'targets' runtime argument as a non-empty list");
+        DeployConfigurationFailure::FailedToParseTransferTargets
+    })?;
+
+    if target_count > max_transfer_targets {
+        debug!(
+            target_count,
+            max_transfer_targets, "number of transfer targets exceeds the maximum limit"
+        );
+        return Err(DeployConfigurationFailure::ExcessiveTransferTargets {
+            max_transfer_targets,
+            got: target_count,
+        });
+    }
+
+    let amounts = args
+        .get(ARG_AMOUNTS)
+        .ok_or_else(|| {
+            debug!("missing transfer 'amounts' runtime argument");
+            DeployConfigurationFailure::MissingTransferAmounts
+        })?
+        .clone()
+        .into_t::<Vec<U512>>()
+        .map_err(|_| {
+            debug!("failed to parse transfer 'amounts' runtime argument as a list of U512");
+            DeployConfigurationFailure::FailedToParseTransferAmounts
+        })?;
+
+    if amounts.len() != target_count as usize {
+        debug!(
+            target_count,
+            amount_count = amounts.len(),
+            "number of transfer amounts does not match the number of targets"
+        );
+        return Err(DeployConfigurationFailure::MismatchedTransferAmounts {
+            targets: target_count,
+            amounts: amounts.len(),
+        });
+    }
+
+    Ok(amounts)
+}
+
 #[cfg(any(feature = "testing", test))]
 impl Deploy {
     /// Returns a random deploy.
@@ -1120,7 +1234,7 @@ mod tests {
     use std::{iter, time::Duration};
 
     use casper_execution_engine::core::engine_state::MAX_PAYMENT_AMOUNT;
-    use casper_types::{bytesrepr::Bytes, CLValue};
+    use casper_types::{account::AccountHash, bytesrepr::Bytes};
 
     use super::*;
 
@@ -1432,6 +1546,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn not_acceptable_due_to_low_gas_price() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let mut deploy_config = DeployConfig::default();
+
+        let deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl,
+            deploy_config.max_dependencies.into(),
+            chain_name,
+        );
+        deploy_config.min_gas_price = deploy.header().gas_price() + 1;
+
+        let expected_error = DeployConfigurationFailure::GasPriceTooLow {
+            min_gas_price: deploy_config.min_gas_price,
+            got: deploy.header().gas_price(),
+        };
+
+        let current_timestamp = deploy.header().timestamp();
+        assert_eq!(
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                TimeDiff::default(),
+                current_timestamp
+            ),
+            Err(expected_error)
+        );
+        assert!(
+            deploy.is_valid.get().is_none(),
+            "deploy should not have run expensive `is_valid` call"
+        );
+    }
+
     #[test]
     fn not_acceptable_due_to_timestamp_in_future() {
         let mut rng = crate::new_rng();
@@ -1534,6 +1684,16 @@ mod tests {
             ),
             Err(DeployConfigurationFailure::MissingPaymentAmount)
         );
+        assert_eq!(
+            deploy.is_config_compliant_ignoring_payment(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                TimeDiff::default(),
+                current_timestamp
+            ),
+            Ok(())
+        );
         assert!(
             deploy.is_valid.get().is_none(),
             "deploy should not have run expensive `is_valid` call"
@@ -1828,4 +1988,65 @@ mod tests {
             )
         )
     }
+
+    #[test]
+    fn not_acceptable_due_to_invalid_transfer_targets() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig::default();
+        let mut deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl,
+            deploy_config.max_dependencies as usize,
+            chain_name,
+        );
+        let amount = U512::from(deploy_config.native_transfer_minimum_motes);
+        let current_timestamp = deploy.header().timestamp();
+
+        let mut check = |targets: Vec<AccountHash>, amounts: Vec<U512>| {
+            deploy.session = ExecutableDeployItem::Transfer {
+                args: runtime_args! {
+                    "targets" => targets,
+                    "amounts" => amounts,
+                },
+            };
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                TimeDiff::default(),
+                current_timestamp,
+            )
+        };
+
+        let target = PublicKey::random(&mut rng).to_account_hash();
+        assert_eq!(check(vec![target; 2], vec![amount; 2]), Ok(()));
+        assert_eq!(
+            check(vec![], vec![]),
+            Err(DeployConfigurationFailure::FailedToParseTransferTargets)
+        );
+        let max_transfer_targets = deploy_config.max_transfer_targets;
+        let excessive = max_transfer_targets as usize + 1;
+        assert_eq!(
+            check(vec![target; excessive], vec![amount; excessive]),
+            Err(DeployConfigurationFailure::ExcessiveTransferTargets {
+                max_transfer_targets,
+                got: max_transfer_targets + 1,
+            })
+        );
+        assert_eq!(
+            check(vec![target; 2], vec![amount]),
+            Err(DeployConfigurationFailure::MismatchedTransferAmounts {
+                targets: 2,
+                amounts: 1,
+            })
+        );
+        assert_eq!(
+            check(vec![target; 2], vec![amount, amount - 1]),
+            Err(DeployConfigurationFailure::InsufficientTransferAmount {
+                minimum: Box::new(amount),
+                attempted: Box::new(amount - 1),
+            })
+        );
+    }
 }
@@ -92,8 +92,9 @@ impl DeployHeader {
         &self.chain_name
     }
 
-    /// Returns Ok if and only if the dependencies count and TTL are within limits, and the
-    /// timestamp is not later than `at + timestamp_leeway`.  Does NOT check for expiry.
+    /// Returns Ok if and only if the dependencies count and TTL are within limits, the gas price is
+    /// not below the minimum, and the timestamp is not later than `at + timestamp_leeway`.  Does
+    /// NOT check for expiry.
     pub fn is_valid(
         &self,
         config: &DeployConfig,
@@ -127,6 +128,19 @@ impl DeployHeader {
             });
         }
 
+        if self.gas_price() < config.min_gas_price {
+            debug!(
+                %deploy_hash,
+                deploy_header = %self,
+                min_gas_price = %config.min_gas_price,
+                "deploy gas price too low"
+            );
+            return Err(DeployConfigurationFailure::GasPriceTooLow {
+                min_gas_price: config.min_gas_price,
+                got: self.gas_price(),
+            });
+        }
+
         if self.timestamp() > at + timestamp_leeway {
             debug!(%deploy_hash, deploy_header = %self, %at, "deploy timestamp in the future");
             return Err(DeployConfigurationFailure::TimestampInFuture {
@@ -40,6 +40,15 @@ pub enum DeployConfigurationFailure {
         got: TimeDiff,
     },
 
+    /// Gas price below the minimum.
+    #[error("gas price of {got} is below the minimum of {min_gas_price}")]
+    GasPriceTooLow {
+        /// The minimum gas price.
+        min_gas_price: u64,
+        /// The received gas price.
+        got: u64,
+    },
+
     /// Deploy's timestamp is in the future.
     #[error(
         "timestamp of {got} is later than node's validation timestamp of {validation_timestamp} \
@@ -127,6 +136,36 @@ pub enum DeployConfigurationFailure {
         attempted: Box<U512>,
     },
 
+    /// Failed to parse transfer "targets" runtime argument.
+    #[error("failed to parse transfer 'targets' as a non-empty list")]
+    FailedToParseTransferTargets,
+
+    /// Too many transfer targets.
+    #[error("{got} transfer targets exceeds limit of {max_transfer_targets}")]
+    ExcessiveTransferTargets {
+        /// The transfer targets limit.
+        max_transfer_targets: u32,
+        /// The number of transfer targets provided.
+        got: u32,
+    },
+
+    /// Missing transfer "amounts" runtime argument.
+    #[error("missing transfer 'amounts' runtime argument")]
+    MissingTransferAmounts,
+
+    /// Failed to parse transfer "amounts" runtime argument.
+    #[error("failed to parse transfer 'amounts' as a list of U512")]
+    FailedToParseTransferAmounts,
+
+    /// The number of transfer amounts does not match the number of transfer targets.
+    #[error("number of transfer amounts {amounts} does not match number of targets {targets}")]
+    MismatchedTransferAmounts {
+        /// The number of transfer targets provided.
+        targets: u32,
+        /// The number of transfer amounts provided.
+        amounts: usize,
+    },
+
     /// The amount of approvals on the deploy exceeds the max_associated_keys limit.
     #[error("number of associated keys {got} exceeds the maximum {max_associated_keys}")]
     ExcessiveApprovals {
@@ -3,6 +3,7 @@
 mod account;
 mod auction_state;
 mod contracts;
+mod execution_trace;
 mod stored_value;
 
 use casper_types::{contracts::NamedKeys, NamedKey};
@@ -10,6 +11,7 @@ use casper_types::{contracts::NamedKeys, NamedKey};
 pub use account::Account;
 pub use auction_state::AuctionState;
 pub use contracts::{Contract, ContractPackage};
+pub use execution_trace::ExecutionTrace;
 pub use stored_value::StoredValue;
 
 /// A helper function to change NamedKeys into a `Vec<NamedKey>`
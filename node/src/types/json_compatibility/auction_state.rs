@@ -66,7 +66,7 @@ static AUCTION_INFO: Lazy<AuctionState> = Lazy::new(|| {
     let height: u64 = 10;
     let era_validators = EraValidators::doc_example().clone();
     let bids = Bids::doc_example().clone();
-    AuctionState::new(state_root_hash, height, era_validators, bids)
+    AuctionState::new(state_root_hash, Some(height), era_validators, bids)
 });
 
 /// A validator's weight.
@@ -146,8 +146,9 @@ pub struct JsonBids {
 pub struct AuctionState {
     /// Global state hash.
     pub state_root_hash: Digest,
-    /// Block height.
-    pub block_height: u64,
+    /// Block height, omitted if the auction state was requested by state root hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
     /// Era validators.
     pub era_validators: Vec<JsonEraValidators>,
     /// All bids contained within a vector.
@@ -158,7 +159,7 @@ impl AuctionState {
     /// Create new instance of `AuctionState`
     pub fn new(
         state_root_hash: Digest,
-        block_height: u64,
+        block_height: Option<u64>,
         era_validators: EraValidators,
         bids: Bids,
     ) -> Self {
@@ -0,0 +1,171 @@
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_execution_engine::core::runtime::{
+    ExecutionTrace as DomainExecutionTrace, HostFunctionCallTrace as DomainHostFunctionCallTrace,
+    RevertTrace as DomainRevertTrace,
+};
+use casper_types::{
+    account::AccountHash, system::CallStackElement as DomainCallStackElement, ContractHash,
+    ContractPackageHash, Phase as DomainPhase, U512,
+};
+
+/// The phase of execution of a deploy.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+pub enum Phase {
+    /// Committing the genesis or upgrade configurations.
+    System,
+    /// Executing the payment code.
+    Payment,
+    /// Executing the session code.
+    Session,
+    /// Finalizing the payment.
+    FinalizePayment,
+}
+
+impl From<DomainPhase> for Phase {
+    fn from(phase: DomainPhase) -> Self {
+        match phase {
+            DomainPhase::System => Phase::System,
+            DomainPhase::Payment => Phase::Payment,
+            DomainPhase::Session => Phase::Session,
+            DomainPhase::FinalizePayment => Phase::FinalizePayment,
+        }
+    }
+}
+
+/// A frame of the call stack.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum CallStackElement {
+    /// Session code.
+    Session {
+        /// The account hash of the caller.
+        account_hash: AccountHash,
+    },
+    /// Stored session code.
+    StoredSession {
+        /// The account hash of the caller.
+        account_hash: AccountHash,
+        /// The contract package hash.
+        contract_package_hash: ContractPackageHash,
+        /// The contract hash.
+        contract_hash: ContractHash,
+    },
+    /// A stored contract.
+    StoredContract {
+        /// The contract package hash.
+        contract_package_hash: ContractPackageHash,
+        /// The contract hash.
+        contract_hash: ContractHash,
+    },
+}
+
+impl From<DomainCallStackElement> for CallStackElement {
+    fn from(element: DomainCallStackElement) -> Self {
+        match element {
+            DomainCallStackElement::Session { account_hash } => {
+                CallStackElement::Session { account_hash }
+            }
+            DomainCallStackElement::StoredSession {
+                account_hash,
+                contract_package_hash,
+                contract_hash,
+            } => CallStackElement::StoredSession {
+                account_hash,
+                contract_package_hash,
+                contract_hash,
+            },
+            DomainCallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            } => CallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            },
+        }
+    }
+}
+
+/// A host function invoked by wasm code.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HostFunctionCallTrace {
+    /// The name of the host function.
+    pub host_function: String,
+    /// The arguments used to calculate the cost of the call.
+    pub args: Vec<u32>,
+    /// The gas charged for the call.
+    pub gas_charged: U512,
+    /// The phase during which the call was made.
+    pub phase: Phase,
+    /// The call stack at the time of the call, outermost frame first.
+    pub call_stack: Vec<CallStackElement>,
+}
+
+impl From<DomainHostFunctionCallTrace> for HostFunctionCallTrace {
+    fn from(call: DomainHostFunctionCallTrace) -> Self {
+        HostFunctionCallTrace {
+            host_function: call.host_function,
+            args: call.args,
+            gas_charged: call.gas_charged.value(),
+            phase: call.phase.into(),
+            call_stack: call.call_stack.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The point at which wasm code reverted.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RevertTrace {
+    /// The numeric code of the error with which the code reverted.
+    pub error_code: u32,
+    /// A description of the error.
+    pub error: String,
+    /// The phase during which the code reverted.
+    pub phase: Phase,
+    /// The call stack at the time of the revert, outermost frame first.
+    pub call_stack: Vec<CallStackElement>,
+}
+
+impl From<DomainRevertTrace> for RevertTrace {
+    fn from(revert: DomainRevertTrace) -> Self {
+        RevertTrace {
+            error_code: revert.error.into(),
+            error: revert.error.to_string(),
+            phase: revert.phase.into(),
+            call_stack: revert.call_stack.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A trace of the host functions invoked during the execution of a deploy.
+#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutionTrace {
+    /// The host functions invoked, in order.
+    pub host_function_calls: Vec<HostFunctionCallTrace>,
+    /// The number of further host function calls which were not recorded as the trace reached its
+    /// size limit.
+    pub omitted_host_function_calls: u64,
+    /// The point at which the code reverted, if it did.
+    pub revert: Option<RevertTrace>,
+}
+
+impl From<DomainExecutionTrace> for ExecutionTrace {
+    fn from(trace: DomainExecutionTrace) -> Self {
+        ExecutionTrace {
+            host_function_calls: trace
+                .host_function_calls
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            omitted_host_function_calls: trace.omitted_host_function_calls,
+            revert: trace.revert.map(Into::into),
+        }
+    }
+}
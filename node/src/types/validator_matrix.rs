@@ -3,6 +3,7 @@ use std::iter;
 use std::{
     collections::{BTreeMap, HashSet},
     fmt::{self, Debug, Formatter},
+    future::Future,
     sync::{Arc, RwLock, RwLockReadGuard},
 };
 
@@ -11,9 +12,15 @@ use itertools::Itertools;
 use num_rational::Ratio;
 use serde::Serialize;
 use static_assertions::const_assert;
-use tracing::info;
+use tracing::{info, warn};
 
-use casper_types::{EraId, PublicKey, SecretKey, U512};
+#[cfg(test)]
+use casper_types::SecretKey;
+use casper_types::{EraId, PublicKey, U512};
+
+#[cfg(test)]
+use crate::signer::LocalSigner;
+use crate::signer::Signer;
 
 use super::{BlockHeader, FinalitySignature};
 
@@ -47,7 +54,8 @@ pub(crate) struct ValidatorMatrix {
     chainspec_activation_era: EraId,
     #[data_size(skip)]
     finality_threshold_fraction: Ratio<u64>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_signing_key: PublicKey,
     auction_delay: u64,
     retrograde_latch: Option<EraId>,
@@ -58,17 +66,17 @@ impl ValidatorMatrix {
         finality_threshold_fraction: Ratio<u64>,
         chainspec_validators: Option<BTreeMap<PublicKey, U512>>,
         chainspec_activation_era: EraId,
-        secret_signing_key: Arc<SecretKey>,
-        public_signing_key: PublicKey,
+        signer: Arc<dyn Signer>,
         auction_delay: u64,
     ) -> Self {
         let inner = Arc::new(RwLock::new(BTreeMap::new()));
+        let public_signing_key = signer.public_key().clone();
         ValidatorMatrix {
             inner,
             finality_threshold_fraction,
             chainspec_validators: chainspec_validators.map(Arc::new),
             chainspec_activation_era,
-            secret_signing_key,
+            signer,
             public_signing_key,
             auction_delay,
             retrograde_latch: None,
@@ -92,7 +100,7 @@ impl ValidatorMatrix {
             chainspec_activation_era: EraId::from(0),
             finality_threshold_fraction,
             public_signing_key,
-            secret_signing_key,
+            signer: Arc::new(LocalSigner::new(secret_signing_key)),
             auction_delay: 1,
             retrograde_latch: None,
         }
@@ -262,22 +270,32 @@ impl ValidatorMatrix {
             .any(|validator_weights| validator_weights.is_validator(public_key))
     }
 
+    /// Returns a future creating our finality signature for the given block, if we are a validator
+    /// in its era.
+    ///
+    /// The future resolves to `None` if the signer fails or refuses to sign.
     pub(crate) fn create_finality_signature(
         &self,
         block_header: &BlockHeader,
-    ) -> Option<FinalitySignature> {
-        if self
+    ) -> Option<impl Future<Output = Option<FinalitySignature>> + Send + 'static> {
+        if !self
             .is_self_validator_in_era(block_header.era_id())
             .unwrap_or(false)
         {
-            return Some(FinalitySignature::create(
-                block_header.block_hash(),
-                block_header.era_id(),
-                &self.secret_signing_key,
-                self.public_signing_key.clone(),
-            ));
+            return None;
         }
-        None
+        let block_hash = block_header.block_hash();
+        let era_id = block_header.era_id();
+        let signer = Arc::clone(&self.signer);
+        Some(async move {
+            match FinalitySignature::sign(block_hash, era_id, signer).await {
+                Ok(finality_signature) => Some(finality_signature),
+                Err(error) => {
+                    warn!(%error, %block_hash, "failed to create finality signature");
+                    None
+                }
+            }
+        })
     }
 
     fn read_inner(&self) -> RwLockReadGuard<BTreeMap<EraId, EraValidatorWeights>> {
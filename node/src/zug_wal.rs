@@ -0,0 +1,127 @@
+//! Offline inspection and compaction of Zug write-ahead logs.
+//!
+//! A Zug validator records every message it adds to an era's protocol state in the era's
+//! write-ahead log in the `unit_files` directory, and replays it after a restart.  The entries are
+//! checked against the validator set and instance ID of that era, which are derived from the
+//! switch block of the preceding era in the node's storage and from the chainspec.  The chainspec
+//! must therefore be the one that was active in the inspected era.
+
+use std::path::Path;
+
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey};
+
+use crate::{
+    components::{
+        consensus::{
+            self,
+            protocols::{
+                common,
+                zug::{ReadWalError, WalCompactionSummary, WalInspection, WriteWalError},
+            },
+            utils::Validators,
+            ClContext,
+        },
+        storage::{FatalStorageError, Storage},
+    },
+    reactor::main_reactor::Config,
+    types::{chainspec, Chainspec, ChainspecRawBytes},
+    utils::{Loadable, WithDir},
+};
+
+/// Error returned as a result of inspecting or compacting a write-ahead log.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error reading the block store.
+    #[error("block storage error: {0}")]
+    BlockStorage(#[from] FatalStorageError),
+
+    /// The switch block preceding the era is not in storage.
+    #[error("no switch block found in storage preceding era {0}")]
+    MissingKeyBlock(EraId),
+
+    /// Error reading the write-ahead log.
+    #[error("error reading write-ahead log: {0}")]
+    Read(#[from] ReadWalError),
+
+    /// Error writing the compacted write-ahead log.
+    #[error("error writing write-ahead log: {0}")]
+    Write(#[from] WriteWalError),
+}
+
+/// Reads the Zug write-ahead log at `wal_path` and validates its entries against era `era_id` of
+/// the node configured by `config`.
+pub(crate) fn inspect_zug_wal(
+    config: WithDir<Config>,
+    era_id: EraId,
+    wal_path: &Path,
+) -> Result<WalInspection<ClContext>, Error> {
+    let (validators, instance_id) = era_validators(config, era_id)?;
+    let inspection = WalInspection::read(wal_path, &validators, instance_id)?;
+    if !inspection.is_valid() {
+        warn!(
+            corruption_count = inspection.corruptions.len(),
+            invalid_or_redundant_count = inspection
+                .entries
+                .iter()
+                .filter(|inspected| !inspected.problems.is_empty())
+                .count(),
+            "write-ahead log contains corrupted, invalid or redundant entries"
+        );
+    }
+    Ok(inspection)
+}
+
+/// Writes the valid entries of the Zug write-ahead log at `input` to a new file at `output`,
+/// leaving out corrupted, invalid and redundant entries.
+pub(crate) fn compact_zug_wal(
+    config: WithDir<Config>,
+    era_id: EraId,
+    input: &Path,
+    output: &Path,
+) -> Result<WalCompactionSummary, Error> {
+    let inspection = inspect_zug_wal(config, era_id, input)?;
+    let summary = inspection.write_compacted(output)?;
+    info!(
+        retained_entry_count = summary.retained_entry_count,
+        removed_entry_count = summary.removed_entry_count,
+        corruption_count = summary.corruption_count,
+        "compacted write-ahead log"
+    );
+    Ok(summary)
+}
+
+/// Returns the validator set and instance ID of the given era.
+fn era_validators(
+    config: WithDir<Config>,
+    era_id: EraId,
+) -> Result<(Validators<PublicKey>, Digest), Error> {
+    let (chainspec, _) =
+        <(Chainspec, ChainspecRawBytes)>::from_path(config.dir()).map_err(Error::LoadChainspec)?;
+    let (root_dir, config) = config.into_parts();
+
+    let storage =
+        Storage::open_offline(&WithDir::new(root_dir, config.storage.clone()), &chainspec)?;
+    let key_block = match era_id.predecessor() {
+        Some(key_era_id) => storage.read_switch_block_by_era_id(key_era_id)?,
+        None => None,
+    }
+    .ok_or(Error::MissingKeyBlock(era_id))?;
+    let weights = key_block
+        .header()
+        .next_era_validator_weights()
+        .ok_or(Error::MissingKeyBlock(era_id))?
+        .clone();
+
+    let validators =
+        common::validators::<ClContext>(&Default::default(), &Default::default(), weights);
+    let instance_id = consensus::instance_id(chainspec.hash(), era_id, *key_block.hash());
+    Ok((validators, instance_id))
+}
@@ -6,7 +6,7 @@ use core::mem::MaybeUninit;
 use casper_types::{
     account::AccountHash,
     api_error,
-    bytesrepr::{self, FromBytes},
+    bytesrepr::{self, FromBytes, ToBytes},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
     ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
@@ -403,6 +403,17 @@ pub fn get_call_stack() -> Vec<CallStackElement> {
     bytesrepr::deserialize(bytes).unwrap_or_revert()
 }
 
+/// Records an event with the given topic and payload in the execution result of the current
+/// deploy.
+///
+/// Events emitted by session code which reverts are discarded.
+pub fn emit_event<T: CLTyped + ToBytes>(topic: &str, payload: T) {
+    let payload = CLValue::from_t(payload).unwrap_or_revert();
+    let (topic_ptr, topic_size, _bytes1) = contract_api::to_ptr(topic);
+    let (payload_ptr, payload_size, _bytes2) = contract_api::to_ptr(payload);
+    unsafe { ext_ffi::casper_emit_event(topic_ptr, topic_size, payload_ptr, payload_size) }
+}
+
 #[cfg(feature = "test-support")]
 /// Prints a debug message
 pub fn print(text: &str) {
@@ -61,6 +61,21 @@ pub fn write<T: CLTyped + ToBytes>(uref: URef, value: T) {
     }
 }
 
+/// Removes the value under `uref` from the global state.
+///
+/// Returns `false` if there was no value under `uref`.
+pub fn prune(uref: URef) -> bool {
+    let key = Key::from(uref);
+    let (key_ptr, key_size, _bytes) = contract_api::to_ptr(key);
+
+    let ret = unsafe { ext_ffi::casper_prune(key_ptr, key_size) };
+    match api_error::result_from(ret) {
+        Ok(()) => true,
+        Err(ApiError::ValueNotFound) => false,
+        Err(error) => runtime::revert(error),
+    }
+}
+
 /// Adds `value` to the one currently under `uref` in the global state.
 pub fn add<T: CLTyped + ToBytes>(uref: URef, value: T) {
     let key = Key::from(uref);
@@ -452,6 +467,70 @@ pub fn dictionary_put<V: CLTyped + ToBytes>(
     result.unwrap_or_revert()
 }
 
+/// Returns a page of the item keys of the dictionary accessed by `dictionary_seed_uref`, along with
+/// the key from which to continue listing if there are further dictionary items.
+///
+/// A page holds `page_size` item keys, starting from `page_start` if given, unless it is the last
+/// page.  The gas charged grows with `page_size`.
+///
+/// Reverts with [`ApiError::DictionaryNotIndexed`] if the dictionary was created before the host
+/// function became available, since its items aren't indexed.
+pub fn dictionary_keys(
+    dictionary_seed_uref: URef,
+    page_start: Option<Key>,
+    page_size: u32,
+) -> (Vec<String>, Option<Key>) {
+    let (uref_ptr, uref_size, _bytes1) = contract_api::to_ptr(dictionary_seed_uref);
+    let (page_start_ptr, page_start_size, _bytes2) = contract_api::to_ptr(page_start);
+
+    let value_size = {
+        let mut value_size = MaybeUninit::uninit();
+        let ret = unsafe {
+            ext_ffi::casper_dictionary_keys(
+                uref_ptr,
+                uref_size,
+                page_start_ptr,
+                page_start_size,
+                page_size,
+                value_size.as_mut_ptr(),
+            )
+        };
+        api_error::result_from(ret).unwrap_or_revert();
+        unsafe { value_size.assume_init() }
+    };
+
+    let value_bytes = runtime::read_host_buffer(value_size).unwrap_or_revert();
+    bytesrepr::deserialize(value_bytes).unwrap_or_revert()
+}
+
+/// Removes the item under `dictionary_item_key` from the dictionary accessed by
+/// `dictionary_seed_uref`.
+///
+/// Returns `false` if there was no such item.
+pub fn dictionary_remove(dictionary_seed_uref: URef, dictionary_item_key: &str) -> bool {
+    let (uref_ptr, uref_size, _bytes1) = contract_api::to_ptr(dictionary_seed_uref);
+    let (dictionary_item_key_ptr, dictionary_item_key_size) =
+        contract_api::dictionary_item_key_to_ptr(dictionary_item_key);
+
+    if dictionary_item_key_size > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+        revert(ApiError::DictionaryItemKeyExceedsLength)
+    }
+
+    let ret = unsafe {
+        ext_ffi::casper_dictionary_remove(
+            uref_ptr,
+            uref_size,
+            dictionary_item_key_ptr,
+            dictionary_item_key_size,
+        )
+    };
+    match api_error::result_from(ret) {
+        Ok(()) => true,
+        Err(ApiError::ValueNotFound) => false,
+        Err(error) => runtime::revert(error),
+    }
+}
+
 /// Reads value under `dictionary_key` in the global state.
 pub fn dictionary_read<T: CLTyped + FromBytes>(dictionary_key: Key) -> Result<Option<T>, ApiError> {
     if !dictionary_key.is_dictionary_key() {
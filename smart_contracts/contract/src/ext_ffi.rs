@@ -805,4 +805,74 @@ extern "C" {
         contract_hash_ptr: *const u8,
         contract_hash_size: usize,
     ) -> i32;
+    /// Lists the item keys of the dictionary accessed by the URef's seed.  At most `page_size`
+    /// item keys are listed, starting from the serialized optional
+    /// Key::Dictionary in wasm memory from offset `page_start_ptr` to
+    /// `page_start_ptr + page_start_size`.  The item keys found, along with the Key::Dictionary
+    /// from which to continue listing if there are further items, are serialized and buffered in
+    /// the runtime.  This result can be obtained via the [`casper_read_host_buffer`] function.
+    /// Returns standard error code.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to bytes representing the dictionary's seed URef
+    /// * `uref_size` - size of the URef (in bytes)
+    /// * `page_start_ptr` - pointer to bytes representing the optional key to start listing from
+    /// * `page_start_size` - size of the optional key (in bytes)
+    /// * `page_size` - maximum number of dictionary item keys to list
+    /// * `output_size` - pointer to a value where host will write size of bytes of the result
+    pub fn casper_dictionary_keys(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        page_start_ptr: *const u8,
+        page_start_size: usize,
+        page_size: u32,
+        output_size: *mut usize,
+    ) -> i32;
+    /// The bytes in wasm memory from offset `key_ptr` to `key_ptr + key_size`
+    /// will be used together with the passed URef's seed to identify a dictionary item, which is
+    /// removed from the global state.  Gas is refunded for the bytes no longer stored once the
+    /// deploy has executed successfully, up to half of the gas it used.  Returns standard error
+    /// code, which is [`ApiError::ValueNotFound`](casper_types::ApiError) if the item does not
+    /// exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to bytes representing the dictionary's seed URef
+    /// * `uref_size` - size of the URef (in bytes)
+    /// * `key_ptr` - pointer to bytes representing the user-defined key to remove
+    /// * `key_size` - size of the key (in bytes)
+    pub fn casper_dictionary_remove(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        key_ptr: *const u8,
+        key_size: usize,
+    ) -> i32;
+    /// The bytes in wasm memory from offset `key_ptr` to `key_ptr + key_size`
+    /// will be used to read a [`Key`](casper_types::Key), whose value is removed from the global
+    /// state.  The key must be a [`URef`](casper_types::URef) with write access.  Gas is refunded
+    /// for the bytes no longer stored once the deploy has executed successfully, up to half of the
+    /// gas it used.  Returns standard error code, which is
+    /// [`ApiError::ValueNotFound`](casper_types::ApiError) if there is no value under the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_ptr` - pointer to bytes representing the key to remove
+    /// * `key_size` - size of the key (in bytes)
+    pub fn casper_prune(key_ptr: *const u8, key_size: usize) -> i32;
+    /// Records an event in the execution result of the current deploy.  The event is discarded if
+    /// the session code reverts.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_ptr` - pointer to bytes representing the topic name
+    /// * `topic_size` - size of the topic name (in bytes)
+    /// * `payload_ptr` - pointer to bytes representing the event's [`CLValue`](casper_types::CLValue)
+    /// * `payload_size` - size of the payload (in bytes)
+    pub fn casper_emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        payload_ptr: *const u8,
+        payload_size: usize,
+    );
 }
@@ -0,0 +1,84 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{ApiError, URef};
+
+const DICTIONARY_NAME: &str = "dictionary-name";
+const OTHER_DICTIONARY_NAME: &str = "other-dictionary-name";
+const DICTIONARY_ITEM_KEYS: [&str; 3] = ["item-key-a", "item-key-b", "item-key-c"];
+const OTHER_DICTIONARY_ITEM_KEY: &str = "other-item-key";
+const MISSING_DICTIONARY_ITEM_KEY: &str = "missing-item-key";
+const DICTIONARY_VALUE: &str = "dictionary-value";
+
+/// Lists all item keys of the dictionary, a single item per page.
+fn all_dictionary_keys(dictionary_seed_uref: URef) -> Vec<String> {
+    let mut all_item_keys = vec![];
+    let mut page_start = None;
+    loop {
+        let (item_keys, next_page_start) =
+            storage::dictionary_keys(dictionary_seed_uref, page_start, 1);
+        if item_keys.len() != 1 {
+            runtime::revert(ApiError::User(5))
+        }
+        all_item_keys.extend(item_keys);
+        page_start = match next_page_start {
+            Some(next_page_start) => Some(next_page_start),
+            None => break,
+        };
+    }
+    all_item_keys.sort();
+    all_item_keys
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let dictionary_seed_uref = storage::new_dictionary(DICTIONARY_NAME).unwrap_or_revert();
+    for dictionary_item_key in DICTIONARY_ITEM_KEYS {
+        storage::dictionary_put(
+            dictionary_seed_uref,
+            dictionary_item_key,
+            DICTIONARY_VALUE.to_string(),
+        );
+    }
+    let other_dictionary_seed_uref =
+        storage::new_dictionary(OTHER_DICTIONARY_NAME).unwrap_or_revert();
+    storage::dictionary_put(
+        other_dictionary_seed_uref,
+        OTHER_DICTIONARY_ITEM_KEY,
+        DICTIONARY_VALUE.to_string(),
+    );
+
+    if all_dictionary_keys(dictionary_seed_uref) != DICTIONARY_ITEM_KEYS {
+        runtime::revert(ApiError::User(0))
+    }
+
+    if !storage::dictionary_remove(dictionary_seed_uref, DICTIONARY_ITEM_KEYS[1]) {
+        runtime::revert(ApiError::User(1))
+    }
+    if storage::dictionary_remove(dictionary_seed_uref, MISSING_DICTIONARY_ITEM_KEY) {
+        runtime::revert(ApiError::User(2))
+    }
+    let removed_value: Option<String> =
+        storage::dictionary_get(dictionary_seed_uref, DICTIONARY_ITEM_KEYS[1]).unwrap_or_revert();
+    if removed_value.is_some() {
+        runtime::revert(ApiError::User(3))
+    }
+
+    if all_dictionary_keys(dictionary_seed_uref)
+        != [DICTIONARY_ITEM_KEYS[0], DICTIONARY_ITEM_KEYS[2]]
+    {
+        runtime::revert(ApiError::User(4))
+    }
+}
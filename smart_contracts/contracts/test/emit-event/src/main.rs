@@ -0,0 +1,19 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::runtime;
+use casper_types::ApiError;
+
+const ARG_REVERT: &str = "revert";
+const EVENT_TOPIC: &str = "counter-incremented";
+const EVENT_PAYLOAD: u64 = 42;
+
+#[no_mangle]
+pub extern "C" fn call() {
+    runtime::emit_event(EVENT_TOPIC, EVENT_PAYLOAD);
+
+    let revert: bool = runtime::get_named_arg(ARG_REVERT);
+    if revert {
+        runtime::revert(ApiError::User(0))
+    }
+}
@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate alloc;
+
+use casper_contract::contract_api::runtime;
+use casper_types::bytesrepr::Bytes;
+
+const ARG_PAYLOAD_SIZE: &str = "payload_size";
+const ARG_COUNT: &str = "count";
+const EVENT_TOPIC: &str = "large-event";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let payload_size: u32 = runtime::get_named_arg(ARG_PAYLOAD_SIZE);
+    let count: u32 = runtime::get_named_arg(ARG_COUNT);
+
+    let payload = Bytes::from(vec![0u8; payload_size as usize]);
+    for _ in 0..count {
+        runtime::emit_event(EVENT_TOPIC, payload.clone());
+    }
+}
@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::ApiError;
+
+const PRUNED_UREF_NAME: &str = "pruned-uref";
+const PRUNED_VALUE: u64 = 42;
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let uref = storage::new_uref(PRUNED_VALUE);
+    runtime::put_key(PRUNED_UREF_NAME, uref.into());
+
+    if !storage::prune(uref) {
+        runtime::revert(ApiError::User(0))
+    }
+    if storage::prune(uref) {
+        runtime::revert(ApiError::User(1))
+    }
+    if storage::read::<u64>(uref) != Ok(None) {
+        runtime::revert(ApiError::User(2))
+    }
+
+    storage::write(uref, PRUNED_VALUE);
+    if storage::read::<u64>(uref) != Ok(Some(PRUNED_VALUE)) {
+        runtime::revert(ApiError::User(3))
+    }
+    if !storage::prune(uref) {
+        runtime::revert(ApiError::User(4))
+    }
+}
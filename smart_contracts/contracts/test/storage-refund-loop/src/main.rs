@@ -0,0 +1,66 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{bytesrepr::Bytes, ApiError, Key};
+
+const ARG_MODE: &str = "mode";
+const MODE_WRITE: &str = "write";
+const MODE_REMOVE_DICTIONARY_ITEM_IN_LOOP: &str = "remove_dictionary_item_in_loop";
+const MODE_PRUNE_IN_LOOP: &str = "prune_in_loop";
+const DICTIONARY_NAME: &str = "large-dictionary";
+const DICTIONARY_ITEM_KEY: &str = "large-item";
+const UREF_NAME: &str = "large-uref";
+const LARGE_VALUE_LENGTH: usize = 100_000;
+
+fn large_value() -> Bytes {
+    vec![0u8; LARGE_VALUE_LENGTH].into()
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mode: String = runtime::get_named_arg(ARG_MODE);
+    match mode.as_str() {
+        MODE_WRITE => {
+            let dictionary_seed_uref = storage::new_dictionary(DICTIONARY_NAME).unwrap_or_revert();
+            storage::dictionary_put(dictionary_seed_uref, DICTIONARY_ITEM_KEY, large_value());
+            let uref = storage::new_uref(large_value());
+            runtime::put_key(UREF_NAME, uref.into());
+        }
+        MODE_REMOVE_DICTIONARY_ITEM_IN_LOOP => {
+            let dictionary_seed_uref = runtime::get_key(DICTIONARY_NAME)
+                .and_then(Key::into_uref)
+                .unwrap_or_revert();
+            let uref = storage::new_uref(());
+            loop {
+                // Only the first iteration removes the item, which was written by an earlier
+                // deploy.
+                storage::dictionary_remove(dictionary_seed_uref, DICTIONARY_ITEM_KEY);
+                let data: Bytes = vec![0u8; 4096].into();
+                storage::write(uref, data);
+            }
+        }
+        MODE_PRUNE_IN_LOOP => {
+            let pruned_uref = runtime::get_key(UREF_NAME)
+                .and_then(Key::into_uref)
+                .unwrap_or_revert();
+            let uref = storage::new_uref(());
+            loop {
+                // Only the first iteration prunes the value, which was written by an earlier
+                // deploy.
+                storage::prune(pruned_uref);
+                let data: Bytes = vec![0u8; 4096].into();
+                storage::write(uref, data);
+            }
+        }
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
@@ -350,6 +350,13 @@ pub enum ApiError {
     /// assert_eq!(ApiError::from(40), ApiError::NonRepresentableSerialization);
     /// ```
     NonRepresentableSerialization,
+    /// The items of the dictionary can't be listed, as it wasn't created with `new_dictionary`
+    /// since dictionary items are indexed.
+    /// ```
+    /// # use casper_types::ApiError;
+    /// assert_eq!(ApiError::from(41), ApiError::DictionaryNotIndexed);
+    /// ```
+    DictionaryNotIndexed,
     /// Error specific to Auction contract. See
     /// [casper_types::system::auction::Error](crate::system::auction::Error).
     /// ```
@@ -541,6 +548,7 @@ impl From<ApiError> for u32 {
             ApiError::MissingSystemContractHash => 38,
             ApiError::ExceededRecursionDepth => 39,
             ApiError::NonRepresentableSerialization => 40,
+            ApiError::DictionaryNotIndexed => 41,
             ApiError::AuctionError(value) => AUCTION_ERROR_OFFSET + u32::from(value),
             ApiError::ContractHeader(value) => HEADER_ERROR_OFFSET + u32::from(value),
             ApiError::Mint(value) => MINT_ERROR_OFFSET + u32::from(value),
@@ -593,6 +601,7 @@ impl From<u32> for ApiError {
             38 => ApiError::MissingSystemContractHash,
             39 => ApiError::ExceededRecursionDepth,
             40 => ApiError::NonRepresentableSerialization,
+            41 => ApiError::DictionaryNotIndexed,
             USER_ERROR_MIN..=USER_ERROR_MAX => ApiError::User(value as u16),
             HP_ERROR_MIN..=HP_ERROR_MAX => ApiError::HandlePayment(value as u8),
             MINT_ERROR_MIN..=MINT_ERROR_MAX => ApiError::Mint(value as u8),
@@ -652,6 +661,7 @@ impl Debug for ApiError {
                 write!(f, "ApiError::NonRepresentableSerialization")?
             }
             ApiError::ExceededRecursionDepth => write!(f, "ApiError::ExceededRecursionDepth")?,
+            ApiError::DictionaryNotIndexed => write!(f, "ApiError::DictionaryNotIndexed")?,
             ApiError::AuctionError(value) => write!(
                 f,
                 "ApiError::AuctionError({:?})",
@@ -860,6 +870,7 @@ mod tests {
         round_trip(Err(ApiError::HostBufferFull));
         round_trip(Err(ApiError::AllocLayout));
         round_trip(Err(ApiError::NonRepresentableSerialization));
+        round_trip(Err(ApiError::DictionaryNotIndexed));
         round_trip(Err(ApiError::ContractHeader(0)));
         round_trip(Err(ApiError::ContractHeader(u8::MAX)));
         round_trip(Err(ApiError::Mint(0)));
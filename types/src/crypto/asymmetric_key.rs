@@ -13,7 +13,7 @@ use core::{
     iter,
     marker::Copy,
 };
-#[cfg(any(feature = "std", test))]
+#[cfg(any(feature = "std-fs-io", test))]
 use std::path::Path;
 
 #[cfg(feature = "datasize")]
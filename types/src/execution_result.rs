@@ -30,7 +30,7 @@ use rand::{
 };
 #[cfg(feature = "json-schema")]
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "json-schema")]
 use crate::KEY_HASH_LENGTH;
@@ -38,7 +38,7 @@ use crate::{
     account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     system::auction::{Bid, EraInfo, UnbondingPurse, WithdrawPurse},
-    CLValue, DeployInfo, NamedKey, Transfer, TransferAddr, U128, U256, U512,
+    CLValue, DeployInfo, Key, NamedKey, Transfer, TransferAddr, U128, U256, U512,
 };
 
 #[derive(FromPrimitive, ToPrimitive, Debug)]
@@ -46,6 +46,10 @@ use crate::{
 enum ExecutionResultTag {
     Failure = 0,
     Success = 1,
+    // Results with events are tagged separately so that the encoding of results without events is
+    // unchanged.
+    FailureWithEvents = 2,
+    SuccessWithEvents = 3,
 }
 
 impl TryFrom<u8> for ExecutionResultTag {
@@ -95,6 +99,7 @@ enum TransformTag {
     AddKeys = 16,
     Failure = 17,
     WriteUnbonding = 18,
+    Prune = 19,
 }
 
 impl TryFrom<u8> for TransformTag {
@@ -147,14 +152,15 @@ static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
         effect,
         transfers,
         cost: U512::from(123_456),
+        events: vec![],
     }
 });
 
 /// The result of executing a single deploy.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "json-schema", schemars(deny_unknown_fields))]
 pub enum ExecutionResult {
     /// The result of a failed execution.
     Failure {
@@ -166,6 +172,9 @@ pub enum ExecutionResult {
         cost: U512,
         /// The error message associated with executing the deploy.
         error_message: String,
+        /// The events emitted by contracts while executing the deploy which were not reverted.
+        #[cfg_attr(feature = "json-schema", schemars(default))]
+        events: Vec<ContractEvent>,
     },
     /// The result of a successful execution.
     Success {
@@ -175,6 +184,9 @@ pub enum ExecutionResult {
         transfers: Vec<TransferAddr>,
         /// The cost of executing the deploy.
         cost: U512,
+        /// The events emitted by contracts while executing the deploy.
+        #[cfg_attr(feature = "json-schema", schemars(default))]
+        events: Vec<ContractEvent>,
     },
 }
 
@@ -186,19 +198,25 @@ impl ExecutionResult {
         &EXECUTION_RESULT
     }
 
+    /// Returns the events emitted by contracts while executing the deploy.
+    pub fn events(&self) -> &[ContractEvent] {
+        match self {
+            ExecutionResult::Failure { events, .. } | ExecutionResult::Success { events, .. } => {
+                events
+            }
+        }
+    }
+
     fn tag(&self) -> ExecutionResultTag {
         match self {
-            ExecutionResult::Failure {
-                effect: _,
-                transfers: _,
-                cost: _,
-                error_message: _,
-            } => ExecutionResultTag::Failure,
-            ExecutionResult::Success {
-                effect: _,
-                transfers: _,
-                cost: _,
-            } => ExecutionResultTag::Success,
+            ExecutionResult::Failure { events, .. } if events.is_empty() => {
+                ExecutionResultTag::Failure
+            }
+            ExecutionResult::Failure { .. } => ExecutionResultTag::FailureWithEvents,
+            ExecutionResult::Success { events, .. } if events.is_empty() => {
+                ExecutionResultTag::Success
+            }
+            ExecutionResult::Success { .. } => ExecutionResultTag::SuccessWithEvents,
         }
     }
 }
@@ -234,18 +252,26 @@ impl Distribution<ExecutionResult> for Standard {
             transfers.push(TransferAddr::new(rng.gen()))
         }
 
+        let event_count = rng.gen_range(0..3);
+        let mut events = vec![];
+        for _ in 0..event_count {
+            events.push(rng.gen())
+        }
+
         if rng.gen() {
             ExecutionResult::Failure {
                 effect: execution_effect,
                 transfers,
                 cost: rng.gen::<u64>().into(),
                 error_message: format!("Error message {}", rng.gen::<u64>()),
+                events,
             }
         } else {
             ExecutionResult::Success {
                 effect: execution_effect,
                 transfers,
                 cost: rng.gen::<u64>().into(),
+                events,
             }
         }
     }
@@ -263,20 +289,28 @@ impl ToBytes for ExecutionResult {
                 transfers,
                 cost,
                 error_message,
+                events,
             } => {
                 buffer.extend(effect.to_bytes()?);
                 buffer.extend(transfers.to_bytes()?);
                 buffer.extend(cost.to_bytes()?);
                 buffer.extend(error_message.to_bytes()?);
+                if !events.is_empty() {
+                    buffer.extend(events.to_bytes()?);
+                }
             }
             ExecutionResult::Success {
                 effect,
                 transfers,
                 cost,
+                events,
             } => {
                 buffer.extend(effect.to_bytes()?);
                 buffer.extend(transfers.to_bytes()?);
                 buffer.extend(cost.to_bytes()?);
+                if !events.is_empty() {
+                    buffer.extend(events.to_bytes()?);
+                }
             }
         }
         Ok(buffer)
@@ -290,20 +324,32 @@ impl ToBytes for ExecutionResult {
                     transfers,
                     cost,
                     error_message,
+                    events,
                 } => {
                     execution_effect.serialized_length()
                         + transfers.serialized_length()
                         + cost.serialized_length()
                         + error_message.serialized_length()
+                        + if events.is_empty() {
+                            0
+                        } else {
+                            events.serialized_length()
+                        }
                 }
                 ExecutionResult::Success {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    events,
                 } => {
                     execution_effect.serialized_length()
                         + transfers.serialized_length()
                         + cost.serialized_length()
+                        + if events.is_empty() {
+                            0
+                        } else {
+                            events.serialized_length()
+                        }
                 }
             }
     }
@@ -313,27 +359,31 @@ impl FromBytes for ExecutionResult {
     fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
         let (tag, remainder) = u8::from_bytes(bytes)?;
         match TryFrom::try_from(tag)? {
-            ExecutionResultTag::Failure => {
+            tag @ (ExecutionResultTag::Failure | ExecutionResultTag::FailureWithEvents) => {
                 let (effect, remainder) = ExecutionEffect::from_bytes(remainder)?;
                 let (transfers, remainder) = Vec::<TransferAddr>::from_bytes(remainder)?;
                 let (cost, remainder) = U512::from_bytes(remainder)?;
                 let (error_message, remainder) = String::from_bytes(remainder)?;
+                let (events, remainder) = events_from_bytes(tag, remainder)?;
                 let execution_result = ExecutionResult::Failure {
                     effect,
                     transfers,
                     cost,
                     error_message,
+                    events,
                 };
                 Ok((execution_result, remainder))
             }
-            ExecutionResultTag::Success => {
+            tag @ (ExecutionResultTag::Success | ExecutionResultTag::SuccessWithEvents) => {
                 let (execution_effect, remainder) = ExecutionEffect::from_bytes(remainder)?;
                 let (transfers, remainder) = Vec::<TransferAddr>::from_bytes(remainder)?;
                 let (cost, remainder) = U512::from_bytes(remainder)?;
+                let (events, remainder) = events_from_bytes(tag, remainder)?;
                 let execution_result = ExecutionResult::Success {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    events,
                 };
                 Ok((execution_result, remainder))
             }
@@ -341,6 +391,383 @@ impl FromBytes for ExecutionResult {
     }
 }
 
+/// Deserializes the events of an execution result with the given tag, which are only encoded if
+/// there is at least one.
+fn events_from_bytes(
+    tag: ExecutionResultTag,
+    bytes: &[u8],
+) -> Result<(Vec<ContractEvent>, &[u8]), bytesrepr::Error> {
+    match tag {
+        ExecutionResultTag::Failure | ExecutionResultTag::Success => Ok((vec![], bytes)),
+        ExecutionResultTag::FailureWithEvents | ExecutionResultTag::SuccessWithEvents => {
+            let (events, remainder) = Vec::<ContractEvent>::from_bytes(bytes)?;
+            if events.is_empty() {
+                return Err(bytesrepr::Error::Formatting);
+            }
+            Ok((events, remainder))
+        }
+    }
+}
+
+impl Serialize for ExecutionResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serde_helpers::HumanReadableSerHelper::from(self).serialize(serializer)
+        } else {
+            serde_helpers::BinarySerHelper::from(self).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ExecutionResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let human_readable =
+                serde_helpers::HumanReadableDeserHelper::deserialize(deserializer)?;
+            Ok(ExecutionResult::from(human_readable))
+        } else {
+            let binary_helper = serde_helpers::BinaryDeserHelper::deserialize(deserializer)?;
+            Ok(ExecutionResult::from(binary_helper))
+        }
+    }
+}
+
+/// Helpers for serializing an `ExecutionResult` via serde.
+///
+/// In the binary representation, results with events use separate variants so that results
+/// without events retain the layout they had before events were introduced.  In the human-readable
+/// representation, the `events` field is omitted if there are none.
+mod serde_helpers {
+    use super::*;
+
+    #[derive(Serialize)]
+    pub(super) enum HumanReadableSerHelper<'a> {
+        Failure {
+            effect: &'a ExecutionEffect,
+            transfers: &'a Vec<TransferAddr>,
+            cost: &'a U512,
+            error_message: &'a String,
+            #[serde(skip_serializing_if = "is_empty")]
+            events: &'a Vec<ContractEvent>,
+        },
+        Success {
+            effect: &'a ExecutionEffect,
+            transfers: &'a Vec<TransferAddr>,
+            cost: &'a U512,
+            #[serde(skip_serializing_if = "is_empty")]
+            events: &'a Vec<ContractEvent>,
+        },
+    }
+
+    fn is_empty(events: &&Vec<ContractEvent>) -> bool {
+        events.is_empty()
+    }
+
+    impl<'a> From<&'a ExecutionResult> for HumanReadableSerHelper<'a> {
+        fn from(execution_result: &'a ExecutionResult) -> Self {
+            match execution_result {
+                ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                } => HumanReadableSerHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                },
+                ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => HumanReadableSerHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) enum HumanReadableDeserHelper {
+        Failure {
+            effect: ExecutionEffect,
+            transfers: Vec<TransferAddr>,
+            cost: U512,
+            error_message: String,
+            #[serde(default)]
+            events: Vec<ContractEvent>,
+        },
+        Success {
+            effect: ExecutionEffect,
+            transfers: Vec<TransferAddr>,
+            cost: U512,
+            #[serde(default)]
+            events: Vec<ContractEvent>,
+        },
+    }
+
+    impl From<HumanReadableDeserHelper> for ExecutionResult {
+        fn from(helper: HumanReadableDeserHelper) -> Self {
+            match helper {
+                HumanReadableDeserHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                } => ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                },
+                HumanReadableDeserHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    pub(super) enum BinarySerHelper<'a> {
+        Failure {
+            effect: &'a ExecutionEffect,
+            transfers: &'a Vec<TransferAddr>,
+            cost: &'a U512,
+            error_message: &'a String,
+        },
+        Success {
+            effect: &'a ExecutionEffect,
+            transfers: &'a Vec<TransferAddr>,
+            cost: &'a U512,
+        },
+        FailureWithEvents {
+            effect: &'a ExecutionEffect,
+            transfers: &'a Vec<TransferAddr>,
+            cost: &'a U512,
+            error_message: &'a String,
+            events: &'a Vec<ContractEvent>,
+        },
+        SuccessWithEvents {
+            effect: &'a ExecutionEffect,
+            transfers: &'a Vec<TransferAddr>,
+            cost: &'a U512,
+            events: &'a Vec<ContractEvent>,
+        },
+    }
+
+    impl<'a> From<&'a ExecutionResult> for BinarySerHelper<'a> {
+        fn from(execution_result: &'a ExecutionResult) -> Self {
+            match execution_result {
+                ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                } if events.is_empty() => BinarySerHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                },
+                ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                } => BinarySerHelper::FailureWithEvents {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                },
+                ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } if events.is_empty() => BinarySerHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                },
+                ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => BinarySerHelper::SuccessWithEvents {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub(super) enum BinaryDeserHelper {
+        Failure {
+            effect: ExecutionEffect,
+            transfers: Vec<TransferAddr>,
+            cost: U512,
+            error_message: String,
+        },
+        Success {
+            effect: ExecutionEffect,
+            transfers: Vec<TransferAddr>,
+            cost: U512,
+        },
+        FailureWithEvents {
+            effect: ExecutionEffect,
+            transfers: Vec<TransferAddr>,
+            cost: U512,
+            error_message: String,
+            events: Vec<ContractEvent>,
+        },
+        SuccessWithEvents {
+            effect: ExecutionEffect,
+            transfers: Vec<TransferAddr>,
+            cost: U512,
+            events: Vec<ContractEvent>,
+        },
+    }
+
+    impl From<BinaryDeserHelper> for ExecutionResult {
+        fn from(helper: BinaryDeserHelper) -> Self {
+            match helper {
+                BinaryDeserHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                } => ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events: vec![],
+                },
+                BinaryDeserHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                } => ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events: vec![],
+                },
+                BinaryDeserHelper::FailureWithEvents {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                } => ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                    events,
+                },
+                BinaryDeserHelper::SuccessWithEvents {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            }
+        }
+    }
+}
+
+/// An event emitted by an account's session code or a contract while executing a deploy.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractEvent {
+    /// The key of the account or contract which emitted the event.
+    #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
+    pub emitter: Key,
+    /// The topic of the event.
+    pub topic: String,
+    /// The payload of the event.
+    pub payload: CLValue,
+}
+
+impl Distribution<ContractEvent> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ContractEvent {
+        ContractEvent {
+            emitter: Key::Hash(rng.gen()),
+            topic: format!("topic-{}", rng.gen::<u8>()),
+            payload: CLValue::from_t(rng.gen::<u64>()).unwrap(),
+        }
+    }
+}
+
+impl ToBytes for ContractEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.emitter.serialized_length()
+            + self.topic.serialized_length()
+            + self.payload.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.emitter.write_bytes(writer)?;
+        self.topic.write_bytes(writer)?;
+        self.payload.write_bytes(writer)?;
+        Ok(())
+    }
+}
+
+impl FromBytes for ContractEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (emitter, remainder) = Key::from_bytes(bytes)?;
+        let (topic, remainder) = String::from_bytes(remainder)?;
+        let (payload, remainder) = CLValue::from_bytes(remainder)?;
+        let contract_event = ContractEvent {
+            emitter,
+            topic,
+            payload,
+        };
+        Ok((contract_event, remainder))
+    }
+}
+
 /// The journal of execution transforms from a single deploy.
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Default, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
@@ -554,6 +981,10 @@ pub enum Transform {
     Failure(String),
     /// Writes the given Unbonding to global state.
     WriteUnbonding(Vec<UnbondingPurse>),
+    /// Removes the given key from global state.
+    ///
+    /// The value remains reachable from the state root hashes of earlier global states.
+    Prune(#[cfg_attr(feature = "json-schema", schemars(with = "String"))] Key),
 }
 
 impl Transform {
@@ -578,6 +1009,7 @@ impl Transform {
             Transform::AddKeys(_) => TransformTag::AddKeys,
             Transform::Failure(_) => TransformTag::Failure,
             Transform::WriteUnbonding(_) => TransformTag::WriteUnbonding,
+            Transform::Prune(_) => TransformTag::Prune,
         }
     }
 }
@@ -638,6 +1070,9 @@ impl ToBytes for Transform {
             Transform::WriteUnbonding(value) => {
                 buffer.extend(value.to_bytes()?);
             }
+            Transform::Prune(key) => {
+                buffer.extend(key.to_bytes()?);
+            }
         }
         Ok(buffer)
     }
@@ -663,6 +1098,7 @@ impl ToBytes for Transform {
             Transform::WriteBid(value) => value.serialized_length(),
             Transform::WriteWithdraw(value) => value.serialized_length(),
             Transform::WriteUnbonding(value) => value.serialized_length(),
+            Transform::Prune(key) => key.serialized_length(),
         };
         U8_SERIALIZED_LENGTH + body_len
     }
@@ -738,6 +1174,10 @@ impl FromBytes for Transform {
                     <Vec<UnbondingPurse> as FromBytes>::from_bytes(remainder)?;
                 Ok((Transform::WriteUnbonding(unbonding_purses), remainder))
             }
+            TransformTag::Prune => {
+                let (key, remainder) = Key::from_bytes(remainder)?;
+                Ok((Transform::Prune(key), remainder))
+            }
         }
     }
 }
@@ -745,7 +1185,7 @@ impl FromBytes for Transform {
 impl Distribution<Transform> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Transform {
         // TODO - include WriteDeployInfo and WriteTransfer as options
-        match rng.gen_range(0..13) {
+        match rng.gen_range(0..14) {
             0 => Transform::Identity,
             1 => Transform::WriteCLValue(CLValue::from_t(true).unwrap()),
             2 => Transform::WriteAccount(AccountHash::new(rng.gen())),
@@ -768,6 +1208,7 @@ impl Distribution<Transform> for Standard {
                 Transform::AddKeys(named_keys)
             }
             12 => Transform::Failure(rng.gen::<u64>().to_string()),
+            13 => Transform::Prune(Key::Dictionary(rng.gen())),
             _ => unreachable!(),
         }
     }
@@ -798,4 +1239,57 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn bytesrepr_test_contract_event() {
+        let mut rng = get_rng();
+        let contract_event: ContractEvent = rng.gen();
+        bytesrepr::test_serialization_roundtrip(&contract_event);
+    }
+
+    #[test]
+    fn serde_roundtrip_execution_result() {
+        let mut rng = get_rng();
+        for events in [vec![], vec![rng.gen(), rng.gen()]] {
+            let execution_result = ExecutionResult::Success {
+                effect: ExecutionEffect::default(),
+                transfers: vec![],
+                cost: rng.gen::<u64>().into(),
+                events,
+            };
+            let json = serde_json::to_string(&execution_result).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ExecutionResult>(&json).unwrap(),
+                execution_result
+            );
+            let binary = bincode::serialize(&execution_result).unwrap();
+            assert_eq!(
+                bincode::deserialize::<ExecutionResult>(&binary).unwrap(),
+                execution_result
+            );
+        }
+    }
+
+    #[test]
+    fn should_encode_execution_result_without_events_as_before() {
+        let mut rng = get_rng();
+        let effect = ExecutionEffect::default();
+        let transfers = vec![TransferAddr::new(rng.gen())];
+        let cost = U512::from(rng.gen::<u64>());
+        let execution_result = ExecutionResult::Success {
+            effect: effect.clone(),
+            transfers: transfers.clone(),
+            cost,
+            events: vec![],
+        };
+
+        let mut expected_bytes = vec![ExecutionResultTag::Success as u8];
+        expected_bytes.extend(effect.to_bytes().unwrap());
+        expected_bytes.extend(transfers.to_bytes().unwrap());
+        expected_bytes.extend(cost.to_bytes().unwrap());
+        assert_eq!(execution_result.to_bytes().unwrap(), expected_bytes);
+
+        let json = serde_json::to_value(&execution_result).unwrap();
+        assert!(json["Success"].get("events").is_none());
+    }
 }